@@ -1,6 +1,6 @@
 use cosmwasm_schema::write_api;
 
-use hackatom::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+use hackatom::msg::{ExecuteMsg, HackatomEvent, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
@@ -9,5 +9,6 @@ fn main() {
         execute: ExecuteMsg,
         sudo: SudoMsg,
         migrate: MigrateMsg,
+        events: HackatomEvent,
     }
 }
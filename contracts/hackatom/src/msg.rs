@@ -1,4 +1,4 @@
-use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_schema::{cw_serde, ContractEvents, QueryResponses};
 
 use cosmwasm_std::{Binary, Coin};
 
@@ -90,3 +90,13 @@ pub struct RecurseResponse {
 pub struct IntResponse {
     pub int: u32,
 }
+
+/// The events emitted by the contract
+#[derive(ContractEvents)]
+pub enum HackatomEvent {
+    /// The funds were released to the beneficiary
+    #[event(name = "wasm")]
+    Release { action: String, destination: String },
+    /// Emitted together with the release
+    Hackatom { action: String },
+}
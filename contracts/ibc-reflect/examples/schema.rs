@@ -1,6 +1,6 @@
 use std::env::current_dir;
 
-use cosmwasm_schema::{export_schema, export_schema_with_title, schema_for, write_api};
+use cosmwasm_schema::{export_schema_with_title, schema_for, write_api};
 use cosmwasm_std::Empty;
 
 use ibc_reflect::msg::{
@@ -14,12 +14,12 @@ fn main() {
         instantiate: InstantiateMsg,
         query: QueryMsg,
         migrate: Empty,
+        ibc_packet: PacketMsg,
     }
 
-    // Schemas for inter-contract communication
+    // The acknowledgement type depends on the packet
     let mut out_dir = current_dir().unwrap();
     out_dir.push("schema");
-    export_schema(&schema_for!(PacketMsg), &out_dir);
     export_schema_with_title(
         &schema_for!(AcknowledgementMsg<BalancesResponse>),
         &out_dir,
@@ -13,10 +13,11 @@ cargo fmt
   cargo wasm-debug
   cargo wasm-debug --features iterator,staking,stargate
   cargo clippy --all-targets --features iterator,staking,stargate -- -D warnings
-  cargo schema --features cosmwasm_1_1
+  cargo schema --features cosmwasm_1_2
 )
 (cd packages/storage && cargo build && cargo clippy --all-targets --features iterator -- -D warnings)
 (cd packages/schema && cargo build && cargo clippy --all-targets -- -D warnings)
 (cd packages/schema-derive && cargo build && cargo clippy --all-targets -- -D warnings)
 (cd packages/vm && cargo build --features iterator,stargate && cargo clippy --all-targets --features iterator,stargate -- -D warnings)
 (cd packages/check && cargo build && cargo clippy --all-targets -- -D warnings)
+(cd packages/vm-conformance && cargo build && cargo clippy --all-targets -- -D warnings)
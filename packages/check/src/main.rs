@@ -1,22 +1,34 @@
-use std::collections::HashSet;
-use std::fs::File;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use colored::Colorize;
+use rayon::prelude::*;
 
-use cosmwasm_vm::capabilities_from_csv;
-use cosmwasm_vm::internals::{check_wasm, compile};
+use cosmwasm_vm::internals::{check_wasm_with_proposals, compile_with_proposals};
+use cosmwasm_vm::{
+    capabilities_from_csv, check_optimizer, entry_point_sizes, import_usage, required_capabilities,
+    wasm_proposals_from_csv, Checksum, WasmProposals,
+};
 
-const DEFAULT_AVAILABLE_CAPABILITIES: &str = "iterator,staking,stargate,cosmwasm_1_1";
+mod verify;
+
+use verify::{fetch_code, read_code, STDIN};
+
+const DEFAULT_AVAILABLE_CAPABILITIES: &str = "iterator,staking,stargate,cosmwasm_1_1,cosmwasm_1_2";
+const KNOWN_OPTIMIZERS: &[&str] = &["rust-optimizer", "workspace-optimizer"];
+/// The number of contracts listed in the summary of the largest contracts
+const LARGEST_CONTRACTS: usize = 5;
 
 pub fn main() {
     let matches = App::new("Contract checking")
         .version(env!("CARGO_PKG_VERSION"))
         .long_about("Checks the given wasm file (memories, exports, imports, available capabilities, and non-determinism).")
         .author("Mauro Lacy <mauro@lacy.com.es>")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("CAPABILITIES")
                 // `long` setting required to turn the position argument into an option 🤷
@@ -26,13 +38,76 @@ pub fn main() {
                 .help("Sets the available capabilities that the desired target chain has")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("WASM_PROPOSALS")
+                .long("wasm-proposals")
+                .value_name("PROPOSALS")
+                .help("Sets the Wasm proposals the target chain allows, e.g. \"sign_extension,bulk_memory\". None by default.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("OPTIMIZER_VERSION")
+                .long("optimizer-version")
+                .value_name("VERSION_REQ")
+                .help("Requires the wasm files to be processed by rust-optimizer or workspace-optimizer in a matching version, e.g. \">=0.12.6, <0.13\"")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("SIZE_REPORT")
+                .long("size-report")
+                .help("Prints the code size reachable from each exported entry point")
+        )
+        .arg(
+            Arg::with_name("JOBS")
+                .long("jobs")
+                .short("j")
+                .value_name("N")
+                .help("Sets the number of contracts checked in parallel. Defaults to the number of CPUs.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("WASM")
-                .help("Wasm file to read and compile")
+                .help("Wasm file to read and compile. Directories are searched for .wasm files, and * and ? in file names match like in a shell.")
                 .required(true)
                 .index(1)
                 .multiple(true),
         )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Compares the code stored on chain with a local wasm file")
+                .arg(
+                    Arg::with_name("NODE")
+                        .long("node")
+                        .value_name("URL")
+                        .help("Fetches the code from the REST API of the node at this https:// URL")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("CODE")
+                        .long("code")
+                        .value_name("FILE")
+                        .help("Reads the response of the node's /cosmwasm/wasm/v1/code/{code_id} REST endpoint from a file, or from stdin for - (the default without --node)")
+                        .takes_value(true)
+                        .conflicts_with("NODE")
+                )
+                .arg(
+                    Arg::with_name("CHECK")
+                        .long("check")
+                        .help("Also checks the code like a local wasm file")
+                )
+                .arg(
+                    Arg::with_name("CODE_ID")
+                        .help("Code ID on chain")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("WASM")
+                        .help("Wasm file the code must match")
+                        .required(true)
+                        .index(2),
+                ),
+        )
         .get_matches();
 
     // Available capabilities
@@ -41,59 +116,384 @@ pub fn main() {
         .unwrap_or(DEFAULT_AVAILABLE_CAPABILITIES);
     let available_capabilities = capabilities_from_csv(available_capabilities_csv);
     println!("Available capabilities: {:?}", available_capabilities);
+    let wasm_proposals =
+        match wasm_proposals_from_csv(matches.value_of("WASM_PROPOSALS").unwrap_or("")) {
+            Ok(proposals) => proposals,
+            Err(e) => {
+                println!("{}", e);
+                exit(1);
+            }
+        };
+    println!("Wasm proposals: {:?}", wasm_proposals);
+    let optimizer_version = matches.value_of("OPTIMIZER_VERSION");
+    let size_report = matches.is_present("SIZE_REPORT");
+    if let Some(version_req) = optimizer_version {
+        println!("Required optimizer version: {}", version_req);
+    }
     println!();
 
-    // File
-    let paths = matches.values_of("WASM").expect("Error parsing file names");
-
-    let (passes, failures): (Vec<_>, _) = paths
-        .map(|p| {
-            let result = check_contract(p, &available_capabilities);
-            match &result {
-                Ok(_) => println!("{}: {}", p, "pass".green()),
-                Err(e) => {
-                    println!("{}: {}", p, "failure".red());
-                    println!("{}", e);
+    if let Some(matches) = matches.subcommand_matches("verify") {
+        verify_code(
+            matches,
+            &available_capabilities,
+            &wasm_proposals,
+            optimizer_version,
+        );
+        return;
+    }
+
+    let jobs = match matches.value_of("JOBS").map(str::parse::<usize>) {
+        None => 0, // let the thread pool pick the number of CPUs
+        Some(Ok(jobs)) if jobs > 0 => jobs,
+        Some(_) => {
+            println!("The number of jobs must be a positive integer");
+            exit(1);
+        }
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Error creating thread pool");
+
+    // Files
+    let inputs = matches.values_of("WASM").expect("Error parsing file names");
+    let paths = match expand_paths(inputs) {
+        Ok(paths) => paths,
+        Err(e) => {
+            println!("{}", e);
+            exit(1);
+        }
+    };
+
+    let results: Vec<_> = pool.install(|| {
+        paths
+            .par_iter()
+            .map(|p| {
+                check_contract(
+                    p,
+                    &available_capabilities,
+                    &wasm_proposals,
+                    optimizer_version,
+                )
+            })
+            .collect()
+    });
+
+    let mut summary = Summary::default();
+    for (path, result) in paths.iter().zip(results) {
+        let p = path.display();
+        match result {
+            Ok(wasm) => {
+                println!("{}: {}", p, "pass".green());
+                print_unused_imports(&wasm);
+                if size_report {
+                    print_size_report(&wasm);
                 }
-            };
-            result
-        })
-        .partition(|result| result.is_ok());
+                summary.add_pass(path, &wasm);
+            }
+            Err(e) => {
+                println!("{}: {}", p, "failure".red());
+                println!("{}", e);
+                summary.failures += 1;
+            }
+        };
+    }
     println!();
 
-    if failures.is_empty() {
+    if summary.passes.len() > 1 {
+        summary.print_statistics();
+        println!();
+    }
+
+    if summary.failures == 0 {
         println!(
             "All contracts ({}) {} checks!",
-            passes.len(),
+            summary.passes.len(),
             "passed".green()
         );
     } else {
         println!(
             "{}: {}, {}: {}",
             "Passes".green(),
-            passes.len(),
+            summary.passes.len(),
             "failures".red(),
-            failures.len()
+            summary.failures
         );
         exit(1);
     }
 }
 
+/// Turns the command line arguments into the list of files to check. Directories are
+/// replaced by the .wasm files they contain and file name patterns by the matching files,
+/// both sorted by name.
+fn expand_paths<'a>(inputs: impl Iterator<Item = &'a str>) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for input in inputs {
+        let path = Path::new(input);
+        let file_name = path.file_name().and_then(|name| name.to_str());
+        if path.is_dir() {
+            let found = files_in(path, |name| name.ends_with(".wasm"))?;
+            if found.is_empty() {
+                anyhow::bail!("No .wasm files found in {}", input);
+            }
+            paths.extend(found);
+        } else if let Some(pattern) = file_name.filter(|name| name.contains(['*', '?'])) {
+            let dir = match path.parent() {
+                Some(parent) if parent != Path::new("") => parent,
+                _ => Path::new("."),
+            };
+            let found = files_in(dir, |name| wildcard_match(pattern, name))?;
+            if found.is_empty() {
+                anyhow::bail!("No files match {}", input);
+            }
+            paths.extend(found);
+        } else {
+            paths.push(path.to_path_buf());
+        }
+    }
+    Ok(paths)
+}
+
+/// The files in `dir` whose names are accepted by `filter`, sorted by name
+fn files_in(dir: &Path, filter: impl Fn(&str) -> bool) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let accepted =
+            matches!(path.file_name().and_then(|name| name.to_str()), Some(name) if filter(name));
+        if accepted && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Matches a file name against a pattern where `*` matches any sequence of characters
+/// and `?` any single character
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // matched[j] is true if the pattern so far matches the first j characters of the name
+    let mut matched = vec![false; name.len() + 1];
+    matched[0] = true;
+    for p in pattern {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match p {
+                '*' => matched[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matched[j - 1],
+                c => j > 0 && matched[j - 1] && name[j - 1] == c,
+            };
+        }
+        matched = next;
+    }
+    matched[name.len()]
+}
+
+/// Statistics over all checked contracts
+#[derive(Default)]
+struct Summary {
+    /// The paths and sizes of the contracts that passed
+    passes: Vec<(String, usize)>,
+    failures: usize,
+    /// How many of the passed contracts require each capability
+    capabilities: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    fn add_pass(&mut self, path: &Path, wasm: &[u8]) {
+        self.passes.push((path.display().to_string(), wasm.len()));
+        // the contract passed the checks, so it can be parsed
+        for capability in required_capabilities(wasm).unwrap_or_default() {
+            *self.capabilities.entry(capability).or_default() += 1;
+        }
+    }
+
+    fn print_statistics(&self) {
+        let mut largest: Vec<_> = self.passes.iter().collect();
+        largest.sort_by(|(a_path, a_size), (b_path, b_size)| {
+            b_size.cmp(a_size).then_with(|| a_path.cmp(b_path))
+        });
+        println!("Largest contracts:");
+        for (path, size) in largest.into_iter().take(LARGEST_CONTRACTS) {
+            println!("  {:>10} bytes  {}", size, path);
+        }
+
+        println!("Required capabilities:");
+        if self.capabilities.is_empty() {
+            println!("  none");
+        }
+        let mut capabilities: Vec<_> = self.capabilities.iter().collect();
+        capabilities.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        for (capability, count) in capabilities {
+            println!(
+                "  {:<24} {:>4} of {} contracts",
+                capability,
+                count,
+                self.passes.len()
+            );
+        }
+    }
+}
+
 fn check_contract(
     path: impl AsRef<Path>,
     available_capabilities: &HashSet<String>,
-) -> anyhow::Result<()> {
+    wasm_proposals: &WasmProposals,
+    optimizer_version: Option<&str>,
+) -> anyhow::Result<Vec<u8>> {
     let mut file = File::open(path)?;
 
     // Read wasm
     let mut wasm = Vec::<u8>::new();
     file.read_to_end(&mut wasm)?;
 
+    check_code(
+        &wasm,
+        available_capabilities,
+        wasm_proposals,
+        optimizer_version,
+    )?;
+    Ok(wasm)
+}
+
+fn check_code(
+    wasm: &[u8],
+    available_capabilities: &HashSet<String>,
+    wasm_proposals: &WasmProposals,
+    optimizer_version: Option<&str>,
+) -> anyhow::Result<()> {
     // Check wasm
-    check_wasm(&wasm, available_capabilities)?;
+    check_wasm_with_proposals(wasm, available_capabilities, wasm_proposals)?;
+
+    // Check reproducible build
+    if let Some(version_req) = optimizer_version {
+        check_optimizer(wasm, KNOWN_OPTIMIZERS, version_req)?;
+    }
 
     // Compile module
-    compile(&wasm, None, &[])?;
+    compile_with_proposals(wasm, None, &[], wasm_proposals)?;
 
     Ok(())
 }
+
+/// Runs the `verify` subcommand, which compares the code stored on chain with a local file
+fn verify_code(
+    matches: &ArgMatches,
+    available_capabilities: &HashSet<String>,
+    wasm_proposals: &WasmProposals,
+    optimizer_version: Option<&str>,
+) {
+    let path = matches.value_of("WASM").unwrap();
+    let code_id = match matches.value_of("CODE_ID").unwrap().parse::<u64>() {
+        Ok(code_id) => code_id,
+        Err(_) => {
+            println!("The code ID must be a positive integer");
+            exit(1);
+        }
+    };
+
+    let local_checksum = match fs::read(path) {
+        Ok(wasm) => Checksum::generate(&wasm),
+        Err(e) => {
+            println!("{}: {}", path, e);
+            exit(1);
+        }
+    };
+    let code = match matches.value_of("NODE") {
+        Some(node) => fetch_code(node, code_id),
+        None => read_code(matches.value_of("CODE").unwrap_or(STDIN), code_id),
+    };
+    let code = match code {
+        Ok(code) => code,
+        Err(e) => {
+            println!("{:#}", e);
+            exit(1);
+        }
+    };
+    let checksum = code.checksum();
+    println!("Code ID {}: {}", code_id, checksum);
+    println!("{}: {}", path, local_checksum);
+    println!();
+
+    if checksum != local_checksum {
+        println!("Code ID {} {} match {}", code_id, "does not".red(), path);
+        exit(1);
+    }
+    println!("Code ID {} {} {}", code_id, "matches".green(), path);
+
+    if matches.is_present("CHECK") {
+        match check_code(
+            &code.wasm,
+            available_capabilities,
+            wasm_proposals,
+            optimizer_version,
+        ) {
+            Ok(()) => {
+                println!("Code ID {}: {}", code_id, "pass".green());
+                print_unused_imports(&code.wasm);
+            }
+            Err(e) => {
+                println!("Code ID {}: {}", code_id, "failure".red());
+                println!("{}", e);
+                exit(1);
+            }
+        }
+    }
+}
+
+/// Warns about host functions the contract imports but can never call
+fn print_unused_imports(wasm: &[u8]) {
+    match import_usage(wasm) {
+        Ok(usage) => {
+            for import in usage.iter().filter(|import| !import.used) {
+                println!(
+                    "  {}: imports {}.{} but never calls it",
+                    "warning".yellow(),
+                    import.module,
+                    import.name
+                );
+            }
+        }
+        Err(e) => println!("  Import analysis failed: {}", e),
+    }
+}
+
+fn print_size_report(wasm: &[u8]) {
+    match entry_point_sizes(wasm) {
+        Ok(sizes) => {
+            println!(
+                "  {:<24} {:>10} {:>16} {:>16}",
+                "entry point", "functions", "reachable bytes", "exclusive bytes"
+            );
+            for size in sizes {
+                println!(
+                    "  {:<24} {:>10} {:>16} {:>16}",
+                    size.name, size.functions, size.reachable_size, size.exclusive_size
+                );
+            }
+        }
+        Err(e) => println!("  Size report failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_match_works() {
+        assert!(wildcard_match("*.wasm", "hackatom.wasm"));
+        assert!(wildcard_match("*.wasm", ".wasm"));
+        assert!(wildcard_match("hack*", "hackatom.wasm"));
+        assert!(wildcard_match("h?ckatom.wasm", "hackatom.wasm"));
+        assert!(wildcard_match("*atom*", "hackatom.wasm"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("*.wasm", "hackatom.wat"));
+        assert!(!wildcard_match("?", ""));
+        assert!(!wildcard_match("hackatom", "hackatom.wasm"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Reading code stored on chain to compare it with a local artifact.
+//!
+//! The code is the JSON response of the `/cosmwasm/wasm/v1/code/{code_id}` endpoint of the
+//! REST API of a node (the gRPC gateway of wasmd). It is fetched from the node over HTTPS.
+//! For nodes that are not reachable like this, the response can also be read from a file
+//! or stdin, e.g. after downloading it with
+//! `curl -s https://node.example.com/cosmwasm/wasm/v1/code/42`.
+
+use std::fs;
+use std::io::{self, Read};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use cosmwasm_std::Binary;
+use cosmwasm_vm::Checksum;
+
+/// Reads the code from stdin if given as `source`
+pub const STDIN: &str = "-";
+
+/// A code stored on chain
+#[derive(Debug, PartialEq, Eq)]
+pub struct OnChainCode {
+    pub wasm: Vec<u8>,
+    /// The code ID the chain reports for the code
+    pub code_id: Option<u64>,
+    /// The checksum the chain reports for the code, in lowercase hex
+    pub data_hash: Option<String>,
+}
+
+impl OnChainCode {
+    pub fn checksum(&self) -> Checksum {
+        Checksum::generate(&self.wasm)
+    }
+}
+
+/// Wasm code is limited to 800 KiB by wasmd. This leaves enough room for the base64
+/// encoding and the other fields of the response.
+const MAX_RESPONSE_SIZE: u64 = 2 * 1024 * 1024;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Fetches the code with the given ID from the REST API of the node at `node`,
+/// e.g. `https://node.example.com`
+pub fn fetch_code(node: &str, code_id: u64) -> anyhow::Result<OnChainCode> {
+    let url = code_url(node, code_id)?;
+    let response = match ureq::get(&url).timeout(FETCH_TIMEOUT).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status, _)) => {
+            bail!("The node responded with status {} for {}", status, url)
+        }
+        Err(e) => return Err(e).context("Error fetching the code"),
+    };
+    let mut body = vec![];
+    response
+        .into_reader()
+        .take(MAX_RESPONSE_SIZE + 1)
+        .read_to_end(&mut body)
+        .with_context(|| format!("Error reading the response of {}", url))?;
+    if body.len() as u64 > MAX_RESPONSE_SIZE {
+        bail!(
+            "The response of {} exceeds {} bytes",
+            url,
+            MAX_RESPONSE_SIZE
+        );
+    }
+    code_from_response(&body, code_id)
+}
+
+/// The URL of the code endpoint. Only HTTPS is supported, since the result is only as
+/// trustworthy as the connection to the node.
+fn code_url(node: &str, code_id: u64) -> anyhow::Result<String> {
+    if !node.starts_with("https://") {
+        bail!(
+            "The node URL must start with https://, got {}. Use --code to read a response fetched otherwise.",
+            node
+        );
+    }
+    Ok(format!(
+        "{}/cosmwasm/wasm/v1/code/{}",
+        node.trim_end_matches('/'),
+        code_id
+    ))
+}
+
+/// Reads the code with the given ID from the file `source`, or from stdin for [`STDIN`]
+pub fn read_code(source: &str, code_id: u64) -> anyhow::Result<OnChainCode> {
+    let body = if source == STDIN {
+        let mut body = vec![];
+        io::stdin()
+            .read_to_end(&mut body)
+            .context("Error reading stdin")?;
+        body
+    } else {
+        fs::read(source).with_context(|| format!("Error reading {}", source))?
+    };
+    code_from_response(&body, code_id)
+}
+
+/// Parses the response and checks that it is consistent with itself and the code ID
+fn code_from_response(body: &[u8], code_id: u64) -> anyhow::Result<OnChainCode> {
+    let code = parse_code_response(body)?;
+    if let Some(actual) = code.code_id {
+        if actual != code_id {
+            bail!(
+                "The response contains code ID {}, but code ID {} was expected",
+                actual,
+                code_id
+            );
+        }
+    }
+    // Don't trust the node blindly, it must at least be consistent
+    if let Some(data_hash) = &code.data_hash {
+        let checksum = code.checksum().to_hex();
+        if *data_hash != checksum {
+            bail!(
+                "The response contains code with checksum {} for code ID {}, but reports checksum {}",
+                checksum,
+                code_id,
+                data_hash
+            );
+        }
+    }
+    Ok(code)
+}
+
+/// Parses the JSON response of the `/cosmwasm/wasm/v1/code/{code_id}` endpoint
+fn parse_code_response(body: &[u8]) -> anyhow::Result<OnChainCode> {
+    let response: serde_json::Value =
+        serde_json::from_slice(body).context("The response is not valid JSON")?;
+    let data = response["data"]
+        .as_str()
+        .ok_or_else(|| anyhow!("The response contains no code"))?;
+    let wasm = Binary::from_base64(data)
+        .map_err(|_| anyhow!("The code in the response is not valid base64"))?
+        .0;
+    // uint64 fields are encoded as strings
+    let code_id = match response["code_info"]["code_id"].as_str() {
+        Some(code_id) => Some(
+            code_id
+                .parse()
+                .map_err(|_| anyhow!("Invalid code ID {} in the response", code_id))?,
+        ),
+        None => None,
+    };
+    let data_hash = response["code_info"]["data_hash"]
+        .as_str()
+        .map(str::to_ascii_lowercase);
+    Ok(OnChainCode {
+        wasm,
+        code_id,
+        data_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn code_response(data_hash: &str) -> String {
+        format!(
+            r#"{{"code_info":{{"code_id":"7","creator":"wasm1abc","data_hash":"{}","instantiate_permission":{{}}}},"data":"{}"}}"#,
+            data_hash,
+            Binary::from(WASM).to_base64()
+        )
+    }
+
+    #[test]
+    fn parse_code_response_works() {
+        let checksum = Checksum::generate(WASM).to_hex();
+        let code = parse_code_response(code_response(&checksum.to_uppercase()).as_bytes()).unwrap();
+        assert_eq!(
+            code,
+            OnChainCode {
+                wasm: WASM.to_vec(),
+                code_id: Some(7),
+                data_hash: Some(checksum),
+            }
+        );
+
+        let err = parse_code_response(br#"{"code_info":null,"data":null}"#).unwrap_err();
+        assert_eq!(err.to_string(), "The response contains no code");
+        assert!(parse_code_response(b"not json").is_err());
+    }
+
+    #[test]
+    fn code_url_works() {
+        assert_eq!(
+            code_url("https://node.example.com", 42).unwrap(),
+            "https://node.example.com/cosmwasm/wasm/v1/code/42"
+        );
+        assert_eq!(
+            code_url("https://[::1]:1317/", 42).unwrap(),
+            "https://[::1]:1317/cosmwasm/wasm/v1/code/42"
+        );
+        assert_eq!(
+            code_url("https://node.example.com/rest/", 1).unwrap(),
+            "https://node.example.com/rest/cosmwasm/wasm/v1/code/1"
+        );
+        let err = code_url("http://node.example.com", 42).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("The node URL must start with https://"));
+    }
+
+    #[test]
+    fn read_code_works() {
+        let path = std::env::temp_dir().join(format!("cosmwasm-check-{}.json", std::process::id()));
+        fs::write(&path, code_response(&Checksum::generate(WASM).to_hex())).unwrap();
+        let code = read_code(path.to_str().unwrap(), 7).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(code.wasm, WASM);
+        assert_eq!(code.checksum(), Checksum::generate(WASM));
+
+        let err = read_code(path.to_str().unwrap(), 7).unwrap_err();
+        assert!(err.to_string().starts_with("Error reading"));
+    }
+
+    #[test]
+    fn code_from_response_fails_for_other_code_id() {
+        let body = code_response(&Checksum::generate(WASM).to_hex());
+        let err = code_from_response(body.as_bytes(), 8).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The response contains code ID 7, but code ID 8 was expected"
+        );
+    }
+
+    #[test]
+    fn code_from_response_fails_for_inconsistent_checksum() {
+        let body = code_response(&Checksum::generate(b"other").to_hex());
+        let err = code_from_response(body.as_bytes(), 7).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("The response contains code with checksum"));
+    }
+}
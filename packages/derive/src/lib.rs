@@ -4,6 +4,9 @@ extern crate syn;
 use proc_macro::TokenStream;
 use std::str::FromStr;
 
+mod reply_id;
+mod test_harness;
+
 /// This attribute macro generates the boilerplate required to call into the
 /// contract-specific logic from the entry-points to the Wasm module.
 ///
@@ -51,6 +54,11 @@ use std::str::FromStr;
 ///
 /// where `InstantiateMsg`, `ExecuteMsg`, and `QueryMsg` are contract defined
 /// types that implement `DeserializeOwned + JsonSchema`.
+///
+/// No Wasm exports are generated when the contract's `library` feature is enabled.
+/// This allows using a contract as a dependency of another contract without
+/// conflicting exports. Contracts that want to support this only need to declare
+/// `library = []` in the `[features]` section of their `Cargo.toml`.
 #[proc_macro_attribute]
 pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     let cloned = item.clone();
@@ -67,7 +75,9 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     let new_code = format!(
         r##"
         #[cfg(target_arch = "wasm32")]
+        #[allow(unknown_lints, unexpected_cfgs)] // not every contract has a library feature
         mod __wasm_export_{name} {{ // new module to avoid conflict of function name
+            #[cfg(not(feature = "library"))]
             #[no_mangle]
             extern "C" fn {name}({typed_ptrs}) -> u32 {{
                 cosmwasm_std::do_{name}(&super::{name}, {ptrs})
@@ -82,3 +92,116 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     item.extend(entry);
     item
 }
+
+/// This attribute macro fills a module with a `TestHarness`, which calls the entry points
+/// of a contract with mock dependencies. It replaces the setup at the top of contract tests.
+///
+/// The options are the message types of the entry points. Only `instantiate` is required,
+/// methods are generated for the others that are set. The entry points are expected in
+/// `crate::contract`, which can be changed with `contract = ...`. Contracts whose entry
+/// points return `Response<CustomMsg>` set `custom = CustomMsg`.
+///
+/// The methods return errors as strings, such that entry points with different error types
+/// can be used together. The `query` method deserializes the response into the type it is
+/// called with.
+///
+/// ```
+/// # mod contract {
+/// #     use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+/// #     pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, count: u32) -> StdResult<Response> {
+/// #         deps.storage.set(b"count", &count.to_be_bytes());
+/// #         Ok(Response::new().add_attribute("owner", info.sender))
+/// #     }
+/// #     pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, add: u32) -> StdResult<Response> {
+/// #         if info.sender != "owner" {
+/// #             return Err(StdError::generic_err("unauthorized"));
+/// #         }
+/// #         let count = u32::from_be_bytes(deps.storage.get(b"count").unwrap().try_into().unwrap());
+/// #         deps.storage.set(b"count", &(count + add).to_be_bytes());
+/// #         Ok(Response::new())
+/// #     }
+/// #     pub fn query(deps: Deps, _env: Env, _msg: ()) -> StdResult<Binary> {
+/// #         to_binary(&u32::from_be_bytes(deps.storage.get(b"count").unwrap().try_into().unwrap()))
+/// #     }
+/// # }
+/// # type InstantiateMsg = u32;
+/// # type ExecuteMsg = u32;
+/// # type QueryMsg = ();
+/// use cosmwasm_std::testing::cw_test_harness;
+///
+/// #[cw_test_harness(
+///     instantiate = InstantiateMsg,
+///     execute = ExecuteMsg,
+///     query = QueryMsg,
+/// )]
+/// mod harness {}
+///
+/// # fn main() {
+/// let mut contract = harness::TestHarness::new();
+/// contract.instantiate("owner", &[], 1).unwrap();
+/// contract.execute("owner", &[], 2).unwrap();
+/// assert_eq!(contract.execute("someone", &[], 2).unwrap_err(), "Generic error: unauthorized");
+/// let count: u32 = contract.query(()).unwrap();
+/// assert_eq!(count, 3);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn cw_test_harness(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(attr as test_harness::Options);
+    let expanded = test_harness::parse_module(item.into())
+        .and_then(|module| test_harness::test_harness_impl(options, module));
+    match expanded {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derives `cosmwasm_std::ReplyId` for an enum with one variant per kind of submessage
+/// the contract handles in `reply`. The reply ID of a variant is its discriminant, so the
+/// compiler rejects two variants with the same ID. Set the discriminants explicitly to keep
+/// the IDs stable when variants are added or reordered, since submessages dispatched by
+/// an older version of the contract can still be replied to after a migration.
+///
+/// ```
+/// use cosmwasm_std::{Reply, ReplyId, SubMsg, WasmMsg};
+///
+/// #[derive(ReplyId, Debug, PartialEq)]
+/// enum ReplyKind {
+///     InstantiatePool = 1,
+///     Swap = 2,
+/// }
+///
+/// # fn main() {
+/// # let msg = WasmMsg::ClearAdmin { contract_addr: "pool".to_string() };
+/// let sub_msg: SubMsg = SubMsg::reply_on_success(msg, ReplyKind::Swap.reply_id());
+/// assert_eq!(sub_msg.id, 2);
+///
+/// // in the reply entry point
+/// # let reply: Reply = cosmwasm_std::from_slice(br#"{"id":2,"result":{"ok":{"events":[],"data":null}}}"#).unwrap();
+/// match ReplyKind::from_reply_id(reply.id).unwrap() {
+///     ReplyKind::InstantiatePool => { /* ... */ }
+///     ReplyKind::Swap => { /* ... */ }
+/// }
+/// assert!(ReplyKind::from_reply_id(3).is_err());
+/// # }
+/// ```
+///
+/// Using an ID twice is a compile error:
+///
+/// ```compile_fail
+/// use cosmwasm_std::ReplyId;
+///
+/// #[derive(ReplyId)]
+/// enum ReplyKind {
+///     InstantiatePool = 1,
+///     Swap = 1,
+/// }
+/// ```
+#[proc_macro_derive(ReplyId)]
+pub fn reply_id(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match reply_id::reply_id_impl(input) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
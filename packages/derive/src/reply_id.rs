@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+pub fn reply_id_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ReplyId can only be derived for enums",
+            ))
+        }
+    };
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return Err(syn::Error::new_spanned(
+            variant,
+            "ReplyId can only be derived for enums without data, the reply ID is the discriminant",
+        ));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let variants: Vec<_> = data.variants.iter().map(|variant| &variant.ident).collect();
+    let name = ident.to_string();
+
+    // Discriminants are unique, so rustc already rejects two variants with the same reply ID
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics cosmwasm_std::ReplyId for #ident #ty_generics #where_clause {
+            fn reply_id(&self) -> u64 {
+                match *self {
+                    #( Self::#variants => Self::#variants as u64, )*
+                }
+            }
+
+            fn from_reply_id(id: u64) -> cosmwasm_std::StdResult<Self> {
+                #(
+                    if id == Self::#variants as u64 {
+                        return Ok(Self::#variants);
+                    }
+                )*
+                Err(cosmwasm_std::StdError::generic_err(format!(
+                    "Unknown reply ID {} for {}",
+                    id, #name
+                )))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(item: &str) -> Result<String, String> {
+        let input: DeriveInput = syn::parse_str(item).unwrap();
+        reply_id_impl(input)
+            .map(|tokens| tokens.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn maps_variants_to_discriminants() {
+        let expanded = expand("enum Kind { Instantiate = 1, Swap = 7 }").unwrap();
+        assert!(expanded.contains("impl cosmwasm_std :: ReplyId for Kind"));
+        assert!(expanded.contains("Self :: Instantiate => Self :: Instantiate as u64"));
+        assert!(expanded.contains("if id == Self :: Swap as u64 { return Ok (Self :: Swap) ; }"));
+        assert!(expanded.contains("\"Unknown reply ID {} for {}\" , id , \"Kind\""));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(
+            expand("struct Kind;").unwrap_err(),
+            "ReplyId can only be derived for enums"
+        );
+        assert_eq!(
+            expand("enum Kind { Swap { pool: u64 } }").unwrap_err(),
+            "ReplyId can only be derived for enums without data, the reply ID is the discriminant"
+        );
+    }
+}
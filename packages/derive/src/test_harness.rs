@@ -0,0 +1,296 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Ident, Item, ItemMod, Path, Token, Type};
+
+/// The options of `#[cw_test_harness(...)]`, e.g. `instantiate = InstantiateMsg`
+pub struct Options {
+    contract: Path,
+    instantiate: Type,
+    execute: Option<Type>,
+    query: Option<Type>,
+    migrate: Option<Type>,
+    sudo: Option<Type>,
+    custom: Option<Type>,
+}
+
+struct Pair {
+    key: Ident,
+    value: Type,
+}
+
+impl Parse for Pair {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Pair { key, value })
+    }
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut contract = None;
+        let mut instantiate = None;
+        let mut execute = None;
+        let mut query = None;
+        let mut migrate = None;
+        let mut sudo = None;
+        let mut custom = None;
+        for pair in Punctuated::<Pair, Token![,]>::parse_terminated(input)? {
+            let slot = match pair.key.to_string().as_str() {
+                "contract" => {
+                    contract = Some(match pair.value {
+                        Type::Path(path) if path.qself.is_none() => path.path,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                pair.value,
+                                "contract must be the path of a module",
+                            ))
+                        }
+                    });
+                    continue;
+                }
+                "instantiate" => &mut instantiate,
+                "execute" => &mut execute,
+                "query" => &mut query,
+                "migrate" => &mut migrate,
+                "sudo" => &mut sudo,
+                "custom" => &mut custom,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &pair.key,
+                        format!("unknown cw_test_harness option: {}", pair.key),
+                    ))
+                }
+            };
+            *slot = Some(pair.value);
+        }
+        Ok(Options {
+            contract: contract.unwrap_or_else(|| syn::parse_quote!(crate::contract)),
+            instantiate: instantiate.ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    "cw_test_harness requires the instantiate message type",
+                )
+            })?,
+            execute,
+            query,
+            migrate,
+            sudo,
+            custom,
+        })
+    }
+}
+
+pub fn test_harness_impl(options: Options, mut module: ItemMod) -> syn::Result<TokenStream> {
+    let Options {
+        contract,
+        instantiate,
+        execute,
+        query,
+        migrate,
+        sudo,
+        custom,
+    } = options;
+    let custom = custom.unwrap_or_else(|| syn::parse_quote!(cosmwasm_std::Empty));
+    let response = quote! { std::result::Result<cosmwasm_std::Response<#custom>, String> };
+
+    let execute = execute.map(|msg| {
+        quote! {
+            /// Calls `execute` with the given sender and funds
+            pub fn execute(&mut self, sender: &str, funds: &[cosmwasm_std::Coin], msg: #msg) -> #response {
+                #contract::execute(
+                    self.deps.as_mut(),
+                    self.env.clone(),
+                    cosmwasm_std::testing::mock_info(sender, funds),
+                    msg,
+                )
+                .map_err(|err| err.to_string())
+            }
+        }
+    });
+    let query = query.map(|msg| {
+        quote! {
+            /// Calls `query` and deserializes the response
+            pub fn query<T: cosmwasm_std::testing::DeserializeOwned>(&self, msg: #msg) -> std::result::Result<T, String> {
+                let response = #contract::query(self.deps.as_ref(), self.env.clone(), msg)
+                    .map_err(|err| err.to_string())?;
+                cosmwasm_std::from_binary(&response).map_err(|err| err.to_string())
+            }
+        }
+    });
+    let migrate = migrate.map(|msg| {
+        quote! {
+            /// Calls `migrate`
+            pub fn migrate(&mut self, msg: #msg) -> #response {
+                #contract::migrate(self.deps.as_mut(), self.env.clone(), msg)
+                    .map_err(|err| err.to_string())
+            }
+        }
+    });
+    let sudo = sudo.map(|msg| {
+        quote! {
+            /// Calls `sudo`
+            pub fn sudo(&mut self, msg: #msg) -> #response {
+                #contract::sudo(self.deps.as_mut(), self.env.clone(), msg)
+                    .map_err(|err| err.to_string())
+            }
+        }
+    });
+
+    let harness: syn::File = syn::parse2(quote! {
+        #[allow(unused_imports)]
+        use super::*;
+
+        /// Calls the entry points of the contract with mock dependencies.
+        /// Generated by `#[cw_test_harness]`.
+        pub struct TestHarness {
+            pub deps: cosmwasm_std::OwnedDeps<
+                cosmwasm_std::testing::MockStorage,
+                cosmwasm_std::testing::MockApi,
+                cosmwasm_std::testing::MockQuerier,
+            >,
+            pub env: cosmwasm_std::Env,
+        }
+
+        impl Default for TestHarness {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[allow(dead_code)]
+        impl TestHarness {
+            pub fn new() -> Self {
+                TestHarness {
+                    deps: cosmwasm_std::testing::mock_dependencies(),
+                    env: cosmwasm_std::testing::mock_env(),
+                }
+            }
+
+            /// Sets the balance of the contract
+            pub fn with_balance(mut self, balance: &[cosmwasm_std::Coin]) -> Self {
+                self.deps.querier.update_balance(
+                    self.env.contract.address.as_str(),
+                    balance.to_vec(),
+                );
+                self
+            }
+
+            /// Moves to the next block, which is 5 seconds later
+            pub fn next_block(&mut self) {
+                self.env.block.height += 1;
+                self.env.block.time = self.env.block.time.plus_seconds(5);
+            }
+
+            /// Calls `instantiate` with the given sender and funds
+            pub fn instantiate(&mut self, sender: &str, funds: &[cosmwasm_std::Coin], msg: #instantiate) -> #response {
+                #contract::instantiate(
+                    self.deps.as_mut(),
+                    self.env.clone(),
+                    cosmwasm_std::testing::mock_info(sender, funds),
+                    msg,
+                )
+                .map_err(|err| err.to_string())
+            }
+
+            #execute
+            #query
+            #migrate
+            #sudo
+        }
+    })?;
+
+    match &mut module.content {
+        Some((_, items)) => {
+            let existing = std::mem::take(items);
+            *items = harness.items;
+            items.extend(existing);
+        }
+        None => {
+            return Err(syn::Error::new_spanned(
+                &module,
+                "cw_test_harness must be used on a module with a body, e.g. `mod harness {}`",
+            ))
+        }
+    }
+    Ok(quote! { #module })
+}
+
+/// Parses the item `#[cw_test_harness]` is used on, which must be a module
+pub fn parse_module(item: TokenStream) -> syn::Result<ItemMod> {
+    match syn::parse2::<Item>(item)? {
+        Item::Mod(module) => Ok(module),
+        item => Err(syn::Error::new_spanned(
+            item,
+            "cw_test_harness can only be used on a module",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(attr: &str, item: &str) -> Result<String, String> {
+        let options: Options = syn::parse_str(attr).map_err(|err| err.to_string())?;
+        let module = parse_module(item.parse().unwrap()).map_err(|err| err.to_string())?;
+        test_harness_impl(options, module)
+            .map(|tokens| tokens.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn generates_methods_for_given_messages() {
+        let expanded = expand(
+            "contract = my_contract::contract, instantiate = InstantiateMsg, query = QueryMsg",
+            "mod harness { fn helper() {} }",
+        )
+        .unwrap();
+        assert!(expanded.contains("pub struct TestHarness"));
+        assert!(expanded.contains("my_contract :: contract :: instantiate"));
+        assert!(expanded.contains("pub fn query"));
+        assert!(!expanded.contains("pub fn execute"));
+        assert!(!expanded.contains("pub fn migrate"));
+        assert!(expanded.contains("cosmwasm_std :: Response < cosmwasm_std :: Empty >"));
+        // existing items are kept
+        assert!(expanded.contains("fn helper ()"));
+    }
+
+    #[test]
+    fn uses_crate_contract_and_custom_messages() {
+        let expanded = expand(
+            "instantiate = InstantiateMsg, execute = ExecuteMsg, custom = CustomMsg",
+            "mod harness {}",
+        )
+        .unwrap();
+        assert!(expanded.contains("crate :: contract :: execute"));
+        assert!(expanded.contains("cosmwasm_std :: Response < CustomMsg >"));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(
+            expand("execute = ExecuteMsg", "mod harness {}").unwrap_err(),
+            "cw_test_harness requires the instantiate message type"
+        );
+        assert_eq!(
+            expand(
+                "instantiate = InstantiateMsg, reply = Reply",
+                "mod harness {}"
+            )
+            .unwrap_err(),
+            "unknown cw_test_harness option: reply"
+        );
+        assert_eq!(
+            expand("instantiate = InstantiateMsg", "mod harness;").unwrap_err(),
+            "cw_test_harness must be used on a module with a body, e.g. `mod harness {}`"
+        );
+        assert_eq!(
+            expand("instantiate = InstantiateMsg", "fn harness() {}").unwrap_err(),
+            "cw_test_harness can only be used on a module"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use quote::quote;
+use syn::{parse_quote, Attribute, Lit, LitStr, Meta, NestedMeta};
+
+/// The constraints of a field given by `#[schema(...)]`, e.g. `#[schema(min = 1, max = 100)]`
+#[derive(Default)]
+pub struct Constraints {
+    pub min: Option<Lit>,
+    pub max: Option<Lit>,
+    pub min_length: Option<Lit>,
+    pub max_length: Option<Lit>,
+    pub pattern: Option<LitStr>,
+    /// The field is validated with its own `Validate` implementation
+    pub nested: bool,
+}
+
+impl Constraints {
+    /// Parses the `#[schema(...)]` attributes of a field
+    pub fn parse(attrs: &[Attribute]) -> Self {
+        let mut constraints = Constraints::default();
+        for attr in attrs.iter().filter(|attr| is_schema_attr(attr)) {
+            let list = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list,
+                _ => panic!("schema must be a list of constraints, e.g. #[schema(min = 1)]"),
+            };
+            for nested in list.nested {
+                let pair = match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("nested") => {
+                        constraints.nested = true;
+                        continue;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+                    _ => panic!("schema constraints must be of the form `name = value`"),
+                };
+                let name = pair.path.get_ident().map(ToString::to_string);
+                let bound = match pair.lit {
+                    Lit::Int(_) | Lit::Float(_) | Lit::Str(_) => Some(pair.lit.clone()),
+                    _ => None,
+                };
+                let slot = match name.as_deref() {
+                    Some("min") => &mut constraints.min,
+                    Some("max") => &mut constraints.max,
+                    Some("min_length") => &mut constraints.min_length,
+                    Some("max_length") => &mut constraints.max_length,
+                    Some("pattern") => {
+                        match pair.lit {
+                            Lit::Str(pattern) => constraints.pattern = Some(pattern),
+                            _ => panic!("pattern must be a string literal"),
+                        }
+                        continue;
+                    }
+                    _ => panic!(
+                        "unknown schema constraint: {}",
+                        name.unwrap_or_else(|| "<path>".to_string())
+                    ),
+                };
+                match bound {
+                    Some(bound) => *slot = Some(bound),
+                    None => panic!(
+                        "{} must be a number or the path of a constant",
+                        name.unwrap()
+                    ),
+                }
+            }
+        }
+        constraints
+    }
+
+    pub fn has_range(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+
+    pub fn has_length(&self) -> bool {
+        self.min_length.is_some() || self.max_length.is_some()
+    }
+
+    /// The schemars attributes adding the constraints to the schema
+    pub fn schemars_attrs(&self) -> Vec<Attribute> {
+        let mut attrs = vec![];
+        if self.has_range() {
+            let bounds = bound_args(&self.min, &self.max);
+            attrs.push(parse_quote! { #[schemars(range(#( #bounds ),*))] });
+        }
+        if self.has_length() {
+            let bounds = bound_args(&self.min_length, &self.max_length);
+            attrs.push(parse_quote! { #[schemars(length(#( #bounds ),*))] });
+        }
+        if let Some(pattern) = &self.pattern {
+            attrs.push(parse_quote! { #[schemars(regex(pattern = #pattern))] });
+        }
+        attrs
+    }
+
+    /// The path of the helper checking the range or length on deserialization
+    pub fn deserialize_with(&self, cosmwasm_schema: &str) -> Option<String> {
+        match (self.has_range(), self.has_length()) {
+            (false, false) => None,
+            (true, true) => panic!("validate supports either a range or a length per field"),
+            (true, false) => Some(format!(
+                "{}::constraints::range::<_, _, {{ {} }}, {{ {} }}>",
+                cosmwasm_schema,
+                self.range_bounds().0,
+                self.range_bounds().1
+            )),
+            (false, true) => Some(format!(
+                "{}::constraints::length::<_, _, {{ {} }}, {{ {} }}>",
+                cosmwasm_schema,
+                self.length_bounds().0,
+                self.length_bounds().1
+            )),
+        }
+    }
+
+    /// The bounds of the range as `i128` expressions, e.g. `5 as i128` or `i128::MAX`
+    pub fn range_bounds(&self) -> (String, String) {
+        (
+            bound_value(&self.min, "i128", "i128::MIN"),
+            bound_value(&self.max, "i128", "i128::MAX"),
+        )
+    }
+
+    /// The bounds of the length as `usize` expressions, e.g. `MAX_LEN as usize` or `0`
+    pub fn length_bounds(&self) -> (String, String) {
+        (
+            bound_value(&self.min_length, "usize", "0"),
+            bound_value(&self.max_length, "usize", "usize::MAX"),
+        )
+    }
+}
+
+pub fn is_schema_attr(attr: &Attribute) -> bool {
+    attr.path.is_ident("schema")
+}
+
+fn bound_args(min: &Option<Lit>, max: &Option<Lit>) -> Vec<proc_macro2::TokenStream> {
+    let min = min.iter().map(|min| quote! { min = #min });
+    let max = max.iter().map(|max| quote! { max = #max });
+    min.chain(max).collect()
+}
+
+fn bound_value(bound: &Option<Lit>, ty: &str, unbounded: &str) -> String {
+    match bound {
+        Some(Lit::Int(int)) => format!("{} as {}", int, ty),
+        Some(Lit::Str(path)) => format!("{} as {}", path.value(), ty),
+        Some(_) => panic!("validate requires integer bounds"),
+        None => unbounded.to_string(),
+    }
+}
@@ -0,0 +1,187 @@
+use syn::{parse_quote, Attribute, Expr, Field, Fields, ItemEnum, ItemImpl, Lit, Meta, NestedMeta};
+
+use crate::cw_serde::is_option;
+use crate::query_responses::{doc_description, to_snake_case};
+
+pub fn contract_events_derive_impl(input: ItemEnum) -> ItemImpl {
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let events = input.variants.into_iter().map(|variant| {
+        let ty = event_value(&variant.attrs, "name")
+            .unwrap_or_else(|| to_snake_case(&variant.ident.to_string()));
+        let attributes = match variant.fields {
+            Fields::Named(fields) => fields.named.into_iter().map(parse_attribute).collect(),
+            Fields::Unit => vec![],
+            Fields::Unnamed(_) => panic!(
+                "the attributes of event {} must be named fields",
+                variant.ident
+            ),
+        };
+        let mut event: Expr = parse_quote!(cosmwasm_schema::EventSchema::new(#ty));
+        if let Some(description) = doc_description(&variant.attrs) {
+            event = parse_quote!(#event.description(#description));
+        }
+        if is_deprecated(&variant.attrs) {
+            event = parse_quote!(#event.deprecated());
+        }
+        for attribute in attributes {
+            event = parse_quote!(#event.attribute(#attribute));
+        }
+        event
+    });
+
+    parse_quote! {
+        #[automatically_derived]
+        #[cfg(not(target_arch = "wasm32"))]
+        impl #impl_generics cosmwasm_schema::ContractEvents for #ident #ty_generics #where_clause {
+            fn event_schemas() -> Vec<cosmwasm_schema::EventSchema> {
+                vec![
+                    #( #events, )*
+                ]
+            }
+        }
+    }
+}
+
+/// The attribute schema of a field, which may be renamed with `#[event(key = "...")]`
+fn parse_attribute(field: Field) -> Expr {
+    let key = event_value(&field.attrs, "key")
+        .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string());
+    let mut attribute: Expr = if is_option(&field.ty) {
+        parse_quote!(cosmwasm_schema::AttributeSchema::optional(#key))
+    } else {
+        parse_quote!(cosmwasm_schema::AttributeSchema::required(#key))
+    };
+    if let Some(description) = doc_description(&field.attrs) {
+        attribute = parse_quote!(#attribute.description(#description));
+    }
+    if is_deprecated(&field.attrs) {
+        attribute = parse_quote!(#attribute.deprecated());
+    }
+    attribute
+}
+
+pub(crate) fn is_deprecated(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("deprecated"))
+}
+
+/// Finds the value of `#[event(key = "...")]` in the given attributes
+fn event_value(attrs: &[Attribute], key: &str) -> Option<String> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("event")) {
+        let list = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list,
+            _ => panic!("expected #[event(name = \"...\")] or #[event(key = \"...\")]"),
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident(key) => match nv.lit {
+                    Lit::Str(s) => value = Some(s.value()),
+                    _ => panic!("the {} of an event must be a string literal", key),
+                },
+                _ => panic!("unknown event option, expected `{} = \"...\"`", key),
+            }
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn happy_path() {
+        let input: ItemEnum = parse_quote! {
+            pub enum ContractEvent {
+                /// Tokens were sent
+                Transfer {
+                    from: String,
+                    /// The receiver
+                    to: String,
+                    #[deprecated]
+                    memo: Option<String>,
+                },
+                #[event(name = "wasm")]
+                Action {
+                    #[event(key = "action")]
+                    kind: String,
+                },
+                #[deprecated(note = "use transfer")]
+                Paused,
+            }
+        };
+
+        assert_eq!(
+            contract_events_derive_impl(input),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl cosmwasm_schema::ContractEvents for ContractEvent {
+                    fn event_schemas() -> Vec<cosmwasm_schema::EventSchema> {
+                        vec![
+                            cosmwasm_schema::EventSchema::new("transfer")
+                                .description("Tokens were sent")
+                                .attribute(cosmwasm_schema::AttributeSchema::required("from"))
+                                .attribute(cosmwasm_schema::AttributeSchema::required("to").description("The receiver"))
+                                .attribute(cosmwasm_schema::AttributeSchema::optional("memo").deprecated()),
+                            cosmwasm_schema::EventSchema::new("wasm")
+                                .attribute(cosmwasm_schema::AttributeSchema::required("action")),
+                            cosmwasm_schema::EventSchema::new("paused").deprecated(),
+                        ]
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn generic_events() {
+        let input: ItemEnum = parse_quote! {
+            pub enum ContractEvent<T> {
+                Custom { value: T },
+            }
+        };
+
+        assert_eq!(
+            contract_events_derive_impl(input),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl<T> cosmwasm_schema::ContractEvents for ContractEvent<T> {
+                    fn event_schemas() -> Vec<cosmwasm_schema::EventSchema> {
+                        vec![
+                            cosmwasm_schema::EventSchema::new("custom")
+                                .attribute(cosmwasm_schema::AttributeSchema::required("value")),
+                        ]
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the attributes of event Transfer must be named fields")]
+    fn tuple_variants_are_rejected() {
+        let input: ItemEnum = parse_quote! {
+            pub enum ContractEvent {
+                Transfer(String),
+            }
+        };
+        contract_events_derive_impl(input);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown event option, expected `key = \"...\"`")]
+    fn unknown_option() {
+        let input: ItemEnum = parse_quote! {
+            pub enum ContractEvent {
+                Transfer {
+                    #[event(name = "sender")]
+                    from: String,
+                },
+            }
+        };
+        contract_events_derive_impl(input);
+    }
+}
@@ -0,0 +1,226 @@
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Attribute, Expr, Fields, ItemEnum, ItemImpl, Lit, Meta, NestedMeta, Variant,
+};
+
+use crate::contract_events::is_deprecated;
+use crate::query_responses::{collect_results, doc_description, to_snake_case};
+
+pub fn contract_state_derive_impl(input: ItemEnum) -> syn::Result<ItemImpl> {
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let items = collect_results(input.variants.into_iter().map(parse_item))?;
+
+    Ok(parse_quote! {
+        #[automatically_derived]
+        #[cfg(not(target_arch = "wasm32"))]
+        impl #impl_generics cosmwasm_schema::ContractState for #ident #ty_generics #where_clause {
+            fn state_schemas() -> Vec<cosmwasm_schema::StateSchema> {
+                vec![
+                    #( #items, )*
+                ]
+            }
+        }
+    })
+}
+
+/// The options of a storage item given in `#[state(...)]`
+#[derive(Default)]
+struct StateOptions {
+    namespace: Option<String>,
+    raw: bool,
+    keys: Vec<Expr>,
+}
+
+fn parse_item(variant: Variant) -> syn::Result<Expr> {
+    let value = match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+        _ => {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!(
+                    "state item {} must have the type of the stored value as its only field",
+                    variant.ident
+                ),
+            ))
+        }
+    };
+    let options = parse_options(&variant.attrs)?;
+    let namespace = options
+        .namespace
+        .unwrap_or_else(|| to_snake_case(&variant.ident.to_string()));
+
+    let mut item: Expr = parse_quote! {
+        cosmwasm_schema::StateSchema::new(#namespace, cosmwasm_schema::schema_for!(#value))
+    };
+    if let Some(description) = doc_description(&variant.attrs) {
+        item = parse_quote!(#item.description(#description));
+    }
+    if is_deprecated(&variant.attrs) {
+        item = parse_quote!(#item.deprecated());
+    }
+    if options.raw {
+        item = parse_quote!(#item.raw());
+    }
+    for key in options.keys {
+        item = parse_quote!(#item.key(#key));
+    }
+    Ok(item)
+}
+
+fn parse_options(attrs: &[Attribute]) -> syn::Result<StateOptions> {
+    let mut options = StateOptions::default();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("state")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "expected #[state(namespace = \"...\", key = \"...\", raw)]",
+                ))
+            }
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("namespace") => {
+                    match nv.lit {
+                        Lit::Str(s) => options.namespace = Some(s.value()),
+                        lit => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "the namespace of a state item must be a string literal",
+                            ))
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("key") => {
+                    let encoding = match &nv.lit {
+                        Lit::Str(s) => s.value(),
+                        _ => String::new(),
+                    };
+                    let key: Expr = match encoding.as_str() {
+                        "utf8" => parse_quote!(cosmwasm_schema::KeyEncoding::Utf8),
+                        "big_endian" => parse_quote!(cosmwasm_schema::KeyEncoding::BigEndian),
+                        "raw" => parse_quote!(cosmwasm_schema::KeyEncoding::Raw),
+                        _ => {
+                            return Err(syn::Error::new(
+                                nv.lit.span(),
+                                "unknown key encoding, expected \"utf8\", \"big_endian\" or \"raw\"",
+                            ))
+                        }
+                    };
+                    options.keys.push(key);
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("raw") => options.raw = true,
+                nested => {
+                    return Err(syn::Error::new(
+                        nested.span(),
+                        "unknown state option, expected `namespace = \"...\"`, `key = \"...\"` or `raw`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn happy_path() {
+        let input: ItemEnum = parse_quote! {
+            pub enum State {
+                /// The configuration
+                Config(Config),
+                #[state(namespace = "balance", key = "utf8")]
+                Balances(Uint128),
+                #[state(key = "utf8", key = "big_endian")]
+                #[deprecated]
+                Claims(Vec<Claim>),
+                #[state(namespace = "owner", raw)]
+                Owner(String),
+            }
+        };
+
+        assert_eq!(
+            contract_state_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl cosmwasm_schema::ContractState for State {
+                    fn state_schemas() -> Vec<cosmwasm_schema::StateSchema> {
+                        vec![
+                            cosmwasm_schema::StateSchema::new("config", cosmwasm_schema::schema_for!(Config))
+                                .description("The configuration"),
+                            cosmwasm_schema::StateSchema::new("balance", cosmwasm_schema::schema_for!(Uint128))
+                                .key(cosmwasm_schema::KeyEncoding::Utf8),
+                            cosmwasm_schema::StateSchema::new("claims", cosmwasm_schema::schema_for!(Vec<Claim>))
+                                .deprecated()
+                                .key(cosmwasm_schema::KeyEncoding::Utf8)
+                                .key(cosmwasm_schema::KeyEncoding::BigEndian),
+                            cosmwasm_schema::StateSchema::new("owner", cosmwasm_schema::schema_for!(String))
+                                .raw(),
+                        ]
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn generics() {
+        let input: ItemEnum = parse_quote! {
+            pub enum State<T: JsonSchema> {
+                #[state(key = "raw")]
+                Items(T),
+            }
+        };
+
+        assert_eq!(
+            contract_state_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl<T: JsonSchema> cosmwasm_schema::ContractState for State<T> {
+                    fn state_schemas() -> Vec<cosmwasm_schema::StateSchema> {
+                        vec![
+                            cosmwasm_schema::StateSchema::new("items", cosmwasm_schema::schema_for!(T))
+                                .key(cosmwasm_schema::KeyEncoding::Raw),
+                        ]
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_items_are_reported() {
+        let input: ItemEnum = parse_quote! {
+            pub enum State {
+                Config { owner: String },
+                Empty,
+                #[state(key = "u64")]
+                Counter(u64),
+                #[state(prefix = "x")]
+                Other(u64),
+            }
+        };
+
+        let errors: Vec<_> = contract_state_derive_impl(input)
+            .unwrap_err()
+            .into_iter()
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "state item Config must have the type of the stored value as its only field",
+                "state item Empty must have the type of the stored value as its only field",
+                "unknown key encoding, expected \"utf8\", \"big_endian\" or \"raw\"",
+                "unknown state option, expected `namespace = \"...\"`, `key = \"...\"` or `raw`",
+            ]
+        );
+    }
+}
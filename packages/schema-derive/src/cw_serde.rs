@@ -1,34 +1,434 @@
-use syn::{parse_quote, DeriveInput};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    Attribute, DeriveInput, Expr, Fields, ItemFn, Lit, LitStr, Meta, NestedMeta, Token, Type,
+    Variant,
+};
 
-pub fn cw_serde_impl(input: DeriveInput) -> DeriveInput {
-    match input.data {
+use crate::constraints::{is_schema_attr, Constraints};
+
+/// Options of the `cw_serde` attribute, e.g. `#[cw_serde(bound = "T: MyTrait")]`
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Replaces the trait bounds serde infers for the generic parameters, see
+    /// <https://serde.rs/container-attrs.html#bound>
+    bound: Option<LitStr>,
+    /// Omits `#[serde(deny_unknown_fields)]` so that unknown fields are ignored
+    allow_unknown_fields: bool,
+    /// The path of `cosmwasm_schema` if it is re-exported by another crate,
+    /// which is used to refer to serde, schemars and the array helpers
+    crate_path: Option<LitStr>,
+    /// Traits derived in addition to the default ones, e.g. `Eq` or `Hash`
+    derives: Vec<syn::Path>,
+    /// The field containing the variant name of an internally or adjacently tagged enum,
+    /// see <https://serde.rs/enum-representations.html>
+    tag: Option<LitStr>,
+    /// The field containing the variant content of an adjacently tagged enum
+    content: Option<LitStr>,
+    /// Checks the ranges and lengths of `#[schema(...)]` field attributes on deserialization
+    validate: bool,
+    /// Encodes `[u8; N]` fields as base64 strings instead of arrays of numbers
+    base64_arrays: bool,
+}
+
+/// The paths used in the expansion
+struct Paths {
+    serde: String,
+    schemars: String,
+    cosmwasm_schema: String,
+}
+
+impl Paths {
+    fn new(crate_path: Option<&LitStr>) -> Self {
+        match crate_path {
+            Some(path) => {
+                let path = path.value();
+                syn::parse_str::<syn::Path>(&path)
+                    .unwrap_or_else(|_| panic!("crate must be a path, got \"{}\"", path));
+                Paths {
+                    serde: format!("{}::serde", path),
+                    schemars: format!("{}::schemars", path),
+                    cosmwasm_schema: path,
+                }
+            }
+            None => Paths {
+                serde: "serde".to_string(),
+                schemars: "schemars".to_string(),
+                cosmwasm_schema: "::cosmwasm_schema".to_string(),
+            },
+        }
+    }
+
+    fn serde(&self, item: &str) -> syn::Path {
+        syn::parse_str(&format!("{}::{}", self.serde, item)).unwrap()
+    }
+
+    fn schemars(&self, item: &str) -> syn::Path {
+        syn::parse_str(&format!("{}::{}", self.schemars, item)).unwrap()
+    }
+}
+
+/// The traits `cw_serde` always derives
+const DEFAULT_DERIVES: [&str; 6] = [
+    "Serialize",
+    "Deserialize",
+    "Clone",
+    "Debug",
+    "PartialEq",
+    "JsonSchema",
+];
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let mut options = Options::default();
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        for meta in metas {
+            match meta {
+                Meta::NameValue(pair) if pair.path.is_ident("bound") => match pair.lit {
+                    Lit::Str(bound) => options.bound = Some(bound),
+                    _ => panic!("bound must be a string literal"),
+                },
+                Meta::Path(path) if path.is_ident("allow_unknown_fields") => {
+                    options.allow_unknown_fields = true
+                }
+                Meta::Path(path) if path.is_ident("validate") => options.validate = true,
+                Meta::Path(path) if path.is_ident("base64_arrays") => options.base64_arrays = true,
+                Meta::NameValue(pair) if pair.path.is_ident("crate") => match pair.lit {
+                    Lit::Str(path) => options.crate_path = Some(path),
+                    _ => panic!("crate must be a string literal"),
+                },
+                Meta::NameValue(pair) if pair.path.is_ident("tag") => match pair.lit {
+                    Lit::Str(tag) => options.tag = Some(tag),
+                    _ => panic!("tag must be a string literal"),
+                },
+                Meta::NameValue(pair) if pair.path.is_ident("content") => match pair.lit {
+                    Lit::Str(content) => options.content = Some(content),
+                    _ => panic!("content must be a string literal"),
+                },
+                Meta::List(list) if list.path.is_ident("derive") => {
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::Path(path)) => {
+                                if let Some(name) = DEFAULT_DERIVES
+                                    .iter()
+                                    .find(|name| path.segments.last().unwrap().ident == name)
+                                {
+                                    panic!("{} is always derived by cw_serde", name);
+                                }
+                                options.derives.push(path)
+                            }
+                            _ => panic!("derive must be a list of traits"),
+                        }
+                    }
+                }
+                other => panic!(
+                    "unknown cw_serde option: {}",
+                    other
+                        .path()
+                        .get_ident()
+                        .map_or_else(|| "<path>".to_string(), ToString::to_string)
+                ),
+            }
+        }
+        if options.content.is_some() && options.tag.is_none() {
+            panic!("content requires a tag");
+        }
+        Ok(options)
+    }
+}
+
+/// Replaces the `#[schema_example(...)]` attributes of the type, its variants and fields by
+/// schemars examples. schemars expects the path of a function returning the example, so
+/// the returned functions have to be emitted next to the type.
+pub fn cw_serde_examples(options: &Options, input: &mut DeriveInput) -> Vec<ItemFn> {
+    let paths = Paths::new(options.crate_path.as_ref());
+    let serialize = paths.serde("Serialize");
+    let type_name = input.ident.to_string();
+    let mut functions = vec![];
+    let mut replace = |attrs: &mut Vec<Attribute>| {
+        let (examples, others) = std::mem::take(attrs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|attr| attr.path.is_ident("schema_example"));
+        *attrs = others;
+        for example in examples {
+            let value: Expr = example
+                .parse_args()
+                .unwrap_or_else(|_| panic!("schema_example must contain an expression"));
+            let name = format_ident!("__cw_serde_example_{}_{}", type_name, functions.len());
+            let path = name.to_string();
+            attrs.push(parse_quote! { #[schemars(example = #path)] });
+            functions.push(parse_quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn #name() -> impl #serialize {
+                    #value
+                }
+            });
+        }
+    };
+
+    replace(&mut input.attrs);
+    match &mut input.data {
+        syn::Data::Struct(data) => data.fields.iter_mut().for_each(|f| replace(&mut f.attrs)),
+        syn::Data::Enum(data) => {
+            for variant in &mut data.variants {
+                replace(&mut variant.attrs);
+                variant
+                    .fields
+                    .iter_mut()
+                    .for_each(|f| replace(&mut f.attrs));
+            }
+        }
+        syn::Data::Union(_) => {}
+    }
+    functions
+}
+
+pub fn cw_serde_impl(options: Options, mut input: DeriveInput) -> DeriveInput {
+    let paths = Paths::new(options.crate_path.as_ref());
+    let keep_constraints = derives_validate(&options.derives, &input.attrs);
+    match &mut input.data {
+        syn::Data::Struct(data) => {
+            annotate_constraints(&mut data.fields, &paths, options.validate, keep_constraints);
+            annotate_arrays(&mut data.fields, &paths, options.base64_arrays);
+        }
+        syn::Data::Enum(data) => {
+            for variant in &mut data.variants {
+                annotate_constraints(
+                    &mut variant.fields,
+                    &paths,
+                    options.validate,
+                    keep_constraints,
+                );
+                annotate_arrays(&mut variant.fields, &paths, options.base64_arrays);
+                annotate_query_aliases(variant);
+            }
+        }
+        syn::Data::Union(_) => {}
+    }
+
+    let deny_unknown_fields = if options.allow_unknown_fields {
+        vec![]
+    } else {
+        vec![quote! { deny_unknown_fields }]
+    };
+
+    let serialize = paths.serde("Serialize");
+    let deserialize = paths.serde("Deserialize");
+    let json_schema = paths.schemars("JsonSchema");
+    let derives = &options.derives;
+    let tag = options.tag.iter();
+    let content = options.content.iter();
+
+    let mut expanded: DeriveInput = match input.data {
+        syn::Data::Struct(_) if options.tag.is_some() => {
+            panic!("tag is only supported for enums")
+        }
         syn::Data::Struct(_) => parse_quote! {
             #[derive(
-                serde::Serialize,
-                serde::Deserialize,
+                #serialize,
+                #deserialize,
                 Clone,
                 Debug,
                 PartialEq,
-                schemars::JsonSchema
+                #json_schema
+                #( , #derives )*
             )]
             #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
-            #[serde(deny_unknown_fields)]
+            #( #[serde(#deny_unknown_fields)] )*
             #input
         },
         syn::Data::Enum(_) => parse_quote! {
             #[derive(
-                serde::Serialize,
-                serde::Deserialize,
+                #serialize,
+                #deserialize,
                 Clone,
                 Debug,
                 PartialEq,
-                schemars::JsonSchema
+                #json_schema
+                #( , #derives )*
             )]
             #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
-            #[serde(deny_unknown_fields, rename_all = "snake_case")]
+            #[serde(
+                #( #deny_unknown_fields, )*
+                rename_all = "snake_case"
+                #( , tag = #tag )*
+                #( , content = #content )*
+            )]
             #input
         },
         syn::Data::Union(_) => panic!("unions are not supported"),
+    };
+
+    if let Some(bound) = options.bound {
+        expanded
+            .attrs
+            .push(parse_quote! { #[serde(bound = #bound)] });
+    }
+
+    if options.crate_path.is_some() {
+        let serde = &paths.serde;
+        let schemars = &paths.schemars;
+        expanded
+            .attrs
+            .push(parse_quote! { #[serde(crate = #serde)] });
+        expanded
+            .attrs
+            .push(parse_quote! { #[schemars(crate = #schemars)] });
+    }
+
+    expanded
+}
+
+/// Turns `#[schema(...)]` field attributes into schemars validation attributes and,
+/// if `validate` is set, into serde helpers checking the ranges and lengths.
+///
+/// The `#[schema(...)]` attributes are removed unless they are needed by `#[derive(Validate)]`.
+fn annotate_constraints(fields: &mut Fields, paths: &Paths, validate: bool, keep: bool) {
+    for field in fields.iter_mut() {
+        let constraints = Constraints::parse(&field.attrs);
+        if !keep {
+            field.attrs.retain(|attr| !is_schema_attr(attr));
+        }
+        field.attrs.extend(constraints.schemars_attrs());
+        if !validate {
+            continue;
+        }
+        let deserialize_with = match constraints.deserialize_with(&paths.cosmwasm_schema) {
+            Some(path) => path,
+            None => continue,
+        };
+        if matches!(field.ty, Type::Array(_)) || field.attrs.iter().any(has_custom_serialization) {
+            panic!("validate does not support fields with custom serialization");
+        }
+        field.attrs.push(if is_option(&field.ty) {
+            // Using `deserialize_with` makes missing optional fields an error otherwise
+            parse_quote! { #[serde(deserialize_with = #deserialize_with, default)] }
+        } else {
+            parse_quote! { #[serde(deserialize_with = #deserialize_with)] }
+        });
+    }
+}
+
+/// Adds serde and schemars helpers to fixed-size array fields, since serde supports
+/// `[T; N]` for `N <= 32` only and schemars describes them as tuples. The JSON stays the
+/// one of serde, unless `base64` is set, which encodes `[u8; N]` as a base64 string.
+///
+/// Fields that already customize their serialization or schema are left untouched.
+fn annotate_arrays(fields: &mut Fields, paths: &Paths, base64: bool) {
+    for field in fields.iter_mut() {
+        let array = match &field.ty {
+            Type::Array(array) => array,
+            _ => continue,
+        };
+        if field.attrs.iter().any(has_custom_serialization) {
+            continue;
+        }
+
+        let elem = &array.elem;
+        let len = &array.len;
+        let attrs: [Attribute; 2] = if base64 && is_u8(elem) {
+            let schema = format!(
+                "{}::array::Base64Array<{{ {} }}>",
+                paths.cosmwasm_schema,
+                quote!(#len)
+            );
+            let with = format!("{}::array::base64", paths.cosmwasm_schema);
+            [
+                parse_quote! { #[serde(with = #with)] },
+                parse_quote! { #[schemars(with = #schema)] },
+            ]
+        } else {
+            let schema = format!(
+                "{}::array::FixedArray<{}, {{ {} }}>",
+                paths.cosmwasm_schema,
+                quote!(#elem),
+                quote!(#len)
+            );
+            let with = format!("{}::array::elements", paths.cosmwasm_schema);
+            // Using `with` disables serde's bound inference for this field
+            let ser_bound = format!("{}: {}::Serialize", quote!(#elem), paths.serde);
+            let de_bound = format!("{}: {}::Deserialize<'de>", quote!(#elem), paths.serde);
+            [
+                parse_quote! {
+                    #[serde(
+                        with = #with,
+                        bound(serialize = #ser_bound, deserialize = #de_bound)
+                    )]
+                },
+                parse_quote! { #[schemars(with = #schema)] },
+            ]
+        };
+        field.attrs.extend(attrs);
+    }
+}
+
+/// Makes the variant accept the legacy names given as `#[query_alias("old_name")]`.
+///
+/// The attributes are kept for `#[derive(QueryResponses)]`, which documents the aliases
+/// in the schema and reports invalid ones.
+fn annotate_query_aliases(variant: &mut Variant) {
+    let aliases: Vec<LitStr> = variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("query_alias"))
+        .filter_map(|attr| attr.parse_args().ok())
+        .collect();
+    for alias in aliases {
+        variant
+            .attrs
+            .push(parse_quote! { #[serde(alias = #alias)] });
+    }
+}
+
+/// Whether `Validate` is derived, either with `#[cw_serde(derive(Validate))]` or
+/// a `#[derive(Validate)]` on the type
+fn derives_validate(derives: &[syn::Path], attrs: &[Attribute]) -> bool {
+    let is_validate = |path: &syn::Path| path.segments.last().unwrap().ident == "Validate";
+    derives.iter().any(is_validate)
+        || attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("derive"))
+            .any(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+                    NestedMeta::Meta(Meta::Path(path)) => is_validate(path),
+                    _ => false,
+                }),
+                _ => false,
+            })
+}
+
+pub(crate) fn is_option(ty: &Type) -> bool {
+    let last = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    };
+    matches!(last, Some(segment) if segment.ident == "Option")
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8"))
+}
+
+fn has_custom_serialization(attr: &Attribute) -> bool {
+    const KEYS: [&str; 5] = [
+        "with",
+        "serialize_with",
+        "deserialize_with",
+        "schema_with",
+        "skip",
+    ];
+    if !(attr.path.is_ident("serde") || attr.path.is_ident("schemars")) {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+            syn::NestedMeta::Meta(meta) => KEYS.iter().any(|key| meta.path().is_ident(key)),
+            syn::NestedMeta::Lit(_) => false,
+        }),
+        _ => false,
     }
 }
 
@@ -38,12 +438,15 @@ mod tests {
 
     #[test]
     fn structs() {
-        let expanded = cw_serde_impl(parse_quote! {
-            pub struct InstantiateMsg {
-                pub verifier: String,
-                pub beneficiary: String,
-            }
-        });
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub struct InstantiateMsg {
+                    pub verifier: String,
+                    pub beneficiary: String,
+                }
+            },
+        );
 
         let expected = parse_quote! {
             #[derive(
@@ -67,9 +470,12 @@ mod tests {
 
     #[test]
     fn empty_struct() {
-        let expanded = cw_serde_impl(parse_quote! {
-            pub struct InstantiateMsg {}
-        });
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub struct InstantiateMsg {}
+            },
+        );
 
         let expected = parse_quote! {
             #[derive(
@@ -90,14 +496,52 @@ mod tests {
 
     #[test]
     fn enums() {
-        let expanded = cw_serde_impl(parse_quote! {
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub enum SudoMsg {
+                    StealFunds {
+                        recipient: String,
+                        amount: Vec<Coin>,
+                    },
+                }
+            },
+        );
+
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(deny_unknown_fields, rename_all = "snake_case")]
             pub enum SudoMsg {
                 StealFunds {
                     recipient: String,
                     amount: Vec<Coin>,
                 },
             }
-        });
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn enums_with_query_aliases() {
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub enum QueryMsg {
+                    #[returns(SupplyResponse)]
+                    #[query_alias("total_supply")]
+                    Supply {},
+                }
+            },
+        );
 
         let expected = parse_quote! {
             #[derive(
@@ -110,25 +554,572 @@ mod tests {
             )]
             #[allow(clippy::derive_partial_eq_without_eq)]
             #[serde(deny_unknown_fields, rename_all = "snake_case")]
+            pub enum QueryMsg {
+                #[returns(SupplyResponse)]
+                #[query_alias("total_supply")]
+                #[serde(alias = "total_supply")]
+                Supply {},
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn generic_struct_with_bound() {
+        let expanded = cw_serde_impl(
+            parse_quote! { bound = "T: MyTrait" },
+            parse_quote! {
+                pub struct Wrapper<T: MyTrait> {
+                    pub inner: T::Assoc,
+                }
+            },
+        );
+
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(deny_unknown_fields)]
+            #[serde(bound = "T: MyTrait")]
+            pub struct Wrapper<T: MyTrait> {
+                pub inner: T::Assoc,
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn arrays() {
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub struct Hashes<T> {
+                    pub hash: [u8; 32],
+                    pub items: [T; N],
+                    #[serde(with = "my_format")]
+                    pub custom: [u8; 4],
+                    pub other: Vec<u8>,
+                }
+            },
+        );
+
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(deny_unknown_fields)]
+            pub struct Hashes<T> {
+                #[serde(
+                    with = "::cosmwasm_schema::array::elements",
+                    bound(serialize = "u8: serde::Serialize", deserialize = "u8: serde::Deserialize<'de>")
+                )]
+                #[schemars(with = "::cosmwasm_schema::array::FixedArray<u8, { 32 }>")]
+                pub hash: [u8; 32],
+                #[serde(
+                    with = "::cosmwasm_schema::array::elements",
+                    bound(serialize = "T: serde::Serialize", deserialize = "T: serde::Deserialize<'de>")
+                )]
+                #[schemars(with = "::cosmwasm_schema::array::FixedArray<T, { N }>")]
+                pub items: [T; N],
+                #[serde(with = "my_format")]
+                pub custom: [u8; 4],
+                pub other: Vec<u8>,
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn base64_arrays() {
+        let options: Options = parse_quote! { base64_arrays };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub enum Key {
+                    Ed25519([u8; 32]),
+                    Other { points: [u32; 2] },
+                }
+            },
+        );
+
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(deny_unknown_fields, rename_all = "snake_case")]
+            pub enum Key {
+                Ed25519(
+                    #[serde(with = "::cosmwasm_schema::array::base64")]
+                    #[schemars(with = "::cosmwasm_schema::array::Base64Array<{ 32 }>")]
+                    [u8; 32]
+                ),
+                Other {
+                    #[serde(
+                        with = "::cosmwasm_schema::array::elements",
+                        bound(serialize = "u32: serde::Serialize", deserialize = "u32: serde::Deserialize<'de>")
+                    )]
+                    #[schemars(with = "::cosmwasm_schema::array::FixedArray<u32, { 2 }>")]
+                    points: [u32; 2]
+                },
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn options_parse() {
+        let options: Options = parse_quote! {};
+        assert!(options.bound.is_none());
+        assert!(!options.allow_unknown_fields);
+        let options: Options = parse_quote! { bound = "" };
+        assert_eq!(options.bound.unwrap().value(), "");
+        let options: Options = parse_quote! { allow_unknown_fields, bound = "T: Clone" };
+        assert!(options.allow_unknown_fields);
+        assert_eq!(options.bound.unwrap().value(), "T: Clone");
+        let options: Options = parse_quote! { crate = "sdk::cosmwasm_schema" };
+        assert_eq!(options.crate_path.unwrap().value(), "sdk::cosmwasm_schema");
+        assert!(!options.base64_arrays);
+        let options: Options = parse_quote! { base64_arrays };
+        assert!(options.base64_arrays);
+    }
+
+    #[test]
+    fn allow_unknown_fields() {
+        let options: Options = parse_quote! { allow_unknown_fields };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub struct InstantiateMsg {
+                    pub verifier: String,
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            pub struct InstantiateMsg {
+                pub verifier: String,
+            }
+        };
+        assert_eq!(expanded, expected);
+
+        let options: Options = parse_quote! { allow_unknown_fields };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub enum SudoMsg {
+                    Kill {},
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(rename_all = "snake_case")]
             pub enum SudoMsg {
-                StealFunds {
-                    recipient: String,
-                    amount: Vec<Coin>,
+                Kill {},
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn crate_path() {
+        let options: Options = parse_quote! { crate = "my_sdk::cosmwasm_schema", base64_arrays };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub struct InstantiateMsg {
+                    pub pubkey: [u8; 33],
+                    pub points: [T; 2],
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                my_sdk::cosmwasm_schema::serde::Serialize,
+                my_sdk::cosmwasm_schema::serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                my_sdk::cosmwasm_schema::schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(deny_unknown_fields)]
+            #[serde(crate = "my_sdk::cosmwasm_schema::serde")]
+            #[schemars(crate = "my_sdk::cosmwasm_schema::schemars")]
+            pub struct InstantiateMsg {
+                #[serde(with = "my_sdk::cosmwasm_schema::array::base64")]
+                #[schemars(with = "my_sdk::cosmwasm_schema::array::Base64Array<{ 33 }>")]
+                pub pubkey: [u8; 33],
+                #[serde(
+                    with = "my_sdk::cosmwasm_schema::array::elements",
+                    bound(
+                        serialize = "T: my_sdk::cosmwasm_schema::serde::Serialize",
+                        deserialize = "T: my_sdk::cosmwasm_schema::serde::Deserialize<'de>"
+                    )
+                )]
+                #[schemars(with = "my_sdk::cosmwasm_schema::array::FixedArray<T, { 2 }>")]
+                pub points: [T; 2],
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn derives() {
+        let options: Options = parse_quote! { derive(Eq, Hash, std::cmp::PartialOrd) };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub enum Denom {
+                    Native(String),
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema,
+                Eq,
+                Hash,
+                std::cmp::PartialOrd
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(deny_unknown_fields, rename_all = "snake_case")]
+            pub enum Denom {
+                Native(String),
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn tagged_enums() {
+        let options: Options = parse_quote! { tag = "type", content = "value" };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub enum ExecuteMsg {
+                    Transfer { recipient: String },
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "type", content = "value")]
+            pub enum ExecuteMsg {
+                Transfer { recipient: String },
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn constraints() {
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub struct Order {
+                    #[schema(min = 1, max = 100)]
+                    pub quantity: u32,
+                    #[schema(min_length = 1, max_length = "MAX_LEN", pattern = "^[a-z]+$")]
+                    pub memo: String,
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(deny_unknown_fields)]
+            pub struct Order {
+                #[schemars(range(min = 1, max = 100))]
+                pub quantity: u32,
+                #[schemars(length(min = 1, max = "MAX_LEN"))]
+                #[schemars(regex(pattern = "^[a-z]+$"))]
+                pub memo: String,
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn validated_constraints() {
+        let options: Options = parse_quote! { validate };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub enum ExecuteMsg {
+                    Buy {
+                        #[schema(min = 1)]
+                        quantity: u32,
+                        #[schema(max_length = 3)]
+                        memo: Option<String>,
+                        #[schema(pattern = "^[a-z]+$")]
+                        denom: String,
+                    },
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(deny_unknown_fields, rename_all = "snake_case")]
+            pub enum ExecuteMsg {
+                Buy {
+                    #[schemars(range(min = 1))]
+                    #[serde(deserialize_with = "::cosmwasm_schema::constraints::range::<_, _, { 1 as i128 }, { i128::MAX }>")]
+                    quantity: u32,
+                    #[schemars(length(max = 3))]
+                    #[serde(deserialize_with = "::cosmwasm_schema::constraints::length::<_, _, { 0 }, { 3 as usize }>", default)]
+                    memo: Option<String>,
+                    #[schemars(regex(pattern = "^[a-z]+$"))]
+                    denom: String,
                 },
             }
         };
+        assert_eq!(expanded, expected);
+    }
 
+    #[test]
+    fn constraints_with_validate_derive() {
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                #[derive(Validate)]
+                pub struct Order {
+                    #[schema(min = 1)]
+                    pub quantity: u32,
+                }
+            },
+        );
+        let expected = parse_quote! {
+            #[derive(
+                serde::Serialize,
+                serde::Deserialize,
+                Clone,
+                Debug,
+                PartialEq,
+                schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+            #[serde(deny_unknown_fields)]
+            #[derive(Validate)]
+            pub struct Order {
+                #[schema(min = 1)]
+                #[schemars(range(min = 1))]
+                pub quantity: u32,
+            }
+        };
         assert_eq!(expanded, expected);
     }
 
+    #[test]
+    fn examples() {
+        let mut input = parse_quote! {
+            #[schema_example(ExecuteMsg::Pause {})]
+            pub enum ExecuteMsg {
+                #[schema_example(json!({ "transfer": { "recipient": "bob" } }))]
+                Transfer {
+                    #[schema_example("bob")]
+                    recipient: String,
+                },
+                Pause {},
+            }
+        };
+        let examples = cw_serde_examples(&Options::default(), &mut input);
+        let expected: DeriveInput = parse_quote! {
+            #[schemars(example = "__cw_serde_example_ExecuteMsg_0")]
+            pub enum ExecuteMsg {
+                #[schemars(example = "__cw_serde_example_ExecuteMsg_1")]
+                Transfer {
+                    #[schemars(example = "__cw_serde_example_ExecuteMsg_2")]
+                    recipient: String,
+                },
+                Pause {},
+            }
+        };
+        assert_eq!(input, expected);
+        let expected: Vec<ItemFn> = vec![
+            parse_quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn __cw_serde_example_ExecuteMsg_0() -> impl serde::Serialize {
+                    ExecuteMsg::Pause {}
+                }
+            },
+            parse_quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn __cw_serde_example_ExecuteMsg_1() -> impl serde::Serialize {
+                    json!({ "transfer": { "recipient": "bob" } })
+                }
+            },
+            parse_quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn __cw_serde_example_ExecuteMsg_2() -> impl serde::Serialize {
+                    "bob"
+                }
+            },
+        ];
+        assert_eq!(examples, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "schema_example must contain an expression")]
+    fn examples_invalid() {
+        let mut input = parse_quote! {
+            #[schema_example]
+            pub struct Empty {}
+        };
+        cw_serde_examples(&Options::default(), &mut input);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown schema constraint: minimum")]
+    fn constraints_unknown() {
+        cw_serde_impl(
+            Options::default(),
+            parse_quote! { pub struct Msg { #[schema(minimum = 1)] x: u32 } },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "validate requires integer bounds")]
+    fn validated_constraints_with_float() {
+        cw_serde_impl(
+            parse_quote! { validate },
+            parse_quote! { pub struct Msg { #[schema(max = 0.5)] x: u32 } },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "validate does not support fields with custom serialization")]
+    fn validated_constraints_with_custom_serialization() {
+        cw_serde_impl(
+            parse_quote! { validate },
+            parse_quote! {
+                pub struct Msg {
+                    #[schema(min = 1)]
+                    #[serde(with = "my_format")]
+                    x: u32,
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "content requires a tag")]
+    fn content_without_tag() {
+        let _options: Options = parse_quote! { content = "value" };
+    }
+
+    #[test]
+    #[should_panic(expected = "tag is only supported for enums")]
+    fn tagged_struct() {
+        let options: Options = parse_quote! { tag = "type" };
+        cw_serde_impl(options, parse_quote! { pub struct Empty {} });
+    }
+
+    #[test]
+    #[should_panic(expected = "Clone is always derived by cw_serde")]
+    fn derives_default() {
+        let _options: Options = parse_quote! { derive(Eq, Clone) };
+    }
+
+    #[test]
+    #[should_panic(expected = "derive must be a list of traits")]
+    fn derives_invalid() {
+        let _options: Options = parse_quote! { derive(Eq = "foo") };
+    }
+
+    #[test]
+    #[should_panic(expected = "crate must be a path, got \"not a path\"")]
+    fn crate_path_invalid() {
+        let options: Options = parse_quote! { crate = "not a path" };
+        cw_serde_impl(options, parse_quote! { pub struct Empty {} });
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown cw_serde option: rename")]
+    fn options_unknown() {
+        let _options: Options = parse_quote! { rename = "foo" };
+    }
+
     #[test]
     #[should_panic(expected = "unions are not supported")]
     fn unions() {
-        cw_serde_impl(parse_quote! {
-            pub union SudoMsg {
-                x: u32,
-                y: u32,
-            }
-        });
+        cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub union SudoMsg {
+                    x: u32,
+                    y: u32,
+                }
+            },
+        );
     }
 }
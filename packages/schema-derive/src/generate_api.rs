@@ -3,6 +3,7 @@ use std::collections::BTreeMap;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
+    braced,
     parse::{Parse, ParseStream},
     parse_quote, Block, ExprStruct, Ident, Path, Token,
 };
@@ -16,20 +17,91 @@ pub fn write_api_impl(input: Options) -> Block {
             use std::env::current_dir;
             use std::fs::{create_dir_all, write};
 
-            use cosmwasm_schema::{remove_schemas, Api, QueryResponses};
+            use cosmwasm_schema::{
+                check_schema_file, dialect_mode, diff_api_file, diff_mode_base, go_package_name,
+                is_bundle_mode, is_check_mode, is_go_mode, is_openapi_mode, is_proto_descriptor_mode,
+                is_proto_mode, is_python_mode, is_ts_mode, proto_package_name, python_module_name,
+                remove_schemas, Api, QueryResponses,
+            };
 
             let mut out_dir = current_dir().unwrap();
             out_dir.push("schema");
-            create_dir_all(&out_dir).unwrap();
-            remove_schemas(&out_dir).unwrap();
-
             let path = out_dir.join(concat!(#name, ".json"));
 
             let api = #api_object.render();
+            let dialect = match dialect_mode() {
+                Ok(dialect) => dialect,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+            let json = if is_bundle_mode() {
+                api.to_bundled_string(dialect).unwrap()
+            } else {
+                api.to_string_with_dialect(dialect).unwrap()
+            } + "\n";
+
+            let mut extra_files = Vec::new();
+            if is_go_mode() {
+                extra_files.push(("Go types", out_dir.join(concat!(#name, ".go")), api.to_go(&go_package_name(#name)).into_bytes()));
+            }
+            if is_openapi_mode() {
+                extra_files.push(("OpenAPI document", out_dir.join(concat!(#name, ".openapi.json")), (api.to_openapi().unwrap() + "\n").into_bytes()));
+            }
+            if is_proto_mode() {
+                extra_files.push(("proto definitions", out_dir.join(concat!(#name, ".proto")), api.to_proto(&proto_package_name(#name)).into_bytes()));
+            }
+            if is_proto_descriptor_mode() {
+                extra_files.push(("proto descriptor set", out_dir.join(concat!(#name, ".binpb")), api.to_proto_descriptor_set(&proto_package_name(#name))));
+            }
+            if is_python_mode() {
+                extra_files.push(("Python models", out_dir.join(python_module_name(#name) + ".py"), api.to_python().into_bytes()));
+            }
+            if is_ts_mode() {
+                extra_files.push(("TypeScript definitions", out_dir.join(concat!(#name, ".ts")), api.to_ts().into_bytes()));
+            }
 
-            let json = api.to_string().unwrap();
-            write(&path, json + "\n").unwrap();
-            println!("Exported the full API as {}", path.to_str().unwrap());
+            if let Some(base) = diff_mode_base() {
+                match diff_api_file(&base, &json) {
+                    Ok(changes) if changes.is_empty() => {
+                        println!("The API has no breaking changes compared to {}", base.to_str().unwrap());
+                    }
+                    Ok(changes) => {
+                        eprintln!("The API has breaking changes compared to {}:", base.to_str().unwrap());
+                        for change in changes {
+                            eprintln!("- {}", change);
+                        }
+                        std::process::exit(1);
+                    }
+                    Err(err) => {
+                        eprintln!("{}", err);
+                        std::process::exit(1);
+                    }
+                }
+            } else if is_check_mode() {
+                if let Err(err) = check_schema_file(&path, &json) {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+                println!("The API in {} is up to date", path.to_str().unwrap());
+                for (kind, path, content) in extra_files {
+                    if let Err(err) = check_schema_file(&path, &content) {
+                        eprintln!("{}", err);
+                        std::process::exit(1);
+                    }
+                    println!("The {} in {} are up to date", kind, path.to_str().unwrap());
+                }
+            } else {
+                create_dir_all(&out_dir).unwrap();
+                remove_schemas(&out_dir).unwrap();
+                write(&path, json).unwrap();
+                println!("Exported the full API as {}", path.to_str().unwrap());
+                for (kind, path, content) in extra_files {
+                    write(&path, content).unwrap();
+                    println!("Exported {} as {}", kind, path.to_str().unwrap());
+                }
+            }
         }
     }
 }
@@ -43,6 +115,12 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
         query,
         migrate,
         sudo,
+        ibc_packet,
+        ibc_ack,
+        reply,
+        entry_points,
+        events,
+        state,
         responses,
     } = input;
 
@@ -55,6 +133,12 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
             query: #query,
             migrate: #migrate,
             sudo: #sudo,
+            ibc_packet: #ibc_packet,
+            ibc_ack: #ibc_ack,
+            reply: #reply,
+            entry_points: #entry_points,
+            events: #events,
+            state: #state,
             responses: #responses,
         }
     }
@@ -64,6 +148,7 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
 enum Value {
     Type(syn::Path),
     Str(syn::LitStr),
+    Map(Vec<(Ident, Value)>),
 }
 
 impl Value {
@@ -82,11 +167,24 @@ impl Value {
             panic!("expected a string literal");
         }
     }
+
+    fn unwrap_map(self) -> Vec<(Ident, Value)> {
+        if let Self::Map(m) = self {
+            m
+        } else {
+            panic!("expected a map like {{ name: Type }}");
+        }
+    }
 }
 
 impl Parse for Value {
     fn parse(input: ParseStream) -> syn::parse::Result<Self> {
-        if let Ok(p) = input.parse::<syn::Path>() {
+        if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            let pairs = content.parse_terminated::<Pair, Token![,]>(Pair::parse)?;
+            Ok(Self::Map(pairs.into_iter().map(|p| p.0).collect()))
+        } else if let Ok(p) = input.parse::<syn::Path>() {
             Ok(Self::Type(p))
         } else {
             Ok(Self::Str(input.parse::<syn::LitStr>()?))
@@ -107,6 +205,18 @@ impl Parse for Pair {
     }
 }
 
+/// The entry points with their own option, which cannot be given in `entry_points`
+const BUILTIN_ENTRY_POINTS: [&str; 8] = [
+    "instantiate",
+    "execute",
+    "query",
+    "migrate",
+    "sudo",
+    "reply",
+    "ibc_packet",
+    "ibc_ack",
+];
+
 #[derive(Debug)]
 pub struct Options {
     name: TokenStream,
@@ -116,6 +226,12 @@ pub struct Options {
     query: TokenStream,
     migrate: TokenStream,
     sudo: TokenStream,
+    ibc_packet: TokenStream,
+    ibc_ack: TokenStream,
+    reply: TokenStream,
+    entry_points: TokenStream,
+    events: TokenStream,
+    state: TokenStream,
     responses: TokenStream,
 }
 
@@ -163,8 +279,8 @@ impl Parse for Options {
             Some(ty) => {
                 let ty = ty.unwrap_type();
                 (
-                    quote! {Some(cosmwasm_schema::schema_for!(#ty))},
-                    quote! { Some(#ty::response_schemas().unwrap()) },
+                    quote! { Some(<#ty as cosmwasm_schema::QueryResponses>::query_schema()) },
+                    quote! { Some(<#ty as cosmwasm_schema::QueryResponses>::response_schemas().unwrap()) },
                 )
             }
             None => (quote! { None }, quote! { None }),
@@ -186,6 +302,64 @@ impl Parse for Options {
             None => quote! { None },
         };
 
+        let ibc_packet = match map.remove(&parse_quote!(ibc_packet)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                quote! {Some(cosmwasm_schema::schema_for!(#ty))}
+            }
+            None => quote! { None },
+        };
+
+        let ibc_ack = match map.remove(&parse_quote!(ibc_ack)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                quote! {Some(cosmwasm_schema::schema_for!(#ty))}
+            }
+            None => quote! { None },
+        };
+
+        let reply = match map.remove(&parse_quote!(reply)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                quote! {Some(cosmwasm_schema::schema_for!(#ty))}
+            }
+            None => quote! { None },
+        };
+
+        let entry_points = match map.remove(&parse_quote!(entry_points)) {
+            Some(entry_points) => entry_points.unwrap_map(),
+            None => vec![],
+        };
+        let entry_points = entry_points.into_iter().map(|(name, ty)| {
+            if BUILTIN_ENTRY_POINTS.contains(&name.to_string().as_str()) {
+                panic!("entry point {} has its own generate_api option", name);
+            }
+            let name = name.to_string();
+            let ty = ty.unwrap_type();
+            quote! { (#name.to_string(), cosmwasm_schema::schema_for!(#ty)) }
+        });
+        let entry_points = quote! {
+            std::collections::BTreeMap::from([
+                #( #entry_points, )*
+            ])
+        };
+
+        let events = match map.remove(&parse_quote!(events)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                quote! { Some(<#ty as cosmwasm_schema::ContractEvents>::event_schemas()) }
+            }
+            None => quote! { None },
+        };
+
+        let state = match map.remove(&parse_quote!(state)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                quote! { Some(<#ty as cosmwasm_schema::ContractState>::state_schemas()) }
+            }
+            None => quote! { None },
+        };
+
         if let Some((invalid_option, _)) = map.into_iter().next() {
             panic!("unknown generate_api option: {}", invalid_option);
         }
@@ -198,6 +372,12 @@ impl Parse for Options {
             query,
             migrate,
             sudo,
+            ibc_packet,
+            ibc_ack,
+            reply,
+            entry_points,
+            events,
+            state,
             responses,
         })
     }
@@ -222,6 +402,12 @@ mod tests {
                     query: None,
                     migrate: None,
                     sudo: None,
+                    ibc_packet: None,
+                    ibc_ack: None,
+                    reply: None,
+                    entry_points: std::collections::BTreeMap::from([]),
+                    events: None,
+                    state: None,
                     responses: None,
                 }
             }
@@ -245,6 +431,12 @@ mod tests {
                     query: None,
                     migrate: None,
                     sudo: None,
+                    ibc_packet: None,
+                    ibc_ack: None,
+                    reply: None,
+                    entry_points: std::collections::BTreeMap::from([]),
+                    events: None,
+                    state: None,
                     responses: None,
                 }
             }
@@ -260,6 +452,15 @@ mod tests {
                 query: QueryMsg,
                 migrate: MigrateMsg,
                 sudo: SudoMsg,
+                ibc_packet: PacketMsg,
+                ibc_ack: AcknowledgementMsg<DispatchResponse>,
+                reply: ReplyPayload,
+                entry_points: {
+                    before_send: hooks::BeforeSendMsg,
+                    end_block: EndBlockMsg<Empty>,
+                },
+                events: ContractEvent,
+                state: State,
             }),
             parse_quote! {
                 cosmwasm_schema::Api {
@@ -267,15 +468,51 @@ mod tests {
                     contract_version: env!("CARGO_PKG_VERSION").to_string(),
                     instantiate: cosmwasm_schema::schema_for!(InstantiateMsg),
                     execute: Some(cosmwasm_schema::schema_for!(ExecuteMsg)),
-                    query: Some(cosmwasm_schema::schema_for!(QueryMsg)),
+                    query: Some(<QueryMsg as cosmwasm_schema::QueryResponses>::query_schema()),
                     migrate: Some(cosmwasm_schema::schema_for!(MigrateMsg)),
                     sudo: Some(cosmwasm_schema::schema_for!(SudoMsg)),
-                    responses: Some(QueryMsg::response_schemas().unwrap()),
+                    ibc_packet: Some(cosmwasm_schema::schema_for!(PacketMsg)),
+                    ibc_ack: Some(cosmwasm_schema::schema_for!(AcknowledgementMsg<DispatchResponse>)),
+                    reply: Some(cosmwasm_schema::schema_for!(ReplyPayload)),
+                    entry_points: std::collections::BTreeMap::from([
+                        ("before_send".to_string(), cosmwasm_schema::schema_for!(hooks::BeforeSendMsg)),
+                        ("end_block".to_string(), cosmwasm_schema::schema_for!(EndBlockMsg<Empty>)),
+                    ]),
+                    events: Some(<ContractEvent as cosmwasm_schema::ContractEvents>::event_schemas()),
+                    state: Some(<State as cosmwasm_schema::ContractState>::state_schemas()),
+                    responses: Some(<QueryMsg as cosmwasm_schema::QueryResponses>::response_schemas().unwrap()),
                 }
             }
         );
     }
 
+    #[test]
+    fn api_object_generic_query() {
+        let api_object = generate_api_impl(&parse_quote! {
+            instantiate: InstantiateMsg,
+            query: QueryMsg<Empty>,
+        });
+        let expected: ExprStruct = parse_quote! {
+            cosmwasm_schema::Api {
+                contract_name: env!("CARGO_PKG_NAME").to_string(),
+                contract_version: env!("CARGO_PKG_VERSION").to_string(),
+                instantiate: cosmwasm_schema::schema_for!(InstantiateMsg),
+                execute: None,
+                query: Some(<QueryMsg<Empty> as cosmwasm_schema::QueryResponses>::query_schema()),
+                migrate: None,
+                sudo: None,
+                ibc_packet: None,
+                ibc_ack: None,
+                reply: None,
+                entry_points: std::collections::BTreeMap::from([]),
+                events: None,
+                state: None,
+                responses: Some(<QueryMsg<Empty> as cosmwasm_schema::QueryResponses>::response_schemas().unwrap()),
+            }
+        };
+        assert_eq!(api_object, expected);
+    }
+
     #[test]
     #[should_panic(expected = "unknown generate_api option: asd")]
     fn invalid_option() {
@@ -284,4 +521,13 @@ mod tests {
             asd: Asd,
         };
     }
+
+    #[test]
+    #[should_panic(expected = "entry point sudo has its own generate_api option")]
+    fn builtin_entry_point() {
+        let _options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+            entry_points: { sudo: SudoMsg },
+        };
+    }
 }
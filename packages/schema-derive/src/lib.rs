@@ -1,15 +1,52 @@
+mod constraints;
+mod contract_events;
+mod contract_state;
 mod cw_serde;
 mod generate_api;
 mod query_responses;
+mod validate;
 
 use quote::ToTokens;
 use syn::{parse_macro_input, DeriveInput, ItemEnum};
 
-#[proc_macro_derive(QueryResponses, attributes(returns))]
+#[proc_macro_derive(QueryResponses, attributes(returns, query_responses, query_alias))]
 pub fn query_responses_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as ItemEnum);
 
-    let expanded = query_responses::query_responses_derive_impl(input).into_token_stream();
+    let expanded = match query_responses::query_responses_derive_impl(input) {
+        Ok(expanded) => expanded.into_token_stream(),
+        Err(err) => err.to_compile_error(),
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(ContractEvents, attributes(event))]
+pub fn contract_events_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as ItemEnum);
+
+    let expanded = contract_events::contract_events_derive_impl(input).into_token_stream();
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(ContractState, attributes(state))]
+pub fn contract_state_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as ItemEnum);
+
+    let expanded = match contract_state::contract_state_derive_impl(input) {
+        Ok(expanded) => expanded.into_token_stream(),
+        Err(err) => err.to_compile_error(),
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(Validate, attributes(schema))]
+pub fn validate_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let expanded = validate::validate_derive_impl(input).into_token_stream();
 
     proc_macro::TokenStream::from(expanded)
 }
@@ -34,12 +71,18 @@ pub fn generate_api(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
 #[proc_macro_attribute]
 pub fn cw_serde(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
+    let options = parse_macro_input!(attr as cw_serde::Options);
+    let mut input = parse_macro_input!(input as DeriveInput);
 
-    let expanded = cw_serde::cw_serde_impl(input).into_token_stream();
+    let examples = cw_serde::cw_serde_examples(&options, &mut input);
+    let expanded = cw_serde::cw_serde_impl(options, input);
+    let expanded = quote::quote! {
+        #expanded
+        #( #examples )*
+    };
 
     proc_macro::TokenStream::from(expanded)
 }
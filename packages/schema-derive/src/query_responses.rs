@@ -1,40 +1,342 @@
-use syn::{parse_quote, Expr, ExprTuple, ItemEnum, ItemImpl, Type, Variant};
+use std::collections::BTreeSet;
 
-pub fn query_responses_derive_impl(input: ItemEnum) -> ItemImpl {
+use quote::quote;
+use syn::{
+    parse_quote, Attribute, Expr, ExprTuple, Fields, ItemEnum, ItemImpl, Lit, LitStr, Meta,
+    NestedMeta, Type, Variant,
+};
+
+pub fn query_responses_derive_impl(input: ItemEnum) -> syn::Result<ItemImpl> {
     let ident = input.ident;
-    let mappings = input.variants.into_iter().map(parse_query);
-    let mut queries: Vec<_> = mappings.clone().map(|(q, _)| q).collect();
-    queries.sort();
-    let mappings = mappings.map(parse_tuple);
+    // Every type parameter may be used in the response types, so they need schemas
+    let mut generics = input.generics;
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(schemars::JsonSchema));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    parse_quote! {
+    if is_nested(&input.attrs)? {
+        let subqueries = collect_results(input.variants.into_iter().map(parse_subquery))?;
+        let count = subqueries.len();
+        return Ok(parse_quote! {
+            #[automatically_derived]
+            #[cfg(not(target_arch = "wasm32"))]
+            impl #impl_generics cosmwasm_schema::QueryResponses for #ident #ty_generics #where_clause {
+                fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
+                    cosmwasm_schema::combine_subqueries::<#count, Self>([
+                        #( <#subqueries as cosmwasm_schema::QueryResponses>::response_schemas_impl(), )*
+                    ])
+                }
+
+                fn query_aliases() -> std::collections::BTreeMap<String, String> {
+                    cosmwasm_schema::combine_query_aliases::<#count, Self>([
+                        #( <#subqueries as cosmwasm_schema::QueryResponses>::query_aliases(), )*
+                    ])
+                }
+            }
+        });
+    }
+
+    let rename_all = serde_rename_all(&input.attrs);
+    let queries = collect_results(input.variants.into_iter().map(|variant| {
+        let aliases = parse_aliases(&variant)?;
+        let (query, schema) = parse_query(variant, rename_all.as_deref())?;
+        Ok((query, schema, aliases))
+    }))?;
+    check_aliases(&queries)?;
+
+    let aliases: Vec<_> = queries
+        .iter()
+        .flat_map(|(query, _, aliases)| aliases.iter().map(move |alias| (alias, query)))
+        .map(|(alias, query)| quote! { (#alias.to_string(), #query.to_string()) })
+        .collect();
+    let query_aliases = if aliases.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn query_aliases() -> std::collections::BTreeMap<String, String> {
+                std::collections::BTreeMap::from([
+                    #( #aliases, )*
+                ])
+            }
+        }
+    };
+    let mappings = queries
+        .into_iter()
+        .map(|(query, schema, _)| parse_tuple((query, schema)));
+
+    Ok(parse_quote! {
         #[automatically_derived]
         #[cfg(not(target_arch = "wasm32"))]
-        impl cosmwasm_schema::QueryResponses for #ident {
+        impl #impl_generics cosmwasm_schema::QueryResponses for #ident #ty_generics #where_clause {
             fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
                 std::collections::BTreeMap::from([
                     #( #mappings, )*
                 ])
             }
+
+            #query_aliases
+        }
+    })
+}
+
+/// The legacy names of a renamed query, given as `#[query_alias("old_name")]`
+fn parse_aliases(v: &Variant) -> syn::Result<Vec<LitStr>> {
+    v.attrs
+        .iter()
+        .filter(|a| a.path.is_ident("query_alias"))
+        .map(|attr| {
+            attr.parse_args::<LitStr>().map_err(|err| {
+                syn::Error::new(
+                    err.span(),
+                    format!(
+                        "query_alias for {} must be a string literal, e.g. #[query_alias(\"old_name\")]",
+                        v.ident
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Ensures that every alias is unique and does not shadow the name of a query
+fn check_aliases(queries: &[(String, Expr, Vec<LitStr>)]) -> syn::Result<()> {
+    let mut names: BTreeSet<_> = queries.iter().map(|(query, _, _)| query.clone()).collect();
+    let aliases = queries.iter().flat_map(|(_, _, aliases)| aliases);
+    collect_results(aliases.map(|alias| {
+        if names.insert(alias.value()) {
+            Ok(())
+        } else {
+            Err(syn::Error::new_spanned(
+                alias,
+                format!(
+                    "query alias {} is already used by another query",
+                    alias.value()
+                ),
+            ))
+        }
+    }))
+    .map(drop)
+}
+
+/// Collects the results of all variants, such that the errors of all of them are reported
+/// at once
+pub(crate) fn collect_results<T>(
+    results: impl Iterator<Item = syn::Result<T>>,
+) -> syn::Result<Vec<T>> {
+    let mut values = Vec::new();
+    let mut error: Option<syn::Error> = None;
+    for result in results {
+        match (result, &mut error) {
+            (Ok(value), _) => values.push(value),
+            (Err(err), Some(error)) => error.combine(err),
+            (Err(err), None) => error = Some(err),
+        }
+    }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(values),
+    }
+}
+
+/// Whether the enum is marked with `#[query_responses(nested)]`
+fn is_nested(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut nested = false;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("query_responses")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => {
+                for item in list.nested {
+                    match item {
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("nested") => {
+                            nested = true
+                        }
+                        item => {
+                            return Err(syn::Error::new_spanned(
+                                item,
+                                "unknown query_responses option, expected `nested`",
+                            ))
+                        }
+                    }
+                }
+            }
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected #[query_responses(nested)]",
+                ))
+            }
+        }
+    }
+    Ok(nested)
+}
+
+/// Extract the child query enum out of a variant of a nested query enum
+fn parse_subquery(v: Variant) -> syn::Result<Type> {
+    if let Some(alias) = v.attrs.iter().find(|a| a.path.is_ident("query_alias")) {
+        return Err(syn::Error::new_spanned(
+            alias,
+            "query_alias is not supported on nested queries, add it to the variant of the child query enum",
+        ));
+    }
+    match v.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            Ok(fields.unnamed.into_iter().next().unwrap().ty)
         }
+        fields => Err(syn::Error::new_spanned(
+            fields,
+            format!(
+                "nested query {} must be a tuple variant with a single query enum",
+                v.ident
+            ),
+        )),
     }
 }
 
 /// Extract the query -> response mapping out of an enum variant.
-fn parse_query(v: Variant) -> (String, Expr) {
-    let query = to_snake_case(&v.ident.to_string());
-    let response_ty: Type = v
-        .attrs
+///
+/// The query name is the name the variant has on the wire, i.e. its `#[serde(rename = "...")]`
+/// if set or otherwise the variant name with the enum's `rename_all` rule applied.
+/// Serde aliases only affect deserialization and thus are not relevant here, while the
+/// `#[query_alias(...)]` names are collected separately.
+///
+/// The response type in `#[returns(...)]` can be any type, e.g. a path like
+/// `cw20::BalanceResponse` or a generic like `Vec<cw_utils::Expiration>`.
+fn parse_query(v: Variant, rename_all: Option<&str>) -> syn::Result<(String, Expr)> {
+    let query = match serde_name_value(&v.attrs, "rename") {
+        Some(name) => name,
+        None => apply_rename_rule(&v.ident.to_string(), rename_all),
+    };
+    let mut returns = v.attrs.iter().filter(|a| a.path.is_ident("returns"));
+    let attr = returns.next().ok_or_else(|| {
+        syn::Error::new_spanned(
+            &v.ident,
+            format!(
+                "missing return type for query: {}, add e.g. #[returns(ResponseType)]",
+                v.ident
+            ),
+        )
+    })?;
+    if let Some(duplicate) = returns.next() {
+        return Err(syn::Error::new_spanned(
+            duplicate,
+            format!("multiple return types for query: {}", v.ident),
+        ));
+    }
+    let response_ty: Type = attr.parse_args().map_err(|err| {
+        // `parse_args` points at the tokens that are not a type, or the whole attribute
+        // if there are no arguments
+        syn::Error::new(
+            err.span(),
+            format!("return for {} must be a type: {}", v.ident, err),
+        )
+    })?;
+
+    let schema = match doc_description(&v.attrs) {
+        // The query's docs describe what is returned, so they are used for response types
+        // that do not document themselves (e.g. `Vec<String>` or `u64`).
+        Some(description) => parse_quote! {
+            {
+                let mut schema = cosmwasm_schema::schema_for!(#response_ty);
+                schema
+                    .schema
+                    .metadata()
+                    .description
+                    .get_or_insert_with(|| #description.to_string());
+                schema
+            }
+        },
+        None => parse_quote!(cosmwasm_schema::schema_for!(#response_ty)),
+    };
+
+    Ok((query, schema))
+}
+
+/// Merges the `///` doc comments in the given attributes into a description the same way
+/// schemars does for types, variants and fields: lines of a paragraph are joined by spaces
+/// and paragraphs are separated by an empty line.
+pub(crate) fn doc_description(attrs: &[Attribute]) -> Option<String> {
+    let doc: Vec<_> = attrs
+        .iter()
+        .filter(|a| a.path.is_ident("doc"))
+        .filter_map(|a| match a.parse_meta() {
+            Ok(Meta::NameValue(nv)) => match nv.lit {
+                Lit::Str(s) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let lines: Vec<_> = doc
         .iter()
-        .find(|a| a.path.get_ident().unwrap() == "returns")
-        .unwrap_or_else(|| panic!("missing return type for query: {}", v.ident))
-        .parse_args()
-        .unwrap_or_else(|_| panic!("return for {} must be a type", v.ident));
-
-    (
-        query,
-        parse_quote!(cosmwasm_schema::schema_for!(#response_ty)),
-    )
+        .flat_map(|d| d.split('\n'))
+        .map(str::trim)
+        .collect();
+    let description = lines
+        .join("\n")
+        .split("\n\n")
+        .map(|paragraph| paragraph.trim().replace('\n', " "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+/// The `rename_all` rule of an enum, e.g. "snake_case"
+pub(crate) fn serde_rename_all(attrs: &[Attribute]) -> Option<String> {
+    serde_name_value(attrs, "rename_all")
+}
+
+/// Finds the serialization value of `#[serde(key = "...")]` or
+/// `#[serde(key(serialize = "..."))]` in the given attributes.
+pub(crate) fn serde_name_value(attrs: &[Attribute], key: &str) -> Option<String> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("serde")) {
+        let list = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list,
+            _ => continue,
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident(key) => {
+                    if let Lit::Str(s) = nv.lit {
+                        value = Some(s.value());
+                    }
+                }
+                NestedMeta::Meta(Meta::List(inner)) if inner.path.is_ident(key) => {
+                    for nested in inner.nested {
+                        if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                            if let (true, Lit::Str(s)) = (nv.path.is_ident("serialize"), nv.lit) {
+                                value = Some(s.value());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    value
+}
+
+/// Applies a serde `rename_all` rule to a variant name like serde does
+pub(crate) fn apply_rename_rule(variant: &str, rule: Option<&str>) -> String {
+    match rule {
+        None | Some("PascalCase") => variant.to_string(),
+        Some("lowercase") => variant.to_ascii_lowercase(),
+        Some("UPPERCASE") => variant.to_ascii_uppercase(),
+        Some("camelCase") => variant[..1].to_ascii_lowercase() + &variant[1..],
+        Some("snake_case") => to_snake_case(variant),
+        Some("SCREAMING_SNAKE_CASE") => to_snake_case(variant).to_ascii_uppercase(),
+        Some("kebab-case") => to_snake_case(variant).replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => to_snake_case(variant)
+            .replace('_', "-")
+            .to_ascii_uppercase(),
+        Some(unknown) => panic!("unknown serde rename_all rule: {}", unknown),
+    }
 }
 
 fn parse_tuple((q, r): (String, Expr)) -> ExprTuple {
@@ -43,7 +345,7 @@ fn parse_tuple((q, r): (String, Expr)) -> ExprTuple {
     }
 }
 
-fn to_snake_case(input: &str) -> String {
+pub(crate) fn to_snake_case(input: &str) -> String {
     // this was stolen from serde for consistent behavior
     let mut snake = String::new();
     for (i, ch) in input.char_indices() {
@@ -75,7 +377,7 @@ mod tests {
         };
 
         assert_eq!(
-            query_responses_derive_impl(input),
+            query_responses_derive_impl(input).unwrap(),
             parse_quote! {
                 #[automatically_derived]
                 #[cfg(not(target_arch = "wasm32"))]
@@ -91,6 +393,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn generic_query_msg() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(rename_all = "snake_case")]
+            pub enum QueryMsg<'a, T: Clone, U>
+            where
+                U: std::fmt::Debug,
+            {
+                #[returns(T)]
+                Item { key: &'a str },
+                #[returns(Vec<U>)]
+                List {},
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl<'a, T: Clone + schemars::JsonSchema, U: schemars::JsonSchema> cosmwasm_schema::QueryResponses for QueryMsg<'a, T, U>
+                where
+                    U: std::fmt::Debug,
+                {
+                    fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
+                        std::collections::BTreeMap::from([
+                            ("item".to_string(), cosmwasm_schema::schema_for!(T)),
+                            ("list".to_string(), cosmwasm_schema::schema_for!(Vec<U>)),
+                        ])
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn nested_query_msg() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(untagged)]
+            #[query_responses(nested)]
+            pub enum QueryMsg {
+                Cw20(Cw20QueryMsg),
+                Extension(some_crate::ExtensionQueryMsg<Empty>),
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl cosmwasm_schema::QueryResponses for QueryMsg {
+                    fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
+                        cosmwasm_schema::combine_subqueries::<2usize, Self>([
+                            <Cw20QueryMsg as cosmwasm_schema::QueryResponses>::response_schemas_impl(),
+                            <some_crate::ExtensionQueryMsg<Empty> as cosmwasm_schema::QueryResponses>::response_schemas_impl(),
+                        ])
+                    }
+
+                    fn query_aliases() -> std::collections::BTreeMap<String, String> {
+                        cosmwasm_schema::combine_query_aliases::<2usize, Self>([
+                            <Cw20QueryMsg as cosmwasm_schema::QueryResponses>::query_aliases(),
+                            <some_crate::ExtensionQueryMsg<Empty> as cosmwasm_schema::QueryResponses>::query_aliases(),
+                        ])
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn nested_query_msg_requires_tuple_variants() {
+        let input: ItemEnum = parse_quote! {
+            #[serde(untagged)]
+            #[query_responses(nested)]
+            pub enum QueryMsg {
+                Cw20 { msg: Cw20QueryMsg },
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap_err().to_string(),
+            "nested query Cw20 must be a tuple variant with a single query enum"
+        );
+    }
+
+    #[test]
+    fn unknown_query_responses_option() {
+        let input: ItemEnum = parse_quote! {
+            #[query_responses(flatten)]
+            pub enum QueryMsg {
+                Cw20(Cw20QueryMsg),
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap_err().to_string(),
+            "unknown query_responses option, expected `nested`"
+        );
+    }
+
     #[test]
     fn empty_query_msg() {
         let input: ItemEnum = parse_quote! {
@@ -100,7 +505,7 @@ mod tests {
         };
 
         assert_eq!(
-            query_responses_derive_impl(input),
+            query_responses_derive_impl(input).unwrap(),
             parse_quote! {
                 #[automatically_derived]
                 #[cfg(not(target_arch = "wasm32"))]
@@ -114,7 +519,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "missing return type for query: Supply")]
     fn missing_return() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -126,11 +530,13 @@ mod tests {
             }
         };
 
-        query_responses_derive_impl(input);
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap_err().to_string(),
+            "missing return type for query: Supply, add e.g. #[returns(ResponseType)]"
+        );
     }
 
     #[test]
-    #[should_panic(expected = "return for Supply must be a type")]
     fn invalid_return() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -143,7 +549,171 @@ mod tests {
             }
         };
 
-        query_responses_derive_impl(input);
+        let err = query_responses_derive_impl(input).unwrap_err().to_string();
+        assert!(
+            err.starts_with("return for Supply must be a type: "),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn duplicate_return() {
+        let input: ItemEnum = parse_quote! {
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                #[returns(AnotherType)]
+                Supply {},
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap_err().to_string(),
+            "multiple return types for query: Supply"
+        );
+    }
+
+    #[test]
+    fn errors_of_all_queries_are_reported() {
+        let input: ItemEnum = parse_quote! {
+            pub enum QueryMsg {
+                Supply {},
+                #[returns(1)]
+                Balance {},
+                #[returns(SomeType)]
+                Config {},
+            }
+        };
+
+        let messages: Vec<_> = query_responses_derive_impl(input)
+            .unwrap_err()
+            .into_iter()
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("missing return type for query: Supply"));
+        assert!(messages[1].starts_with("return for Balance must be a type"));
+    }
+
+    #[test]
+    fn other_attributes_with_paths_are_ignored() {
+        let input: ItemEnum = parse_quote! {
+            pub enum QueryMsg {
+                #[cfg_attr(feature = "docs", doc = "Returns the supply")]
+                #[serde::rename = "foo"]
+                #[returns(cw20::BalanceResponse)]
+                Supply {},
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl cosmwasm_schema::QueryResponses for QueryMsg {
+                    fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
+                        std::collections::BTreeMap::from([
+                            ("Supply".to_string(), cosmwasm_schema::schema_for!(cw20::BalanceResponse)),
+                        ])
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn query_aliases() {
+        let input: ItemEnum = parse_quote! {
+            #[serde(rename_all = "snake_case")]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                #[query_alias("total_supply")]
+                #[query_alias("supply_info")]
+                Supply {},
+                #[returns(AnotherType)]
+                Balance {},
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl cosmwasm_schema::QueryResponses for QueryMsg {
+                    fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
+                        std::collections::BTreeMap::from([
+                            ("supply".to_string(), cosmwasm_schema::schema_for!(SomeType)),
+                            ("balance".to_string(), cosmwasm_schema::schema_for!(AnotherType)),
+                        ])
+                    }
+
+                    fn query_aliases() -> std::collections::BTreeMap<String, String> {
+                        std::collections::BTreeMap::from([
+                            ("total_supply".to_string(), "supply".to_string()),
+                            ("supply_info".to_string(), "supply".to_string()),
+                        ])
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_query_aliases() {
+        let input: ItemEnum = parse_quote! {
+            #[serde(rename_all = "snake_case")]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                #[query_alias(total_supply)]
+                Supply {},
+            }
+        };
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap_err().to_string(),
+            "query_alias for Supply must be a string literal, e.g. #[query_alias(\"old_name\")]"
+        );
+
+        let input: ItemEnum = parse_quote! {
+            #[serde(rename_all = "snake_case")]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                #[query_alias("balance")]
+                Supply {},
+                #[returns(AnotherType)]
+                #[query_alias("old")]
+                Balance {},
+                #[returns(AnotherType)]
+                #[query_alias("old")]
+                Config {},
+            }
+        };
+        let messages: Vec<_> = query_responses_derive_impl(input)
+            .unwrap_err()
+            .into_iter()
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "query alias balance is already used by another query",
+                "query alias old is already used by another query"
+            ]
+        );
+
+        let input: ItemEnum = parse_quote! {
+            #[serde(untagged)]
+            #[query_responses(nested)]
+            pub enum QueryMsg {
+                #[query_alias("old")]
+                Cw20(Cw20QueryMsg),
+            }
+        };
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap_err().to_string(),
+            "query_alias is not supported on nested queries, add it to the variant of the child query enum"
+        );
     }
 
     #[test]
@@ -154,7 +724,7 @@ mod tests {
         };
 
         assert_eq!(
-            parse_tuple(parse_query(variant)),
+            parse_tuple(parse_query(variant, Some("snake_case")).unwrap()),
             parse_quote! {
                 ("get_foo".to_string(), cosmwasm_schema::schema_for!(Foo))
             }
@@ -166,9 +736,185 @@ mod tests {
         };
 
         assert_eq!(
-            parse_tuple(parse_query(variant)),
+            parse_tuple(parse_query(variant, Some("snake_case")).unwrap()),
             parse_quote! { ("get_foo".to_string(), cosmwasm_schema::schema_for!(some_crate::Foo)) }
         );
+
+        let variant = parse_quote! {
+            #[returns(Vec<::cw_utils::Expiration>)]
+            GetFoo {}
+        };
+
+        assert_eq!(
+            parse_tuple(parse_query(variant, Some("snake_case")).unwrap()),
+            parse_quote! { ("get_foo".to_string(), cosmwasm_schema::schema_for!(Vec<::cw_utils::Expiration>)) }
+        );
+    }
+
+    #[test]
+    fn parse_query_uses_doc_comments() {
+        let variant = parse_quote! {
+            /// Returns the foo.
+            ///
+            /// The foo is
+            /// never empty.
+            #[returns(Foo)]
+            GetFoo {}
+        };
+
+        assert_eq!(
+            parse_tuple(parse_query(variant, Some("snake_case")).unwrap()),
+            parse_quote! {
+                ("get_foo".to_string(), {
+                    let mut schema = cosmwasm_schema::schema_for!(Foo);
+                    schema
+                        .schema
+                        .metadata()
+                        .description
+                        .get_or_insert_with(|| "Returns the foo.\n\nThe foo is never empty.".to_string());
+                    schema
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn doc_description_works() {
+        let variant: Variant = parse_quote! {
+            #[returns(Foo)]
+            GetFoo {}
+        };
+        assert_eq!(doc_description(&variant.attrs), None);
+
+        let variant: Variant = parse_quote! {
+            ///
+            #[doc = " "]
+            #[returns(Foo)]
+            GetFoo {}
+        };
+        assert_eq!(doc_description(&variant.attrs), None);
+
+        let variant: Variant = parse_quote! {
+            /// First line
+            ///   second line
+            ///
+            ///
+            /// Next paragraph
+            #[doc = "with attribute\nand newline"]
+            #[returns(Foo)]
+            GetFoo {}
+        };
+        assert_eq!(
+            doc_description(&variant.attrs).as_deref(),
+            Some("First line second line\n\nNext paragraph with attribute and newline")
+        );
+    }
+
+    #[test]
+    fn parse_query_respects_serde_rename() {
+        let variant = parse_quote! {
+            #[returns(Foo)]
+            #[serde(rename = "foo_v2")]
+            GetFoo {}
+        };
+        assert_eq!(
+            parse_query(variant, Some("snake_case")).unwrap().0,
+            "foo_v2"
+        );
+
+        let variant = parse_quote! {
+            #[returns(Foo)]
+            #[serde(rename(serialize = "foo_ser", deserialize = "foo_de"))]
+            GetFoo {}
+        };
+        assert_eq!(
+            parse_query(variant, Some("snake_case")).unwrap().0,
+            "foo_ser"
+        );
+
+        // aliases do not change the serialized name
+        let variant = parse_quote! {
+            #[returns(Foo)]
+            #[serde(alias = "get_the_foo")]
+            GetFoo {}
+        };
+        assert_eq!(
+            parse_query(variant, Some("snake_case")).unwrap().0,
+            "get_foo"
+        );
+
+        // without rename_all serde uses the variant name
+        let variant = parse_quote! {
+            #[returns(Foo)]
+            GetFoo {}
+        };
+        assert_eq!(parse_query(variant, None).unwrap().0, "GetFoo");
+    }
+
+    #[test]
+    fn derive_respects_enum_rename_all() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, JsonSchema, QueryResponses)]
+            #[serde(rename_all = "camelCase")]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                #[serde(rename_all = "snake_case")]
+                GetBalance { some_field: String },
+                #[returns(SomeType)]
+                #[serde(rename = "cfg")]
+                Config {},
+            }
+        };
+
+        assert_eq!(
+            query_responses_derive_impl(input).unwrap(),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl cosmwasm_schema::QueryResponses for QueryMsg {
+                    fn response_schemas_impl() -> std::collections::BTreeMap<String, schemars::schema::RootSchema> {
+                        std::collections::BTreeMap::from([
+                            ("getBalance".to_string(), cosmwasm_schema::schema_for!(SomeType)),
+                            ("cfg".to_string(), cosmwasm_schema::schema_for!(SomeType)),
+                        ])
+                    }
+                }
+            }
+        );
+
+        let input: ItemEnum = parse_quote! {
+            #[serde(deny_unknown_fields, rename_all(serialize = "kebab-case"))]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                GetBalance {},
+            }
+        };
+        let rename_all = serde_rename_all(&input.attrs);
+        assert_eq!(rename_all.as_deref(), Some("kebab-case"));
+    }
+
+    #[test]
+    fn apply_rename_rule_works() {
+        let cases = [
+            (None, "GetTotalSupply"),
+            (Some("PascalCase"), "GetTotalSupply"),
+            (Some("lowercase"), "gettotalsupply"),
+            (Some("UPPERCASE"), "GETTOTALSUPPLY"),
+            (Some("camelCase"), "getTotalSupply"),
+            (Some("snake_case"), "get_total_supply"),
+            (Some("SCREAMING_SNAKE_CASE"), "GET_TOTAL_SUPPLY"),
+            (Some("kebab-case"), "get-total-supply"),
+            (Some("SCREAMING-KEBAB-CASE"), "GET-TOTAL-SUPPLY"),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(apply_rename_rule("GetTotalSupply", rule), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown serde rename_all rule: Snake")]
+    fn apply_rename_rule_panics_for_unknown_rule() {
+        apply_rename_rule("GetFoo", Some("Snake"));
     }
 
     #[test]
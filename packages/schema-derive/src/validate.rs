@@ -0,0 +1,207 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Expr, Fields, ItemImpl};
+
+use crate::constraints::Constraints;
+use crate::query_responses::{apply_rename_rule, serde_name_value, serde_rename_all};
+
+pub fn validate_derive_impl(input: DeriveInput) -> ItemImpl {
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match input.data {
+        Data::Struct(data) => {
+            let (pattern, checks) = validate_fields(&data.fields, "");
+            quote! {
+                let Self #pattern = self;
+                #( #checks )*
+            }
+        }
+        Data::Enum(data) => {
+            let rename_all = serde_rename_all(&input.attrs);
+            let arms = data.variants.iter().map(|variant| {
+                let name = serde_name_value(&variant.attrs, "rename").unwrap_or_else(|| {
+                    apply_rename_rule(&variant.ident.to_string(), rename_all.as_deref())
+                });
+                let (pattern, checks) = validate_fields(&variant.fields, &name);
+                let variant = &variant.ident;
+                quote! {
+                    Self::#variant #pattern => {
+                        #( #checks )*
+                    }
+                }
+            });
+            let arms: Vec<_> = arms.collect();
+            if arms.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! { match self { #( #arms )* } }
+            }
+        }
+        Data::Union(_) => panic!("unions are not supported"),
+    };
+
+    parse_quote! {
+        #[automatically_derived]
+        impl #impl_generics cosmwasm_schema::Validate for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn validate(&self) -> Result<(), cosmwasm_schema::ValidationError> {
+                #body
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns a pattern binding the fields and the checks of their constraints. The errors
+/// refer to the fields by their JSON names, prefixed with `prefix` (e.g. an enum variant).
+fn validate_fields(fields: &Fields, prefix: &str) -> (TokenStream, Vec<TokenStream>) {
+    let mut bindings = Vec::new();
+    let mut checks = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let binding = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("field{}", index),
+        };
+        let name = match &field.ident {
+            Some(ident) => {
+                serde_name_value(&field.attrs, "rename").unwrap_or_else(|| ident.to_string())
+            }
+            // Newtypes are serialized as their content
+            None if fields.len() == 1 => String::new(),
+            None => index.to_string(),
+        };
+        let path = [prefix, &name]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(".");
+        checks.extend(field_checks(
+            &Constraints::parse(&field.attrs),
+            &binding,
+            &path,
+        ));
+        bindings.push(binding);
+    }
+
+    let pattern = match fields {
+        Fields::Named(_) => quote! { { #( #bindings, )* } },
+        Fields::Unnamed(_) => quote! { ( #( #bindings, )* ) },
+        Fields::Unit => quote! {},
+    };
+    (pattern, checks)
+}
+
+fn field_checks(constraints: &Constraints, binding: &syn::Ident, path: &str) -> Vec<TokenStream> {
+    let mut checks = Vec::new();
+    if constraints.has_range() {
+        let (min, max) = constraints.range_bounds();
+        let (min, max) = (parse_expr(&min), parse_expr(&max));
+        checks.push(quote! {
+            cosmwasm_schema::constraints::RangeCheck::check_range(#binding, #min, #max)
+                .map_err(|message| cosmwasm_schema::ValidationError::new(#path, message))?;
+        });
+    }
+    if constraints.has_length() {
+        let (min, max) = constraints.length_bounds();
+        let (min, max) = (parse_expr(&min), parse_expr(&max));
+        checks.push(quote! {
+            cosmwasm_schema::constraints::LengthCheck::check_length(#binding, #min, #max)
+                .map_err(|message| cosmwasm_schema::ValidationError::new(#path, message))?;
+        });
+    }
+    if constraints.nested {
+        checks.push(quote! {
+            cosmwasm_schema::Validate::validate(#binding).map_err(|err| err.prefixed(#path))?;
+        });
+    }
+    checks
+}
+
+fn parse_expr(expr: &str) -> Expr {
+    syn::parse_str(expr).unwrap_or_else(|_| panic!("invalid bound: {}", expr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structs() {
+        let expanded = validate_derive_impl(parse_quote! {
+            pub struct Order {
+                #[schema(min = 1, max = "MAX")]
+                pub quantity: u32,
+                #[serde(rename = "note")]
+                #[schema(min_length = 1, pattern = "^[a-z]+$")]
+                pub memo: String,
+                #[schema(nested)]
+                pub config: Config,
+                pub other: u32,
+            }
+        });
+        let expected = parse_quote! {
+            #[automatically_derived]
+            impl cosmwasm_schema::Validate for Order {
+                #[allow(unused_variables)]
+                fn validate(&self) -> Result<(), cosmwasm_schema::ValidationError> {
+                    let Self { quantity, memo, config, other, } = self;
+                    cosmwasm_schema::constraints::RangeCheck::check_range(quantity, 1 as i128, MAX as i128)
+                        .map_err(|message| cosmwasm_schema::ValidationError::new("quantity", message))?;
+                    cosmwasm_schema::constraints::LengthCheck::check_length(memo, 1 as usize, usize::MAX)
+                        .map_err(|message| cosmwasm_schema::ValidationError::new("note", message))?;
+                    cosmwasm_schema::Validate::validate(config).map_err(|err| err.prefixed("config"))?;
+                    Ok(())
+                }
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn enums() {
+        let expanded = validate_derive_impl(parse_quote! {
+            #[serde(rename_all = "snake_case")]
+            pub enum ExecuteMsg<T> {
+                SetCount(#[schema(max = 10)] u8),
+                Pair(u8, #[schema(min = 1)] u8),
+                Pause,
+                Custom { data: T },
+            }
+        });
+        let expected = parse_quote! {
+            #[automatically_derived]
+            impl<T> cosmwasm_schema::Validate for ExecuteMsg<T> {
+                #[allow(unused_variables)]
+                fn validate(&self) -> Result<(), cosmwasm_schema::ValidationError> {
+                    match self {
+                        Self::SetCount(field0,) => {
+                            cosmwasm_schema::constraints::RangeCheck::check_range(field0, i128::MIN, 10 as i128)
+                                .map_err(|message| cosmwasm_schema::ValidationError::new("set_count", message))?;
+                        }
+                        Self::Pair(field0, field1,) => {
+                            cosmwasm_schema::constraints::RangeCheck::check_range(field1, 1 as i128, i128::MAX)
+                                .map_err(|message| cosmwasm_schema::ValidationError::new("pair.1", message))?;
+                        }
+                        Self::Pause => {}
+                        Self::Custom { data, } => {}
+                    }
+                    Ok(())
+                }
+            }
+        };
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "validate requires integer bounds")]
+    fn float_bounds() {
+        validate_derive_impl(parse_quote! {
+            pub struct Msg {
+                #[schema(min = 0.5)]
+                pub x: u32,
+            }
+        });
+    }
+}
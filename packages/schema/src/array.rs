@@ -0,0 +1,292 @@
+//! Serialization and schema helpers for fixed-size array fields.
+//!
+//! `#[cw_serde]` applies [`elements`] automatically to fields of type `[T; N]`, and
+//! [`base64`] to fields of type `[u8; N]` if the `base64_arrays` option is set. So they
+//! don't need to be used directly in most cases.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{ArrayValidation, InstanceType, Schema, SchemaObject, StringValidation};
+use schemars::JsonSchema;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::{Serialize, Serializer};
+
+/// Helper for `#[serde(with = "...")]` that encodes `[u8; N]` as a base64 string,
+/// like `cosmwasm_std::Binary` does for `Vec<u8>`.
+///
+/// To allow switching existing types to this encoding, an array of `N` numbers is
+/// accepted as well when deserializing.
+pub mod base64 {
+    use super::*;
+
+    pub fn serialize<S, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&::base64::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(Base64Visitor::<N>)
+    }
+
+    struct Base64Visitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for Base64Visitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "base64 encoded string or array of {} bytes", N)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            let bytes =
+                ::base64::decode(v).map_err(|_| E::custom(format!("invalid base64: {}", v)))?;
+            let len = bytes.len();
+            bytes.try_into().map_err(|_| {
+                E::custom(format!(
+                    "invalid data size: expected {} bytes, got {}",
+                    N, len
+                ))
+            })
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            super::elements::ElementsVisitor::<u8, N>(PhantomData).visit_seq(seq)
+        }
+    }
+}
+
+/// Helper for `#[serde(with = "...")]` that encodes `[T; N]` as a JSON array of
+/// exactly `N` elements. In contrast to serde's built-in implementation this works
+/// for any `N`.
+pub mod elements {
+    use super::*;
+
+    pub fn serialize<S, T, const N: usize>(
+        elements: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serializer.collect_seq(elements)
+    }
+
+    pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserializer.deserialize_seq(ElementsVisitor::<T, N>(PhantomData))
+    }
+
+    pub(super) struct ElementsVisitor<T, const N: usize>(pub(super) PhantomData<T>);
+
+    impl<'de, T, const N: usize> Visitor<'de> for ElementsVisitor<T, N>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = [T; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an array of {} elements", N)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut out = Vec::with_capacity(N);
+            while let Some(element) = seq.next_element()? {
+                if out.len() == N {
+                    return Err(de::Error::invalid_length(N + 1, &self));
+                }
+                out.push(element);
+            }
+            let len = out.len();
+            out.try_into()
+                .map_err(|_| de::Error::invalid_length(len, &self))
+        }
+    }
+}
+
+/// The schema of a `[u8; N]` encoded with [`base64`], i.e. a string of the exact
+/// length of the base64 encoding of `N` bytes.
+pub struct Base64Array<const N: usize>;
+
+impl<const N: usize> JsonSchema for Base64Array<N> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("Base64Array_size_{}", N)
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // base64 with padding uses 4 characters for every group of 3 bytes, including
+        // the last group if it is incomplete
+        let padded = match N % 3 {
+            0 => 0,
+            _ => 4,
+        };
+        let len = (N / 3 * 4 + padded) as u32;
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                min_length: Some(len),
+                max_length: Some(len),
+                pattern: None,
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// The schema of a `[T; N]` encoded with [`elements`], i.e. an array of exactly `N` items.
+pub struct FixedArray<T, const N: usize>(PhantomData<T>);
+
+impl<T: JsonSchema, const N: usize> JsonSchema for FixedArray<T, N> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("Array_size_{}_of_{}", N, T::schema_name())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(gen.subschema_for::<T>().into()),
+                min_items: Some(N as u32),
+                max_items: Some(N as u32),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema_for;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Hashes {
+        #[serde(with = "base64")]
+        hash: [u8; 4],
+        #[serde(with = "elements")]
+        words: [u16; 40],
+    }
+
+    #[test]
+    fn serialization_works() {
+        let hashes = Hashes {
+            hash: [0xde, 0xad, 0xbe, 0xef],
+            words: [7; 40],
+        };
+        let json = serde_json::to_value(&hashes).unwrap();
+        assert_eq!(json, json!({"hash": "3q2+7w==", "words": vec![7; 40]}));
+        assert_eq!(serde_json::from_value::<Hashes>(json).unwrap(), hashes);
+    }
+
+    #[test]
+    fn deserialization_checks_length() {
+        let err = serde_json::from_value::<Hashes>(json!({"hash": "3q2+", "words": vec![7; 40]}))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid data size: expected 4 bytes, got 3"
+        );
+
+        let err =
+            serde_json::from_value::<Hashes>(json!({"hash": "3q2+7w==", "words": vec![7; 39]}))
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 39, expected an array of 40 elements"
+        );
+
+        let err =
+            serde_json::from_value::<Hashes>(json!({"hash": "3q2+7w==", "words": vec![7; 41]}))
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 41, expected an array of 40 elements"
+        );
+
+        let err = serde_json::from_value::<Hashes>(json!({"hash": "!", "words": []})).unwrap_err();
+        assert_eq!(err.to_string(), "invalid base64: !");
+
+        let err =
+            serde_json::from_value::<Hashes>(json!({"hash": [1, 2, 3], "words": vec![7; 40]}))
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 3, expected an array of 4 elements"
+        );
+    }
+
+    #[test]
+    fn deserialization_accepts_legacy_number_arrays() {
+        let hashes = Hashes {
+            hash: [0xde, 0xad, 0xbe, 0xef],
+            words: [7; 40],
+        };
+        // serde's default encoding of `[u8; N]`
+        let legacy = r#"{"hash":[222,173,190,239],"words":[7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7,7]}"#;
+        assert_eq!(serde_json::from_str::<Hashes>(legacy).unwrap(), hashes);
+    }
+
+    #[test]
+    fn schemas_have_length_constraints() {
+        let schema = serde_json::to_value(schema_for!(Base64Array<32>)).unwrap();
+        assert_eq!(
+            schema,
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "title": "Base64Array_size_32",
+                "type": "string",
+                "maxLength": 44,
+                "minLength": 44
+            })
+        );
+
+        let schema = serde_json::to_value(schema_for!(FixedArray<u16, 40>)).unwrap();
+        assert_eq!(
+            schema,
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "title": "Array_size_40_of_uint16",
+                "type": "array",
+                "items": {
+                    "type": "integer",
+                    "format": "uint16",
+                    "minimum": 0.0
+                },
+                "maxItems": 40,
+                "minItems": 40
+            })
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! Bundling of the definitions of all schemas of an API file into one `$defs` section
+
+use std::collections::BTreeMap;
+
+use schemars::schema::{RootSchema, Schema};
+use schemars::visit::{visit_root_schema, Visitor};
+use schemars::Map;
+
+use crate::shared::{references, ReferenceRewriter};
+
+/// The command line flag that makes `write_api!` write a bundled API file
+pub const BUNDLE_FLAG: &str = "--bundle";
+
+const BUNDLE_PREFIX: &str = "#/$defs/";
+
+/// Returns true if the current process was started with [`--bundle`](BUNDLE_FLAG),
+/// e.g. via `cargo schema -- --bundle`.
+///
+/// In this mode `write_api!` moves the definitions of all messages and responses into a
+/// `$defs` section at the top level of the API file, such that types used in many places
+/// (e.g. `Uint128` or `Coin`) are only written once. The references are rewritten into
+/// that section, e.g. `#/$defs/Coin`, so they resolve within the API file.
+///
+/// In contrast to the schemas of a regular API file, the bundled schemas cannot be used
+/// on their own. Different types with the same name are kept apart with a number suffix,
+/// e.g. `Config2`. The `--diff` mode works with bundled files, while `--check` must be used
+/// together with `--bundle` for them.
+pub fn is_bundle_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == BUNDLE_FLAG)
+}
+
+/// Removes the definitions of the given schemas and returns them, with references rewritten
+/// into the `$defs` of the document containing the schemas
+pub(crate) fn bundle_definitions<'a>(
+    schemas: impl IntoIterator<Item = &'a mut RootSchema>,
+) -> Map<String, Schema> {
+    let mut bundle = Bundle::default();
+    for schema in schemas {
+        bundle.add(schema);
+    }
+    bundle.definitions
+}
+
+#[derive(Default)]
+struct Bundle {
+    definitions: Map<String, Schema>,
+    /// The definitions the bundled definitions were created from, together with the definitions
+    /// they use, to detect different types of the same name
+    originals: BTreeMap<String, BTreeMap<String, Schema>>,
+}
+
+impl Bundle {
+    fn add(&mut self, root: &mut RootSchema) {
+        let definitions = std::mem::take(&mut root.definitions);
+        let mapping: BTreeMap<String, String> = definitions
+            .keys()
+            .map(|name| (name.clone(), self.reserve(name, &definitions)))
+            .collect();
+        let mut rewriter = ReferenceRewriter(|name: &str| {
+            mapping
+                .get(name)
+                .map(|bundled| format!("{}{}", BUNDLE_PREFIX, bundled))
+        });
+        visit_root_schema(&mut rewriter, root);
+        for (name, mut definition) in definitions {
+            let bundled = &mapping[&name];
+            if !self.definitions.contains_key(bundled) {
+                rewriter.visit_schema(&mut definition);
+                self.definitions.insert(bundled.clone(), definition);
+            }
+        }
+    }
+
+    /// Returns the name of the bundled definition for the given definition
+    fn reserve(&mut self, name: &str, definitions: &Map<String, Schema>) -> String {
+        let original = used_definitions(name, definitions);
+        let mut bundled = name.to_string();
+        let mut count = 1;
+        loop {
+            match self.originals.get(&bundled) {
+                Some(existing) if *existing == original => return bundled,
+                Some(_) => {
+                    count += 1;
+                    bundled = format!("{}{}", name, count);
+                }
+                None => {
+                    self.originals.insert(bundled.clone(), original);
+                    return bundled;
+                }
+            }
+        }
+    }
+}
+
+/// The definition with the given name and all definitions it uses, directly or indirectly
+pub(crate) fn used_definitions(
+    name: &str,
+    definitions: &Map<String, Schema>,
+) -> BTreeMap<String, Schema> {
+    let mut used = BTreeMap::new();
+    let mut pending = vec![name.to_string()];
+    while let Some(name) = pending.pop() {
+        if used.contains_key(&name) {
+            continue;
+        }
+        if let Some(definition) = definitions.get(&name) {
+            pending.extend(references(definition));
+            used.insert(name, definition.clone());
+        }
+    }
+    used
+}
+
+/// Moves the `$defs` of a bundled API file into each of its schemas, such that they can be
+/// used on their own again. Does nothing for API files that are not bundled.
+pub(crate) fn unbundle_definitions(api: &mut serde_json::Value, schemas: &[&str]) {
+    let definitions = match api.as_object_mut().and_then(|api| api.remove("$defs")) {
+        Some(definitions) => definitions,
+        None => return,
+    };
+    let add_definitions = |schema: &mut serde_json::Value| {
+        if let serde_json::Value::Object(schema) = schema {
+            schema.insert("$defs".to_string(), definitions.clone());
+        }
+    };
+    for name in schemas {
+        if let Some(schema) = api.get_mut(*name) {
+            add_definitions(schema);
+        }
+    }
+    for map in ["entry_points", "responses"] {
+        if let Some(serde_json::Value::Object(schemas)) = api.get_mut(map) {
+            schemas.values_mut().for_each(add_definitions);
+        }
+    }
+    if let Some(serde_json::Value::Array(state)) = api.get_mut("state") {
+        state
+            .iter_mut()
+            .filter_map(|item| item.get_mut("value"))
+            .for_each(add_definitions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::tests::{a, b, Uint128};
+    use schemars::{schema_for, JsonSchema};
+    use serde_json::json;
+
+    #[test]
+    fn bundle_definitions_works() {
+        let mut instantiate = schema_for!(a::InstantiateMsg);
+        let mut execute = schema_for!(b::ExecuteMsg);
+        let mut balance = schema_for!(Uint128);
+
+        let definitions = bundle_definitions([&mut instantiate, &mut execute, &mut balance]);
+        assert_eq!(
+            definitions.keys().collect::<Vec<_>>(),
+            ["Coin", "Config", "Config2", "Uint128"]
+        );
+        for schema in [&instantiate, &execute, &balance] {
+            assert!(schema.definitions.is_empty());
+        }
+
+        let execute = serde_json::to_value(&execute).unwrap();
+        assert_eq!(
+            execute["properties"]["fee"],
+            json!({ "$ref": "#/$defs/Coin" })
+        );
+        assert_eq!(
+            execute["properties"]["config"],
+            json!({ "$ref": "#/$defs/Config2" })
+        );
+        let coin = serde_json::to_value(&definitions["Coin"]).unwrap();
+        assert_eq!(
+            coin["properties"]["amount"],
+            json!({ "$ref": "#/$defs/Uint128" })
+        );
+        let config = serde_json::to_value(&definitions["Config2"]).unwrap();
+        assert_eq!(
+            config["properties"]["admin"]["type"],
+            json!(["string", "null"])
+        );
+    }
+
+    #[test]
+    fn bundle_definitions_compares_used_definitions() {
+        mod c {
+            use schemars::JsonSchema;
+
+            #[derive(JsonSchema)]
+            #[allow(dead_code)]
+            pub struct Inner {
+                pub value: u32,
+            }
+
+            #[derive(JsonSchema)]
+            #[allow(dead_code)]
+            pub struct Outer {
+                pub inner: Inner,
+            }
+        }
+        mod d {
+            use schemars::JsonSchema;
+
+            #[derive(JsonSchema)]
+            #[allow(dead_code)]
+            pub struct Inner {
+                pub value: String,
+            }
+
+            #[derive(JsonSchema)]
+            #[allow(dead_code)]
+            pub struct Outer {
+                pub inner: Inner,
+            }
+        }
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct First(c::Outer);
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Second(d::Outer);
+
+        // the two `Outer`s look the same, but refer to different types
+        let mut first = schema_for!(First);
+        let mut second = schema_for!(Second);
+        let definitions = bundle_definitions([&mut first, &mut second]);
+        assert_eq!(
+            definitions.keys().collect::<Vec<_>>(),
+            ["Inner", "Inner2", "Outer", "Outer2"]
+        );
+        let outer = serde_json::to_value(&definitions["Outer2"]).unwrap();
+        assert_eq!(
+            outer["properties"]["inner"],
+            json!({ "$ref": "#/$defs/Inner2" })
+        );
+    }
+
+    #[test]
+    fn unbundle_definitions_works() {
+        let mut api = json!({
+            "contract_name": "test",
+            "instantiate": { "$ref": "#/$defs/Coin" },
+            "query": null,
+            "entry_points": { "hook": { "$ref": "#/$defs/Coin" } },
+            "responses": { "balance": { "$ref": "#/$defs/Coin" } },
+            "state": [{ "namespace": "balance", "keys": ["utf8"], "value": { "$ref": "#/$defs/Coin" } }],
+            "$defs": { "Coin": { "type": "string" } }
+        });
+        unbundle_definitions(&mut api, &["instantiate", "query"]);
+        assert_eq!(
+            api,
+            json!({
+                "contract_name": "test",
+                "instantiate": {
+                    "$ref": "#/$defs/Coin",
+                    "$defs": { "Coin": { "type": "string" } }
+                },
+                "query": null,
+                "entry_points": {
+                    "hook": {
+                        "$ref": "#/$defs/Coin",
+                        "$defs": { "Coin": { "type": "string" } }
+                    }
+                },
+                "responses": {
+                    "balance": {
+                        "$ref": "#/$defs/Coin",
+                        "$defs": { "Coin": { "type": "string" } }
+                    }
+                },
+                "state": [{
+                    "namespace": "balance",
+                    "keys": ["utf8"],
+                    "value": {
+                        "$ref": "#/$defs/Coin",
+                        "$defs": { "Coin": { "type": "string" } }
+                    }
+                }]
+            })
+        );
+
+        // API files that are not bundled are not changed
+        let mut api = json!({ "instantiate": { "$ref": "#/definitions/Coin" } });
+        let original = api.clone();
+        unbundle_definitions(&mut api, &["instantiate"]);
+        assert_eq!(api, original);
+    }
+}
@@ -9,6 +9,32 @@ pub fn to_snake_case(name: &str) -> String {
     out
 }
 
+/// Converts names like `snake_case`, `kebab-case` or `Array_of_Coin` to `PascalCase`
+pub fn to_pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// Converts names like `snake_case` or `kebab-case` to `camelCase`
+pub fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(name);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase())
+        .into_iter()
+        .chain(chars)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,4 +53,24 @@ mod tests {
         assert_eq!(to_snake_case("FooBar"), "foo_bar");
         assert_eq!(to_snake_case("ABC"), "a_b_c");
     }
+
+    #[test]
+    fn to_pascal_case_works() {
+        assert_eq!(to_pascal_case(""), "");
+        assert_eq!(to_pascal_case("a"), "A");
+        assert_eq!(to_pascal_case("get_int"), "GetInt");
+        assert_eq!(to_pascal_case("crypto-verify"), "CryptoVerify");
+        assert_eq!(to_pascal_case("Array_of_Coin"), "ArrayOfCoin");
+        assert_eq!(to_pascal_case("Uint128"), "Uint128");
+        assert_eq!(to_pascal_case("snake__case_"), "SnakeCase");
+    }
+
+    #[test]
+    fn to_camel_case_works() {
+        assert_eq!(to_camel_case(""), "");
+        assert_eq!(to_camel_case("a"), "a");
+        assert_eq!(to_camel_case("get_int"), "getInt");
+        assert_eq!(to_camel_case("crypto-verify"), "cryptoVerify");
+        assert_eq!(to_camel_case("GetInt"), "getInt");
+    }
 }
@@ -0,0 +1,121 @@
+//! Support for verifying that committed schema files are up to date
+
+use std::fs::read;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::remove::schema_files;
+
+/// The command line flag that makes schema binaries compare instead of write
+pub const CHECK_FLAG: &str = "--check";
+
+#[derive(Error, Debug)]
+pub enum SchemaCheckError {
+    #[error("Schema file {} is missing. Run the schema generator to create it.", .path.display())]
+    Missing { path: PathBuf },
+    #[error("Schema file {} is outdated. Run the schema generator to update it.", .path.display())]
+    Outdated { path: PathBuf },
+    #[error("Schema file {} is not generated anymore. Run the schema generator to remove it.", .path.display())]
+    Stale { path: PathBuf },
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// Returns true if the current process was started with [`--check`](CHECK_FLAG),
+/// e.g. via `cargo schema -- --check`.
+///
+/// In this mode `write_api!` and [`write_workspace_api`](crate::write_workspace_api)
+/// do not modify any files but fail if the generated output differs from the files on disk.
+pub fn is_check_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == CHECK_FLAG)
+}
+
+/// Checks that the file at `path` has exactly the given contents.
+pub fn check_schema_file(path: &Path, expected: impl AsRef<[u8]>) -> Result<(), SchemaCheckError> {
+    let actual = match read(path) {
+        Ok(actual) => actual,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(SchemaCheckError::Missing {
+                path: path.to_path_buf(),
+            })
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if actual != expected.as_ref() {
+        return Err(SchemaCheckError::Outdated {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `schemas_dir` contains no schema files besides the `generated` ones.
+///
+/// The schema generator removes all JSON files from the directory before writing the new
+/// ones, so any other file would be gone after regenerating the schemas. A missing directory
+/// is fine here, the missing files are reported by [`check_schema_file`].
+pub fn check_no_stale_schemas(
+    schemas_dir: &Path,
+    generated: &[PathBuf],
+) -> Result<(), SchemaCheckError> {
+    let mut files = match schema_files(schemas_dir) {
+        Ok(files) => files,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    files.sort();
+    match files.into_iter().find(|path| !generated.contains(path)) {
+        Some(path) => Err(SchemaCheckError::Stale { path }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::write;
+
+    #[test]
+    fn check_schema_file_works() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contract.json");
+
+        let err = check_schema_file(&path, "{}\n").unwrap_err();
+        assert!(matches!(err, SchemaCheckError::Missing { path: p } if p == path));
+
+        write(&path, "{}\n").unwrap();
+        check_schema_file(&path, "{}\n").unwrap();
+
+        let err = check_schema_file(&path, "{\"a\":1}\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Schema file {} is outdated. Run the schema generator to update it.",
+                path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn check_no_stale_schemas_works() {
+        let dir = tempfile::tempdir().unwrap();
+        let schemas_dir = dir.path().join("schema");
+        let path = schemas_dir.join("contract.json");
+        let generated = vec![path.clone()];
+        check_no_stale_schemas(&schemas_dir, &generated).unwrap();
+
+        std::fs::create_dir(&schemas_dir).unwrap();
+        write(&path, "{}\n").unwrap();
+        write(schemas_dir.join("contract.ts"), "").unwrap();
+        write(schemas_dir.join(".prettierrc.json"), "{}").unwrap();
+        check_no_stale_schemas(&schemas_dir, &generated).unwrap();
+
+        let stale = schemas_dir.join("packet_msg.json");
+        write(&stale, "{}\n").unwrap();
+        let err = check_no_stale_schemas(&schemas_dir, &generated).unwrap_err();
+        assert!(matches!(err, SchemaCheckError::Stale { path: p } if p == stale));
+        check_no_stale_schemas(&schemas_dir, &[path, stale]).unwrap();
+    }
+}
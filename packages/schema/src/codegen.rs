@@ -0,0 +1,521 @@
+//! Helpers shared by the generators of type definitions for other languages
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use schemars::schema::{
+    InstanceType, Metadata, RootSchema, Schema, SchemaObject, SingleOrVec, SubschemaValidation,
+};
+use schemars::Map;
+
+use crate::bundle::used_definitions;
+use crate::casing::to_pascal_case;
+use crate::shared::{references, DEFINITIONS_PREFIX};
+
+/// A definition together with all definitions it uses, by name
+type Original = BTreeMap<String, Schema>;
+
+/// The type names of a generated file. Definitions are looked up in the root schema
+/// currently processed, such that different types of the same name in different roots
+/// (e.g. two `Config`s) get different type names, e.g. `Config` and `Config2`. Like in
+/// [`bundle_definitions`](crate::bundle::bundle_definitions), a definition is the same
+/// type as one of another root if it and all the definitions it uses are equal.
+#[derive(Default)]
+pub struct TypeNames<'a> {
+    /// The definitions of the root schema currently processed
+    scope: Option<&'a Map<String, Schema>>,
+    /// The type names of the definitions of the current root schema
+    local: HashMap<String, String>,
+    /// The type names of all definitions and roots processed so far by definition name,
+    /// together with the definitions they were created from
+    known: HashMap<String, Vec<(Original, String)>>,
+    /// All type names in use
+    names: HashSet<String>,
+}
+
+impl<'a> TypeNames<'a> {
+    /// The definitions of the root schema currently processed
+    pub fn scope(&self) -> Option<&'a Map<String, Schema>> {
+        self.scope
+    }
+
+    /// Reserves a unique type name, appending a number if the name is taken already
+    pub fn reserve(&mut self, name: &str) -> String {
+        let mut base = to_pascal_case(name);
+        if !base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            base.insert(0, 'T');
+        }
+        let mut name = base.clone();
+        let mut count = 1;
+        while self.names.contains(&name) {
+            count += 1;
+            name = format!("{}{}", base, count);
+        }
+        self.names.insert(name.clone());
+        name
+    }
+
+    /// Returns the type name of a root schema or definition with the given definitions
+    /// it was created from, if it was seen before
+    fn find(&self, name: &str, original: &Original) -> Option<String> {
+        self.known
+            .get(name)?
+            .iter()
+            .find(|(known, _)| known == original)
+            .map(|(_, type_name)| type_name.clone())
+    }
+
+    fn insert(&mut self, name: &str, original: Original, type_name: &str) {
+        self.local.insert(name.to_string(), type_name.to_string());
+        self.known
+            .entry(name.to_string())
+            .or_default()
+            .push((original, type_name.to_string()));
+    }
+}
+
+/// The traversal of schemas shared by the generators of all languages. Implementors
+/// render the types, while references, `Option`s and the definitions of multiple root
+/// schemas are resolved here.
+pub trait Generator<'a> {
+    /// A type expression of the language
+    type Type;
+
+    fn type_names(&mut self) -> &mut TypeNames<'a>;
+
+    /// The type for schemas without an equivalent in the language, e.g. arbitrary JSON
+    fn raw(&mut self) -> Self::Type;
+
+    fn optional(&mut self, ty: Self::Type) -> Self::Type;
+
+    /// The type referring to a type defined with [`define`](Generator::define)
+    fn named(&mut self, name: &str) -> Self::Type;
+
+    /// Renders a named type definition
+    fn define(&mut self, name: &str, schema: &SchemaObject);
+
+    /// The type of a schema that is not rendered as a named type, with the (non-null)
+    /// instance type of the schema. Nested types are named after the `context`.
+    fn unnamed(
+        &mut self,
+        instance_type: InstanceType,
+        schema: &SchemaObject,
+        context: &str,
+    ) -> Self::Type;
+
+    /// Returns true if an inline schema is rendered as a named type
+    fn is_named(&self, schema: &SchemaObject) -> bool {
+        is_named(schema)
+    }
+
+    /// Returns true if a definition is rendered as a named type. Other definitions are
+    /// used inline.
+    fn is_defined(&self, _definition: &SchemaObject) -> bool {
+        true
+    }
+
+    fn reserve(&mut self, name: &str) -> String {
+        self.type_names().reserve(name)
+    }
+
+    /// Looks up definitions in the given root schema from now on
+    fn enter(&mut self, root: &'a RootSchema) {
+        let type_names = self.type_names();
+        type_names.scope = Some(&root.definitions);
+        type_names.local.clear();
+    }
+
+    /// Defines the type of a root schema and its definitions. Returns the type name, unless
+    /// the root schema is not rendered as a named type.
+    fn root(&mut self, name: &str, root: &'a RootSchema) -> Option<String> {
+        self.enter(root);
+        let mut type_name = None;
+        if self.is_defined(&root.schema) {
+            // The title is the only difference to the same type used as a definition
+            let mut schema = root.schema.clone();
+            if let Some(metadata) = &mut schema.metadata {
+                metadata.title = None;
+                if **metadata == Metadata::default() {
+                    schema.metadata = None;
+                }
+            }
+            let schema = Schema::Object(schema);
+            let mut original = BTreeMap::new();
+            for reference in references(&schema) {
+                original.extend(used_definitions(&reference, &root.definitions));
+            }
+            original.insert(name.to_string(), schema);
+
+            // A response type may be used in messages or other responses as well
+            let name = match self.type_names().find(name, &original) {
+                Some(existing) => {
+                    self.type_names().insert(name, original, &existing);
+                    existing
+                }
+                None => {
+                    let type_name = self.reserve(name);
+                    self.type_names().insert(name, original, &type_name);
+                    self.define(&type_name, &root.schema);
+                    type_name
+                }
+            };
+            type_name = Some(name);
+        }
+        self.unreferenced_definitions();
+        type_name
+    }
+
+    /// Definitions are emitted when referenced. This adds unreferenced ones of the current
+    /// root schema, if any.
+    fn unreferenced_definitions(&mut self) {
+        let mut definitions: Vec<&String> = self
+            .type_names()
+            .scope
+            .into_iter()
+            .flat_map(|scope| scope.keys())
+            .collect();
+        definitions.sort();
+        for definition in definitions {
+            self.reference(definition);
+        }
+    }
+
+    /// Returns the type of a definition in the current root schema, generating it if needed
+    fn reference(&mut self, definition: &str) -> Self::Type {
+        if let Some(name) = self.type_names().local.get(definition).cloned() {
+            return self.named(&name);
+        }
+        let scope = self.type_names().scope;
+        let schema = match scope.and_then(|scope| scope.get(definition)) {
+            Some(Schema::Object(schema)) => schema,
+            _ => return self.raw(),
+        };
+        if !self.is_defined(schema) {
+            return self.type_for(schema, definition);
+        }
+        let original = used_definitions(definition, scope.unwrap());
+        if let Some(name) = self.type_names().find(definition, &original) {
+            self.type_names().insert(definition, original, &name);
+            return self.named(&name);
+        }
+        let name = self.reserve(definition);
+        // Insert before generating to support recursive types
+        self.type_names().insert(definition, original, &name);
+        self.define(&name, schema);
+        self.named(&name)
+    }
+
+    fn type_of(&mut self, schema: &Schema, context: &str) -> Self::Type {
+        match schema {
+            Schema::Object(schema) => self.type_for(schema, context),
+            Schema::Bool(_) => self.raw(),
+        }
+    }
+
+    /// Returns a type expression for the schema. Inline named types are defined using
+    /// the `context` as name.
+    fn type_for(&mut self, schema: &SchemaObject, context: &str) -> Self::Type {
+        if let Some(reference) = &schema.reference {
+            return match reference.strip_prefix(DEFINITIONS_PREFIX) {
+                Some(definition) => self.reference(definition),
+                None => self.raw(),
+            };
+        }
+        if let Some(subschemas) = &schema.subschemas {
+            match &**subschemas {
+                // schemars uses `allOf` to add a description to a reference
+                SubschemaValidation {
+                    all_of: Some(all_of),
+                    ..
+                } if all_of.len() == 1 => return self.type_of(&all_of[0], context),
+                // `Option<T>` for a referenced `T`
+                SubschemaValidation {
+                    any_of: Some(any_of),
+                    ..
+                } if any_of.len() == 2 && is_null(&any_of[1]) => {
+                    let ty = self.type_of(&any_of[0], context);
+                    return self.optional(ty);
+                }
+                _ => {}
+            }
+        }
+        if self.is_named(schema) {
+            let name = self.reserve(context);
+            self.define(&name, schema);
+            return self.named(&name);
+        }
+
+        let (instance_type, nullable) = match &schema.instance_type {
+            Some(SingleOrVec::Single(ty)) => (**ty, false),
+            Some(SingleOrVec::Vec(types)) => {
+                let non_null: Vec<&InstanceType> = types
+                    .iter()
+                    .filter(|ty| **ty != InstanceType::Null)
+                    .collect();
+                match non_null[..] {
+                    [ty] => (*ty, non_null.len() != types.len()),
+                    _ => return self.raw(),
+                }
+            }
+            None => return self.raw(),
+        };
+        let ty = self.unnamed(instance_type, schema, context);
+        if nullable {
+            self.optional(ty)
+        } else {
+            ty
+        }
+    }
+}
+
+/// Returns true if the schema is rendered as a named type, i.e. it is not
+/// just a primitive, array or map.
+pub fn is_named(schema: &SchemaObject) -> bool {
+    variants(schema).is_some() || string_values(schema).is_some() || is_struct(schema)
+}
+
+pub fn variants(schema: &SchemaObject) -> Option<&[Schema]> {
+    let subschemas = schema.subschemas.as_ref()?;
+    let variants = subschemas.one_of.as_ref().or(subschemas.any_of.as_ref())?;
+    // `Option<T>` is an `anyOf` with null, but not an enum
+    if variants.iter().any(is_null) {
+        return None;
+    }
+    Some(variants)
+}
+
+pub fn string_values(schema: &SchemaObject) -> Option<Vec<String>> {
+    schema
+        .enum_values
+        .as_ref()?
+        .iter()
+        .map(|value| value.as_str().map(ToString::to_string))
+        .collect()
+}
+
+pub fn is_struct(schema: &SchemaObject) -> bool {
+    let is_object = matches!(
+        &schema.instance_type,
+        Some(SingleOrVec::Single(ty)) if **ty == InstanceType::Object
+    );
+    // Objects with additional properties of a certain type are maps
+    is_object
+        && match &schema.object {
+            Some(object) => {
+                !object.properties.is_empty()
+                    || matches!(
+                        object.additional_properties.as_deref(),
+                        None | Some(Schema::Bool(false))
+                    )
+            }
+            None => true,
+        }
+}
+
+pub fn single_property(schema: &SchemaObject) -> Option<(&str, &Schema)> {
+    let object = schema.object.as_ref()?;
+    if object.properties.len() != 1 || object.required.len() != 1 {
+        return None;
+    }
+    let (property, content) = object.properties.iter().next()?;
+    if object.required.contains(property) {
+        Some((property.as_str(), content))
+    } else {
+        None
+    }
+}
+
+pub fn is_null(schema: &Schema) -> bool {
+    matches!(
+        schema,
+        Schema::Object(SchemaObject {
+            instance_type: Some(SingleOrVec::Single(ty)),
+            ..
+        }) if **ty == InstanceType::Null
+    )
+}
+
+pub fn description(schema: &SchemaObject) -> Option<&str> {
+    schema.metadata.as_ref()?.description.as_deref()
+}
+
+pub fn property_schema_description(schema: &Schema) -> Option<&str> {
+    match schema {
+        Schema::Object(schema) => description(schema),
+        Schema::Bool(_) => None,
+    }
+}
+
+/// Returns true if the schema is marked as deprecated, e.g. with `#[deprecated]` on a field
+/// or variant
+pub fn is_deprecated(schema: &Schema) -> bool {
+    matches!(
+        schema,
+        Schema::Object(SchemaObject {
+            metadata: Some(metadata),
+            ..
+        }) if metadata.deprecated
+    )
+}
+
+/// The description of the schema, followed by the `notice` if it is marked as deprecated,
+/// e.g. with `#[deprecated]` on a field or variant.
+///
+/// Unit variants of an enum are not marked by schemars, since they are combined into a
+/// single `enum` of strings.
+pub fn documentation<'a>(schema: &'a SchemaObject, notice: &'static str) -> Option<Cow<'a, str>> {
+    let metadata = schema.metadata.as_deref()?;
+    match (metadata.description.as_deref(), metadata.deprecated) {
+        (Some(description), true) => Some(format!("{}\n\n{}", description, notice).into()),
+        (None, true) => Some(notice.into()),
+        (description, false) => description.map(Cow::Borrowed),
+    }
+}
+
+pub fn property_schema_documentation<'a>(
+    schema: &'a Schema,
+    notice: &'static str,
+) -> Option<Cow<'a, str>> {
+    match schema {
+        Schema::Object(schema) => documentation(schema, notice),
+        Schema::Bool(_) => None,
+    }
+}
+
+/// The first line of every generated file, naming the contract release the file belongs to.
+/// It matches `^// Code generated .* DO NOT EDIT\.$` (with the comment marker of the
+/// language), which Go tooling and many linters use to recognize generated files.
+pub fn generated_notice(contract_name: &str, contract_version: &str) -> String {
+    format!(
+        "Code generated by cosmwasm-schema for {} {}. DO NOT EDIT.",
+        contract_name, contract_version
+    )
+}
+
+pub fn comment(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}//\n", indent)
+            } else {
+                format!("{}// {}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+pub fn with_comment(description: Option<&str>, code: String) -> String {
+    match description {
+        Some(description) => comment(description, "") + &code,
+        None => code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::tests::{a, b};
+    use schemars::{schema_for, JsonSchema};
+
+    /// Renders every definition as a list of its fields and their types
+    #[derive(Default)]
+    struct FieldsGenerator<'a> {
+        type_names: TypeNames<'a>,
+        definitions: Vec<(String, String)>,
+    }
+
+    impl<'a> Generator<'a> for FieldsGenerator<'a> {
+        type Type = String;
+
+        fn type_names(&mut self) -> &mut TypeNames<'a> {
+            &mut self.type_names
+        }
+
+        fn raw(&mut self) -> String {
+            "raw".to_string()
+        }
+
+        fn optional(&mut self, ty: String) -> String {
+            format!("Option<{}>", ty)
+        }
+
+        fn named(&mut self, name: &str) -> String {
+            name.to_string()
+        }
+
+        fn define(&mut self, name: &str, schema: &SchemaObject) {
+            let mut fields = vec![];
+            if let Some(object) = &schema.object {
+                for (field, field_schema) in &object.properties {
+                    let ty = self.type_of(field_schema, field);
+                    fields.push(format!("{}: {}", field, ty));
+                }
+            }
+            self.definitions.push((name.to_string(), fields.join(", ")));
+        }
+
+        fn unnamed(
+            &mut self,
+            instance_type: InstanceType,
+            schema: &SchemaObject,
+            context: &str,
+        ) -> String {
+            match (
+                instance_type,
+                schema.array.as_ref().and_then(|a| a.items.as_ref()),
+            ) {
+                (InstanceType::Array, Some(SingleOrVec::Single(items))) => {
+                    format!("Vec<{}>", self.type_of(items, context))
+                }
+                _ => format!("{:?}", instance_type),
+            }
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Config2 {
+        value: u32,
+    }
+
+    #[test]
+    fn type_names_keep_types_of_the_same_name_apart() {
+        let instantiate = schema_for!(a::InstantiateMsg);
+        let execute = schema_for!(b::ExecuteMsg);
+        let config = schema_for!(b::Config);
+        let config2 = schema_for!(Config2);
+
+        let mut gen = FieldsGenerator::default();
+        assert_eq!(
+            gen.root("InstantiateMsg", &instantiate).as_deref(),
+            Some("InstantiateMsg")
+        );
+        assert_eq!(
+            gen.root("ExecuteMsg", &execute).as_deref(),
+            Some("ExecuteMsg")
+        );
+        // The same type as the `Config` of the `ExecuteMsg`
+        assert_eq!(gen.root("Config", &config).as_deref(), Some("Config2"));
+        // A type actually called `Config2` does not clash with the renamed one
+        assert_eq!(gen.root("Config2", &config2).as_deref(), Some("Config22"));
+
+        let definitions: Vec<(&str, &str)> = gen
+            .definitions
+            .iter()
+            .map(|(name, fields)| (name.as_str(), fields.as_str()))
+            .collect();
+        // Shared types like `Coin` are defined once
+        assert_eq!(
+            definitions,
+            [
+                ("Config", "owner: String"),
+                ("Uint128", ""),
+                ("Coin", "amount: Uint128, denom: String"),
+                ("InstantiateMsg", "config: Config, funds: Vec<Coin>"),
+                ("Config2", "admin: Option<String>"),
+                ("ExecuteMsg", "config: Config2, fee: Coin"),
+                ("Config22", "value: Integer"),
+            ]
+        );
+    }
+}
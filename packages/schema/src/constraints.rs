@@ -0,0 +1,342 @@
+//! Helpers that check the constraints of `#[schema(...)]` field attributes.
+//!
+//! `#[cw_serde(validate)]` and `#[derive(Validate)]` apply those automatically, so they don't
+//! need to be used directly in most cases.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+use serde::de::{self, Deserialize, Deserializer};
+
+/// A value that violates a constraint, as returned by [`Validate::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The path of the field in the JSON message, e.g. `transfer.amount`.
+    /// Empty if the value itself is invalid.
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Moves the error into a field of an enclosing value
+    pub fn prefixed(mut self, prefix: &str) -> Self {
+        self.field = match (prefix.is_empty(), self.field.is_empty()) {
+            (true, _) => self.field,
+            (false, true) => prefix.to_string(),
+            (false, false) => format!("{}.{}", prefix, self.field),
+        };
+        self
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.field.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.field, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks the constraints of a message, e.g. at the beginning of an entry point.
+/// Derive it with `#[derive(Validate)]`, see [`cw_serde`](crate::cw_serde).
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        match self {
+            Some(value) => value.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Validate> Validate for Box<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        T::validate(self)
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        for (index, value) in self.iter().enumerate() {
+            value
+                .validate()
+                .map_err(|err| err.prefixed(&index.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Helper for `#[serde(deserialize_with = "...")]` that fails if the value is not within
+/// `MIN..=MAX`. Use `i128::MIN` and `i128::MAX` for bounds that are not set.
+pub fn range<'de, D, T, const MIN: i128, const MAX: i128>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + RangeCheck,
+{
+    let value = T::deserialize(deserializer)?;
+    value.check_range(MIN, MAX).map_err(de::Error::custom)?;
+    Ok(value)
+}
+
+/// Helper for `#[serde(deserialize_with = "...")]` that fails if the length of the value
+/// is not within `MIN..=MAX`. Use `0` and `usize::MAX` for bounds that are not set.
+pub fn length<'de, D, T, const MIN: usize, const MAX: usize>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + LengthCheck,
+{
+    let value = T::deserialize(deserializer)?;
+    value.check_length(MIN, MAX).map_err(de::Error::custom)?;
+    Ok(value)
+}
+
+/// Types whose values can be checked by [`range`]
+pub trait RangeCheck {
+    /// Returns an error message if the value is not within `min..=max`
+    fn check_range(&self, min: i128, max: i128) -> Result<(), String>;
+}
+
+macro_rules! impl_range_check {
+    ($($ty:ty),*) => {
+        $(
+            impl RangeCheck for $ty {
+                fn check_range(&self, min: i128, max: i128) -> Result<(), String> {
+                    let in_range = match i128::try_from(*self) {
+                        Ok(value) => min <= value && value <= max,
+                        // only `u128` values larger than any bound end up here
+                        Err(_) => max == i128::MAX,
+                    };
+                    if in_range {
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "invalid value {}, expected a value {}",
+                            self,
+                            expected(min, max, i128::MIN, i128::MAX)
+                        ))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_range_check!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<T: RangeCheck> RangeCheck for Option<T> {
+    fn check_range(&self, min: i128, max: i128) -> Result<(), String> {
+        match self {
+            Some(value) => value.check_range(min, max),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Types whose values can be checked by [`length`]. The length of a string is
+/// the number of characters, like for JSON Schema's `minLength` and `maxLength`.
+pub trait LengthCheck {
+    /// Returns an error message if the length is not within `min..=max`
+    fn check_length(&self, min: usize, max: usize) -> Result<(), String>;
+}
+
+fn check_length(len: usize, min: usize, max: usize) -> Result<(), String> {
+    if min <= len && len <= max {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid length {}, expected a length {}",
+            len,
+            expected(min, max, 0, usize::MAX)
+        ))
+    }
+}
+
+impl LengthCheck for String {
+    fn check_length(&self, min: usize, max: usize) -> Result<(), String> {
+        check_length(self.chars().count(), min, max)
+    }
+}
+
+impl<T> LengthCheck for Vec<T> {
+    fn check_length(&self, min: usize, max: usize) -> Result<(), String> {
+        check_length(self.len(), min, max)
+    }
+}
+
+impl<T> LengthCheck for BTreeSet<T> {
+    fn check_length(&self, min: usize, max: usize) -> Result<(), String> {
+        check_length(self.len(), min, max)
+    }
+}
+
+impl<K, V> LengthCheck for BTreeMap<K, V> {
+    fn check_length(&self, min: usize, max: usize) -> Result<(), String> {
+        check_length(self.len(), min, max)
+    }
+}
+
+impl<T: LengthCheck> LengthCheck for Option<T> {
+    fn check_length(&self, min: usize, max: usize) -> Result<(), String> {
+        match self {
+            Some(value) => value.check_length(min, max),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Describes the bounds that are set, e.g. "between 1 and 10" or "of at least 1"
+fn expected<T: Display + PartialEq>(min: T, max: T, no_min: T, no_max: T) -> String {
+    match (min != no_min, max != no_max) {
+        (true, true) => format!("between {} and {}", min, max),
+        (true, false) => format!("of at least {}", min),
+        (false, true) => format!("of at most {}", max),
+        (false, false) => "of any size".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Order {
+        #[serde(deserialize_with = "range::<_, _, 1, 100>")]
+        quantity: u32,
+        #[serde(deserialize_with = "range::<_, _, { i128::MIN }, 0>", default)]
+        offset: Option<i64>,
+        #[serde(deserialize_with = "range::<_, _, 1, { i128::MAX }>")]
+        price: u128,
+        #[serde(deserialize_with = "length::<_, _, 1, 3>")]
+        memo: String,
+        #[serde(deserialize_with = "length::<_, _, 0, 2>")]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn deserialization_works() {
+        let order: Order = serde_json::from_value(json!({
+            "quantity": 100,
+            "price": u64::MAX,
+            "memo": "äöü",
+            "tags": ["a", "b"]
+        }))
+        .unwrap();
+        assert_eq!(
+            order,
+            Order {
+                quantity: 100,
+                offset: None,
+                price: u64::MAX as u128,
+                memo: "äöü".to_string(),
+                tags: vec!["a".to_string(), "b".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn deserialization_checks_constraints() {
+        let order = |quantity: u32, offset: i64, memo: &str, tags: &[&str]| {
+            serde_json::from_value::<Order>(json!({
+                "quantity": quantity,
+                "offset": offset,
+                "price": 1,
+                "memo": memo,
+                "tags": tags
+            }))
+            .map_err(|err| err.to_string())
+        };
+        assert_eq!(
+            order(0, 0, "a", &[]).unwrap_err(),
+            "invalid value 0, expected a value between 1 and 100"
+        );
+        assert_eq!(
+            order(101, 0, "a", &[]).unwrap_err(),
+            "invalid value 101, expected a value between 1 and 100"
+        );
+        assert_eq!(
+            order(1, 1, "a", &[]).unwrap_err(),
+            "invalid value 1, expected a value of at most 0"
+        );
+        assert_eq!(
+            order(1, 0, "", &[]).unwrap_err(),
+            "invalid length 0, expected a length between 1 and 3"
+        );
+        assert_eq!(
+            order(1, 0, "abcd", &[]).unwrap_err(),
+            "invalid length 4, expected a length between 1 and 3"
+        );
+        assert_eq!(
+            order(1, 0, "a", &["a", "b", "c"]).unwrap_err(),
+            "invalid length 3, expected a length of at most 2"
+        );
+        assert!(order(1, -5, "a", &[]).is_ok());
+    }
+
+    struct Positive(i32);
+
+    impl Validate for Positive {
+        fn validate(&self) -> Result<(), ValidationError> {
+            self.0
+                .check_range(1, i128::MAX)
+                .map_err(|message| ValidationError::new("", message))
+        }
+    }
+
+    #[test]
+    fn validation_error_works() {
+        let err = ValidationError::new("amount", "invalid value 0");
+        assert_eq!(err.to_string(), "amount: invalid value 0");
+        assert_eq!(
+            err.clone().prefixed("transfer").to_string(),
+            "transfer.amount: invalid value 0"
+        );
+        assert_eq!(err.clone().prefixed(""), err);
+        assert_eq!(
+            ValidationError::new("", "invalid value 0").to_string(),
+            "invalid value 0"
+        );
+        assert_eq!(
+            ValidationError::new("", "invalid value 0").prefixed("amount"),
+            err
+        );
+    }
+
+    #[test]
+    fn validate_works_for_containers() {
+        assert_eq!(Some(Positive(1)).validate(), Ok(()));
+        assert_eq!(None::<Positive>.validate(), Ok(()));
+        let err = vec![Positive(1), Positive(0)].validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "1: invalid value 0, expected a value of at least 1"
+        );
+        assert!(Box::new(Positive(-1)).validate().is_err());
+    }
+
+    #[test]
+    fn range_check_works_for_large_values() {
+        assert_eq!(u128::MAX.check_range(0, i128::MAX), Ok(()));
+        assert!(u128::MAX.check_range(0, 1000).is_err());
+        assert!(i128::MIN.check_range(i128::MIN, 0).is_ok());
+        assert_eq!(
+            5u8.check_range(10, i128::MAX).unwrap_err(),
+            "invalid value 5, expected a value of at least 10"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Conversion of the generated schemas (JSON Schema draft-07) into other dialects
+
+use std::str::FromStr;
+
+use schemars::schema::RootSchema;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// The command line flag that sets the JSON Schema dialect of the files written by `write_api!`
+pub const DIALECT_FLAG: &str = "--dialect";
+
+const DRAFT_07_META_SCHEMA: &str = "http://json-schema.org/draft-07/schema#";
+const DRAFT_2020_12_META_SCHEMA: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A JSON Schema dialect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// [Draft-07](https://json-schema.org/draft-07/schema), as generated by schemars
+    Draft07,
+    /// [Draft 2020-12](https://json-schema.org/draft/2020-12/schema), as used by OpenAPI 3.1.
+    /// Definitions are in `$defs` and tuples use `prefixItems`.
+    Draft2020_12,
+}
+
+// Draft-07 stays the default, since it is what schemars generates. Implemented by hand
+// because our minimum Rust version cannot derive `Default` for enums.
+#[allow(clippy::derivable_impls)]
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect::Draft07
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Unknown JSON Schema dialect '{0}', expected 'draft-07' or '2020-12'")]
+pub struct UnknownDialectError(String);
+
+impl FromStr for Dialect {
+    type Err = UnknownDialectError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "draft-07" => Ok(Dialect::Draft07),
+            "2020-12" => Ok(Dialect::Draft2020_12),
+            _ => Err(UnknownDialectError(name.to_string())),
+        }
+    }
+}
+
+/// Returns the dialect set with [`--dialect <name>`](DIALECT_FLAG), e.g. via
+/// `cargo schema -- --dialect 2020-12`, or draft-07 if the flag is not used.
+///
+/// The `--check` and `--diff` modes work with files of either dialect, but `--check` must be
+/// used with the same dialect the files were written with.
+pub fn dialect_mode() -> Result<Dialect, UnknownDialectError> {
+    let mut args = std::env::args().skip(1);
+    match args.find(|arg| arg == DIALECT_FLAG) {
+        Some(_) => args.next().unwrap_or_default().parse(),
+        None => Ok(Dialect::default()),
+    }
+}
+
+/// Converts a schema generated by schemars into the given dialect
+pub fn schema_to_dialect(schema: &RootSchema, dialect: Dialect) -> Value {
+    let mut value = serde_json::to_value(schema).unwrap();
+    if dialect == Dialect::Draft2020_12 {
+        if let Value::Object(object) = &mut value {
+            object.insert(
+                "$schema".to_string(),
+                Value::String(DRAFT_2020_12_META_SCHEMA.to_string()),
+            );
+        }
+        convert(&mut value, Direction::To2020_12);
+    }
+    value
+}
+
+/// Converts a root schema in draft 2020-12 back into draft-07, such that schemars can parse it.
+/// Schemas of other dialects are not changed.
+pub(crate) fn normalize_dialect(value: &mut Value) {
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    if object.get("$schema").and_then(Value::as_str) == Some(DRAFT_2020_12_META_SCHEMA) {
+        object.insert(
+            "$schema".to_string(),
+            Value::String(DRAFT_07_META_SCHEMA.to_string()),
+        );
+        convert(value, Direction::ToDraft07);
+    } else if object.contains_key("$defs") {
+        // Bundled API files use `$defs` in every dialect, see `is_bundle_mode`
+        convert(value, Direction::ToDraft07);
+    }
+}
+
+/// Converts a schema that is not a root schema (e.g. an OpenAPI component) from draft-07
+/// into draft 2020-12
+pub(crate) fn subschema_to_2020_12(value: &mut Value) {
+    convert(value, Direction::To2020_12);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    To2020_12,
+    ToDraft07,
+}
+
+/// Keywords whose value is a schema
+const SCHEMA_KEYWORDS: [&str; 8] = [
+    "additionalProperties",
+    "propertyNames",
+    "contains",
+    "not",
+    "if",
+    "then",
+    "else",
+    "additionalItems",
+];
+/// Keywords whose value is a list of schemas
+const LIST_KEYWORDS: [&str; 4] = ["allOf", "anyOf", "oneOf", "prefixItems"];
+/// Keywords whose value is a map of schemas
+const MAP_KEYWORDS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependentSchemas",
+];
+
+/// Converts a schema and all its subschemas. Only keywords are renamed, so that
+/// e.g. a property named `definitions` is kept.
+fn convert(value: &mut Value, direction: Direction) {
+    let object = match value {
+        Value::Object(object) => object,
+        // `true` and `false` are valid schemas in both dialects
+        _ => return,
+    };
+
+    match direction {
+        Direction::To2020_12 => {
+            rename(object, "definitions", "$defs");
+            if let Some(Value::Array(_)) = object.get("items") {
+                let additional = object.remove("additionalItems");
+                rename(object, "items", "prefixItems");
+                if let Some(additional) = additional {
+                    object.insert("items".to_string(), additional);
+                }
+            }
+        }
+        Direction::ToDraft07 => {
+            rename(object, "$defs", "definitions");
+            if object.contains_key("prefixItems") {
+                rename(object, "items", "additionalItems");
+                rename(object, "prefixItems", "items");
+            }
+        }
+    }
+    if let Some(Value::String(reference)) = object.get_mut("$ref") {
+        let (from, to) = match direction {
+            Direction::To2020_12 => ("#/definitions/", "#/$defs/"),
+            Direction::ToDraft07 => ("#/$defs/", "#/definitions/"),
+        };
+        if let Some(name) = reference.strip_prefix(from) {
+            *reference = format!("{}{}", to, name);
+        }
+    }
+
+    for (keyword, subschema) in object.iter_mut() {
+        let keyword = keyword.as_str();
+        if SCHEMA_KEYWORDS.contains(&keyword) {
+            convert(subschema, direction);
+        } else if keyword == "items" {
+            // A single schema or, in draft-07, a list of schemas
+            match subschema {
+                Value::Array(items) => items.iter_mut().for_each(|s| convert(s, direction)),
+                _ => convert(subschema, direction),
+            }
+        } else if LIST_KEYWORDS.contains(&keyword) {
+            if let Value::Array(subschemas) = subschema {
+                subschemas.iter_mut().for_each(|s| convert(s, direction));
+            }
+        } else if MAP_KEYWORDS.contains(&keyword) {
+            if let Value::Object(subschemas) = subschema {
+                subschemas.values_mut().for_each(|s| convert(s, direction));
+            }
+        }
+    }
+}
+
+fn rename(object: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(value) = object.remove(from) {
+        object.insert(to.to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+    use serde_json::json;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Coin {
+        denom: String,
+        amount: u128,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Msg {
+        funds: Vec<Coin>,
+        pair: (String, Coin),
+        definitions: Option<Coin>,
+    }
+
+    #[test]
+    fn dialect_from_str_works() {
+        assert_eq!("draft-07".parse(), Ok(Dialect::Draft07));
+        assert_eq!("2020-12".parse(), Ok(Dialect::Draft2020_12));
+        assert_eq!(
+            "draft-04".parse::<Dialect>(),
+            Err(UnknownDialectError("draft-04".to_string()))
+        );
+    }
+
+    #[test]
+    fn schema_to_dialect_works() {
+        let schema = schema_for!(Msg);
+        assert_eq!(
+            schema_to_dialect(&schema, Dialect::Draft07),
+            serde_json::to_value(&schema).unwrap()
+        );
+
+        let converted = schema_to_dialect(&schema, Dialect::Draft2020_12);
+        assert_eq!(converted["$schema"], DRAFT_2020_12_META_SCHEMA);
+        assert!(converted.get("definitions").is_none());
+        assert!(converted["$defs"]["Coin"].is_object());
+        assert_eq!(
+            converted["properties"]["funds"]["items"],
+            json!({ "$ref": "#/$defs/Coin" })
+        );
+        assert_eq!(
+            converted["properties"]["pair"]["prefixItems"],
+            json!([{ "type": "string" }, { "$ref": "#/$defs/Coin" }])
+        );
+        assert!(converted["properties"]["pair"].get("items").is_none());
+        // a property named like a keyword is kept
+        assert_eq!(
+            converted["properties"]["definitions"]["anyOf"][0],
+            json!({ "$ref": "#/$defs/Coin" })
+        );
+    }
+
+    #[test]
+    fn normalize_dialect_works() {
+        let schema = schema_for!(Msg);
+        let mut converted = schema_to_dialect(&schema, Dialect::Draft2020_12);
+        normalize_dialect(&mut converted);
+        assert_eq!(converted, serde_json::to_value(&schema).unwrap());
+
+        // draft-07 is kept as is
+        let mut draft_07 = serde_json::to_value(&schema).unwrap();
+        normalize_dialect(&mut draft_07);
+        assert_eq!(draft_07, serde_json::to_value(&schema).unwrap());
+    }
+}
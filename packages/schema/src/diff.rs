@@ -0,0 +1,764 @@
+//! Detection of breaking changes between two versions of a contract's API
+//!
+//! Messages (`instantiate`, `execute`, `query`, `migrate` and `sudo` as well as `reply` and
+//! the messages of further `entry_points`) are sent to the contract, so the new version must
+//! accept everything the old version accepted. Responses are
+//! read by clients, so the new version must not return anything the old version could not
+//! return. Since `#[cw_serde]` types reject unknown fields, this means:
+//!
+//! - Messages must not remove variants or fields, add required fields or stop accepting `null`.
+//! - Responses must not add enum variants, remove fields, make fields optional or become
+//!   nullable.
+//! - Types of variants and fields must not change. Changes in the integer format
+//!   (e.g. `uint32` to `uint64`) are reported as well.
+//!
+//! IBC packets and acknowledgements (`ibc_packet` and `ibc_ack`) are sent as well as received
+//! by the contract, so they are checked like messages and like responses.
+//!
+//! Events are read by indexers, so events and their attributes must not be removed and
+//! attributes must not become optional.
+//!
+//! Stored values are written by the old version and read by the new one after a migration, so
+//! the `state` items are checked like messages and their keys must be encoded the same way.
+//! Removing an item only leaves its data behind and is not reported.
+//!
+//! Enums without variant names (such as `#[serde(untagged)]` ones) cannot be compared
+//! in detail and are reported as changed if their schemas differ at all.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::bundle::unbundle_definitions;
+use crate::codegen::{is_null, is_struct, single_property, string_values, variants};
+use crate::dialect::normalize_dialect;
+use crate::events::EventSchema;
+use crate::state::StateSchema;
+
+/// The command line flag that makes `write_api!` compare the generated API with an
+/// older version instead of writing it
+pub const DIFF_FLAG: &str = "--diff";
+
+#[derive(Error, Debug)]
+pub enum SchemaDiffError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Error parsing API file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A change of the API which breaks existing clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakingChange {
+    /// The location of the change, e.g. `execute.transfer.amount`
+    pub path: String,
+    pub kind: BreakingChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakingChangeKind {
+    MessageRemoved,
+    VariantRemoved { variant: String },
+    VariantAdded { variant: String },
+    FieldRemoved { field: String },
+    RequiredFieldAdded { field: String },
+    FieldNowRequired { field: String },
+    FieldNowOptional { field: String },
+    NullNoLongerAccepted,
+    NowNullable,
+    TypeChanged { old: String, new: String },
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        match &self.kind {
+            BreakingChangeKind::MessageRemoved => write!(f, "message was removed"),
+            BreakingChangeKind::VariantRemoved { variant } => {
+                write!(f, "variant `{}` was removed", variant)
+            }
+            BreakingChangeKind::VariantAdded { variant } => {
+                write!(f, "variant `{}` was added", variant)
+            }
+            BreakingChangeKind::FieldRemoved { field } => {
+                write!(f, "field `{}` was removed", field)
+            }
+            BreakingChangeKind::RequiredFieldAdded { field } => {
+                write!(f, "required field `{}` was added", field)
+            }
+            BreakingChangeKind::FieldNowRequired { field } => {
+                write!(f, "field `{}` is now required", field)
+            }
+            BreakingChangeKind::FieldNowOptional { field } => {
+                write!(f, "field `{}` is no longer required", field)
+            }
+            BreakingChangeKind::NullNoLongerAccepted => write!(f, "null is no longer accepted"),
+            BreakingChangeKind::NowNullable => write!(f, "value may now be null"),
+            BreakingChangeKind::TypeChanged { old, new } => {
+                write!(f, "type changed from {} to {}", old, new)
+            }
+        }
+    }
+}
+
+/// Returns the API file to compare with if the current process was started with
+/// [`--diff <path>`](DIFF_FLAG), e.g. via `cargo schema -- --diff old/schema/cw20.json`.
+///
+/// In this mode `write_api!` does not modify any files but fails if the generated API has
+/// breaking changes compared to the given file. See [`diff_apis`] for what is considered
+/// breaking.
+pub fn diff_mode_base() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == DIFF_FLAG)?;
+    args.next().map(PathBuf::from)
+}
+
+/// Compares the API file at `old_path` with the JSON of a newer API version
+/// and returns all breaking changes. See [`diff_apis`] for details.
+pub fn diff_api_file(old_path: &Path, new: &str) -> Result<Vec<BreakingChange>, SchemaDiffError> {
+    let old = read_to_string(old_path)?;
+    diff_apis(&old, new)
+}
+
+/// Compares two versions of a contract's API, as written by `write_api!`, and returns
+/// all changes which break existing clients. An empty list means the new version is
+/// compatible.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_schema::{cw_serde, diff_apis, generate_api, BreakingChangeKind};
+///
+/// #[cw_serde]
+/// struct OldInstantiateMsg {
+///     owner: String,
+/// }
+///
+/// #[cw_serde]
+/// struct NewInstantiateMsg {
+///     owner: String,
+///     admin: String,
+/// }
+///
+/// let old = generate_api! { instantiate: OldInstantiateMsg }.render();
+/// let new = generate_api! { instantiate: NewInstantiateMsg }.render();
+/// let changes = diff_apis(&old.to_string().unwrap(), &new.to_string().unwrap()).unwrap();
+/// assert_eq!(changes.len(), 1);
+/// assert_eq!(changes[0].path, "instantiate");
+/// assert_eq!(
+///     changes[0].kind,
+///     BreakingChangeKind::RequiredFieldAdded { field: "admin".to_string() },
+/// );
+/// ```
+pub fn diff_apis(old: &str, new: &str) -> Result<Vec<BreakingChange>, SchemaDiffError> {
+    let old = parse_api(old)?;
+    let new = parse_api(new)?;
+
+    let mut changes = Vec::new();
+    let entry_points = old.entry_points.iter().map(|(name, old_message)| {
+        (
+            format!("entry_points.{}", name),
+            Some(old_message),
+            new.entry_points.get(name),
+        )
+    });
+    let messages = [
+        ("instantiate", &old.instantiate, &new.instantiate),
+        ("execute", &old.execute, &new.execute),
+        ("query", &old.query, &new.query),
+        ("migrate", &old.migrate, &new.migrate),
+        ("sudo", &old.sudo, &new.sudo),
+        ("reply", &old.reply, &new.reply),
+    ]
+    .into_iter()
+    .map(|(name, old_message, new_message)| {
+        (name.to_string(), old_message.as_ref(), new_message.as_ref())
+    })
+    .chain(entry_points);
+    for (name, old_message, new_message) in messages {
+        match (old_message, new_message) {
+            (Some(old_message), Some(new_message)) => {
+                Differ::new(old_message, new_message, Direction::Input, &mut changes)
+                    .compare_roots(&name)
+            }
+            (Some(_), None) => changes.push(BreakingChange {
+                path: name,
+                kind: BreakingChangeKind::MessageRemoved,
+            }),
+            (None, _) => {}
+        }
+    }
+    // Responses of removed queries are covered by the removed query variant
+    if let (Some(old_responses), Some(new_responses)) = (&old.responses, &new.responses) {
+        for (query, old_response) in old_responses {
+            if let Some(new_response) = new_responses.get(query) {
+                Differ::new(old_response, new_response, Direction::Output, &mut changes)
+                    .compare_roots(&format!("responses.{}", query));
+            }
+        }
+    }
+    for (name, old_schema, new_schema) in [
+        ("ibc_packet", &old.ibc_packet, &new.ibc_packet),
+        ("ibc_ack", &old.ibc_ack, &new.ibc_ack),
+    ] {
+        match (old_schema, new_schema) {
+            (Some(old_schema), Some(new_schema)) => {
+                let mut found = Vec::new();
+                for direction in [Direction::Input, Direction::Output] {
+                    Differ::new(old_schema, new_schema, direction, &mut found).compare_roots(name);
+                }
+                // Type changes are found in both directions
+                for change in found {
+                    if !changes.contains(&change) {
+                        changes.push(change);
+                    }
+                }
+            }
+            (Some(_), None) => changes.push(BreakingChange {
+                path: name.to_string(),
+                kind: BreakingChangeKind::MessageRemoved,
+            }),
+            (None, _) => {}
+        }
+    }
+    if let Some(old_events) = &old.events {
+        diff_events(
+            old_events,
+            new.events.as_deref().unwrap_or_default(),
+            &mut changes,
+        );
+    }
+    if let (Some(old_state), Some(new_state)) = (&old.state, &new.state) {
+        diff_state(old_state, new_state, &mut changes);
+    }
+    Ok(changes)
+}
+
+fn diff_state(old: &[StateSchema], new: &[StateSchema], changes: &mut Vec<BreakingChange>) {
+    for old_item in old {
+        let new_item = match new.iter().find(|item| item.namespace == old_item.namespace) {
+            Some(item) => item,
+            None => continue,
+        };
+        let path = format!("state.{}", old_item.namespace);
+        let (old_layout, new_layout) = (key_layout(old_item), key_layout(new_item));
+        if old_layout != new_layout {
+            changes.push(BreakingChange {
+                path: path.clone(),
+                kind: BreakingChangeKind::TypeChanged {
+                    old: old_layout,
+                    new: new_layout,
+                },
+            });
+        }
+        Differ::new(&old_item.value, &new_item.value, Direction::Input, changes)
+            .compare_roots(&path);
+    }
+}
+
+/// A description of how the keys of a state item are encoded, e.g. `map with utf8 keys`
+fn key_layout(item: &StateSchema) -> String {
+    let namespace = if item.raw { "raw " } else { "" };
+    if item.keys.is_empty() {
+        return format!("{}singleton", namespace);
+    }
+    let keys: Vec<_> = item.keys.iter().map(|key| key.as_str()).collect();
+    format!("{}map with {} keys", namespace, keys.join(", "))
+}
+
+fn diff_events(old: &[EventSchema], new: &[EventSchema], changes: &mut Vec<BreakingChange>) {
+    for old_event in old {
+        let new_event = match new.iter().find(|event| event.ty == old_event.ty) {
+            Some(event) => event,
+            None => {
+                changes.push(BreakingChange {
+                    path: "events".to_string(),
+                    kind: BreakingChangeKind::VariantRemoved {
+                        variant: old_event.ty.clone(),
+                    },
+                });
+                continue;
+            }
+        };
+        let path = format!("events.{}", old_event.ty);
+        for old_attribute in &old_event.attributes {
+            let field = old_attribute.key.clone();
+            let kind = match new_event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == old_attribute.key)
+            {
+                None => BreakingChangeKind::FieldRemoved { field },
+                Some(new_attribute) if old_attribute.required && !new_attribute.required => {
+                    BreakingChangeKind::FieldNowOptional { field }
+                }
+                Some(_) => continue,
+            };
+            changes.push(BreakingChange {
+                path: path.clone(),
+                kind,
+            });
+        }
+    }
+}
+
+/// The parts of an API file relevant for comparing
+#[derive(Deserialize)]
+struct ParsedApi {
+    instantiate: Option<RootSchema>,
+    execute: Option<RootSchema>,
+    query: Option<RootSchema>,
+    migrate: Option<RootSchema>,
+    sudo: Option<RootSchema>,
+    ibc_packet: Option<RootSchema>,
+    ibc_ack: Option<RootSchema>,
+    reply: Option<RootSchema>,
+    #[serde(default)]
+    entry_points: BTreeMap<String, RootSchema>,
+    #[serde(default)]
+    events: Option<Vec<EventSchema>>,
+    #[serde(default)]
+    state: Option<Vec<StateSchema>>,
+    responses: Option<BTreeMap<String, RootSchema>>,
+}
+
+/// Parses an API file, whose schemas may be in any supported dialect
+fn parse_api(json: &str) -> Result<ParsedApi, serde_json::Error> {
+    let mut api: serde_json::Value = serde_json::from_str(json)?;
+    let messages = [
+        "instantiate",
+        "execute",
+        "query",
+        "migrate",
+        "sudo",
+        "ibc_packet",
+        "ibc_ack",
+        "reply",
+    ];
+    unbundle_definitions(&mut api, &messages);
+    for message in messages {
+        if let Some(schema) = api.get_mut(message) {
+            normalize_dialect(schema);
+        }
+    }
+    for map in ["entry_points", "responses"] {
+        if let Some(serde_json::Value::Object(schemas)) = api.get_mut(map) {
+            schemas.values_mut().for_each(normalize_dialect);
+        }
+    }
+    if let Some(serde_json::Value::Array(state)) = api.get_mut("state") {
+        state
+            .iter_mut()
+            .filter_map(|item| item.get_mut("value"))
+            .for_each(normalize_dialect);
+    }
+    serde_json::from_value(api)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Sent by clients, the new version must accept everything the old version accepted
+    Input,
+    /// Received by clients, the new version must not return anything new
+    Output,
+}
+
+struct Differ<'a> {
+    old: &'a RootSchema,
+    new: &'a RootSchema,
+    direction: Direction,
+    changes: &'a mut Vec<BreakingChange>,
+    /// Pairs of compared definitions, to support recursive types and report
+    /// changes in shared types only once
+    visited: HashSet<(String, String)>,
+}
+
+impl<'a> Differ<'a> {
+    fn new(
+        old: &'a RootSchema,
+        new: &'a RootSchema,
+        direction: Direction,
+        changes: &'a mut Vec<BreakingChange>,
+    ) -> Self {
+        Differ {
+            old,
+            new,
+            direction,
+            changes,
+            visited: HashSet::new(),
+        }
+    }
+
+    fn compare_roots(&mut self, path: &str) {
+        let old = Schema::Object(self.old.schema.clone());
+        let new = Schema::Object(self.new.schema.clone());
+        self.compare(&old, &new, path);
+    }
+
+    fn report(&mut self, path: &str, kind: BreakingChangeKind) {
+        self.changes.push(BreakingChange {
+            path: path.to_string(),
+            kind,
+        });
+    }
+
+    fn compare(&mut self, old: &Schema, new: &Schema, path: &str) {
+        let (old, old_definition) = resolve(old, &self.old.definitions);
+        let (new, new_definition) = resolve(new, &self.new.definitions);
+        if let (Some(old_definition), Some(new_definition)) = (old_definition, new_definition) {
+            let pair = (old_definition.to_string(), new_definition.to_string());
+            if !self.visited.insert(pair) {
+                return;
+            }
+        }
+
+        let (old, old_nullable) = without_null(&old);
+        let (new, new_nullable) = without_null(&new);
+        match self.direction {
+            Direction::Input if old_nullable && !new_nullable => {
+                self.report(path, BreakingChangeKind::NullNoLongerAccepted)
+            }
+            Direction::Output if !old_nullable && new_nullable => {
+                self.report(path, BreakingChangeKind::NowNullable)
+            }
+            _ => {}
+        }
+        if old_nullable || new_nullable {
+            // The non-null part may be a reference or a nullable type again
+            return self.compare(&Schema::Object(old), &Schema::Object(new), path);
+        }
+
+        // An empty schema accepts any value
+        let old_is_any = is_any(&old);
+        let new_is_any = is_any(&new);
+        if old_is_any || new_is_any {
+            let is_breaking = match self.direction {
+                Direction::Input => !new_is_any,
+                Direction::Output => !old_is_any,
+            };
+            if is_breaking {
+                self.type_changed(path, &old, &new);
+            }
+            return;
+        }
+
+        match (enum_variants(&old), enum_variants(&new)) {
+            (Some(old_variants), Some(new_variants)) => {
+                return self.compare_variants(&old_variants, &new_variants, path)
+            }
+            (None, None) => {}
+            _ => return self.type_changed(path, &old, &new),
+        }
+        if variants(&old).is_some() || variants(&new).is_some() {
+            // Enums without variant names can only be compared as a whole
+            if old != new {
+                self.type_changed(path, &old, &new);
+            }
+            return;
+        }
+
+        match (is_struct(&old), is_struct(&new)) {
+            (true, true) => return self.compare_structs(&old, &new, path),
+            (false, false) => {}
+            _ => return self.type_changed(path, &old, &new),
+        }
+
+        if old.instance_type != new.instance_type || old.format != new.format {
+            return self.type_changed(path, &old, &new);
+        }
+        let old_items = old.array.as_ref().and_then(|array| array.items.as_ref());
+        let new_items = new.array.as_ref().and_then(|array| array.items.as_ref());
+        match (old_items, new_items) {
+            (Some(SingleOrVec::Single(old_items)), Some(SingleOrVec::Single(new_items))) => {
+                self.compare(old_items, new_items, &format!("{}[]", path))
+            }
+            (Some(SingleOrVec::Vec(old_items)), Some(SingleOrVec::Vec(new_items)))
+                if old_items.len() == new_items.len() =>
+            {
+                for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                    self.compare(old_item, new_item, &format!("{}[{}]", path, index));
+                }
+            }
+            (None, None) => {}
+            _ => return self.type_changed(path, &old, &new),
+        }
+        let old_values = old
+            .object
+            .as_ref()
+            .and_then(|object| object.additional_properties.as_deref());
+        let new_values = new
+            .object
+            .as_ref()
+            .and_then(|object| object.additional_properties.as_deref());
+        if let (Some(old_values), Some(new_values)) = (old_values, new_values) {
+            self.compare(old_values, new_values, &format!("{}{{}}", path));
+        }
+    }
+
+    fn compare_variants(&mut self, old: &[Variant], new: &[Variant], path: &str) {
+        let (required, available) = match self.direction {
+            Direction::Input => (old, new),
+            Direction::Output => (new, old),
+        };
+        for variant in required {
+            if !available.iter().any(|v| v.name == variant.name) {
+                let variant = variant.name.clone();
+                let kind = match self.direction {
+                    Direction::Input => BreakingChangeKind::VariantRemoved { variant },
+                    Direction::Output => BreakingChangeKind::VariantAdded { variant },
+                };
+                self.report(path, kind);
+            }
+        }
+        for old_variant in old {
+            let new_variant = match new.iter().find(|v| v.name == old_variant.name) {
+                Some(new_variant) => new_variant,
+                None => continue,
+            };
+            let variant_path = format!("{}.{}", path, old_variant.name);
+            match (&old_variant.content, &new_variant.content) {
+                (Some(old_content), Some(new_content)) => {
+                    self.compare(old_content, new_content, &variant_path)
+                }
+                (None, None) => {}
+                (old_content, new_content) => self.report(
+                    &variant_path,
+                    BreakingChangeKind::TypeChanged {
+                        old: variant_kind(old_content.is_some()).to_string(),
+                        new: variant_kind(new_content.is_some()).to_string(),
+                    },
+                ),
+            }
+        }
+    }
+
+    fn compare_structs(&mut self, old: &SchemaObject, new: &SchemaObject, path: &str) {
+        let default = Default::default();
+        let old = old.object.as_deref().unwrap_or(&default);
+        let new = new.object.as_deref().unwrap_or(&default);
+        for (field, old_schema) in &old.properties {
+            let new_schema = match new.properties.get(field) {
+                Some(new_schema) => new_schema,
+                None => {
+                    self.report(
+                        path,
+                        BreakingChangeKind::FieldRemoved {
+                            field: field.clone(),
+                        },
+                    );
+                    continue;
+                }
+            };
+            if self.direction == Direction::Output
+                && old.required.contains(field)
+                && !new.required.contains(field)
+            {
+                self.report(
+                    path,
+                    BreakingChangeKind::FieldNowOptional {
+                        field: field.clone(),
+                    },
+                );
+            }
+            self.compare(old_schema, new_schema, &format!("{}.{}", path, field));
+        }
+        if self.direction == Direction::Input {
+            for field in &new.required {
+                if old.required.contains(field) {
+                    continue;
+                }
+                let field = field.clone();
+                let kind = if old.properties.contains_key(&field) {
+                    BreakingChangeKind::FieldNowRequired { field }
+                } else {
+                    BreakingChangeKind::RequiredFieldAdded { field }
+                };
+                self.report(path, kind);
+            }
+        }
+    }
+
+    fn type_changed(&mut self, path: &str, old: &SchemaObject, new: &SchemaObject) {
+        let kind = BreakingChangeKind::TypeChanged {
+            old: describe(old),
+            new: describe(new),
+        };
+        self.report(path, kind);
+    }
+}
+
+/// A named enum variant with the schema of its content, if any
+struct Variant<'a> {
+    name: String,
+    content: Option<&'a Schema>,
+}
+
+/// Returns the variants of an enum with unit variants (strings) and/or data variants
+/// (single-property objects)
+fn enum_variants(schema: &SchemaObject) -> Option<Vec<Variant<'_>>> {
+    if let Some(values) = string_values(schema) {
+        return Some(
+            values
+                .into_iter()
+                .map(|name| Variant {
+                    name,
+                    content: None,
+                })
+                .collect(),
+        );
+    }
+    let mut out = Vec::new();
+    for variant in variants(schema)? {
+        let variant = match variant {
+            Schema::Object(variant) => variant,
+            Schema::Bool(_) => return None,
+        };
+        if let Some(values) = string_values(variant) {
+            out.extend(values.into_iter().map(|name| Variant {
+                name,
+                content: None,
+            }));
+        } else {
+            let (name, content) = single_property(variant)?;
+            out.push(Variant {
+                name: name.to_string(),
+                content: Some(content),
+            });
+        }
+    }
+    Some(out)
+}
+
+fn variant_kind(has_content: bool) -> &'static str {
+    if has_content {
+        "data variant"
+    } else {
+        "unit variant"
+    }
+}
+
+/// Follows references and `allOf`s with a single element (which schemars uses to add a
+/// description to a reference). Returns the schema and the name of the last definition.
+fn resolve<'s>(
+    mut schema: &'s Schema,
+    definitions: &'s schemars::Map<String, Schema>,
+) -> (SchemaObject, Option<&'s str>) {
+    let mut definition = None;
+    // Limits the depth for definitions referencing each other in a cycle
+    for _ in 0..definitions.len() + 16 {
+        let object = match schema {
+            Schema::Object(object) => object,
+            Schema::Bool(_) => break,
+        };
+        if let Some(reference) = &object.reference {
+            match reference
+                .strip_prefix("#/definitions/")
+                .and_then(|name| definitions.get_key_value(name))
+            {
+                Some((name, target)) => {
+                    definition = Some(name.as_str());
+                    schema = target;
+                    continue;
+                }
+                None => break,
+            }
+        }
+        match object.subschemas.as_ref().and_then(|s| s.all_of.as_ref()) {
+            Some(all_of) if all_of.len() == 1 => schema = &all_of[0],
+            _ => break,
+        }
+    }
+    let object = match schema {
+        Schema::Object(object) => object.clone(),
+        // `true` accepts anything, `false` nothing, which never happens in generated schemas
+        Schema::Bool(_) => SchemaObject::default(),
+    };
+    (object, definition)
+}
+
+/// Removes `null` from the accepted values, e.g. for `Option<T>`. Returns the remaining
+/// schema and whether `null` was accepted.
+fn without_null(schema: &SchemaObject) -> (SchemaObject, bool) {
+    if let Some(any_of) = schema.subschemas.as_ref().and_then(|s| s.any_of.as_ref()) {
+        if any_of.len() == 2 && any_of.iter().any(is_null) {
+            let inner = any_of.iter().find(|s| !is_null(s)).unwrap();
+            let inner = SchemaObject {
+                subschemas: Some(Box::new(schemars::schema::SubschemaValidation {
+                    all_of: Some(vec![inner.clone()]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            };
+            return (inner, true);
+        }
+    }
+    if let Some(SingleOrVec::Vec(types)) = &schema.instance_type {
+        if types.contains(&InstanceType::Null) {
+            let mut types: Vec<InstanceType> = types
+                .iter()
+                .copied()
+                .filter(|ty| *ty != InstanceType::Null)
+                .collect();
+            let mut inner = schema.clone();
+            inner.instance_type = match types.len() {
+                0 => None,
+                1 => Some(SingleOrVec::Single(Box::new(types.remove(0)))),
+                _ => Some(SingleOrVec::Vec(types)),
+            };
+            return (inner, true);
+        }
+    }
+    (schema.clone(), false)
+}
+
+fn is_any(schema: &SchemaObject) -> bool {
+    schema.instance_type.is_none()
+        && schema.enum_values.is_none()
+        && schema.const_value.is_none()
+        && schema.subschemas.is_none()
+        && schema.reference.is_none()
+}
+
+/// A short description of the type for error messages
+fn describe(schema: &SchemaObject) -> String {
+    if is_any(schema) {
+        return "any value".to_string();
+    }
+    if enum_variants(schema).is_some() || variants(schema).is_some() {
+        return "enum".to_string();
+    }
+    if is_struct(schema) {
+        return "object".to_string();
+    }
+    let ty = match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => instance_type_name(ty).to_string(),
+        Some(SingleOrVec::Vec(types)) => types
+            .iter()
+            .map(instance_type_name)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        None => "other".to_string(),
+    };
+    match &schema.format {
+        Some(format) => format!("{} ({})", ty, format),
+        None => ty,
+    }
+}
+
+fn instance_type_name(ty: &InstanceType) -> &'static str {
+    match ty {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "boolean",
+        InstanceType::Object => "map",
+        InstanceType::Array => "array",
+        InstanceType::Number => "number",
+        InstanceType::String => "string",
+        InstanceType::Integer => "integer",
+    }
+}
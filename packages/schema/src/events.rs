@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+pub use cosmwasm_schema_derive::ContractEvents;
+
+/// A trait for declaring the events a contract emits and the attributes they have,
+/// such that indexers can rely on them instead of reverse-engineering the attributes.
+/// The events end up in the `events` of the generated API description.
+///
+/// Using the derive macro on an enum with one variant per event is the preferred way of
+/// implementing this trait. The event type is the variant name in snake_case (overridable
+/// with `#[event(name = "...")]`) and the attributes are the named fields of the variant
+/// (overridable with `#[event(key = "...")]`). `Option` fields are attributes that are not
+/// always set. Doc comments end up as descriptions.
+///
+/// Custom events are emitted with `wasm-` prefixed to their type on chain. The attributes added
+/// directly to a `Response` are part of the `wasm` event, which can be declared with
+/// `#[event(name = "wasm")]`. Events and attributes marked with `#[deprecated]` are declared as
+/// deprecated, such that indexers can move away from them before they are removed.
+///
+/// # Example
+/// ```
+/// use cosmwasm_schema::{AttributeSchema, ContractEvents, EventSchema};
+///
+/// #[derive(ContractEvents)]
+/// pub enum Event {
+///     /// Tokens were sent to another account
+///     Transfer {
+///         from: String,
+///         to: String,
+///         amount: u128,
+///         memo: Option<String>,
+///     },
+///     #[event(name = "wasm")]
+///     Action {
+///         #[event(key = "action")]
+///         kind: String,
+///     },
+/// }
+///
+/// let events = Event::event_schemas();
+/// assert_eq!(events[0].ty, "transfer");
+/// assert_eq!(events[0].description.as_deref(), Some("Tokens were sent to another account"));
+/// assert_eq!(events[0].attributes[3], AttributeSchema::optional("memo"));
+/// assert_eq!(events[1], EventSchema::new("wasm").attribute(AttributeSchema::required("action")));
+/// ```
+pub trait ContractEvents {
+    fn event_schemas() -> Vec<EventSchema>;
+}
+
+/// An event emitted by a contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EventSchema {
+    /// The type of the event as set in `Event::new`, i.e. without the `wasm-` prefix
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the event will stop being emitted in a future version
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+    pub attributes: Vec<AttributeSchema>,
+}
+
+impl EventSchema {
+    pub fn new(ty: impl Into<String>) -> Self {
+        EventSchema {
+            ty: ty.into(),
+            description: None,
+            deprecated: false,
+            attributes: vec![],
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    pub fn attribute(mut self, attribute: AttributeSchema) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+}
+
+/// An attribute of an [`EventSchema`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AttributeSchema {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the attribute is set every time the event is emitted
+    pub required: bool,
+    /// Whether the attribute will stop being set in a future version
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+}
+
+impl AttributeSchema {
+    /// An attribute that is set every time the event is emitted
+    pub fn required(key: impl Into<String>) -> Self {
+        AttributeSchema {
+            key: key.into(),
+            description: None,
+            required: true,
+            deprecated: false,
+        }
+    }
+
+    /// An attribute that is only set in some cases
+    pub fn optional(key: impl Into<String>) -> Self {
+        AttributeSchema {
+            required: false,
+            ..AttributeSchema::required(key)
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+}
+
+pub(crate) fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_schema_serializes_like_the_idl() {
+        let event = EventSchema::new("transfer")
+            .description("Tokens were sent")
+            .attribute(AttributeSchema::required("amount").description("In the smallest unit"))
+            .attribute(AttributeSchema::optional("memo").deprecated());
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "type": "transfer",
+                "description": "Tokens were sent",
+                "attributes": [
+                    { "key": "amount", "description": "In the smallest unit", "required": true },
+                    { "key": "memo", "required": false, "deprecated": true }
+                ]
+            })
+        );
+        let parsed: EventSchema =
+            serde_json::from_value(serde_json::to_value(&event).unwrap()).unwrap();
+        assert_eq!(parsed, event);
+    }
+}
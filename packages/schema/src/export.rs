@@ -6,6 +6,7 @@ use std::path::Path;
 use schemars::schema::RootSchema;
 
 use crate::casing::to_snake_case;
+use crate::check::{check_schema_file, is_check_mode};
 
 // Exports a schema, auto-generating filename based on the metadata title of the generated schema.
 pub fn export_schema(schema: &RootSchema, out_dir: &Path) {
@@ -31,10 +32,21 @@ pub fn export_schema_with_title(schema: &RootSchema, out_dir: &Path, title: &str
 
 /// Writes schema to file. Overwrites existing file.
 /// Panics on any error writing out the schema.
+///
+/// In [check mode](crate::is_check_mode) the file is compared instead and the process exits
+/// with an error if it is missing or outdated.
 fn write_schema(schema: &RootSchema, out_dir: &Path, title: &str) {
     // first, we set the title as we wish
     let path = out_dir.join(format!("{}.json", to_snake_case(title)));
-    let json = serde_json::to_string_pretty(schema).unwrap();
-    write(&path, json + "\n").unwrap();
-    println!("Created {}", path.to_str().unwrap());
+    let json = serde_json::to_string_pretty(schema).unwrap() + "\n";
+    if is_check_mode() {
+        if let Err(err) = check_schema_file(&path, &json) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        println!("The schema in {} is up to date", path.to_str().unwrap());
+    } else {
+        write(&path, json).unwrap();
+        println!("Created {}", path.to_str().unwrap());
+    }
 }
@@ -0,0 +1,495 @@
+//! Generation of Go type definitions from a contract's API.
+//!
+//! This is meant for the chain side: wasmd modules, test fixtures and relayers that
+//! send messages to a contract from Go. The generated types use `json` struct tags
+//! matching the serde names, so `encoding/json` produces the JSON the contract expects.
+//!
+//! Mapping rules:
+//!
+//! - Objects become structs. Optional fields are pointers with `omitempty`.
+//! - Enums with data (`oneOf` of single-property objects) become structs with one
+//!   optional pointer field per variant, of which exactly one must be set.
+//! - Enums without data become a `string` type with one constant per variant.
+//! - Integers use the sized Go type of their format. `u128`/`i128` use `json.Number`.
+//! - Everything without a Go equivalent (tuples, enums mixing unit and data variants,
+//!   arbitrary JSON) becomes `json.RawMessage`.
+//! - Fields and data variants marked with `#[deprecated]` get a `Deprecated:` comment.
+
+use std::collections::BTreeMap;
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+
+use crate::casing::to_pascal_case;
+use crate::codegen::{
+    comment, documentation, generated_notice, is_named, is_struct, property_schema_documentation,
+    single_property, string_values, variants, with_comment, Generator, TypeNames,
+};
+
+/// The command line flag that makes `write_api!` also export Go type definitions
+pub const GO_FLAG: &str = "--go";
+
+const RAW: &str = "json.RawMessage";
+/// Appended to the comments of deprecated fields and variants, as recognized by Go tooling
+const DEPRECATED: &str = "Deprecated: Marked as deprecated by the contract.";
+
+/// Returns true if the current process was started with [`--go`](GO_FLAG),
+/// e.g. via `cargo schema -- --go`.
+pub fn is_go_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == GO_FLAG)
+}
+
+/// Returns a valid Go package name for the given contract name, e.g. `cryptoverify`
+/// for `crypto-verify`.
+pub fn go_package_name(contract_name: &str) -> String {
+    let name: String = contract_name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    match name.chars().next() {
+        Some(first) if !first.is_ascii_digit() => name,
+        _ => format!("contract{}", name),
+    }
+}
+
+/// Renders Go type definitions for the given root schemas.
+///
+/// `messages` are the top level messages with their Go type names. For `responses`
+/// the type name is taken from the schema title.
+pub(crate) fn render_go<'a>(
+    package: &str,
+    contract_name: &str,
+    contract_version: &str,
+    messages: impl IntoIterator<Item = (&'a str, &'a RootSchema)>,
+    responses: Option<&'a BTreeMap<String, RootSchema>>,
+) -> String {
+    let mut gen = GoGenerator::default();
+    for (name, root) in messages {
+        gen.root(name, root);
+    }
+    for (query, root) in responses.into_iter().flatten() {
+        // Responses like `String` or `Vec<Coin>` map to Go types directly
+        if is_named(&root.schema) {
+            let name = root
+                .schema
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.title.clone())
+                .unwrap_or_else(|| format!("{}_response", query));
+            gen.root(&name, root);
+        }
+    }
+
+    let body = gen.types.join("\n");
+    let mut out = format!(
+        "// {}\n\npackage {}\n",
+        generated_notice(contract_name, contract_version),
+        package
+    );
+    if body.contains("json.") {
+        out.push_str("\nimport \"encoding/json\"\n");
+    }
+    if !body.is_empty() {
+        out.push('\n');
+        out.push_str(&body);
+    }
+    out
+}
+
+#[derive(Default)]
+struct GoGenerator<'a> {
+    /// The rendered type definitions in output order
+    types: Vec<String>,
+    type_names: TypeNames<'a>,
+}
+
+impl<'a> Generator<'a> for GoGenerator<'a> {
+    type Type = String;
+
+    fn type_names(&mut self) -> &mut TypeNames<'a> {
+        &mut self.type_names
+    }
+
+    fn raw(&mut self) -> String {
+        RAW.to_string()
+    }
+
+    fn optional(&mut self, ty: String) -> String {
+        optional(ty)
+    }
+
+    fn named(&mut self, name: &str) -> String {
+        name.to_string()
+    }
+
+    /// Renders a named type definition
+    fn define(&mut self, name: &str, schema: &SchemaObject) {
+        // Reserve the position of this type before the types it uses
+        let index = self.types.len();
+        self.types.push(String::new());
+
+        let code = if let Some(variants) = variants(schema) {
+            self.enumeration(name, variants)
+        } else if let Some(values) = string_values(schema) {
+            string_enum(name, &values)
+        } else if is_struct(schema) {
+            self.structure(name, schema)
+        } else {
+            match self.type_for(schema, name) {
+                ty if ty == RAW => format!("type {} = {}\n", name, ty),
+                ty => format!("type {} {}\n", name, ty),
+            }
+        };
+        self.types[index] = with_comment(documentation(schema, DEPRECATED).as_deref(), code);
+    }
+
+    fn unnamed(
+        &mut self,
+        instance_type: InstanceType,
+        schema: &SchemaObject,
+        context: &str,
+    ) -> String {
+        match instance_type {
+            InstanceType::Boolean => "bool".to_string(),
+            InstanceType::String => "string".to_string(),
+            InstanceType::Number => "float64".to_string(),
+            InstanceType::Integer => integer_type(schema.format.as_deref()).to_string(),
+            InstanceType::Array => match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+                Some(SingleOrVec::Single(items)) => {
+                    format!("[]{}", self.type_of(items, &format!("{}Item", context)))
+                }
+                _ => format!("[]{}", RAW),
+            },
+            InstanceType::Object => {
+                match schema
+                    .object
+                    .as_ref()
+                    .and_then(|o| o.additional_properties.as_ref())
+                {
+                    Some(values) => format!(
+                        "map[string]{}",
+                        self.type_of(values, &format!("{}Value", context))
+                    ),
+                    None => format!("map[string]{}", RAW),
+                }
+            }
+            InstanceType::Null => RAW.to_string(),
+        }
+    }
+}
+
+impl GoGenerator<'_> {
+    fn structure(&mut self, name: &str, schema: &SchemaObject) -> String {
+        let object = schema.object.as_ref().unwrap();
+        let mut fields = String::new();
+        for (property, property_schema) in &object.properties {
+            let field = to_pascal_case(property);
+            let ty = self.type_of(property_schema, &format!("{}{}", name, field));
+            let (ty, tag) = if object.required.contains(property) {
+                (ty, property.to_string())
+            } else {
+                (optional(ty), format!("{},omitempty", property))
+            };
+            if let Some(docs) = property_schema_documentation(property_schema, DEPRECATED) {
+                fields.push_str(&comment(&docs, "\t"));
+            }
+            fields.push_str(&format!("\t{} {} `json:\"{}\"`\n", field, ty, tag));
+        }
+        if fields.is_empty() {
+            format!("type {} struct{{}}\n", name)
+        } else {
+            format!("type {} struct {{\n{}}}\n", name, fields)
+        }
+    }
+
+    /// Renders an enum, i.e. a `oneOf` or `anyOf` as generated by schemars for Rust enums
+    fn enumeration(&mut self, name: &str, variants: &[Schema]) -> String {
+        let mut values = Vec::new();
+        let mut cases = Vec::new();
+        for variant in variants {
+            match variant {
+                Schema::Object(variant) => {
+                    if let Some(variant_values) = string_values(variant) {
+                        values.extend(variant_values);
+                    } else if let Some((property, content)) = single_property(variant) {
+                        cases.push((property, content, documentation(variant, DEPRECATED)));
+                    } else {
+                        return format!("type {} = {}\n", name, RAW);
+                    }
+                }
+                Schema::Bool(_) => return format!("type {} = {}\n", name, RAW),
+            }
+        }
+
+        match (values.is_empty(), cases.is_empty()) {
+            (false, true) => string_enum(name, &values),
+            (true, false) => {
+                let mut fields = String::new();
+                for (property, content, docs) in cases {
+                    let field = to_pascal_case(property);
+                    let ty = self.type_of(content, &format!("{}{}", name, field));
+                    if let Some(docs) = docs {
+                        fields.push_str(&comment(&docs, "\t"));
+                    }
+                    fields.push_str(&format!(
+                        "\t{} {} `json:\"{},omitempty\"`\n",
+                        field,
+                        optional(ty),
+                        property
+                    ));
+                }
+                format!("type {} struct {{\n{}}}\n", name, fields)
+            }
+            // Mixing unit variants (strings) with data variants (objects) has no Go equivalent
+            _ => format!("type {} = {}\n", name, RAW),
+        }
+    }
+}
+
+fn integer_type(format: Option<&str>) -> &'static str {
+    match format {
+        Some("int8") => "int8",
+        Some("int16") => "int16",
+        Some("int32") => "int32",
+        Some("uint8") => "uint8",
+        Some("uint16") => "uint16",
+        Some("uint32") => "uint32",
+        Some("uint64") => "uint64",
+        Some("uint") => "uint",
+        Some("int") => "int",
+        Some("int128") | Some("uint128") => "json.Number",
+        _ => "int64",
+    }
+}
+
+/// Makes a type optional. Slices, maps and raw JSON are nil-able already.
+fn optional(ty: String) -> String {
+    if ty.starts_with('*') || ty.starts_with("[]") || ty.starts_with("map[") || ty == RAW {
+        ty
+    } else {
+        format!("*{}", ty)
+    }
+}
+
+fn string_enum(name: &str, values: &[String]) -> String {
+    let mut out = format!("type {} string\n\nconst (\n", name);
+    for value in values {
+        out.push_str(&format!(
+            "\t{}{} {} = \"{}\"\n",
+            name,
+            to_pascal_case(value),
+            name,
+            value
+        ));
+    }
+    out.push_str(")\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Color {
+        Red,
+        DarkBlue,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    pub struct Coin {
+        denom: String,
+        amount: u128,
+    }
+
+    /// Instantiates the contract
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct InstantiateMsg {
+        /// The owner
+        owner: String,
+        admin: Option<String>,
+        color: Color,
+        funds: Vec<Coin>,
+        limit: Option<u32>,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum ExecuteMsg {
+        /// Sends funds
+        Send {
+            to: String,
+            amount: Coin,
+        },
+        Reset {},
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Mixed {
+        Unit,
+        Data { value: bool },
+    }
+
+    #[test]
+    fn go_package_name_works() {
+        assert_eq!(go_package_name("hackatom"), "hackatom");
+        assert_eq!(go_package_name("crypto-verify"), "cryptoverify");
+        assert_eq!(go_package_name("My_Contract"), "mycontract");
+        assert_eq!(go_package_name("1inch"), "contract1inch");
+        assert_eq!(go_package_name(""), "contract");
+    }
+
+    #[test]
+    fn render_go_works() {
+        let instantiate = schema_for!(InstantiateMsg);
+        let execute = schema_for!(ExecuteMsg);
+        let mut responses = BTreeMap::new();
+        responses.insert("balance".to_string(), schema_for!(Coin));
+        responses.insert("owner".to_string(), schema_for!(String));
+
+        let go = render_go(
+            "example",
+            "example",
+            "0.1.0",
+            [("InstantiateMsg", &instantiate), ("ExecuteMsg", &execute)],
+            Some(&responses),
+        );
+        assert_eq!(
+            go,
+            r#"// Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+
+package example
+
+import "encoding/json"
+
+// Instantiates the contract
+type InstantiateMsg struct {
+	Admin *string `json:"admin,omitempty"`
+	Color Color `json:"color"`
+	Funds []Coin `json:"funds"`
+	Limit *uint32 `json:"limit,omitempty"`
+	// The owner
+	Owner string `json:"owner"`
+}
+
+type Color string
+
+const (
+	ColorRed Color = "red"
+	ColorDarkBlue Color = "dark_blue"
+)
+
+type Coin struct {
+	Amount json.Number `json:"amount"`
+	Denom string `json:"denom"`
+}
+
+type ExecuteMsg struct {
+	// Sends funds
+	Send *ExecuteMsgSend `json:"send,omitempty"`
+	Reset *ExecuteMsgReset `json:"reset,omitempty"`
+}
+
+type ExecuteMsgSend struct {
+	Amount Coin `json:"amount"`
+	To string `json:"to"`
+}
+
+type ExecuteMsgReset struct{}
+"#
+        );
+    }
+
+    /// A decimal string like `cosmwasm_std::Uint128`
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Uint128(String);
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Expiration {
+        AtHeight(u64),
+        Never {},
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Config {
+        weights: std::collections::BTreeMap<String, Uint128>,
+        expires: Option<Expiration>,
+        #[serde(rename = "tokenIds")]
+        token_ids: Vec<u32>,
+    }
+
+    #[test]
+    fn render_go_works_for_nested_types() {
+        let config = schema_for!(Config);
+        let go = render_go("example", "example", "0.1.0", [("Config", &config)], None);
+        assert_eq!(
+            go,
+            r#"// Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+
+package example
+
+type Config struct {
+	Expires *Expiration `json:"expires,omitempty"`
+	TokenIds []uint32 `json:"tokenIds"`
+	Weights map[string]Uint128 `json:"weights"`
+}
+
+type Expiration struct {
+	AtHeight *uint64 `json:"at_height,omitempty"`
+	Never *ExpirationNever `json:"never,omitempty"`
+}
+
+type ExpirationNever struct{}
+
+// A decimal string like `cosmwasm_std::Uint128`
+type Uint128 string
+"#
+        );
+    }
+
+    #[test]
+    fn render_go_uses_raw_json_for_mixed_enums() {
+        let mixed = schema_for!(Mixed);
+        let go = render_go("example", "example", "0.1.0", [("Mixed", &mixed)], None);
+        assert_eq!(
+            go,
+            r#"// Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+
+package example
+
+import "encoding/json"
+
+type Mixed = json.RawMessage
+"#
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum LegacyMsg {
+        /// Sets the price
+        #[deprecated]
+        SetPrice {
+            #[deprecated]
+            denom: Option<String>,
+        },
+    }
+
+    #[test]
+    fn render_go_marks_deprecated_items() {
+        let msg = schema_for!(LegacyMsg);
+        let go = render_go("example", "example", "0.1.0", [("LegacyMsg", &msg)], None);
+        assert!(go.contains(
+            "\t// Sets the price\n\t//\n\t// Deprecated: Marked as deprecated by the contract.\n\tSetPrice *LegacyMsgSetPrice"
+        ));
+        assert!(go.contains(
+            "\t// Deprecated: Marked as deprecated by the contract.\n\tDenom *string `json:\"denom,omitempty\"`"
+        ));
+    }
+}
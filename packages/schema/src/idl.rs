@@ -2,14 +2,21 @@
 
 use std::collections::BTreeMap;
 
-use schemars::schema::RootSchema;
+use schemars::schema::{RootSchema, SchemaObject};
+use schemars::visit::{visit_root_schema, visit_schema_object, Visitor};
 use thiserror::Error;
 
+use crate::bundle::bundle_definitions;
+use crate::casing::to_pascal_case;
+use crate::dialect::{schema_to_dialect, subschema_to_2020_12, Dialect};
+use crate::events::EventSchema;
+use crate::state::StateSchema;
+
 /// The version of the CosmWasm IDL.
 ///
 /// Follows Semantic Versioning 2.0.0: <https://semver.org/>
 // To determine if a change is breaking, assume consumers allow unknown fields and bump accordingly.
-pub const IDL_VERSION: &str = "1.0.0";
+pub const IDL_VERSION: &str = "1.4.0";
 
 /// Rust representation of a contract's API.
 pub struct Api {
@@ -20,12 +27,45 @@ pub struct Api {
     pub query: Option<RootSchema>,
     pub migrate: Option<RootSchema>,
     pub sudo: Option<RootSchema>,
+    /// The packets sent and received over IBC channels
+    pub ibc_packet: Option<RootSchema>,
+    /// The acknowledgements of IBC packets
+    pub ibc_ack: Option<RootSchema>,
+    /// The payloads the contract attaches to submessages and reads in `reply`
+    pub reply: Option<RootSchema>,
+    /// The messages of further entry points by entry point name, e.g. chain-specific hooks
+    pub entry_points: BTreeMap<String, RootSchema>,
+    /// The events the contract emits, see [`ContractEvents`](crate::ContractEvents)
+    pub events: Option<Vec<EventSchema>>,
+    /// The storage layout of the contract, see [`ContractState`](crate::ContractState)
+    pub state: Option<Vec<StateSchema>>,
     /// A mapping of query variants to response types
     pub responses: Option<BTreeMap<String, RootSchema>>,
 }
 
 impl Api {
-    pub fn render(self) -> JsonApi {
+    pub fn render(mut self) -> JsonApi {
+        canonicalize(&mut self.instantiate);
+        for schema in [
+            &mut self.execute,
+            &mut self.query,
+            &mut self.migrate,
+            &mut self.sudo,
+            &mut self.ibc_packet,
+            &mut self.ibc_ack,
+            &mut self.reply,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.entry_points.values_mut())
+        .chain(self.state.iter_mut().flatten().map(|item| &mut item.value))
+        {
+            canonicalize(schema);
+        }
+        for schema in self.responses.iter_mut().flat_map(|r| r.values_mut()) {
+            canonicalize(schema);
+        }
+
         let mut json_api = JsonApi {
             contract_name: self.contract_name,
             contract_version: self.contract_version,
@@ -35,6 +75,12 @@ impl Api {
             query: self.query,
             migrate: self.migrate,
             sudo: self.sudo,
+            ibc_packet: self.ibc_packet,
+            ibc_ack: self.ibc_ack,
+            reply: self.reply,
+            entry_points: self.entry_points,
+            events: self.events,
+            state: self.state,
             responses: self.responses,
         };
 
@@ -61,13 +107,32 @@ impl Api {
                 metadata.title = Some("SudoMsg".to_string());
             }
         }
+        if let Some(ibc_packet) = &mut json_api.ibc_packet {
+            if let Some(metadata) = &mut ibc_packet.schema.metadata {
+                metadata.title = Some("IbcPacket".to_string());
+            }
+        }
+        if let Some(ibc_ack) = &mut json_api.ibc_ack {
+            if let Some(metadata) = &mut ibc_ack.schema.metadata {
+                metadata.title = Some("IbcAck".to_string());
+            }
+        }
+        if let Some(reply) = &mut json_api.reply {
+            if let Some(metadata) = &mut reply.schema.metadata {
+                metadata.title = Some("ReplyMsg".to_string());
+            }
+        }
+        // The titles name the types in generated code, e.g. `BeforeSendMsg` for `before_send`
+        for (name, schema) in &mut json_api.entry_points {
+            schema.schema.metadata().title = Some(format!("{}Msg", to_pascal_case(name)));
+        }
 
         json_api
     }
 }
 
 /// A JSON representation of a contract's API.
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone)]
 pub struct JsonApi {
     contract_name: String,
     contract_version: String,
@@ -77,9 +142,57 @@ pub struct JsonApi {
     query: Option<RootSchema>,
     migrate: Option<RootSchema>,
     sudo: Option<RootSchema>,
+    // Omitted if not set, so that the files of contracts without IBC do not change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ibc_packet: Option<RootSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ibc_ack: Option<RootSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply: Option<RootSchema>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    entry_points: BTreeMap<String, RootSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<EventSchema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<Vec<StateSchema>>,
     responses: Option<BTreeMap<String, RootSchema>>,
 }
 
+/// A [`JsonApi`] with schemas converted into another dialect, using the same field order
+#[derive(serde::Serialize)]
+struct DialectApi<'a> {
+    contract_name: &'a str,
+    contract_version: &'a str,
+    idl_version: &'a str,
+    instantiate: serde_json::Value,
+    execute: Option<serde_json::Value>,
+    query: Option<serde_json::Value>,
+    migrate: Option<serde_json::Value>,
+    sudo: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ibc_packet: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ibc_ack: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    entry_points: BTreeMap<&'a str, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<&'a [EventSchema]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<Vec<serde_json::Value>>,
+    responses: Option<BTreeMap<&'a str, serde_json::Value>>,
+}
+
+/// An API with the definitions of all its schemas in one place, see [`JsonApi::to_bundled_string`]
+#[derive(serde::Serialize)]
+struct BundledApi<A, D> {
+    #[serde(flatten)]
+    api: A,
+    #[serde(rename = "$defs")]
+    definitions: D,
+}
+
 impl JsonApi {
     pub fn to_string(&self) -> Result<String, EncodeError> {
         serde_json::to_string_pretty(&self).map_err(Into::into)
@@ -88,6 +201,251 @@ impl JsonApi {
     pub fn to_writer(&self, writer: impl std::io::Write) -> Result<(), EncodeError> {
         serde_json::to_writer_pretty(writer, self).map_err(Into::into)
     }
+
+    /// Like [`JsonApi::to_string`], but with all schemas in the given JSON Schema dialect.
+    /// The structure of the API file does not change.
+    pub fn to_string_with_dialect(&self, dialect: Dialect) -> Result<String, EncodeError> {
+        if dialect == Dialect::Draft07 {
+            return self.to_string();
+        }
+        serde_json::to_string_pretty(&self.to_dialect(dialect)).map_err(Into::into)
+    }
+
+    /// Like [`JsonApi::to_string_with_dialect`], but with the definitions of all schemas
+    /// moved into a `$defs` section at the top level, see [`is_bundle_mode`](crate::is_bundle_mode).
+    pub fn to_bundled_string(&self, dialect: Dialect) -> Result<String, EncodeError> {
+        let mut api = self.clone();
+        let definitions = bundle_definitions(api.schemas_mut());
+        if dialect == Dialect::Draft07 {
+            let bundled = BundledApi {
+                api: &api,
+                definitions,
+            };
+            return serde_json::to_string_pretty(&bundled).map_err(Into::into);
+        }
+        let bundled = BundledApi {
+            api: api.to_dialect(dialect),
+            definitions: definitions
+                .iter()
+                .map(|(name, definition)| {
+                    let mut definition = serde_json::to_value(definition).unwrap();
+                    subschema_to_2020_12(&mut definition);
+                    (name.as_str(), definition)
+                })
+                .collect::<BTreeMap<_, _>>(),
+        };
+        serde_json::to_string_pretty(&bundled).map_err(Into::into)
+    }
+
+    fn to_dialect(&self, dialect: Dialect) -> DialectApi<'_> {
+        let convert = |schema: &RootSchema| schema_to_dialect(schema, dialect);
+        DialectApi {
+            contract_name: &self.contract_name,
+            contract_version: &self.contract_version,
+            idl_version: &self.idl_version,
+            instantiate: convert(&self.instantiate),
+            execute: self.execute.as_ref().map(convert),
+            query: self.query.as_ref().map(convert),
+            migrate: self.migrate.as_ref().map(convert),
+            sudo: self.sudo.as_ref().map(convert),
+            ibc_packet: self.ibc_packet.as_ref().map(convert),
+            ibc_ack: self.ibc_ack.as_ref().map(convert),
+            reply: self.reply.as_ref().map(convert),
+            entry_points: self
+                .entry_points
+                .iter()
+                .map(|(name, schema)| (name.as_str(), convert(schema)))
+                .collect(),
+            events: self.events.as_deref(),
+            state: self.state.as_ref().map(|state| {
+                state
+                    .iter()
+                    .map(|item| {
+                        let mut json = serde_json::to_value(item).unwrap();
+                        json["value"] = convert(&item.value);
+                        json
+                    })
+                    .collect()
+            }),
+            responses: self.responses.as_ref().map(|responses| {
+                responses
+                    .iter()
+                    .map(|(query, schema)| (query.as_str(), convert(schema)))
+                    .collect()
+            }),
+        }
+    }
+
+    /// Renders Go type definitions for all messages and responses of the API
+    /// into a Go file of the given package. See [`go_package_name`](crate::go_package_name)
+    /// for deriving a package name from the contract name.
+    ///
+    /// The output is not formatted, so you may want to run `gofmt` on it.
+    pub fn to_go(&self, package: &str) -> String {
+        crate::go::render_go(
+            package,
+            &self.contract_name,
+            &self.contract_version,
+            self.messages(),
+            self.responses.as_ref(),
+        )
+    }
+
+    /// Renders an OpenAPI 3.1 document with one operation per query, such that REST gateways
+    /// and API documentation can be generated from it. See
+    /// [`is_openapi_mode`](crate::is_openapi_mode) for the mapping.
+    pub fn to_openapi(&self) -> Result<String, EncodeError> {
+        let openapi = crate::openapi::render_openapi(
+            &self.contract_name,
+            &self.contract_version,
+            self.query.as_ref(),
+            self.responses.as_ref(),
+        );
+        serde_json::to_string_pretty(&openapi).map_err(Into::into)
+    }
+
+    /// Renders Protocol Buffers definitions for all messages and responses of the API
+    /// into a proto file of the given package. The mapping is experimental, see the
+    /// documentation of [`is_proto_mode`](crate::is_proto_mode) for details.
+    pub fn to_proto(&self, package: &str) -> String {
+        crate::proto::render_proto(
+            package,
+            &self.contract_name,
+            &self.contract_version,
+            self.messages(),
+            self.responses.as_ref(),
+        )
+    }
+
+    /// Encodes the definitions of [`to_proto`](Self::to_proto) as a binary
+    /// `google.protobuf.FileDescriptorSet` with a single file `<contract name>.proto`.
+    /// See [`is_proto_descriptor_mode`](crate::is_proto_descriptor_mode) for details.
+    pub fn to_proto_descriptor_set(&self, package: &str) -> Vec<u8> {
+        crate::proto::render_proto_descriptor_set(
+            package,
+            &format!("{}.proto", self.contract_name),
+            self.messages(),
+            self.responses.as_ref(),
+        )
+    }
+
+    /// Renders pydantic (v2) models for all messages and responses of the API into a
+    /// Python module. See [`python_module_name`](crate::python_module_name) for deriving
+    /// a module name from the contract name.
+    ///
+    /// The output is not formatted, so you may want to run e.g. `black` on it.
+    pub fn to_python(&self) -> String {
+        crate::python::render_python(
+            &self.contract_name,
+            &self.contract_version,
+            self.messages(),
+            self.responses.as_ref(),
+        )
+    }
+
+    /// Renders TypeScript type definitions for all messages and responses of the API,
+    /// together with typed query and execute clients named after the contract.
+    ///
+    /// Integers are mapped to `number`, which cannot represent values above 2^53 exactly.
+    /// Use `Uint64`/`Uint128` in messages to avoid this.
+    pub fn to_ts(&self) -> String {
+        crate::ts::render_ts(
+            &self.contract_name,
+            &self.contract_version,
+            self.messages(),
+            self.responses.as_ref(),
+        )
+    }
+
+    /// All schemas of the API, i.e. those of the messages, responses and stored values
+    pub(crate) fn schemas_mut(&mut self) -> impl Iterator<Item = &mut RootSchema> {
+        [
+            Some(&mut self.instantiate),
+            self.execute.as_mut(),
+            self.query.as_mut(),
+            self.migrate.as_mut(),
+            self.sudo.as_mut(),
+            self.ibc_packet.as_mut(),
+            self.ibc_ack.as_mut(),
+            self.reply.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.entry_points.values_mut())
+        .chain(self.state.iter_mut().flatten().map(|item| &mut item.value))
+        .chain(self.responses.iter_mut().flat_map(|r| r.values_mut()))
+    }
+
+    /// The message schemas with their type names
+    fn messages(&self) -> impl Iterator<Item = (&str, &RootSchema)> {
+        [
+            ("InstantiateMsg", Some(&self.instantiate)),
+            ("ExecuteMsg", self.execute.as_ref()),
+            ("QueryMsg", self.query.as_ref()),
+            ("MigrateMsg", self.migrate.as_ref()),
+            ("SudoMsg", self.sudo.as_ref()),
+            ("IbcPacket", self.ibc_packet.as_ref()),
+            ("IbcAck", self.ibc_ack.as_ref()),
+            ("ReplyMsg", self.reply.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(name, schema)| schema.map(|schema| (name, schema)))
+        .chain(self.entry_points.iter().map(|(name, schema)| {
+            let title = schema
+                .schema
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.title.as_deref());
+            (title.unwrap_or(name), schema)
+        }))
+    }
+}
+
+/// Brings a schema into a canonical form such that its serialization does not depend
+/// on the platform or on the feature set schemars was compiled with.
+///
+/// - `definitions`, `properties` and `patternProperties` are sorted by name. Depending on
+///   the `preserve_order` feature of schemars they otherwise keep the insertion order.
+/// - Windows line endings in titles and descriptions (taken from doc comments) are
+///   replaced by `\n`.
+///
+/// Lists where the order has a meaning, such as `anyOf`, `oneOf` or `enum`, are kept as is.
+/// This is applied to all schemas in [`Api::render`].
+pub fn canonicalize(schema: &mut RootSchema) {
+    visit_root_schema(&mut Canonicalizer, schema);
+}
+
+struct Canonicalizer;
+
+impl Visitor for Canonicalizer {
+    fn visit_root_schema(&mut self, root: &mut RootSchema) {
+        sort_map(&mut root.definitions);
+        visit_root_schema(self, root)
+    }
+
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(metadata) = &mut schema.metadata {
+            for text in [&mut metadata.title, &mut metadata.description]
+                .into_iter()
+                .flatten()
+            {
+                if text.contains('\r') {
+                    *text = text.replace("\r\n", "\n");
+                }
+            }
+        }
+        if let Some(object) = &mut schema.object {
+            sort_map(&mut object.properties);
+            sort_map(&mut object.pattern_properties);
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+fn sort_map<V>(map: &mut schemars::Map<String, V>) {
+    let mut entries: Vec<(String, V)> = std::mem::take(map).into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    *map = entries.into_iter().collect();
 }
 
 #[derive(Error, Debug)]
@@ -104,4 +462,28 @@ mod tests {
     fn version_is_semver() {
         semver::Version::parse(IDL_VERSION).unwrap();
     }
+
+    #[test]
+    fn canonicalize_works() {
+        let mut schema: RootSchema = serde_json::from_str(
+            r##"{
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "title": "Msg",
+                "description": "Some\r\nlines",
+                "type": "object",
+                "required": ["zeta", "alpha"],
+                "properties": {"zeta": {"type": "string"}, "alpha": {"$ref": "#/definitions/B"}},
+                "definitions": {
+                    "B": {"enum": ["y", "x"]},
+                    "A": {"anyOf": [{"$ref": "#/definitions/B"}, {"type": "object", "properties": {"y": {}, "x": {}}}]}
+                }
+            }"##,
+        )
+        .unwrap();
+        canonicalize(&mut schema);
+        assert_eq!(
+            serde_json::to_string(&schema).unwrap(),
+            r##"{"$schema":"http://json-schema.org/draft-07/schema#","title":"Msg","description":"Some\nlines","type":"object","required":["alpha","zeta"],"properties":{"alpha":{"$ref":"#/definitions/B"},"zeta":{"type":"string"}},"definitions":{"A":{"anyOf":[{"$ref":"#/definitions/B"},{"type":"object","properties":{"x":{},"y":{}}}]},"B":{"enum":["y","x"]}}}"##
+        );
+    }
 }
@@ -1,13 +1,52 @@
+pub mod array;
+mod bundle;
 mod casing;
+mod check;
+mod codegen;
+pub mod constraints;
+mod dialect;
+mod diff;
+mod events;
 mod export;
+mod go;
 mod idl;
+mod openapi;
+mod proto;
+mod python;
 mod query_response;
 mod remove;
+mod shared;
+mod state;
+mod ts;
+mod workspace;
 
+pub use bundle::{is_bundle_mode, BUNDLE_FLAG};
+pub use check::{
+    check_no_stale_schemas, check_schema_file, is_check_mode, SchemaCheckError, CHECK_FLAG,
+};
+pub use constraints::{Validate, ValidationError};
+pub use dialect::{dialect_mode, schema_to_dialect, Dialect, UnknownDialectError, DIALECT_FLAG};
+pub use diff::{
+    diff_api_file, diff_apis, diff_mode_base, BreakingChange, BreakingChangeKind, SchemaDiffError,
+    DIFF_FLAG,
+};
+pub use events::{AttributeSchema, ContractEvents, EventSchema};
 pub use export::{export_schema, export_schema_with_title};
-pub use idl::{Api, IDL_VERSION};
-pub use query_response::QueryResponses;
+pub use go::{go_package_name, is_go_mode, GO_FLAG};
+pub use idl::{canonicalize, Api, IDL_VERSION};
+pub use openapi::{is_openapi_mode, OPENAPI_FLAG};
+pub use proto::{
+    is_proto_descriptor_mode, is_proto_mode, proto_package_name, PROTO_DESCRIPTOR_FLAG, PROTO_FLAG,
+};
+pub use python::{is_python_mode, python_module_name, PYTHON_FLAG};
+pub use query_response::{combine_query_aliases, combine_subqueries, QueryResponses};
 pub use remove::remove_schemas;
+pub use shared::{extract_shared_definitions, is_shared_definitions_mode, SHARED_DEFINITIONS_FLAG};
+pub use state::{ContractState, KeyEncoding, StateSchema};
+pub use ts::{is_ts_mode, TS_FLAG};
+pub use workspace::{
+    write_workspace_api, WorkspaceExportError, WORKSPACE_DEFINITIONS_FILE, WORKSPACE_INDEX_FILE,
+};
 
 // Re-exports
 /// An attribute macro that annotates types with things they need to be properly (de)serialized
@@ -34,6 +73,159 @@ pub use remove::remove_schemas;
 ///     AccountName { account: String },
 /// }
 /// ```
+///
+/// Fixed-size array fields `[T; N]` are encoded as a JSON array of exactly `N` elements,
+/// like serde does, but for any `N` and with the length constraints in the schema. With
+/// `#[cw_serde(base64_arrays)]`, `[u8; N]` fields are encoded like `cosmwasm_std::Binary`
+/// (base64) instead, while arrays of numbers are still accepted when deserializing. See
+/// [`array`] for the helpers used. Arrays nested in other types (e.g. `Option<[u8; 32]>`)
+/// keep serde's default encoding. Fields with a custom `#[serde(with = "...")]` are not
+/// changed.
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+///
+/// #[cw_serde(base64_arrays)]
+/// pub struct Signer {
+///     pub pubkey: [u8; 4],
+/// }
+///
+/// let signer = Signer { pubkey: [1, 2, 3, 4] };
+/// assert_eq!(serde_json::to_string(&signer).unwrap(), r#"{"pubkey":"AQIDBA=="}"#);
+/// ```
+///
+/// Generic types are supported. The trait bounds for the serde implementations are inferred
+/// from the fields, just like for `#[derive(Serialize, Deserialize)]`. If those don't fit, they
+/// can be replaced using `#[cw_serde(bound = "...")]`, which is forwarded as
+/// [`#[serde(bound = "...")]`](https://serde.rs/container-attrs.html#bound).
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+///
+/// pub trait Extension {
+///     type Data: serde::Serialize
+///         + serde::de::DeserializeOwned
+///         + Clone
+///         + std::fmt::Debug
+///         + PartialEq
+///         + schemars::JsonSchema;
+/// }
+///
+/// #[cw_serde(bound = "")]
+/// pub struct ExecuteMsg<T: Extension> {
+///     data: T::Data,
+/// }
+/// ```
+///
+/// Unknown fields are rejected by default. Contracts that accept messages from newer
+/// versions of their clients can ignore them using `#[cw_serde(allow_unknown_fields)]`.
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+///
+/// #[cw_serde(allow_unknown_fields)]
+/// pub struct Config {
+///     owner: String,
+/// }
+///
+/// let config: Config = serde_json::from_str(r#"{"owner":"alice","added_later":1}"#).unwrap();
+/// assert_eq!(config, Config { owner: "alice".to_string() });
+/// ```
+///
+/// Additional traits can be derived using `#[cw_serde(derive(...))]`, e.g. to use a type as a
+/// map key or to sort it.
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+/// use std::collections::BTreeSet;
+///
+/// #[cw_serde(derive(Eq, PartialOrd, Ord))]
+/// pub enum Denom {
+///     Native(String),
+///     Cw20(String),
+/// }
+///
+/// let denoms: BTreeSet<Denom> = [Denom::Cw20("abc".to_string())].into_iter().collect();
+/// ```
+///
+/// Enums are externally tagged by default, e.g. `{"transfer":{"recipient":"..."}}`. Tooling that
+/// expects other shapes can use internally tagged enums with `#[cw_serde(tag = "...")]` or
+/// adjacently tagged enums with `#[cw_serde(tag = "...", content = "...")]`, see
+/// [serde's enum representations](https://serde.rs/enum-representations.html). These can
+/// derive [`QueryResponses`] as well.
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+///
+/// #[cw_serde(tag = "type", content = "value")]
+/// pub enum ExecuteMsg {
+///     Transfer { recipient: String },
+/// }
+///
+/// let msg = ExecuteMsg::Transfer { recipient: "bob".to_string() };
+/// assert_eq!(
+///     serde_json::to_string(&msg).unwrap(),
+///     r#"{"type":"transfer","value":{"recipient":"bob"}}"#,
+/// );
+/// ```
+///
+/// Fields can be constrained with `#[schema(...)]`, which adds the constraints to the schema:
+/// `min` and `max` for numbers, `min_length` and `max_length` for strings and collections and
+/// `pattern` for strings. Bounds are integer or float literals or the path of a constant, e.g.
+/// `max = "MAX_AMOUNT"`, which also allows negative bounds. With `#[cw_serde(validate)]` the
+/// ranges and lengths are checked on deserialization as well, using the helpers in
+/// [`constraints`], so contracts and clients validate with the same rules. Patterns are only
+/// part of the schema, since a regex engine would bloat the contract. To check messages
+/// explicitly instead, see [`Validate`](derive@Validate).
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+///
+/// #[cw_serde(validate)]
+/// pub struct Order {
+///     #[schema(min = 1, max = 100)]
+///     quantity: u32,
+///     #[schema(max_length = 64, pattern = "^[a-z]+$")]
+///     denom: String,
+/// }
+///
+/// let err = serde_json::from_str::<Order>(r#"{"quantity":0,"denom":"ucosm"}"#).unwrap_err();
+/// assert!(err.to_string().starts_with("invalid value 0, expected a value between 1 and 100"));
+/// ```
+///
+/// Example payloads are added to the schemas with `#[schema_example(...)]` on types, variants
+/// and fields, such that explorers and documentation generators can show ready-to-copy
+/// messages. The expression can be a value of the type itself or anything else that implements
+/// `Serialize`, e.g. a `serde_json::json!` value. Examples of response types end up in the
+/// response schemas of [`QueryResponses`] as well.
+///
+/// ```
+/// use cosmwasm_schema::{cw_serde, schema_for};
+/// use serde_json::json;
+///
+/// #[cw_serde]
+/// pub enum ExecuteMsg {
+///     #[schema_example(json!({ "transfer": { "recipient": "cosmos1..." } }))]
+///     Transfer { recipient: String },
+///     #[schema_example(ExecuteMsg::Freeze {})]
+///     Freeze {},
+/// }
+///
+/// let schema = serde_json::to_value(schema_for!(ExecuteMsg)).unwrap();
+/// assert_eq!(schema["oneOf"][1]["examples"], json!([{ "freeze": {} }]));
+/// ```
+///
+/// The expansion refers to the `serde` and `schemars` crates, which the using crate needs to
+/// depend on. If `cosmwasm_schema` is re-exported by another crate instead, point to it with
+/// `#[cw_serde(crate = "...")]` and serde and schemars are used through its re-exports.
+///
+/// ```
+/// // e.g. `crate = "my_sdk::cosmwasm_schema"` for a crate that has `pub use cosmwasm_schema;`
+/// #[cosmwasm_schema::cw_serde(crate = "::cosmwasm_schema")]
+/// pub struct Config {
+///     owner: String,
+/// }
+/// ```
 pub use cosmwasm_schema_derive::cw_serde;
 /// Generates an [`Api`](crate::Api) for the contract. The body describes the message
 /// types exported in the schema and allows setting contract name and version overrides.
@@ -73,6 +265,58 @@ pub use cosmwasm_schema_derive::generate_api;
 /// - `execute` - execute msg type, empty by default
 /// - `migrate` - migrate msg type, empty by default
 /// - `sudo` - sudo msg type, empty by default
+/// - `ibc_packet` - the type of the packets the contract sends and receives over IBC, empty by
+///   default
+/// - `ibc_ack` - the type of the acknowledgements of those packets, empty by default
+/// - `reply` - the type of the payloads the contract attaches to submessages and reads in
+///   `reply`, empty by default
+/// - `entry_points` - the message types of further entry points by name, e.g.
+///   `{ token_factory_hook: HookMsg, end_block: EndBlockMsg }` for chain-specific hooks,
+///   empty by default
+/// - `events` - a type implementing [`ContractEvents`] that declares the events the contract
+///   emits, empty by default
+/// - `state` - a type implementing [`ContractState`] that declares the storage layout of the
+///   contract, empty by default
+///
+/// # Output
+/// The whole API is written to a single file `schema/<name>.json`, which code generators can
+/// consume without knowing about the individual message types. It is a JSON object with the
+/// fields `contract_name`, `contract_version`, `idl_version` (see [`IDL_VERSION`]),
+/// `instantiate`, `execute`, `query`, `migrate`, `sudo` (each a JSON schema or `null`) and
+/// `responses` (a map from query names to JSON schemas or `null`). Contracts with IBC entry
+/// points can add `ibc_packet` and `ibc_ack` (each a JSON schema), contracts with typed replies
+/// or further entry points can add `reply` (a JSON schema) and `entry_points` (a map from
+/// entry point names to JSON schemas titled e.g. `EndBlockMsg` for `end_block`), and contracts
+/// declaring their events can add `events` (a list of objects with `type`, `description` and
+/// `attributes`, which have a `key`, `description` and `required`). Contracts declaring their
+/// storage layout can add `state` (a list of objects with `namespace`, `description`, `keys`
+/// and a JSON schema of the stored `value`). Those are omitted if not set. Other JSON files in
+/// `schema/` are removed, so schemas from older versions do not linger around.
+///
+/// # Command line flags
+/// - `--check` - compare the generated files with the existing ones instead of writing them,
+///   see [`is_check_mode`]
+/// - `--diff <path>` - compare the generated API with an older version in `<path>` instead of
+///   writing files and fail on breaking changes, see [`diff_mode_base`]
+/// - `--dialect <name>` - the JSON Schema dialect of the schemas, `draft-07` (default) or
+///   `2020-12`, see [`dialect_mode`]
+/// - `--go` - also generate Go type definitions for all messages and responses in
+///   `schema/<name>.go`, see [`is_go_mode`]
+/// - `--openapi` - also generate an OpenAPI document with one operation per query in
+///   `schema/<name>.openapi.json`, see [`is_openapi_mode`]
+/// - `--proto` - also generate (experimental) Protocol Buffers definitions for all messages and
+///   responses in `schema/<name>.proto`, see [`is_proto_mode`]
+/// - `--proto-descriptor` - also generate those definitions as a binary `FileDescriptorSet`
+///   in `schema/<name>.binpb`, see [`is_proto_descriptor_mode`]
+/// - `--python` - also generate pydantic models for all messages and responses in
+///   `schema/<module>.py`, see [`is_python_mode`] and [`python_module_name`]
+/// - `--ts` - also generate TypeScript type definitions and typed query and execute clients
+///   in `schema/<name>.ts`, see [`is_ts_mode`]
+///
+/// Every generated file names the contract release it belongs to, such that registries can
+/// match schemas to releases: the API file in `contract_name` and `contract_version`, the
+/// OpenAPI document in `info`, and the code files in their first line, e.g.
+/// `// Code generated by cosmwasm-schema for cw20 1.0.0. DO NOT EDIT.`
 ///
 /// # Example
 /// ```
@@ -91,4 +335,40 @@ pub use cosmwasm_schema_derive::generate_api;
 /// };
 /// ```
 pub use cosmwasm_schema_derive::write_api;
+/// Derives [`Validate`](trait@Validate), which checks the `#[schema(...)]` constraints of the
+/// fields (see [`cw_serde`]) with a call of `validate()`, e.g. at the beginning of an entry
+/// point. Ranges and lengths are checked, patterns are not. Fields whose type implements
+/// `Validate` itself are checked with `#[schema(nested)]`. The errors refer to the fields by
+/// their path in the JSON message.
+///
+/// ```
+/// use cosmwasm_schema::{cw_serde, Validate};
+///
+/// #[cw_serde]
+/// #[derive(Validate)]
+/// pub struct Order {
+///     #[schema(min = 1, max = 100)]
+///     quantity: u32,
+/// }
+///
+/// #[cw_serde]
+/// #[derive(Validate)]
+/// pub enum ExecuteMsg {
+///     PlaceOrders {
+///         #[schema(min_length = 1, nested)]
+///         orders: Vec<Order>,
+///     },
+/// }
+///
+/// let msg = ExecuteMsg::PlaceOrders { orders: vec![Order { quantity: 0 }] };
+/// assert_eq!(
+///     msg.validate().unwrap_err().to_string(),
+///     "place_orders.orders.0.quantity: invalid value 0, expected a value between 1 and 100",
+/// );
+/// ```
+pub use cosmwasm_schema_derive::Validate;
 pub use schemars::schema_for;
+
+// Re-exported for `#[cw_serde(crate = "...")]`, which refers to serde and schemars through this crate
+pub use schemars;
+pub use serde;
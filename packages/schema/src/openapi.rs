@@ -0,0 +1,397 @@
+//! Generation of an [OpenAPI](https://spec.openapis.org/oas/v3.1.0) document describing the
+//! queries of a contract.
+//!
+//! Every query becomes a `POST` operation on the path `/<query name>`, e.g. `/balance`. The
+//! request body is the query message as sent to the contract, e.g. `{"balance":{...}}`, and
+//! the response is the query response. This matches a REST gateway that forwards the body as
+//! a smart query to a contract, so the paths are relative to the contract, whose URL the
+//! gateway defines in `servers`. Query variants marked with `#[deprecated]` become deprecated
+//! operations.
+//!
+//! All types are in `components/schemas`. OpenAPI 3.1 uses JSON Schema draft 2020-12, so the
+//! schemas are converted accordingly.
+
+use std::collections::BTreeMap;
+
+use schemars::schema::{Metadata, RootSchema, Schema, SchemaObject};
+use schemars::visit::{visit_schema_object, Visitor};
+use schemars::Map;
+use serde_json::{json, Value};
+
+use crate::codegen::{description, is_named};
+use crate::dialect::subschema_to_2020_12;
+use crate::query_response::{enum_tag, tag_value};
+
+/// The command line flag that makes `write_api!` also export an OpenAPI document
+pub const OPENAPI_FLAG: &str = "--openapi";
+
+const OPENAPI_VERSION: &str = "3.1.0";
+const DEFINITIONS_PREFIX: &str = "#/definitions/";
+const COMPONENTS_PREFIX: &str = "#/components/schemas/";
+
+/// Returns true if the current process was started with [`--openapi`](OPENAPI_FLAG),
+/// e.g. via `cargo schema -- --openapi`.
+pub fn is_openapi_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == OPENAPI_FLAG)
+}
+
+/// Renders an OpenAPI document with one operation per query
+pub(crate) fn render_openapi(
+    contract_name: &str,
+    contract_version: &str,
+    query: Option<&RootSchema>,
+    responses: Option<&BTreeMap<String, RootSchema>>,
+) -> Value {
+    let mut components = Components::default();
+    let mut paths = serde_json::Map::new();
+
+    let queries = match query {
+        Some(query) => {
+            let mapping = components.add_definitions(query);
+            let mut queries = Vec::new();
+            collect_queries(&query.schema, &query.definitions, &mut queries);
+            queries
+                .into_iter()
+                .map(|(name, mut variant)| {
+                    rewrite_references(&mut variant, &mapping);
+                    (name, variant)
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    for (name, variant) in queries {
+        let mut operation = serde_json::Map::new();
+        operation.insert("operationId".to_string(), json!(name));
+        if let Some(description) = description(&variant) {
+            operation.insert("description".to_string(), json!(description));
+        }
+        if matches!(&variant.metadata, Some(metadata) if metadata.deprecated) {
+            operation.insert("deprecated".to_string(), json!(true));
+        }
+        operation.insert(
+            "requestBody".to_string(),
+            json!({
+                "required": true,
+                "content": { "application/json": { "schema": to_2020_12(&variant) } }
+            }),
+        );
+        let response = match responses.and_then(|responses| responses.get(&name)) {
+            Some(root) => {
+                let response_description = description(&root.schema)
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| format!("The response of the {} query", name));
+                json!({
+                    "description": response_description,
+                    "content": {
+                        "application/json": { "schema": components.add_response(root) }
+                    }
+                })
+            }
+            None => json!({ "description": format!("The response of the {} query", name) }),
+        };
+        operation.insert("responses".to_string(), json!({ "200": response }));
+        paths.insert(
+            format!("/{}", name),
+            json!({ "post": Value::Object(operation) }),
+        );
+    }
+
+    let schemas: serde_json::Map<String, Value> = components
+        .schemas
+        .iter()
+        .map(|(name, schema)| (name.clone(), to_2020_12(schema)))
+        .collect();
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": { "title": contract_name, "version": contract_version },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+/// Collects the variants of a query enum with their names. Nested query enums are
+/// `#[serde(untagged)]` and refer to the child enums in an `anyOf`.
+fn collect_queries<'a>(
+    schema: &'a SchemaObject,
+    definitions: &'a Map<String, Schema>,
+    queries: &mut Vec<(String, SchemaObject)>,
+) {
+    let subschemas = match &schema.subschemas {
+        Some(subschemas) => subschemas,
+        None => return,
+    };
+    if let Some(any_of) = &subschemas.any_of {
+        for child in any_of {
+            let child = match child {
+                Schema::Object(SchemaObject {
+                    reference: Some(reference),
+                    ..
+                }) => reference
+                    .strip_prefix(DEFINITIONS_PREFIX)
+                    .and_then(|name| definitions.get(name)),
+                _ => None,
+            };
+            if let Some(Schema::Object(child)) = child {
+                collect_queries(child, definitions, queries);
+            }
+        }
+        return;
+    }
+
+    let variants: Vec<&SchemaObject> = subschemas
+        .one_of
+        .iter()
+        .flatten()
+        .filter_map(|variant| match variant {
+            Schema::Object(variant) => Some(variant),
+            Schema::Bool(_) => None,
+        })
+        .collect();
+    let tag = enum_tag(&variants);
+    for variant in variants {
+        let name = match &tag {
+            Some(tag) => tag_value(variant, tag),
+            None => variant_name(variant),
+        };
+        if let Some(name) = name {
+            queries.push((name, variant.clone()));
+        }
+    }
+}
+
+/// The name of an externally tagged variant, i.e. the only property of an object or the
+/// value of a unit variant
+fn variant_name(variant: &SchemaObject) -> Option<String> {
+    if let Some(object) = &variant.object {
+        return object.required.iter().next().cloned();
+    }
+    match variant.enum_values.as_deref() {
+        Some([value]) => value.as_str().map(ToString::to_string),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Components {
+    /// The component schemas with references to other components
+    schemas: BTreeMap<String, Schema>,
+    /// The schemas the components were created from, to detect different types of the same name
+    originals: BTreeMap<String, Schema>,
+}
+
+impl Components {
+    /// Adds the definitions of a root schema and returns the component names of the
+    /// definitions. Types with the same name but a different definition get a number suffix.
+    fn add_definitions(&mut self, root: &RootSchema) -> BTreeMap<String, String> {
+        let mut mapping = BTreeMap::new();
+        for (name, definition) in &root.definitions {
+            mapping.insert(name.clone(), self.reserve(name, definition));
+        }
+        for (name, definition) in &root.definitions {
+            let component = &mapping[name];
+            if !self.schemas.contains_key(component) {
+                let mut definition = definition.clone();
+                if let Schema::Object(definition) = &mut definition {
+                    rewrite_references(definition, &mapping);
+                }
+                self.schemas.insert(component.clone(), definition);
+            }
+        }
+        mapping
+    }
+
+    /// Returns the name of the component for the given definition
+    fn reserve(&mut self, name: &str, definition: &Schema) -> String {
+        let mut component = name.to_string();
+        let mut count = 1;
+        loop {
+            match self.originals.get(&component) {
+                Some(original) if original == definition => return component,
+                Some(_) => {
+                    count += 1;
+                    component = format!("{}{}", name, count);
+                }
+                None => {
+                    self.originals.insert(component.clone(), definition.clone());
+                    return component;
+                }
+            }
+        }
+    }
+
+    /// Adds a response schema and returns the schema to use in the response, which is
+    /// a reference for named types like structs and enums
+    fn add_response(&mut self, root: &RootSchema) -> Value {
+        let mapping = self.add_definitions(root);
+        let mut original = root.schema.clone();
+        let title = original
+            .metadata
+            .as_mut()
+            .and_then(|metadata| metadata.title.take());
+        if original.metadata.as_deref() == Some(&Metadata::default()) {
+            original.metadata = None;
+        }
+        let mut schema = original.clone();
+        rewrite_references(&mut schema, &mapping);
+
+        match title {
+            // The title is removed since definitions have none, so the same type
+            // in the definitions of other schemas maps to the same component
+            Some(title) if is_named(&schema) => {
+                let component = self.reserve(&title, &Schema::Object(original));
+                self.schemas
+                    .entry(component.clone())
+                    .or_insert(Schema::Object(schema));
+                json!({ "$ref": format!("{}{}", COMPONENTS_PREFIX, component) })
+            }
+            _ => to_2020_12(&schema),
+        }
+    }
+}
+
+fn rewrite_references(schema: &mut SchemaObject, mapping: &BTreeMap<String, String>) {
+    ReferenceRewriter { mapping }.visit_schema_object(schema);
+}
+
+/// Replaces references to definitions by references to components
+struct ReferenceRewriter<'a> {
+    mapping: &'a BTreeMap<String, String>,
+}
+
+impl Visitor for ReferenceRewriter<'_> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(reference) = &mut schema.reference {
+            if let Some(component) = reference
+                .strip_prefix(DEFINITIONS_PREFIX)
+                .and_then(|name| self.mapping.get(name))
+            {
+                *reference = format!("{}{}", COMPONENTS_PREFIX, component);
+            }
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+fn to_2020_12(schema: &impl serde::Serialize) -> Value {
+    let mut value = serde_json::to_value(schema).unwrap();
+    subschema_to_2020_12(&mut value);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Coin {
+        denom: String,
+        amount: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum QueryMsg {
+        /// Returns the balance of an address
+        Balance {
+            address: String,
+        },
+        #[deprecated]
+        Pair {},
+        Other {},
+        Count,
+    }
+
+    mod other {
+        use schemars::JsonSchema;
+
+        /// The other coin
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        pub struct Coin {
+            pub id: u32,
+        }
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        pub struct OtherResponse {
+            pub coin: Coin,
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct PairResponse {
+        coins: (Coin, Coin),
+    }
+
+    #[test]
+    fn render_openapi_works() {
+        let query = schema_for!(QueryMsg);
+        let mut responses = BTreeMap::new();
+        responses.insert("balance".to_string(), schema_for!(Coin));
+        responses.insert("pair".to_string(), schema_for!(PairResponse));
+        responses.insert("other".to_string(), schema_for!(other::OtherResponse));
+        responses.insert("count".to_string(), schema_for!(u32));
+
+        let openapi = render_openapi("token", "1.2.3", Some(&query), Some(&responses));
+        assert_eq!(openapi["openapi"], "3.1.0");
+        assert_eq!(
+            openapi["info"],
+            json!({ "title": "token", "version": "1.2.3" })
+        );
+
+        let balance = &openapi["paths"]["/balance"]["post"];
+        assert_eq!(balance["operationId"], "balance");
+        assert_eq!(balance["description"], "Returns the balance of an address");
+        let request = &balance["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(request["required"], json!(["balance"]));
+        assert_eq!(
+            balance["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/Coin" })
+        );
+
+        assert_eq!(balance.get("deprecated"), None);
+        assert_eq!(openapi["paths"]["/pair"]["post"]["deprecated"], true);
+
+        let count = &openapi["paths"]["/count"]["post"];
+        assert_eq!(
+            count["requestBody"]["content"]["application/json"]["schema"]["enum"],
+            json!(["count"])
+        );
+        assert_eq!(
+            count["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "type": "integer", "format": "uint32", "minimum": 0.0 })
+        );
+
+        // the two types called `Coin` become different components
+        let schemas = &openapi["components"]["schemas"];
+        assert_eq!(
+            schemas.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["Coin", "Coin2", "OtherResponse", "PairResponse"]
+        );
+        assert_eq!(schemas["Coin2"]["description"], "The other coin");
+        assert_eq!(
+            schemas["OtherResponse"]["properties"]["coin"],
+            json!({ "$ref": "#/components/schemas/Coin2" })
+        );
+        assert_eq!(
+            schemas["PairResponse"]["properties"]["coins"]["prefixItems"],
+            json!([
+                { "$ref": "#/components/schemas/Coin" },
+                { "$ref": "#/components/schemas/Coin" }
+            ])
+        );
+    }
+
+    #[test]
+    fn render_openapi_works_without_queries() {
+        let openapi = render_openapi("token", "1.2.3", None, None);
+        assert_eq!(openapi["paths"], json!({}));
+        assert_eq!(openapi["components"], json!({ "schemas": {} }));
+    }
+}
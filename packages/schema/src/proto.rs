@@ -0,0 +1,1164 @@
+//! Experimental generation of Protocol Buffers definitions from a contract's API.
+//!
+//! The generated messages are designed such that their
+//! [proto3 JSON mapping](https://protobuf.dev/programming-guides/proto3/#json) matches
+//! the JSON the contract expects, so that e.g. gRPC gateways can expose contract queries
+//! with strongly-typed endpoints and forward them as JSON.
+//!
+//! Mapping rules:
+//!
+//! - Objects become messages. Fields are numbered in alphabetical order and use
+//!   `json_name` to keep the serde names. Optional fields use `optional`.
+//! - Enums with data (`oneOf` of single-property objects) become messages with a `oneof`
+//!   with one field per variant. This matches serde's externally tagged representation.
+//! - Enums without data become `string` fields, since proto3 JSON omits enum fields with
+//!   the default (first) value. The allowed values are listed in a comment.
+//! - 8 and 16 bit integers are widened to 32 bit. Note that proto3 JSON encodes 64 bit
+//!   integers as strings, which `u64`/`i64` fields do not accept. Use `Uint64`/`Int64`
+//!   in messages to avoid this.
+//! - `Option<Vec<T>>` is mapped like `Vec<T>`, i.e. `null` and `[]` cannot be distinguished.
+//! - Everything without a proto equivalent (128 bit integers, tuples, nested lists,
+//!   enums mixing unit and data variants, arbitrary JSON) becomes `google.protobuf.Value`.
+//! - Fields and data variants marked with `#[deprecated]` get the `deprecated` option.
+//! - If the API has queries with responses, a `Query` service with one method per query
+//!   variant is added. Requests are the variant contents, responses without a message
+//!   type are `google.protobuf.Value`.
+//!
+//! The same definitions can be encoded as a binary `FileDescriptorSet` for tools that load
+//! types at runtime, see [`is_proto_descriptor_mode`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+
+use crate::casing::to_pascal_case;
+use crate::codegen::{
+    comment, description, generated_notice, is_deprecated, is_named, is_struct,
+    property_schema_description, single_property, string_values, variants, with_comment, Generator,
+    TypeNames,
+};
+
+/// The command line flag that makes `write_api!` also export Protocol Buffers definitions
+pub const PROTO_FLAG: &str = "--proto";
+
+const VALUE: &str = "google.protobuf.Value";
+
+/// The command line flag that makes `write_api!` also export the Protocol Buffers
+/// definitions as a binary `FileDescriptorSet`
+pub const PROTO_DESCRIPTOR_FLAG: &str = "--proto-descriptor";
+
+/// Returns true if the current process was started with [`--proto`](PROTO_FLAG),
+/// e.g. via `cargo schema -- --proto`.
+pub fn is_proto_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == PROTO_FLAG)
+}
+
+/// Returns true if the current process was started with
+/// [`--proto-descriptor`](PROTO_DESCRIPTOR_FLAG), e.g. via `cargo schema -- --proto-descriptor`.
+///
+/// In this mode `write_api!` also writes the definitions of [`--proto`](PROTO_FLAG) as a
+/// binary `google.protobuf.FileDescriptorSet`, like `protoc --descriptor_set_out` would.
+/// This allows tools to load the types at runtime without compiling the proto file.
+/// The set does not contain comments and, like without `--include_imports`, the imported
+/// `google/protobuf/struct.proto`.
+pub fn is_proto_descriptor_mode() -> bool {
+    std::env::args()
+        .skip(1)
+        .any(|arg| arg == PROTO_DESCRIPTOR_FLAG)
+}
+
+/// Returns a valid proto package name for the given contract name, e.g. `crypto_verify`
+/// for `crypto-verify`.
+pub fn proto_package_name(contract_name: &str) -> String {
+    let name: String = contract_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("contract_{}", name)
+    }
+}
+
+/// Renders proto definitions for the given root schemas.
+///
+/// `messages` are the top level messages with their message names. For `responses`
+/// the message name is taken from the schema title.
+pub(crate) fn render_proto<'a>(
+    package: &str,
+    contract_name: &str,
+    contract_version: &str,
+    messages: impl IntoIterator<Item = (&'a str, &'a RootSchema)>,
+    responses: Option<&'a BTreeMap<String, RootSchema>>,
+) -> String {
+    let items = generate(messages, responses);
+    let body: Vec<String> = items.iter().map(Item::render).collect();
+    let body = body.join("\n");
+    let mut out = format!(
+        "// {}\n\
+         // Experimental: see the cosmwasm-schema documentation for the mapping rules.\n\n\
+         syntax = \"proto3\";\n\npackage {};\n",
+        generated_notice(contract_name, contract_version),
+        package
+    );
+    if body.contains(VALUE) {
+        out.push_str("\nimport \"google/protobuf/struct.proto\";\n");
+    }
+    if !body.is_empty() {
+        out.push('\n');
+        out.push_str(&body);
+    }
+    out
+}
+
+/// Encodes the definitions [`render_proto`] renders as a `google.protobuf.FileDescriptorSet`
+/// with a single file of the given name.
+pub(crate) fn render_proto_descriptor_set<'a>(
+    package: &str,
+    file_name: &str,
+    messages: impl IntoIterator<Item = (&'a str, &'a RootSchema)>,
+    responses: Option<&'a BTreeMap<String, RootSchema>>,
+) -> Vec<u8> {
+    let items = generate(messages, responses);
+    let mut file = Encoder::default();
+    file.string(1, file_name);
+    file.string(2, package);
+    if items.iter().any(Item::uses_value) {
+        file.string(3, "google/protobuf/struct.proto");
+    }
+    for item in &items {
+        match item {
+            Item::Message(message) => file.message(4, message.encode(package)),
+            Item::Service(service) => file.message(6, service.encode(package)),
+        }
+    }
+    file.string(12, "proto3");
+
+    let mut set = Encoder::default();
+    set.message(1, file);
+    set.buf
+}
+
+fn generate<'a>(
+    messages: impl IntoIterator<Item = (&'a str, &'a RootSchema)>,
+    responses: Option<&'a BTreeMap<String, RootSchema>>,
+) -> Vec<Item> {
+    let mut gen = ProtoGenerator::default();
+    let mut query = None;
+    for (name, root) in messages {
+        let message = gen.root(name, root);
+        if name == "QueryMsg" {
+            query = message.map(|message| (message, root));
+        }
+    }
+    let mut response_types = BTreeMap::new();
+    for (variant, root) in responses.into_iter().flatten() {
+        let ty = if is_named(&root.schema) {
+            let name = root
+                .schema
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.title.clone())
+                .unwrap_or_else(|| format!("{}_response", variant));
+            gen.root(&name, root).unwrap_or_else(|| VALUE.to_string())
+        } else {
+            VALUE.to_string()
+        };
+        response_types.insert(variant.as_str(), ty);
+    }
+    if let (Some((message, root)), false) = (query, response_types.is_empty()) {
+        gen.service(&message, &root.schema, &response_types);
+    }
+    gen.items
+}
+
+/// The type of a message field
+struct FieldType {
+    /// The scalar or message type, or the value type of a map
+    ty: String,
+    repeated: bool,
+    optional: bool,
+    /// A `map<string, ty>`
+    map: bool,
+}
+
+impl FieldType {
+    fn scalar(ty: impl Into<String>) -> Self {
+        FieldType {
+            ty: ty.into(),
+            repeated: false,
+            optional: false,
+            map: false,
+        }
+    }
+
+    /// Returns true if the type has a label or is a map, which is not allowed in oneofs,
+    /// lists and maps
+    fn is_labeled(&self) -> bool {
+        self.repeated || self.optional || self.map
+    }
+
+    fn make_optional(self) -> Self {
+        // repeated fields and maps cannot be optional and `google.protobuf.Value` can hold null
+        if self.repeated || self.map || self.ty == VALUE {
+            self
+        } else {
+            FieldType {
+                optional: true,
+                ..self
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let label = if self.repeated {
+            "repeated "
+        } else if self.optional {
+            "optional "
+        } else {
+            ""
+        };
+        if self.map {
+            format!("{}map<string, {}>", label, self.ty)
+        } else {
+            format!("{}{}", label, self.ty)
+        }
+    }
+}
+
+/// A generated message or service
+enum Item {
+    Message(Message),
+    Service(Service),
+}
+
+impl Item {
+    fn render(&self) -> String {
+        match self {
+            Item::Message(message) => message.render(),
+            Item::Service(service) => service.render(),
+        }
+    }
+
+    fn uses_value(&self) -> bool {
+        match self {
+            Item::Message(message) => message.fields.iter().any(|field| field.ty.ty == VALUE),
+            Item::Service(service) => service
+                .methods
+                .iter()
+                .any(|method| method.request == VALUE || method.response == VALUE),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Message {
+    name: String,
+    description: Option<String>,
+    /// The name of the oneof containing all fields, which is used for enums with data
+    oneof: Option<String>,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    /// The name of the field, which is also its JSON name
+    name: String,
+    number: usize,
+    ty: FieldType,
+    deprecated: bool,
+    comment: Option<String>,
+}
+
+impl Field {
+    fn render(&self, indent: &str) -> String {
+        let mut out = self
+            .comment
+            .as_deref()
+            .map(|text| comment(text, indent))
+            .unwrap_or_default();
+        let deprecated = if self.deprecated {
+            ", deprecated = true"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "{}{} {} = {} [json_name = \"{}\"{}];\n",
+            indent,
+            self.ty.render(),
+            self.name,
+            self.number,
+            self.name,
+            deprecated
+        ));
+        out
+    }
+}
+
+impl Message {
+    fn render(&self) -> String {
+        let mut fields = String::new();
+        match &self.oneof {
+            Some(oneof) => {
+                fields.push_str(&format!("  oneof {} {{\n", oneof));
+                for field in &self.fields {
+                    fields.push_str(&field.render("    "));
+                }
+                fields.push_str("  }\n");
+            }
+            None => {
+                for field in &self.fields {
+                    fields.push_str(&field.render("  "));
+                }
+            }
+        }
+        let code = if fields.is_empty() {
+            format!("message {} {{}}\n", self.name)
+        } else {
+            format!("message {} {{\n{}}}\n", self.name, fields)
+        };
+        with_comment(self.description.as_deref(), code)
+    }
+}
+
+struct Service {
+    query_message: String,
+    methods: Vec<Method>,
+}
+
+struct Method {
+    name: String,
+    request: String,
+    response: String,
+    description: Option<String>,
+}
+
+impl Service {
+    fn render(&self) -> String {
+        let mut methods = String::new();
+        for method in &self.methods {
+            if let Some(description) = &method.description {
+                methods.push_str(&comment(description, "  "));
+            }
+            methods.push_str(&format!(
+                "  rpc {}({}) returns ({});\n",
+                method.name, method.request, method.response
+            ));
+        }
+        format!(
+            "// Each method sends the request as the content of the {} variant with the\n\
+             // snake_case name of the method\n\
+             service Query {{\n{}}}\n",
+            self.query_message, methods
+        )
+    }
+}
+
+#[derive(Default)]
+struct ProtoGenerator<'a> {
+    /// The messages and services in output order
+    items: Vec<Item>,
+    type_names: TypeNames<'a>,
+    /// The field types of the variants of enum messages by message and variant name
+    cases: HashMap<(String, String), String>,
+}
+
+impl<'a> Generator<'a> for ProtoGenerator<'a> {
+    type Type = FieldType;
+
+    fn type_names(&mut self) -> &mut TypeNames<'a> {
+        &mut self.type_names
+    }
+
+    fn raw(&mut self) -> FieldType {
+        FieldType::scalar(VALUE)
+    }
+
+    fn optional(&mut self, ty: FieldType) -> FieldType {
+        ty.make_optional()
+    }
+
+    fn named(&mut self, name: &str) -> FieldType {
+        FieldType::scalar(name)
+    }
+
+    /// Structs and enums with data are messages. Unit enums become strings.
+    fn is_named(&self, schema: &SchemaObject) -> bool {
+        is_message(schema)
+    }
+
+    /// Definitions like `Uint128` or `Binary` are used inline
+    fn is_defined(&self, definition: &SchemaObject) -> bool {
+        is_message(definition)
+    }
+
+    /// Generates a message. The schema must be a struct or an enum with data.
+    fn define(&mut self, name: &str, schema: &SchemaObject) {
+        // Reserve the position of this message before the messages it uses
+        let index = self.items.len();
+        self.items.push(Item::Message(Message::default()));
+
+        let mut message = Message {
+            name: name.to_string(),
+            description: description(schema).map(ToString::to_string),
+            ..Message::default()
+        };
+        if let Some(variants) = variants(schema) {
+            let cases: Vec<(&str, &Schema, &SchemaObject)> = variants
+                .iter()
+                .filter_map(|variant| match variant {
+                    Schema::Object(variant) => single_property(variant)
+                        .map(|(property, content)| (property, content, variant)),
+                    Schema::Bool(_) => None,
+                })
+                .collect();
+            let oneof = if cases.iter().any(|(property, ..)| *property == "variant") {
+                "variant_"
+            } else {
+                "variant"
+            };
+            message.oneof = Some(oneof.to_string());
+            for (number, (property, content, variant)) in cases.into_iter().enumerate() {
+                let context = format!("{}{}", name, to_pascal_case(property));
+                let mut ty = self.type_of(content, &context);
+                if ty.is_labeled() {
+                    // oneof fields cannot have labels
+                    ty = FieldType::scalar(VALUE);
+                }
+                self.cases
+                    .insert((name.to_string(), property.to_string()), ty.ty.clone());
+                message.fields.push(Field {
+                    name: property.to_string(),
+                    number: number + 1,
+                    ty,
+                    deprecated: matches!(&variant.metadata, Some(metadata) if metadata.deprecated),
+                    comment: description(variant).map(ToString::to_string),
+                });
+            }
+        } else if let Some(object) = &schema.object {
+            for (number, (property, property_schema)) in object.properties.iter().enumerate() {
+                let context = format!("{}{}", name, to_pascal_case(property));
+                let mut ty = self.type_of(property_schema, &context);
+                if !object.required.contains(property) {
+                    ty = ty.make_optional();
+                }
+                let mut text = property_schema_description(property_schema)
+                    .unwrap_or_default()
+                    .to_string();
+                if let Some(values) = property_enum_values(property_schema, self.type_names.scope())
+                {
+                    if !text.is_empty() {
+                        text.push_str("\n\n");
+                    }
+                    text.push_str(&format!("One of: {}", values.join(", ")));
+                }
+                message.fields.push(Field {
+                    name: property.to_string(),
+                    number: number + 1,
+                    ty,
+                    deprecated: is_deprecated(property_schema),
+                    comment: Some(text).filter(|text| !text.is_empty()),
+                });
+            }
+        }
+        self.items[index] = Item::Message(message);
+    }
+
+    fn unnamed(
+        &mut self,
+        instance_type: InstanceType,
+        schema: &SchemaObject,
+        context: &str,
+    ) -> FieldType {
+        match instance_type {
+            InstanceType::Boolean => FieldType::scalar("bool"),
+            InstanceType::String => FieldType::scalar("string"),
+            InstanceType::Number => FieldType::scalar("double"),
+            InstanceType::Integer => FieldType::scalar(integer_type(schema.format.as_deref())),
+            InstanceType::Array => match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+                Some(SingleOrVec::Single(items)) => {
+                    let item = self.type_of(items, &format!("{}Item", context));
+                    if item.is_labeled() {
+                        FieldType::scalar(VALUE)
+                    } else {
+                        FieldType {
+                            repeated: true,
+                            ..item
+                        }
+                    }
+                }
+                _ => FieldType::scalar(VALUE),
+            },
+            InstanceType::Object => match schema
+                .object
+                .as_ref()
+                .and_then(|o| o.additional_properties.as_ref())
+            {
+                Some(values) => {
+                    let value = self.type_of(values, &format!("{}Value", context));
+                    if value.is_labeled() {
+                        FieldType::scalar(VALUE)
+                    } else {
+                        FieldType {
+                            map: true,
+                            ..FieldType::scalar(value.ty)
+                        }
+                    }
+                }
+                None => FieldType::scalar(VALUE),
+            },
+            InstanceType::Null => FieldType::scalar(VALUE),
+        }
+    }
+}
+
+impl ProtoGenerator<'_> {
+    /// Adds a `Query` service with one method per query variant that has a response
+    fn service(
+        &mut self,
+        query_message: &str,
+        query: &SchemaObject,
+        responses: &BTreeMap<&str, String>,
+    ) {
+        let mut methods = Vec::new();
+        for variant in variants(query).into_iter().flatten() {
+            let variant = match variant {
+                Schema::Object(variant) => variant,
+                Schema::Bool(_) => continue,
+            };
+            // The query message is an enum with data variants only, see `is_message`
+            let property = match single_property(variant) {
+                Some((property, _)) => property,
+                None => continue,
+            };
+            if let Some(response) = responses.get(property) {
+                let request = self
+                    .cases
+                    .get(&(query_message.to_string(), property.to_string()))
+                    .cloned()
+                    .unwrap_or_else(|| VALUE.to_string());
+                methods.push(Method {
+                    name: to_pascal_case(property),
+                    request,
+                    response: response.clone(),
+                    description: description(variant).map(ToString::to_string),
+                });
+            }
+        }
+        if !methods.is_empty() {
+            self.items.push(Item::Service(Service {
+                query_message: query_message.to_string(),
+                methods,
+            }));
+        }
+    }
+}
+
+/// Returns true if the schema becomes a message, i.e. it is a struct or an enum
+/// consisting of data variants only
+fn is_message(schema: &SchemaObject) -> bool {
+    if let Some(variants) = variants(schema) {
+        return variants.iter().all(|variant| match variant {
+            Schema::Object(variant) => single_property(variant).is_some(),
+            Schema::Bool(_) => false,
+        });
+    }
+    string_values(schema).is_none() && is_struct(schema)
+}
+
+/// The allowed values of a property that is mapped to a `string` because it is an enum
+fn property_enum_values(
+    schema: &Schema,
+    scope: Option<&schemars::Map<String, Schema>>,
+) -> Option<Vec<String>> {
+    let mut schema = match schema {
+        Schema::Object(schema) => schema,
+        Schema::Bool(_) => return None,
+    };
+    if let Some(all_of) = schema.subschemas.as_ref().and_then(|s| s.all_of.as_ref()) {
+        if let [Schema::Object(inner)] = &all_of[..] {
+            schema = inner;
+        }
+    }
+    if let Some(definition) = schema
+        .reference
+        .as_ref()
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+    {
+        schema = match scope?.get(definition)? {
+            Schema::Object(schema) => schema,
+            Schema::Bool(_) => return None,
+        };
+    }
+    string_values(schema).map(|values| values.into_iter().map(|v| format!("\"{}\"", v)).collect())
+}
+
+fn integer_type(format: Option<&str>) -> &'static str {
+    match format {
+        Some("int8") | Some("int16") | Some("int32") => "int32",
+        Some("uint8") | Some("uint16") | Some("uint32") => "uint32",
+        Some("uint64") | Some("uint") => "uint64",
+        Some("int128") | Some("uint128") => VALUE,
+        _ => "int64",
+    }
+}
+
+/// `TYPE_MESSAGE` of `google.protobuf.FieldDescriptorProto.Type`
+const TYPE_MESSAGE: u64 = 11;
+/// `LABEL_OPTIONAL` of `google.protobuf.FieldDescriptorProto.Label`, which is also used for
+/// fields without a label in proto3
+const LABEL_OPTIONAL: u64 = 1;
+/// `LABEL_REPEATED` of `google.protobuf.FieldDescriptorProto.Label`
+const LABEL_REPEATED: u64 = 3;
+
+/// The `google.protobuf.FieldDescriptorProto.Type` of a scalar type
+fn scalar_type(ty: &str) -> Option<u64> {
+    match ty {
+        "double" => Some(1),
+        "int64" => Some(3),
+        "uint64" => Some(4),
+        "int32" => Some(5),
+        "bool" => Some(8),
+        "string" => Some(9),
+        "uint32" => Some(13),
+        _ => None,
+    }
+}
+
+/// The fully qualified name of a message of the package, e.g. `.example.Coin`
+fn qualified_name(package: &str, message: &str) -> String {
+    if message == VALUE {
+        format!(".{}", VALUE)
+    } else {
+        format!(".{}.{}", package, message)
+    }
+}
+
+/// The name of the nested message protoc generates for a map field, e.g. `BalancesByDenomEntry`
+/// for `balances_by_denom`
+fn map_entry_name(field: &str) -> String {
+    let mut name = String::with_capacity(field.len() + 5);
+    let mut capitalize = true;
+    for c in field.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            name.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name + "Entry"
+}
+
+/// Encodes a `google.protobuf.FieldDescriptorProto` without label and oneof
+fn encode_field(package: &str, name: &str, number: usize, ty: &str) -> Encoder {
+    let mut field = Encoder::default();
+    field.string(1, name);
+    field.uint(3, number as u64);
+    match scalar_type(ty) {
+        Some(scalar) => field.uint(5, scalar),
+        None => {
+            field.uint(5, TYPE_MESSAGE);
+            field.string(6, &qualified_name(package, ty));
+        }
+    }
+    field.string(10, name);
+    field
+}
+
+impl Message {
+    /// Encodes the message as a `google.protobuf.DescriptorProto`
+    fn encode(&self, package: &str) -> Encoder {
+        let mut message = Encoder::default();
+        message.string(1, &self.name);
+        let mut oneofs: Vec<String> = self.oneof.iter().cloned().collect();
+        for field in &self.fields {
+            let mut encoded = if field.ty.map {
+                let entry_name = map_entry_name(&field.name);
+                let mut entry = Encoder::default();
+                entry.string(1, &entry_name);
+                for (name, number, ty) in [("key", 1, "string"), ("value", 2, &field.ty.ty)] {
+                    let mut entry_field = encode_field(package, name, number, ty);
+                    entry_field.uint(4, LABEL_OPTIONAL);
+                    entry.message(2, entry_field);
+                }
+                let mut options = Encoder::default();
+                options.bool(7, true);
+                entry.message(7, options);
+                message.message(3, entry);
+                let entry_type = format!("{}.{}", self.name, entry_name);
+                encode_field(package, &field.name, field.number, &entry_type)
+            } else {
+                encode_field(package, &field.name, field.number, &field.ty.ty)
+            };
+            let repeated = field.ty.repeated || field.ty.map;
+            encoded.uint(
+                4,
+                if repeated {
+                    LABEL_REPEATED
+                } else {
+                    LABEL_OPTIONAL
+                },
+            );
+            if self.oneof.is_some() {
+                encoded.uint(9, 0);
+            } else if field.ty.optional {
+                // proto3 `optional` fields are in a synthetic oneof after the real oneofs
+                oneofs.push(format!("_{}", field.name));
+                encoded.uint(9, oneofs.len() as u64 - 1);
+                encoded.bool(17, true);
+            }
+            if field.deprecated {
+                let mut options = Encoder::default();
+                options.bool(3, true);
+                encoded.message(8, options);
+            }
+            message.message(2, encoded);
+        }
+        for oneof in oneofs {
+            let mut decl = Encoder::default();
+            decl.string(1, &oneof);
+            message.message(8, decl);
+        }
+        message
+    }
+}
+
+impl Service {
+    /// Encodes the service as a `google.protobuf.ServiceDescriptorProto`
+    fn encode(&self, package: &str) -> Encoder {
+        let mut service = Encoder::default();
+        service.string(1, "Query");
+        for method in &self.methods {
+            let mut encoded = Encoder::default();
+            encoded.string(1, &method.name);
+            encoded.string(2, &qualified_name(package, &method.request));
+            encoded.string(3, &qualified_name(package, &method.response));
+            service.message(2, encoded);
+        }
+        service
+    }
+}
+
+/// A minimal encoder of the protobuf wire format, which is enough for descriptors
+#[derive(Default)]
+struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint(u64::from(field) << 3 | u64::from(wire_type));
+    }
+
+    fn uint(&mut self, field: u32, value: u64) {
+        self.key(field, 0);
+        self.varint(value);
+    }
+
+    fn bool(&mut self, field: u32, value: bool) {
+        self.uint(field, value.into());
+    }
+
+    fn bytes(&mut self, field: u32, value: &[u8]) {
+        self.key(field, 2);
+        self.varint(value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+
+    fn string(&mut self, field: u32, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn message(&mut self, field: u32, message: Encoder) {
+        self.bytes(field, &message.buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Color {
+        Red,
+        DarkBlue,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Coin {
+        denom: String,
+        amount: u128,
+    }
+
+    /// Instantiates the contract
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct InstantiateMsg {
+        /// The owner
+        owner: String,
+        admin: Option<String>,
+        color: Color,
+        funds: Vec<Coin>,
+        limits: Vec<Vec<u16>>,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum QueryMsg {
+        /// Returns the balance
+        Balance {
+            address: String,
+        },
+        Config {},
+        Owner {},
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Mixed {
+        Unit,
+        Data { value: bool },
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Config {
+        mixed: Mixed,
+    }
+
+    #[test]
+    fn proto_package_name_works() {
+        assert_eq!(proto_package_name("hackatom"), "hackatom");
+        assert_eq!(proto_package_name("crypto-verify"), "crypto_verify");
+        assert_eq!(proto_package_name("My_Contract"), "my_contract");
+        assert_eq!(proto_package_name("1inch"), "contract_1inch");
+    }
+
+    #[test]
+    fn render_proto_works() {
+        let instantiate = schema_for!(InstantiateMsg);
+        let query = schema_for!(QueryMsg);
+        let mut responses = BTreeMap::new();
+        responses.insert("balance".to_string(), schema_for!(Coin));
+        responses.insert("config".to_string(), schema_for!(Config));
+        responses.insert("owner".to_string(), schema_for!(String));
+
+        let proto = render_proto(
+            "example",
+            "example",
+            "0.1.0",
+            [("InstantiateMsg", &instantiate), ("QueryMsg", &query)],
+            Some(&responses),
+        );
+        assert_eq!(
+            proto,
+            r#"// Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+// Experimental: see the cosmwasm-schema documentation for the mapping rules.
+
+syntax = "proto3";
+
+package example;
+
+import "google/protobuf/struct.proto";
+
+// Instantiates the contract
+message InstantiateMsg {
+  optional string admin = 1 [json_name = "admin"];
+  // One of: "red", "dark_blue"
+  string color = 2 [json_name = "color"];
+  repeated Coin funds = 3 [json_name = "funds"];
+  google.protobuf.Value limits = 4 [json_name = "limits"];
+  // The owner
+  string owner = 5 [json_name = "owner"];
+}
+
+message Coin {
+  google.protobuf.Value amount = 1 [json_name = "amount"];
+  string denom = 2 [json_name = "denom"];
+}
+
+message QueryMsg {
+  oneof variant {
+    // Returns the balance
+    QueryMsgBalance balance = 1 [json_name = "balance"];
+    QueryMsgConfig config = 2 [json_name = "config"];
+    QueryMsgOwner owner = 3 [json_name = "owner"];
+  }
+}
+
+message QueryMsgBalance {
+  string address = 1 [json_name = "address"];
+}
+
+message QueryMsgConfig {}
+
+message QueryMsgOwner {}
+
+message Config {
+  google.protobuf.Value mixed = 1 [json_name = "mixed"];
+}
+
+// Each method sends the request as the content of the QueryMsg variant with the
+// snake_case name of the method
+service Query {
+  // Returns the balance
+  rpc Balance(QueryMsgBalance) returns (Coin);
+  rpc Config(QueryMsgConfig) returns (Config);
+  rpc Owner(QueryMsgOwner) returns (google.protobuf.Value);
+}
+"#
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum LegacyMsg {
+        /// Sets the price
+        #[deprecated]
+        SetPrice {
+            #[deprecated]
+            denom: Option<String>,
+        },
+    }
+
+    #[test]
+    fn render_proto_marks_deprecated_items() {
+        let msg = schema_for!(LegacyMsg);
+        let proto = render_proto("example", "example", "0.1.0", [("LegacyMsg", &msg)], None);
+        assert!(proto.contains("[json_name = \"set_price\", deprecated = true];"));
+        assert!(proto.contains("[json_name = \"denom\", deprecated = true];"));
+    }
+
+    enum Wire {
+        Varint(u64),
+        Bytes(Vec<u8>),
+    }
+
+    /// Decodes the protobuf wire format into field numbers and values
+    fn decode(mut buf: &[u8]) -> Vec<(u32, Wire)> {
+        fn varint(buf: &mut &[u8]) -> u64 {
+            let mut value = 0;
+            let mut shift = 0;
+            loop {
+                let byte = buf[0];
+                *buf = &buf[1..];
+                value |= u64::from(byte & 0x7f) << shift;
+                if byte < 0x80 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+        let mut fields = Vec::new();
+        while !buf.is_empty() {
+            let key = varint(&mut buf);
+            let value = match key & 7 {
+                0 => Wire::Varint(varint(&mut buf)),
+                2 => {
+                    let len = varint(&mut buf) as usize;
+                    let (value, rest) = buf.split_at(len);
+                    buf = rest;
+                    Wire::Bytes(value.to_vec())
+                }
+                wire_type => panic!("unexpected wire type {}", wire_type),
+            };
+            fields.push(((key >> 3) as u32, value));
+        }
+        fields
+    }
+
+    fn bytes_of(buf: &[u8], field: u32) -> Vec<Vec<u8>> {
+        decode(buf)
+            .into_iter()
+            .filter_map(|(number, value)| match value {
+                Wire::Bytes(bytes) if number == field => Some(bytes),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn strings_of(buf: &[u8], field: u32) -> Vec<String> {
+        bytes_of(buf, field)
+            .into_iter()
+            .map(|bytes| String::from_utf8(bytes).unwrap())
+            .collect()
+    }
+
+    fn uint_of(buf: &[u8], field: u32) -> Option<u64> {
+        decode(buf)
+            .into_iter()
+            .find_map(|(number, value)| match value {
+                Wire::Varint(value) if number == field => Some(value),
+                _ => None,
+            })
+    }
+
+    /// The encoded `DescriptorProto` or `FieldDescriptorProto` with the given name
+    fn named(items: &[Vec<u8>], name: &str) -> Vec<u8> {
+        items
+            .iter()
+            .find(|item| strings_of(item, 1) == [name])
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn render_proto_descriptor_set_works() {
+        let instantiate = schema_for!(InstantiateMsg);
+        let query = schema_for!(QueryMsg);
+        let mut responses = BTreeMap::new();
+        responses.insert("balance".to_string(), schema_for!(Coin));
+        responses.insert("owner".to_string(), schema_for!(String));
+
+        let set = render_proto_descriptor_set(
+            "example",
+            "example.proto",
+            [("InstantiateMsg", &instantiate), ("QueryMsg", &query)],
+            Some(&responses),
+        );
+        let files = bytes_of(&set, 1);
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(strings_of(file, 1), ["example.proto"]);
+        assert_eq!(strings_of(file, 2), ["example"]);
+        assert_eq!(strings_of(file, 3), ["google/protobuf/struct.proto"]);
+        assert_eq!(strings_of(file, 12), ["proto3"]);
+
+        let messages = bytes_of(file, 4);
+        let names: Vec<String> = messages
+            .iter()
+            .map(|message| strings_of(message, 1).remove(0))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "InstantiateMsg",
+                "Coin",
+                "QueryMsg",
+                "QueryMsgBalance",
+                "QueryMsgConfig",
+                "QueryMsgOwner"
+            ]
+        );
+
+        // optional fields are in a synthetic oneof
+        let instantiate = named(&messages, "InstantiateMsg");
+        let fields = bytes_of(&instantiate, 2);
+        let admin = named(&fields, "admin");
+        assert_eq!(uint_of(&admin, 3), Some(1));
+        assert_eq!(uint_of(&admin, 4), Some(LABEL_OPTIONAL));
+        assert_eq!(uint_of(&admin, 5), Some(9));
+        assert_eq!(uint_of(&admin, 9), Some(0));
+        assert_eq!(uint_of(&admin, 17), Some(1));
+        assert_eq!(strings_of(&admin, 10), ["admin"]);
+        let oneofs = bytes_of(&instantiate, 8);
+        assert_eq!(oneofs.len(), 1);
+        assert_eq!(strings_of(&oneofs[0], 1), ["_admin"]);
+        let funds = named(&fields, "funds");
+        assert_eq!(uint_of(&funds, 4), Some(LABEL_REPEATED));
+        assert_eq!(uint_of(&funds, 5), Some(TYPE_MESSAGE));
+        assert_eq!(strings_of(&funds, 6), [".example.Coin"]);
+        assert_eq!(uint_of(&funds, 9), None);
+        let limits = named(&fields, "limits");
+        assert_eq!(strings_of(&limits, 6), [".google.protobuf.Value"]);
+
+        // the variants of enums are in a oneof
+        let query = named(&messages, "QueryMsg");
+        let oneofs = bytes_of(&query, 8);
+        assert_eq!(oneofs.len(), 1);
+        assert_eq!(strings_of(&oneofs[0], 1), ["variant"]);
+        for field in bytes_of(&query, 2) {
+            assert_eq!(uint_of(&field, 9), Some(0));
+        }
+
+        let services = bytes_of(file, 6);
+        assert_eq!(services.len(), 1);
+        assert_eq!(strings_of(&services[0], 1), ["Query"]);
+        let methods = bytes_of(&services[0], 2);
+        assert_eq!(methods.len(), 2);
+        let balance = named(&methods, "Balance");
+        assert_eq!(strings_of(&balance, 2), [".example.QueryMsgBalance"]);
+        assert_eq!(strings_of(&balance, 3), [".example.Coin"]);
+        let owner = named(&methods, "Owner");
+        assert_eq!(strings_of(&owner, 3), [".google.protobuf.Value"]);
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Balances {
+        balances_by_denom: BTreeMap<String, u32>,
+        #[deprecated]
+        total: u32,
+    }
+
+    #[test]
+    fn render_proto_descriptor_set_works_for_maps() {
+        let balances = schema_for!(Balances);
+        let proto = render_proto(
+            "example",
+            "example",
+            "0.1.0",
+            [("Balances", &balances)],
+            None,
+        );
+        assert!(proto.contains(
+            "  map<string, uint32> balances_by_denom = 1 [json_name = \"balances_by_denom\"];\n"
+        ));
+
+        let set = render_proto_descriptor_set(
+            "example",
+            "example.proto",
+            [("Balances", &balances)],
+            None,
+        );
+        let file = &bytes_of(&set, 1)[0];
+        // no `google.protobuf.Value` is used
+        assert_eq!(strings_of(file, 3), Vec::<String>::new());
+        let message = named(&bytes_of(file, 4), "Balances");
+
+        let entries = bytes_of(&message, 3);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(strings_of(&entries[0], 1), ["BalancesByDenomEntry"]);
+        let options = bytes_of(&entries[0], 7);
+        assert_eq!(uint_of(&options[0], 7), Some(1));
+        let entry_fields = bytes_of(&entries[0], 2);
+        assert_eq!(uint_of(&named(&entry_fields, "key"), 5), Some(9));
+        assert_eq!(uint_of(&named(&entry_fields, "value"), 5), Some(13));
+
+        let fields = bytes_of(&message, 2);
+        let map = named(&fields, "balances_by_denom");
+        assert_eq!(uint_of(&map, 4), Some(LABEL_REPEATED));
+        assert_eq!(
+            strings_of(&map, 6),
+            [".example.Balances.BalancesByDenomEntry"]
+        );
+        let total = named(&fields, "total");
+        assert_eq!(uint_of(&bytes_of(&total, 8)[0], 3), Some(1));
+    }
+
+    #[test]
+    fn map_entry_name_works() {
+        assert_eq!(map_entry_name("balances"), "BalancesEntry");
+        assert_eq!(map_entry_name("balances_by_denom"), "BalancesByDenomEntry");
+        assert_eq!(map_entry_name("_x_1"), "X1Entry");
+    }
+}
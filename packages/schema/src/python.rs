@@ -0,0 +1,646 @@
+//! Generation of Python type definitions ([pydantic](https://docs.pydantic.dev) v2 models)
+//! from a contract's API.
+//!
+//! This is meant for bots and indexers written in Python. Unlike type hints alone, the
+//! models validate the JSON at runtime, so a response that does not match the contract
+//! API (e.g. after a migration) fails when it is parsed rather than when it is used.
+//!
+//! Mapping rules:
+//!
+//! - Objects become `BaseModel` classes. Optional fields default to `None`. Fields whose
+//!   names are no valid Python identifiers (e.g. `from`) get a trailing `_` and an alias.
+//! - Enums become `RootModel`s of a `Union` of their variants. Data variants are wrapped in
+//!   a model with a single field named after the variant, e.g. `ExecuteMsgSendVariant` with
+//!   the field `send`. Unit variants are string `Literal`s.
+//! - All other named types (e.g. `Uint128`) become `RootModel`s of their content.
+//! - Integers are `int`, tuples become `Tuple`s and maps become `Dict[str, T]`.
+//! - Everything else (arbitrary JSON) becomes `Any`.
+//! - Fields and data variants marked with `#[deprecated]` get a `Deprecated.` comment.
+//!
+//! Types are defined before they are used. Models can be serialized into messages with
+//! `model_dump_json(by_alias=True, exclude_none=True)`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+
+use crate::casing::to_pascal_case;
+use crate::codegen::{
+    documentation, generated_notice, is_named, is_struct, property_schema_documentation,
+    single_property, string_values, variants, Generator, TypeNames,
+};
+
+/// The command line flag that makes `write_api!` also export Python type definitions
+pub const PYTHON_FLAG: &str = "--python";
+
+/// Appended to the comments of deprecated fields and variants
+const DEPRECATED: &str = "Deprecated.";
+
+const ANY: &str = "Any";
+
+/// The names imported by the generated code, which must not be used for types
+const TYPING_NAMES: [&str; 7] = [
+    "Any", "Dict", "List", "Literal", "Optional", "Tuple", "Union",
+];
+const PYDANTIC_NAMES: [&str; 4] = ["BaseModel", "ConfigDict", "Field", "RootModel"];
+
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Returns true if the current process was started with [`--python`](PYTHON_FLAG),
+/// e.g. via `cargo schema -- --python`.
+pub fn is_python_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == PYTHON_FLAG)
+}
+
+/// Returns a valid Python module name for the given contract name, e.g. `crypto_verify`
+/// for `crypto-verify`.
+pub fn python_module_name(contract_name: &str) -> String {
+    let name: String = contract_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match name.chars().next() {
+        Some(first) if !first.is_ascii_digit() && !KEYWORDS.contains(&name.as_str()) => name,
+        _ => format!("contract_{}", name),
+    }
+}
+
+/// Renders pydantic models for the given root schemas.
+///
+/// `messages` are the top level messages with their class names. For `responses`
+/// the class name is taken from the schema title.
+pub(crate) fn render_python<'a>(
+    contract_name: &str,
+    contract_version: &str,
+    messages: impl IntoIterator<Item = (&'a str, &'a RootSchema)>,
+    responses: Option<&'a BTreeMap<String, RootSchema>>,
+) -> String {
+    let mut gen = PythonGenerator::default();
+    // Make sure the types of the contract do not shadow the imported names
+    for name in TYPING_NAMES.iter().chain(&PYDANTIC_NAMES) {
+        gen.reserve(name);
+    }
+    for (name, root) in messages {
+        gen.root(name, root);
+    }
+    for (query, root) in responses.into_iter().flatten() {
+        // Responses like `String` or `Vec<Coin>` map to Python types directly
+        if is_named(&root.schema) {
+            let name = root
+                .schema
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.title.clone())
+                .unwrap_or_else(|| format!("{}_response", query));
+            gen.root(&name, root);
+        } else {
+            gen.enter(root);
+            gen.unreferenced_definitions();
+        }
+    }
+
+    // Annotations are evaluated lazily by pydantic, which allows recursive models
+    let mut out = format!(
+        "# {}\n\nfrom __future__ import annotations\n",
+        generated_notice(contract_name, contract_version)
+    );
+    if !gen.typing.is_empty() {
+        let names: Vec<&str> = gen.typing.iter().copied().collect();
+        out.push_str(&format!("\nfrom typing import {}\n", names.join(", ")));
+    }
+    if !gen.pydantic.is_empty() {
+        let names: Vec<&str> = gen.pydantic.iter().copied().collect();
+        out.push_str(&format!("\nfrom pydantic import {}\n", names.join(", ")));
+    }
+    for item in &gen.types {
+        out.push_str("\n\n");
+        out.push_str(item);
+    }
+    out
+}
+
+#[derive(Default)]
+struct PythonGenerator<'a> {
+    /// The rendered class definitions in output order
+    types: Vec<String>,
+    type_names: TypeNames<'a>,
+    /// The names used from `typing`
+    typing: BTreeSet<&'static str>,
+    /// The names used from `pydantic`
+    pydantic: BTreeSet<&'static str>,
+}
+
+impl<'a> Generator<'a> for PythonGenerator<'a> {
+    type Type = String;
+
+    fn type_names(&mut self) -> &mut TypeNames<'a> {
+        &mut self.type_names
+    }
+
+    fn raw(&mut self) -> String {
+        self.typing.insert(ANY);
+        ANY.to_string()
+    }
+
+    fn optional(&mut self, ty: String) -> String {
+        if ty == ANY || ty.starts_with("Optional[") {
+            ty
+        } else {
+            self.typing.insert("Optional");
+            format!("Optional[{}]", ty)
+        }
+    }
+
+    fn named(&mut self, name: &str) -> String {
+        name.to_string()
+    }
+
+    /// Renders a class definition. The classes it uses are defined first.
+    fn define(&mut self, name: &str, schema: &SchemaObject) {
+        let (base, body) = if let Some(variants) = variants(schema) {
+            (self.enumeration(name, variants), Vec::new())
+        } else if let Some(values) = string_values(schema) {
+            (self.root_model(literal(&values)), Vec::new())
+        } else if is_struct(schema) {
+            self.structure(name, schema)
+        } else {
+            let ty = self.type_for(schema, name);
+            (self.root_model(ty), Vec::new())
+        };
+        self.push_class(
+            name,
+            documentation(schema, DEPRECATED).as_deref(),
+            &base,
+            body,
+        );
+    }
+
+    fn unnamed(
+        &mut self,
+        instance_type: InstanceType,
+        schema: &SchemaObject,
+        context: &str,
+    ) -> String {
+        match instance_type {
+            InstanceType::Boolean => "bool".to_string(),
+            InstanceType::String => "str".to_string(),
+            InstanceType::Number => "float".to_string(),
+            InstanceType::Integer => "int".to_string(),
+            InstanceType::Array => match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+                Some(SingleOrVec::Vec(items)) => {
+                    let items: Vec<String> = items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            self.type_of(item, &format!("{}Item{}", context, index + 1))
+                        })
+                        .collect();
+                    self.typing.insert("Tuple");
+                    format!("Tuple[{}]", items.join(", "))
+                }
+                items => {
+                    let item = match items {
+                        Some(SingleOrVec::Single(items)) => {
+                            self.type_of(items, &format!("{}Item", context))
+                        }
+                        _ => self.raw(),
+                    };
+                    self.typing.insert("List");
+                    format!("List[{}]", item)
+                }
+            },
+            InstanceType::Object => {
+                let value = match schema
+                    .object
+                    .as_ref()
+                    .and_then(|o| o.additional_properties.as_ref())
+                {
+                    Some(values) => self.type_of(values, &format!("{}Value", context)),
+                    None => self.raw(),
+                };
+                self.typing.insert("Dict");
+                format!("Dict[str, {}]", value)
+            }
+            InstanceType::Null => "None".to_string(),
+        }
+    }
+}
+
+impl PythonGenerator<'_> {
+    fn push_class(&mut self, name: &str, description: Option<&str>, base: &str, body: Vec<String>) {
+        let mut code = String::new();
+        if let Some(description) = description {
+            code.push_str(&comment(description, ""));
+        }
+        code.push_str(&format!("class {}({}):\n", name, base));
+        if body.is_empty() {
+            code.push_str("    pass\n");
+        }
+        for line in body {
+            code.push_str(&line);
+        }
+        self.types.push(code);
+    }
+
+    fn root_model(&mut self, ty: String) -> String {
+        self.pydantic.insert("RootModel");
+        format!("RootModel[{}]", ty)
+    }
+
+    /// Returns the base class and the body of a model for an object
+    fn structure(&mut self, name: &str, schema: &SchemaObject) -> (String, Vec<String>) {
+        self.pydantic.insert("BaseModel");
+        let object = schema.object.as_ref().unwrap();
+        let mut config = Vec::new();
+        if matches!(
+            object.additional_properties.as_deref(),
+            Some(Schema::Bool(false))
+        ) {
+            config.push("extra=\"forbid\"");
+        }
+
+        let mut fields = Vec::new();
+        let mut has_alias = false;
+        for (property, property_schema) in &object.properties {
+            let field = field_name(property);
+            let context = format!("{}{}", name, to_pascal_case(property));
+            let ty = self.type_of(property_schema, &context);
+            let required = object.required.contains(property);
+            let ty = if required { ty } else { self.optional(ty) };
+            let default = match (field == *property, required) {
+                (true, true) => String::new(),
+                (true, false) => " = None".to_string(),
+                (false, required) => {
+                    has_alias = true;
+                    self.pydantic.insert("Field");
+                    let default = if required { "" } else { "default=None, " };
+                    format!(" = Field({}alias={})", default, quote(property))
+                }
+            };
+            let mut line = String::new();
+            if let Some(docs) = property_schema_documentation(property_schema, DEPRECATED) {
+                line.push_str(&comment(&docs, "    "));
+            }
+            line.push_str(&format!("    {}: {}{}\n", field, ty, default));
+            fields.push(line);
+        }
+        if has_alias {
+            config.push("populate_by_name=True");
+        }
+
+        let mut body = Vec::new();
+        if !config.is_empty() {
+            self.pydantic.insert("ConfigDict");
+            body.push(format!(
+                "    model_config = ConfigDict({})\n",
+                config.join(", ")
+            ));
+            if !fields.is_empty() {
+                body.push("\n".to_string());
+            }
+        }
+        body.extend(fields);
+        ("BaseModel".to_string(), body)
+    }
+
+    /// Returns the base class of a `RootModel` for an enum, i.e. a `oneOf` or `anyOf` as
+    /// generated by schemars for Rust enums. Data variants are defined as wrapper models.
+    fn enumeration(&mut self, name: &str, variants: &[Schema]) -> String {
+        let mut values = Vec::new();
+        let mut cases = Vec::new();
+        for (index, variant) in variants.iter().enumerate() {
+            match variant {
+                Schema::Object(variant) => {
+                    if let Some(variant_values) = string_values(variant) {
+                        values.extend(variant_values);
+                    } else if let Some((property, content)) = single_property(variant) {
+                        let context = format!("{}{}", name, to_pascal_case(property));
+                        let ty = self.type_of(content, &context);
+                        let wrapper = self.reserve(&format!("{}Variant", context));
+                        let field = field_name(property);
+                        let mut config = "extra=\"forbid\"".to_string();
+                        let mut alias = String::new();
+                        if field != property {
+                            self.pydantic.insert("Field");
+                            config.push_str(", populate_by_name=True");
+                            alias = format!(" = Field(alias={})", quote(property));
+                        }
+                        self.pydantic.insert("BaseModel");
+                        self.pydantic.insert("ConfigDict");
+                        let body = vec![
+                            format!("    model_config = ConfigDict({})\n", config),
+                            "\n".to_string(),
+                            format!("    {}: {}{}\n", field, ty, alias),
+                        ];
+                        let docs = documentation(variant, DEPRECATED);
+                        self.push_class(&wrapper, docs.as_deref(), "BaseModel", body);
+                        cases.push(wrapper);
+                    } else {
+                        let ty = self.type_for(variant, &format!("{}{}", name, index + 1));
+                        cases.push(ty);
+                    }
+                }
+                Schema::Bool(_) => cases.push(self.raw()),
+            }
+        }
+        if !values.is_empty() {
+            self.typing.insert("Literal");
+            cases.insert(0, literal(&values));
+        }
+        let ty = if cases.len() == 1 {
+            cases.remove(0)
+        } else {
+            self.typing.insert("Union");
+            format!("Union[{}]", cases.join(", "))
+        };
+        self.root_model(ty)
+    }
+}
+
+/// Makes a quoted string literal, which uses the same escaping as JSON
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+fn literal(values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| quote(value)).collect();
+    format!("Literal[{}]", values.join(", "))
+}
+
+/// Returns a valid Python identifier for a property name. Names starting with `_` are
+/// private in pydantic and `model_` is reserved for pydantic's own methods.
+fn field_name(property: &str) -> String {
+    let mut name: String = property
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) || name.starts_with("model_") {
+        name.insert_str(0, "field");
+        if !name[5..].starts_with('_') {
+            name.insert(5, '_');
+        }
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+fn comment(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}#\n", indent)
+            } else {
+                format!("{}# {}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Color {
+        Red,
+        DarkBlue,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    struct Coin {
+        denom: String,
+        amount: String,
+    }
+
+    /// Instantiates the contract
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    struct InstantiateMsg {
+        /// The owner
+        owner: String,
+        admin: Option<String>,
+        color: Color,
+        funds: Vec<Coin>,
+        pair: (String, u64),
+        #[serde(rename = "from")]
+        sender: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case", deny_unknown_fields)]
+    enum ExecuteMsg {
+        /// Sends funds
+        Send {
+            to: String,
+            amount: Coin,
+        },
+        Reset {},
+        Freeze,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Node {
+        children: Vec<Node>,
+    }
+
+    #[test]
+    fn python_module_name_works() {
+        assert_eq!(python_module_name("hackatom"), "hackatom");
+        assert_eq!(python_module_name("crypto-verify"), "crypto_verify");
+        assert_eq!(python_module_name("My_Contract"), "my_contract");
+        assert_eq!(python_module_name("1inch"), "contract_1inch");
+        assert_eq!(python_module_name("import"), "contract_import");
+        assert_eq!(python_module_name(""), "contract_");
+    }
+
+    #[test]
+    fn field_name_works() {
+        assert_eq!(field_name("owner"), "owner");
+        assert_eq!(field_name("from"), "from_");
+        assert_eq!(field_name("the-value"), "the_value");
+        assert_eq!(field_name("_id"), "field_id");
+        assert_eq!(field_name("1st"), "field_1st");
+        assert_eq!(field_name("model_id"), "field_model_id");
+    }
+
+    #[test]
+    fn render_python_works() {
+        let instantiate = schema_for!(InstantiateMsg);
+        let execute = schema_for!(ExecuteMsg);
+        let mut responses = BTreeMap::new();
+        responses.insert("balance".to_string(), schema_for!(Coin));
+        responses.insert("owner".to_string(), schema_for!(String));
+        responses.insert("tree".to_string(), schema_for!(Node));
+
+        let python = render_python(
+            "example",
+            "0.1.0",
+            [("InstantiateMsg", &instantiate), ("ExecuteMsg", &execute)],
+            Some(&responses),
+        );
+        assert_eq!(
+            python,
+            r#"# Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+
+from __future__ import annotations
+
+from typing import List, Literal, Optional, Tuple, Union
+
+from pydantic import BaseModel, ConfigDict, Field, RootModel
+
+
+class Color(RootModel[Literal["red", "dark_blue"]]):
+    pass
+
+
+class Coin(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    amount: str
+    denom: str
+
+
+# Instantiates the contract
+class InstantiateMsg(BaseModel):
+    model_config = ConfigDict(extra="forbid", populate_by_name=True)
+
+    admin: Optional[str] = None
+    color: Color
+    from_: Optional[str] = Field(default=None, alias="from")
+    funds: List[Coin]
+    # The owner
+    owner: str
+    pair: Tuple[str, int]
+
+
+class ExecuteMsgSend(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    amount: Coin
+    to: str
+
+
+# Sends funds
+class ExecuteMsgSendVariant(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    send: ExecuteMsgSend
+
+
+class ExecuteMsgReset(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+
+class ExecuteMsgResetVariant(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    reset: ExecuteMsgReset
+
+
+class ExecuteMsg(RootModel[Union[Literal["freeze"], ExecuteMsgSendVariant, ExecuteMsgResetVariant]]):
+    pass
+
+
+class Node(BaseModel):
+    children: List[Node]
+"#
+        );
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Mixed {
+        Unit,
+        AtHeight(u64),
+        Data {
+            #[serde(rename = "the-value")]
+            value: BTreeMap<String, bool>,
+            extra: serde_json::Value,
+        },
+    }
+
+    #[test]
+    fn render_python_works_for_mixed_enums() {
+        let mixed = schema_for!(Mixed);
+        let python = render_python("example", "0.1.0", [("Mixed", &mixed)], None);
+        assert_eq!(
+            python,
+            r#"# Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+
+from __future__ import annotations
+
+from typing import Any, Dict, Literal, Union
+
+from pydantic import BaseModel, ConfigDict, Field, RootModel
+
+
+class MixedAtHeightVariant(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    at_height: int
+
+
+class MixedData(BaseModel):
+    model_config = ConfigDict(populate_by_name=True)
+
+    extra: Any
+    the_value: Dict[str, bool] = Field(alias="the-value")
+
+
+class MixedDataVariant(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    data: MixedData
+
+
+class Mixed(RootModel[Union[Literal["unit"], MixedAtHeightVariant, MixedDataVariant]]):
+    pass
+"#
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum LegacyMsg {
+        /// Sets the price
+        #[deprecated]
+        SetPrice {
+            #[deprecated]
+            denom: Option<String>,
+        },
+    }
+
+    #[test]
+    fn render_python_marks_deprecated_items() {
+        let msg = schema_for!(LegacyMsg);
+        let python = render_python("example", "0.1.0", [("LegacyMsg", &msg)], None);
+        assert!(
+            python.contains("# Sets the price\n#\n# Deprecated.\nclass LegacyMsgSetPriceVariant")
+        );
+        assert!(python.contains("    # Deprecated.\n    denom: Optional[str] = None\n"));
+    }
+}
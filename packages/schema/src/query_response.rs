@@ -1,9 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use schemars::{
-    schema::{InstanceType, RootSchema, SingleOrVec},
-    JsonSchema,
+    schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec},
+    JsonSchema, Map,
 };
+use serde_json::Value;
 use thiserror::Error;
 
 pub use cosmwasm_schema_derive::QueryResponses;
@@ -12,6 +13,50 @@ pub use cosmwasm_schema_derive::QueryResponses;
 /// This is mostly useful for the generated contracted API description when using `cargo schema`.
 ///
 /// Using the derive macro is the preferred way of implementing this trait.
+/// Doc comments on the variants end up as the `description` of the response schemas
+/// unless the response type is documented itself.
+///
+/// A query enum composed of other query enums, such as `QueryMsg::Cw20(Cw20QueryMsg)`,
+/// must be `#[serde(untagged)]` and can derive the trait with `#[query_responses(nested)]`,
+/// which combines the responses of the child enums.
+///
+/// Internally and adjacently tagged query enums (`#[serde(tag = "...")]` or
+/// `#[cw_serde(tag = "...")]`) are supported as well. The query names are the tag values.
+///
+/// The response type can be any type, including paths like `cw20::BalanceResponse` and
+/// generics like `Vec<cw_utils::Expiration>`. A variant without `#[returns(...)]` is a
+/// compile error pointing at the variant:
+///
+/// ```compile_fail
+/// use cosmwasm_schema::QueryResponses;
+/// use schemars::JsonSchema;
+///
+/// #[derive(JsonSchema, QueryResponses)]
+/// enum QueryMsg {
+///     Denoms {},
+/// }
+/// ```
+///
+/// A renamed query can keep its old name with `#[query_alias("old_name")]`, so deployed
+/// frontends keep working. [`cw_serde`](crate::cw_serde) makes the enum accept the old name
+/// (without it, add `#[serde(alias = "old_name")]` as well) and the schema documents it as
+/// a deprecated query with the same response:
+///
+/// ```
+/// use cosmwasm_schema::{cw_serde, QueryResponses};
+///
+/// #[cw_serde]
+/// #[derive(QueryResponses)]
+/// pub enum QueryMsg {
+///     #[returns(u64)]
+///     #[query_alias("total_supply")]
+///     Supply {},
+/// }
+///
+/// let query: QueryMsg = serde_json::from_str(r#"{"total_supply":{}}"#).unwrap();
+/// assert_eq!(query, QueryMsg::Supply {});
+/// assert!(QueryMsg::response_schemas().unwrap().contains_key("total_supply"));
+/// ```
 ///
 /// # Example
 /// ```
@@ -30,10 +75,30 @@ pub use cosmwasm_schema_derive::QueryResponses;
 ///     #[returns(AccountInfo)]
 ///     AccountInfo { account: String },
 /// }
+///
+/// #[derive(JsonSchema, QueryResponses)]
+/// enum ExtensionQueryMsg {
+///     #[returns(String)]
+///     Owner {},
+/// }
+///
+/// #[derive(JsonSchema, QueryResponses)]
+/// #[serde(untagged)]
+/// #[query_responses(nested)]
+/// enum ContractQueryMsg {
+///     Base(QueryMsg),
+///     Extension(ExtensionQueryMsg),
+/// }
 /// ```
 pub trait QueryResponses: JsonSchema {
     fn response_schemas() -> Result<BTreeMap<String, RootSchema>, IntegrityError> {
-        let response_schemas = Self::response_schemas_impl();
+        let mut response_schemas = Self::response_schemas_impl();
+        // An alias is answered the same way as the query it is an alias for
+        for (alias, query) in Self::query_aliases() {
+            if let Some(schema) = response_schemas.get(&query).cloned() {
+                response_schemas.insert(alias, schema);
+            }
+        }
 
         let queries: BTreeSet<_> = response_schemas.keys().cloned().collect();
 
@@ -43,57 +108,65 @@ pub trait QueryResponses: JsonSchema {
     }
 
     fn response_schemas_impl() -> BTreeMap<String, RootSchema>;
+
+    /// The legacy names of renamed queries, mapped to their current names.
+    ///
+    /// The derive macro collects them from the `#[query_alias("old_name")]` attributes
+    /// of the variants.
+    fn query_aliases() -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    /// The schema of the query enum. The aliases of renamed queries are documented as
+    /// deprecated variants next to the variants with the current names.
+    fn query_schema() -> RootSchema {
+        let mut schema = crate::schema_for!(Self);
+        for (alias, query) in Self::query_aliases() {
+            add_query_alias(&mut schema, &alias, &query);
+        }
+        schema
+    }
+}
+
+/// Combines the response schemas of the child query enums of a nested query enum.
+///
+/// This is used by the [`QueryResponses`] derive for `#[query_responses(nested)]` and panics
+/// if two child enums contain queries with the same name.
+pub fn combine_subqueries<const N: usize, T: ?Sized>(
+    subqueries: [BTreeMap<String, RootSchema>; N],
+) -> BTreeMap<String, RootSchema> {
+    combine::<N, T, _>(subqueries)
+}
+
+/// Combines the query aliases of the child query enums of a nested query enum.
+///
+/// This is used by the [`QueryResponses`] derive for `#[query_responses(nested)]` and panics
+/// if two child enums contain aliases with the same name.
+pub fn combine_query_aliases<const N: usize, T: ?Sized>(
+    aliases: [BTreeMap<String, String>; N],
+) -> BTreeMap<String, String> {
+    combine::<N, T, _>(aliases)
+}
+
+fn combine<const N: usize, T: ?Sized, V>(maps: [BTreeMap<String, V>; N]) -> BTreeMap<String, V> {
+    let mut combined = BTreeMap::new();
+    for (query, value) in maps.into_iter().flatten() {
+        if combined.insert(query.clone(), value).is_some() {
+            panic!(
+                "query {} is defined by multiple subqueries of {}",
+                query,
+                std::any::type_name::<T>()
+            );
+        }
+    }
+    combined
 }
 
-/// `generated_queries` is expected to be a sorted slice here!
 fn check_api_integrity<T: QueryResponses + ?Sized>(
     generated_queries: BTreeSet<String>,
 ) -> Result<(), IntegrityError> {
-    let schema = crate::schema_for!(T);
-
-    // something more readable below?
-
-    let schema_queries: BTreeSet<_> = match schema.schema.subschemas {
-        Some(subschemas) => subschemas
-            .one_of
-            .ok_or(IntegrityError::InvalidQueryMsgSchema)?
-            .into_iter()
-            .map(|s| {
-                let s = s.into_object();
-
-                if let Some(SingleOrVec::Single(ty)) = s.instance_type {
-                    match *ty {
-                        // We'll have an object if the Rust enum variant was C-like or tuple-like
-                        InstanceType::Object => s
-                            .object
-                            .ok_or(IntegrityError::InvalidQueryMsgSchema)?
-                            .required
-                            .into_iter()
-                            .next()
-                            .ok_or(IntegrityError::InvalidQueryMsgSchema),
-                        // We might have a string here if the Rust enum variant was unit-like
-                        InstanceType::String => {
-                            let values =
-                                s.enum_values.ok_or(IntegrityError::InvalidQueryMsgSchema)?;
-
-                            if values.len() != 1 {
-                                return Err(IntegrityError::InvalidQueryMsgSchema);
-                            }
-
-                            values[0]
-                                .as_str()
-                                .map(String::from)
-                                .ok_or(IntegrityError::InvalidQueryMsgSchema)
-                        }
-                        _ => Err(IntegrityError::InvalidQueryMsgSchema),
-                    }
-                } else {
-                    Err(IntegrityError::InvalidQueryMsgSchema)
-                }
-            })
-            .collect::<Result<_, _>>()?,
-        None => BTreeSet::new(),
-    };
+    let schema = T::query_schema();
+    let schema_queries = query_names(&schema.schema, &schema.definitions)?;
 
     if schema_queries != generated_queries {
         return Err(IntegrityError::InconsistentQueries {
@@ -105,6 +178,239 @@ fn check_api_integrity<T: QueryResponses + ?Sized>(
     Ok(())
 }
 
+/// The names of the queries in a query enum schema.
+///
+/// Nested query enums are `#[serde(untagged)]` and have an `anyOf` referencing the schemas
+/// of the child enums, whose queries are collected recursively.
+fn query_names(
+    schema: &SchemaObject,
+    definitions: &Map<String, Schema>,
+) -> Result<BTreeSet<String>, IntegrityError> {
+    let subschemas = match &schema.subschemas {
+        Some(subschemas) => subschemas,
+        None => return Ok(BTreeSet::new()),
+    };
+
+    if let Some(any_of) = &subschemas.any_of {
+        let mut names = BTreeSet::new();
+        for subschema in any_of {
+            let child = match subschema {
+                Schema::Object(SchemaObject {
+                    reference: Some(reference),
+                    ..
+                }) => reference
+                    .strip_prefix("#/definitions/")
+                    .and_then(|name| definitions.get(name)),
+                _ => None,
+            };
+            match child {
+                Some(Schema::Object(child)) => names.extend(query_names(child, definitions)?),
+                _ => return Err(IntegrityError::InvalidQueryMsgSchema),
+            }
+        }
+        return Ok(names);
+    }
+
+    let variants = subschemas
+        .one_of
+        .as_ref()
+        .ok_or(IntegrityError::InvalidQueryMsgSchema)?
+        .iter()
+        .map(|s| match s {
+            Schema::Object(s) => Ok(s),
+            Schema::Bool(_) => Err(IntegrityError::InvalidQueryMsgSchema),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Internally and adjacently tagged enums (`#[serde(tag = "...")]`) have the variant name
+    // in the tag field of every variant
+    if let Some(tag) = enum_tag(&variants) {
+        return variants
+            .iter()
+            .map(|s| tag_value(s, &tag).ok_or(IntegrityError::InvalidQueryMsgSchema))
+            .collect();
+    }
+
+    variants
+        .iter()
+        .map(|s| {
+            if let Some(SingleOrVec::Single(ty)) = &s.instance_type {
+                match **ty {
+                    // We'll have an object if the Rust enum variant was C-like or tuple-like
+                    InstanceType::Object => s
+                        .object
+                        .as_ref()
+                        .ok_or(IntegrityError::InvalidQueryMsgSchema)?
+                        .required
+                        .iter()
+                        .next()
+                        .cloned()
+                        .ok_or(IntegrityError::InvalidQueryMsgSchema),
+                    // We might have a string here if the Rust enum variant was unit-like
+                    InstanceType::String => {
+                        let values = s
+                            .enum_values
+                            .as_ref()
+                            .ok_or(IntegrityError::InvalidQueryMsgSchema)?;
+
+                        if values.len() != 1 {
+                            return Err(IntegrityError::InvalidQueryMsgSchema);
+                        }
+
+                        values[0]
+                            .as_str()
+                            .map(String::from)
+                            .ok_or(IntegrityError::InvalidQueryMsgSchema)
+                    }
+                    _ => Err(IntegrityError::InvalidQueryMsgSchema),
+                }
+            } else {
+                Err(IntegrityError::InvalidQueryMsgSchema)
+            }
+        })
+        .collect()
+}
+
+/// Adds a deprecated copy of the variant of `query` named `alias` to the query enum schema.
+/// Does nothing if the schema has no variant for `query`.
+fn add_query_alias(schema: &mut RootSchema, alias: &str, query: &str) {
+    add_alias(&mut schema.schema, &mut schema.definitions, alias, query);
+}
+
+/// Returns true if the variant was found in the schema or the child enums it refers to
+fn add_alias(
+    schema: &mut SchemaObject,
+    definitions: &mut Map<String, Schema>,
+    alias: &str,
+    query: &str,
+) -> bool {
+    let subschemas = match &mut schema.subschemas {
+        Some(subschemas) => subschemas,
+        None => return false,
+    };
+
+    // Nested query enums refer to the schemas of their child enums
+    if let Some(any_of) = &subschemas.any_of {
+        let children = any_of.iter().filter_map(|subschema| match subschema {
+            Schema::Object(SchemaObject {
+                reference: Some(reference),
+                ..
+            }) => reference.strip_prefix("#/definitions/"),
+            _ => None,
+        });
+        for name in children {
+            if let Some(Schema::Object(mut child)) = definitions.remove(name) {
+                let found = add_alias(&mut child, definitions, alias, query);
+                definitions.insert(name.to_string(), child.into());
+                if found {
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+
+    let variants = match &mut subschemas.one_of {
+        Some(variants) => variants,
+        None => return false,
+    };
+    let objects: Vec<_> = variants
+        .iter()
+        .filter_map(|variant| match variant {
+            Schema::Object(variant) => Some(variant),
+            Schema::Bool(_) => None,
+        })
+        .collect();
+    let tag = enum_tag(&objects);
+    let position = objects.iter().position(|variant| {
+        let name = match &tag {
+            Some(tag) => tag_value(variant, tag),
+            None => variant_name(variant),
+        };
+        name.as_deref() == Some(query)
+    });
+    let mut aliased = match position {
+        Some(position) => objects[position].clone(),
+        None => return false,
+    };
+
+    let renamed: Value = alias.into();
+    match (&tag, aliased.object.as_deref_mut()) {
+        (Some(tag), Some(object)) => {
+            if let Some(Schema::Object(tag_schema)) = object.properties.get_mut(tag) {
+                tag_schema.enum_values = Some(vec![renamed]);
+            }
+        }
+        (None, Some(object)) => {
+            object.required.remove(query);
+            object.required.insert(alias.to_string());
+            if let Some(content) = object.properties.remove(query) {
+                object.properties.insert(alias.to_string(), content);
+            }
+        }
+        (_, None) => aliased.enum_values = Some(vec![renamed]),
+    }
+    let metadata = aliased.metadata();
+    metadata.deprecated = true;
+    let notice = format!("Deprecated alias of `{}`.", query);
+    metadata.description = Some(match metadata.description.take() {
+        Some(description) => format!("{}\n\n{}", description, notice),
+        None => notice,
+    });
+
+    let position = position.unwrap();
+    variants.insert(position + 1, aliased.into());
+    true
+}
+
+/// The name of an externally tagged enum variant, i.e. the only required field of an object
+/// or the only value of a string
+fn variant_name(variant: &SchemaObject) -> Option<String> {
+    if let Some(object) = &variant.object {
+        return object.required.iter().next().cloned();
+    }
+    match variant.enum_values.as_deref() {
+        Some([value]) => value.as_str().map(String::from),
+        _ => None,
+    }
+}
+
+/// Returns the name of the tag field if all variants have a required string field with a
+/// single value, as schemars generates for internally and adjacently tagged enums.
+pub(crate) fn enum_tag(variants: &[&SchemaObject]) -> Option<String> {
+    let (first, others) = variants.split_first()?;
+    let object = first.object.as_ref()?;
+    object
+        .required
+        .iter()
+        .find(|field| {
+            tag_value(first, field).is_some()
+                && others
+                    .iter()
+                    .all(|variant| tag_value(variant, field).is_some())
+        })
+        .cloned()
+}
+
+/// The value of a required string field with a single value, i.e. the variant name in the
+/// tag field of a tagged enum variant
+pub(crate) fn tag_value(variant: &SchemaObject, tag: &str) -> Option<String> {
+    let object = variant.object.as_ref()?;
+    if !object.required.contains(tag) {
+        return None;
+    }
+    match object.properties.get(tag)? {
+        Schema::Object(SchemaObject {
+            instance_type: Some(SingleOrVec::Single(ty)),
+            enum_values: Some(values),
+            ..
+        }) if **ty == InstanceType::String && values.len() == 1 => {
+            values[0].as_str().map(String::from)
+        }
+        _ => None,
+    }
+}
+
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum IntegrityError {
     #[error("the structure of the QueryMsg schema was unexpected")]
@@ -121,6 +427,7 @@ pub enum IntegrityError {
 #[cfg(test)]
 mod tests {
     use schemars::schema_for;
+    use serde_json::json;
 
     use super::*;
 
@@ -179,6 +486,284 @@ mod tests {
         assert_eq!(response_schemas, BTreeMap::from([]));
     }
 
+    #[derive(Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum ExtensionMsg {
+        Owner {},
+    }
+
+    impl QueryResponses for ExtensionMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([("owner".to_string(), schema_for!(String))])
+        }
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(untagged)]
+    #[allow(dead_code)]
+    pub enum NestedMsg {
+        Good(GoodMsg),
+        Extension(ExtensionMsg),
+    }
+
+    impl QueryResponses for NestedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            combine_subqueries::<2, Self>([
+                GoodMsg::response_schemas_impl(),
+                ExtensionMsg::response_schemas_impl(),
+            ])
+        }
+    }
+
+    #[test]
+    fn nested_msg_works() {
+        let response_schemas = NestedMsg::response_schemas().unwrap();
+        assert_eq!(
+            response_schemas.keys().collect::<Vec<_>>(),
+            [
+                "account_count",
+                "account_id_for",
+                "balance_for",
+                "liquidity",
+                "owner",
+                "supply"
+            ]
+        );
+        assert_eq!(response_schemas["owner"], schema_for!(String));
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(untagged)]
+    #[allow(dead_code)]
+    pub enum IncompleteNestedMsg {
+        Good(GoodMsg),
+        Extension(ExtensionMsg),
+    }
+
+    impl QueryResponses for IncompleteNestedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            combine_subqueries::<1, Self>([ExtensionMsg::response_schemas_impl()])
+        }
+    }
+
+    #[test]
+    fn nested_msg_checks_integrity_of_flattened_queries() {
+        let err = IncompleteNestedMsg::response_schemas().unwrap_err();
+        assert!(matches!(
+            err,
+            IntegrityError::InconsistentQueries { query_msg, responses }
+                if query_msg.len() == 6 && responses.len() == 1
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "query owner is defined by multiple subqueries of")]
+    fn combine_subqueries_panics_for_duplicates() {
+        combine_subqueries::<2, NestedMsg>([
+            ExtensionMsg::response_schemas_impl(),
+            ExtensionMsg::response_schemas_impl(),
+        ]);
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum InternallyTaggedMsg {
+        BalanceFor { account: String },
+        Supply {},
+        Liquidity,
+    }
+
+    impl QueryResponses for InternallyTaggedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([
+                ("balance_for".to_string(), schema_for!(u128)),
+                ("supply".to_string(), schema_for!(u128)),
+                ("liquidity".to_string(), schema_for!(u128)),
+            ])
+        }
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum AdjacentlyTaggedMsg {
+        BalanceFor { account: String },
+        AccountIdFor(String),
+        Liquidity,
+    }
+
+    impl QueryResponses for AdjacentlyTaggedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([
+                ("balance_for".to_string(), schema_for!(u128)),
+                ("account_id_for".to_string(), schema_for!(u128)),
+            ])
+        }
+    }
+
+    #[test]
+    fn tagged_msg_works() {
+        let response_schemas = InternallyTaggedMsg::response_schemas().unwrap();
+        assert_eq!(
+            response_schemas.keys().collect::<Vec<_>>(),
+            ["balance_for", "liquidity", "supply"]
+        );
+
+        // the tag is used rather than the (missing) content field
+        let err = AdjacentlyTaggedMsg::response_schemas().unwrap_err();
+        assert_eq!(
+            err,
+            IntegrityError::InconsistentQueries {
+                query_msg: BTreeSet::from([
+                    "account_id_for".to_string(),
+                    "balance_for".to_string(),
+                    "liquidity".to_string()
+                ]),
+                responses: BTreeSet::from([
+                    "account_id_for".to_string(),
+                    "balance_for".to_string()
+                ])
+            }
+        );
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum AliasedMsg {
+        /// The balance of the account
+        BalanceFor {
+            account: String,
+        },
+        Supply {},
+        Liquidity,
+    }
+
+    impl QueryResponses for AliasedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([
+                ("balance_for".to_string(), schema_for!(u128)),
+                ("supply".to_string(), schema_for!(u64)),
+                ("liquidity".to_string(), schema_for!(u128)),
+            ])
+        }
+
+        fn query_aliases() -> BTreeMap<String, String> {
+            BTreeMap::from([
+                ("balance".to_string(), "balance_for".to_string()),
+                ("total_supply".to_string(), "supply".to_string()),
+                ("pool".to_string(), "liquidity".to_string()),
+            ])
+        }
+    }
+
+    #[test]
+    fn query_aliases_work() {
+        let response_schemas = AliasedMsg::response_schemas().unwrap();
+        assert_eq!(
+            response_schemas.keys().collect::<Vec<_>>(),
+            [
+                "balance",
+                "balance_for",
+                "liquidity",
+                "pool",
+                "supply",
+                "total_supply"
+            ]
+        );
+        assert_eq!(response_schemas["total_supply"], schema_for!(u64));
+
+        let schema = serde_json::to_value(AliasedMsg::query_schema()).unwrap();
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 6);
+        // the aliases follow the variants they are an alias for, schemars puts unit variants first
+        assert_eq!(variants[0]["enum"], json!(["liquidity"]));
+        assert_eq!(variants[1]["enum"], json!(["pool"]));
+        assert_eq!(variants[1]["deprecated"], json!(true));
+        assert_eq!(variants[2]["required"], json!(["balance_for"]));
+        assert_eq!(variants[3]["required"], json!(["balance"]));
+        assert_eq!(variants[3]["deprecated"], json!(true));
+        assert_eq!(
+            variants[3]["description"],
+            "The balance of the account\n\nDeprecated alias of `balance_for`."
+        );
+        assert_eq!(
+            variants[3]["properties"]["balance"],
+            variants[2]["properties"]["balance_for"]
+        );
+        assert_eq!(variants[5]["required"], json!(["total_supply"]));
+        assert_eq!(variants[5]["description"], "Deprecated alias of `supply`.");
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum AliasedTaggedMsg {
+        BalanceFor { account: String },
+        Supply {},
+    }
+
+    impl QueryResponses for AliasedTaggedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([
+                ("balance_for".to_string(), schema_for!(u128)),
+                ("supply".to_string(), schema_for!(u128)),
+            ])
+        }
+
+        fn query_aliases() -> BTreeMap<String, String> {
+            BTreeMap::from([("balance".to_string(), "balance_for".to_string())])
+        }
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(untagged)]
+    #[allow(dead_code)]
+    pub enum NestedAliasedMsg {
+        Extension(ExtensionMsg),
+        Tagged(AliasedTaggedMsg),
+    }
+
+    impl QueryResponses for NestedAliasedMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            combine_subqueries::<2, Self>([
+                ExtensionMsg::response_schemas_impl(),
+                AliasedTaggedMsg::response_schemas_impl(),
+            ])
+        }
+
+        fn query_aliases() -> BTreeMap<String, String> {
+            combine_query_aliases::<2, Self>([
+                ExtensionMsg::query_aliases(),
+                AliasedTaggedMsg::query_aliases(),
+            ])
+        }
+    }
+
+    #[test]
+    fn query_aliases_work_for_tagged_and_nested_msgs() {
+        let response_schemas = NestedAliasedMsg::response_schemas().unwrap();
+        assert_eq!(
+            response_schemas.keys().collect::<Vec<_>>(),
+            ["balance", "balance_for", "owner", "supply"]
+        );
+
+        // the alias is added to the schema of the child enum
+        let schema = serde_json::to_value(NestedAliasedMsg::query_schema()).unwrap();
+        let variants = schema["definitions"]["AliasedTaggedMsg"]["oneOf"]
+            .as_array()
+            .unwrap();
+        assert_eq!(variants.len(), 3);
+        assert_eq!(
+            variants[1]["properties"]["type"]["enum"],
+            json!(["balance"])
+        );
+        assert_eq!(variants[1]["required"], variants[0]["required"]);
+        assert_eq!(variants[1]["deprecated"], json!(true));
+    }
+
     #[derive(Debug, JsonSchema)]
     #[serde(rename_all = "kebab-case")]
     #[allow(dead_code)]
@@ -18,16 +18,20 @@ fn is_json(path: &path::Path) -> bool {
     }
 }
 
-pub fn remove_schemas(schemas_dir: &path::Path) -> Result<(), io::Error> {
+/// The files in `schemas_dir` that are managed by the schema generator
+pub(crate) fn schema_files(schemas_dir: &path::Path) -> Result<Vec<path::PathBuf>, io::Error> {
     let file_paths = fs::read_dir(schemas_dir)?
         .filter_map(Result::ok) // skip read errors on entries
         .map(|entry| entry.path())
         .filter(|path| is_regular_file(path).unwrap_or(false)) // skip directories and symlinks
         .filter(|path| !is_hidden(path)) // skip hidden
         .filter(|path| is_json(path)) // skip non JSON
-        ;
+        .collect();
+    Ok(file_paths)
+}
 
-    for file_path in file_paths {
+pub fn remove_schemas(schemas_dir: &path::Path) -> Result<(), io::Error> {
+    for file_path in schema_files(schemas_dir)? {
         println!("Removing {:?} …", file_path);
         fs::remove_file(file_path)?;
     }
@@ -0,0 +1,283 @@
+//! Deduplication of the definitions shared by multiple schemas
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::schema::{Metadata, RootSchema, Schema, SchemaObject};
+use schemars::visit::{visit_root_schema, visit_schema_object, Visitor};
+
+/// The command line flag that makes `write_workspace_api` move shared definitions into
+/// a common file
+pub const SHARED_DEFINITIONS_FLAG: &str = "--shared-definitions";
+
+pub(crate) const DEFINITIONS_PREFIX: &str = "#/definitions/";
+
+/// Returns true if the current process was started with
+/// [`--shared-definitions`](SHARED_DEFINITIONS_FLAG), e.g. via
+/// `cargo schema -- --shared-definitions`.
+///
+/// In this mode [`write_workspace_api`](crate::write_workspace_api) writes definitions used
+/// by multiple schemas (e.g. `Coin` or `Uint128`) only once, see
+/// [`extract_shared_definitions`].
+pub fn is_shared_definitions_mode() -> bool {
+    std::env::args()
+        .skip(1)
+        .any(|arg| arg == SHARED_DEFINITIONS_FLAG)
+}
+
+/// Moves the definitions that are used by more than one of the given schemas into a
+/// common schema, which is returned. The references to them are replaced by references
+/// into `common_path`, e.g. `../common.json#/definitions/Coin` for `../common.json`.
+///
+/// A definition is shared if all schemas using it have the same definition (e.g. two
+/// different types called `Config` are not shared) and all the definitions it uses are
+/// shared as well.
+pub fn extract_shared_definitions<'a>(
+    schemas: impl IntoIterator<Item = &'a mut RootSchema>,
+    common_path: &str,
+) -> RootSchema {
+    let mut schemas: Vec<&mut RootSchema> = schemas.into_iter().collect();
+
+    let mut candidates: BTreeMap<&String, (&Schema, usize)> = BTreeMap::new();
+    let mut conflicts = BTreeSet::new();
+    for schema in &schemas {
+        for (name, definition) in &schema.definitions {
+            match candidates.get_mut(name) {
+                Some((existing, count)) if *existing == definition => *count += 1,
+                Some(_) => {
+                    conflicts.insert(name);
+                }
+                None => {
+                    candidates.insert(name, (definition, 1));
+                }
+            }
+        }
+    }
+    let mut shared: BTreeMap<String, Schema> = candidates
+        .into_iter()
+        .filter(|(name, (_, count))| *count > 1 && !conflicts.contains(name))
+        .map(|(name, (definition, _))| (name.clone(), definition.clone()))
+        .collect();
+
+    // The common definitions must not refer to definitions that are not shared
+    loop {
+        let unresolved: Vec<String> = shared
+            .iter()
+            .filter(|(_, definition)| {
+                references(definition)
+                    .iter()
+                    .any(|reference| !shared.contains_key(reference))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        if unresolved.is_empty() {
+            break;
+        }
+        for name in unresolved {
+            shared.remove(&name);
+        }
+    }
+
+    let prefix = format!("{}{}", common_path, DEFINITIONS_PREFIX);
+    let mut rewriter = ReferenceRewriter(|name: &str| {
+        if shared.contains_key(name) {
+            Some(format!("{}{}", prefix, name))
+        } else {
+            None
+        }
+    });
+    for schema in &mut schemas {
+        schema
+            .definitions
+            .retain(|name, _| !shared.contains_key(name));
+        visit_root_schema(&mut rewriter, schema);
+    }
+
+    RootSchema {
+        meta_schema: Some("http://json-schema.org/draft-07/schema#".to_string()),
+        schema: SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                title: Some("Shared definitions".to_string()),
+                ..Metadata::default()
+            })),
+            ..SchemaObject::default()
+        },
+        definitions: shared.into_iter().collect(),
+    }
+}
+
+/// The names of the definitions a schema refers to directly
+pub(crate) fn references(schema: &Schema) -> BTreeSet<String> {
+    let mut collector = ReferenceCollector::default();
+    let mut schema = schema.clone();
+    if let Schema::Object(schema) = &mut schema {
+        collector.visit_schema_object(schema);
+    }
+    collector.references
+}
+
+#[derive(Default)]
+struct ReferenceCollector {
+    references: BTreeSet<String>,
+}
+
+impl Visitor for ReferenceCollector {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(name) = schema
+            .reference
+            .as_deref()
+            .and_then(|reference| reference.strip_prefix(DEFINITIONS_PREFIX))
+        {
+            self.references.insert(name.to_string());
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+/// Replaces references to definitions (e.g. `#/definitions/Coin`) by the reference the
+/// function returns for the definition name. References it returns `None` for are kept.
+pub(crate) struct ReferenceRewriter<F>(pub F);
+
+impl<F: FnMut(&str) -> Option<String>> Visitor for ReferenceRewriter<F> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(reference) = &mut schema.reference {
+            if let Some(rewritten) = reference
+                .strip_prefix(DEFINITIONS_PREFIX)
+                .and_then(|name| (self.0)(name))
+            {
+                *reference = rewritten;
+            }
+        }
+        visit_schema_object(self, schema)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    pub struct Uint128(String);
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    pub struct Coin {
+        denom: String,
+        amount: Uint128,
+    }
+
+    pub mod a {
+        use super::*;
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        pub struct Config {
+            pub owner: String,
+        }
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        pub struct InstantiateMsg {
+            pub funds: Vec<Coin>,
+            pub config: Config,
+        }
+    }
+
+    pub mod b {
+        use super::*;
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        pub struct Config {
+            pub admin: Option<String>,
+        }
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        pub struct ExecuteMsg {
+            pub fee: Coin,
+            pub config: Config,
+        }
+    }
+
+    #[test]
+    fn extract_shared_definitions_works() {
+        let mut instantiate = schema_for!(a::InstantiateMsg);
+        let mut execute = schema_for!(b::ExecuteMsg);
+        let mut single = schema_for!(Uint128);
+
+        let common = extract_shared_definitions(
+            [&mut instantiate, &mut execute, &mut single],
+            "common.json",
+        );
+        assert_eq!(
+            common.definitions.keys().collect::<Vec<_>>(),
+            ["Coin", "Uint128"]
+        );
+        // references within the common definitions stay local
+        let coin = serde_json::to_value(&common.definitions["Coin"]).unwrap();
+        assert_eq!(
+            coin["properties"]["amount"]["$ref"],
+            "#/definitions/Uint128"
+        );
+
+        // Config is defined differently and thus not shared
+        assert_eq!(
+            instantiate.definitions.keys().collect::<Vec<_>>(),
+            ["Config"]
+        );
+        assert_eq!(execute.definitions.keys().collect::<Vec<_>>(), ["Config"]);
+        let instantiate = serde_json::to_value(&instantiate).unwrap();
+        assert_eq!(
+            instantiate["properties"]["funds"]["items"]["$ref"],
+            "common.json#/definitions/Coin"
+        );
+        assert_eq!(
+            instantiate["properties"]["config"]["$ref"],
+            "#/definitions/Config"
+        );
+        let execute = serde_json::to_value(&execute).unwrap();
+        assert_eq!(
+            execute["properties"]["fee"]["$ref"],
+            "common.json#/definitions/Coin"
+        );
+        // the root schema of a type is not a definition
+        assert!(single.definitions.is_empty());
+    }
+
+    #[test]
+    fn extract_shared_definitions_keeps_definitions_with_unshared_dependencies() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Wrapper {
+            config: a::Config,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct First {
+            wrapper: Wrapper,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Second {
+            wrapper: Wrapper,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Third {
+            config: b::Config,
+        }
+
+        let mut first = schema_for!(First);
+        let mut second = schema_for!(Second);
+        // `Config` is defined differently in `Third`, so `Wrapper` cannot be shared either
+        let mut third = schema_for!(Third);
+        let common = extract_shared_definitions([&mut first, &mut second, &mut third], "c.json");
+        assert!(common.definitions.is_empty());
+        assert_eq!(
+            first.definitions.keys().collect::<Vec<_>>(),
+            ["Config", "Wrapper"]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use schemars::schema::RootSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::events::is_false;
+
+pub use cosmwasm_schema_derive::ContractState;
+
+/// A trait for declaring the storage layout of a contract, such that indexers can decode the
+/// raw state and auditors can review the layout without reading the code that writes it.
+/// The items end up in the `state` of the generated API description.
+///
+/// Using the derive macro on an enum with one variant per storage item is the preferred way of
+/// implementing this trait. Each variant has the type of the stored value as its only field.
+/// The namespace is the variant name in snake_case (overridable with
+/// `#[state(namespace = "...")]`) and every `#[state(key = "...")]` adds a key component
+/// with the given [`KeyEncoding`], such that items without keys are singletons and items with
+/// keys are maps. Doc comments end up as descriptions and items marked with `#[deprecated]`
+/// are declared as deprecated.
+///
+/// The namespace is length-prefixed like in `Singleton` and `Bucket` of cosmwasm-storage,
+/// unless the item is marked with `#[state(raw)]` for values written with `Storage::set`
+/// directly. In a map, all key components but the last one are length-prefixed as well, like
+/// nested namespaces.
+///
+/// # Example
+/// ```
+/// use cosmwasm_schema::{cw_serde, ContractState, KeyEncoding};
+///
+/// #[cw_serde]
+/// pub struct Config {
+///     pub owner: String,
+/// }
+///
+/// #[derive(ContractState)]
+/// pub enum State {
+///     /// The configuration set on instantiation
+///     Config(Config),
+///     /// The balances by address
+///     #[state(namespace = "balance", key = "utf8")]
+///     Balances(u128),
+/// }
+///
+/// let state = State::state_schemas();
+/// assert_eq!(state[0].namespace, "config");
+/// assert_eq!(state[0].keys, vec![]);
+/// assert_eq!(state[0].description.as_deref(), Some("The configuration set on instantiation"));
+/// assert_eq!(state[1].namespace, "balance");
+/// assert_eq!(state[1].keys, vec![KeyEncoding::Utf8]);
+/// ```
+pub trait ContractState {
+    fn state_schemas() -> Vec<StateSchema>;
+}
+
+/// An item in the storage of a contract, i.e. a singleton or a map
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StateSchema {
+    /// The key of a singleton or the key prefix of the entries of a map
+    pub namespace: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the item will not be written anymore in a future version
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+    /// Whether the namespace is used as is instead of being length-prefixed
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub raw: bool,
+    /// The encodings of the key components following the namespace, empty for singletons
+    #[serde(default)]
+    pub keys: Vec<KeyEncoding>,
+    /// The schema of the JSON stored under the key(s)
+    pub value: RootSchema,
+}
+
+impl StateSchema {
+    pub fn new(namespace: impl Into<String>, value: RootSchema) -> Self {
+        StateSchema {
+            namespace: namespace.into(),
+            description: None,
+            deprecated: false,
+            raw: false,
+            keys: vec![],
+            value,
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    pub fn raw(mut self) -> Self {
+        self.raw = true;
+        self
+    }
+
+    /// Adds a key component, turning a singleton into a map
+    pub fn key(mut self, key: KeyEncoding) -> Self {
+        self.keys.push(key);
+        self
+    }
+}
+
+/// How a key component of a [`StateSchema`] is encoded into bytes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyEncoding {
+    /// The UTF-8 bytes of a string, e.g. an address
+    Utf8,
+    /// The big endian bytes of an unsigned integer, such that keys are sorted numerically
+    BigEndian,
+    /// Bytes without further structure, e.g. a hash
+    Raw,
+}
+
+impl KeyEncoding {
+    /// The name used in the API file
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            KeyEncoding::Utf8 => "utf8",
+            KeyEncoding::BigEndian => "big_endian",
+            KeyEncoding::Raw => "raw",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema_for;
+
+    #[test]
+    fn state_schema_serializes_like_the_idl() {
+        let state = StateSchema::new("allowance", schema_for!(u64))
+            .description("Allowances by owner and spender")
+            .key(KeyEncoding::Utf8)
+            .key(KeyEncoding::Utf8);
+        assert_eq!(
+            serde_json::to_value(&state).unwrap(),
+            serde_json::json!({
+                "namespace": "allowance",
+                "description": "Allowances by owner and spender",
+                "keys": ["utf8", "utf8"],
+                "value": {
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "title": "uint64",
+                    "type": "integer",
+                    "format": "uint64",
+                    "minimum": 0.0
+                }
+            })
+        );
+        let parsed: StateSchema =
+            serde_json::from_value(serde_json::to_value(&state).unwrap()).unwrap();
+        assert_eq!(parsed, state);
+
+        let config = StateSchema::new("config", schema_for!(String))
+            .raw()
+            .deprecated();
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["raw"], true);
+        assert_eq!(json["deprecated"], true);
+        assert_eq!(json["keys"], serde_json::json!([]));
+    }
+}
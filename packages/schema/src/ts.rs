@@ -0,0 +1,738 @@
+//! Generation of TypeScript type definitions and typed clients from a contract's API.
+//!
+//! This is meant for frontends. The generated types describe the JSON the contract
+//! expects and returns, and the generated clients turn every message variant into a
+//! method, such that a changed contract API shows up as a type error in the frontend.
+//!
+//! Mapping rules:
+//!
+//! - Objects become interfaces. Optional fields are marked with `?`.
+//! - Enums become unions of their variants, e.g. `{ send: ExecuteMsgSend } | "reset"`.
+//!   The contents of data variants are interfaces named after the enum and variant.
+//! - Enums without data become unions of string literals.
+//! - All integers are `number`. Note that JavaScript numbers cannot represent integers
+//!   above 2^53 exactly, so use `Uint64`/`Uint128` (which are strings) in messages.
+//! - Tuples become tuple types, maps become `Record<string, T>`.
+//! - Everything else (arbitrary JSON) becomes `unknown`.
+//! - Fields and data variants marked with `#[deprecated]` get a `@deprecated` tag.
+//!
+//! If the API has a `QueryMsg`, a query client class with one method per query variant is
+//! added. Similarly an execute client is added for the `ExecuteMsg`. The methods take the
+//! variant content as argument and the query methods return the response type of the
+//! query. The clients wrap a `CosmWasmClient` or `SigningCosmWasmClient` of
+//! [CosmJS](https://github.com/cosmos/cosmjs) or anything with compatible methods.
+
+use std::collections::{BTreeMap, HashMap};
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+
+use crate::casing::{to_camel_case, to_pascal_case};
+use crate::codegen::{
+    documentation, generated_notice, is_named, is_struct, property_schema_documentation,
+    single_property, string_values, variants, Generator, TypeNames,
+};
+
+/// The command line flag that makes `write_api!` also export TypeScript definitions
+pub const TS_FLAG: &str = "--ts";
+
+const UNKNOWN: &str = "unknown";
+/// Appended to the doc comments of deprecated fields and variants, which editors strike through
+const DEPRECATED: &str = "@deprecated";
+
+const QUERY_INTERFACE: &str = "CosmWasmQueryClient";
+const EXECUTE_INTERFACE: &str = "CosmWasmExecuteClient";
+const COIN: &str = "CosmWasmCoin";
+const FEE: &str = "CosmWasmFee";
+
+/// Returns true if the current process was started with [`--ts`](TS_FLAG),
+/// e.g. via `cargo schema -- --ts`.
+pub fn is_ts_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == TS_FLAG)
+}
+
+/// Renders TypeScript type definitions and clients for the given root schemas.
+///
+/// `messages` are the top level messages with their type names. For `responses`
+/// the type name is taken from the schema title. The client classes are named after
+/// the contract, e.g. `CryptoVerifyQueryClient` for `crypto-verify`.
+pub(crate) fn render_ts<'a>(
+    contract_name: &str,
+    contract_version: &str,
+    messages: impl IntoIterator<Item = (&'a str, &'a RootSchema)>,
+    responses: Option<&'a BTreeMap<String, RootSchema>>,
+) -> String {
+    let mut gen = TsGenerator::default();
+    // Make sure the types of the contract do not shadow the client types
+    for name in [QUERY_INTERFACE, EXECUTE_INTERFACE, COIN, FEE] {
+        gen.reserve(name);
+    }
+    let contract = to_pascal_case(contract_name);
+    let query_client = gen.reserve(&format!("{}QueryClient", contract));
+    let execute_client = gen.reserve(&format!("{}ExecuteClient", contract));
+
+    let mut query = None;
+    let mut execute = None;
+    for (name, root) in messages {
+        // Every message is a named type in TypeScript
+        let ty = gen.root(name, root).unwrap();
+        match name {
+            "QueryMsg" => query = Some((ty, root)),
+            "ExecuteMsg" => execute = Some((ty, root)),
+            _ => {}
+        }
+    }
+    let mut response_types = BTreeMap::new();
+    for (variant, root) in responses.into_iter().flatten() {
+        let ty = if is_named(&root.schema) {
+            let name = root
+                .schema
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.title.clone())
+                .unwrap_or_else(|| format!("{}_response", variant));
+            gen.root(&name, root).unwrap()
+        } else {
+            // Responses like `String` or `Vec<Coin>` map to TypeScript types directly
+            gen.enter(root);
+            gen.type_for(&root.schema, &format!("{}_response", variant))
+        };
+        response_types.insert(variant.as_str(), ty);
+    }
+
+    let mut clients = Vec::new();
+    if let Some((ty, root)) = query {
+        let methods = gen.query_methods(&ty, &root.schema, &response_types);
+        if !methods.is_empty() {
+            clients.push(format!(
+                "export interface {interface} {{\n  \
+                 queryContractSmart(address: string, queryMsg: unknown): Promise<unknown>;\n}}\n\n\
+                 export class {class} {{\n  \
+                 readonly client: {interface};\n  \
+                 readonly contractAddress: string;\n\n  \
+                 constructor(client: {interface}, contractAddress: string) {{\n    \
+                 this.client = client;\n    \
+                 this.contractAddress = contractAddress;\n  \
+                 }}\n{methods}}}\n",
+                interface = QUERY_INTERFACE,
+                class = query_client,
+                methods = methods,
+            ));
+        }
+    }
+    if let Some((ty, root)) = execute {
+        let methods = gen.execute_methods(&ty, &root.schema);
+        if !methods.is_empty() {
+            clients.push(format!(
+                "export interface {coin} {{\n  \
+                 denom: string;\n  \
+                 amount: string;\n}}\n\n\
+                 export type {fee} = number | \"auto\" | {{ amount: readonly {coin}[]; gas: string }};\n\n\
+                 export interface {interface} {{\n  \
+                 execute(\n    \
+                 senderAddress: string,\n    \
+                 contractAddress: string,\n    \
+                 msg: unknown,\n    \
+                 fee: {fee},\n    \
+                 memo?: string,\n    \
+                 funds?: readonly {coin}[],\n  \
+                 ): Promise<unknown>;\n}}\n\n\
+                 export class {class} {{\n  \
+                 readonly client: {interface};\n  \
+                 readonly sender: string;\n  \
+                 readonly contractAddress: string;\n\n  \
+                 constructor(client: {interface}, sender: string, contractAddress: string) {{\n    \
+                 this.client = client;\n    \
+                 this.sender = sender;\n    \
+                 this.contractAddress = contractAddress;\n  \
+                 }}\n{methods}}}\n",
+                coin = COIN,
+                fee = FEE,
+                interface = EXECUTE_INTERFACE,
+                class = execute_client,
+                methods = methods,
+            ));
+        }
+    }
+
+    let mut out = format!("// {}\n", generated_notice(contract_name, contract_version));
+    for item in gen.types.iter().chain(&clients) {
+        out.push('\n');
+        out.push_str(item);
+    }
+    out
+}
+
+#[derive(Default)]
+struct TsGenerator<'a> {
+    /// The rendered type definitions in output order
+    types: Vec<String>,
+    type_names: TypeNames<'a>,
+    /// Maps enum type names and variant names to the types of the variant contents
+    cases: HashMap<(String, String), String>,
+}
+
+impl<'a> Generator<'a> for TsGenerator<'a> {
+    type Type = String;
+
+    fn type_names(&mut self) -> &mut TypeNames<'a> {
+        &mut self.type_names
+    }
+
+    fn raw(&mut self) -> String {
+        UNKNOWN.to_string()
+    }
+
+    fn optional(&mut self, ty: String) -> String {
+        nullable(ty)
+    }
+
+    fn named(&mut self, name: &str) -> String {
+        name.to_string()
+    }
+
+    /// Renders a named type definition
+    fn define(&mut self, name: &str, schema: &SchemaObject) {
+        // Reserve the position of this type before the types it uses
+        let index = self.types.len();
+        self.types.push(String::new());
+
+        let code = if let Some(variants) = variants(schema) {
+            self.enumeration(name, variants)
+        } else if let Some(values) = string_values(schema) {
+            let values: Vec<String> = values.iter().map(|value| quote(value)).collect();
+            format!("export type {} = {};\n", name, values.join(" | "))
+        } else if is_struct(schema) {
+            self.structure(name, schema)
+        } else {
+            format!("export type {} = {};\n", name, self.type_for(schema, name))
+        };
+        self.types[index] = with_doc_comment(documentation(schema, DEPRECATED).as_deref(), code);
+    }
+
+    fn unnamed(
+        &mut self,
+        instance_type: InstanceType,
+        schema: &SchemaObject,
+        context: &str,
+    ) -> String {
+        match instance_type {
+            InstanceType::Boolean => "boolean".to_string(),
+            InstanceType::String => "string".to_string(),
+            InstanceType::Number | InstanceType::Integer => "number".to_string(),
+            InstanceType::Array => match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+                Some(SingleOrVec::Single(items)) => {
+                    array(self.type_of(items, &format!("{}Item", context)))
+                }
+                Some(SingleOrVec::Vec(items)) => {
+                    let items: Vec<String> = items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            self.type_of(item, &format!("{}Item{}", context, index + 1))
+                        })
+                        .collect();
+                    format!("[{}]", items.join(", "))
+                }
+                None => array(UNKNOWN.to_string()),
+            },
+            InstanceType::Object => {
+                match schema
+                    .object
+                    .as_ref()
+                    .and_then(|o| o.additional_properties.as_ref())
+                {
+                    Some(values) => format!(
+                        "Record<string, {}>",
+                        self.type_of(values, &format!("{}Value", context))
+                    ),
+                    None => format!("Record<string, {}>", UNKNOWN),
+                }
+            }
+            InstanceType::Null => "null".to_string(),
+        }
+    }
+}
+
+impl TsGenerator<'_> {
+    fn structure(&mut self, name: &str, schema: &SchemaObject) -> String {
+        let object = schema.object.as_ref().unwrap();
+        let mut fields = String::new();
+        for (property, property_schema) in &object.properties {
+            let context = format!("{}{}", name, to_pascal_case(property));
+            let ty = self.type_of(property_schema, &context);
+            let optional = if object.required.contains(property) {
+                ""
+            } else {
+                "?"
+            };
+            if let Some(docs) = property_schema_documentation(property_schema, DEPRECATED) {
+                fields.push_str(&doc_comment(&docs, "  "));
+            }
+            fields.push_str(&format!(
+                "  {}{}: {};\n",
+                property_name(property),
+                optional,
+                ty
+            ));
+        }
+        if fields.is_empty() {
+            format!("export interface {} {{}}\n", name)
+        } else {
+            format!("export interface {} {{\n{}}}\n", name, fields)
+        }
+    }
+
+    /// Renders an enum, i.e. a `oneOf` or `anyOf` as generated by schemars for Rust enums
+    fn enumeration(&mut self, name: &str, variants: &[Schema]) -> String {
+        let mut cases = String::new();
+        for (index, variant) in variants.iter().enumerate() {
+            let (ty, docs) = match variant {
+                Schema::Object(variant) => {
+                    let ty = if let Some(values) = string_values(variant) {
+                        let values: Vec<String> = values.iter().map(|value| quote(value)).collect();
+                        values.join(" | ")
+                    } else if let Some((property, content)) = single_property(variant) {
+                        let context = format!("{}{}", name, to_pascal_case(property));
+                        let ty = self.type_of(content, &context);
+                        self.cases
+                            .insert((name.to_string(), property.to_string()), ty.clone());
+                        format!("{{ {}: {} }}", property_name(property), ty)
+                    } else {
+                        self.type_for(variant, &format!("{}{}", name, index + 1))
+                    };
+                    (ty, documentation(variant, DEPRECATED))
+                }
+                Schema::Bool(_) => (UNKNOWN.to_string(), None),
+            };
+            if let Some(docs) = docs {
+                cases.push_str(&doc_comment(&docs, "  "));
+            }
+            cases.push_str(&format!("  | {}\n", ty));
+        }
+        format!("export type {} =\n{};\n", name, cases.trim_end())
+    }
+
+    /// Renders the query client methods, one per query variant
+    fn query_methods(
+        &mut self,
+        query_type: &str,
+        query: &SchemaObject,
+        responses: &BTreeMap<&str, String>,
+    ) -> String {
+        self.methods(query_type, query, "", |variant, msg| {
+            let response = responses
+                .get(variant)
+                .map(String::as_str)
+                .unwrap_or(UNKNOWN);
+            (
+                format!("Promise<{}>", response),
+                format!(
+                    "this.client.queryContractSmart(this.contractAddress, {}) as Promise<{}>",
+                    msg, response
+                ),
+            )
+        })
+    }
+
+    /// Renders the execute client methods, one per execute variant
+    fn execute_methods(&mut self, execute_type: &str, execute: &SchemaObject) -> String {
+        let extra_params = format!("fee: {}, memo?: string, funds?: readonly {}[]", FEE, COIN);
+        self.methods(execute_type, execute, &extra_params, |_, msg| {
+            (
+                "Promise<unknown>".to_string(),
+                format!(
+                    "this.client.execute(this.sender, this.contractAddress, {}, fee, memo, funds)",
+                    msg
+                ),
+            )
+        })
+    }
+
+    /// Renders one client method per variant of the given message enum. `call` returns
+    /// the return type and the expression sending the message in the variable `msg`.
+    /// The `extra_params` are appended to the parameters of each method.
+    fn methods(
+        &mut self,
+        msg_type: &str,
+        msg: &SchemaObject,
+        extra_params: &str,
+        call: impl Fn(&str, &str) -> (String, String),
+    ) -> String {
+        let mut methods = String::new();
+        for variant in variants(msg).into_iter().flatten() {
+            let variant = match variant {
+                Schema::Object(variant) => variant,
+                Schema::Bool(_) => continue,
+            };
+            let mut calls = Vec::new();
+            if let Some(values) = string_values(variant) {
+                for value in values {
+                    calls.push((value.clone(), None, quote(&value)));
+                }
+            } else if let Some((property, content)) = single_property(variant) {
+                let args = self
+                    .cases
+                    .get(&(msg_type.to_string(), property.to_string()))
+                    .cloned()
+                    .unwrap_or_else(|| UNKNOWN.to_string());
+                // Variants like `Config {}` need no arguments
+                let is_empty = match content {
+                    Schema::Object(content) => {
+                        is_struct(content)
+                            && match &content.object {
+                                Some(object) => object.properties.is_empty(),
+                                None => true,
+                            }
+                    }
+                    Schema::Bool(_) => false,
+                };
+                if is_empty {
+                    calls.push((
+                        property.to_string(),
+                        None,
+                        format!("{{ {}: {{}} }}", property_name(property)),
+                    ));
+                } else {
+                    calls.push((
+                        property.to_string(),
+                        Some(args),
+                        format!("{{ {}: args }}", property_name(property)),
+                    ));
+                }
+            } else {
+                continue;
+            }
+
+            for (variant_name, args, value) in calls {
+                let mut params = Vec::new();
+                if let Some(args) = args {
+                    params.push(format!("args: {}", args));
+                }
+                if !extra_params.is_empty() {
+                    params.push(extra_params.to_string());
+                }
+                let (return_type, expression) = call(&variant_name, "msg");
+                methods.push('\n');
+                if let Some(docs) = documentation(variant, DEPRECATED) {
+                    methods.push_str(&doc_comment(&docs, "  "));
+                }
+                methods.push_str(&format!(
+                    "  {}({}): {} {{\n    const msg: {} = {};\n    return {};\n  }}\n",
+                    to_camel_case(&variant_name),
+                    params.join(", "),
+                    return_type,
+                    msg_type,
+                    value,
+                    expression
+                ));
+            }
+        }
+        methods
+    }
+}
+
+fn nullable(ty: String) -> String {
+    if ty == UNKNOWN || ty.ends_with(" | null") {
+        ty
+    } else {
+        format!("{} | null", ty)
+    }
+}
+
+fn array(item: String) -> String {
+    if item.contains(" | ") {
+        format!("({})[]", item)
+    } else {
+        format!("{}[]", item)
+    }
+}
+
+/// A string literal, which uses the same escaping as JSON
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+/// Property names that are no valid identifiers need to be quoted
+fn property_name(name: &str) -> String {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+fn doc_comment(text: &str, indent: &str) -> String {
+    let text = text.replace("*/", "*\\/");
+    let mut lines = text.lines();
+    match (lines.next(), lines.next()) {
+        (Some(line), None) => format!("{}/** {} */\n", indent, line),
+        _ => {
+            let mut out = format!("{}/**\n", indent);
+            for line in text.lines() {
+                if line.is_empty() {
+                    out.push_str(&format!("{} *\n", indent));
+                } else {
+                    out.push_str(&format!("{} * {}\n", indent, line));
+                }
+            }
+            out.push_str(&format!("{} */\n", indent));
+            out
+        }
+    }
+}
+
+fn with_doc_comment(description: Option<&str>, code: String) -> String {
+    match description {
+        Some(description) => doc_comment(description, "") + &code,
+        None => code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::{schema_for, JsonSchema};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Color {
+        Red,
+        DarkBlue,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Coin {
+        denom: String,
+        amount: String,
+    }
+
+    /// Instantiates the contract
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct InstantiateMsg {
+        /// The owner
+        owner: String,
+        admin: Option<String>,
+        color: Color,
+        funds: Vec<Coin>,
+        limits: Vec<Option<u32>>,
+        pair: (String, u64),
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum ExecuteMsg {
+        /// Sends funds
+        Send {
+            to: String,
+            amount: Coin,
+        },
+        Reset {},
+        Freeze,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum QueryMsg {
+        /// Returns the balance
+        Balance {
+            address: String,
+        },
+        Owner {},
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Mixed {
+        Unit,
+        Data {
+            #[serde(rename = "the-value")]
+            value: std::collections::BTreeMap<String, bool>,
+        },
+    }
+
+    #[test]
+    fn render_ts_works_for_mixed_enums() {
+        let mixed = schema_for!(Mixed);
+        let ts = render_ts("example", "0.1.0", [("Mixed", &mixed)], None);
+        assert_eq!(
+            ts,
+            r#"// Code generated by cosmwasm-schema for example 0.1.0. DO NOT EDIT.
+
+export type Mixed =
+  | "unit"
+  | { data: MixedData };
+
+export interface MixedData {
+  "the-value": Record<string, boolean>;
+}
+"#
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum LegacyQueryMsg {
+        /// Returns the price
+        #[deprecated]
+        Price {
+            #[deprecated]
+            denom: Option<String>,
+        },
+    }
+
+    #[test]
+    fn render_ts_marks_deprecated_items() {
+        let query = schema_for!(LegacyQueryMsg);
+        let ts = render_ts("example", "0.1.0", [("QueryMsg", &query)], None);
+        assert!(ts.contains(
+            "export type QueryMsg =\n  /**\n   * Returns the price\n   *\n   * @deprecated\n   */\n  | { price: QueryMsgPrice };"
+        ));
+        assert!(ts.contains("  /** @deprecated */\n  denom?: string | null;"));
+        assert!(ts.contains("   * @deprecated\n   */\n  price(args: QueryMsgPrice)"));
+    }
+
+    #[test]
+    fn render_ts_works() {
+        let instantiate = schema_for!(InstantiateMsg);
+        let execute = schema_for!(ExecuteMsg);
+        let query = schema_for!(QueryMsg);
+        let mut responses = BTreeMap::new();
+        responses.insert("balance".to_string(), schema_for!(Coin));
+        responses.insert("owner".to_string(), schema_for!(String));
+
+        let ts = render_ts(
+            "crypto-verify",
+            "0.1.0",
+            [
+                ("InstantiateMsg", &instantiate),
+                ("ExecuteMsg", &execute),
+                ("QueryMsg", &query),
+            ],
+            Some(&responses),
+        );
+        assert_eq!(
+            ts,
+            r#"// Code generated by cosmwasm-schema for crypto-verify 0.1.0. DO NOT EDIT.
+
+/** Instantiates the contract */
+export interface InstantiateMsg {
+  admin?: string | null;
+  color: Color;
+  funds: Coin[];
+  limits: (number | null)[];
+  /** The owner */
+  owner: string;
+  pair: [string, number];
+}
+
+export type Color = "red" | "dark_blue";
+
+export interface Coin {
+  amount: string;
+  denom: string;
+}
+
+export type ExecuteMsg =
+  | "freeze"
+  /** Sends funds */
+  | { send: ExecuteMsgSend }
+  | { reset: ExecuteMsgReset };
+
+export interface ExecuteMsgSend {
+  amount: Coin;
+  to: string;
+}
+
+export interface ExecuteMsgReset {}
+
+export type QueryMsg =
+  /** Returns the balance */
+  | { balance: QueryMsgBalance }
+  | { owner: QueryMsgOwner };
+
+export interface QueryMsgBalance {
+  address: string;
+}
+
+export interface QueryMsgOwner {}
+
+export interface CosmWasmQueryClient {
+  queryContractSmart(address: string, queryMsg: unknown): Promise<unknown>;
+}
+
+export class CryptoVerifyQueryClient {
+  readonly client: CosmWasmQueryClient;
+  readonly contractAddress: string;
+
+  constructor(client: CosmWasmQueryClient, contractAddress: string) {
+    this.client = client;
+    this.contractAddress = contractAddress;
+  }
+
+  /** Returns the balance */
+  balance(args: QueryMsgBalance): Promise<Coin> {
+    const msg: QueryMsg = { balance: args };
+    return this.client.queryContractSmart(this.contractAddress, msg) as Promise<Coin>;
+  }
+
+  owner(): Promise<string> {
+    const msg: QueryMsg = { owner: {} };
+    return this.client.queryContractSmart(this.contractAddress, msg) as Promise<string>;
+  }
+}
+
+export interface CosmWasmCoin {
+  denom: string;
+  amount: string;
+}
+
+export type CosmWasmFee = number | "auto" | { amount: readonly CosmWasmCoin[]; gas: string };
+
+export interface CosmWasmExecuteClient {
+  execute(
+    senderAddress: string,
+    contractAddress: string,
+    msg: unknown,
+    fee: CosmWasmFee,
+    memo?: string,
+    funds?: readonly CosmWasmCoin[],
+  ): Promise<unknown>;
+}
+
+export class CryptoVerifyExecuteClient {
+  readonly client: CosmWasmExecuteClient;
+  readonly sender: string;
+  readonly contractAddress: string;
+
+  constructor(client: CosmWasmExecuteClient, sender: string, contractAddress: string) {
+    this.client = client;
+    this.sender = sender;
+    this.contractAddress = contractAddress;
+  }
+
+  freeze(fee: CosmWasmFee, memo?: string, funds?: readonly CosmWasmCoin[]): Promise<unknown> {
+    const msg: ExecuteMsg = "freeze";
+    return this.client.execute(this.sender, this.contractAddress, msg, fee, memo, funds);
+  }
+
+  /** Sends funds */
+  send(args: ExecuteMsgSend, fee: CosmWasmFee, memo?: string, funds?: readonly CosmWasmCoin[]): Promise<unknown> {
+    const msg: ExecuteMsg = { send: args };
+    return this.client.execute(this.sender, this.contractAddress, msg, fee, memo, funds);
+  }
+
+  reset(fee: CosmWasmFee, memo?: string, funds?: readonly CosmWasmCoin[]): Promise<unknown> {
+    const msg: ExecuteMsg = { reset: {} };
+    return this.client.execute(this.sender, this.contractAddress, msg, fee, memo, funds);
+  }
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Export of the APIs of all contracts in a workspace into one directory
+
+use std::collections::BTreeSet;
+use std::fs::{create_dir_all, write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::check::{check_no_stale_schemas, check_schema_file, is_check_mode, SchemaCheckError};
+use crate::idl::{Api, EncodeError, JsonApi, IDL_VERSION};
+use crate::remove::remove_schemas;
+use crate::shared::{extract_shared_definitions, is_shared_definitions_mode};
+
+/// The name of the index file listing all contracts of a workspace export
+pub const WORKSPACE_INDEX_FILE: &str = "index.json";
+
+/// The name of the file with the definitions shared by the contracts of a workspace export,
+/// see [`is_shared_definitions_mode`](crate::is_shared_definitions_mode)
+pub const WORKSPACE_DEFINITIONS_FILE: &str = "common.json";
+
+#[derive(Error, Debug)]
+pub enum WorkspaceExportError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    Encode(#[from] EncodeError),
+    #[error("{0}")]
+    Check(#[from] SchemaCheckError),
+    #[error("Contract name '{0}' is used more than once")]
+    DuplicateContract(String),
+}
+
+#[derive(Serialize)]
+struct WorkspaceIndex {
+    idl_version: String,
+    /// Path of the shared definitions relative to the index file, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    definitions: Option<String>,
+    contracts: Vec<IndexEntry>,
+}
+
+#[derive(Serialize)]
+struct IndexEntry {
+    contract_name: String,
+    contract_version: String,
+    /// Path of the API file relative to the index file
+    path: String,
+}
+
+/// Writes the APIs of multiple contracts into `out_dir`, one directory per contract,
+/// and an [`index.json`](WORKSPACE_INDEX_FILE) listing all of them.
+///
+/// The resulting layout is
+///
+/// ```text
+/// out_dir
+/// ├── index.json
+/// ├── cw20
+/// │   └── cw20.json
+/// └── cw721
+///     └── cw721.json
+/// ```
+///
+/// where each `<name>.json` has the same content `write_api!` would generate for the contract.
+/// This allows a workspace with many contracts to use a single schema binary instead of one
+/// per contract crate. Existing JSON files in the contract directories are removed.
+///
+/// When the schema binary runs with `--shared-definitions` (see
+/// [`is_shared_definitions_mode`](crate::is_shared_definitions_mode)), the definitions used by
+/// multiple schemas are written only once into [`common.json`](WORKSPACE_DEFINITIONS_FILE)
+/// next to the index and referenced from there, e.g. `../common.json#/definitions/Coin`.
+/// The API files are then not self-contained anymore, such that they cannot be used with
+/// [`diff_apis`](crate::diff_apis).
+///
+/// When the schema binary runs with `--check` (see [`is_check_mode`](crate::is_check_mode)),
+/// nothing is written and an error is returned if any file is missing or outdated, or if a
+/// contract directory contains schema files that would be removed.
+///
+/// Returns the path of the index file.
+///
+/// # Example
+///
+/// ```no_run
+/// use cosmwasm_schema::{cw_serde, generate_api, write_workspace_api};
+///
+/// # #[cw_serde]
+/// # struct Cw20InstantiateMsg {}
+/// # #[cw_serde]
+/// # struct Cw721InstantiateMsg {}
+/// write_workspace_api(
+///     "schema",
+///     vec![
+///         generate_api! { name: "cw20", version: "0.16.0", instantiate: Cw20InstantiateMsg },
+///         generate_api! { name: "cw721", version: "0.16.0", instantiate: Cw721InstantiateMsg },
+///     ],
+/// )
+/// .unwrap();
+/// ```
+pub fn write_workspace_api(
+    out_dir: impl AsRef<Path>,
+    apis: Vec<Api>,
+) -> Result<PathBuf, WorkspaceExportError> {
+    write_workspace(
+        out_dir.as_ref(),
+        apis,
+        is_check_mode(),
+        is_shared_definitions_mode(),
+    )
+}
+
+fn write_workspace(
+    out_dir: &Path,
+    apis: Vec<Api>,
+    check: bool,
+    shared_definitions: bool,
+) -> Result<PathBuf, WorkspaceExportError> {
+    let mut names = BTreeSet::new();
+    for api in &apis {
+        if !names.insert(api.contract_name.as_str()) {
+            return Err(WorkspaceExportError::DuplicateContract(
+                api.contract_name.clone(),
+            ));
+        }
+    }
+
+    let mut rendered: Vec<(String, String, JsonApi)> = apis
+        .into_iter()
+        .map(|api| {
+            let name = api.contract_name.clone();
+            let version = api.contract_version.clone();
+            (name, version, api.render())
+        })
+        .collect();
+
+    let mut definitions = None;
+    if shared_definitions {
+        let common = extract_shared_definitions(
+            rendered
+                .iter_mut()
+                .flat_map(|(_, _, api)| api.schemas_mut()),
+            &format!("../{}", WORKSPACE_DEFINITIONS_FILE),
+        );
+        let path = out_dir.join(WORKSPACE_DEFINITIONS_FILE);
+        let json = serde_json::to_string_pretty(&common).map_err(EncodeError::from)? + "\n";
+        if check {
+            check_schema_file(&path, &json)?;
+        } else {
+            create_dir_all(out_dir)?;
+            write(&path, json)?;
+            println!("Exported the shared definitions as {}", path.display());
+        }
+        definitions = Some(WORKSPACE_DEFINITIONS_FILE.to_string());
+    }
+
+    let mut contracts = Vec::with_capacity(rendered.len());
+    for (contract_name, contract_version, api) in rendered {
+        let contract_dir = out_dir.join(&contract_name);
+        let file_name = format!("{}.json", contract_name);
+        contracts.push(IndexEntry {
+            path: format!("{}/{}", contract_name, file_name),
+            contract_name,
+            contract_version,
+        });
+
+        let path = contract_dir.join(file_name);
+        let json = api.to_string()? + "\n";
+        if check {
+            check_schema_file(&path, &json)?;
+            check_no_stale_schemas(&contract_dir, &[path])?;
+        } else {
+            create_dir_all(&contract_dir)?;
+            remove_schemas(&contract_dir)?;
+            write(&path, json)?;
+            println!("Exported the full API as {}", path.display());
+        }
+    }
+    contracts.sort_by(|a, b| a.contract_name.cmp(&b.contract_name));
+
+    let index = WorkspaceIndex {
+        idl_version: IDL_VERSION.to_string(),
+        definitions,
+        contracts,
+    };
+    let index_path = out_dir.join(WORKSPACE_INDEX_FILE);
+    let json = serde_json::to_string_pretty(&index).map_err(EncodeError::from)? + "\n";
+    if check {
+        check_schema_file(&index_path, &json)?;
+        println!("The workspace API in {} is up to date", out_dir.display());
+    } else {
+        write(&index_path, json)?;
+        println!("Exported the workspace index as {}", index_path.display());
+    }
+    Ok(index_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema::RootSchema;
+    use schemars::{schema_for, JsonSchema};
+    use serde_json::Value;
+    use std::fs::read_to_string;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Coin {
+        denom: String,
+        amount: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TokenInstantiateMsg {
+        cap: Coin,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct NftInstantiateMsg {
+        price: Option<Coin>,
+    }
+
+    fn api(name: &str, instantiate: RootSchema) -> Api {
+        Api {
+            contract_name: name.to_string(),
+            contract_version: "1.0.0".to_string(),
+            instantiate,
+            execute: None,
+            query: None,
+            migrate: None,
+            sudo: None,
+            ibc_packet: None,
+            ibc_ack: None,
+            reply: None,
+            entry_points: Default::default(),
+            events: None,
+            state: None,
+            responses: None,
+        }
+    }
+
+    fn read_json(path: PathBuf) -> Value {
+        serde_json::from_str(&read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn write_workspace_works_with_shared_definitions() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path().join("schema");
+        let apis = || {
+            vec![
+                api("token", schema_for!(TokenInstantiateMsg)),
+                api("nft", schema_for!(NftInstantiateMsg)),
+            ]
+        };
+
+        let index_path = write_workspace(&out_dir, apis(), false, true).unwrap();
+        let index = read_json(index_path);
+        assert_eq!(index["definitions"], WORKSPACE_DEFINITIONS_FILE);
+
+        let common = read_json(out_dir.join(WORKSPACE_DEFINITIONS_FILE));
+        assert_eq!(common["definitions"]["Coin"]["required"][0], "amount");
+        let token = read_json(out_dir.join("token/token.json"));
+        assert!(token["instantiate"].get("definitions").is_none());
+        assert_eq!(
+            token["instantiate"]["properties"]["cap"]["$ref"],
+            "../common.json#/definitions/Coin"
+        );
+
+        // check mode accepts the written files
+        write_workspace(&out_dir, apis(), true, true).unwrap();
+        assert!(matches!(
+            write_workspace(&out_dir, apis(), true, false).unwrap_err(),
+            WorkspaceExportError::Check(_)
+        ));
+
+        // but not leftovers of previous exports
+        write(out_dir.join("nft/nft_query_msg.json"), "{}\n").unwrap();
+        assert!(matches!(
+            write_workspace(&out_dir, apis(), true, true).unwrap_err(),
+            WorkspaceExportError::Check(SchemaCheckError::Stale { .. })
+        ));
+    }
+}
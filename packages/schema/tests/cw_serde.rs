@@ -0,0 +1,436 @@
+use cosmwasm_schema::{cw_serde, schema_for, Validate, ValidationError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub trait Extension {
+    type Data: Serialize
+        + DeserializeOwned
+        + Clone
+        + std::fmt::Debug
+        + PartialEq
+        + schemars::JsonSchema;
+}
+
+#[cw_serde]
+pub struct Empty {}
+
+impl Extension for Empty {
+    type Data = String;
+}
+
+#[cw_serde]
+pub struct Wrapper<T> {
+    pub inner: T,
+}
+
+#[cw_serde(bound = "T::Data: Serialize + DeserializeOwned")]
+pub struct WithExtension<T: Extension> {
+    pub data: T::Data,
+}
+
+#[cw_serde(bound = "")]
+pub enum ExecuteMsg<T: Extension> {
+    Update { data: T::Data },
+    Wrapped(Wrapper<u32>),
+}
+
+#[test]
+fn generic_struct_works() {
+    let msg = Wrapper { inner: Empty {} };
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(json, r#"{"inner":{}}"#);
+    assert_eq!(serde_json::from_str::<Wrapper<Empty>>(&json).unwrap(), msg);
+
+    let schema = serde_json::to_value(schema_for!(Wrapper<Empty>)).unwrap();
+    assert_eq!(schema["title"], "Wrapper_for_Empty");
+}
+
+#[test]
+fn bound_works() {
+    let msg = WithExtension::<Empty> {
+        data: "foo".to_string(),
+    };
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(json, r#"{"data":"foo"}"#);
+    assert_eq!(
+        serde_json::from_str::<WithExtension<Empty>>(&json).unwrap(),
+        msg
+    );
+
+    let msg = ExecuteMsg::<Empty>::Update {
+        data: "bar".to_string(),
+    };
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(json, r#"{"update":{"data":"bar"}}"#);
+    assert_eq!(
+        serde_json::from_str::<ExecuteMsg<Empty>>(&json).unwrap(),
+        msg
+    );
+
+    let schema = serde_json::to_value(schema_for!(ExecuteMsg<Empty>)).unwrap();
+    assert_eq!(schema["title"], "ExecuteMsg_for_Empty");
+}
+
+#[cw_serde]
+pub struct Hashes<const N: usize> {
+    pub sha256: [u8; 32],
+    pub history: [[u8; 4]; N],
+    pub counters: [u64; 40],
+}
+
+#[cw_serde(base64_arrays)]
+pub enum Key {
+    Ed25519([u8; 32]),
+}
+
+#[test]
+fn arrays_work() {
+    let msg = Hashes::<2> {
+        sha256: [0xab; 32],
+        history: [[1, 2, 3, 4], [5, 6, 7, 8]],
+        counters: [3; 40],
+    };
+    let json = serde_json::to_value(&msg).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "sha256": vec![0xab; 32],
+            // only the outer array is handled by cw_serde
+            "history": [[1, 2, 3, 4], [5, 6, 7, 8]],
+            "counters": vec![3; 40],
+        })
+    );
+    assert_eq!(serde_json::from_value::<Hashes<2>>(json).unwrap(), msg);
+
+    let schema = serde_json::to_value(schema_for!(Hashes<2>)).unwrap();
+    let properties = &schema["properties"];
+    assert_eq!(properties["sha256"]["minItems"], 32);
+    assert_eq!(properties["sha256"]["maxItems"], 32);
+    assert_eq!(properties["history"]["minItems"], 2);
+    assert_eq!(properties["history"]["maxItems"], 2);
+    assert_eq!(properties["counters"]["minItems"], 40);
+    assert_eq!(properties["counters"]["items"]["format"], "uint64");
+
+    // With `base64_arrays`, `[u8; N]` is encoded like `Binary`
+    let json = serde_json::to_string(&Key::Ed25519([0; 32])).unwrap();
+    assert_eq!(
+        json,
+        r#"{"ed25519":"AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="}"#
+    );
+    let schema = serde_json::to_value(schema_for!(Key)).unwrap();
+    assert_eq!(
+        schema["oneOf"][0]["properties"]["ed25519"],
+        serde_json::json!({"type": "string", "minLength": 44, "maxLength": 44})
+    );
+    // Number arrays written without `base64_arrays` are still accepted
+    let legacy = format!(r#"{{"ed25519":{:?}}}"#, [0u8; 32]);
+    assert_eq!(
+        serde_json::from_str::<Key>(&legacy).unwrap(),
+        Key::Ed25519([0; 32])
+    );
+}
+
+#[cw_serde]
+pub struct Strict {
+    pub owner: String,
+}
+
+#[cw_serde(allow_unknown_fields)]
+pub struct Lenient {
+    pub owner: String,
+}
+
+#[cw_serde(allow_unknown_fields)]
+pub enum LenientMsg {
+    Transfer { recipient: String },
+}
+
+#[test]
+fn allow_unknown_fields_works() {
+    let json = r#"{"owner":"alice","added_later":true}"#;
+    serde_json::from_str::<Strict>(json).unwrap_err();
+    assert_eq!(
+        serde_json::from_str::<Lenient>(json).unwrap(),
+        Lenient {
+            owner: "alice".to_string()
+        }
+    );
+    assert_eq!(
+        serde_json::from_str::<LenientMsg>(r#"{"transfer":{"recipient":"bob","memo":"hi"}}"#)
+            .unwrap(),
+        LenientMsg::Transfer {
+            recipient: "bob".to_string()
+        }
+    );
+    // variants keep the snake_case names
+    serde_json::from_str::<LenientMsg>(r#"{"Transfer":{"recipient":"bob"}}"#).unwrap_err();
+
+    // the schema documents whether additional properties are accepted
+    let strict = serde_json::to_value(schema_for!(Strict)).unwrap();
+    assert_eq!(strict["additionalProperties"], false);
+    let lenient = serde_json::to_value(schema_for!(Lenient)).unwrap();
+    assert_eq!(lenient.get("additionalProperties"), None);
+}
+
+mod my_sdk {
+    pub use cosmwasm_schema;
+}
+
+#[cw_serde(crate = "crate::my_sdk::cosmwasm_schema", base64_arrays)]
+pub struct ReExported<T> {
+    pub owner: String,
+    pub pubkey: [u8; 4],
+    pub weights: [T; 2],
+}
+
+#[test]
+fn crate_path_works() {
+    let msg = ReExported {
+        owner: "alice".to_string(),
+        pubkey: [1, 2, 3, 4],
+        weights: [5u32, 6],
+    };
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(
+        json,
+        r#"{"owner":"alice","pubkey":"AQIDBA==","weights":[5,6]}"#
+    );
+    assert_eq!(serde_json::from_str::<ReExported<u32>>(&json).unwrap(), msg);
+
+    let schema = serde_json::to_value(schema_for!(ReExported<u32>)).unwrap();
+    assert_eq!(schema["title"], "ReExported_for_uint32");
+    assert_eq!(schema["properties"]["weights"]["maxItems"], 2);
+}
+
+#[cw_serde(derive(Eq, Hash, PartialOrd, Ord))]
+pub enum Denom {
+    Native(String),
+    Cw20(String),
+}
+
+#[test]
+fn derives_work() {
+    use std::collections::{BTreeMap, HashSet};
+
+    let mut balances = BTreeMap::new();
+    balances.insert(Denom::Native("uatom".to_string()), 5u32);
+    balances.insert(Denom::Cw20("token".to_string()), 7u32);
+    // variants are ordered by declaration
+    assert_eq!(
+        balances.keys().next(),
+        Some(&Denom::Native("uatom".to_string()))
+    );
+
+    let set: HashSet<Denom> = balances.into_keys().collect();
+    assert!(set.contains(&Denom::Cw20("token".to_string())));
+    assert_eq!(
+        serde_json::to_string(&Denom::Cw20("token".to_string())).unwrap(),
+        r#"{"cw20":"token"}"#
+    );
+}
+
+#[cw_serde(tag = "type")]
+#[derive(cosmwasm_schema::QueryResponses)]
+pub enum InternallyTaggedQueryMsg {
+    #[returns(String)]
+    Balance { address: String },
+    #[returns(u32)]
+    Count {},
+}
+
+#[cw_serde(tag = "type", content = "value")]
+#[derive(cosmwasm_schema::QueryResponses)]
+pub enum AdjacentlyTaggedQueryMsg {
+    #[returns(String)]
+    Balance { address: String },
+    #[returns(u32)]
+    Count,
+    #[returns(u32)]
+    Height(u64),
+}
+
+#[test]
+fn tagged_enums_work() {
+    use cosmwasm_schema::QueryResponses;
+
+    let msg = InternallyTaggedQueryMsg::Balance {
+        address: "alice".to_string(),
+    };
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(json, r#"{"type":"balance","address":"alice"}"#);
+    assert_eq!(
+        serde_json::from_str::<InternallyTaggedQueryMsg>(&json).unwrap(),
+        msg
+    );
+    assert!(serde_json::from_str::<InternallyTaggedQueryMsg>(
+        r#"{"type":"balance","address":"alice","extra":1}"#
+    )
+    .is_err());
+    let responses = InternallyTaggedQueryMsg::response_schemas().unwrap();
+    assert_eq!(responses.keys().collect::<Vec<_>>(), ["balance", "count"]);
+
+    let msg = AdjacentlyTaggedQueryMsg::Balance {
+        address: "alice".to_string(),
+    };
+    let json = serde_json::to_string(&msg).unwrap();
+    assert_eq!(json, r#"{"type":"balance","value":{"address":"alice"}}"#);
+    assert_eq!(
+        serde_json::from_str::<AdjacentlyTaggedQueryMsg>(&json).unwrap(),
+        msg
+    );
+    assert_eq!(
+        serde_json::to_string(&AdjacentlyTaggedQueryMsg::Count).unwrap(),
+        r#"{"type":"count"}"#
+    );
+    let responses = AdjacentlyTaggedQueryMsg::response_schemas().unwrap();
+    assert_eq!(
+        responses.keys().collect::<Vec<_>>(),
+        ["balance", "count", "height"]
+    );
+}
+
+const MAX_MEMO_LENGTH: u32 = 8;
+
+#[cw_serde(validate)]
+#[derive(Validate)]
+pub struct Order {
+    #[schema(min = 1, max = 100)]
+    pub quantity: u32,
+    #[schema(max_length = "MAX_MEMO_LENGTH")]
+    pub memo: Option<String>,
+    #[schema(pattern = "^[a-z]+$")]
+    pub denom: String,
+}
+
+#[test]
+fn constraints_work() {
+    let schema = serde_json::to_value(schema_for!(Order)).unwrap();
+    let properties = &schema["properties"];
+    assert_eq!(properties["quantity"]["minimum"], 1.0);
+    assert_eq!(properties["quantity"]["maximum"], 100.0);
+    assert_eq!(properties["memo"]["maxLength"], 8);
+    assert_eq!(properties["denom"]["pattern"], "^[a-z]+$");
+
+    let order: Order = serde_json::from_str(r#"{"quantity":100,"denom":"ucosm"}"#).unwrap();
+    assert_eq!(
+        order,
+        Order {
+            quantity: 100,
+            memo: None,
+            denom: "ucosm".to_string(),
+        }
+    );
+
+    let err = serde_json::from_str::<Order>(r#"{"quantity":0,"denom":"ucosm"}"#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value 0, expected a value between 1 and 100 at line 1 column 13"
+    );
+    let err =
+        serde_json::from_str::<Order>(r#"{"quantity":1,"memo":"too long memo","denom":"ucosm"}"#)
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid length 13, expected a length of at most 8 at line 1 column 36"
+    );
+}
+
+#[cw_serde(derive(Validate))]
+pub enum ExecuteMsg2 {
+    Buy {
+        #[schema(nested)]
+        order: Order,
+        #[schema(min_length = 1, max_length = 2)]
+        #[serde(rename = "labels")]
+        tags: Vec<String>,
+    },
+    Amount(#[schema(max = 10)] u64),
+    Pair(#[schema(min = 1)] u8, #[schema(min = 2)] u8),
+    Stop,
+}
+
+#[test]
+fn validate_works() {
+    let order = Order {
+        quantity: 1,
+        memo: None,
+        denom: "ucosm".to_string(),
+    };
+    let msg = ExecuteMsg2::Buy {
+        order: order.clone(),
+        tags: vec!["a".to_string()],
+    };
+    assert_eq!(msg.validate(), Ok(()));
+    assert_eq!(ExecuteMsg2::Stop.validate(), Ok(()));
+
+    // the constraints are still part of the schema
+    let schema = serde_json::to_value(schema_for!(ExecuteMsg2)).unwrap();
+    assert_eq!(
+        schema["oneOf"][1]["properties"]["buy"]["properties"]["labels"]["minItems"],
+        1
+    );
+
+    let msg = ExecuteMsg2::Buy {
+        order: Order {
+            quantity: 0,
+            ..order.clone()
+        },
+        tags: vec![],
+    };
+    assert_eq!(
+        msg.validate().unwrap_err(),
+        ValidationError::new(
+            "buy.order.quantity",
+            "invalid value 0, expected a value between 1 and 100"
+        )
+    );
+    let msg = ExecuteMsg2::Buy {
+        order,
+        tags: vec![],
+    };
+    assert_eq!(
+        msg.validate().unwrap_err().to_string(),
+        "buy.labels: invalid length 0, expected a length between 1 and 2"
+    );
+    assert_eq!(
+        ExecuteMsg2::Amount(11).validate().unwrap_err().field,
+        "amount"
+    );
+    assert_eq!(
+        ExecuteMsg2::Pair(1, 1).validate().unwrap_err().field,
+        "pair.1"
+    );
+}
+
+/// A coin with an example
+#[cw_serde]
+#[schema_example(ExampleCoin { denom: "ucosm".to_string(), amount: 100 })]
+pub struct ExampleCoin {
+    #[schema_example("uatom")]
+    pub denom: String,
+    pub amount: u32,
+}
+
+#[cw_serde]
+pub enum ExampleMsg {
+    #[schema_example(serde_json::json!({ "send": { "coin": { "denom": "ucosm", "amount": 1 } } }))]
+    Send { coin: ExampleCoin },
+}
+
+#[test]
+fn examples_work() {
+    let schema = serde_json::to_value(schema_for!(ExampleMsg)).unwrap();
+    assert_eq!(
+        schema["oneOf"][0]["examples"],
+        serde_json::json!([{ "send": { "coin": { "denom": "ucosm", "amount": 1 } } }])
+    );
+    let coin = &schema["definitions"]["ExampleCoin"];
+    assert_eq!(
+        coin["examples"],
+        serde_json::json!([{ "denom": "ucosm", "amount": 100 }])
+    );
+    assert_eq!(
+        coin["properties"]["denom"]["examples"],
+        serde_json::json!(["uatom"])
+    );
+}
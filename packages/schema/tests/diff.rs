@@ -0,0 +1,727 @@
+use cosmwasm_schema::{
+    diff_api_file, diff_apis, generate_api, BreakingChange, BreakingChangeKind, Dialect,
+};
+
+mod v1 {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    pub struct Coin {
+        pub denom: String,
+        pub amount: String,
+    }
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub owner: String,
+        pub admin: Option<String>,
+        pub cap: u64,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Transfer { recipient: String, amount: Coin },
+        Burn { amount: Coin },
+        Pause {},
+        Freeze,
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(Coin)]
+        Balance { address: String },
+        #[returns(ConfigResponse)]
+        Config {},
+        #[returns(Vec<String>)]
+        Minters {},
+    }
+
+    #[cw_serde]
+    pub enum Status {
+        Active,
+        Paused,
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub owner: String,
+        pub admin: String,
+        pub status: Status,
+        pub limit: Option<u32>,
+    }
+}
+
+/// Compatible with v1
+mod v2 {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    pub struct Coin {
+        pub denom: String,
+        pub amount: String,
+    }
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub owner: String,
+        pub admin: Option<String>,
+        pub cap: u64,
+        pub label: Option<String>,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Transfer { recipient: String, amount: Coin },
+        Burn { amount: Coin, memo: Option<String> },
+        Pause {},
+        Freeze,
+        Unfreeze,
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(Coin)]
+        Balance { address: String },
+        #[returns(ConfigResponse)]
+        Config {},
+        #[returns(Vec<String>)]
+        Minters {},
+        #[returns(u32)]
+        Count {},
+    }
+
+    #[cw_serde]
+    pub enum Status {
+        Active,
+        Paused,
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub owner: String,
+        pub admin: String,
+        pub status: Status,
+        pub limit: Option<u32>,
+        pub created: u64,
+    }
+}
+
+/// Incompatible with v1
+mod v3 {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    pub struct Coin {
+        pub denom: String,
+        pub amount: u128,
+    }
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub owner: String,
+        pub admin: String,
+        pub cap: u32,
+        pub label: String,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Transfer { recipient: String, amount: Coin },
+        Pause { reason: String },
+        Freeze {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(Coin)]
+        Balance { address: String },
+        #[returns(ConfigResponse)]
+        Config {},
+        #[returns(Vec<u64>)]
+        Minters {},
+    }
+
+    #[cw_serde]
+    pub enum Status {
+        Active,
+        Paused,
+        Migrating,
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub owner: String,
+        pub admin: Option<String>,
+        pub status: Status,
+    }
+}
+
+fn v1() -> String {
+    generate_api! {
+        name: "token",
+        version: "1.0.0",
+        instantiate: v1::InstantiateMsg,
+        execute: v1::ExecuteMsg,
+        query: v1::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap()
+}
+
+fn change(path: &str, kind: BreakingChangeKind) -> BreakingChange {
+    BreakingChange {
+        path: path.to_string(),
+        kind,
+    }
+}
+
+#[test]
+fn diff_apis_accepts_identical_apis() {
+    assert_eq!(diff_apis(&v1(), &v1()).unwrap(), vec![]);
+}
+
+#[test]
+fn diff_apis_accepts_compatible_changes() {
+    let v2 = generate_api! {
+        name: "token",
+        version: "2.0.0",
+        instantiate: v2::InstantiateMsg,
+        execute: v2::ExecuteMsg,
+        query: v2::QueryMsg,
+        migrate: v2::InstantiateMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    assert_eq!(diff_apis(&v1(), &v2).unwrap(), vec![]);
+}
+
+#[test]
+fn diff_apis_reports_breaking_changes() {
+    let v3 = generate_api! {
+        name: "token",
+        version: "3.0.0",
+        instantiate: v3::InstantiateMsg,
+        query: v3::QueryMsg,
+        execute: v3::ExecuteMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let changes = diff_apis(&v1(), &v3).unwrap();
+    assert_eq!(
+        changes,
+        vec![
+            change(
+                "instantiate.admin",
+                BreakingChangeKind::NullNoLongerAccepted
+            ),
+            change(
+                "instantiate.cap",
+                BreakingChangeKind::TypeChanged {
+                    old: "integer (uint64)".to_string(),
+                    new: "integer (uint32)".to_string()
+                }
+            ),
+            change(
+                "instantiate",
+                BreakingChangeKind::FieldNowRequired {
+                    field: "admin".to_string()
+                }
+            ),
+            change(
+                "instantiate",
+                BreakingChangeKind::RequiredFieldAdded {
+                    field: "label".to_string()
+                }
+            ),
+            change(
+                "execute",
+                BreakingChangeKind::VariantRemoved {
+                    variant: "burn".to_string()
+                }
+            ),
+            change(
+                "execute.freeze",
+                BreakingChangeKind::TypeChanged {
+                    old: "unit variant".to_string(),
+                    new: "data variant".to_string()
+                }
+            ),
+            change(
+                "execute.transfer.amount.amount",
+                BreakingChangeKind::TypeChanged {
+                    old: "string".to_string(),
+                    new: "integer (uint128)".to_string()
+                }
+            ),
+            change(
+                "execute.pause",
+                BreakingChangeKind::RequiredFieldAdded {
+                    field: "reason".to_string()
+                }
+            ),
+            change(
+                "responses.balance.amount",
+                BreakingChangeKind::TypeChanged {
+                    old: "string".to_string(),
+                    new: "integer (uint128)".to_string()
+                }
+            ),
+            change(
+                "responses.config",
+                BreakingChangeKind::FieldNowOptional {
+                    field: "admin".to_string()
+                }
+            ),
+            change("responses.config.admin", BreakingChangeKind::NowNullable),
+            change(
+                "responses.config",
+                BreakingChangeKind::FieldRemoved {
+                    field: "limit".to_string()
+                }
+            ),
+            change(
+                "responses.config.status",
+                BreakingChangeKind::VariantAdded {
+                    variant: "migrating".to_string()
+                }
+            ),
+            change(
+                "responses.minters[]",
+                BreakingChangeKind::TypeChanged {
+                    old: "string".to_string(),
+                    new: "integer (uint64)".to_string()
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        changes[4].to_string(),
+        "execute: variant `burn` was removed"
+    );
+}
+
+#[test]
+fn diff_apis_reports_removed_messages() {
+    let v2 = generate_api! {
+        name: "token",
+        version: "2.0.0",
+        instantiate: v2::InstantiateMsg,
+        query: v2::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    assert_eq!(
+        diff_apis(&v1(), &v2).unwrap(),
+        vec![change("execute", BreakingChangeKind::MessageRemoved)]
+    );
+}
+
+#[test]
+fn diff_apis_supports_draft_2020_12() {
+    let v1_2020_12 = generate_api! {
+        name: "token",
+        version: "1.0.0",
+        instantiate: v1::InstantiateMsg,
+        execute: v1::ExecuteMsg,
+        query: v1::QueryMsg,
+    }
+    .render()
+    .to_string_with_dialect(Dialect::Draft2020_12)
+    .unwrap();
+    assert!(v1_2020_12.contains("\"$defs\""));
+    assert_eq!(diff_apis(&v1(), &v1_2020_12).unwrap(), vec![]);
+    assert_eq!(diff_apis(&v1_2020_12, &v1_2020_12).unwrap(), vec![]);
+
+    let v3 = generate_api! {
+        name: "token",
+        version: "3.0.0",
+        instantiate: v3::InstantiateMsg,
+        execute: v3::ExecuteMsg,
+        query: v3::QueryMsg,
+    }
+    .render();
+    assert_eq!(
+        diff_apis(
+            &v1_2020_12,
+            &v3.to_string_with_dialect(Dialect::Draft2020_12).unwrap()
+        )
+        .unwrap(),
+        diff_apis(&v1(), &v3.to_string().unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn diff_apis_supports_bundled_apis() {
+    let v1 = generate_api! {
+        name: "token",
+        version: "1.0.0",
+        instantiate: v1::InstantiateMsg,
+        execute: v1::ExecuteMsg,
+        query: v1::QueryMsg,
+    }
+    .render();
+    let v3 = generate_api! {
+        name: "token",
+        version: "3.0.0",
+        instantiate: v3::InstantiateMsg,
+        execute: v3::ExecuteMsg,
+        query: v3::QueryMsg,
+    }
+    .render();
+    let expected = diff_apis(&v1.to_string().unwrap(), &v3.to_string().unwrap()).unwrap();
+
+    for dialect in [Dialect::Draft07, Dialect::Draft2020_12] {
+        let v1_bundled = v1.to_bundled_string(dialect).unwrap();
+        let v3_bundled = v3.to_bundled_string(dialect).unwrap();
+        assert_eq!(
+            diff_apis(&v1.to_string().unwrap(), &v1_bundled).unwrap(),
+            vec![]
+        );
+        assert_eq!(diff_apis(&v1_bundled, &v3_bundled).unwrap(), expected);
+    }
+}
+
+mod ibc {
+    use cosmwasm_schema::cw_serde;
+
+    #[cw_serde]
+    pub enum PacketMsg {
+        Dispatch { msgs: Vec<String> },
+        WhoAmI {},
+    }
+
+    #[cw_serde]
+    pub enum PacketMsgV2 {
+        Dispatch { msgs: Vec<String> },
+        WhoAmI {},
+        Balances {},
+    }
+
+    #[cw_serde]
+    pub enum AcknowledgementMsg {
+        Ok(String),
+        Error(String),
+    }
+
+    #[cw_serde]
+    pub enum AcknowledgementMsgV2 {
+        Ok(u64),
+        Error(String),
+    }
+}
+
+#[test]
+fn diff_apis_checks_ibc_types_in_both_directions() {
+    let without_ibc = generate_api! { instantiate: v1::InstantiateMsg }
+        .render()
+        .to_string()
+        .unwrap();
+    let old = generate_api! {
+        instantiate: v1::InstantiateMsg,
+        ibc_packet: ibc::PacketMsg,
+        ibc_ack: ibc::AcknowledgementMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    assert_eq!(diff_apis(&without_ibc, &old).unwrap(), vec![]);
+
+    // Counterparties running the old version cannot handle the new variant
+    let new = generate_api! {
+        instantiate: v1::InstantiateMsg,
+        ibc_packet: ibc::PacketMsgV2,
+        ibc_ack: ibc::AcknowledgementMsgV2,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    assert_eq!(
+        diff_apis(&old, &new).unwrap(),
+        vec![
+            change(
+                "ibc_packet",
+                BreakingChangeKind::VariantAdded {
+                    variant: "balances".to_string()
+                }
+            ),
+            change(
+                "ibc_ack.ok",
+                BreakingChangeKind::TypeChanged {
+                    old: "string".to_string(),
+                    new: "integer (uint64)".to_string()
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        diff_apis(&new, &old).unwrap()[0],
+        change(
+            "ibc_packet",
+            BreakingChangeKind::VariantRemoved {
+                variant: "balances".to_string()
+            }
+        )
+    );
+    assert_eq!(
+        diff_apis(&old, &without_ibc).unwrap(),
+        vec![
+            change("ibc_packet", BreakingChangeKind::MessageRemoved),
+            change("ibc_ack", BreakingChangeKind::MessageRemoved),
+        ]
+    );
+}
+
+mod hooks {
+    use cosmwasm_schema::cw_serde;
+
+    #[cw_serde]
+    pub enum HookMsg {
+        BeforeSend { from: String },
+    }
+
+    #[cw_serde]
+    pub enum HookMsgV2 {
+        BeforeSend { from: String, to: String },
+    }
+}
+
+#[test]
+fn diff_apis_checks_reply_and_entry_points() {
+    let old = generate_api! {
+        instantiate: v1::InstantiateMsg,
+        reply: hooks::HookMsg,
+        entry_points: { token_factory_hook: hooks::HookMsg, end_block: hooks::HookMsg },
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    assert_eq!(diff_apis(&old, &old).unwrap(), vec![]);
+
+    // the chain calling the entry points does not know the new field
+    let new = generate_api! {
+        instantiate: v1::InstantiateMsg,
+        reply: hooks::HookMsgV2,
+        entry_points: { token_factory_hook: hooks::HookMsgV2, begin_block: hooks::HookMsg },
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let required = || BreakingChangeKind::RequiredFieldAdded {
+        field: "to".to_string(),
+    };
+    assert_eq!(
+        diff_apis(&old, &new).unwrap(),
+        vec![
+            change("reply.before_send", required()),
+            change("entry_points.end_block", BreakingChangeKind::MessageRemoved),
+            change("entry_points.token_factory_hook.before_send", required()),
+        ]
+    );
+}
+
+#[allow(dead_code)] // the events are only declared here, never emitted
+mod events {
+    use cosmwasm_schema::ContractEvents;
+
+    #[derive(ContractEvents)]
+    pub enum V1 {
+        Transfer { to: String, amount: String },
+        Burn { amount: String },
+    }
+
+    #[derive(ContractEvents)]
+    pub enum V2 {
+        Transfer {
+            to: String,
+            amount: Option<String>,
+            memo: String,
+        },
+        Mint {
+            amount: String,
+        },
+    }
+}
+
+#[test]
+fn diff_apis_checks_events() {
+    let without_events = generate_api! { instantiate: v1::InstantiateMsg }
+        .render()
+        .to_string()
+        .unwrap();
+    let old = generate_api! { instantiate: v1::InstantiateMsg, events: events::V1 }
+        .render()
+        .to_string()
+        .unwrap();
+    assert_eq!(diff_apis(&without_events, &old).unwrap(), vec![]);
+    assert_eq!(diff_apis(&old, &old).unwrap(), vec![]);
+
+    // Indexers of the old version miss the burn event and the amount of transfers
+    let new = generate_api! { instantiate: v1::InstantiateMsg, events: events::V2 }
+        .render()
+        .to_string()
+        .unwrap();
+    assert_eq!(
+        diff_apis(&old, &new).unwrap(),
+        vec![
+            change(
+                "events.transfer",
+                BreakingChangeKind::FieldNowOptional {
+                    field: "amount".to_string()
+                }
+            ),
+            change(
+                "events",
+                BreakingChangeKind::VariantRemoved {
+                    variant: "burn".to_string()
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        diff_apis(&new, &old).unwrap(),
+        vec![
+            change(
+                "events.transfer",
+                BreakingChangeKind::FieldRemoved {
+                    field: "memo".to_string()
+                }
+            ),
+            change(
+                "events",
+                BreakingChangeKind::VariantRemoved {
+                    variant: "mint".to_string()
+                }
+            ),
+        ]
+    );
+    assert_eq!(diff_apis(&old, &without_events).unwrap().len(), 2);
+}
+
+#[allow(dead_code)] // the state is only declared here, never stored
+mod state {
+    use cosmwasm_schema::{cw_serde, ContractState};
+
+    #[cw_serde]
+    pub struct ConfigV1 {
+        pub owner: String,
+    }
+
+    #[cw_serde]
+    pub struct ConfigV2 {
+        pub owner: String,
+        pub admin: String,
+    }
+
+    #[derive(ContractState)]
+    pub enum V1 {
+        #[state(namespace = "config")]
+        Config(ConfigV1),
+        #[state(key = "utf8")]
+        Balance(String),
+        #[state(key = "big_endian")]
+        Claims(Vec<String>),
+    }
+
+    #[derive(ContractState)]
+    pub enum V2 {
+        #[state(namespace = "config")]
+        Config(ConfigV2),
+        #[state(key = "utf8", key = "utf8")]
+        Balance(String),
+    }
+}
+
+#[test]
+fn diff_apis_checks_state() {
+    let old = generate_api! { instantiate: v1::InstantiateMsg, state: state::V1 }
+        .render()
+        .to_string()
+        .unwrap();
+    assert_eq!(diff_apis(&old, &old).unwrap(), vec![]);
+
+    // After a migration, the new version cannot read the old config and finds the old
+    // balances under different keys. The claims are not read anymore.
+    let new = generate_api! { instantiate: v1::InstantiateMsg, state: state::V2 }
+        .render()
+        .to_string()
+        .unwrap();
+    assert_eq!(
+        diff_apis(&old, &new).unwrap(),
+        vec![
+            change(
+                "state.config",
+                BreakingChangeKind::RequiredFieldAdded {
+                    field: "admin".to_string()
+                }
+            ),
+            change(
+                "state.balance",
+                BreakingChangeKind::TypeChanged {
+                    old: "map with utf8 keys".to_string(),
+                    new: "map with utf8, utf8 keys".to_string(),
+                }
+            ),
+        ]
+    );
+    // `#[cw_serde]` types reject unknown fields, so not even removals are compatible
+    assert_eq!(
+        diff_apis(&new, &old).unwrap(),
+        vec![
+            change(
+                "state.config",
+                BreakingChangeKind::FieldRemoved {
+                    field: "admin".to_string()
+                }
+            ),
+            change(
+                "state.balance",
+                BreakingChangeKind::TypeChanged {
+                    old: "map with utf8, utf8 keys".to_string(),
+                    new: "map with utf8 keys".to_string(),
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn diff_api_file_works() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("token.json");
+    std::fs::write(&path, v1()).unwrap();
+    assert_eq!(diff_api_file(&path, &v1()).unwrap(), vec![]);
+
+    let err = diff_api_file(&dir.path().join("missing.json"), &v1()).unwrap_err();
+    assert!(err.to_string().contains("No such file"));
+    let err = diff_apis("{", &v1()).unwrap_err();
+    assert!(err.to_string().starts_with("Error parsing API file"));
+}
+
+mod recursive {
+    use cosmwasm_schema::cw_serde;
+
+    #[cw_serde]
+    pub enum Condition {
+        Not { inner: Box<Condition> },
+        All { conditions: Vec<Condition> },
+        Sender { address: String },
+    }
+}
+
+#[test]
+fn diff_apis_supports_recursive_types() {
+    let api = generate_api! {
+        instantiate: recursive::Condition,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    assert_eq!(diff_apis(&api, &api).unwrap(), vec![]);
+}
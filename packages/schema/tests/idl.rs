@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use cosmwasm_schema::{generate_api, QueryResponses, IDL_VERSION};
+use cosmwasm_schema::{
+    generate_api, ContractEvents, ContractState, Dialect, QueryResponses, IDL_VERSION,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -77,6 +79,319 @@ fn test_basic_structure() {
     );
 }
 
+#[test]
+fn test_top_level_fields() {
+    let api_str = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+
+    // Code generators rely on these fields, so they are part of the IDL version
+    let api_json: serde_json::Map<String, Value> = serde_json::from_str(&api_str).unwrap();
+    let mut fields: Vec<_> = api_json.keys().map(String::as_str).collect();
+    fields.sort_unstable();
+    assert_eq!(
+        fields,
+        [
+            "contract_name",
+            "contract_version",
+            "execute",
+            "idl_version",
+            "instantiate",
+            "migrate",
+            "query",
+            "responses",
+            "sudo",
+        ]
+    );
+    for absent in ["execute", "migrate", "sudo"] {
+        assert_eq!(api_json[absent], Value::Null);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketMsg {
+    Dispatch { msgs: Vec<String> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcknowledgementMsg<T> {
+    Ok(T),
+    Error(String),
+}
+
+#[test]
+fn test_ibc_types() {
+    let api_str = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        ibc_packet: PacketMsg,
+        ibc_ack: AcknowledgementMsg<u64>,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+
+    let api_json: Value = serde_json::from_str(&api_str).unwrap();
+    assert_eq!(api_json["ibc_packet"]["title"], "IbcPacket");
+    assert_eq!(
+        api_json["ibc_packet"]["oneOf"][0]["required"],
+        serde_json::json!(["dispatch"])
+    );
+    assert_eq!(api_json["ibc_ack"]["title"], "IbcAck");
+    assert_eq!(api_json["ibc_ack"]["oneOf"].as_array().unwrap().len(), 2);
+}
+
+mod hooks {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    pub struct ReplyPayload {
+        pub order_id: u64,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum TokenFactoryHookMsg {
+        BlockBeforeSend { from: String, to: String },
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    pub struct EndBlockMsg {}
+}
+
+#[test]
+fn test_reply_and_custom_entry_points() {
+    let api = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        reply: hooks::ReplyPayload,
+        entry_points: {
+            token_factory_hook: hooks::TokenFactoryHookMsg,
+            end_block: hooks::EndBlockMsg,
+        },
+    }
+    .render();
+    let api_json: Value = serde_json::from_str(&api.to_string().unwrap()).unwrap();
+
+    assert_eq!(api_json["reply"]["title"], "ReplyMsg");
+    assert_eq!(
+        api_json["reply"]["required"],
+        serde_json::json!(["order_id"])
+    );
+    // each entry point has a section named after it
+    let entry_points = api_json["entry_points"].as_object().unwrap();
+    assert_eq!(
+        entry_points.keys().collect::<Vec<_>>(),
+        ["end_block", "token_factory_hook"]
+    );
+    assert_eq!(entry_points["end_block"]["title"], "EndBlockMsg");
+    assert_eq!(
+        entry_points["token_factory_hook"]["title"],
+        "TokenFactoryHookMsg"
+    );
+    assert_eq!(
+        entry_points["token_factory_hook"]["oneOf"][0]["required"],
+        serde_json::json!(["block_before_send"])
+    );
+
+    // the titles name the types in generated code
+    let ts = api.to_ts();
+    assert!(ts.contains("export interface ReplyMsg {"));
+    assert!(ts.contains("export type TokenFactoryHookMsg ="));
+    assert!(ts.contains("export interface EndBlockMsg {"));
+
+    // both are omitted if not set
+    let api_json: Value = serde_json::from_str(
+        &generate_api! { instantiate: InstantiateMsg }
+            .render()
+            .to_string()
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(api_json.get("reply").is_none());
+    assert!(api_json.get("entry_points").is_none());
+}
+
+mod bundled {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    pub struct Coin {
+        pub denom: String,
+        pub amount: u128,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    pub struct InstantiateMsg {
+        pub funds: Vec<Coin>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExecuteMsg {
+        Send { to: String, amount: Coin },
+    }
+}
+
+#[test]
+fn test_bundled_api() {
+    use cosmwasm_schema::Dialect;
+
+    let api = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: bundled::InstantiateMsg,
+        execute: bundled::ExecuteMsg,
+    }
+    .render();
+
+    for dialect in [Dialect::Draft07, Dialect::Draft2020_12] {
+        let api_json: Value =
+            serde_json::from_str(&api.to_bundled_string(dialect).unwrap()).unwrap();
+        assert_eq!(api_json["contract_name"], "test");
+        assert_eq!(
+            api_json["$defs"]["Coin"]["required"],
+            serde_json::json!(["amount", "denom"])
+        );
+        for message in ["instantiate", "execute"] {
+            assert!(api_json[message].get("definitions").is_none());
+            assert!(api_json[message].get("$defs").is_none());
+        }
+        assert_eq!(
+            api_json["instantiate"]["properties"]["funds"]["items"]["$ref"],
+            "#/$defs/Coin"
+        );
+        assert_eq!(
+            api_json["execute"]["oneOf"][0]["properties"]["send"]["properties"]["amount"]["$ref"],
+            "#/$defs/Coin"
+        );
+    }
+}
+
+#[test]
+fn test_release_in_generated_files() {
+    let api = generate_api! {
+        name: "test",
+        version: "1.2.3-beta.1",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+    }
+    .render();
+
+    let notice = "Code generated by cosmwasm-schema for test 1.2.3-beta.1. DO NOT EDIT.\n";
+    for code in [api.to_ts(), api.to_go("test"), api.to_proto("test")] {
+        assert!(code.starts_with(&format!("// {}", notice)), "{}", code);
+    }
+    assert!(api.to_python().starts_with(&format!("# {}", notice)));
+
+    let openapi: Value = serde_json::from_str(&api.to_openapi().unwrap()).unwrap();
+    assert_eq!(openapi["info"]["title"], "test");
+    assert_eq!(openapi["info"]["version"], "1.2.3-beta.1");
+}
+
+#[derive(ContractEvents)]
+pub enum ContractEvent {
+    /// Tokens were sent to another account
+    Transfer { to: String, memo: Option<String> },
+}
+
+#[test]
+fn test_events() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        events: ContractEvent,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+
+    let api_json: Value = serde_json::from_str(&api_str).unwrap();
+    assert_eq!(
+        api_json["events"],
+        serde_json::json!([{
+            "type": "transfer",
+            "description": "Tokens were sent to another account",
+            "attributes": [
+                { "key": "to", "required": true },
+                { "key": "memo", "required": false }
+            ]
+        }])
+    );
+
+    // omitted if not declared
+    let api_str = generate_api! { instantiate: InstantiateMsg }
+        .render()
+        .to_string()
+        .unwrap();
+    let api_json: Value = serde_json::from_str(&api_str).unwrap();
+    assert!(api_json.get("events").is_none());
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub admin: String,
+}
+
+#[derive(ContractState)]
+pub enum State {
+    /// The configuration set on instantiation
+    Config(Config),
+    #[state(namespace = "balance", key = "utf8")]
+    Balances(u128),
+}
+
+#[test]
+fn test_state() {
+    let api = generate_api! {
+        instantiate: InstantiateMsg,
+        state: State,
+    }
+    .render();
+
+    let api_json: Value = serde_json::from_str(&api.to_string().unwrap()).unwrap();
+    assert_eq!(api_json["state"][0]["namespace"], "config");
+    assert_eq!(
+        api_json["state"][0]["description"],
+        "The configuration set on instantiation"
+    );
+    assert_eq!(api_json["state"][0]["keys"], serde_json::json!([]));
+    assert_eq!(api_json["state"][0]["value"]["title"], "Config");
+    assert_eq!(
+        api_json["state"][0]["value"]["properties"]["admin"]["type"],
+        "string"
+    );
+    assert_eq!(api_json["state"][1]["namespace"], "balance");
+    assert_eq!(api_json["state"][1]["keys"], serde_json::json!(["utf8"]));
+    assert_eq!(api_json["state"][1]["value"]["format"], "uint128");
+
+    // the stored values are converted like all other schemas
+    let api_json: Value =
+        serde_json::from_str(&api.to_string_with_dialect(Dialect::Draft2020_12).unwrap()).unwrap();
+    assert_eq!(
+        api_json["state"][0]["value"]["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(api_json["state"][1]["keys"], serde_json::json!(["utf8"]));
+
+    // omitted if not declared
+    let api_str = generate_api! { instantiate: InstantiateMsg }
+        .render()
+        .to_string()
+        .unwrap();
+    let api_json: Value = serde_json::from_str(&api_str).unwrap();
+    assert!(api_json.get("state").is_none());
+}
+
 #[test]
 fn test_query_responses() {
     let api_str = generate_api! {
@@ -106,3 +421,294 @@ fn test_query_responses() {
     // Find the "balance" query in responses
     api.get("responses").unwrap().get("balance").unwrap();
 }
+
+mod documented {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    /// Messages of the documented contract
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        /// Returns the current config
+        #[returns(Config)]
+        Config {},
+        /// Returns the names of all members.
+        ///
+        /// The list is sorted alphabetically.
+        #[returns(Vec<String>)]
+        Names {
+            /// Maximum number of names to return
+            limit: Option<u32>,
+        },
+    }
+
+    /// The contract configuration
+    #[cw_serde]
+    pub struct Config {
+        /// Address that may update the config
+        pub owner: String,
+    }
+}
+
+#[test]
+fn test_doc_comments() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        query: documented::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+
+    let query = &api["query"];
+    assert_eq!(query["description"], "Messages of the documented contract");
+    assert_eq!(
+        query["oneOf"][0]["description"],
+        "Returns the current config"
+    );
+    assert_eq!(
+        query["oneOf"][1]["properties"]["names"]["properties"]["limit"]["description"],
+        "Maximum number of names to return"
+    );
+
+    // response types keep their own docs
+    let config = &api["responses"]["config"];
+    assert_eq!(config["description"], "The contract configuration");
+    assert_eq!(
+        config["properties"]["owner"]["description"],
+        "Address that may update the config"
+    );
+    // undocumented response types are described by the query's docs
+    assert_eq!(
+        api["responses"]["names"]["description"],
+        "Returns the names of all members.\n\nThe list is sorted alphabetically."
+    );
+}
+
+mod generic {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg<T>
+    where
+        T: Clone,
+    {
+        #[returns(T)]
+        Item { key: String },
+        #[returns(Vec<T>)]
+        List { start_after: Option<T> },
+        #[returns(u32)]
+        Count {},
+    }
+
+    #[cw_serde]
+    pub struct Member {
+        pub weight: u64,
+    }
+}
+
+#[test]
+fn test_generic_query_responses() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        query: generic::QueryMsg<generic::Member>,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+
+    let responses = &api["responses"];
+    assert_eq!(responses["item"]["title"], "Member");
+    assert_eq!(responses["list"]["title"], "Array_of_Member");
+    assert_eq!(responses["list"]["items"]["$ref"], "#/definitions/Member");
+    assert_eq!(responses["count"]["title"], "uint32");
+}
+
+mod nested {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum BaseQueryMsg {
+        #[returns(String)]
+        Owner {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum ExtensionQueryMsg<T> {
+        #[returns(Vec<T>)]
+        Items { start_after: Option<T> },
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    #[serde(untagged)]
+    #[query_responses(nested)]
+    pub enum QueryMsg {
+        Base(BaseQueryMsg),
+        Extension(ExtensionQueryMsg<u64>),
+    }
+}
+
+#[test]
+fn test_nested_query_responses() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        query: nested::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+
+    let responses = api["responses"].as_object().unwrap();
+    assert_eq!(responses.keys().collect::<Vec<_>>(), ["items", "owner"]);
+    assert_eq!(responses["owner"]["title"], "String");
+    assert_eq!(responses["items"]["title"], "Array_of_uint64");
+
+    // the queries are flattened on the wire
+    let query: nested::QueryMsg = serde_json::from_str(r#"{"owner":{}}"#).unwrap();
+    assert_eq!(
+        query,
+        nested::QueryMsg::Base(nested::BaseQueryMsg::Owner {})
+    );
+}
+
+mod renamed {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum ExtensionQueryMsg {
+        #[returns(u64)]
+        #[serde(rename = "total_v2")]
+        Total {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(String)]
+        Owner {},
+        #[returns(String)]
+        #[serde(rename = "cfg")]
+        Config {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    #[serde(untagged)]
+    #[query_responses(nested)]
+    pub enum CombinedQueryMsg {
+        Base(QueryMsg),
+        Extension(ExtensionQueryMsg),
+    }
+}
+
+#[test]
+fn test_renamed_query_responses() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        query: renamed::CombinedQueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+
+    // the responses use the names of the queries on the wire
+    let responses = api["responses"].as_object().unwrap();
+    assert_eq!(
+        responses.keys().collect::<Vec<_>>(),
+        ["cfg", "owner", "total_v2"]
+    );
+    assert_eq!(responses["total_v2"]["title"], "uint64");
+    let query: renamed::CombinedQueryMsg = serde_json::from_str(r#"{"cfg":{}}"#).unwrap();
+    assert_eq!(
+        query,
+        renamed::CombinedQueryMsg::Base(renamed::QueryMsg::Config {})
+    );
+}
+
+mod aliased {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        /// The total supply
+        #[returns(u64)]
+        #[query_alias("total_supply")]
+        Supply {},
+        #[returns(String)]
+        #[query_alias("get_owner")]
+        Owner {},
+    }
+}
+
+#[test]
+fn test_query_aliases() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        query: aliased::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+
+    // the legacy names are documented as deprecated
+    let variants = api["query"]["oneOf"].as_array().unwrap();
+    let names: Vec<_> = variants
+        .iter()
+        .map(|variant| variant["required"][0].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["supply", "total_supply", "owner", "get_owner"]);
+    assert_eq!(variants[1]["deprecated"], true);
+    assert_eq!(
+        variants[1]["description"],
+        "The total supply\n\nDeprecated alias of `supply`."
+    );
+    let responses = api["responses"].as_object().unwrap();
+    assert_eq!(
+        responses.keys().collect::<Vec<_>>(),
+        ["get_owner", "owner", "supply", "total_supply"]
+    );
+    assert_eq!(responses["total_supply"], responses["supply"]);
+
+    // and still accepted on the wire, while the current names are used for serialization
+    let query: aliased::QueryMsg = serde_json::from_str(r#"{"total_supply":{}}"#).unwrap();
+    assert_eq!(query, aliased::QueryMsg::Supply {});
+    assert_eq!(serde_json::to_string(&query).unwrap(), r#"{"supply":{}}"#);
+}
+
+#[test]
+fn test_openapi_for_nested_queries() {
+    let openapi = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: nested::QueryMsg,
+    }
+    .render()
+    .to_openapi()
+    .unwrap();
+    let openapi: Value = serde_json::from_str(&openapi).unwrap();
+
+    assert_eq!(openapi["info"]["title"], "test");
+    let paths = openapi["paths"].as_object().unwrap();
+    assert_eq!(paths.keys().collect::<Vec<_>>(), ["/items", "/owner"]);
+    let items = &paths["/items"]["post"];
+    assert_eq!(
+        items["requestBody"]["content"]["application/json"]["schema"]["required"],
+        serde_json::json!(["items"])
+    );
+    assert_eq!(
+        items["responses"]["200"]["content"]["application/json"]["schema"]["type"],
+        "array"
+    );
+}
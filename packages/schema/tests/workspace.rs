@@ -0,0 +1,109 @@
+use std::fs::{read_to_string, write};
+
+use cosmwasm_schema::{
+    cw_serde, generate_api, write_workspace_api, QueryResponses, WorkspaceExportError, IDL_VERSION,
+    WORKSPACE_INDEX_FILE,
+};
+use serde_json::Value;
+
+#[cw_serde]
+pub struct TokenInstantiateMsg {
+    pub cap: u128,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum TokenQueryMsg {
+    #[returns(u128)]
+    Balance { account: String },
+}
+
+#[cw_serde]
+pub struct NftInstantiateMsg {
+    pub minter: String,
+}
+
+#[test]
+fn write_workspace_api_works() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().join("schema");
+
+    let index_path = write_workspace_api(
+        &out_dir,
+        vec![
+            generate_api! {
+                name: "token",
+                version: "1.2.3",
+                instantiate: TokenInstantiateMsg,
+                query: TokenQueryMsg,
+            },
+            generate_api! {
+                name: "nft",
+                version: "0.1.0",
+                instantiate: NftInstantiateMsg,
+            },
+        ],
+    )
+    .unwrap();
+    assert_eq!(index_path, out_dir.join(WORKSPACE_INDEX_FILE));
+
+    let index: Value = serde_json::from_str(&read_to_string(&index_path).unwrap()).unwrap();
+    assert_eq!(
+        index,
+        serde_json::json!({
+            "idl_version": IDL_VERSION,
+            "contracts": [
+                {
+                    "contract_name": "nft",
+                    "contract_version": "0.1.0",
+                    "path": "nft/nft.json"
+                },
+                {
+                    "contract_name": "token",
+                    "contract_version": "1.2.3",
+                    "path": "token/token.json"
+                }
+            ]
+        })
+    );
+
+    let token: Value =
+        serde_json::from_str(&read_to_string(out_dir.join("token/token.json")).unwrap()).unwrap();
+    assert_eq!(token["contract_name"], "token");
+    assert_eq!(token["instantiate"]["title"], "InstantiateMsg");
+    assert_eq!(token["query"]["title"], "QueryMsg");
+    assert!(token["responses"]["balance"].is_object());
+    let nft: Value =
+        serde_json::from_str(&read_to_string(out_dir.join("nft/nft.json")).unwrap()).unwrap();
+    assert_eq!(nft["contract_version"], "0.1.0");
+    assert!(nft["query"].is_null());
+}
+
+#[test]
+fn write_workspace_api_removes_stale_schemas() {
+    let dir = tempfile::tempdir().unwrap();
+    let api = || generate_api! { name: "nft", instantiate: NftInstantiateMsg };
+
+    write_workspace_api(dir.path(), vec![api()]).unwrap();
+    let stale = dir.path().join("nft/nft_instantiate_msg.json");
+    write(&stale, "{}").unwrap();
+
+    write_workspace_api(dir.path(), vec![api()]).unwrap();
+    assert!(!stale.exists());
+    assert!(dir.path().join("nft/nft.json").exists());
+}
+
+#[test]
+fn write_workspace_api_rejects_duplicate_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = write_workspace_api(
+        dir.path(),
+        vec![
+            generate_api! { name: "nft", instantiate: NftInstantiateMsg },
+            generate_api! { name: "nft", instantiate: TokenInstantiateMsg },
+        ],
+    )
+    .unwrap_err();
+    assert!(matches!(err, WorkspaceExportError::DuplicateContract(name) if name == "nft"));
+    assert!(!dir.path().join(WORKSPACE_INDEX_FILE).exists());
+}
@@ -0,0 +1,344 @@
+use crate::addresses::Addr;
+use crate::errors::{StdError, StdResult};
+#[cfg(feature = "iterator")]
+use crate::iterator::Order;
+use crate::results::Event;
+use crate::traits::{Api, Storage};
+
+/// The event type emitted by [`AddrSet::add_many`] and [`AddrSet::remove_many`]
+pub const ADDR_SET_EVENT: &str = "addr_set";
+
+/// A set of addresses in storage, e.g. for allowlists and blocklists.
+///
+/// Members are stored under their canonical address, so that differently formatted versions
+/// of the same address (such as upper and lower case bech32) are the same member. Checking
+/// membership takes a single storage read and the number of members is tracked separately.
+///
+/// All data is stored under a length-prefixed namespace, which must not be used
+/// by anything else.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::testing::mock_dependencies;
+/// use cosmwasm_std::{Addr, AddrSet};
+///
+/// const ALLOWLIST: AddrSet = AddrSet::new(b"allowlist");
+///
+/// let mut deps = mock_dependencies();
+/// let event = ALLOWLIST
+///     .add_many(&mut deps.storage, &deps.api, &["alice", "bob", "alice"])
+///     .unwrap();
+/// assert_eq!(event.attributes.len(), 4); // set, action and the two added addresses
+///
+/// let alice = Addr::unchecked("alice");
+/// assert!(ALLOWLIST.contains(&deps.storage, &deps.api, &alice).unwrap());
+/// assert_eq!(ALLOWLIST.len(&deps.storage).unwrap(), 2);
+/// ```
+pub struct AddrSet<'a> {
+    namespace: &'a [u8],
+}
+
+const COUNT_KEY: u8 = b'n';
+const MEMBER_KEY: u8 = b'm';
+
+impl<'a> AddrSet<'a> {
+    pub const fn new(namespace: &'a [u8]) -> Self {
+        AddrSet { namespace }
+    }
+
+    fn prefix(&self, kind: u8) -> Vec<u8> {
+        if self.namespace.len() > 0xFFFF {
+            panic!("only supports namespaces up to length 0xFFFF")
+        }
+        let mut out = Vec::with_capacity(self.namespace.len() + 3);
+        out.extend_from_slice(&(self.namespace.len() as u16).to_be_bytes());
+        out.extend_from_slice(self.namespace);
+        out.push(kind);
+        out
+    }
+
+    fn member_key(&self, api: &dyn Api, addr: &Addr) -> StdResult<Vec<u8>> {
+        let mut key = self.prefix(MEMBER_KEY);
+        key.extend_from_slice(api.addr_canonicalize(addr.as_str())?.as_slice());
+        Ok(key)
+    }
+
+    /// The number of members
+    pub fn len(&self, storage: &dyn Storage) -> StdResult<u64> {
+        match storage.get(&self.prefix(COUNT_KEY)) {
+            Some(bytes) => {
+                let bytes: [u8; 8] = bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| StdError::parse_err("AddrSet", "invalid member count"))?;
+                Ok(u64::from_be_bytes(bytes))
+            }
+            None => Ok(0),
+        }
+    }
+
+    pub fn is_empty(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.len(storage)? == 0)
+    }
+
+    fn set_len(&self, storage: &mut dyn Storage, len: u64) {
+        storage.set(&self.prefix(COUNT_KEY), &len.to_be_bytes());
+    }
+
+    pub fn contains(&self, storage: &dyn Storage, api: &dyn Api, addr: &Addr) -> StdResult<bool> {
+        Ok(storage.get(&self.member_key(api, addr)?).is_some())
+    }
+
+    /// Adds the address and returns whether it was not a member before
+    pub fn add(&self, storage: &mut dyn Storage, api: &dyn Api, addr: &Addr) -> StdResult<bool> {
+        let key = self.member_key(api, addr)?;
+        if storage.get(&key).is_some() {
+            return Ok(false);
+        }
+        // The address is stored as value so that pagination does not need to humanize keys
+        storage.set(&key, addr.as_bytes());
+        let len = self.len(storage)?;
+        self.set_len(storage, len + 1);
+        Ok(true)
+    }
+
+    /// Removes the address and returns whether it was a member before
+    pub fn remove(&self, storage: &mut dyn Storage, api: &dyn Api, addr: &Addr) -> StdResult<bool> {
+        let key = self.member_key(api, addr)?;
+        if storage.get(&key).is_none() {
+            return Ok(false);
+        }
+        storage.remove(&key);
+        let len = self.len(storage)?;
+        self.set_len(storage, len - 1);
+        Ok(true)
+    }
+
+    /// Validates all addresses and adds them to the set.
+    ///
+    /// If one of the addresses is invalid, an error mentioning its position is returned and
+    /// the set is not changed. The returned event lists the newly added addresses.
+    pub fn add_many(
+        &self,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        addresses: &[impl AsRef<str>],
+    ) -> StdResult<Event> {
+        let validated = addresses
+            .iter()
+            .enumerate()
+            .map(|(index, addr)| {
+                api.addr_validate(addr.as_ref()).map_err(|err| {
+                    StdError::generic_err(format!("Invalid address at index {}: {}", index, err))
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut event = self.event("add");
+        for addr in validated {
+            if self.add(storage, api, &addr)? {
+                event = event.add_attribute("address", addr);
+            }
+        }
+        Ok(event)
+    }
+
+    /// Removes all given addresses. The returned event lists the addresses that were members.
+    pub fn remove_many(
+        &self,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        addresses: &[Addr],
+    ) -> StdResult<Event> {
+        let mut event = self.event("remove");
+        for addr in addresses {
+            if self.remove(storage, api, addr)? {
+                event = event.add_attribute("address", addr.as_str());
+            }
+        }
+        Ok(event)
+    }
+
+    fn event(&self, action: &str) -> Event {
+        Event::new(ADDR_SET_EVENT)
+            .add_attribute("set", String::from_utf8_lossy(self.namespace))
+            .add_attribute("action", action)
+    }
+
+    /// Returns up to `limit` members following `start_after`, or from the beginning if it is `None`.
+    ///
+    /// Members are sorted by their canonical address, which is stable but generally differs
+    /// from the order of the human readable addresses. Pass the last address of a page as
+    /// `start_after` to get the next page.
+    #[cfg(feature = "iterator")]
+    pub fn paginate(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        start_after: Option<&Addr>,
+        limit: usize,
+    ) -> StdResult<Vec<Addr>> {
+        let prefix = self.prefix(MEMBER_KEY);
+        let start = match start_after {
+            Some(addr) => {
+                // the smallest key after the given member
+                let mut start = self.member_key(api, addr)?;
+                start.push(0x00);
+                start
+            }
+            None => prefix.clone(),
+        };
+        let mut end = prefix;
+        // the member prefix ends with `MEMBER_KEY`, so incrementing gives the end of the range
+        *end.last_mut().unwrap() += 1;
+
+        storage
+            .range(Some(&start), Some(&end), Order::Ascending)
+            .take(limit)
+            .map(|(_, value)| {
+                String::from_utf8(value)
+                    .map(Addr::unchecked)
+                    .map_err(|_| StdError::invalid_utf8("AddrSet"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockApi, MockStorage};
+
+    const SET: AddrSet = AddrSet::new(b"set");
+
+    fn addr(input: &str) -> Addr {
+        Addr::unchecked(input)
+    }
+
+    #[test]
+    fn add_remove_contains_works() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        assert!(SET.is_empty(&storage).unwrap());
+        assert!(!SET.contains(&storage, &api, &addr("alice")).unwrap());
+
+        assert!(SET.add(&mut storage, &api, &addr("alice")).unwrap());
+        assert!(!SET.add(&mut storage, &api, &addr("alice")).unwrap());
+        assert!(SET.add(&mut storage, &api, &addr("bob")).unwrap());
+        assert_eq!(SET.len(&storage).unwrap(), 2);
+        assert!(SET.contains(&storage, &api, &addr("alice")).unwrap());
+        // the canonical address is the same
+        assert!(SET.contains(&storage, &api, &addr("ALICE")).unwrap());
+        assert!(!SET.contains(&storage, &api, &addr("carol")).unwrap());
+
+        assert!(SET.remove(&mut storage, &api, &addr("alice")).unwrap());
+        assert!(!SET.remove(&mut storage, &api, &addr("alice")).unwrap());
+        assert!(!SET.contains(&storage, &api, &addr("alice")).unwrap());
+        assert_eq!(SET.len(&storage).unwrap(), 1);
+
+        // sets are independent
+        let other = AddrSet::new(b"other");
+        assert!(!other.contains(&storage, &api, &addr("bob")).unwrap());
+        assert!(other.is_empty(&storage).unwrap());
+    }
+
+    #[test]
+    fn add_many_works() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        SET.add(&mut storage, &api, &addr("bob")).unwrap();
+
+        let event = SET
+            .add_many(&mut storage, &api, &["alice", "bob", "carol", "alice"])
+            .unwrap();
+        assert_eq!(
+            event,
+            Event::new("addr_set")
+                .add_attribute("set", "set")
+                .add_attribute("action", "add")
+                .add_attribute("address", "alice")
+                .add_attribute("address", "carol")
+        );
+        assert_eq!(SET.len(&storage).unwrap(), 3);
+    }
+
+    #[test]
+    fn add_many_validates_all_addresses_first() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+
+        let err = SET
+            .add_many(&mut storage, &api, &["alice", "Bob", "carol"])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Invalid address at index 1: Generic error: Invalid input: address not normalized"
+        );
+        assert!(SET.is_empty(&storage).unwrap());
+        assert!(!SET.contains(&storage, &api, &addr("alice")).unwrap());
+    }
+
+    #[test]
+    fn remove_many_works() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        SET.add_many(&mut storage, &api, &["alice", "bob"]).unwrap();
+
+        let event = SET
+            .remove_many(&mut storage, &api, &[addr("bob"), addr("carol")])
+            .unwrap();
+        assert_eq!(
+            event,
+            Event::new("addr_set")
+                .add_attribute("set", "set")
+                .add_attribute("action", "remove")
+                .add_attribute("address", "bob")
+        );
+        assert_eq!(SET.len(&storage).unwrap(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn paginate_works() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        let members: Vec<String> = (0..25).map(|i| format!("member{}", i)).collect();
+        SET.add_many(&mut storage, &api, &members).unwrap();
+        // members of other sets are not included
+        AddrSet::new(b"sett")
+            .add(&mut storage, &api, &addr("intruder"))
+            .unwrap();
+
+        let mut pages = vec![];
+        let mut start_after = None;
+        loop {
+            let page = SET
+                .paginate(&storage, &api, start_after.as_ref(), 10)
+                .unwrap();
+            if page.is_empty() {
+                break;
+            }
+            start_after = page.last().cloned();
+            pages.push(page);
+        }
+        assert_eq!(
+            pages.iter().map(|page| page.len()).collect::<Vec<_>>(),
+            [10, 10, 5]
+        );
+
+        let mut all: Vec<String> = pages.concat().into_iter().map(String::from).collect();
+        all.sort();
+        let mut expected = members;
+        expected.sort();
+        assert_eq!(all, expected);
+
+        // pagination continues after a removed member
+        let first_page = SET.paginate(&storage, &api, None, 3).unwrap();
+        SET.remove(&mut storage, &api, &first_page[1]).unwrap();
+        let next = SET
+            .paginate(&storage, &api, Some(&first_page[1]), 1)
+            .unwrap();
+        assert_eq!(next, [first_page[2].clone()]);
+    }
+}
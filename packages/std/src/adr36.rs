@@ -0,0 +1,147 @@
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::encoding::{bech32_decode, bech32_encode, Bech32Variant};
+use crate::errors::{Bech32Error, VerificationError};
+use crate::{Api, Binary};
+
+/// Creates the [ADR-36](https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md)
+/// sign doc for arbitrary data, i.e. the bytes whose SHA-256 hash a wallet signs in
+/// `signArbitrary` (Keplr, Leap and others).
+///
+/// This is the Amino JSON encoding of a `StdSignDoc` with a single `MsgSignData`,
+/// no fee, an empty chain ID and zero account number and sequence.
+pub fn adr36_sign_doc(signer: &str, data: &[u8]) -> Vec<u8> {
+    // The amino JSON sign doc with sorted keys. Signer and base64 data do not need escaping.
+    format!(
+        r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+        Binary::from(data).to_base64(),
+        signer
+    )
+    .into_bytes()
+}
+
+/// Verifies an [ADR-36](https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md)
+/// signature of `data` by `signer`, as created by `signArbitrary` in wallets like Keplr and Leap.
+///
+/// The signature is the 64 byte compact secp256k1 signature and the public key the 33 byte
+/// compressed key, i.e. the base64 decoded `signature` and `pub_key.value` fields returned
+/// by the wallet.
+///
+/// Returns false if the signature is invalid or the public key does not belong to `signer`.
+/// Data signed as a string in the wallet must be passed as its UTF-8 bytes.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{verify_adr36, Deps, StdError, StdResult};
+/// fn login(deps: Deps, signer: &str, signature: &[u8], pubkey: &[u8]) -> StdResult<()> {
+///     let challenge = b"Sign in to example.app";
+///     if !verify_adr36(deps.api, signer, challenge, signature, pubkey)? {
+///         return Err(StdError::generic_err("Invalid login signature"));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn verify_adr36(
+    api: &dyn Api,
+    signer: &str,
+    data: &[u8],
+    signature: &[u8],
+    pubkey: &[u8],
+) -> Result<bool, VerificationError> {
+    if pubkey.len() != 33 {
+        return Err(VerificationError::InvalidPubkeyFormat);
+    }
+    let prefix = match bech32_decode(signer) {
+        Ok((prefix, _, Bech32Variant::Bech32)) => prefix,
+        _ => return Ok(false),
+    };
+    match secp256k1_pubkey_to_address(&prefix, pubkey) {
+        Ok(address) if address == signer => {}
+        _ => return Ok(false),
+    }
+    let hash = Sha256::digest(adr36_sign_doc(signer, data));
+    api.secp256k1_verify(&hash, signature, pubkey)
+}
+
+/// Derives the Cosmos SDK account address of a compressed secp256k1 public key,
+/// i.e. the bech32 encoding of `ripemd160(sha256(pubkey))`.
+pub fn secp256k1_pubkey_to_address(
+    bech32_prefix: &str,
+    pubkey: &[u8],
+) -> Result<String, Bech32Error> {
+    let hash = Ripemd160::digest(Sha256::digest(pubkey));
+    bech32_encode(bech32_prefix, &hash, Bech32Variant::Bech32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockApi;
+    use hex_literal::hex;
+
+    // Created with a fixed test key using the Python cryptography package
+    const PUBKEY: [u8; 33] =
+        hex!("02bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020d");
+    const SIGNER: &str = "cosmos15wgtkntdf26hqan77g0kdsldcxjddypxughytg";
+    const DATA: &[u8] = b"Sign in to example.app\nNonce: 42";
+    const SIGNATURE: [u8; 64] = hex!("1d8eafa57b5978272cd8e3fde392c4c42ba32ebf329b10a232bde2e2b704f20f3d190eb0e55af1149ea89c366082fc9db2f63d3797d3bb23c16d9f543b80a06b");
+
+    #[test]
+    fn secp256k1_pubkey_to_address_works() {
+        assert_eq!(
+            secp256k1_pubkey_to_address("cosmos", &PUBKEY).unwrap(),
+            SIGNER
+        );
+        assert_eq!(
+            secp256k1_pubkey_to_address("juno", &PUBKEY).unwrap(),
+            "juno15wgtkntdf26hqan77g0kdsldcxjddypx265lv5"
+        );
+    }
+
+    #[test]
+    fn adr36_sign_doc_works() {
+        let doc = adr36_sign_doc(SIGNER, b"hello");
+        assert_eq!(
+            String::from_utf8(doc).unwrap(),
+            r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"aGVsbG8=","signer":"cosmos15wgtkntdf26hqan77g0kdsldcxjddypxughytg"}}],"sequence":"0"}"#
+        );
+    }
+
+    #[test]
+    fn verify_adr36_works() {
+        let api = MockApi::default();
+        assert!(verify_adr36(&api, SIGNER, DATA, &SIGNATURE, &PUBKEY).unwrap());
+
+        // other data
+        assert!(!verify_adr36(&api, SIGNER, b"Sign in", &SIGNATURE, &PUBKEY).unwrap());
+
+        // manipulated signature
+        let mut signature = SIGNATURE;
+        signature[10] ^= 1;
+        assert!(!verify_adr36(&api, SIGNER, DATA, &signature, &PUBKEY).unwrap());
+    }
+
+    #[test]
+    fn verify_adr36_checks_signer() {
+        let api = MockApi::default();
+
+        // same key on another chain is a different signer
+        let other_prefix = secp256k1_pubkey_to_address("juno", &PUBKEY).unwrap();
+        assert!(!verify_adr36(&api, &other_prefix, DATA, &SIGNATURE, &PUBKEY).unwrap());
+
+        // signer not matching the key
+        let other_key = secp256k1_pubkey_to_address("cosmos", &[3; 33]).unwrap();
+        assert!(!verify_adr36(&api, &other_key, DATA, &SIGNATURE, &PUBKEY).unwrap());
+
+        // not a bech32 address
+        assert!(!verify_adr36(&api, "cosmos", DATA, &SIGNATURE, &PUBKEY).unwrap());
+
+        // uncompressed or truncated public key
+        assert_eq!(
+            verify_adr36(&api, SIGNER, DATA, &SIGNATURE, &PUBKEY[1..]).unwrap_err(),
+            VerificationError::InvalidPubkeyFormat
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::traits::{Querier, QuerierResult};
+
+/// A [`Querier`] that memoizes the results of another querier, such that identical
+/// requests only cause one host round trip.
+///
+/// This is useful when the same query (e.g. a price from an oracle contract) is issued
+/// from different code paths within one execution. Requests are compared by their
+/// serialized form, so they only match if they are serialized identically.
+///
+/// The results are kept for the lifetime of the `CachingQuerier`, so create one per
+/// execution. Queries observe the state at the beginning of the execution, with one
+/// exception: a contract querying itself after a storage write may see the write.
+/// Use [`CachingQuerier::clear`] after writing if such queries are cached.
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+/// # use cosmwasm_std::{Response, StdResult};
+/// use cosmwasm_std::{CachingQuerier, DepsMut, Empty, Env, MessageInfo, QuerierWrapper};
+///
+/// fn execute(deps: DepsMut, env: Env, _info: MessageInfo) -> StdResult<Response> {
+///     let cache = CachingQuerier::new(&*deps.querier);
+///     let querier = QuerierWrapper::<Empty>::new(&cache);
+///     // Only the first call reaches the host
+///     let first = querier.query_balance(&env.contract.address, "uatom")?;
+///     let second = querier.query_balance(&env.contract.address, "uatom")?;
+///     assert_eq!(first, second);
+///     Ok(Response::new())
+/// }
+/// # let mut deps = mock_dependencies();
+/// # execute(deps.as_mut(), mock_env(), mock_info("creator", &[])).unwrap();
+/// ```
+pub struct CachingQuerier<'a> {
+    querier: &'a dyn Querier,
+    cache: RefCell<HashMap<Vec<u8>, QuerierResult>>,
+}
+
+impl<'a> CachingQuerier<'a> {
+    pub fn new(querier: &'a dyn Querier) -> Self {
+        CachingQuerier {
+            querier,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Removes all cached results
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+    }
+}
+
+impl<'a> Querier for CachingQuerier<'a> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        if let Some(result) = self.cache.borrow().get(bin_request) {
+            return result.clone();
+        }
+        // The cache is not borrowed during the query, such that queriers calling back
+        // into this one do not panic
+        let result = self.querier.raw_query(bin_request);
+        self.cache
+            .borrow_mut()
+            .insert(bin_request.to_vec(), result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockQuerier;
+    use crate::{coin, BankQuery, Empty, QuerierWrapper, QueryRequest};
+    use std::cell::Cell;
+
+    /// Counts the queries reaching the inner querier
+    struct CountingQuerier {
+        inner: MockQuerier,
+        count: Cell<usize>,
+    }
+
+    impl Querier for CountingQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            self.count.set(self.count.get() + 1);
+            self.inner.raw_query(bin_request)
+        }
+    }
+
+    #[test]
+    fn caching_querier_deduplicates_queries() {
+        let counting = CountingQuerier {
+            inner: MockQuerier::new(&[("alice", &[coin(5, "uatom")])]),
+            count: Cell::new(0),
+        };
+        let cache = CachingQuerier::new(&counting);
+        let querier = QuerierWrapper::<Empty>::new(&cache);
+
+        let balance = querier.query_balance("alice", "uatom").unwrap();
+        assert_eq!(balance, coin(5, "uatom"));
+        assert_eq!(querier.query_balance("alice", "uatom").unwrap(), balance);
+        assert_eq!(counting.count.get(), 1);
+
+        // different requests are not deduplicated
+        querier.query_balance("bob", "uatom").unwrap();
+        querier.query_all_balances("alice").unwrap();
+        assert_eq!(counting.count.get(), 3);
+
+        // errors are cached as well
+        let request: QueryRequest<Empty> = QueryRequest::Custom(Empty {});
+        querier.query::<Empty>(&request).unwrap_err();
+        querier.query::<Empty>(&request).unwrap_err();
+        assert_eq!(counting.count.get(), 4);
+
+        cache.clear();
+        querier
+            .query::<crate::BalanceResponse>(&QueryRequest::Bank(BankQuery::Balance {
+                address: "alice".to_string(),
+                denom: "uatom".to_string(),
+            }))
+            .unwrap();
+        assert_eq!(counting.count.get(), 5);
+    }
+}
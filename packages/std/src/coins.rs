@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::errors::{StdError, StdResult};
 use crate::math::Uint128;
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
@@ -83,6 +84,99 @@ pub fn has_coins(coins: &[Coin], required: &Coin) -> bool {
         .unwrap_or(false)
 }
 
+/// Where the remainder of a [`split_funds`] goes, i.e. the indivisible amounts
+/// that are left after rounding every share down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitRemainder {
+    /// The remainder goes to the first share
+    First,
+    /// The remainder goes to the last share
+    Last,
+    /// The remainder goes to the share with the largest weight (the first one in case of a tie)
+    Largest,
+    /// The remainder goes to the share at the given index
+    Index(usize),
+}
+
+/// Splits `funds` into one share per weight, proportional to the weights.
+///
+/// Every share is rounded down and the remainder of each denom is added to exactly
+/// one share according to `remainder`. This way the sum of all shares always equals
+/// the input and no dust is lost or created. Duplicate denoms in `funds` are merged and coins
+/// with a zero amount are omitted from the shares, so a share can be empty.
+///
+/// Weights must not be empty or all zero and an `Index` must be in range.
+///
+/// # Examples
+///
+/// ```
+/// use cosmwasm_std::{coin, coins, split_funds, SplitRemainder};
+///
+/// // 70% to the treasury, 30% to the developers
+/// let shares = split_funds(&coins(1001, "ucosm"), &[70, 30], SplitRemainder::First).unwrap();
+/// assert_eq!(shares, vec![coins(701, "ucosm"), coins(300, "ucosm")]);
+/// ```
+pub fn split_funds(
+    funds: &[Coin],
+    weights: &[u64],
+    remainder: SplitRemainder,
+) -> StdResult<Vec<Vec<Coin>>> {
+    let total_weight: u128 = weights.iter().map(|&w| w as u128).sum();
+    if total_weight == 0 {
+        return Err(StdError::generic_err(
+            "Weights must not be empty or all zero",
+        ));
+    }
+    let remainder_index = match remainder {
+        SplitRemainder::First => 0,
+        SplitRemainder::Last => weights.len() - 1,
+        SplitRemainder::Largest => {
+            let max = weights.iter().max().unwrap(); // weights are not empty here
+            weights.iter().position(|w| w == max).unwrap()
+        }
+        SplitRemainder::Index(index) => {
+            if index >= weights.len() {
+                return Err(StdError::generic_err(format!(
+                    "Remainder index {} out of range for {} weights",
+                    index,
+                    weights.len()
+                )));
+            }
+            index
+        }
+    };
+
+    // merge duplicate denoms such that there is only one remainder per denom
+    let mut merged: Vec<Coin> = Vec::with_capacity(funds.len());
+    for coin in funds {
+        match merged.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount = existing.amount.checked_add(coin.amount)?,
+            None => merged.push(coin.clone()),
+        }
+    }
+
+    let mut shares: Vec<Vec<Coin>> = vec![vec![]; weights.len()];
+    for coin in merged {
+        let mut amounts: Vec<Uint128> = weights
+            .iter()
+            .map(|&weight| coin.amount.multiply_ratio(weight, total_weight))
+            .collect();
+        // cannot underflow as all amounts are rounded down
+        let distributed: Uint128 = amounts.iter().sum();
+        amounts[remainder_index] += coin.amount - distributed;
+
+        for (share, amount) in shares.iter_mut().zip(amounts) {
+            if !amount.is_zero() {
+                share.push(Coin {
+                    denom: coin.denom.clone(),
+                    amount,
+                });
+            }
+        }
+    }
+    Ok(shares)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +260,116 @@ mod tests {
         // less than same type
         assert!(has_coins(&wallet, &coin(777, "ETH")));
     }
+
+    #[test]
+    fn split_funds_works() {
+        let funds = vec![coin(1000, "ucosm"), coin(7, "uatom")];
+        let shares = split_funds(&funds, &[1, 1, 2], SplitRemainder::First).unwrap();
+        assert_eq!(
+            shares,
+            vec![
+                vec![coin(250, "ucosm"), coin(3, "uatom")],
+                vec![coin(250, "ucosm"), coin(1, "uatom")],
+                vec![coin(500, "ucosm"), coin(3, "uatom")],
+            ]
+        );
+
+        // single weight gets everything
+        let shares = split_funds(&funds, &[5], SplitRemainder::Last).unwrap();
+        assert_eq!(shares, vec![funds.clone()]);
+
+        // no funds
+        let shares = split_funds(&[], &[1, 2], SplitRemainder::First).unwrap();
+        assert_eq!(shares, vec![vec![], vec![]]);
+    }
+
+    #[test]
+    fn split_funds_remainder_policies() {
+        let funds = coins(10, "ucosm");
+        let weights = [1, 1, 1];
+        let split = |remainder| split_funds(&funds, &weights, remainder).unwrap();
+        assert_eq!(
+            split(SplitRemainder::First),
+            vec![coins(4, "ucosm"), coins(3, "ucosm"), coins(3, "ucosm")]
+        );
+        assert_eq!(
+            split(SplitRemainder::Last),
+            vec![coins(3, "ucosm"), coins(3, "ucosm"), coins(4, "ucosm")]
+        );
+        assert_eq!(
+            split(SplitRemainder::Index(1)),
+            vec![coins(3, "ucosm"), coins(4, "ucosm"), coins(3, "ucosm")]
+        );
+        // first in case of a tie
+        assert_eq!(
+            split(SplitRemainder::Largest),
+            vec![coins(4, "ucosm"), coins(3, "ucosm"), coins(3, "ucosm")]
+        );
+        let shares = split_funds(&funds, &[1, 3, 3], SplitRemainder::Largest).unwrap();
+        assert_eq!(
+            shares,
+            vec![coins(1, "ucosm"), coins(5, "ucosm"), coins(4, "ucosm")]
+        );
+    }
+
+    #[test]
+    fn split_funds_never_loses_funds() {
+        let funds = vec![
+            coin(u128::MAX, "big"),
+            coin(1, "dust"),
+            coin(999_999_999_999, "odd"),
+        ];
+        let weights = [u64::MAX, 1, 0, 12345, u64::MAX - 1];
+        for remainder in [
+            SplitRemainder::First,
+            SplitRemainder::Last,
+            SplitRemainder::Largest,
+            SplitRemainder::Index(2),
+        ] {
+            let shares = split_funds(&funds, &weights, remainder).unwrap();
+            assert_eq!(shares.len(), weights.len());
+            for coin in &funds {
+                let total: Uint128 = shares
+                    .iter()
+                    .flatten()
+                    .filter(|c| c.denom == coin.denom)
+                    .map(|c| c.amount)
+                    .sum();
+                assert_eq!(total, coin.amount);
+            }
+            // no zero coins
+            assert!(shares.iter().flatten().all(|c| !c.amount.is_zero()));
+        }
+    }
+
+    #[test]
+    fn split_funds_merges_duplicate_denoms() {
+        let funds = vec![coin(3, "ucosm"), coin(5, "ucosm")];
+        let shares = split_funds(&funds, &[1, 1], SplitRemainder::First).unwrap();
+        assert_eq!(shares, vec![coins(4, "ucosm"), coins(4, "ucosm")]);
+
+        let overflowing = vec![coin(u128::MAX, "ucosm"), coin(1, "ucosm")];
+        let err = split_funds(&overflowing, &[1], SplitRemainder::First).unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+    }
+
+    #[test]
+    fn split_funds_rejects_invalid_input() {
+        let funds = coins(10, "ucosm");
+        let err = split_funds(&funds, &[], SplitRemainder::First).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Weights must not be empty or all zero"
+        );
+        let err = split_funds(&funds, &[0, 0], SplitRemainder::Last).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Weights must not be empty or all zero"
+        );
+        let err = split_funds(&funds, &[1, 2], SplitRemainder::Index(2)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Remainder index 2 out of range for 2 weights"
+        );
+    }
 }
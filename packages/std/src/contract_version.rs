@@ -0,0 +1,98 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{StdError, StdResult};
+use crate::serde::{from_slice, to_vec};
+use crate::traits::Storage;
+
+/// The storage key of the [`ContractVersion`].
+///
+/// This is the same key cw2 uses, such that contracts can switch to the helpers in this crate
+/// without a migration and other contracts and tools can read the version with a raw query.
+pub const CONTRACT_VERSION_KEY: &[u8] = b"contract_info";
+
+/// The name and version of the code a contract instance is running, e.g. to check
+/// which version is migrated from in `migrate`.
+///
+/// This is stored in the same format as in cw2.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ContractVersion {
+    /// The crate name of the contract, e.g. `crates.io:cw20-base`
+    pub contract: String,
+    /// The version of the contract, ideally [semver](https://semver.org)
+    pub version: String,
+}
+
+/// Stores the name and version of the contract. This should be called in `instantiate`
+/// and in `migrate`.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{get_contract_version, set_contract_version, MemoryStorage};
+///
+/// let mut storage = MemoryStorage::new();
+/// set_contract_version(&mut storage, "crates.io:my-contract", "1.2.3").unwrap();
+///
+/// let version = get_contract_version(&storage).unwrap();
+/// assert_eq!(version.contract, "crates.io:my-contract");
+/// assert_eq!(version.version, "1.2.3");
+/// ```
+pub fn set_contract_version(
+    storage: &mut dyn Storage,
+    contract: impl Into<String>,
+    version: impl Into<String>,
+) -> StdResult<()> {
+    let value = ContractVersion {
+        contract: contract.into(),
+        version: version.into(),
+    };
+    storage.set(CONTRACT_VERSION_KEY, &to_vec(&value)?);
+    Ok(())
+}
+
+/// Loads the name and version of the contract, which fails with [`StdError::NotFound`]
+/// if it was never stored.
+pub fn get_contract_version(storage: &dyn Storage) -> StdResult<ContractVersion> {
+    match storage.get(CONTRACT_VERSION_KEY) {
+        Some(data) => from_slice(&data),
+        None => Err(StdError::not_found("cosmwasm_std::ContractVersion")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn set_and_get_contract_version_work() {
+        let mut storage = MemoryStorage::new();
+        let err = get_contract_version(&storage).unwrap_err();
+        assert!(
+            matches!(err, StdError::NotFound { kind, .. } if kind == "cosmwasm_std::ContractVersion")
+        );
+
+        set_contract_version(&mut storage, "crates.io:foo", "0.1.0").unwrap();
+        assert_eq!(
+            get_contract_version(&storage).unwrap(),
+            ContractVersion {
+                contract: "crates.io:foo".to_string(),
+                version: "0.1.0".to_string(),
+            }
+        );
+
+        set_contract_version(&mut storage, "crates.io:foo", "0.2.0").unwrap();
+        assert_eq!(get_contract_version(&storage).unwrap().version, "0.2.0");
+    }
+
+    #[test]
+    fn contract_version_uses_cw2_format() {
+        let mut storage = MemoryStorage::new();
+        set_contract_version(&mut storage, "crates.io:foo", "0.1.0").unwrap();
+        assert_eq!(
+            storage.get(b"contract_info").unwrap(),
+            br#"{"contract":"crates.io:foo","version":"0.1.0"}"#
+        );
+    }
+}
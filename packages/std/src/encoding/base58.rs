@@ -0,0 +1,211 @@
+use sha2::{Digest, Sha256};
+
+use crate::errors::Base58Error;
+
+/// The Bitcoin base58 alphabet, which is also used by Solana, IPFS and others
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Encodes bytes as base58 using the Bitcoin alphabet.
+///
+/// Leading zero bytes are encoded as leading `1` characters.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::base58_encode;
+///
+/// assert_eq!(base58_encode(b"hello world"), "StV1DL6CwTryKyV");
+/// assert_eq!(base58_encode(&[0, 0, 1]), "112");
+/// ```
+pub fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+
+    // Base 58 digits in little endian order. log(256)/log(58) ≈ 1.37
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut out = String::with_capacity(zeros + digits.len());
+    out.push_str(&"1".repeat(zeros));
+    out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    out
+}
+
+/// Decodes a base58 string using the Bitcoin alphabet.
+pub fn base58_decode(encoded: &str) -> Result<Vec<u8>, Base58Error> {
+    let ones = encoded.bytes().take_while(|&b| b == b'1').count();
+
+    // Bytes in little endian order. log(58)/log(256) ≈ 0.733
+    let mut bytes: Vec<u8> = Vec::with_capacity(encoded.len() * 733 / 1000 + 1);
+    for (index, character) in encoded.char_indices().skip(ones) {
+        let value =
+            decode_char(character).ok_or(Base58Error::InvalidCharacter { character, index })?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut out = vec![0u8; ones];
+    out.extend(bytes.iter().rev());
+    Ok(out)
+}
+
+/// Encodes bytes as Base58Check, i.e. base58 with a 4 byte double SHA-256 checksum appended.
+///
+/// The payload usually starts with a version byte, which is not added by this function.
+pub fn base58check_encode(payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + CHECKSUM_LENGTH);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&checksum(payload));
+    base58_encode(&data)
+}
+
+/// Decodes a Base58Check string and verifies its checksum. The checksum is removed from the result.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::base58check_decode;
+///
+/// // A Bitcoin P2PKH address: version byte 0 followed by the public key hash
+/// let decoded = base58check_decode("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").unwrap();
+/// assert_eq!(decoded.len(), 21);
+/// assert_eq!(decoded[0], 0);
+/// ```
+pub fn base58check_decode(encoded: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut data = base58_decode(encoded)?;
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(Base58Error::TooShort);
+    }
+    let checksum_start = data.len() - CHECKSUM_LENGTH;
+    if data[checksum_start..] != checksum(&data[..checksum_start]) {
+        return Err(Base58Error::InvalidChecksum);
+    }
+    data.truncate(checksum_start);
+    Ok(data)
+}
+
+fn decode_char(character: char) -> Option<u8> {
+    if !character.is_ascii() {
+        return None;
+    }
+    ALPHABET
+        .iter()
+        .position(|&c| c == character as u8)
+        .map(|pos| pos as u8)
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    let mut out = [0u8; CHECKSUM_LENGTH];
+    out.copy_from_slice(&hash[..CHECKSUM_LENGTH]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    /// Test vectors from https://github.com/bitcoin/bitcoin/blob/v23.0/src/test/data/base58_encode_decode.json
+    const VECTORS: &[(&[u8], &str)] = &[
+        (&[], ""),
+        (&hex!("61"), "2g"),
+        (&hex!("626262"), "a3gV"),
+        (&hex!("636363"), "aPEr"),
+        (
+            &hex!("73696d706c792061206c6f6e6720737472696e67"),
+            "2cFupjhnEsSn59qHXstmK2ffpLv2",
+        ),
+        (
+            &hex!("00eb15231dfceb60925886b67d065299925915aeb172c06647"),
+            "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+        ),
+        (&hex!("516b6fcd0f"), "ABnLTmg"),
+        (&hex!("bf4f89001e670274dd"), "3SEo3LWLoPntC"),
+        (&hex!("572e4794"), "3EFU7m"),
+        (&hex!("ecac89cad93923c02321"), "EJDM8drfXA6uyA"),
+        (&hex!("10c8511e"), "Rt5zm"),
+        (&hex!("00000000000000000000"), "1111111111"),
+    ];
+
+    #[test]
+    fn base58_encode_works() {
+        for (data, encoded) in VECTORS {
+            assert_eq!(base58_encode(data), *encoded);
+        }
+    }
+
+    #[test]
+    fn base58_decode_works() {
+        for (data, encoded) in VECTORS {
+            assert_eq!(base58_decode(encoded).unwrap(), *data);
+        }
+    }
+
+    #[test]
+    fn base58_decode_rejects_invalid_characters() {
+        assert_eq!(
+            base58_decode("3SEo3L0LoPntC").unwrap_err(),
+            Base58Error::InvalidCharacter {
+                character: '0',
+                index: 6
+            }
+        );
+        assert_eq!(
+            base58_decode("1lI").unwrap_err(),
+            Base58Error::InvalidCharacter {
+                character: 'l',
+                index: 1
+            }
+        );
+        assert_eq!(
+            base58_decode("2gä").unwrap_err(),
+            Base58Error::InvalidCharacter {
+                character: 'ä',
+                index: 2
+            }
+        );
+        assert!(base58_decode(" 2g").is_err());
+    }
+
+    #[test]
+    fn base58check_works() {
+        // The address of the Bitcoin genesis block coinbase
+        let payload = hex!("0062e907b15cbf27d5425399ebf6f0fb50ebb88f18");
+        let encoded = base58check_encode(&payload);
+        assert_eq!(encoded, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+        assert_eq!(base58check_decode(&encoded).unwrap(), payload);
+
+        // empty payload
+        assert_eq!(base58check_decode(&base58check_encode(&[])).unwrap(), b"");
+    }
+
+    #[test]
+    fn base58check_decode_rejects_invalid_checksum() {
+        assert_eq!(
+            base58check_decode("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").unwrap_err(),
+            Base58Error::InvalidChecksum
+        );
+        assert_eq!(base58check_decode("2g").unwrap_err(), Base58Error::TooShort);
+    }
+}
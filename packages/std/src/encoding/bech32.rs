@@ -0,0 +1,498 @@
+use crate::errors::Bech32Error;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const SEPARATOR: char = '1';
+const CHECKSUM_LENGTH: usize = 6;
+const MAX_LENGTH: usize = 90;
+const GENERATORS: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// The checksum variant of a bech32 string.
+///
+/// Bech32 is specified in [BIP-173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki)
+/// and is the format used for Cosmos SDK addresses.
+/// Bech32m is specified in [BIP-350](https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki)
+/// and is used for segwit v1+ addresses such as Taproot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Bech32Variant {
+    const fn constant(self) -> u32 {
+        match self {
+            Bech32Variant::Bech32 => 1,
+            Bech32Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+
+    fn from_constant(constant: u32) -> Option<Self> {
+        match constant {
+            1 => Some(Bech32Variant::Bech32),
+            0x2bc830a3 => Some(Bech32Variant::Bech32m),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes bytes as a bech32 or bech32m string with the given human-readable part.
+///
+/// The human-readable part is converted to lowercase.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{bech32_decode, bech32_encode, Bech32Variant};
+///
+/// let encoded = bech32_encode("cosmos", &[0xab; 20], Bech32Variant::Bech32).unwrap();
+/// assert_eq!(encoded, "cosmos14w46h2at4w46h2at4w46h2at4w46h2atuw643a");
+///
+/// let (hrp, data, variant) = bech32_decode(&encoded).unwrap();
+/// assert_eq!(hrp, "cosmos");
+/// assert_eq!(data, [0xab; 20]);
+/// assert_eq!(variant, Bech32Variant::Bech32);
+/// ```
+pub fn bech32_encode(
+    hrp: &str,
+    data: &[u8],
+    variant: Bech32Variant,
+) -> Result<String, Bech32Error> {
+    encode_u5(hrp, &convert_bits(data, 8, 5, true)?, variant)
+}
+
+/// Decodes a bech32 or bech32m string into its lowercase human-readable part,
+/// the data bytes and the checksum variant that was used.
+///
+/// The data part must be padded correctly, i.e. it must have been created from whole bytes.
+pub fn bech32_decode(encoded: &str) -> Result<(String, Vec<u8>, Bech32Variant), Bech32Error> {
+    let (hrp, data, variant) = decode_u5(encoded)?;
+    Ok((hrp, convert_bits(&data, 5, 8, false)?, variant))
+}
+
+/// Encodes a segwit address as specified in BIP-173 and BIP-350.
+///
+/// Witness version 0 uses bech32, versions 1 to 16 use bech32m.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{decode_segwit_address, encode_segwit_address};
+///
+/// let program = [0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6];
+/// let address = encode_segwit_address("bc", 0, &program).unwrap();
+/// assert_eq!(address, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+/// assert_eq!(decode_segwit_address("bc", &address).unwrap(), (0, program.to_vec()));
+/// ```
+pub fn encode_segwit_address(
+    hrp: &str,
+    witness_version: u8,
+    program: &[u8],
+) -> Result<String, Bech32Error> {
+    validate_segwit_program(witness_version, program)?;
+    let mut data = Vec::with_capacity(2 + program.len() * 8 / 5);
+    data.push(witness_version);
+    data.extend(convert_bits(program, 8, 5, true)?);
+    encode_u5(hrp, &data, segwit_variant(witness_version))
+}
+
+/// Decodes a segwit address and returns its witness version and witness program.
+///
+/// The human-readable part must match `expected_hrp` (case-insensitive)
+/// and the checksum variant must match the witness version.
+pub fn decode_segwit_address(
+    expected_hrp: &str,
+    address: &str,
+) -> Result<(u8, Vec<u8>), Bech32Error> {
+    let (hrp, data, variant) = decode_u5(address)?;
+    if hrp != expected_hrp.to_ascii_lowercase() {
+        return Err(Bech32Error::HrpMismatch {
+            expected: expected_hrp.to_ascii_lowercase(),
+            actual: hrp,
+        });
+    }
+    let (&witness_version, program) = data.split_first().ok_or(Bech32Error::InvalidLength)?;
+    let program = convert_bits(program, 5, 8, false)?;
+    validate_segwit_program(witness_version, &program)?;
+    if variant != segwit_variant(witness_version) {
+        return Err(Bech32Error::InvalidVariant);
+    }
+    Ok((witness_version, program))
+}
+
+fn segwit_variant(witness_version: u8) -> Bech32Variant {
+    if witness_version == 0 {
+        Bech32Variant::Bech32
+    } else {
+        Bech32Variant::Bech32m
+    }
+}
+
+fn validate_segwit_program(witness_version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if witness_version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion(witness_version));
+    }
+    if !(2..=40).contains(&program.len()) {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+    if witness_version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+    Ok(())
+}
+
+fn validate_hrp(hrp: &str) -> Result<(), Bech32Error> {
+    if hrp.is_empty() || !hrp.bytes().all(|b| (33..=126).contains(&b)) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+    Ok(())
+}
+
+/// Encodes 5 bit values. The caller must ensure all values are < 32.
+fn encode_u5(hrp: &str, data: &[u8], variant: Bech32Variant) -> Result<String, Bech32Error> {
+    validate_hrp(hrp)?;
+    if hrp.len() + 1 + data.len() + CHECKSUM_LENGTH > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength);
+    }
+    let hrp = hrp.to_ascii_lowercase();
+
+    let mut values = hrp_expand(&hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0; CHECKSUM_LENGTH]);
+    let polymod = polymod(&values) ^ variant.constant();
+
+    let mut out = String::with_capacity(hrp.len() + 1 + data.len() + CHECKSUM_LENGTH);
+    out.push_str(&hrp);
+    out.push(SEPARATOR);
+    out.extend(data.iter().map(|&d| CHARSET[d as usize] as char));
+    out.extend(
+        (0..CHECKSUM_LENGTH).map(|i| CHARSET[((polymod >> (5 * (5 - i))) & 31) as usize] as char),
+    );
+    Ok(out)
+}
+
+/// Decodes into the lowercase human-readable part and the 5 bit values without checksum
+fn decode_u5(encoded: &str) -> Result<(String, Vec<u8>, Bech32Variant), Bech32Error> {
+    if encoded.len() > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength);
+    }
+    let has_lower = encoded.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = encoded.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(Bech32Error::MixedCase);
+    }
+    let encoded = encoded.to_ascii_lowercase();
+
+    let separator = encoded
+        .rfind(SEPARATOR)
+        .ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, data_part) = (&encoded[..separator], &encoded[separator + 1..]);
+    validate_hrp(hrp)?;
+    if data_part.len() < CHECKSUM_LENGTH {
+        return Err(Bech32Error::InvalidLength);
+    }
+
+    let mut data = Vec::with_capacity(data_part.len());
+    for character in data_part.chars() {
+        let value = CHARSET
+            .iter()
+            .position(|&c| c as char == character)
+            .ok_or(Bech32Error::InvalidCharacter { character })?;
+        data.push(value as u8);
+    }
+
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    let variant =
+        Bech32Variant::from_constant(polymod(&values)).ok_or(Bech32Error::InvalidChecksum)?;
+
+    data.truncate(data.len() - CHECKSUM_LENGTH);
+    Ok((hrp.to_string(), data, variant))
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(hrp.len() * 2 + 1);
+    out.extend(hrp.bytes().map(|b| b >> 5));
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk: u32 = 1;
+    for &value in values {
+        let top = chk >> 25;
+        chk = (chk & 0x1ffffff) << 5 ^ value as u32;
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+/// Regroups bits, e.g. from bytes (8 bits) to bech32 characters (5 bits) and back.
+///
+/// When `pad` is false, leftover bits must be fewer than `from` and all zero.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_value = (1 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max_value != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn bech32_checksums_work() {
+        // valid checksums from BIP-173
+        for valid in [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ] {
+            let (_, _, variant) = decode_u5(valid).unwrap();
+            assert_eq!(variant, Bech32Variant::Bech32, "{}", valid);
+        }
+
+        // valid checksums from BIP-350
+        for valid in [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ] {
+            let (_, _, variant) = decode_u5(valid).unwrap();
+            assert_eq!(variant, Bech32Variant::Bech32m, "{}", valid);
+        }
+    }
+
+    #[test]
+    fn bech32_decode_rejects_invalid_strings() {
+        // HRP character out of range
+        assert_eq!(
+            decode_u5("\x201nwldj5").unwrap_err(),
+            Bech32Error::InvalidHrp
+        );
+        // overall max length exceeded
+        assert_eq!(
+            decode_u5("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx")
+                .unwrap_err(),
+            Bech32Error::InvalidLength
+        );
+        assert_eq!(
+            decode_u5("pzry9x0s0muk").unwrap_err(),
+            Bech32Error::MissingSeparator
+        );
+        assert_eq!(
+            decode_u5("1pzry9x0s0muk").unwrap_err(),
+            Bech32Error::InvalidHrp
+        );
+        assert_eq!(
+            decode_u5("x1b4n0q5v").unwrap_err(),
+            Bech32Error::InvalidCharacter { character: 'b' }
+        );
+        // too short checksum
+        assert_eq!(
+            decode_u5("li1dgmt3").unwrap_err(),
+            Bech32Error::InvalidLength
+        );
+        assert_eq!(
+            decode_u5("A1G7SGD8").unwrap_err(),
+            Bech32Error::InvalidChecksum
+        );
+        assert_eq!(decode_u5("10a06t8").unwrap_err(), Bech32Error::InvalidHrp);
+        assert_eq!(decode_u5("A12uEL5L").unwrap_err(), Bech32Error::MixedCase);
+    }
+
+    #[test]
+    fn bech32_encode_decode_roundtrip() {
+        for variant in [Bech32Variant::Bech32, Bech32Variant::Bech32m] {
+            for len in [0, 1, 19, 20, 32, 45] {
+                let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37)).collect();
+                let encoded = bech32_encode("juno", &data, variant).unwrap();
+                assert_eq!(
+                    bech32_decode(&encoded).unwrap(),
+                    ("juno".to_string(), data, variant)
+                );
+            }
+        }
+
+        // uppercase HRP is normalized
+        let encoded = bech32_encode("COSMOS", &[1, 2, 3], Bech32Variant::Bech32).unwrap();
+        assert!(encoded.starts_with("cosmos1"));
+        let (hrp, ..) = bech32_decode(&encoded.to_ascii_uppercase()).unwrap();
+        assert_eq!(hrp, "cosmos");
+    }
+
+    #[test]
+    fn bech32_encode_rejects_invalid_input() {
+        assert_eq!(
+            bech32_encode("", &[1], Bech32Variant::Bech32).unwrap_err(),
+            Bech32Error::InvalidHrp
+        );
+        assert_eq!(
+            bech32_encode("a b", &[1], Bech32Variant::Bech32).unwrap_err(),
+            Bech32Error::InvalidHrp
+        );
+        assert_eq!(
+            bech32_encode("cosmos", &[0; 50], Bech32Variant::Bech32).unwrap_err(),
+            Bech32Error::InvalidLength
+        );
+    }
+
+    #[test]
+    fn bech32_decode_rejects_invalid_padding() {
+        // a single character are 5 bits, which is too much padding
+        let encoded = encode_u5("a", &[0], Bech32Variant::Bech32).unwrap();
+        assert_eq!(
+            bech32_decode(&encoded).unwrap_err(),
+            Bech32Error::InvalidPadding
+        );
+        // non-zero padding bits
+        let encoded = encode_u5("a", &[0, 1], Bech32Variant::Bech32).unwrap();
+        assert_eq!(
+            bech32_decode(&encoded).unwrap_err(),
+            Bech32Error::InvalidPadding
+        );
+    }
+
+    #[test]
+    fn segwit_addresses_work() {
+        // valid addresses from BIP-350
+        let cases: &[(&str, &str, &[u8])] = &[
+            (
+                "bc",
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+                &hex!("0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+            ),
+            (
+                "tb",
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+                &hex!("00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+            ),
+            (
+                "bc",
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+                &hex!("5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+            ),
+            ("bc", "BC1SW50QGDZ25J", &hex!("6002751e")),
+            (
+                "bc",
+                "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+                &hex!("5210751e76e8199196d454941c45d1b3a323"),
+            ),
+            (
+                "bc",
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                &hex!("512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+            ),
+        ];
+        for (hrp, address, script_pubkey) in cases {
+            // A script pubkey is OP_n (0x00 or 0x50 + n), the push length and the program
+            let witness_version = match script_pubkey[0] {
+                0 => 0,
+                op => op - 0x50,
+            };
+            let program = &script_pubkey[2..];
+
+            assert_eq!(
+                decode_segwit_address(hrp, address).unwrap(),
+                (witness_version, program.to_vec())
+            );
+            assert_eq!(
+                encode_segwit_address(hrp, witness_version, program).unwrap(),
+                address.to_ascii_lowercase()
+            );
+        }
+    }
+
+    #[test]
+    fn decode_segwit_address_rejects_invalid_addresses() {
+        // v1 encoded with bech32
+        assert_eq!(
+            decode_segwit_address(
+                "bc",
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"
+            )
+            .unwrap_err(),
+            Bech32Error::InvalidVariant
+        );
+        // v0 encoded with bech32m
+        assert_eq!(
+            decode_segwit_address("bc", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh").unwrap_err(),
+            Bech32Error::InvalidVariant
+        );
+        assert_eq!(
+            decode_segwit_address("tb", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap_err(),
+            Bech32Error::HrpMismatch {
+                expected: "tb".to_string(),
+                actual: "bc".to_string()
+            }
+        );
+        // invalid witness version
+        assert_eq!(
+            decode_segwit_address(
+                "bc",
+                "BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R"
+            )
+            .unwrap_err(),
+            Bech32Error::InvalidWitnessVersion(17)
+        );
+        // invalid program length (1 byte)
+        assert_eq!(
+            decode_segwit_address("bc", "bc1pw5dgrnzv").unwrap_err(),
+            Bech32Error::InvalidProgramLength(1)
+        );
+        // invalid program length for witness version 0
+        assert_eq!(
+            decode_segwit_address("bc", "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P").unwrap_err(),
+            Bech32Error::InvalidProgramLength(16)
+        );
+        // no witness version
+        assert_eq!(
+            decode_segwit_address("bc", "bc1gmk9yu").unwrap_err(),
+            Bech32Error::InvalidLength
+        );
+    }
+
+    #[test]
+    fn encode_segwit_address_rejects_invalid_programs() {
+        assert_eq!(
+            encode_segwit_address("bc", 17, &[0; 32]).unwrap_err(),
+            Bech32Error::InvalidWitnessVersion(17)
+        );
+        assert_eq!(
+            encode_segwit_address("bc", 0, &[0; 21]).unwrap_err(),
+            Bech32Error::InvalidProgramLength(21)
+        );
+        assert_eq!(
+            encode_segwit_address("bc", 1, &[0; 41]).unwrap_err(),
+            Bech32Error::InvalidProgramLength(41)
+        );
+    }
+}
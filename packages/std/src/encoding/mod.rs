@@ -0,0 +1,7 @@
+mod base58;
+mod bech32;
+
+pub use base58::{base58_decode, base58_encode, base58check_decode, base58check_encode};
+pub use bech32::{
+    bech32_decode, bech32_encode, decode_segwit_address, encode_segwit_address, Bech32Variant,
+};
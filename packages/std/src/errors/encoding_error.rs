@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Base58Error {
+    #[error("Invalid base58 character '{character}' at position {index}")]
+    InvalidCharacter { character: char, index: usize },
+    #[error("Base58Check data must be at least 4 bytes long")]
+    TooShort,
+    #[error("Invalid Base58Check checksum")]
+    InvalidChecksum,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Bech32Error {
+    #[error("Invalid bech32 string length")]
+    InvalidLength,
+    #[error("Bech32 string must not mix upper and lower case")]
+    MixedCase,
+    #[error("Missing bech32 separator '1'")]
+    MissingSeparator,
+    #[error("Invalid bech32 human-readable part")]
+    InvalidHrp,
+    #[error("Invalid bech32 character '{character}'")]
+    InvalidCharacter { character: char },
+    #[error("Invalid bech32 checksum")]
+    InvalidChecksum,
+    #[error("Invalid padding in bech32 data")]
+    InvalidPadding,
+    #[error("Unexpected human-readable part: expected {expected}, actual {actual}")]
+    HrpMismatch { expected: String, actual: String },
+    #[error("Invalid segwit witness version {0}")]
+    InvalidWitnessVersion(u8),
+    #[error("Invalid segwit program length {0}")]
+    InvalidProgramLength(usize),
+    #[error("Invalid checksum variant for segwit witness version")]
+    InvalidVariant,
+}
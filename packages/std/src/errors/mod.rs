@@ -1,9 +1,13 @@
+mod encoding_error;
 mod recover_pubkey_error;
+mod response_limit_error;
 mod std_error;
 mod system_error;
 mod verification_error;
 
+pub use encoding_error::{Base58Error, Bech32Error};
 pub use recover_pubkey_error::RecoverPubkeyError;
+pub use response_limit_error::ResponseLimitError;
 pub use std_error::{
     CheckedFromRatioError, CheckedMultiplyRatioError, ConversionOverflowError, DivideByZeroError,
     OverflowError, OverflowOperation, RoundUpOverflowError, StdError, StdResult,
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+/// A limit of [`ResponseLimits`](crate::ResponseLimits) a response exceeds
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResponseLimitError {
+    #[error("Response has {count} messages, the maximum is {max}")]
+    TooManyMessages { count: usize, max: usize },
+    #[error("Response has {count} attributes, the maximum is {max}")]
+    TooManyAttributes { count: usize, max: usize },
+    #[error("Attribute {key:?} has {size} bytes, the maximum is {max}")]
+    AttributeTooLarge {
+        key: String,
+        size: usize,
+        max: usize,
+    },
+    #[error("Response data has {size} bytes, the maximum is {max}")]
+    DataTooLarge { size: usize, max: usize },
+}
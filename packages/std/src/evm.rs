@@ -0,0 +1,329 @@
+use std::fmt;
+
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+use crate::errors::RecoverPubkeyError;
+use crate::{Api, Binary};
+
+/// A 20 byte address as used by Ethereum and other EVM chains.
+///
+/// It is displayed in the checksummed hex format of
+/// [EIP-55](https://eips.ethereum.org/EIPS/eip-55), e.g. `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{Binary, EvmAddress};
+/// let address = EvmAddress::from_hex("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+/// assert_eq!(address.to_string(), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+///
+/// let binary = Binary::from(address);
+/// assert_eq!(EvmAddress::try_from(&binary).unwrap(), address);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EvmAddress([u8; 20]);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum EvmAddressError {
+    #[error("EVM address must be 20 bytes long, got {length}")]
+    InvalidLength { length: usize },
+    #[error("EVM address must be 0x followed by 40 hex characters")]
+    InvalidHex,
+    #[error("Invalid EIP-55 checksum in EVM address")]
+    InvalidChecksum,
+    #[error("Public key must be an uncompressed secp256k1 key of 65 bytes")]
+    InvalidPubkey,
+    #[error("Signature must be 65 bytes (r, s, v) with v being 0, 1, 27 or 28")]
+    InvalidSignature,
+    #[error("{0}")]
+    Recover(#[from] RecoverPubkeyError),
+}
+
+impl EvmAddress {
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        EvmAddress(bytes)
+    }
+
+    /// Parses an address in hex format with a `0x` prefix.
+    ///
+    /// Mixed case addresses must have a valid EIP-55 checksum. All lowercase and all uppercase
+    /// addresses are accepted without a checksum, like in most Ethereum tooling.
+    pub fn from_hex(input: &str) -> Result<Self, EvmAddressError> {
+        let digits = input
+            .strip_prefix("0x")
+            .filter(|digits| digits.len() == 40)
+            .ok_or(EvmAddressError::InvalidHex)?;
+        let mut bytes = [0u8; 20];
+        for (byte, pair) in bytes.iter_mut().zip(digits.as_bytes().chunks_exact(2)) {
+            *byte = (hex_value(pair[0])? << 4) | hex_value(pair[1])?;
+        }
+        let address = EvmAddress(bytes);
+
+        let is_lowercase = !digits.bytes().any(|c| c.is_ascii_uppercase());
+        let is_uppercase = !digits.bytes().any(|c| c.is_ascii_lowercase());
+        if !is_lowercase && !is_uppercase && address.to_checksummed()[2..] != *digits {
+            return Err(EvmAddressError::InvalidChecksum);
+        }
+        Ok(address)
+    }
+
+    /// Derives the address of an uncompressed secp256k1 public key (65 bytes starting with 0x04),
+    /// i.e. the last 20 bytes of the Keccak-256 hash of the key without the prefix.
+    ///
+    /// This is the format returned by [`Api::secp256k1_recover_pubkey`].
+    pub fn from_pubkey(pubkey: &[u8]) -> Result<Self, EvmAddressError> {
+        if pubkey.len() != 65 || pubkey[0] != 0x04 {
+            return Err(EvmAddressError::InvalidPubkey);
+        }
+        let hash = Keccak256::digest(&pubkey[1..]);
+        let mut bytes = [0u8; 20];
+        bytes.copy_from_slice(&hash[12..]);
+        Ok(EvmAddress(bytes))
+    }
+
+    /// Recovers the address that created an Ethereum style signature of the given message hash
+    /// (`ecrecover`). The signature is 65 bytes long, consisting of r, s and the recovery id v.
+    ///
+    /// Use [`evm_personal_message_hash`] for signatures created with `personal_sign`.
+    pub fn recover(
+        api: &dyn Api,
+        message_hash: &[u8],
+        signature: &[u8],
+    ) -> Result<Self, EvmAddressError> {
+        if signature.len() != 65 {
+            return Err(EvmAddressError::InvalidSignature);
+        }
+        let recovery_param = match signature[64] {
+            0 | 27 => 0,
+            1 | 28 => 1,
+            _ => return Err(EvmAddressError::InvalidSignature),
+        };
+        let pubkey =
+            api.secp256k1_recover_pubkey(message_hash, &signature[..64], recovery_param)?;
+        Self::from_pubkey(&pubkey)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// Returns the address in hex format with EIP-55 checksum
+    pub fn to_checksummed(&self) -> String {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut lowercase = Vec::with_capacity(40);
+        for byte in self.0 {
+            lowercase.push(DIGITS[(byte >> 4) as usize]);
+            lowercase.push(DIGITS[(byte & 0xf) as usize]);
+        }
+
+        // Letters are uppercase if the corresponding nibble of the hash is 8 or higher
+        let hash = Keccak256::digest(&lowercase);
+        let mut out = String::with_capacity(42);
+        out.push_str("0x");
+        for (i, digit) in lowercase.into_iter().enumerate() {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0xf
+            };
+            if nibble >= 8 {
+                out.push(digit.to_ascii_uppercase() as char);
+            } else {
+                out.push(digit as char);
+            }
+        }
+        out
+    }
+}
+
+/// Returns the hash signed by `personal_sign` (EIP-191 version 0x45) for the given message,
+/// i.e. the Keccak-256 hash of `"\x19Ethereum Signed Message:\n" + len(message) + message`.
+pub fn evm_personal_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    Keccak256::digest(&data).into()
+}
+
+fn hex_value(digit: u8) -> Result<u8, EvmAddressError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(EvmAddressError::InvalidHex),
+    }
+}
+
+impl fmt::Display for EvmAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_checksummed())
+    }
+}
+
+impl From<[u8; 20]> for EvmAddress {
+    fn from(bytes: [u8; 20]) -> Self {
+        EvmAddress(bytes)
+    }
+}
+
+impl From<EvmAddress> for Binary {
+    fn from(address: EvmAddress) -> Self {
+        Binary::from(address.0)
+    }
+}
+
+impl TryFrom<&[u8]> for EvmAddress {
+    type Error = EvmAddressError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; 20] = value
+            .try_into()
+            .map_err(|_| EvmAddressError::InvalidLength {
+                length: value.len(),
+            })?;
+        Ok(EvmAddress(bytes))
+    }
+}
+
+impl TryFrom<&Binary> for EvmAddress {
+    type Error = EvmAddressError;
+
+    fn try_from(value: &Binary) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockApi;
+    use hex_literal::hex;
+
+    #[test]
+    fn from_hex_works() {
+        // Test vectors from EIP-55
+        for checksummed in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = EvmAddress::from_hex(checksummed).unwrap();
+            assert_eq!(address.to_string(), checksummed);
+            assert_eq!(
+                EvmAddress::from_hex(&checksummed.to_lowercase()).unwrap(),
+                address
+            );
+            assert_eq!(
+                EvmAddress::from_hex(&format!("0x{}", checksummed[2..].to_uppercase())).unwrap(),
+                address
+            );
+        }
+        assert_eq!(
+            EvmAddress::from_hex("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+                .unwrap()
+                .as_bytes(),
+            &hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+        );
+    }
+
+    #[test]
+    fn from_hex_fails_for_invalid_input() {
+        assert_eq!(
+            EvmAddress::from_hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(EvmAddressError::InvalidChecksum)
+        );
+        assert_eq!(
+            EvmAddress::from_hex("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            Err(EvmAddressError::InvalidHex)
+        );
+        assert_eq!(
+            EvmAddress::from_hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"),
+            Err(EvmAddressError::InvalidHex)
+        );
+        assert_eq!(
+            EvmAddress::from_hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAgg"),
+            Err(EvmAddressError::InvalidHex)
+        );
+    }
+
+    #[test]
+    fn from_pubkey_works() {
+        // The public key of private key 1, i.e. the generator point
+        let pubkey = hex!("0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8");
+        assert_eq!(
+            EvmAddress::from_pubkey(&pubkey).unwrap().to_string(),
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+        );
+
+        assert_eq!(
+            EvmAddress::from_pubkey(&pubkey[1..]),
+            Err(EvmAddressError::InvalidPubkey)
+        );
+        let mut compressed = [0u8; 33];
+        compressed[0] = 0x02;
+        assert_eq!(
+            EvmAddress::from_pubkey(&compressed),
+            Err(EvmAddressError::InvalidPubkey)
+        );
+    }
+
+    #[test]
+    fn evm_personal_message_hash_works() {
+        assert_eq!(
+            evm_personal_message_hash(b"Hello from Cosmos"),
+            hex!("5274844189406bf9787950e9535015adc59b04c4c51981329aa1ecbd865bec72")
+        );
+    }
+
+    #[test]
+    fn recover_works() {
+        // Signed by the private key 0x4c0883a6... from the web3.js documentation
+        let api = MockApi::default();
+        let hash = evm_personal_message_hash(b"Hello from Cosmos");
+        let mut signature = hex!("6991109cb59d65ef4b68b9534fadf67c424ffcde1b8e602b8e08053187d16848063d65ff5e619830c67e081a7c798f06d65c42e9073fd4e24e9abd1133c530ca1b");
+        let expected = EvmAddress::from_hex("0x2c7536E3605D9C16a7a3D7b1898e529396a65c23").unwrap();
+        assert_eq!(
+            EvmAddress::recover(&api, &hash, &signature).unwrap(),
+            expected
+        );
+
+        // v without the offset of 27
+        signature[64] = 0;
+        assert_eq!(
+            EvmAddress::recover(&api, &hash, &signature).unwrap(),
+            expected
+        );
+
+        // wrong v recovers another key
+        signature[64] = 1;
+        assert_ne!(
+            EvmAddress::recover(&api, &hash, &signature).unwrap(),
+            expected
+        );
+
+        signature[64] = 29;
+        assert_eq!(
+            EvmAddress::recover(&api, &hash, &signature),
+            Err(EvmAddressError::InvalidSignature)
+        );
+        assert_eq!(
+            EvmAddress::recover(&api, &hash, &signature[..64]),
+            Err(EvmAddressError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn binary_conversions_work() {
+        let address = EvmAddress::new([0xab; 20]);
+        let binary = Binary::from(address);
+        assert_eq!(binary.as_slice(), &[0xab; 20]);
+        assert_eq!(EvmAddress::try_from(&binary).unwrap(), address);
+        assert_eq!(EvmAddress::from([0xab; 20]), address);
+
+        assert_eq!(
+            EvmAddress::try_from(&[0xab; 32][..]),
+            Err(EvmAddressError::InvalidLength { length: 32 })
+        );
+    }
+}
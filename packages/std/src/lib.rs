@@ -2,37 +2,69 @@
 
 // Exposed on all platforms
 
+mod addr_set;
 mod addresses;
+mod adr36;
 mod assertions;
 mod binary;
+mod caching_querier;
 mod coins;
+mod contract_version;
 mod conversion;
 mod deps;
+mod encoding;
 mod errors;
+mod evm;
 mod ibc;
 mod import_helpers;
 #[cfg(feature = "iterator")]
 mod iterator;
 mod math;
 mod panic;
+mod permit;
+mod policy;
+mod prefixed_storage;
 mod query;
+mod rand;
 mod results;
 mod sections;
 mod serde;
+mod snapshot;
+mod solana;
+mod sorted_kv;
+mod split_deps;
 mod storage;
+mod strings;
+#[cfg(feature = "iterator")]
+mod time_queue;
 mod timestamp;
 mod traits;
+mod twap;
 mod types;
+mod uri;
+mod value;
+mod vesting;
 
+pub use crate::addr_set::{AddrSet, ADDR_SET_EVENT};
 pub use crate::addresses::{Addr, CanonicalAddr};
+pub use crate::adr36::{adr36_sign_doc, secp256k1_pubkey_to_address, verify_adr36};
 pub use crate::binary::Binary;
-pub use crate::coins::{coin, coins, has_coins, Coin};
+pub use crate::caching_querier::CachingQuerier;
+pub use crate::coins::{coin, coins, has_coins, split_funds, Coin, SplitRemainder};
+pub use crate::contract_version::{
+    get_contract_version, set_contract_version, ContractVersion, CONTRACT_VERSION_KEY,
+};
 pub use crate::deps::{Deps, DepsMut, OwnedDeps};
+pub use crate::encoding::{
+    base58_decode, base58_encode, base58check_decode, base58check_encode, bech32_decode,
+    bech32_encode, decode_segwit_address, encode_segwit_address, Bech32Variant,
+};
 pub use crate::errors::{
-    CheckedFromRatioError, CheckedMultiplyRatioError, ConversionOverflowError, DivideByZeroError,
-    OverflowError, OverflowOperation, RecoverPubkeyError, StdError, StdResult, SystemError,
-    VerificationError,
+    Base58Error, Bech32Error, CheckedFromRatioError, CheckedMultiplyRatioError,
+    ConversionOverflowError, DivideByZeroError, OverflowError, OverflowOperation,
+    RecoverPubkeyError, ResponseLimitError, StdError, StdResult, SystemError, VerificationError,
 };
+pub use crate::evm::{evm_personal_message_hash, EvmAddress, EvmAddressError};
 #[cfg(feature = "stargate")]
 pub use crate::ibc::{
     Ibc3ChannelOpenResponse, IbcAcknowledgement, IbcBasicResponse, IbcChannel, IbcChannelCloseMsg,
@@ -43,8 +75,19 @@ pub use crate::ibc::{
 #[cfg(feature = "iterator")]
 pub use crate::iterator::{Order, Record};
 pub use crate::math::{
-    Decimal, Decimal256, Decimal256RangeExceeded, DecimalRangeExceeded, Fraction, Isqrt, Uint128,
-    Uint256, Uint512, Uint64,
+    apr_to_apy, apy_to_apr, assert_belief_price, assert_min_receive, collateral_value, compound,
+    constant_product_input, constant_product_output, debt_value, expected_return,
+    exponential_decay_price, health_factor, is_liquidatable, linear_decay_price, loan_to_value,
+    min_receive, stable_swap_d, stable_swap_output, stable_swap_y, total_debt_value,
+    weighted_collateral_value, Bps, CollateralAsset, CollateralError, DebtAsset, Decimal,
+    Decimal256, Decimal256RangeExceeded, DecimalRangeExceeded, Fee, FeeError, Fraction, Isqrt,
+    SlippageError, SwapMathError, Uint128, Uint256, Uint512, Uint64, STABLE_SWAP_MAX_AMP,
+    STABLE_SWAP_MAX_COINS,
+};
+pub use crate::permit::{Permit, PermitError, PermitParams, PermitSignature};
+pub use crate::policy::{Policy, PolicyError};
+pub use crate::prefixed_storage::{
+    assert_unique_namespaces, Namespace, PrefixedStorage, ReadonlyPrefixedStorage,
 };
 #[cfg(feature = "cosmwasm_1_1")]
 pub use crate::query::SupplyResponse;
@@ -59,22 +102,38 @@ pub use crate::query::{
 };
 #[cfg(feature = "stargate")]
 pub use crate::query::{ChannelResponse, IbcQuery, ListChannelsResponse, PortIdResponse};
+#[cfg(feature = "cosmwasm_1_2")]
+pub use crate::query::{HostInfoResponse, StorageUsageResponse};
+pub use crate::rand::{select_weighted, Xoshiro256PlusPlus};
 #[allow(deprecated)]
 pub use crate::results::SubMsgExecutionResponse;
 pub use crate::results::{
     attr, wasm_execute, wasm_instantiate, Attribute, BankMsg, ContractResult, CosmosMsg, CustomMsg,
-    Empty, Event, QueryResponse, Reply, ReplyOn, Response, SubMsg, SubMsgResponse, SubMsgResult,
-    SystemResult, WasmMsg,
+    Empty, Event, QueryResponse, Reply, ReplyId, ReplyOn, Response, ResponseLimits, SubMsg,
+    SubMsgResponse, SubMsgResult, SystemResult, WasmMsg,
 };
+#[cfg(feature = "cosmwasm_1_2")]
+pub use crate::results::{multi_send_total, MultiSendOutput};
 #[cfg(feature = "staking")]
 pub use crate::results::{DistributionMsg, StakingMsg};
 #[cfg(feature = "stargate")]
 pub use crate::results::{GovMsg, VoteOption};
 pub use crate::serde::{from_binary, from_slice, to_binary, to_vec};
+pub use crate::snapshot::Snapshot;
+pub use crate::solana::{solana_offchain_message, SolanaError, SolanaPubkey};
+pub use crate::sorted_kv::{SortKey, SortedKV};
+pub use crate::split_deps::{ReadonlyStorageNamespace, SplitDeps, StorageNamespace};
 pub use crate::storage::MemoryStorage;
+pub use crate::strings::{truncate_str, truncate_string, validate_byte_length, StringLengthError};
+#[cfg(feature = "iterator")]
+pub use crate::time_queue::{TimeQueue, TimeQueueId};
 pub use crate::timestamp::Timestamp;
 pub use crate::traits::{Api, Querier, QuerierResult, QuerierWrapper, Storage};
+pub use crate::twap::{TwapAccumulator, TwapError, TwapObservation};
 pub use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
+pub use crate::uri::{validate_uri, UriError};
+pub use crate::value::{from_value, to_value, Number, Value};
+pub use crate::vesting::{VestingError, VestingPoint, VestingSchedule};
 
 // Exposed in wasm build only
 
@@ -102,13 +161,18 @@ pub use crate::imports::{ExternalApi, ExternalQuerier, ExternalStorage};
 mod mock;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod testing {
+    pub use cosmwasm_derive::cw_test_harness;
+    // Used by the code generated by `#[cw_test_harness]`
+    #[doc(hidden)]
+    pub use serde::de::DeserializeOwned;
+
     #[cfg(feature = "staking")]
     pub use crate::mock::StakingQuerier;
     pub use crate::mock::{
         digit_sum, mock_dependencies, mock_dependencies_with_balance,
         mock_dependencies_with_balances, mock_env, mock_info, mock_wasmd_attr, riffle_shuffle,
         BankQuerier, MockApi, MockQuerier, MockQuerierCustomHandlerResult, MockStorage,
-        MOCK_CONTRACT_ADDR,
+        RecordingStorage, StorageMutation, MOCK_CONTRACT_ADDR,
     };
     #[cfg(feature = "stargate")]
     pub use crate::mock::{
@@ -121,4 +185,4 @@ pub mod testing {
 
 // Re-exports
 
-pub use cosmwasm_derive::entry_point;
+pub use cosmwasm_derive::{entry_point, ReplyId};
@@ -0,0 +1,345 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::errors::{OverflowError, OverflowOperation};
+use crate::{Decimal256, Uint256, Uint512};
+
+use super::div_ceil;
+
+/// `10^18`, the denominator of [`Decimal256`]
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+
+/// An amount of a collateral asset in a lending position.
+///
+/// Values are expressed in the smallest unit of a common quote asset, prices are the quote
+/// amount per smallest unit of the asset.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CollateralAsset {
+    pub amount: Uint256,
+    pub price: Decimal256,
+    /// The share of the value that can be borrowed against before the position can be
+    /// liquidated. Must not be greater than 1.
+    pub liquidation_threshold: Decimal256,
+}
+
+/// An amount of a borrowed asset in a lending position
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DebtAsset {
+    pub amount: Uint256,
+    pub price: Decimal256,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CollateralError {
+    #[error("Liquidation threshold {threshold} must not be greater than 1")]
+    InvalidLiquidationThreshold { threshold: Decimal256 },
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+}
+
+/// Returns the value of `amount` at `price`, rounded down
+pub fn collateral_value(amount: Uint256, price: Decimal256) -> Result<Uint256, OverflowError> {
+    let value = amount.full_mul(price.atomics()) / Uint512::from(DECIMAL_FRACTIONAL);
+    to_uint256(value, amount, price)
+}
+
+/// Returns the value of `amount` at `price`, rounded up
+pub fn debt_value(amount: Uint256, price: Decimal256) -> Result<Uint256, OverflowError> {
+    let value = div_ceil(
+        amount.full_mul(price.atomics()),
+        Uint512::from(DECIMAL_FRACTIONAL),
+    );
+    to_uint256(value, amount, price)
+}
+
+impl CollateralAsset {
+    /// The value of the collateral, rounded down
+    pub fn value(&self) -> Result<Uint256, OverflowError> {
+        collateral_value(self.amount, self.price)
+    }
+
+    /// The value that can be borrowed against before the position can be liquidated,
+    /// rounded down
+    pub fn weighted_value(&self) -> Result<Uint256, CollateralError> {
+        if self.liquidation_threshold > Decimal256::one() {
+            return Err(CollateralError::InvalidLiquidationThreshold {
+                threshold: self.liquidation_threshold,
+            });
+        }
+        // Multiply first to round only once. If the product overflows, the result is at
+        // least 2^512 / 10^36 and would not fit into a Uint256 anyway.
+        let value = self
+            .amount
+            .full_mul(self.price.atomics())
+            .checked_mul(Uint512::from(self.liquidation_threshold.atomics()))
+            .map_err(|_| OverflowError::new(OverflowOperation::Mul, self.amount, self.price))?
+            / Uint512::from(DECIMAL_FRACTIONAL)
+            / Uint512::from(DECIMAL_FRACTIONAL);
+        Ok(to_uint256(value, self.amount, self.price)?)
+    }
+}
+
+impl DebtAsset {
+    /// The value of the debt, rounded up
+    pub fn value(&self) -> Result<Uint256, OverflowError> {
+        debt_value(self.amount, self.price)
+    }
+}
+
+/// Returns the sum of the weighted values of the collaterals, rounded down
+pub fn weighted_collateral_value(
+    collaterals: &[CollateralAsset],
+) -> Result<Uint256, CollateralError> {
+    collaterals.iter().try_fold(Uint256::zero(), |sum, asset| {
+        Ok(sum.checked_add(asset.weighted_value()?)?)
+    })
+}
+
+/// Returns the sum of the values of the debts, rounded up
+pub fn total_debt_value(debts: &[DebtAsset]) -> Result<Uint256, OverflowError> {
+    debts.iter().try_fold(Uint256::zero(), |sum, asset| {
+        sum.checked_add(asset.value()?)
+    })
+}
+
+/// Returns the health factor `weighted_collateral / debt`, rounded down. A position with
+/// a health factor below 1 can be liquidated.
+///
+/// Returns `None` if there is no debt. Health factors larger than [`Decimal256::MAX`]
+/// are capped.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{health_factor, Decimal256, Uint256};
+///
+/// let health = health_factor(Uint256::from(800u32), Uint256::from(600u32));
+/// assert_eq!(health.unwrap().to_string(), "1.333333333333333333");
+/// assert_eq!(health_factor(Uint256::from(800u32), Uint256::zero()), None);
+/// ```
+pub fn health_factor(weighted_collateral: Uint256, debt: Uint256) -> Option<Decimal256> {
+    if debt.is_zero() {
+        return None;
+    }
+    let ratio =
+        weighted_collateral.full_mul(Uint256::from(DECIMAL_FRACTIONAL)) / Uint512::from(debt);
+    Some(
+        Uint256::try_from(ratio)
+            .map(Decimal256::new)
+            .unwrap_or(Decimal256::MAX),
+    )
+}
+
+/// Returns the loan-to-value ratio `debt / collateral`, rounded up.
+///
+/// Returns `None` if there is no collateral. Ratios larger than [`Decimal256::MAX`]
+/// are capped.
+pub fn loan_to_value(debt: Uint256, collateral: Uint256) -> Option<Decimal256> {
+    if collateral.is_zero() {
+        return None;
+    }
+    let ratio = div_ceil(
+        debt.full_mul(Uint256::from(DECIMAL_FRACTIONAL)),
+        Uint512::from(collateral),
+    );
+    Some(
+        Uint256::try_from(ratio)
+            .map(Decimal256::new)
+            .unwrap_or(Decimal256::MAX),
+    )
+}
+
+/// Returns true if a position with this weighted collateral and debt value can be
+/// liquidated, i.e. if its health factor is below 1
+pub fn is_liquidatable(weighted_collateral: Uint256, debt: Uint256) -> bool {
+    // Compare directly to avoid rounding the health factor
+    weighted_collateral < debt
+}
+
+fn to_uint256(
+    value: Uint512,
+    amount: Uint256,
+    price: Decimal256,
+) -> Result<Uint256, OverflowError> {
+    Uint256::try_from(value).map_err(|_| OverflowError::new(OverflowOperation::Mul, amount, price))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collateral(amount: u128, price: Decimal256, threshold: u64) -> CollateralAsset {
+        CollateralAsset {
+            amount: Uint256::from(amount),
+            price,
+            liquidation_threshold: Decimal256::percent(threshold),
+        }
+    }
+
+    fn debt(amount: u128, price: Decimal256) -> DebtAsset {
+        DebtAsset {
+            amount: Uint256::from(amount),
+            price,
+        }
+    }
+
+    #[test]
+    fn values_round_in_favor_of_the_protocol() {
+        let price = Decimal256::from_ratio(1u8, 3u8);
+        assert_eq!(
+            collateral_value(Uint256::from(10u8), price),
+            Ok(Uint256::from(3u8))
+        );
+        assert_eq!(
+            debt_value(Uint256::from(10u8), price),
+            Ok(Uint256::from(4u8))
+        );
+
+        // exact values are not rounded
+        let price = Decimal256::percent(150);
+        assert_eq!(
+            collateral_value(Uint256::from(10u8), price),
+            Ok(Uint256::from(15u8))
+        );
+        assert_eq!(
+            debt_value(Uint256::from(10u8), price),
+            Ok(Uint256::from(15u8))
+        );
+
+        // dust
+        let price = Decimal256::raw(1);
+        assert_eq!(collateral_value(Uint256::one(), price), Ok(Uint256::zero()));
+        assert_eq!(debt_value(Uint256::one(), price), Ok(Uint256::one()));
+        assert_eq!(debt_value(Uint256::zero(), price), Ok(Uint256::zero()));
+    }
+
+    #[test]
+    fn values_fail_on_overflow() {
+        let price = Decimal256::percent(200);
+        assert!(collateral_value(Uint256::MAX, Decimal256::one()).is_ok());
+        assert_eq!(
+            collateral_value(Uint256::MAX, price),
+            Err(OverflowError::new(
+                OverflowOperation::Mul,
+                Uint256::MAX,
+                price
+            ))
+        );
+        assert!(debt_value(Uint256::MAX, price).is_err());
+    }
+
+    #[test]
+    fn weighted_value_works() {
+        // 1000 * 1.5 * 0.8
+        let asset = collateral(1000, Decimal256::percent(150), 80);
+        assert_eq!(asset.value(), Ok(Uint256::from(1500u32)));
+        assert_eq!(asset.weighted_value(), Ok(Uint256::from(1200u32)));
+
+        // rounds once: 7 * 0.5 * 0.5 = 1.75
+        let asset = collateral(7, Decimal256::percent(50), 50);
+        assert_eq!(asset.weighted_value(), Ok(Uint256::one()));
+
+        let asset = collateral(u128::MAX, Decimal256::MAX, 100);
+        assert!(asset.weighted_value().is_err());
+
+        let asset = collateral(1000, Decimal256::one(), 101);
+        assert_eq!(
+            asset.weighted_value(),
+            Err(CollateralError::InvalidLiquidationThreshold {
+                threshold: Decimal256::percent(101)
+            })
+        );
+    }
+
+    #[test]
+    fn totals_work() {
+        let collaterals = [
+            collateral(1000, Decimal256::percent(150), 80),
+            collateral(10, Decimal256::from_ratio(1u8, 3u8), 90),
+        ];
+        // 1200 + floor(3.0)
+        assert_eq!(
+            weighted_collateral_value(&collaterals),
+            Ok(Uint256::from(1202u32))
+        );
+        assert_eq!(weighted_collateral_value(&[]), Ok(Uint256::zero()));
+
+        let debts = [
+            debt(100, Decimal256::percent(200)),
+            debt(10, Decimal256::from_ratio(1u8, 3u8)),
+        ];
+        assert_eq!(total_debt_value(&debts), Ok(Uint256::from(204u32)));
+        assert_eq!(total_debt_value(&[]), Ok(Uint256::zero()));
+
+        let debts = [
+            debt(u128::MAX, Decimal256::MAX),
+            debt(u128::MAX, Decimal256::MAX),
+        ];
+        assert!(total_debt_value(&debts).is_err());
+    }
+
+    #[test]
+    fn health_factor_works() {
+        assert_eq!(
+            health_factor(Uint256::from(1200u32), Uint256::from(600u32)),
+            Some(Decimal256::percent(200))
+        );
+        assert_eq!(
+            health_factor(Uint256::from(2u8), Uint256::from(3u8)),
+            Some(Decimal256::from_ratio(2u8, 3u8))
+        );
+        assert_eq!(
+            health_factor(Uint256::zero(), Uint256::from(3u8)),
+            Some(Decimal256::zero())
+        );
+        assert_eq!(health_factor(Uint256::from(3u8), Uint256::zero()), None);
+        assert_eq!(
+            health_factor(Uint256::MAX, Uint256::one()),
+            Some(Decimal256::MAX)
+        );
+
+        // just below 1 is not rounded up to 1
+        let health = health_factor(Uint256::MAX - Uint256::one(), Uint256::MAX).unwrap();
+        assert!(health < Decimal256::one());
+    }
+
+    #[test]
+    fn loan_to_value_works() {
+        assert_eq!(
+            loan_to_value(Uint256::from(600u32), Uint256::from(1200u32)),
+            Some(Decimal256::percent(50))
+        );
+        assert_eq!(
+            loan_to_value(Uint256::from(1u8), Uint256::from(3u8)),
+            Some(Decimal256::from_ratio(1u8, 3u8) + Decimal256::raw(1))
+        );
+        assert_eq!(loan_to_value(Uint256::from(1u8), Uint256::zero()), None);
+        assert_eq!(
+            loan_to_value(Uint256::zero(), Uint256::from(1u8)),
+            Some(Decimal256::zero())
+        );
+        assert_eq!(
+            loan_to_value(Uint256::MAX, Uint256::one()),
+            Some(Decimal256::MAX)
+        );
+    }
+
+    #[test]
+    fn is_liquidatable_works() {
+        assert!(!is_liquidatable(
+            Uint256::from(600u32),
+            Uint256::from(600u32)
+        ));
+        assert!(!is_liquidatable(
+            Uint256::from(601u32),
+            Uint256::from(600u32)
+        ));
+        assert!(is_liquidatable(
+            Uint256::from(599u32),
+            Uint256::from(600u32)
+        ));
+        assert!(!is_liquidatable(Uint256::zero(), Uint256::zero()));
+        assert!(is_liquidatable(Uint256::MAX - Uint256::one(), Uint256::MAX));
+    }
+}
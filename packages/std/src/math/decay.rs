@@ -0,0 +1,341 @@
+use crate::{Decimal, Timestamp, Uint128, Uint256};
+
+/// `10^36`, the scale of [`HALVING_ROOTS`]
+const ROOT_SCALE: u128 = 1_000_000_000_000_000_000_000_000_000_000_000_000;
+
+/// `2^(-1/2^i)` for i = 1..=64, scaled by `10^36` and rounded down
+const HALVING_ROOTS: [u128; 64] = [
+    707106781186547524400844362104849039,
+    840896415253714543031125476233214895,
+    917004043204671231743541594794144428,
+    957603280698573646936305635147915443,
+    978572062087700134509161125813435745,
+    989228013193975484129124959065583667,
+    994599423483633175652477686222166314,
+    997296056085470126257659913847922601,
+    998647112890970173588121318085920408,
+    999323327502650752360283659843738041,
+    999661606496243683942196868762815655,
+    999830788931929063117480780197673898,
+    999915390886613497533724971564188727,
+    999957694548431132543967537300997975,
+    999978847050491929826500671130393274,
+    999989423469314464242210592253154316,
+    999994711720674283007702412770305325,
+    999997355856841394982252346365042709,
+    999998677927546759705317767598010636,
+    999999338963554895261780529006245097,
+    999999669481722826465117383688205751,
+    999999834740847757938858809473148280,
+    999999917370420465145722351332142646,
+    999999958685209379116899151960952490,
+    999999979342604476194454662509785831,
+    999999989671302184756228051944159016,
+    999999994835651079042864137276512748,
+    999999997417825536187619587855879235,
+    999999998708912767260356672656284649,
+    999999999354456383421815055875720996,
+    999999999677228191658816707807941718,
+    999999999838614095816385648869389483,
+    999999999919307047904937148175786687,
+    999999999959653523951654655023133491,
+    999999999979826761975623847745372677,
+    999999999989913380987761053931137308,
+    999999999994956690493867809480181332,
+    999999999997478345246930725368743827,
+    999999999998739172623464567841535203,
+    999999999999369586311732085210058423,
+    999999999999684793155865992927351917,
+    999999999999842396577932984044256635,
+    999999999999921198288966488917273486,
+    999999999999960599144483243682423035,
+    999999999999980299572241621647158090,
+    999999999999990149786120810775065688,
+    999999999999995074893060405375404505,
+    999999999999997537446530202684670167,
+    999999999999998768723265101341577062,
+    999999999999999384361632550670599026,
+    999999999999999692180816275335252136,
+    999999999999999846090408137667614224,
+    999999999999999923045204068833804151,
+    999999999999999961522602034416901335,
+    999999999999999980761301017208450482,
+    999999999999999990380650508604225195,
+    999999999999999995190325254302112585,
+    999999999999999997595162627151056290,
+    999999999999999998797581313575528144,
+    999999999999999999398790656787764071,
+    999999999999999999699395328393882035,
+    999999999999999999849697664196941017,
+    999999999999999999924848832098470508,
+    999999999999999999962424416049235254,
+];
+
+/// Returns the price at `now` when moving linearly from `start_price` at `start`
+/// to `end_price` at `end`.
+///
+/// Before `start` this is `start_price`, from `end` on it is `end_price`. Prices between are
+/// rounded towards `start_price`, such that a decaying auction price is never too low.
+///
+/// Panics if `end` is not after `start`.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{linear_decay_price, Decimal, Timestamp};
+///
+/// let start = Timestamp::from_seconds(1_000);
+/// let end = start.plus_seconds(300);
+/// let price = linear_decay_price(Decimal::percent(200), Decimal::percent(50), start, end, start.plus_seconds(100));
+/// assert_eq!(price, Decimal::percent(150));
+/// ```
+pub fn linear_decay_price(
+    start_price: Decimal,
+    end_price: Decimal,
+    start: Timestamp,
+    end: Timestamp,
+    now: Timestamp,
+) -> Decimal {
+    assert!(end > start, "Price decay end must be after the start");
+    if now <= start {
+        return start_price;
+    }
+    if now >= end {
+        return end_price;
+    }
+    let elapsed = now.nanos() - start.nanos();
+    let duration = end.nanos() - start.nanos();
+    let change = |diff: Decimal| {
+        let change = diff.atomics().full_mul(elapsed) / Uint256::from(duration);
+        // change < diff since elapsed < duration
+        Uint128::try_from(change).unwrap()
+    };
+    if end_price < start_price {
+        Decimal::new(start_price.atomics() - change(start_price - end_price))
+    } else {
+        Decimal::new(start_price.atomics() + change(end_price - start_price))
+    }
+}
+
+/// Returns the price at `now` when moving exponentially from `start_price` at `start`
+/// towards `end_price`, such that the distance to `end_price` halves every `half_life_seconds`.
+///
+/// Before `start` this is `start_price`. The price approaches `end_price` but only reaches it
+/// once the distance is rounded down to zero. Prices are rounded towards `end_price`.
+/// The exponential is computed with integer math only and has a relative error
+/// below `10^-30` before rounding.
+///
+/// Panics if `half_life_seconds` is 0.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{exponential_decay_price, Decimal, Timestamp};
+///
+/// let start = Timestamp::from_seconds(1_000);
+/// let price = exponential_decay_price(Decimal::percent(500), Decimal::percent(100), 60, start, start.plus_seconds(120));
+/// assert_eq!(price, Decimal::percent(200));
+/// ```
+pub fn exponential_decay_price(
+    start_price: Decimal,
+    end_price: Decimal,
+    half_life_seconds: u64,
+    start: Timestamp,
+    now: Timestamp,
+) -> Decimal {
+    assert!(half_life_seconds > 0, "Price decay half-life must not be 0");
+    if now <= start {
+        return start_price;
+    }
+    let elapsed = (now.nanos() - start.nanos()) as u128;
+    let half_life = half_life_seconds as u128 * 1_000_000_000;
+    let halvings = elapsed / half_life;
+    if halvings >= 128 {
+        return end_price;
+    }
+
+    // 2^(-fraction) for the remaining fraction of a half-life, one binary digit at a time
+    let mut remainder = elapsed % half_life;
+    let mut factor = Uint256::from(ROOT_SCALE);
+    for root in HALVING_ROOTS {
+        if remainder == 0 {
+            break;
+        }
+        remainder *= 2;
+        if remainder >= half_life {
+            remainder -= half_life;
+            factor = factor * Uint256::from(root) / Uint256::from(ROOT_SCALE);
+        }
+    }
+
+    let distance = start_price.abs_diff(end_price).atomics();
+    let remaining =
+        (Uint256::from(distance) * factor / Uint256::from(ROOT_SCALE)) >> halvings as u32;
+    // remaining <= distance since factor <= ROOT_SCALE
+    let remaining = Uint128::try_from(remaining).unwrap();
+    if end_price < start_price {
+        Decimal::new(end_price.atomics() + remaining)
+    } else {
+        Decimal::new(end_price.atomics() - remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(seconds: u64) -> Timestamp {
+        Timestamp::from_seconds(1_600_000_000).plus_seconds(seconds)
+    }
+
+    #[test]
+    fn halving_roots_are_correct() {
+        // squaring each root gives the previous one
+        let mut previous = Uint256::from(ROOT_SCALE) / Uint256::from(2u8);
+        for root in HALVING_ROOTS {
+            let squared = Uint256::from(root) * Uint256::from(root) / Uint256::from(ROOT_SCALE);
+            assert!(previous.abs_diff(squared) <= Uint256::from(2u8));
+            previous = Uint256::from(root);
+        }
+    }
+
+    #[test]
+    fn linear_decay_price_works() {
+        let high = Decimal::percent(200);
+        let low = Decimal::percent(50);
+        assert_eq!(linear_decay_price(high, low, t(100), t(400), t(0)), high);
+        assert_eq!(linear_decay_price(high, low, t(100), t(400), t(100)), high);
+        assert_eq!(
+            linear_decay_price(high, low, t(100), t(400), t(200)),
+            Decimal::percent(150)
+        );
+        assert_eq!(
+            linear_decay_price(high, low, t(100), t(400), t(399)),
+            Decimal::permille(505)
+        );
+        assert_eq!(linear_decay_price(high, low, t(100), t(400), t(400)), low);
+        assert_eq!(linear_decay_price(high, low, t(100), t(400), t(500)), low);
+
+        // increasing prices
+        assert_eq!(
+            linear_decay_price(low, high, t(100), t(400), t(200)),
+            Decimal::percent(100)
+        );
+        assert_eq!(linear_decay_price(low, high, t(100), t(400), t(400)), high);
+
+        // constant price
+        assert_eq!(linear_decay_price(low, low, t(100), t(400), t(200)), low);
+    }
+
+    #[test]
+    fn linear_decay_price_rounds_towards_start_price() {
+        let one_third_elapsed = t(100).plus_nanos(100);
+        let price = linear_decay_price(
+            Decimal::raw(10),
+            Decimal::raw(0),
+            t(100),
+            t(100).plus_nanos(300),
+            one_third_elapsed,
+        );
+        assert_eq!(price, Decimal::raw(7));
+        let price = linear_decay_price(
+            Decimal::raw(0),
+            Decimal::raw(10),
+            t(100),
+            t(100).plus_nanos(300),
+            one_third_elapsed,
+        );
+        assert_eq!(price, Decimal::raw(3));
+
+        // one atomic unit before the end the price is not yet at the end price
+        let price = linear_decay_price(
+            Decimal::raw(1),
+            Decimal::raw(0),
+            t(100),
+            t(200),
+            t(200).minus_nanos(1),
+        );
+        assert_eq!(price, Decimal::raw(1));
+    }
+
+    #[test]
+    fn linear_decay_price_handles_extreme_values() {
+        let price = linear_decay_price(
+            Decimal::MAX,
+            Decimal::zero(),
+            Timestamp::from_nanos(0),
+            Timestamp::from_nanos(u64::MAX),
+            Timestamp::from_nanos(u64::MAX - 1),
+        );
+        // u128::MAX = (2^64 - 1) * (2^64 + 1), so one step is exactly 2^64 + 1
+        assert_eq!(price, Decimal::raw((1 << 64) + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Price decay end must be after the start")]
+    fn linear_decay_price_panics_for_empty_duration() {
+        linear_decay_price(Decimal::one(), Decimal::zero(), t(100), t(100), t(100));
+    }
+
+    #[test]
+    fn exponential_decay_price_works() {
+        let high = Decimal::percent(500);
+        let low = Decimal::percent(100);
+        assert_eq!(exponential_decay_price(high, low, 60, t(100), t(0)), high);
+        assert_eq!(exponential_decay_price(high, low, 60, t(100), t(100)), high);
+        assert_eq!(
+            exponential_decay_price(high, low, 60, t(100), t(160)),
+            Decimal::percent(300)
+        );
+        assert_eq!(
+            exponential_decay_price(high, low, 60, t(100), t(220)),
+            Decimal::percent(200)
+        );
+        // half a half-life: 1 + 4 / sqrt(2)
+        assert_eq!(
+            exponential_decay_price(high, low, 60, t(100), t(130)),
+            Decimal::raw(3_828_427_124_746_190_097)
+        );
+        // a quarter half-life: 1 + 4 * 2^(-1/4)
+        assert_eq!(
+            exponential_decay_price(high, low, 60, t(100), t(115)),
+            Decimal::raw(4_363_585_661_014_858_172)
+        );
+        // after 127 half-lives the distance is rounded to 0
+        assert_eq!(
+            exponential_decay_price(high, low, 60, t(100), t(100 + 70 * 60)),
+            low
+        );
+        assert_eq!(
+            exponential_decay_price(high, low, 60, t(100), t(100 + 200 * 60)),
+            low
+        );
+
+        // increasing prices
+        assert_eq!(
+            exponential_decay_price(low, high, 60, t(100), t(160)),
+            Decimal::percent(300)
+        );
+    }
+
+    #[test]
+    fn exponential_decay_price_is_monotonic() {
+        let high = Decimal::MAX;
+        let low = Decimal::zero();
+        let mut previous = high;
+        let mut now = t(0);
+        while now < t(60 * 130) {
+            let price = exponential_decay_price(high, low, 60, t(0), now);
+            assert!(price <= previous);
+            previous = price;
+            now = now.plus_nanos(7_777_777_777);
+        }
+        assert_eq!(previous, low);
+    }
+
+    #[test]
+    #[should_panic(expected = "Price decay half-life must not be 0")]
+    fn exponential_decay_price_panics_for_zero_half_life() {
+        exponential_decay_price(Decimal::one(), Decimal::zero(), 0, t(100), t(100));
+    }
+}
@@ -182,6 +182,17 @@ impl Decimal {
         self.0
     }
 
+    /// Returns a big endian encoding of the atomics whose lexicographical byte order equals the
+    /// numeric order. Use this for storage keys that should be iterated in numeric order.
+    pub const fn to_be_key(self) -> [u8; 16] {
+        self.0.to_be_key()
+    }
+
+    /// Creates a decimal from an encoding created by [`Decimal::to_be_key`].
+    pub const fn from_be_key(key: [u8; 16]) -> Self {
+        Self(Uint128::from_be_key(key))
+    }
+
     /// The number of decimal places. This is a constant value for now
     /// but this could potentially change as the type evolves.
     ///
@@ -275,7 +286,7 @@ impl Decimal {
                 }
             }
 
-            Ok(x * y)
+            x.checked_mul(y)
         }
 
         inner(self, exp).map_err(|_| OverflowError {
@@ -1663,6 +1674,16 @@ mod tests {
                 operand2: "2".to_string(),
             })
         );
+
+        // overflow in the last multiplication
+        assert_eq!(
+            Decimal::percent(200).checked_pow(69),
+            Err(OverflowError {
+                operation: crate::OverflowOperation::Pow,
+                operand1: "2".to_string(),
+                operand2: "69".to_string(),
+            })
+        );
     }
 
     #[test]
@@ -2015,4 +2036,22 @@ mod tests {
             assert_eq!(&lhs == &rhs, expected);
         }
     }
+
+    #[test]
+    fn decimal_be_key_works() {
+        let values = [
+            Decimal::zero(),
+            Decimal::raw(1),
+            Decimal::percent(99),
+            Decimal::one(),
+            Decimal::percent(1001),
+            Decimal::MAX,
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Decimal::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
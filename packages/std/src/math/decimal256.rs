@@ -195,6 +195,17 @@ impl Decimal256 {
         self.0
     }
 
+    /// Returns a big endian encoding of the atomics whose lexicographical byte order equals the
+    /// numeric order. Use this for storage keys that should be iterated in numeric order.
+    pub const fn to_be_key(self) -> [u8; 32] {
+        self.0.to_be_key()
+    }
+
+    /// Creates a decimal from an encoding created by [`Decimal256::to_be_key`].
+    pub const fn from_be_key(key: [u8; 32]) -> Self {
+        Self(Uint256::from_be_key(key))
+    }
+
     /// The number of decimal places. This is a constant value for now
     /// but this could potentially change as the type evolves.
     ///
@@ -288,7 +299,7 @@ impl Decimal256 {
                 }
             }
 
-            Ok(x * y)
+            x.checked_mul(y)
         }
 
         inner(self, exp).map_err(|_| OverflowError {
@@ -1795,6 +1806,16 @@ mod tests {
                 operand2: "2".to_string(),
             })
         );
+
+        // overflow in the last multiplication
+        assert_eq!(
+            Decimal256::percent(200).checked_pow(197),
+            Err(OverflowError {
+                operation: crate::OverflowOperation::Pow,
+                operand1: "2".to_string(),
+                operand2: "197".to_string(),
+            })
+        );
     }
 
     #[test]
@@ -2162,4 +2183,22 @@ mod tests {
             assert_eq!(&lhs == &rhs, expected);
         }
     }
+
+    #[test]
+    fn decimal256_be_key_works() {
+        let values = [
+            Decimal256::zero(),
+            Decimal256::raw(1),
+            Decimal256::percent(99),
+            Decimal256::one(),
+            Decimal256::percent(1001),
+            Decimal256::MAX,
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Decimal256::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
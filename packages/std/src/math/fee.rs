@@ -0,0 +1,400 @@
+use schemars::schema::{InstanceType, NumberValidation, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Decimal, Uint128, Uint256};
+
+/// `10^18`, the denominator of [`Decimal`]
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FeeError {
+    #[error("Basis points {bps} exceed the maximum of {max}", max = Bps::MAX.bps())]
+    BpsTooLarge { bps: u16 },
+    #[error("Fee rate {rate} must not be greater than 1")]
+    RateTooLarge { rate: Decimal },
+}
+
+/// A share in basis points, i.e. in hundredths of a percent. `Bps` is at most 10000 (100%).
+///
+/// Serializes as a JSON number and fails to deserialize values greater than 10000.
+#[derive(
+    Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "u16", into = "u16")]
+pub struct Bps(u16);
+
+impl Bps {
+    /// 100%
+    pub const MAX: Self = Self(10_000);
+
+    /// Creates a share of `bps` basis points. Returns an error for more than 10000.
+    pub fn new(bps: u16) -> Result<Self, FeeError> {
+        if bps > Self::MAX.0 {
+            return Err(FeeError::BpsTooLarge { bps });
+        }
+        Ok(Self(bps))
+    }
+
+    pub const fn zero() -> Self {
+        Self(0)
+    }
+
+    pub const fn bps(self) -> u16 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn to_decimal(self) -> Decimal {
+        Decimal::from_ratio(self.0, Self::MAX.0)
+    }
+
+    /// Splits `amount` into the share and the remainder, which sum up to `amount`.
+    /// The share is rounded down.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use cosmwasm_std::{Bps, Uint128};
+    ///
+    /// let bps = Bps::new(30).unwrap();
+    /// let (fee, remainder) = bps.apply(Uint128::new(1999));
+    /// assert_eq!(fee, Uint128::new(5));
+    /// assert_eq!(remainder, Uint128::new(1994));
+    /// ```
+    pub fn apply(self, amount: Uint128) -> (Uint128, Uint128) {
+        split(amount, self.0.into(), Self::MAX.0.into(), false)
+    }
+
+    /// Like [`Bps::apply`], but rounds the share up
+    pub fn apply_ceil(self, amount: Uint128) -> (Uint128, Uint128) {
+        split(amount, self.0.into(), Self::MAX.0.into(), true)
+    }
+}
+
+impl TryFrom<u16> for Bps {
+    type Error = FeeError;
+
+    fn try_from(bps: u16) -> Result<Self, Self::Error> {
+        Self::new(bps)
+    }
+}
+
+impl From<Bps> for u16 {
+    fn from(bps: Bps) -> Self {
+        bps.0
+    }
+}
+
+impl JsonSchema for Bps {
+    fn schema_name() -> String {
+        "Bps".to_string()
+    }
+
+    /// An integer from 0 to 10000
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        Schema::Object(SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            format: Some("uint16".to_string()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(0.0),
+                maximum: Some(Self::MAX.0.into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+/// A fee rate between 0 and 1 (100%).
+///
+/// Serializes like a [`Decimal`] and fails to deserialize rates greater than 1.
+#[derive(
+    Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(try_from = "Decimal", into = "Decimal")]
+pub struct Fee(Decimal);
+
+impl Fee {
+    /// A fee of 100%
+    pub const MAX: Self = Self(Decimal::one());
+
+    /// Creates a fee of the given rate. Returns an error for rates greater than 1.
+    pub fn new(rate: Decimal) -> Result<Self, FeeError> {
+        if rate > Decimal::one() {
+            return Err(FeeError::RateTooLarge { rate });
+        }
+        Ok(Self(rate))
+    }
+
+    /// Creates a fee of `x` percent. Returns an error for more than 100.
+    pub fn percent(x: u64) -> Result<Self, FeeError> {
+        if x > 100 {
+            return Err(FeeError::RateTooLarge {
+                rate: Decimal::from_ratio(x, 100u128),
+            });
+        }
+        Ok(Self(Decimal::percent(x)))
+    }
+
+    pub const fn zero() -> Self {
+        Self(Decimal::zero())
+    }
+
+    pub const fn rate(self) -> Decimal {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Splits `amount` into the fee and the remainder, which sum up to `amount`.
+    /// The fee is rounded down.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use cosmwasm_std::{Fee, Uint128};
+    ///
+    /// let fee = Fee::percent(3).unwrap();
+    /// let (fee_amount, remainder) = fee.apply(Uint128::new(99));
+    /// assert_eq!(fee_amount, Uint128::new(2));
+    /// assert_eq!(remainder, Uint128::new(97));
+    /// ```
+    pub fn apply(self, amount: Uint128) -> (Uint128, Uint128) {
+        split(amount, self.0.atomics(), DECIMAL_FRACTIONAL.into(), false)
+    }
+
+    /// Like [`Fee::apply`], but rounds the fee up
+    pub fn apply_ceil(self, amount: Uint128) -> (Uint128, Uint128) {
+        split(amount, self.0.atomics(), DECIMAL_FRACTIONAL.into(), true)
+    }
+}
+
+impl TryFrom<Decimal> for Fee {
+    type Error = FeeError;
+
+    fn try_from(rate: Decimal) -> Result<Self, Self::Error> {
+        Self::new(rate)
+    }
+}
+
+impl From<Fee> for Decimal {
+    fn from(fee: Fee) -> Self {
+        fee.0
+    }
+}
+
+impl From<Bps> for Fee {
+    fn from(bps: Bps) -> Self {
+        Self(bps.to_decimal())
+    }
+}
+
+/// Splits `amount` into `amount * numerator / denominator` and the rest.
+/// Requires `numerator <= denominator`.
+fn split(
+    amount: Uint128,
+    numerator: Uint128,
+    denominator: Uint128,
+    round_up: bool,
+) -> (Uint128, Uint128) {
+    let product = amount.full_mul(numerator);
+    let denominator = Uint256::from(denominator);
+    let mut share = product / denominator;
+    if round_up && !(product % denominator).is_zero() {
+        share += Uint256::one();
+    }
+    // share <= amount since numerator <= denominator
+    let share = Uint128::try_from(share).unwrap();
+    (share, amount - share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_slice, to_vec};
+
+    #[test]
+    fn bps_new_works() {
+        assert_eq!(Bps::new(0).unwrap(), Bps::zero());
+        assert_eq!(Bps::new(30).unwrap().bps(), 30);
+        assert_eq!(Bps::new(10_000).unwrap(), Bps::MAX);
+        assert_eq!(Bps::new(10_001), Err(FeeError::BpsTooLarge { bps: 10_001 }));
+        assert_eq!(
+            Bps::try_from(10_001u16),
+            Err(FeeError::BpsTooLarge { bps: 10_001 })
+        );
+        assert!(Bps::zero().is_zero());
+        assert!(!Bps::MAX.is_zero());
+    }
+
+    #[test]
+    fn bps_to_decimal_works() {
+        assert_eq!(Bps::zero().to_decimal(), Decimal::zero());
+        assert_eq!(Bps::new(30).unwrap().to_decimal(), Decimal::permille(3));
+        assert_eq!(
+            Bps::new(1).unwrap().to_decimal(),
+            Decimal::raw(100_000_000_000_000)
+        );
+        assert_eq!(Bps::MAX.to_decimal(), Decimal::one());
+        assert_eq!(
+            Fee::from(Bps::new(250).unwrap()),
+            Fee::new(Decimal::permille(25)).unwrap()
+        );
+    }
+
+    #[test]
+    fn bps_apply_works() {
+        let bps = Bps::new(30).unwrap();
+        assert_eq!(
+            bps.apply(Uint128::new(10_000)),
+            (Uint128::new(30), Uint128::new(9970))
+        );
+        // 1999 * 0.003 = 5.997
+        assert_eq!(
+            bps.apply(Uint128::new(1999)),
+            (Uint128::new(5), Uint128::new(1994))
+        );
+        assert_eq!(
+            bps.apply_ceil(Uint128::new(1999)),
+            (Uint128::new(6), Uint128::new(1993))
+        );
+        assert_eq!(
+            bps.apply_ceil(Uint128::new(10_000)),
+            (Uint128::new(30), Uint128::new(9970))
+        );
+        assert_eq!(
+            Bps::MAX.apply(Uint128::MAX),
+            (Uint128::MAX, Uint128::zero())
+        );
+        assert_eq!(
+            Bps::zero().apply_ceil(Uint128::MAX),
+            (Uint128::zero(), Uint128::MAX)
+        );
+    }
+
+    #[test]
+    fn bps_serialization_works() {
+        let bps = Bps::new(30).unwrap();
+        assert_eq!(to_vec(&bps).unwrap(), br#"30"#);
+        assert_eq!(from_slice::<Bps>(br#"10000"#).unwrap(), Bps::MAX);
+        let err = from_slice::<Bps>(br#"10001"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Basis points 10001 exceed the maximum of 10000"));
+    }
+
+    #[test]
+    fn bps_schema_has_maximum() {
+        let schema = schemars::schema_for!(Bps).schema;
+        assert_eq!(schema.instance_type, Some(InstanceType::Integer.into()));
+        let number = schema.number.unwrap();
+        assert_eq!(number.minimum, Some(0.0));
+        assert_eq!(number.maximum, Some(10_000.0));
+    }
+
+    #[test]
+    fn fee_new_works() {
+        assert_eq!(Fee::new(Decimal::zero()).unwrap(), Fee::zero());
+        assert_eq!(
+            Fee::new(Decimal::percent(3)).unwrap().rate(),
+            Decimal::percent(3)
+        );
+        assert_eq!(Fee::new(Decimal::one()).unwrap(), Fee::MAX);
+        assert_eq!(
+            Fee::new(Decimal::percent(101)),
+            Err(FeeError::RateTooLarge {
+                rate: Decimal::percent(101)
+            })
+        );
+        assert_eq!(
+            Fee::try_from(Decimal::MAX),
+            Err(FeeError::RateTooLarge { rate: Decimal::MAX })
+        );
+        assert_eq!(Fee::percent(100).unwrap(), Fee::MAX);
+        assert_eq!(
+            Fee::percent(101),
+            Err(FeeError::RateTooLarge {
+                rate: Decimal::percent(101)
+            })
+        );
+        assert_eq!(
+            Fee::percent(u64::MAX),
+            Err(FeeError::RateTooLarge {
+                rate: Decimal::from_ratio(u64::MAX, 100u128)
+            })
+        );
+        assert!(Fee::zero().is_zero());
+    }
+
+    #[test]
+    fn fee_apply_works() {
+        let fee = Fee::percent(3).unwrap();
+        assert_eq!(
+            fee.apply(Uint128::new(100)),
+            (Uint128::new(3), Uint128::new(97))
+        );
+        assert_eq!(
+            fee.apply(Uint128::new(99)),
+            (Uint128::new(2), Uint128::new(97))
+        );
+        assert_eq!(
+            fee.apply_ceil(Uint128::new(99)),
+            (Uint128::new(3), Uint128::new(96))
+        );
+        assert_eq!(
+            fee.apply(Uint128::zero()),
+            (Uint128::zero(), Uint128::zero())
+        );
+        assert_eq!(
+            Fee::MAX.apply(Uint128::MAX),
+            (Uint128::MAX, Uint128::zero())
+        );
+        let tiny = Fee::new(Decimal::raw(1)).unwrap();
+        assert_eq!(
+            tiny.apply(Uint128::MAX).0,
+            Uint128::new(340282366920938463463)
+        );
+        assert_eq!(tiny.apply_ceil(Uint128::new(1)).0, Uint128::new(1));
+    }
+
+    #[test]
+    fn fee_apply_sums_up_to_amount() {
+        let fees = [
+            Fee::zero(),
+            Fee::new(Decimal::raw(1)).unwrap(),
+            Fee::new(Decimal::permille(3)).unwrap(),
+            Fee::new(Decimal::from_ratio(1u128, 3u128)).unwrap(),
+            Fee::MAX,
+        ];
+        let amounts = [0, 1, 2, 999, 1_000_001, u64::MAX as u128, u128::MAX];
+        for fee in fees {
+            for amount in amounts {
+                let amount = Uint128::new(amount);
+                for (fee_amount, remainder) in [fee.apply(amount), fee.apply_ceil(amount)] {
+                    assert_eq!(fee_amount + remainder, amount);
+                }
+                let (down, _) = fee.apply(amount);
+                let (up, _) = fee.apply_ceil(amount);
+                assert!(up == down || up == down + Uint128::one());
+            }
+        }
+    }
+
+    #[test]
+    fn fee_serialization_works() {
+        let fee = Fee::percent(3).unwrap();
+        assert_eq!(to_vec(&fee).unwrap(), br#""0.03""#);
+        assert_eq!(from_slice::<Fee>(br#""1""#).unwrap(), Fee::MAX);
+        let err = from_slice::<Fee>(br#""1.5""#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Fee rate 1.5 must not be greater than 1"));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::errors::OverflowError;
+use crate::{Decimal256, Uint256};
+
+/// Returns the growth factor `(1 + rate_per_period)^periods` of compounding interest.
+///
+/// This uses exponentiation by squaring, where every multiplication rounds down by less than
+/// `10^-18`. Because all intermediate values are at least 1, the result is never larger
+/// than the exact value and has a relative error below `periods * 10^-18`.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{compound, Decimal256};
+///
+/// let factor = compound(Decimal256::percent(10), 2).unwrap();
+/// assert_eq!(factor, Decimal256::percent(121));
+/// ```
+pub fn compound(rate_per_period: Decimal256, periods: u32) -> Result<Decimal256, OverflowError> {
+    (Decimal256::one() + rate_per_period).checked_pow(periods)
+}
+
+/// Converts an annual percentage rate to the annual percentage yield when compounding
+/// `periods_per_year` times, i.e. `(1 + apr / periods_per_year)^periods_per_year - 1`.
+///
+/// The rate per period and the result are rounded down, see [`compound`] for the error bound.
+///
+/// Panics if `periods_per_year` is 0.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{apr_to_apy, Decimal256};
+///
+/// let apy = apr_to_apy(Decimal256::percent(5), 365).unwrap();
+/// assert_eq!(apy.to_string(), "0.051267496467462296");
+/// ```
+pub fn apr_to_apy(apr: Decimal256, periods_per_year: u32) -> Result<Decimal256, OverflowError> {
+    assert!(periods_per_year > 0, "Periods per year must not be 0");
+    let rate = Decimal256::new(apr.atomics() / Uint256::from(periods_per_year));
+    Ok(compound(rate, periods_per_year)? - Decimal256::one())
+}
+
+/// Converts an annual percentage yield to the annual percentage rate that yields it when
+/// compounding `periods_per_year` times. This is the inverse of [`apr_to_apy`].
+///
+/// The rate per period is the largest one whose compounded yield does not exceed `apy`,
+/// so the result is rounded down.
+///
+/// Panics if `periods_per_year` is 0.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{apy_to_apr, Decimal256};
+///
+/// let apr = apy_to_apr(Decimal256::percent(5), 365).unwrap();
+/// assert_eq!(apr.to_string(), "0.048793425246405965");
+/// ```
+pub fn apy_to_apr(apy: Decimal256, periods_per_year: u32) -> Result<Decimal256, OverflowError> {
+    assert!(periods_per_year > 0, "Periods per year must not be 0");
+    let target = apy.checked_add(Decimal256::one())?;
+    // Since (1 + r)^n >= 1 + r, the rate per period is at most the yield
+    let mut low = Uint256::zero();
+    let mut high = apy.atomics();
+    while low < high {
+        let mid = low + (high - low + Uint256::one()) / Uint256::from(2u8);
+        match compound(Decimal256::new(mid), periods_per_year) {
+            Ok(factor) if factor <= target => low = mid,
+            _ => high = mid - Uint256::one(),
+        }
+    }
+    Decimal256::new(low).checked_mul(Decimal256::from_ratio(periods_per_year, 1u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OverflowOperation;
+    use std::str::FromStr;
+
+    fn dec(input: &str) -> Decimal256 {
+        Decimal256::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn compound_works() {
+        assert_eq!(
+            compound(Decimal256::percent(10), 0).unwrap(),
+            Decimal256::one()
+        );
+        assert_eq!(
+            compound(Decimal256::percent(10), 1).unwrap(),
+            Decimal256::percent(110)
+        );
+        assert_eq!(
+            compound(Decimal256::percent(10), 2).unwrap(),
+            Decimal256::percent(121)
+        );
+        assert_eq!(
+            compound(Decimal256::zero(), 1_000_000).unwrap(),
+            Decimal256::one()
+        );
+        assert_eq!(
+            compound(Decimal256::one(), 10).unwrap(),
+            Decimal256::percent(102400)
+        );
+        assert_eq!(
+            compound(Decimal256::percent(1), 12).unwrap(),
+            dec("1.126825030131969720")
+        );
+    }
+
+    #[test]
+    fn compound_error_is_bounded() {
+        // The exact value of (1 + 0.002739726027397260)^365 is 2.714567482021874032.47...
+        let factor = compound(
+            Decimal256::new(Uint256::from(2_739_726_027_397_260u64)),
+            365,
+        );
+        let exact = dec("2.714567482021874032");
+        let factor = factor.unwrap();
+        assert!(factor <= exact);
+        assert!(exact - factor < exact * Decimal256::new(Uint256::from(365u32)));
+        assert_eq!(factor, dec("2.714567482021873489"));
+
+        // Per second compounding of 100% approaches e = 2.718281828459045235...
+        // The exact value of (1 + 0.000000031709791983)^31536000 is 2.718281785295427612.93...
+        let rate = Decimal256::from_ratio(1u8, 31_536_000u32);
+        assert_eq!(rate, dec("0.000000031709791983"));
+        let factor = compound(rate, 31_536_000).unwrap();
+        let exact = dec("2.718281785295427612");
+        assert!(factor <= exact);
+        assert!(exact - factor < exact * Decimal256::new(Uint256::from(31_536_000u32)));
+        assert_eq!(factor, dec("2.718281785263373601"));
+    }
+
+    #[test]
+    fn compound_fails_on_overflow() {
+        let err = compound(Decimal256::one(), 256).unwrap_err();
+        assert_eq!(err.operation, OverflowOperation::Pow);
+        compound(Decimal256::one(), 190).unwrap();
+    }
+
+    #[test]
+    fn apr_to_apy_works() {
+        assert_eq!(
+            apr_to_apy(Decimal256::percent(5), 1).unwrap(),
+            Decimal256::percent(5)
+        );
+        assert_eq!(
+            apr_to_apy(Decimal256::percent(5), 12).unwrap(),
+            dec("0.051161897881733179")
+        );
+        assert_eq!(
+            apr_to_apy(Decimal256::percent(5), 365).unwrap(),
+            dec("0.051267496467462296")
+        );
+        assert_eq!(
+            apr_to_apy(Decimal256::one(), 31_536_000).unwrap(),
+            dec("1.718281785263373601")
+        );
+        assert_eq!(
+            apr_to_apy(Decimal256::zero(), 365).unwrap(),
+            Decimal256::zero()
+        );
+    }
+
+    #[test]
+    fn apy_to_apr_works() {
+        assert_eq!(
+            apy_to_apr(Decimal256::percent(5), 1).unwrap(),
+            Decimal256::percent(5)
+        );
+        assert_eq!(
+            apy_to_apr(Decimal256::percent(5), 365).unwrap(),
+            dec("0.048793425246405965")
+        );
+        assert_eq!(
+            apy_to_apr(Decimal256::zero(), 12).unwrap(),
+            Decimal256::zero()
+        );
+        assert!(apy_to_apr(Decimal256::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn apr_apy_conversions_roundtrip() {
+        for periods in [1, 2, 4, 12, 52, 365, 8760] {
+            for percent in [0, 1, 5, 20, 100, 250] {
+                let apr = Decimal256::percent(percent);
+                let apy = apr_to_apy(apr, periods).unwrap();
+                assert!(apy >= apr);
+                let back = apy_to_apr(apy, periods).unwrap();
+                assert!(back <= apr);
+                // apr_to_apy discards up to one atomic unit per period
+                assert!(
+                    apr - back <= Decimal256::new(Uint256::from(periods)),
+                    "apr: {}, periods: {}, back: {}",
+                    apr,
+                    periods,
+                    back
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Periods per year must not be 0")]
+    fn apr_to_apy_panics_for_zero_periods() {
+        apr_to_apy(Decimal256::percent(5), 0).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Periods per year must not be 0")]
+    fn apy_to_apr_panics_for_zero_periods() {
+        apy_to_apr(Decimal256::percent(5), 0).unwrap();
+    }
+}
@@ -1,26 +1,79 @@
+mod collateral;
+mod decay;
 mod decimal;
 mod decimal256;
+mod fee;
 mod fraction;
+mod interest;
 mod isqrt;
+mod slippage;
+mod swap;
 mod uint128;
 mod uint256;
 mod uint512;
 mod uint64;
 
+pub use collateral::{
+    collateral_value, debt_value, health_factor, is_liquidatable, loan_to_value, total_debt_value,
+    weighted_collateral_value, CollateralAsset, CollateralError, DebtAsset,
+};
+pub use decay::{exponential_decay_price, linear_decay_price};
 pub use decimal::{Decimal, DecimalRangeExceeded};
 pub use decimal256::{Decimal256, Decimal256RangeExceeded};
+pub use fee::{Bps, Fee, FeeError};
 pub use fraction::Fraction;
+pub use interest::{apr_to_apy, apy_to_apr, compound};
 pub use isqrt::Isqrt;
+pub use slippage::{
+    assert_belief_price, assert_min_receive, expected_return, min_receive, SlippageError,
+};
+pub use swap::{
+    constant_product_input, constant_product_output, stable_swap_d, stable_swap_output,
+    stable_swap_y, SwapMathError, STABLE_SWAP_MAX_AMP, STABLE_SWAP_MAX_COINS,
+};
 pub use uint128::Uint128;
 pub use uint256::Uint256;
 pub use uint512::Uint512;
 pub use uint64::Uint64;
 
+use std::ops;
+
+/// Divides and rounds up, e.g. to make sure rounding errors never favour the user
+pub(crate) fn div_ceil<I>(numerator: I, denominator: I) -> I
+where
+    I: isqrt::Unsigned
+        + ops::Add<I, Output = I>
+        + ops::Div<I, Output = I>
+        + ops::Rem<I, Output = I>
+        + PartialEq
+        + Copy
+        + From<u8>,
+{
+    let quotient = numerator / denominator;
+    if numerator % denominator == I::from(0) {
+        quotient
+    } else {
+        quotient + I::from(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::ops::*;
 
+    #[test]
+    fn div_ceil_works() {
+        assert_eq!(div_ceil(0u32, 3), 0);
+        assert_eq!(div_ceil(6u32, 3), 2);
+        assert_eq!(div_ceil(7u32, 3), 3);
+        assert_eq!(
+            div_ceil(Uint256::from(8u32), Uint256::from(3u32)),
+            Uint256::from(3u32)
+        );
+        assert_eq!(div_ceil(Uint512::MAX, Uint512::MAX), Uint512::one());
+    }
+
     /// An trait that ensures other traits are implemented for our number types
     trait AllImpl<'a>:
         Add
@@ -0,0 +1,277 @@
+use thiserror::Error;
+
+use crate::{Decimal, Uint128, Uint256};
+
+use super::div_ceil;
+
+/// `10^18`, the denominator of [`Decimal`]
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SlippageError {
+    #[error("Maximum slippage {max_slippage} must not be greater than 1")]
+    InvalidMaxSlippage { max_slippage: Decimal },
+    #[error("Belief price must not be zero")]
+    ZeroBeliefPrice,
+    #[error("Received amount {actual} is less than the minimum of {minimum}")]
+    MinReceiveNotMet { minimum: Uint128, actual: Uint128 },
+}
+
+/// Returns the smallest amount that may be received when `expected` is quoted and
+/// `max_slippage` is accepted, i.e. `expected * (1 - max_slippage)` rounded up.
+pub fn min_receive(expected: Uint128, max_slippage: Decimal) -> Result<Uint128, SlippageError> {
+    let keep = kept_share(max_slippage)?;
+    let minimum = div_ceil(expected.full_mul(keep), Uint256::from(DECIMAL_FRACTIONAL));
+    // minimum <= expected since keep <= 1
+    Ok(Uint128::try_from(minimum).unwrap())
+}
+
+/// Checks that `actual` is not less than `expected` reduced by `max_slippage`.
+///
+/// The comparison is exact, i.e. an amount just below the minimum is not rounded up to it.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{assert_min_receive, Decimal, SlippageError, Uint128};
+///
+/// let expected = Uint128::new(1000);
+/// let max_slippage = Decimal::percent(1);
+/// assert_min_receive(expected, Uint128::new(990), max_slippage).unwrap();
+/// assert_eq!(
+///     assert_min_receive(expected, Uint128::new(989), max_slippage),
+///     Err(SlippageError::MinReceiveNotMet {
+///         minimum: Uint128::new(990),
+///         actual: Uint128::new(989),
+///     }),
+/// );
+/// ```
+pub fn assert_min_receive(
+    expected: Uint128,
+    actual: Uint128,
+    max_slippage: Decimal,
+) -> Result<(), SlippageError> {
+    let keep = kept_share(max_slippage)?;
+    // actual >= expected * keep / 10^18
+    if actual.full_mul(DECIMAL_FRACTIONAL) < expected.full_mul(keep) {
+        return Err(SlippageError::MinReceiveNotMet {
+            minimum: min_receive(expected, max_slippage)?,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the amount expected for `offer_amount` at `belief_price`, which is the
+/// offered amount per returned amount. The result is rounded down.
+pub fn expected_return(
+    offer_amount: Uint128,
+    belief_price: Decimal,
+) -> Result<Uint128, SlippageError> {
+    if belief_price.is_zero() {
+        return Err(SlippageError::ZeroBeliefPrice);
+    }
+    let expected =
+        offer_amount.full_mul(DECIMAL_FRACTIONAL) / Uint256::from(belief_price.atomics());
+    Ok(Uint128::try_from(expected).unwrap_or(Uint128::MAX))
+}
+
+/// Checks that `return_amount` received for `offer_amount` is not less than the amount
+/// expected at `belief_price` reduced by `max_slippage`. The belief price is the offered
+/// amount per returned amount, like the price shown to the user before swapping.
+///
+/// The comparison is exact, i.e. no expected amount is rounded before comparing.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{assert_belief_price, Decimal, Uint128};
+///
+/// // 2 offered tokens per returned token, so 1000 offered tokens should return 500
+/// let belief_price = Decimal::percent(200);
+/// let max_slippage = Decimal::percent(2);
+/// let offer = Uint128::new(1000);
+/// assert_belief_price(offer, Uint128::new(490), belief_price, max_slippage).unwrap();
+/// assert!(assert_belief_price(offer, Uint128::new(489), belief_price, max_slippage).is_err());
+/// ```
+pub fn assert_belief_price(
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    belief_price: Decimal,
+    max_slippage: Decimal,
+) -> Result<(), SlippageError> {
+    let keep = kept_share(max_slippage)?;
+    if belief_price.is_zero() {
+        return Err(SlippageError::ZeroBeliefPrice);
+    }
+    // return_amount >= offer_amount / belief_price * keep / 10^18, multiplied
+    // by the belief price atomics
+    let price = belief_price.atomics();
+    if return_amount.full_mul(price) < offer_amount.full_mul(keep) {
+        let minimum = div_ceil(offer_amount.full_mul(keep), Uint256::from(price));
+        return Err(SlippageError::MinReceiveNotMet {
+            minimum: Uint128::try_from(minimum).unwrap_or(Uint128::MAX),
+            actual: return_amount,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the atomics of `1 - max_slippage`
+fn kept_share(max_slippage: Decimal) -> Result<Uint128, SlippageError> {
+    if max_slippage > Decimal::one() {
+        return Err(SlippageError::InvalidMaxSlippage { max_slippage });
+    }
+    Ok((Decimal::one() - max_slippage).atomics())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_receive_works() {
+        let expected = Uint128::new(1000);
+        assert_eq!(
+            min_receive(expected, Decimal::percent(1)),
+            Ok(Uint128::new(990))
+        );
+        assert_eq!(min_receive(expected, Decimal::zero()), Ok(expected));
+        assert_eq!(min_receive(expected, Decimal::one()), Ok(Uint128::zero()));
+        // rounds up: 999 * 0.99 = 989.01
+        assert_eq!(
+            min_receive(Uint128::new(999), Decimal::percent(1)),
+            Ok(Uint128::new(990))
+        );
+        assert_eq!(min_receive(Uint128::MAX, Decimal::zero()), Ok(Uint128::MAX));
+        assert_eq!(
+            min_receive(expected, Decimal::percent(101)),
+            Err(SlippageError::InvalidMaxSlippage {
+                max_slippage: Decimal::percent(101)
+            })
+        );
+    }
+
+    #[test]
+    fn assert_min_receive_works() {
+        let expected = Uint128::new(1000);
+        let max_slippage = Decimal::percent(1);
+        assert_min_receive(expected, Uint128::new(1000), max_slippage).unwrap();
+        assert_min_receive(expected, Uint128::new(2000), max_slippage).unwrap();
+        assert_min_receive(expected, Uint128::new(990), max_slippage).unwrap();
+        assert_eq!(
+            assert_min_receive(expected, Uint128::new(989), max_slippage),
+            Err(SlippageError::MinReceiveNotMet {
+                minimum: Uint128::new(990),
+                actual: Uint128::new(989),
+            })
+        );
+
+        // no slippage
+        assert_min_receive(expected, expected, Decimal::zero()).unwrap();
+        assert!(assert_min_receive(expected, Uint128::new(999), Decimal::zero()).is_err());
+
+        // any amount with full slippage
+        assert_min_receive(expected, Uint128::zero(), Decimal::one()).unwrap();
+
+        assert_eq!(
+            assert_min_receive(expected, expected, Decimal::percent(101)),
+            Err(SlippageError::InvalidMaxSlippage {
+                max_slippage: Decimal::percent(101)
+            })
+        );
+    }
+
+    #[test]
+    fn assert_min_receive_is_exact() {
+        // 999 * 0.99 = 989.01, so 989 is not enough
+        let max_slippage = Decimal::percent(1);
+        assert!(assert_min_receive(Uint128::new(999), Uint128::new(989), max_slippage).is_err());
+        assert_min_receive(Uint128::new(999), Uint128::new(990), max_slippage).unwrap();
+
+        // large values do not overflow
+        assert_min_receive(Uint128::MAX, Uint128::MAX, Decimal::zero()).unwrap();
+        assert!(
+            assert_min_receive(Uint128::MAX, Uint128::MAX - Uint128::one(), Decimal::zero())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn expected_return_works() {
+        assert_eq!(
+            expected_return(Uint128::new(1000), Decimal::percent(200)),
+            Ok(Uint128::new(500))
+        );
+        assert_eq!(
+            expected_return(Uint128::new(1000), Decimal::percent(50)),
+            Ok(Uint128::new(2000))
+        );
+        // rounds down
+        assert_eq!(
+            expected_return(Uint128::new(1000), Decimal::percent(300)),
+            Ok(Uint128::new(333))
+        );
+        assert_eq!(
+            expected_return(Uint128::MAX, Decimal::percent(50)),
+            Ok(Uint128::MAX)
+        );
+        assert_eq!(
+            expected_return(Uint128::new(1000), Decimal::zero()),
+            Err(SlippageError::ZeroBeliefPrice)
+        );
+    }
+
+    #[test]
+    fn assert_belief_price_works() {
+        let belief_price = Decimal::percent(200);
+        let max_slippage = Decimal::percent(2);
+        let offer = Uint128::new(1000);
+        assert_belief_price(offer, Uint128::new(500), belief_price, max_slippage).unwrap();
+        assert_belief_price(offer, Uint128::new(600), belief_price, max_slippage).unwrap();
+        assert_belief_price(offer, Uint128::new(490), belief_price, max_slippage).unwrap();
+        assert_eq!(
+            assert_belief_price(offer, Uint128::new(489), belief_price, max_slippage),
+            Err(SlippageError::MinReceiveNotMet {
+                minimum: Uint128::new(490),
+                actual: Uint128::new(489),
+            })
+        );
+
+        // 1000 / 3 * 0.98 = 326.66...
+        let belief_price = Decimal::percent(300);
+        assert_belief_price(offer, Uint128::new(327), belief_price, max_slippage).unwrap();
+        assert_eq!(
+            assert_belief_price(offer, Uint128::new(326), belief_price, max_slippage),
+            Err(SlippageError::MinReceiveNotMet {
+                minimum: Uint128::new(327),
+                actual: Uint128::new(326),
+            })
+        );
+
+        assert_eq!(
+            assert_belief_price(offer, Uint128::new(500), Decimal::zero(), max_slippage),
+            Err(SlippageError::ZeroBeliefPrice)
+        );
+        assert_eq!(
+            assert_belief_price(
+                offer,
+                Uint128::new(500),
+                belief_price,
+                Decimal::percent(200)
+            ),
+            Err(SlippageError::InvalidMaxSlippage {
+                max_slippage: Decimal::percent(200)
+            })
+        );
+
+        // a tiny belief price expects more than fits into a Uint128
+        assert_eq!(
+            assert_belief_price(Uint128::MAX, Uint128::MAX, Decimal::raw(1), Decimal::zero()),
+            Err(SlippageError::MinReceiveNotMet {
+                minimum: Uint128::MAX,
+                actual: Uint128::MAX,
+            })
+        );
+    }
+}
@@ -0,0 +1,543 @@
+use thiserror::Error;
+
+use crate::errors::OverflowError;
+use crate::{Uint256, Uint512};
+
+use super::div_ceil;
+
+/// The largest supported stable swap amplification coefficient
+pub const STABLE_SWAP_MAX_AMP: u64 = 1_000_000;
+/// The largest number of coins supported in a stable swap pool
+pub const STABLE_SWAP_MAX_COINS: usize = 8;
+/// The maximum number of Newton iterations before giving up
+const MAX_ITERATIONS: usize = 255;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SwapMathError {
+    #[error("Pool reserves must not be empty")]
+    EmptyReserves,
+    #[error("Requested amount {amount} is not less than the reserve of {reserve}")]
+    InsufficientReserve { amount: Uint256, reserve: Uint256 },
+    #[error("Stable swap pools must have between 2 and {max} coins, got {count}", max = STABLE_SWAP_MAX_COINS)]
+    InvalidCoinCount { count: usize },
+    #[error("Invalid coin indices {i} and {j} for a pool of {count} coins")]
+    InvalidCoinIndex { i: usize, j: usize, count: usize },
+    #[error("Amplification must be between 1 and {max}, got {amp}", max = STABLE_SWAP_MAX_AMP)]
+    InvalidAmplification { amp: u64 },
+    #[error("Stable swap invariant did not converge")]
+    NotConverged,
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+}
+
+/// Returns the amount received when swapping `amount_in` into a constant product (`x * y = k`)
+/// pool. The result is rounded down, so the product of the reserves never decreases.
+///
+/// Fees are not taken into account, deduct them from `amount_in` or the result as needed.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{constant_product_output, Uint256};
+///
+/// let output = constant_product_output(
+///     Uint256::from(1000u32),
+///     Uint256::from(2000u32),
+///     Uint256::from(250u32),
+/// ).unwrap();
+/// assert_eq!(output, Uint256::from(400u32));
+/// ```
+pub fn constant_product_output(
+    reserve_in: Uint256,
+    reserve_out: Uint256,
+    amount_in: Uint256,
+) -> Result<Uint256, SwapMathError> {
+    if reserve_in.is_zero() || reserve_out.is_zero() {
+        return Err(SwapMathError::EmptyReserves);
+    }
+    let denominator = Uint512::from(reserve_in) + Uint512::from(amount_in);
+    let output = reserve_out.full_mul(amount_in) / denominator;
+    // output < reserve_out since amount_in < reserve_in + amount_in
+    Ok(Uint256::try_from(output).unwrap())
+}
+
+/// Returns the smallest amount that needs to be swapped into a constant product pool
+/// to receive `amount_out`, i.e. the result is rounded up.
+pub fn constant_product_input(
+    reserve_in: Uint256,
+    reserve_out: Uint256,
+    amount_out: Uint256,
+) -> Result<Uint256, SwapMathError> {
+    if reserve_in.is_zero() || reserve_out.is_zero() {
+        return Err(SwapMathError::EmptyReserves);
+    }
+    if amount_out >= reserve_out {
+        return Err(SwapMathError::InsufficientReserve {
+            amount: amount_out,
+            reserve: reserve_out,
+        });
+    }
+    let input = div_ceil(
+        reserve_in.full_mul(amount_out),
+        Uint512::from(reserve_out - amount_out),
+    );
+    Uint256::try_from(input).map_err(|_| {
+        OverflowError::new(crate::OverflowOperation::Mul, reserve_in, amount_out).into()
+    })
+}
+
+/// Computes the stable swap (Curve) invariant `D` of the pool with the given balances
+/// and amplification coefficient `amp`.
+///
+/// The Newton iteration starts at the sum of the balances, which is an upper bound of `D`,
+/// and decreases monotonically from there. It stops as soon as an iteration does not
+/// decrease `D` any more, so it cannot oscillate due to rounding. For very unbalanced pools
+/// the intermediate values can overflow, in which case an error is returned.
+pub fn stable_swap_d(balances: &[Uint256], amp: u64) -> Result<Uint256, SwapMathError> {
+    check_pool(balances, amp)?;
+    let sum = balances.iter().fold(Uint512::zero(), |sum, &balance| {
+        sum + Uint512::from(balance)
+    });
+    if sum.is_zero() {
+        return Ok(Uint256::zero());
+    }
+    if balances.iter().any(Uint256::is_zero) {
+        return Err(SwapMathError::EmptyReserves);
+    }
+
+    let n = Uint512::from(balances.len() as u64);
+    let ann = amp_times_n_pow_n(amp, balances.len());
+    // Dividing by the small balances first keeps the precision of d_p
+    let mut sorted = balances.to_vec();
+    sorted.sort();
+
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = d^(n+1) / (n^n * prod(balances))
+        let mut d_p = d;
+        for &balance in &sorted {
+            d_p = d_p.checked_mul(d)? / (Uint512::from(balance) * n);
+        }
+        let numerator = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(n)?)?
+            .checked_mul(d)?;
+        let denominator = (ann - Uint512::one())
+            .checked_mul(d)?
+            .checked_add((n + Uint512::one()).checked_mul(d_p)?)?;
+        let next = numerator / denominator;
+        if next >= d {
+            // d <= sum fits into a Uint256
+            return Ok(Uint256::try_from(d).unwrap());
+        }
+        d = next;
+    }
+    Err(SwapMathError::NotConverged)
+}
+
+/// Computes the balance of coin `j` that keeps the invariant `d` when the balance of
+/// coin `i` is changed to `new_balance_i`. All other balances are kept.
+///
+/// The result is never more than one unit below the exact value, such that withdrawing
+/// `balances[j] - y - 1` from the pool does not decrease its invariant.
+pub fn stable_swap_y(
+    balances: &[Uint256],
+    amp: u64,
+    i: usize,
+    j: usize,
+    new_balance_i: Uint256,
+    d: Uint256,
+) -> Result<Uint256, SwapMathError> {
+    check_pool(balances, amp)?;
+    let count = balances.len();
+    if i == j || i >= count || j >= count {
+        return Err(SwapMathError::InvalidCoinIndex { i, j, count });
+    }
+
+    let n = Uint512::from(count as u64);
+    let ann = amp_times_n_pow_n(amp, count);
+    let d = Uint512::from(d);
+    let mut others: Vec<Uint256> = (0..count)
+        .filter(|&k| k != j)
+        .map(|k| if k == i { new_balance_i } else { balances[k] })
+        .collect();
+    if others.iter().any(Uint256::is_zero) {
+        return Err(SwapMathError::EmptyReserves);
+    }
+    others.sort();
+
+    // c = d^(n+1) / (n^n * prod(others) * ann * n) and b = sum(others) + d / ann.
+    // Rounding c up and b down rounds y up.
+    let mut sum = Uint512::zero();
+    let mut c = d;
+    for &balance in &others {
+        let balance = Uint512::from(balance);
+        sum += balance;
+        c = div_ceil(c.checked_mul(d)?, balance * n);
+    }
+    c = div_ceil(c.checked_mul(d)?, ann * n);
+    let b = sum + d / ann;
+
+    // y^2 + (b - d) * y = c has a single positive root. One Newton step from any positive
+    // value lands at or above it, from where the iteration decreases monotonically.
+    let step = |y: Uint512| -> Result<Uint512, SwapMathError> {
+        let numerator = y.checked_mul(y)?.checked_add(c)?;
+        let denominator = (y + y + b).checked_sub(d)?;
+        Ok(numerator / denominator)
+    };
+    let mut y = step(d)?;
+    for _ in 0..MAX_ITERATIONS {
+        let next = step(y)?;
+        if next >= y {
+            return Uint256::try_from(y).map_err(|_| {
+                OverflowError::new(crate::OverflowOperation::Mul, y, Uint256::one()).into()
+            });
+        }
+        y = next;
+    }
+    Err(SwapMathError::NotConverged)
+}
+
+/// Returns the amount of coin `j` received when swapping `amount_in` of coin `i` into a stable
+/// swap pool. The result is rounded down by one unit, so the invariant never decreases.
+///
+/// Fees are not taken into account, deduct them from `amount_in` or the result as needed.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{stable_swap_output, Uint256};
+///
+/// let balances = [Uint256::from(1_000_000u32), Uint256::from(1_000_000u32)];
+/// let output = stable_swap_output(&balances, 100, 0, 1, Uint256::from(1000u32)).unwrap();
+/// assert_eq!(output, Uint256::from(999u32));
+/// ```
+pub fn stable_swap_output(
+    balances: &[Uint256],
+    amp: u64,
+    i: usize,
+    j: usize,
+    amount_in: Uint256,
+) -> Result<Uint256, SwapMathError> {
+    check_pool(balances, amp)?;
+    if i == j || i >= balances.len() || j >= balances.len() {
+        return Err(SwapMathError::InvalidCoinIndex {
+            i,
+            j,
+            count: balances.len(),
+        });
+    }
+    let d = stable_swap_d(balances, amp)?;
+    let new_balance_i = balances[i].checked_add(amount_in)?;
+    let y = stable_swap_y(balances, amp, i, j, new_balance_i, d)?;
+    Ok(balances[j].saturating_sub(y).saturating_sub(Uint256::one()))
+}
+
+fn check_pool(balances: &[Uint256], amp: u64) -> Result<(), SwapMathError> {
+    if balances.len() < 2 || balances.len() > STABLE_SWAP_MAX_COINS {
+        return Err(SwapMathError::InvalidCoinCount {
+            count: balances.len(),
+        });
+    }
+    if amp == 0 || amp > STABLE_SWAP_MAX_AMP {
+        return Err(SwapMathError::InvalidAmplification { amp });
+    }
+    Ok(())
+}
+
+/// `amp * n^n`, which is at most `10^6 * 8^8` and thus cannot overflow
+fn amp_times_n_pow_n(amp: u64, n: usize) -> Uint512 {
+    Uint512::from(amp as u128 * (n as u128).pow(n as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Xoshiro256PlusPlus;
+
+    fn u(value: u128) -> Uint256 {
+        Uint256::from(value)
+    }
+
+    /// A random value below 2^bits, where bits is random up to max_bits
+    fn random_amount(rng: &mut Xoshiro256PlusPlus, max_bits: u32) -> Uint256 {
+        let value = rng.next_u128();
+        let bits = (rng.next_u64() % max_bits as u64) as u32 + 1;
+        u(if bits >= 128 {
+            value
+        } else {
+            value % (1 << bits)
+        })
+    }
+
+    #[test]
+    fn constant_product_output_works() {
+        assert_eq!(
+            constant_product_output(u(1000), u(2000), u(250)),
+            Ok(u(400))
+        );
+        assert_eq!(constant_product_output(u(1000), u(2000), u(0)), Ok(u(0)));
+        // rounds down: 2000 * 1 / 1001
+        assert_eq!(constant_product_output(u(1000), u(2000), u(1)), Ok(u(1)));
+        assert_eq!(
+            constant_product_output(u(1), u(1000), Uint256::MAX),
+            Ok(u(999))
+        );
+        assert_eq!(
+            constant_product_output(Uint256::MAX, Uint256::MAX, Uint256::MAX),
+            Ok(Uint256::MAX / u(2))
+        );
+        assert_eq!(
+            constant_product_output(u(0), u(2000), u(250)),
+            Err(SwapMathError::EmptyReserves)
+        );
+        assert_eq!(
+            constant_product_output(u(1000), u(0), u(250)),
+            Err(SwapMathError::EmptyReserves)
+        );
+    }
+
+    #[test]
+    fn constant_product_input_works() {
+        assert_eq!(constant_product_input(u(1000), u(2000), u(400)), Ok(u(250)));
+        assert_eq!(constant_product_input(u(1000), u(2000), u(0)), Ok(u(0)));
+        // rounds up: 1000 * 1 / 1999
+        assert_eq!(constant_product_input(u(1000), u(2000), u(1)), Ok(u(1)));
+        assert_eq!(
+            constant_product_input(u(1000), u(2000), u(1999)),
+            Ok(u(1_999_000))
+        );
+        assert_eq!(
+            constant_product_input(u(1000), u(2000), u(2000)),
+            Err(SwapMathError::InsufficientReserve {
+                amount: u(2000),
+                reserve: u(2000)
+            })
+        );
+        assert!(matches!(
+            constant_product_input(Uint256::MAX, Uint256::MAX, Uint256::MAX - u(1)),
+            Err(SwapMathError::Overflow(_))
+        ));
+        assert_eq!(
+            constant_product_input(u(0), u(2000), u(1)),
+            Err(SwapMathError::EmptyReserves)
+        );
+    }
+
+    #[test]
+    fn constant_product_properties() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(1);
+        for _ in 0..2000 {
+            let reserve_in = random_amount(&mut rng, 128) + u(1);
+            let reserve_out = random_amount(&mut rng, 128) + u(1);
+            let amount_in = random_amount(&mut rng, 128);
+
+            let output = constant_product_output(reserve_in, reserve_out, amount_in).unwrap();
+            assert!(output < reserve_out);
+            // the product of the reserves does not decrease
+            let k = reserve_in.full_mul(reserve_out);
+            let new_k = (reserve_in + amount_in).full_mul(reserve_out - output);
+            assert!(new_k >= k);
+            // one more unit of output would decrease it
+            if output + u(1) < reserve_out {
+                let k_more = (reserve_in + amount_in).full_mul(reserve_out - output - u(1));
+                assert!(k_more < k);
+            }
+
+            // the required input yields at least the requested output, one unit less does not
+            let input = constant_product_input(reserve_in, reserve_out, output).unwrap();
+            assert!(input <= amount_in);
+            assert!(constant_product_output(reserve_in, reserve_out, input).unwrap() >= output);
+            if !input.is_zero() {
+                let less = constant_product_output(reserve_in, reserve_out, input - u(1));
+                assert!(less.unwrap() < output);
+            }
+        }
+    }
+
+    #[test]
+    fn stable_swap_d_works() {
+        // balanced pools have D = sum
+        assert_eq!(
+            stable_swap_d(&[u(1_000_000), u(1_000_000)], 100),
+            Ok(u(2_000_000))
+        );
+        assert_eq!(
+            stable_swap_d(&[u(1_000_000), u(1_000_000)], 1),
+            Ok(u(2_000_000))
+        );
+        let max = Uint256::from(u128::MAX);
+        assert_eq!(stable_swap_d(&[max, max], 1000), Ok(max + max));
+        // reference values computed with arbitrary precision integers
+        assert_eq!(
+            stable_swap_d(&[u(1_000_000), u(2_000_000)], 100),
+            Ok(u(2_999_068))
+        );
+        assert_eq!(
+            stable_swap_d(&[u(1000), u(5000), u(10_000)], 50),
+            Ok(u(15_976))
+        );
+
+        assert_eq!(stable_swap_d(&[u(0), u(0)], 100), Ok(u(0)));
+        assert_eq!(
+            stable_swap_d(&[u(0), u(1)], 100),
+            Err(SwapMathError::EmptyReserves)
+        );
+    }
+
+    #[test]
+    fn stable_swap_d_validates_pool() {
+        assert_eq!(
+            stable_swap_d(&[u(1)], 100),
+            Err(SwapMathError::InvalidCoinCount { count: 1 })
+        );
+        assert_eq!(
+            stable_swap_d(&[u(1); 9], 100),
+            Err(SwapMathError::InvalidCoinCount { count: 9 })
+        );
+        stable_swap_d(&[u(1); 8], 100).unwrap();
+        assert_eq!(
+            stable_swap_d(&[u(1), u(1)], 0),
+            Err(SwapMathError::InvalidAmplification { amp: 0 })
+        );
+        assert_eq!(
+            stable_swap_d(&[u(1), u(1)], STABLE_SWAP_MAX_AMP + 1),
+            Err(SwapMathError::InvalidAmplification {
+                amp: STABLE_SWAP_MAX_AMP + 1
+            })
+        );
+        stable_swap_d(&[u(1), u(1)], STABLE_SWAP_MAX_AMP).unwrap();
+    }
+
+    #[test]
+    fn stable_swap_d_is_precise_for_unbalanced_pools() {
+        // Dividing by the large balances first would round d_p down to 0 here and
+        // return a D that is about 66 times too large
+        let balances = [
+            u(17999149878927530871534016767506280276),
+            u(28095021118676031164319595544092308009),
+            u(1),
+            u(1),
+        ];
+        assert_eq!(stable_swap_d(&balances, 1), Ok(u(273401856393184760808353)));
+    }
+
+    #[test]
+    fn stable_swap_output_works() {
+        let balanced = [u(1_000_000), u(1_000_000)];
+        assert_eq!(
+            stable_swap_output(&balanced, 100, 0, 1, u(1000)),
+            Ok(u(999))
+        );
+        assert_eq!(stable_swap_output(&balanced, 1, 0, 1, u(1000)), Ok(u(999)));
+        assert_eq!(stable_swap_output(&balanced, 100, 0, 1, u(0)), Ok(u(0)));
+
+        let unbalanced = [u(1_000_000), u(2_000_000)];
+        assert_eq!(
+            stable_swap_output(&unbalanced, 100, 0, 1, u(100_000)),
+            Ok(u(100_360))
+        );
+        assert_eq!(
+            stable_swap_output(&unbalanced, 100, 1, 0, u(100_000)),
+            Ok(u(99_513))
+        );
+
+        let large = [u(10u128.pow(24)); 3];
+        assert_eq!(
+            stable_swap_output(&large, 2000, 0, 2, u(10u128.pow(23))),
+            Ok(u(99999438867759813588461))
+        );
+
+        assert_eq!(
+            stable_swap_output(&balanced, 100, 0, 0, u(1000)),
+            Err(SwapMathError::InvalidCoinIndex {
+                i: 0,
+                j: 0,
+                count: 2
+            })
+        );
+        assert_eq!(
+            stable_swap_output(&balanced, 100, 0, 2, u(1000)),
+            Err(SwapMathError::InvalidCoinIndex {
+                i: 0,
+                j: 2,
+                count: 2
+            })
+        );
+        assert!(matches!(
+            stable_swap_output(&[Uint256::MAX, u(1)], 100, 0, 1, u(1)),
+            Err(SwapMathError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn stable_swap_y_works() {
+        let balances = [u(1_000_000), u(1_000_000)];
+        let d = stable_swap_d(&balances, 100).unwrap();
+        // unchanged balances give the current balance, up to the rounding
+        let y = stable_swap_y(&balances, 100, 0, 1, u(1_000_000), d).unwrap();
+        assert!(y >= u(1_000_000) && y <= u(1_000_001));
+        assert_eq!(
+            stable_swap_y(&balances, 100, 0, 1, u(2_000_000), d),
+            Ok(u(47_562))
+        );
+        assert_eq!(
+            stable_swap_y(&balances, 100, 0, 1, u(0), d),
+            Err(SwapMathError::EmptyReserves)
+        );
+        assert_eq!(
+            stable_swap_y(&balances, 100, 1, 1, u(1), d),
+            Err(SwapMathError::InvalidCoinIndex {
+                i: 1,
+                j: 1,
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn stable_swap_properties() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(2);
+        let amps = [1, 10, 100, 1000, STABLE_SWAP_MAX_AMP];
+        for _ in 0..500 {
+            let count = (rng.next_u64() % 4) as usize + 2;
+            let max_bits = [20, 64, 100, 127][(rng.next_u64() % 4) as usize];
+            let balances: Vec<_> = (0..count)
+                .map(|_| random_amount(&mut rng, max_bits) + u(1))
+                .collect();
+            let amp = amps[(rng.next_u64() % 5) as usize];
+            let i = (rng.next_u64() % count as u64) as usize;
+            let j = (i + 1 + (rng.next_u64() % (count as u64 - 1)) as usize) % count;
+            let amount_in = random_amount(&mut rng, max_bits);
+
+            let d = match stable_swap_d(&balances, amp) {
+                Ok(d) => d,
+                // very unbalanced pools may overflow, but never return wrong values
+                Err(SwapMathError::Overflow(_)) => continue,
+                Err(err) => panic!("unexpected error: {}", err),
+            };
+            let sum = balances.iter().fold(Uint256::zero(), |a, &b| a + b);
+            assert!(d <= sum);
+
+            let output = match stable_swap_output(&balances, amp, i, j, amount_in) {
+                Ok(output) => output,
+                Err(SwapMathError::Overflow(_)) => continue,
+                Err(err) => panic!("unexpected error: {}", err),
+            };
+            assert!(output < balances[j]);
+
+            // the invariant does not decrease
+            let mut after = balances.clone();
+            after[i] += amount_in;
+            after[j] -= output;
+            if let Ok(new_d) = stable_swap_d(&after, amp) {
+                assert!(new_d >= d, "D decreased for {:?}, amp {}", balances, amp);
+            }
+
+            // more input never gives less output
+            if let Ok(more) = stable_swap_output(&balances, amp, i, j, amount_in + amount_in) {
+                assert!(more >= output);
+            }
+        }
+    }
+}
@@ -72,6 +72,17 @@ impl Uint128 {
         self.0.to_le_bytes()
     }
 
+    /// Returns a big endian encoding whose lexicographical byte order equals the numeric order.
+    /// Use this for storage keys that should be iterated in numeric order.
+    pub const fn to_be_key(self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// Creates a number from an encoding created by [`Uint128::to_be_key`].
+    pub const fn from_be_key(key: [u8; 16]) -> Self {
+        Self(u128::from_be_bytes(key))
+    }
+
     pub const fn is_zero(&self) -> bool {
         self.0 == 0
     }
@@ -1011,4 +1022,20 @@ mod tests {
             assert_eq!(&lhs == &rhs, expected);
         }
     }
+
+    #[test]
+    fn uint128_be_key_works() {
+        let values = [
+            Uint128::zero(),
+            Uint128::new(255),
+            Uint128::new(256),
+            Uint128::MAX,
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Uint128::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
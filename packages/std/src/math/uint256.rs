@@ -153,6 +153,17 @@ impl Uint256 {
         unsafe { std::mem::transmute::<[[u8; 8]; 4], [u8; 32]>(words) }
     }
 
+    /// Returns a big endian encoding whose lexicographical byte order equals the numeric order.
+    /// Use this for storage keys that should be iterated in numeric order.
+    pub const fn to_be_key(self) -> [u8; 32] {
+        self.to_be_bytes()
+    }
+
+    /// Creates a number from an encoding created by [`Uint256::to_be_key`].
+    pub const fn from_be_key(key: [u8; 32]) -> Self {
+        Self::from_be_bytes(key)
+    }
+
     pub const fn is_zero(&self) -> bool {
         let words = (self.0).0;
         words[0] == 0 && words[1] == 0 && words[2] == 0 && words[3] == 0
@@ -1592,4 +1603,20 @@ mod tests {
             assert_eq!(&lhs == &rhs, expected);
         }
     }
+
+    #[test]
+    fn uint256_be_key_works() {
+        let values = [
+            Uint256::zero(),
+            Uint256::from(255u32),
+            Uint256::from(256u32),
+            Uint256::MAX,
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Uint256::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
@@ -182,6 +182,17 @@ impl Uint512 {
         unsafe { std::mem::transmute::<[[u8; 8]; 8], [u8; 64]>(words) }
     }
 
+    /// Returns a big endian encoding whose lexicographical byte order equals the numeric order.
+    /// Use this for storage keys that should be iterated in numeric order.
+    pub const fn to_be_key(self) -> [u8; 64] {
+        self.to_be_bytes()
+    }
+
+    /// Creates a number from an encoding created by [`Uint512::to_be_key`].
+    pub const fn from_be_key(key: [u8; 64]) -> Self {
+        Self::from_be_bytes(key)
+    }
+
     pub const fn is_zero(&self) -> bool {
         let words = (self.0).0;
         words[0] == 0
@@ -1227,4 +1238,20 @@ mod tests {
             assert_eq!(&lhs == &rhs, expected);
         }
     }
+
+    #[test]
+    fn uint512_be_key_works() {
+        let values = [
+            Uint512::zero(),
+            Uint512::from(255u32),
+            Uint512::from(256u32),
+            Uint512::MAX,
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Uint512::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
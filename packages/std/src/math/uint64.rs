@@ -68,6 +68,17 @@ impl Uint64 {
         self.0.to_le_bytes()
     }
 
+    /// Returns a big endian encoding whose lexicographical byte order equals the numeric order.
+    /// Use this for storage keys that should be iterated in numeric order.
+    pub const fn to_be_key(self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    /// Creates a number from an encoding created by [`Uint64::to_be_key`].
+    pub const fn from_be_key(key: [u8; 8]) -> Self {
+        Self(u64::from_be_bytes(key))
+    }
+
     pub const fn is_zero(&self) -> bool {
         self.0 == 0
     }
@@ -924,4 +935,20 @@ mod tests {
             assert_eq!(&lhs == &rhs, expected);
         }
     }
+
+    #[test]
+    fn uint64_be_key_works() {
+        let values = [
+            Uint64::zero(),
+            Uint64::new(255),
+            Uint64::new(256),
+            Uint64::MAX,
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Uint64::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
@@ -15,6 +15,8 @@ use crate::ibc::{
     IbcEndpoint, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
     IbcTimeoutBlock,
 };
+#[cfg(feature = "iterator")]
+use crate::iterator::{Order, Record};
 use crate::math::Uint128;
 #[cfg(feature = "cosmwasm_1_1")]
 use crate::query::SupplyResponse;
@@ -30,7 +32,7 @@ use crate::results::{ContractResult, Empty, SystemResult};
 use crate::serde::{from_slice, to_binary};
 use crate::storage::MemoryStorage;
 use crate::timestamp::Timestamp;
-use crate::traits::{Api, Querier, QuerierResult};
+use crate::traits::{Api, Querier, QuerierResult, Storage};
 use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
 use crate::Attribute;
 
@@ -75,6 +77,96 @@ pub fn mock_dependencies_with_balances(
 // We can later make simplifications here if needed
 pub type MockStorage = MemoryStorage;
 
+/// A storage mutation recorded by [`RecordingStorage`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StorageMutation {
+    Set { key: Vec<u8>, value: Vec<u8> },
+    Remove { key: Vec<u8> },
+}
+
+/// A [`Storage`] wrapper that records all `set` and `remove` calls in the order
+/// they happen, together with the values written.
+///
+/// This helps to find out why a key ended up with a certain value, e.g. in
+/// flows with many submessages. Reads are not recorded.
+///
+/// # Examples
+///
+/// ```
+/// use cosmwasm_std::testing::{MockStorage, RecordingStorage, StorageMutation};
+/// use cosmwasm_std::Storage;
+///
+/// let mut storage = RecordingStorage::new(MockStorage::new());
+/// storage.set(b"count", b"1");
+/// storage.remove(b"count");
+/// assert_eq!(
+///     storage.mutations(),
+///     [
+///         StorageMutation::Set { key: b"count".to_vec(), value: b"1".to_vec() },
+///         StorageMutation::Remove { key: b"count".to_vec() },
+///     ]
+/// );
+/// ```
+#[derive(Default, Debug)]
+pub struct RecordingStorage<S: Storage = MockStorage> {
+    inner: S,
+    mutations: Vec<StorageMutation>,
+}
+
+impl<S: Storage> RecordingStorage<S> {
+    pub fn new(inner: S) -> Self {
+        RecordingStorage {
+            inner,
+            mutations: Vec::new(),
+        }
+    }
+
+    /// Returns all mutations recorded so far, oldest first
+    pub fn mutations(&self) -> &[StorageMutation] {
+        &self.mutations
+    }
+
+    /// Returns all mutations recorded so far and clears the log.
+    /// Use this to get the mutations of a single call.
+    pub fn take_mutations(&mut self) -> Vec<StorageMutation> {
+        std::mem::take(&mut self.mutations)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Storage> Storage for RecordingStorage<S> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.inner.get(key)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        self.inner.range(start, end, order)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.mutations.push(StorageMutation::Set {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+        self.inner.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.mutations
+            .push(StorageMutation::Remove { key: key.to_vec() });
+        self.inner.remove(key);
+    }
+}
+
 /// Length of canonical addresses created with this API. Contracts should not make any assumtions
 /// what this value is.
 /// The value here must be restorable with `SHUFFLES_ENCODE` + `SHUFFLES_DECODE` in-shuffles.
@@ -557,6 +649,14 @@ impl Default for WasmQuerier {
                 WasmQuery::Smart { contract_addr, .. } => contract_addr,
                 WasmQuery::Raw { contract_addr, .. } => contract_addr,
                 WasmQuery::ContractInfo { contract_addr, .. } => contract_addr,
+                #[cfg(feature = "cosmwasm_1_2")]
+                WasmQuery::StorageUsage { contract_addr, .. } => contract_addr,
+                #[cfg(feature = "cosmwasm_1_2")]
+                WasmQuery::HostInfo {} => {
+                    return SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&mock_host_info()).unwrap(),
+                    ))
+                }
             }
             .clone();
             SystemResult::Err(SystemError::NoSuchContract { addr })
@@ -565,6 +665,25 @@ impl Default for WasmQuerier {
     }
 }
 
+/// The host info returned by the mock querier by default: this library's version and
+/// a capability for each enabled feature
+#[cfg(feature = "cosmwasm_1_2")]
+fn mock_host_info() -> crate::HostInfoResponse {
+    let mut capabilities = vec![];
+    if cfg!(feature = "iterator") {
+        capabilities.push("iterator".to_string());
+    }
+    if cfg!(feature = "staking") {
+        capabilities.push("staking".to_string());
+    }
+    if cfg!(feature = "stargate") {
+        capabilities.push("stargate".to_string());
+    }
+    capabilities.push("cosmwasm_1_1".to_string());
+    capabilities.push("cosmwasm_1_2".to_string());
+    crate::HostInfoResponse::new(env!("CARGO_PKG_VERSION"), capabilities)
+}
+
 #[derive(Clone, Default)]
 pub struct BankQuerier {
     #[allow(dead_code)]
@@ -837,6 +956,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn recording_storage_works() {
+        let mut storage = RecordingStorage::new(MockStorage::new());
+        storage.set(b"foo", b"1");
+        storage.set(b"bar", b"2");
+        storage.remove(b"foo");
+        assert_eq!(storage.get(b"foo"), None);
+        assert_eq!(storage.get(b"bar"), Some(b"2".to_vec()));
+
+        let mutations = storage.take_mutations();
+        assert_eq!(
+            mutations,
+            vec![
+                StorageMutation::Set {
+                    key: b"foo".to_vec(),
+                    value: b"1".to_vec()
+                },
+                StorageMutation::Set {
+                    key: b"bar".to_vec(),
+                    value: b"2".to_vec()
+                },
+                StorageMutation::Remove {
+                    key: b"foo".to_vec()
+                },
+            ]
+        );
+        assert_eq!(storage.mutations(), []);
+
+        let inner = storage.into_inner();
+        assert_eq!(inner.get(b"bar"), Some(b"2".to_vec()));
+    }
+
     #[test]
     fn addr_validate_works() {
         let api = MockApi::default();
@@ -1458,6 +1609,20 @@ mod tests {
             err => panic!("Unexpected error: {:?}", err),
         }
 
+        // Query WasmQuery::StorageUsage
+        #[cfg(feature = "cosmwasm_1_2")]
+        {
+            let system_err = querier
+                .query(&WasmQuery::StorageUsage {
+                    contract_addr: any_addr.clone(),
+                })
+                .unwrap_err();
+            match system_err {
+                SystemError::NoSuchContract { addr } => assert_eq!(addr, any_addr),
+                err => panic!("Unexpected error: {:?}", err),
+            }
+        }
+
         querier.update_handler(|request| {
             let constract1 = Addr::unchecked("contract1");
             let mut storage1 = HashMap::<Binary, Binary>::default();
@@ -1511,6 +1676,27 @@ mod tests {
                         })
                     }
                 }
+                #[cfg(feature = "cosmwasm_1_2")]
+                WasmQuery::StorageUsage { contract_addr } => {
+                    if *contract_addr == constract1 {
+                        let response = crate::StorageUsageResponse::new(
+                            storage1.len() as u64,
+                            storage1
+                                .iter()
+                                .map(|(k, v)| (k.len() + v.len()) as u64)
+                                .sum(),
+                        );
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                    } else {
+                        SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.clone(),
+                        })
+                    }
+                }
+                #[cfg(feature = "cosmwasm_1_2")]
+                WasmQuery::HostInfo {} => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "HostInfo".to_string(),
+                }),
             }
         });
 
@@ -1567,6 +1753,20 @@ mod tests {
             ),
             res => panic!("Unexpected result: {:?}", res),
         }
+
+        // WasmQuery::StorageUsage
+        #[cfg(feature = "cosmwasm_1_2")]
+        {
+            let result = querier.query(&WasmQuery::StorageUsage {
+                contract_addr: "contract1".into(),
+            });
+            match result {
+                SystemResult::Ok(ContractResult::Ok(value)) => {
+                    assert_eq!(value, br#"{"key_count":1,"total_bytes":16}"# as &[u8])
+                }
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
     }
 
     #[test]
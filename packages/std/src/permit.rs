@@ -0,0 +1,264 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::adr36::{secp256k1_pubkey_to_address, verify_adr36};
+use crate::errors::{Bech32Error, StdError, VerificationError};
+use crate::serde::to_vec;
+use crate::{Addr, Api, Binary, Env, Timestamp};
+
+/// An off-chain signed authorization, which allows a contract to act on behalf of the signer
+/// without a transaction from them. The typical use case is querying private state
+/// ("query my balance with a signature") in contracts that do not expose it publicly.
+///
+/// The signer signs the JSON serialization of the [`PermitParams`] as arbitrary data following
+/// [ADR-36](https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md),
+/// e.g. using `signArbitrary` in Keplr. The serialization must match the one created by this
+/// library exactly, i.e. fields in declaration order without whitespace.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{Addr, Deps, Env, Permit, PermitError};
+/// fn authorize_query(deps: Deps, env: &Env, permit: &Permit) -> Result<Addr, PermitError> {
+///     let signer = permit.verify(deps.api, env, "cosmos")?;
+///     if !permit.params.permissions.iter().any(|p| p == "balance") {
+///         return Err(PermitError::PermissionMissing {
+///             permission: "balance".to_string(),
+///         });
+///     }
+///     Ok(signer)
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Permit {
+    pub params: PermitParams,
+    pub signature: PermitSignature,
+}
+
+/// The signed content of a [`Permit`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PermitParams {
+    /// A name chosen by the signer, which contracts can use to let signers revoke permits
+    pub permit_name: String,
+    /// The chain the permit is valid on
+    pub chain_id: String,
+    /// The contract addresses the permit is valid for
+    pub allowed_contracts: Vec<String>,
+    /// Contract specific permissions granted by the permit
+    pub permissions: Vec<String>,
+    /// The block time at which the permit becomes invalid, if any
+    pub expires: Option<Timestamp>,
+    /// A number that must increase with every permit of a signer for contracts that
+    /// need replay protection. See [`PermitParams::check_nonce`].
+    pub nonce: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PermitSignature {
+    /// The compressed secp256k1 public key of the signer (33 bytes)
+    pub pub_key: Binary,
+    /// The secp256k1 signature in compact format (64 bytes)
+    pub signature: Binary,
+}
+
+#[derive(Error, Debug)]
+pub enum PermitError {
+    #[error("Permit is for chain {chain_id}, not for this chain")]
+    WrongChain { chain_id: String },
+    #[error("Permit does not allow contract {contract}")]
+    ContractNotAllowed { contract: String },
+    #[error("Permit expired at {expires}")]
+    Expired { expires: Timestamp },
+    #[error("Permit does not contain a nonce")]
+    NonceMissing,
+    #[error("Permit nonce {nonce} is not greater than the last used nonce {last_used}")]
+    NonceAlreadyUsed { nonce: u64, last_used: u64 },
+    #[error("Permit does not grant permission {permission}")]
+    PermissionMissing { permission: String },
+    #[error("Permit public key must be a 33 byte compressed secp256k1 key")]
+    InvalidPublicKey,
+    #[error("Permit signature is invalid")]
+    InvalidSignature,
+    #[error("Permit signature verification failed: {0}")]
+    Verification(#[from] VerificationError),
+    #[error("{0}")]
+    Bech32(#[from] Bech32Error),
+    #[error("{0}")]
+    Std(#[from] StdError),
+}
+
+impl Permit {
+    /// Checks chain, contract, expiry and signature of the permit and returns the address
+    /// of the signer, using the given bech32 prefix (e.g. "cosmos" or "juno").
+    ///
+    /// The nonce is not checked since this requires state. Use [`PermitParams::check_nonce`]
+    /// for that.
+    pub fn verify(
+        &self,
+        api: &dyn Api,
+        env: &Env,
+        bech32_prefix: &str,
+    ) -> Result<Addr, PermitError> {
+        let params = &self.params;
+        if params.chain_id != env.block.chain_id {
+            return Err(PermitError::WrongChain {
+                chain_id: params.chain_id.clone(),
+            });
+        }
+        if !params
+            .allowed_contracts
+            .iter()
+            .any(|contract| contract.as_str() == env.contract.address.as_str())
+        {
+            return Err(PermitError::ContractNotAllowed {
+                contract: env.contract.address.to_string(),
+            });
+        }
+        if let Some(expires) = params.expires {
+            if env.block.time >= expires {
+                return Err(PermitError::Expired { expires });
+            }
+        }
+
+        let pub_key = self.signature.pub_key.as_slice();
+        if pub_key.len() != 33 {
+            return Err(PermitError::InvalidPublicKey);
+        }
+        let signer = secp256k1_pubkey_to_address(bech32_prefix, pub_key)?;
+        if !verify_adr36(
+            api,
+            &signer,
+            &to_vec(params)?,
+            &self.signature.signature,
+            pub_key,
+        )? {
+            return Err(PermitError::InvalidSignature);
+        }
+        Ok(Addr::unchecked(signer))
+    }
+}
+
+impl PermitParams {
+    /// Checks that the permit's nonce is greater than the last nonce used by the signer,
+    /// which the contract must store after accepting the permit.
+    pub fn check_nonce(&self, last_used: Option<u64>) -> Result<(), PermitError> {
+        let nonce = self.nonce.ok_or(PermitError::NonceMissing)?;
+        match last_used {
+            Some(last_used) if nonce <= last_used => {
+                Err(PermitError::NonceAlreadyUsed { nonce, last_used })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_dependencies, mock_env};
+    use hex_literal::hex;
+
+    // Created with a fixed test key using the Python cryptography package
+    const PUBKEY: [u8; 33] =
+        hex!("02bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020d");
+    const SIGNER: &str = "cosmos15wgtkntdf26hqan77g0kdsldcxjddypxughytg";
+    const SIGNATURE: [u8; 64] = hex!("465a335372be21f1f21c42d3dcfd8a9f56975fcf985364cec006e90cc7476da864ed098783a0faa93f612ced5376ea28bf7d98cb02cbd736ff9199368061d619");
+
+    fn permit() -> Permit {
+        Permit {
+            params: PermitParams {
+                permit_name: "test".to_string(),
+                chain_id: "cosmos-testnet-14002".to_string(),
+                allowed_contracts: vec!["cosmos2contract".to_string()],
+                permissions: vec!["balance".to_string()],
+                expires: Some(Timestamp::from_seconds(1571797520)),
+                nonce: Some(7),
+            },
+            signature: PermitSignature {
+                pub_key: PUBKEY.into(),
+                signature: SIGNATURE.into(),
+            },
+        }
+    }
+
+    #[test]
+    fn verify_works() {
+        let deps = mock_dependencies();
+        let signer = permit().verify(&deps.api, &mock_env(), "cosmos").unwrap();
+        assert_eq!(signer, SIGNER);
+    }
+
+    #[test]
+    fn verify_fails_for_modified_params() {
+        let deps = mock_dependencies();
+        let mut permit = permit();
+        permit.params.permissions.push("owner".to_string());
+        let err = permit.verify(&deps.api, &mock_env(), "cosmos").unwrap_err();
+        assert!(matches!(err, PermitError::InvalidSignature));
+
+        // a different prefix results in a different signer in the sign doc
+        let err = self::permit()
+            .verify(&deps.api, &mock_env(), "juno")
+            .unwrap_err();
+        assert!(matches!(err, PermitError::InvalidSignature));
+    }
+
+    #[test]
+    fn verify_checks_chain_contract_and_expiry() {
+        let deps = mock_dependencies();
+
+        let mut env = mock_env();
+        env.block.chain_id = "other-chain".to_string();
+        let err = permit().verify(&deps.api, &env, "cosmos").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Permit is for chain cosmos-testnet-14002, not for this chain"
+        );
+
+        let mut env = mock_env();
+        env.contract.address = Addr::unchecked("other");
+        let err = permit().verify(&deps.api, &env, "cosmos").unwrap_err();
+        assert!(matches!(err, PermitError::ContractNotAllowed { contract } if contract == "other"));
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1571797520);
+        let err = permit().verify(&deps.api, &env, "cosmos").unwrap_err();
+        assert!(matches!(err, PermitError::Expired { .. }));
+
+        let mut permit = permit();
+        permit.signature.pub_key = Binary::from(&PUBKEY[1..]);
+        let err = permit.verify(&deps.api, &mock_env(), "cosmos").unwrap_err();
+        assert!(matches!(err, PermitError::InvalidPublicKey));
+    }
+
+    #[test]
+    fn check_nonce_works() {
+        let params = permit().params;
+        params.check_nonce(None).unwrap();
+        params.check_nonce(Some(6)).unwrap();
+        let err = params.check_nonce(Some(7)).unwrap_err();
+        assert!(matches!(
+            err,
+            PermitError::NonceAlreadyUsed {
+                nonce: 7,
+                last_used: 7
+            }
+        ));
+
+        let params = PermitParams {
+            nonce: None,
+            ..permit().params
+        };
+        let err = params.check_nonce(None).unwrap_err();
+        assert!(matches!(err, PermitError::NonceMissing));
+    }
+
+    #[test]
+    fn permit_serializes_like_signed_params() {
+        assert_eq!(
+            String::from_utf8(to_vec(&permit().params).unwrap()).unwrap(),
+            r#"{"permit_name":"test","chain_id":"cosmos-testnet-14002","allowed_contracts":["cosmos2contract"],"permissions":["balance"],"expires":"1571797520000000000","nonce":7}"#
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::results::{BankMsg, CosmosMsg, WasmMsg};
+use crate::serde::from_slice;
+use crate::{Coin, Uint128, Value};
+
+/// Describes which messages a proxy contract (e.g. a vault, sub-account or authz-like wrapper)
+/// may dispatch on behalf of its owner.
+///
+/// Only `WasmMsg::Execute` and `BankMsg::Send` can be allowed by a policy.
+/// All other messages are rejected. Each allowlist is optional, where `None` means
+/// no restriction and an empty list means nothing is allowed.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{coins, BankMsg, Binary, CosmosMsg, Policy, PolicyError, WasmMsg};
+///
+/// let policy = Policy {
+///     allowed_contracts: Some(vec!["dex".to_string()]),
+///     allowed_methods: Some(vec!["swap".to_string()]),
+///     allowed_recipients: Some(vec![]),
+///     fund_caps: Some(coins(1000, "ucosm")),
+/// };
+///
+/// let swap: CosmosMsg = WasmMsg::Execute {
+///     contract_addr: "dex".to_string(),
+///     msg: Binary::from(br#"{"swap":{"min_output":"1"}}"#),
+///     funds: coins(500, "ucosm"),
+/// }
+/// .into();
+/// assert_eq!(policy.check(&swap), Ok(()));
+///
+/// let send: CosmosMsg = BankMsg::Send {
+///     to_address: "thief".to_string(),
+///     amount: coins(1, "ucosm"),
+/// }
+/// .into();
+/// assert_eq!(
+///     policy.check(&send),
+///     Err(PolicyError::RecipientNotAllowed { recipient: "thief".to_string() })
+/// );
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct Policy {
+    /// Contracts that may be called with `WasmMsg::Execute`
+    pub allowed_contracts: Option<Vec<String>>,
+    /// The execute message variants that may be called, i.e. the single top-level key
+    /// of the execute message JSON object such as `transfer` for `{"transfer":{...}}`
+    pub allowed_methods: Option<Vec<String>>,
+    /// Recipients of `BankMsg::Send`
+    pub allowed_recipients: Option<Vec<String>>,
+    /// The maximum amount per denom that may be sent or attached as funds.
+    /// Denoms that are not listed cannot be spent at all.
+    pub fund_caps: Option<Vec<Coin>>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PolicyError {
+    #[error("Message type {kind} not allowed by policy")]
+    MessageNotAllowed { kind: String },
+    #[error("Contract {contract} not allowed by policy")]
+    ContractNotAllowed { contract: String },
+    #[error("Execute msg must be a JSON object with exactly one key")]
+    InvalidExecuteMsg,
+    #[error("Method {method} not allowed by policy")]
+    MethodNotAllowed { method: String },
+    #[error("Recipient {recipient} not allowed by policy")]
+    RecipientNotAllowed { recipient: String },
+    #[error("Denom {denom} not allowed by policy")]
+    DenomNotAllowed { denom: String },
+    #[error("Funds of {amount}{denom} exceed the cap of {cap}{denom}")]
+    FundsExceeded {
+        denom: String,
+        amount: Uint128,
+        cap: Uint128,
+    },
+}
+
+impl Policy {
+    /// A policy that allows all `WasmMsg::Execute` and `BankMsg::Send` messages
+    pub fn allow_all() -> Self {
+        Policy::default()
+    }
+
+    /// Checks a single message against the policy
+    pub fn check<T>(&self, msg: &CosmosMsg<T>) -> Result<(), PolicyError> {
+        self.check_all(std::slice::from_ref(msg))
+    }
+
+    /// Checks a list of messages against the policy.
+    ///
+    /// The fund caps apply to the sum of all funds spent by the messages,
+    /// such that splitting a transfer into multiple messages cannot be used to exceed them.
+    pub fn check_all<T>(&self, msgs: &[CosmosMsg<T>]) -> Result<(), PolicyError> {
+        let mut spent: BTreeMap<&str, Uint128> = BTreeMap::new();
+        for msg in msgs {
+            let funds = match msg {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg,
+                    funds,
+                }) => {
+                    if !is_allowed(&self.allowed_contracts, contract_addr) {
+                        return Err(PolicyError::ContractNotAllowed {
+                            contract: contract_addr.clone(),
+                        });
+                    }
+                    if self.allowed_methods.is_some() {
+                        let method = execute_method(msg.as_slice())?;
+                        if !is_allowed(&self.allowed_methods, &method) {
+                            return Err(PolicyError::MethodNotAllowed { method });
+                        }
+                    }
+                    funds
+                }
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    if !is_allowed(&self.allowed_recipients, to_address) {
+                        return Err(PolicyError::RecipientNotAllowed {
+                            recipient: to_address.clone(),
+                        });
+                    }
+                    amount
+                }
+                other => {
+                    return Err(PolicyError::MessageNotAllowed {
+                        kind: msg_kind(other).to_string(),
+                    })
+                }
+            };
+
+            for coin in funds {
+                let total = spent.entry(&coin.denom).or_default();
+                // An overflow can only happen if the amount exceeds any cap
+                *total = total.checked_add(coin.amount).unwrap_or(Uint128::MAX);
+                self.check_cap(&coin.denom, *total)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_cap(&self, denom: &str, amount: Uint128) -> Result<(), PolicyError> {
+        let caps = match &self.fund_caps {
+            Some(caps) => caps,
+            None => return Ok(()),
+        };
+        let cap = caps.iter().find(|cap| cap.denom == denom).ok_or_else(|| {
+            PolicyError::DenomNotAllowed {
+                denom: denom.to_string(),
+            }
+        })?;
+        if amount > cap.amount {
+            return Err(PolicyError::FundsExceeded {
+                denom: denom.to_string(),
+                amount,
+                cap: cap.amount,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn is_allowed(allowlist: &Option<Vec<String>>, value: &str) -> bool {
+    match allowlist {
+        Some(list) => list.iter().any(|allowed| allowed == value),
+        None => true,
+    }
+}
+
+/// Extracts the variant name of an execute message serialized from an externally tagged enum
+fn execute_method(msg: &[u8]) -> Result<String, PolicyError> {
+    let value: Value = from_slice(msg).map_err(|_| PolicyError::InvalidExecuteMsg)?;
+    match value {
+        Value::Object(map) if map.len() == 1 => Ok(map.into_keys().next().unwrap()),
+        _ => Err(PolicyError::InvalidExecuteMsg),
+    }
+}
+
+fn msg_kind<T>(msg: &CosmosMsg<T>) -> &'static str {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { .. }) => "bank_send",
+        CosmosMsg::Bank(BankMsg::Burn { .. }) => "bank_burn",
+        #[cfg(feature = "cosmwasm_1_2")]
+        CosmosMsg::Bank(BankMsg::MultiSend { .. }) => "bank_multi_send",
+        CosmosMsg::Custom(_) => "custom",
+        #[cfg(feature = "staking")]
+        CosmosMsg::Staking(_) => "staking",
+        #[cfg(feature = "staking")]
+        CosmosMsg::Distribution(_) => "distribution",
+        #[cfg(feature = "stargate")]
+        CosmosMsg::Stargate { .. } => "stargate",
+        #[cfg(feature = "stargate")]
+        CosmosMsg::Ibc(_) => "ibc",
+        CosmosMsg::Wasm(WasmMsg::Execute { .. }) => "wasm_execute",
+        CosmosMsg::Wasm(WasmMsg::Instantiate { .. }) => "wasm_instantiate",
+        CosmosMsg::Wasm(WasmMsg::Migrate { .. }) => "wasm_migrate",
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin { .. }) => "wasm_update_admin",
+        CosmosMsg::Wasm(WasmMsg::ClearAdmin { .. }) => "wasm_clear_admin",
+        #[cfg(feature = "cosmwasm_1_2")]
+        CosmosMsg::Wasm(WasmMsg::Retire { .. }) => "wasm_retire",
+        #[cfg(feature = "stargate")]
+        CosmosMsg::Gov(_) => "gov",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coin, coins, to_binary, Binary, Empty};
+
+    fn execute(contract: &str, msg: &str, funds: Vec<Coin>) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: Binary::from(msg.as_bytes()),
+            funds,
+        })
+    }
+
+    fn send(to: &str, amount: Vec<Coin>) -> CosmosMsg {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount,
+        })
+    }
+
+    fn strict_policy() -> Policy {
+        Policy {
+            allowed_contracts: Some(vec!["dex".to_string(), "staking".to_string()]),
+            allowed_methods: Some(vec!["swap".to_string(), "bond".to_string()]),
+            allowed_recipients: Some(vec!["owner".to_string()]),
+            fund_caps: Some(vec![coin(1000, "ucosm"), coin(10, "uatom")]),
+        }
+    }
+
+    #[test]
+    fn allow_all_works() {
+        let policy = Policy::allow_all();
+        policy
+            .check(&execute("any", r#"{"anything":{}}"#, coins(u128::MAX, "x")))
+            .unwrap();
+        // method is not parsed when not restricted
+        policy.check(&execute("any", "not json", vec![])).unwrap();
+        policy.check(&send("anyone", coins(5, "y"))).unwrap();
+
+        let burn: CosmosMsg = BankMsg::Burn {
+            amount: coins(1, "x"),
+        }
+        .into();
+        assert_eq!(
+            policy.check(&burn),
+            Err(PolicyError::MessageNotAllowed {
+                kind: "bank_burn".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn check_restricts_message_types() {
+        let policy = strict_policy();
+        let msgs: Vec<(CosmosMsg, &str)> = vec![
+            (
+                WasmMsg::Instantiate {
+                    admin: None,
+                    code_id: 1,
+                    msg: to_binary(&Empty {}).unwrap(),
+                    funds: vec![],
+                    label: "x".to_string(),
+                }
+                .into(),
+                "wasm_instantiate",
+            ),
+            (
+                WasmMsg::Migrate {
+                    contract_addr: "dex".to_string(),
+                    new_code_id: 2,
+                    msg: to_binary(&Empty {}).unwrap(),
+                }
+                .into(),
+                "wasm_migrate",
+            ),
+            (
+                WasmMsg::ClearAdmin {
+                    contract_addr: "dex".to_string(),
+                }
+                .into(),
+                "wasm_clear_admin",
+            ),
+            #[cfg(feature = "cosmwasm_1_2")]
+            (WasmMsg::Retire { prune_state: false }.into(), "wasm_retire"),
+            (CosmosMsg::Custom(Empty {}), "custom"),
+        ];
+        for (msg, kind) in msgs {
+            assert_eq!(
+                policy.check(&msg),
+                Err(PolicyError::MessageNotAllowed {
+                    kind: kind.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn check_restricts_contracts_and_methods() {
+        let policy = strict_policy();
+        policy
+            .check(&execute("dex", r#"{"swap":{"min":"1"}}"#, vec![]))
+            .unwrap();
+        policy
+            .check(&execute("staking", r#"{"bond":{}}"#, vec![]))
+            .unwrap();
+        // unit variants serialize to a string and are not supported
+        assert_eq!(
+            policy.check(&execute("dex", r#""swap""#, vec![])),
+            Err(PolicyError::InvalidExecuteMsg)
+        );
+
+        assert_eq!(
+            policy.check(&execute("evil", r#"{"swap":{}}"#, vec![])),
+            Err(PolicyError::ContractNotAllowed {
+                contract: "evil".to_string()
+            })
+        );
+        assert_eq!(
+            policy.check(&execute("dex", r#"{"withdraw_all":{}}"#, vec![])),
+            Err(PolicyError::MethodNotAllowed {
+                method: "withdraw_all".to_string()
+            })
+        );
+        for invalid in ["", "{}", "[]", "{\"swap\":{},\"bond\":{}}", "{\"swap\""] {
+            assert_eq!(
+                policy.check(&execute("dex", invalid, vec![])),
+                Err(PolicyError::InvalidExecuteMsg),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn check_restricts_recipients() {
+        let policy = strict_policy();
+        policy.check(&send("owner", coins(5, "ucosm"))).unwrap();
+        assert_eq!(
+            policy.check(&send("other", coins(5, "ucosm"))),
+            Err(PolicyError::RecipientNotAllowed {
+                recipient: "other".to_string()
+            })
+        );
+
+        let no_sends = Policy {
+            allowed_recipients: Some(vec![]),
+            ..Policy::default()
+        };
+        assert_eq!(
+            no_sends.check(&send("owner", vec![])),
+            Err(PolicyError::RecipientNotAllowed {
+                recipient: "owner".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn check_enforces_fund_caps() {
+        let policy = strict_policy();
+        policy.check(&send("owner", coins(1000, "ucosm"))).unwrap();
+        policy
+            .check(&execute(
+                "dex",
+                r#"{"swap":{}}"#,
+                vec![coin(1000, "ucosm"), coin(10, "uatom")],
+            ))
+            .unwrap();
+
+        assert_eq!(
+            policy.check(&send("owner", coins(1001, "ucosm"))),
+            Err(PolicyError::FundsExceeded {
+                denom: "ucosm".to_string(),
+                amount: Uint128::new(1001),
+                cap: Uint128::new(1000),
+            })
+        );
+        assert_eq!(
+            policy.check(&execute("dex", r#"{"swap":{}}"#, coins(1, "uosmo"))),
+            Err(PolicyError::DenomNotAllowed {
+                denom: "uosmo".to_string()
+            })
+        );
+        // duplicate denoms in one message are summed up
+        assert_eq!(
+            policy.check(&send("owner", vec![coin(6, "uatom"), coin(5, "uatom")])),
+            Err(PolicyError::FundsExceeded {
+                denom: "uatom".to_string(),
+                amount: Uint128::new(11),
+                cap: Uint128::new(10),
+            })
+        );
+        // overflow
+        assert_eq!(
+            policy.check(&send(
+                "owner",
+                vec![coin(u128::MAX, "ucosm"), coin(u128::MAX, "ucosm")]
+            )),
+            Err(PolicyError::FundsExceeded {
+                denom: "ucosm".to_string(),
+                amount: Uint128::MAX,
+                cap: Uint128::new(1000),
+            })
+        );
+    }
+
+    #[test]
+    fn check_all_sums_funds_across_messages() {
+        let policy = strict_policy();
+        let msgs = vec![
+            send("owner", coins(600, "ucosm")),
+            execute("dex", r#"{"swap":{}}"#, coins(400, "ucosm")),
+        ];
+        policy.check_all(&msgs).unwrap();
+
+        let msgs = vec![
+            send("owner", coins(600, "ucosm")),
+            execute("dex", r#"{"swap":{}}"#, coins(401, "ucosm")),
+        ];
+        assert_eq!(
+            policy.check_all(&msgs),
+            Err(PolicyError::FundsExceeded {
+                denom: "ucosm".to_string(),
+                amount: Uint128::new(1001),
+                cap: Uint128::new(1000),
+            })
+        );
+
+        policy.check_all::<Empty>(&[]).unwrap();
+    }
+
+    #[test]
+    fn policy_serializes() {
+        let policy = Policy {
+            allowed_contracts: Some(vec!["dex".to_string()]),
+            allowed_methods: None,
+            allowed_recipients: Some(vec![]),
+            fund_caps: Some(coins(5, "ucosm")),
+        };
+        let json = crate::to_vec(&policy).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"allowed_contracts":["dex"],"allowed_methods":null,"allowed_recipients":[],"fund_caps":[{"denom":"ucosm","amount":"5"}]}"#
+        );
+        assert_eq!(from_slice::<Policy>(&json).unwrap(), policy);
+    }
+}
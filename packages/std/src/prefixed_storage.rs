@@ -0,0 +1,283 @@
+use std::marker::PhantomData;
+
+#[cfg(feature = "iterator")]
+use crate::iterator::{Order, Record};
+#[cfg(feature = "iterator")]
+use crate::split_deps::namespace_upper_bound;
+use crate::traits::Storage;
+
+/// A storage namespace known at compile time, used as the type parameter of
+/// [`PrefixedStorage`] and [`ReadonlyPrefixedStorage`].
+///
+/// # Examples
+///
+/// ```
+/// use cosmwasm_std::{assert_unique_namespaces, Namespace};
+///
+/// pub struct Balances;
+///
+/// impl Namespace for Balances {
+///     const NAMESPACE: &'static [u8] = b"balances";
+/// }
+///
+/// pub struct Allowances;
+///
+/// impl Namespace for Allowances {
+///     const NAMESPACE: &'static [u8] = b"allowances";
+/// }
+///
+/// // Fails to compile if two namespaces are equal
+/// const _: () = assert_unique_namespaces(&[Balances::NAMESPACE, Allowances::NAMESPACE]);
+/// ```
+pub trait Namespace {
+    const NAMESPACE: &'static [u8];
+}
+
+/// Panics if any two of the given namespaces are equal. Since namespaces
+/// are length-prefixed, distinct namespaces never share any keys.
+///
+/// This is a `const fn`, so it can be used to check the namespaces of a contract at
+/// compile time, see [`Namespace`].
+pub const fn assert_unique_namespaces(namespaces: &[&[u8]]) {
+    let mut i = 0;
+    while i < namespaces.len() {
+        let mut j = i + 1;
+        while j < namespaces.len() {
+            if bytes_equal(namespaces[i], namespaces[j]) {
+                panic!("Storage namespaces must be unique");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+const fn bytes_equal(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A [`Storage`] that stores all keys under the namespace `N`.
+///
+/// Keys are prefixed with the length-prefixed namespace as described in
+/// <https://github.com/webmaster128/key-namespacing#length-prefixed-keys>,
+/// which makes this compatible with `cosmwasm_storage::PrefixedStorage`.
+pub struct PrefixedStorage<'a, N: Namespace> {
+    storage: &'a mut dyn Storage,
+    prefix: Vec<u8>,
+    namespace: PhantomData<N>,
+}
+
+impl<'a, N: Namespace> PrefixedStorage<'a, N> {
+    pub fn new(storage: &'a mut dyn Storage) -> Self {
+        PrefixedStorage {
+            storage,
+            prefix: length_prefixed(N::NAMESPACE),
+            namespace: PhantomData,
+        }
+    }
+}
+
+impl<'a, N: Namespace> Storage for PrefixedStorage<'a, N> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get(&concat(&self.prefix, key))
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        range_with_prefix(self.storage, &self.prefix, start, end, order)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.storage.set(&concat(&self.prefix, key), value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.storage.remove(&concat(&self.prefix, key));
+    }
+}
+
+/// A read-only [`Storage`] of all keys under the namespace `N`. See [`PrefixedStorage`].
+///
+/// Calling `set` or `remove` panics.
+pub struct ReadonlyPrefixedStorage<'a, N: Namespace> {
+    storage: &'a dyn Storage,
+    prefix: Vec<u8>,
+    namespace: PhantomData<N>,
+}
+
+impl<'a, N: Namespace> ReadonlyPrefixedStorage<'a, N> {
+    pub fn new(storage: &'a dyn Storage) -> Self {
+        ReadonlyPrefixedStorage {
+            storage,
+            prefix: length_prefixed(N::NAMESPACE),
+            namespace: PhantomData,
+        }
+    }
+}
+
+impl<'a, N: Namespace> Storage for ReadonlyPrefixedStorage<'a, N> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get(&concat(&self.prefix, key))
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        range_with_prefix(self.storage, &self.prefix, start, end, order)
+    }
+
+    fn set(&mut self, _key: &[u8], _value: &[u8]) {
+        panic!("Cannot write to a ReadonlyPrefixedStorage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        panic!("Cannot write to a ReadonlyPrefixedStorage");
+    }
+}
+
+/// Prefixes the namespace with its length as a 2 byte big endian integer
+pub(crate) fn length_prefixed(namespace: &[u8]) -> Vec<u8> {
+    let length = u16::try_from(namespace.len()).expect("Namespace must not exceed 0xFFFF bytes");
+    let mut out = Vec::with_capacity(namespace.len() + 2);
+    out.extend_from_slice(&length.to_be_bytes());
+    out.extend_from_slice(namespace);
+    out
+}
+
+pub(crate) fn concat(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(prefix.len() + key.len());
+    out.extend_from_slice(prefix);
+    out.extend_from_slice(key);
+    out
+}
+
+#[cfg(feature = "iterator")]
+pub(crate) fn range_with_prefix<'a>(
+    storage: &'a dyn Storage,
+    prefix: &[u8],
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Box<dyn Iterator<Item = Record> + 'a> {
+    let start = concat(prefix, start.unwrap_or_default());
+    let end = match end {
+        Some(end) => Some(concat(prefix, end)),
+        None => namespace_upper_bound(prefix),
+    };
+    let prefix_len = prefix.len();
+    Box::new(
+        storage
+            .range(Some(&start), end.as_deref(), order)
+            .map(move |(key, value)| (key[prefix_len..].to_vec(), value)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    struct Foo;
+
+    impl Namespace for Foo {
+        const NAMESPACE: &'static [u8] = b"foo";
+    }
+
+    struct Fo;
+
+    impl Namespace for Fo {
+        const NAMESPACE: &'static [u8] = b"fo";
+    }
+
+    const _: () = assert_unique_namespaces(&[Foo::NAMESPACE, Fo::NAMESPACE]);
+
+    #[test]
+    fn prefixed_storage_works() {
+        let mut storage = MemoryStorage::new();
+
+        let mut foo = PrefixedStorage::<Foo>::new(&mut storage);
+        foo.set(b"bar", b"gotcha");
+        assert_eq!(foo.get(b"bar"), Some(b"gotcha".to_vec()));
+        assert_eq!(foo.get(b"elsewhere"), None);
+
+        // same layout as cosmwasm-storage
+        assert_eq!(storage.get(b"\x00\x03foobar"), Some(b"gotcha".to_vec()));
+
+        // namespaces sharing a prefix do not collide
+        let mut fo = PrefixedStorage::<Fo>::new(&mut storage);
+        assert_eq!(fo.get(b"obar"), None);
+        fo.set(b"obar", b"other");
+        let foo = ReadonlyPrefixedStorage::<Foo>::new(&storage);
+        assert_eq!(foo.get(b"bar"), Some(b"gotcha".to_vec()));
+
+        let mut foo = PrefixedStorage::<Foo>::new(&mut storage);
+        foo.remove(b"bar");
+        assert_eq!(foo.get(b"bar"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot write to a ReadonlyPrefixedStorage")]
+    fn readonly_prefixed_storage_panics_on_write() {
+        let storage = MemoryStorage::new();
+        let mut foo = ReadonlyPrefixedStorage::<Foo>::new(&storage);
+        foo.set(b"bar", b"gotcha");
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage namespaces must be unique")]
+    fn assert_unique_namespaces_works() {
+        assert_unique_namespaces(&[b"a", b"b", b"ab"]);
+        assert_unique_namespaces(&[]);
+        assert_unique_namespaces(&[b"a", b"b", b"a"]);
+    }
+
+    #[cfg(feature = "iterator")]
+    #[test]
+    fn range_works() {
+        let mut storage = MemoryStorage::new();
+        storage.set(b"\x00\x02fo\xff", b"other namespace");
+        let mut foo = PrefixedStorage::<Foo>::new(&mut storage);
+        foo.set(b"a", b"1");
+        foo.set(b"b", b"2");
+        foo.set(b"c", b"3");
+
+        let all: Vec<Record> = foo.range(None, None, Order::Ascending).collect();
+        assert_eq!(
+            all,
+            vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"c".to_vec(), b"3".to_vec()),
+            ]
+        );
+
+        let foo = ReadonlyPrefixedStorage::<Foo>::new(&storage);
+        let some: Vec<Record> = foo.range(Some(b"b"), None, Order::Descending).collect();
+        assert_eq!(
+            some,
+            vec![
+                (b"c".to_vec(), b"3".to_vec()),
+                (b"b".to_vec(), b"2".to_vec())
+            ]
+        );
+    }
+}
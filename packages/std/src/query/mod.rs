@@ -21,6 +21,8 @@ pub use staking::{
     DelegationResponse, FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
 pub use wasm::{ContractInfoResponse, WasmQuery};
+#[cfg(feature = "cosmwasm_1_2")]
+pub use wasm::{HostInfoResponse, StorageUsageResponse};
 
 #[non_exhaustive]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -24,6 +24,18 @@ pub enum WasmQuery {
     },
     /// returns a ContractInfoResponse with metadata on the contract from the runtime
     ContractInfo { contract_addr: String },
+    /// Returns a [`StorageUsageResponse`] with the current size of the contract's storage.
+    ///
+    /// This allows contracts implementing storage rent or cleanup incentives to reason
+    /// about their own footprint without iterating over all of their state.
+    #[cfg(feature = "cosmwasm_1_2")]
+    StorageUsage { contract_addr: String },
+    /// Returns a [`HostInfoResponse`] with the CosmWasm version and capabilities of the host.
+    ///
+    /// This allows a single contract binary to adapt its behaviour to the chain it runs on,
+    /// e.g. to use a chain specific module only if the corresponding capability is present.
+    #[cfg(feature = "cosmwasm_1_2")]
+    HostInfo {},
 }
 
 #[non_exhaustive]
@@ -53,3 +65,55 @@ impl ContractInfoResponse {
         }
     }
 }
+
+/// The storage footprint of a contract as tracked by the chain
+#[cfg(feature = "cosmwasm_1_2")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StorageUsageResponse {
+    /// The number of keys in the contract's storage
+    pub key_count: u64,
+    /// The sum of the lengths of all keys and values in bytes
+    pub total_bytes: u64,
+}
+
+#[cfg(feature = "cosmwasm_1_2")]
+impl StorageUsageResponse {
+    /// Convenience constructor for tests / mocks
+    #[doc(hidden)]
+    pub fn new(key_count: u64, total_bytes: u64) -> Self {
+        Self {
+            key_count,
+            total_bytes,
+        }
+    }
+}
+
+/// The version and capabilities of the CosmWasm host the contract is running on
+#[cfg(feature = "cosmwasm_1_2")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct HostInfoResponse {
+    /// The version of cosmwasm-vm used by the host, e.g. "1.1.0"
+    pub cosmwasm_version: String,
+    /// The capabilities the host supports, such as "iterator", "staking" or
+    /// chain specific ones like "token_factory"
+    pub capabilities: Vec<String>,
+}
+
+#[cfg(feature = "cosmwasm_1_2")]
+impl HostInfoResponse {
+    /// Convenience constructor for tests / mocks
+    #[doc(hidden)]
+    pub fn new(cosmwasm_version: impl Into<String>, capabilities: Vec<String>) -> Self {
+        Self {
+            cosmwasm_version: cosmwasm_version.into(),
+            capabilities,
+        }
+    }
+
+    /// Returns true if the host supports the given capability
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+}
@@ -0,0 +1,410 @@
+use sha2::{Digest, Sha256};
+
+use crate::types::Env;
+
+/// A deterministic pseudo random number generator implementing the
+/// [xoshiro256++](https://prng.di.unimi.it/) algorithm.
+///
+/// **This generator is not cryptographically secure.** Everything that goes into the seed
+/// ([`Xoshiro256PlusPlus::from_env`]) is public, so anybody can predict the numbers generated
+/// in a given block. Block proposers can even influence some of the inputs (e.g. the block time
+/// or transaction order). Use it for things like shuffling or sampling where the outcome is not
+/// worth manipulating, or combine it with an unpredictable seed from a commit/reveal scheme or
+/// a randomness beacon.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::testing::mock_env;
+/// use cosmwasm_std::Xoshiro256PlusPlus;
+///
+/// let env = mock_env();
+/// let mut rng = Xoshiro256PlusPlus::from_env(&env, b"round 1");
+/// let dice = rng.next_u64() % 6 + 1;
+/// assert!((1..=6).contains(&dice));
+///
+/// // The same inputs always produce the same sequence
+/// let mut rng2 = Xoshiro256PlusPlus::from_env(&env, b"round 1");
+/// assert_eq!(rng2.next_u64() % 6 + 1, dice);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Xoshiro256PlusPlus {
+    s: [u64; 4],
+}
+
+/// Separates seeds created by [`Xoshiro256PlusPlus::from_env`] from other uses of the same hash.
+const ENV_SEED_DOMAIN: &[u8] = b"cosmwasm_std::Xoshiro256PlusPlus";
+
+impl Xoshiro256PlusPlus {
+    /// Creates a generator from a 32 byte seed.
+    ///
+    /// The seed is interpreted as four little endian u64 values. Since the all-zero state
+    /// is invalid for xoshiro, an all-zero seed is replaced by a fixed non-zero state.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        let mut s = [0u64; 4];
+        for (i, chunk) in seed.chunks_exact(8).enumerate() {
+            s[i] = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        if s == [0; 4] {
+            return Self::seed_from_u64(0);
+        }
+        Xoshiro256PlusPlus { s }
+    }
+
+    /// Creates a generator from a u64 by expanding it with SplitMix64,
+    /// as recommended by the authors of xoshiro.
+    pub fn seed_from_u64(mut state: u64) -> Self {
+        let mut s = [0u64; 4];
+        for value in s.iter_mut() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *value = z ^ (z >> 31);
+        }
+        Xoshiro256PlusPlus { s }
+    }
+
+    /// Creates a generator seeded from the block and contract information of the environment
+    /// as well as a caller provided nonce.
+    ///
+    /// The seed is the SHA-256 hash over chain ID, block height, block time, transaction index
+    /// and contract address along with the nonce. Within one execution, different nonces must be
+    /// used to get independent sequences. Across blocks and contracts the sequences differ
+    /// automatically.
+    ///
+    /// See the type level documentation for why this must not be used where security matters.
+    pub fn from_env(env: &Env, nonce: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(ENV_SEED_DOMAIN);
+        update_length_prefixed(&mut hasher, env.block.chain_id.as_bytes());
+        hasher.update(env.block.height.to_be_bytes());
+        hasher.update(env.block.time.nanos().to_be_bytes());
+        match &env.transaction {
+            Some(transaction) => {
+                hasher.update([1]);
+                hasher.update(transaction.index.to_be_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        update_length_prefixed(&mut hasher, env.contract.address.as_bytes());
+        update_length_prefixed(&mut hasher, nonce);
+        Self::from_seed(hasher.finalize().into())
+    }
+
+    /// Returns the next random u64
+    pub fn next_u64(&mut self) -> u64 {
+        let result = (self.s[0].wrapping_add(self.s[3]))
+            .rotate_left(23)
+            .wrapping_add(self.s[0]);
+
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    /// Returns the next random u32. This uses the upper bits of [`Xoshiro256PlusPlus::next_u64`].
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns the next random u128, built from two consecutive u64 values
+    pub fn next_u128(&mut self) -> u128 {
+        let high = self.next_u64() as u128;
+        let low = self.next_u64() as u128;
+        (high << 64) | low
+    }
+
+    /// Returns a uniformly distributed random number in the range `0..bound`.
+    ///
+    /// This uses rejection sampling and has no modulo bias at all. A rejection happens with a
+    /// probability of less than 50%, so on average less than two u128 values are consumed.
+    ///
+    /// Panics if `bound` is zero.
+    pub fn next_below(&mut self, bound: u128) -> u128 {
+        assert!(bound > 0, "bound must not be zero");
+        // 2^128 mod bound. Values below this threshold are rejected such that the number
+        // of accepted values is a multiple of `bound`.
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u128();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Fills the destination with random bytes
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// Selects an item from a list of items with weights, such that every item is chosen with
+/// probability `weight / total_weight`.
+///
+/// The selection uses pure integer arithmetic and [`Xoshiro256PlusPlus::next_below`],
+/// so the selection probabilities are exact (no modulo bias) apart from the quality of the
+/// underlying generator. Items with a weight of zero are never selected.
+///
+/// Returns `None` if the list is empty or all weights are zero.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::testing::mock_env;
+/// use cosmwasm_std::{select_weighted, Xoshiro256PlusPlus};
+///
+/// let validators = [("alice", 70u64), ("bob", 20), ("carol", 10)];
+/// let mut rng = Xoshiro256PlusPlus::from_env(&mock_env(), b"");
+/// let proposer = select_weighted(&mut rng, &validators).unwrap();
+/// assert!(["alice", "bob", "carol"].contains(proposer));
+/// ```
+pub fn select_weighted<'a, T>(
+    rng: &mut Xoshiro256PlusPlus,
+    items: &'a [(T, u64)],
+) -> Option<&'a T> {
+    // Cannot overflow since we'd need 2^64 items with weight u64::MAX for that
+    let total: u128 = items.iter().map(|(_, weight)| *weight as u128).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut target = rng.next_below(total);
+    for (item, weight) in items {
+        let weight = *weight as u128;
+        if target < weight {
+            return Some(item);
+        }
+        target -= weight;
+    }
+    unreachable!("target is always smaller than the total weight")
+}
+
+fn update_length_prefixed(hasher: &mut Sha256, data: &[u8]) {
+    // Cast is safe because usize is 32 or 64 bit large in all environments we support
+    hasher.update((data.len() as u64).to_be_bytes());
+    hasher.update(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_env;
+    use crate::Addr;
+
+    fn seed(values: [u64; 4]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, value) in values.iter().enumerate() {
+            out[i * 8..(i + 1) * 8].copy_from_slice(&value.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn next_u64_matches_reference_implementation() {
+        // Test vector from https://github.com/rust-random/rngs/blob/master/rand_xoshiro/src/xoshiro256plusplus.rs
+        let mut rng = Xoshiro256PlusPlus::from_seed(seed([1, 2, 3, 4]));
+        let expected = [
+            41943041,
+            58720359,
+            3588806011781223,
+            3591011842654386,
+            9228616714210784205,
+            9973669472204895162,
+            14011001112246962877,
+            12406186145184390807,
+            15849039046786891736,
+            10450023813501588000,
+        ];
+        for value in expected {
+            assert_eq!(rng.next_u64(), value);
+        }
+    }
+
+    #[test]
+    fn from_seed_handles_zero_seed() {
+        let mut rng = Xoshiro256PlusPlus::from_seed([0; 32]);
+        assert_eq!(rng, Xoshiro256PlusPlus::seed_from_u64(0));
+        assert_ne!(rng.next_u64(), 0);
+    }
+
+    #[test]
+    fn seed_from_u64_matches_splitmix64() {
+        // Values from the SplitMix64 reference implementation for state 0
+        let rng = Xoshiro256PlusPlus::seed_from_u64(0);
+        assert_eq!(
+            rng.s,
+            [
+                0xe220a8397b1dcdaf,
+                0x6e789e6aa1b965f4,
+                0x06c45d188009454f,
+                0xf88bb8a8724c81ec
+            ]
+        );
+    }
+
+    #[test]
+    fn next_u32_works() {
+        let mut rng1 = Xoshiro256PlusPlus::seed_from_u64(42);
+        let mut rng2 = rng1.clone();
+        assert_eq!(rng1.next_u32() as u64, rng2.next_u64() >> 32);
+    }
+
+    #[test]
+    fn next_u128_works() {
+        let mut rng1 = Xoshiro256PlusPlus::seed_from_u64(42);
+        let mut rng2 = rng1.clone();
+        let high = rng2.next_u64() as u128;
+        let low = rng2.next_u64() as u128;
+        assert_eq!(rng1.next_u128(), (high << 64) + low);
+    }
+
+    #[test]
+    fn next_below_works() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(42);
+        for bound in [
+            1,
+            2,
+            3,
+            7,
+            100,
+            u64::MAX as u128,
+            u128::MAX / 2 + 1,
+            u128::MAX,
+        ] {
+            for _ in 0..100 {
+                assert!(rng.next_below(bound) < bound);
+            }
+        }
+        assert_eq!(rng.next_below(1), 0);
+    }
+
+    #[test]
+    fn next_below_rejects_values_below_threshold() {
+        // For bound = 2^127 + 1, the threshold is 2^127 - 1, i.e. about half of all values
+        // are rejected.
+        let bound = (1u128 << 127) + 1;
+        let threshold = bound.wrapping_neg() % bound;
+        assert_eq!(threshold, (1u128 << 127) - 1);
+
+        // Advance the generator until the next value will be rejected and the one after accepted
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(1);
+        let expected = loop {
+            let mut probe = rng.clone();
+            let first = probe.next_u128();
+            let second = probe.next_u128();
+            if first < threshold && second >= threshold {
+                break second % bound;
+            }
+            rng.next_u128();
+        };
+        assert_eq!(rng.next_below(bound), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "bound must not be zero")]
+    fn next_below_panics_for_zero() {
+        Xoshiro256PlusPlus::seed_from_u64(42).next_below(0);
+    }
+
+    #[test]
+    fn select_weighted_works() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(42);
+
+        let items = [("a", 1u64)];
+        assert_eq!(select_weighted(&mut rng, &items), Some(&"a"));
+
+        let items = [("a", 0u64), ("b", 5), ("c", 0)];
+        for _ in 0..100 {
+            assert_eq!(select_weighted(&mut rng, &items), Some(&"b"));
+        }
+
+        let items = [("a", u64::MAX), ("b", u64::MAX)];
+        assert!(select_weighted(&mut rng, &items).is_some());
+    }
+
+    #[test]
+    fn select_weighted_returns_none_for_empty_or_zero_weights() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(42);
+        let empty: [(u8, u64); 0] = [];
+        assert_eq!(select_weighted(&mut rng, &empty), None);
+        assert_eq!(select_weighted(&mut rng, &[(1, 0), (2, 0)]), None);
+    }
+
+    #[test]
+    fn select_weighted_distribution_matches_weights() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(7);
+        let items = [(0usize, 1u64), (1, 2), (2, 7)];
+        let mut counts = [0u32; 3];
+        let rounds = 100_000;
+        for _ in 0..rounds {
+            counts[*select_weighted(&mut rng, &items).unwrap()] += 1;
+        }
+        // Each count should be within 2% of the expected value
+        for (i, expected) in [10_000u32, 20_000, 70_000].into_iter().enumerate() {
+            let diff = (counts[i] as i64 - expected as i64).unsigned_abs();
+            assert!(diff < rounds / 50, "count {} is {}", i, counts[i]);
+        }
+    }
+
+    #[test]
+    fn fill_bytes_works() {
+        let mut rng1 = Xoshiro256PlusPlus::seed_from_u64(42);
+        let mut rng2 = rng1.clone();
+
+        let mut bytes = [0u8; 12];
+        rng1.fill_bytes(&mut bytes);
+        assert_eq!(bytes[0..8], rng2.next_u64().to_le_bytes());
+        assert_eq!(bytes[8..12], rng2.next_u64().to_le_bytes()[0..4]);
+
+        let mut empty = [0u8; 0];
+        rng1.fill_bytes(&mut empty);
+    }
+
+    #[test]
+    fn from_env_is_deterministic() {
+        let env = mock_env();
+        let mut rng1 = Xoshiro256PlusPlus::from_env(&env, b"nonce");
+        let mut rng2 = Xoshiro256PlusPlus::from_env(&env, b"nonce");
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+    }
+
+    #[test]
+    fn from_env_depends_on_all_inputs() {
+        let env = mock_env();
+        let original = Xoshiro256PlusPlus::from_env(&env, b"nonce");
+
+        assert_ne!(Xoshiro256PlusPlus::from_env(&env, b"nonce2"), original);
+        assert_ne!(Xoshiro256PlusPlus::from_env(&env, b""), original);
+
+        let mut changed = env.clone();
+        changed.block.height += 1;
+        assert_ne!(Xoshiro256PlusPlus::from_env(&changed, b"nonce"), original);
+
+        let mut changed = env.clone();
+        changed.block.time = changed.block.time.plus_nanos(1);
+        assert_ne!(Xoshiro256PlusPlus::from_env(&changed, b"nonce"), original);
+
+        let mut changed = env.clone();
+        changed.block.chain_id = "other-chain".to_string();
+        assert_ne!(Xoshiro256PlusPlus::from_env(&changed, b"nonce"), original);
+
+        let mut changed = env.clone();
+        changed.transaction = None;
+        assert_ne!(Xoshiro256PlusPlus::from_env(&changed, b"nonce"), original);
+
+        let mut changed = env.clone();
+        changed.contract.address = Addr::unchecked("other");
+        assert_ne!(Xoshiro256PlusPlus::from_env(&changed, b"nonce"), original);
+    }
+}
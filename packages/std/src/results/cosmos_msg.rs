@@ -64,6 +64,86 @@ pub enum BankMsg {
     /// There is no Cosmos SDK message that performs this, but it can be done by calling the bank keeper.
     /// Important if a contract controls significant token supply that must be retired.
     Burn { amount: Vec<Coin> },
+    /// Sends native tokens from the contract to multiple addresses in a single message.
+    /// This is much cheaper than one `Send` message per recipient for airdrops or payouts.
+    ///
+    /// This is translated to a [MsgMultiSend](https://github.com/cosmos/cosmos-sdk/blob/v0.45.0/proto/cosmos/bank/v1beta1/tx.proto#L31-L37)
+    /// with the current contract as the only input, which sends the sum of all outputs.
+    /// Use [`BankMsg::multi_send`] to create a validated message.
+    #[cfg(feature = "cosmwasm_1_2")]
+    MultiSend { outputs: Vec<MultiSendOutput> },
+}
+
+/// A recipient of a [`BankMsg::MultiSend`] and the coins it receives
+#[cfg(feature = "cosmwasm_1_2")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MultiSendOutput {
+    pub to_address: String,
+    pub amount: Vec<Coin>,
+}
+
+#[cfg(feature = "cosmwasm_1_2")]
+impl BankMsg {
+    /// Creates a `BankMsg::MultiSend` after checking the outputs with [`multi_send_total`].
+    pub fn multi_send(outputs: Vec<MultiSendOutput>) -> StdResult<Self> {
+        multi_send_total(&outputs)?;
+        Ok(BankMsg::MultiSend { outputs })
+    }
+}
+
+/// Validates the outputs of a [`BankMsg::MultiSend`] and returns the total amount
+/// that will be sent from the contract, sorted by denom.
+///
+/// This fails if there are no outputs, an output has an empty address, no coins, a zero
+/// amount or the same denom twice, or if the total overflows. The chain would reject such
+/// a message during execution.
+#[cfg(feature = "cosmwasm_1_2")]
+pub fn multi_send_total(outputs: &[MultiSendOutput]) -> StdResult<Vec<Coin>> {
+    use std::collections::BTreeMap;
+
+    use crate::errors::StdError;
+    use crate::math::Uint128;
+
+    if outputs.is_empty() {
+        return Err(StdError::generic_err(
+            "MultiSend must have at least one output",
+        ));
+    }
+    let mut total: BTreeMap<&str, Uint128> = BTreeMap::new();
+    for (index, output) in outputs.iter().enumerate() {
+        if output.to_address.is_empty() {
+            return Err(StdError::generic_err(format!(
+                "MultiSend output {} has an empty address",
+                index
+            )));
+        }
+        if output.amount.is_empty() {
+            return Err(StdError::generic_err(format!(
+                "MultiSend output {} has no coins",
+                index
+            )));
+        }
+        for (i, coin) in output.amount.iter().enumerate() {
+            if coin.amount.is_zero() {
+                return Err(StdError::generic_err(format!(
+                    "MultiSend output {} has a zero amount of {}",
+                    index, coin.denom
+                )));
+            }
+            if output.amount[..i].iter().any(|c| c.denom == coin.denom) {
+                return Err(StdError::generic_err(format!(
+                    "MultiSend output {} contains denom {} more than once",
+                    index, coin.denom
+                )));
+            }
+            let sum = total.entry(&coin.denom).or_default();
+            *sum = sum.checked_add(coin.amount)?;
+        }
+    }
+    Ok(total
+        .into_iter()
+        .map(|(denom, amount)| Coin::new(amount.u128(), denom))
+        .collect())
 }
 
 /// The message types of the staking module.
@@ -175,6 +255,13 @@ pub enum WasmMsg {
     /// Clears the admin on the given contract, so no more migration possible.
     /// Fails if this contract is not currently admin of the target contract.
     ClearAdmin { contract_addr: String },
+    /// Retires the sending contract. This clears its admin, so no more migration is possible,
+    /// and makes the chain reject all further execute calls to it. Queries keep working.
+    ///
+    /// If `prune_state` is set, the chain is allowed to delete the contract's storage
+    /// once it is retired. Use this to decommission deprecated contracts cleanly.
+    #[cfg(feature = "cosmwasm_1_2")]
+    Retire { prune_state: bool },
 }
 
 #[cfg(feature = "stargate")]
@@ -271,8 +358,98 @@ impl<T> From<GovMsg> for CosmosMsg<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "cosmwasm_1_2")]
+    use crate::errors::StdError;
     use crate::{coin, coins};
 
+    #[cfg(feature = "cosmwasm_1_2")]
+    fn output(to_address: &str, amount: Vec<Coin>) -> MultiSendOutput {
+        MultiSendOutput {
+            to_address: to_address.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_2")]
+    fn multi_send_total_works() {
+        let outputs = vec![
+            output("alice", vec![coin(10, "uatom"), coin(5, "ucosm")]),
+            output("bob", coins(7, "ucosm")),
+            output("alice", coins(1, "uatom")),
+        ];
+        assert_eq!(
+            multi_send_total(&outputs).unwrap(),
+            vec![coin(11, "uatom"), coin(12, "ucosm")]
+        );
+
+        let msg = BankMsg::multi_send(outputs.clone()).unwrap();
+        assert_eq!(msg, BankMsg::MultiSend { outputs });
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_2")]
+    fn multi_send_total_rejects_invalid_outputs() {
+        let cases = [
+            (vec![], "MultiSend must have at least one output"),
+            (
+                vec![output("alice", coins(1, "a")), output("", coins(1, "a"))],
+                "MultiSend output 1 has an empty address",
+            ),
+            (
+                vec![output("alice", vec![])],
+                "MultiSend output 0 has no coins",
+            ),
+            (
+                vec![output("alice", vec![coin(1, "a"), coin(0, "b")])],
+                "MultiSend output 0 has a zero amount of b",
+            ),
+            (
+                vec![output("alice", vec![coin(1, "a"), coin(2, "a")])],
+                "MultiSend output 0 contains denom a more than once",
+            ),
+        ];
+        for (outputs, expected) in cases {
+            match BankMsg::multi_send(outputs).unwrap_err() {
+                StdError::GenericErr { msg, .. } => assert_eq!(msg, expected),
+                err => panic!("Unexpected error: {:?}", err),
+            }
+        }
+
+        let overflowing = vec![
+            output("alice", coins(u128::MAX, "a")),
+            output("bob", coins(1, "a")),
+        ];
+        assert!(matches!(
+            multi_send_total(&overflowing).unwrap_err(),
+            StdError::Overflow { .. }
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_2")]
+    fn multi_send_serializes() {
+        let msg = BankMsg::MultiSend {
+            outputs: vec![output("alice", coins(3, "ucosm"))],
+        };
+        assert_eq!(
+            String::from_utf8(crate::to_vec(&msg).unwrap()).unwrap(),
+            r#"{"multi_send":{"outputs":[{"to_address":"alice","amount":[{"denom":"ucosm","amount":"3"}]}]}}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_2")]
+    fn retire_serializes() {
+        let msg = WasmMsg::Retire { prune_state: true };
+        assert_eq!(
+            String::from_utf8(crate::to_vec(&msg).unwrap()).unwrap(),
+            r#"{"retire":{"prune_state":true}}"#
+        );
+        let msg: WasmMsg = crate::from_slice(br#"{"retire":{"prune_state":false}}"#).unwrap();
+        assert_eq!(msg, WasmMsg::Retire { prune_state: false });
+    }
+
     #[test]
     fn from_bank_msg_works() {
         let to_address = String::from("you");
@@ -6,10 +6,13 @@ mod empty;
 mod events;
 mod query;
 mod response;
+mod response_limits;
 mod submessages;
 mod system_result;
 
 pub use contract_result::ContractResult;
+#[cfg(feature = "cosmwasm_1_2")]
+pub use cosmos_msg::{multi_send_total, MultiSendOutput};
 pub use cosmos_msg::{wasm_execute, wasm_instantiate, BankMsg, CosmosMsg, CustomMsg, WasmMsg};
 #[cfg(feature = "staking")]
 pub use cosmos_msg::{DistributionMsg, StakingMsg};
@@ -19,7 +22,8 @@ pub use empty::Empty;
 pub use events::{attr, Attribute, Event};
 pub use query::QueryResponse;
 pub use response::Response;
+pub use response_limits::ResponseLimits;
 #[allow(deprecated)]
 pub use submessages::SubMsgExecutionResponse;
-pub use submessages::{Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult};
+pub use submessages::{Reply, ReplyId, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult};
 pub use system_result::SystemResult;
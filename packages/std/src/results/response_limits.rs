@@ -0,0 +1,177 @@
+use crate::errors::ResponseLimitError;
+use crate::Binary;
+
+use super::{Attribute, Event, Response};
+
+/// Limits on the size of a contract response.
+///
+/// Chains reject responses that are too large, usually with an error that is hard to trace
+/// back to the contract. Contracts can check their responses against the limits of the chain
+/// with [`check_response`](ResponseLimits::check_response) to fail early with a clear error,
+/// and embedders can enforce them in the VM.
+///
+/// The default has no limits, such that only the relevant ones need to be set:
+///
+/// ```
+/// use cosmwasm_std::{Response, ResponseLimits, ResponseLimitError};
+///
+/// let limits = ResponseLimits {
+///     max_attributes: 2,
+///     ..ResponseLimits::default()
+/// };
+/// let response: Response = Response::new()
+///     .add_attribute("action", "swap")
+///     .add_attribute("pool", "1")
+///     .add_attribute("amount", "100");
+/// assert_eq!(
+///     limits.check_response(&response),
+///     Err(ResponseLimitError::TooManyAttributes { count: 3, max: 2 })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResponseLimits {
+    /// The maximum number of messages, including submessages
+    pub max_messages: usize,
+    /// The maximum number of attributes, including those of the events
+    pub max_attributes: usize,
+    /// The maximum size (in bytes) of an attribute, i.e. of its key and value together
+    pub max_attribute_size: usize,
+    /// The maximum size (in bytes) of the data
+    pub max_data_size: usize,
+}
+
+impl Default for ResponseLimits {
+    fn default() -> Self {
+        ResponseLimits {
+            max_messages: usize::MAX,
+            max_attributes: usize::MAX,
+            max_attribute_size: usize::MAX,
+            max_data_size: usize::MAX,
+        }
+    }
+}
+
+impl ResponseLimits {
+    /// Checks a response of `instantiate`, `execute`, `migrate`, `sudo` or `reply`
+    pub fn check_response<T>(&self, response: &Response<T>) -> Result<(), ResponseLimitError> {
+        self.check(
+            response.messages.len(),
+            &response.attributes,
+            &response.events,
+            response.data.as_ref(),
+        )
+    }
+
+    /// Checks the parts of a response, which allows checking other response types,
+    /// e.g. those of the IBC entry points.
+    pub fn check(
+        &self,
+        messages: usize,
+        attributes: &[Attribute],
+        events: &[Event],
+        data: Option<&Binary>,
+    ) -> Result<(), ResponseLimitError> {
+        if messages > self.max_messages {
+            return Err(ResponseLimitError::TooManyMessages {
+                count: messages,
+                max: self.max_messages,
+            });
+        }
+        let all_attributes = || {
+            attributes
+                .iter()
+                .chain(events.iter().flat_map(|event| &event.attributes))
+        };
+        let count = all_attributes().count();
+        if count > self.max_attributes {
+            return Err(ResponseLimitError::TooManyAttributes {
+                count,
+                max: self.max_attributes,
+            });
+        }
+        for attribute in all_attributes() {
+            let size = attribute.key.len() + attribute.value.len();
+            if size > self.max_attribute_size {
+                return Err(ResponseLimitError::AttributeTooLarge {
+                    key: attribute.key.clone(),
+                    size,
+                    max: self.max_attribute_size,
+                });
+            }
+        }
+        if let Some(data) = data {
+            if data.len() > self.max_data_size {
+                return Err(ResponseLimitError::DataTooLarge {
+                    size: data.len(),
+                    max: self.max_data_size,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BankMsg, Empty};
+
+    #[test]
+    fn default_has_no_limits() {
+        let response: Response = Response::new()
+            .add_message(BankMsg::Burn { amount: vec![] })
+            .add_attribute("key", "value".repeat(1000))
+            .set_data(vec![7; 10_000]);
+        ResponseLimits::default().check_response(&response).unwrap();
+    }
+
+    #[test]
+    fn check_response_works() {
+        let limits = ResponseLimits {
+            max_messages: 1,
+            max_attributes: 2,
+            max_attribute_size: 10,
+            max_data_size: 4,
+        };
+        let response: Response = Response::new()
+            .add_message(BankMsg::Burn { amount: vec![] })
+            .add_attribute("action", "swap")
+            .add_event(Event::new("swap").add_attribute("pool", "1"))
+            .set_data(b"data");
+        limits.check_response(&response).unwrap();
+
+        let too_many_messages = response
+            .clone()
+            .add_message(BankMsg::Burn { amount: vec![] });
+        assert_eq!(
+            limits.check_response(&too_many_messages),
+            Err(ResponseLimitError::TooManyMessages { count: 2, max: 1 })
+        );
+
+        // attributes of events count as well
+        let too_many_attributes = response
+            .clone()
+            .add_event(Event::new("transfer").add_attribute("to", "bob"));
+        assert_eq!(
+            limits.check_response(&too_many_attributes),
+            Err(ResponseLimitError::TooManyAttributes { count: 3, max: 2 })
+        );
+
+        let too_large_attribute =
+            Response::<Empty>::new().add_event(Event::new("swap").add_attribute("amount", "12345"));
+        assert_eq!(
+            limits.check_response(&too_large_attribute),
+            Err(ResponseLimitError::AttributeTooLarge {
+                key: "amount".to_string(),
+                size: 11,
+                max: 10,
+            })
+        );
+
+        let too_large_data = response.set_data(b"data!");
+        assert_eq!(
+            limits.check_response(&too_large_data),
+            Err(ResponseLimitError::DataTooLarge { size: 5, max: 4 })
+        );
+    }
+}
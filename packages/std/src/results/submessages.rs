@@ -1,6 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::StdResult;
 use crate::Binary;
 
 use super::{CosmosMsg, Empty, Event};
@@ -103,6 +104,19 @@ pub struct Reply {
     pub result: SubMsgResult,
 }
 
+/// Maps the variants of an enum to the IDs of the submessages a contract dispatches, such that
+/// the `reply` entry point can match on the variants instead of constants.
+///
+/// Derive it with [`#[derive(ReplyId)]`](macro@crate::ReplyId), which uses the discriminants
+/// of the variants as IDs.
+pub trait ReplyId: Sized {
+    /// The ID to use in [`SubMsg`]
+    fn reply_id(&self) -> u64;
+
+    /// Decodes the ID of a [`Reply`], which fails for IDs that belong to no variant
+    fn from_reply_id(id: u64) -> StdResult<Self>;
+}
+
 /// This is the result type that is returned from a sub message execution.
 ///
 /// We use a custom type here instead of Rust's Result because we want to be able to
@@ -0,0 +1,400 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::errors::{StdError, StdResult};
+use crate::serde::{from_slice, to_vec};
+use crate::traits::Storage;
+
+/// A value in storage that keeps its history by block height, e.g. to look up the
+/// voting power an account had when a proposal was created.
+///
+/// Every [`save`](Snapshot::save) records a checkpoint which is valid from its height on
+/// until the next checkpoint, including the height it was saved at. Looking up the value
+/// at a height performs a binary search over the checkpoints, which takes a logarithmic
+/// number of storage reads and does not require the `iterator` feature. Use
+/// `height - 1` to get the value before the changes of a block.
+///
+/// Checkpoints that are not needed anymore can be removed with [`prune`](Snapshot::prune).
+///
+/// All data is stored under a length-prefixed namespace, which must not be used
+/// by anything else. Use one namespace per account for per-account histories.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{MemoryStorage, Snapshot, Uint128};
+///
+/// const TOTAL_POWER: Snapshot<Uint128> = Snapshot::new(b"total_power");
+///
+/// let mut storage = MemoryStorage::new();
+/// TOTAL_POWER.save(&mut storage, 100, &Uint128::new(50)).unwrap();
+/// TOTAL_POWER.save(&mut storage, 200, &Uint128::new(80)).unwrap();
+///
+/// assert_eq!(TOTAL_POWER.may_load_at_height(&storage, 99).unwrap(), None);
+/// assert_eq!(TOTAL_POWER.may_load_at_height(&storage, 150).unwrap(), Some(Uint128::new(50)));
+/// assert_eq!(TOTAL_POWER.may_load_at_height(&storage, 200).unwrap(), Some(Uint128::new(80)));
+/// assert_eq!(TOTAL_POWER.load(&storage).unwrap(), Uint128::new(80));
+/// ```
+pub struct Snapshot<'a, T> {
+    namespace: &'a [u8],
+    value_type: PhantomData<T>,
+}
+
+/// The range of stored checkpoint indices and the height below which history was pruned
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Meta {
+    start: u32,
+    end: u32,
+    pruned_below: u64,
+}
+
+impl Meta {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(16);
+        out.extend_from_slice(&self.start.to_be_bytes());
+        out.extend_from_slice(&self.end.to_be_bytes());
+        out.extend_from_slice(&self.pruned_below.to_be_bytes());
+        out
+    }
+
+    fn from_bytes(bytes: &[u8]) -> StdResult<Self> {
+        if bytes.len() != 16 {
+            return Err(StdError::parse_err(
+                "Snapshot",
+                format!("invalid metadata length {}", bytes.len()),
+            ));
+        }
+        Ok(Meta {
+            start: u32::from_be_bytes(bytes[0..4].try_into().unwrap()),
+            end: u32::from_be_bytes(bytes[4..8].try_into().unwrap()),
+            pruned_below: u64::from_be_bytes(bytes[8..16].try_into().unwrap()),
+        })
+    }
+}
+
+const META_KEY: u8 = b'm';
+const HEIGHT_KEY: u8 = b'h';
+const VALUE_KEY: u8 = b'v';
+
+impl<'a, T> Snapshot<'a, T> {
+    pub const fn new(namespace: &'a [u8]) -> Self {
+        Snapshot {
+            namespace,
+            value_type: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Snapshot<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn key(&self, kind: u8, index: Option<u32>) -> Vec<u8> {
+        if self.namespace.len() > 0xFFFF {
+            panic!("only supports namespaces up to length 0xFFFF")
+        }
+        let mut out = Vec::with_capacity(self.namespace.len() + 7);
+        out.extend_from_slice(&(self.namespace.len() as u16).to_be_bytes());
+        out.extend_from_slice(self.namespace);
+        out.push(kind);
+        if let Some(index) = index {
+            out.extend_from_slice(&index.to_be_bytes());
+        }
+        out
+    }
+
+    fn meta(&self, storage: &dyn Storage) -> StdResult<Meta> {
+        match storage.get(&self.key(META_KEY, None)) {
+            Some(bytes) => Meta::from_bytes(&bytes),
+            None => Ok(Meta::default()),
+        }
+    }
+
+    fn height(&self, storage: &dyn Storage, index: u32) -> StdResult<u64> {
+        let bytes = storage
+            .get(&self.key(HEIGHT_KEY, Some(index)))
+            .ok_or_else(|| StdError::not_found("Snapshot checkpoint"))?;
+        let bytes: [u8; 8] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| StdError::parse_err("Snapshot", "invalid checkpoint height"))?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn value(&self, storage: &dyn Storage, index: u32) -> StdResult<T> {
+        let bytes = storage
+            .get(&self.key(VALUE_KEY, Some(index)))
+            .ok_or_else(|| StdError::not_found("Snapshot checkpoint"))?;
+        from_slice(&bytes)
+    }
+
+    /// Records `value` as the value from `height` on.
+    ///
+    /// Saving twice at the same height overwrites the first value. Returns an error if
+    /// `height` is lower than the height of the latest checkpoint.
+    pub fn save(&self, storage: &mut dyn Storage, height: u64, value: &T) -> StdResult<()> {
+        let mut meta = self.meta(storage)?;
+        let last = if meta.end > meta.start {
+            Some(self.height(storage, meta.end - 1)?)
+        } else {
+            None
+        };
+        let index = match last {
+            Some(last) if height < last => {
+                return Err(StdError::generic_err(format!(
+                    "Snapshot height {} is before the latest checkpoint at {}",
+                    height, last
+                )));
+            }
+            Some(last) if height == last => meta.end - 1,
+            _ => {
+                let index = meta.end;
+                meta.end = meta
+                    .end
+                    .checked_add(1)
+                    .ok_or_else(|| StdError::generic_err("Snapshot checkpoint limit reached"))?;
+                storage.set(&self.key(HEIGHT_KEY, Some(index)), &height.to_be_bytes());
+                storage.set(&self.key(META_KEY, None), &meta.to_bytes());
+                index
+            }
+        };
+        storage.set(&self.key(VALUE_KEY, Some(index)), &to_vec(value)?);
+        Ok(())
+    }
+
+    /// Loads the latest value and returns an error if nothing was saved yet
+    pub fn load(&self, storage: &dyn Storage) -> StdResult<T> {
+        self.may_load(storage)?
+            .ok_or_else(|| StdError::not_found(std::any::type_name::<T>()))
+    }
+
+    /// Loads the latest value and returns `None` if nothing was saved yet
+    pub fn may_load(&self, storage: &dyn Storage) -> StdResult<Option<T>> {
+        let meta = self.meta(storage)?;
+        if meta.end == meta.start {
+            return Ok(None);
+        }
+        self.value(storage, meta.end - 1).map(Some)
+    }
+
+    /// Loads the value at the given height, i.e. the value of the latest checkpoint saved at
+    /// or before `height`. Returns `None` if there was no value at that height.
+    ///
+    /// Returns an error if the history at `height` was removed by [`prune`](Snapshot::prune).
+    pub fn may_load_at_height(&self, storage: &dyn Storage, height: u64) -> StdResult<Option<T>> {
+        let meta = self.meta(storage)?;
+        if height < meta.pruned_below {
+            return Err(StdError::generic_err(format!(
+                "Snapshot history before height {} was pruned",
+                meta.pruned_below
+            )));
+        }
+        match self.find(storage, meta, height)? {
+            Some(index) => self.value(storage, index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The index of the latest checkpoint at or before `height`
+    fn find(&self, storage: &dyn Storage, meta: Meta, height: u64) -> StdResult<Option<u32>> {
+        // invariant: checkpoints before `low` are at or before `height`,
+        // checkpoints from `high` on are after `height`
+        let mut low = meta.start;
+        let mut high = meta.end;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.height(storage, mid)? <= height {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(if low > meta.start {
+            Some(low - 1)
+        } else {
+            None
+        })
+    }
+
+    /// The number of stored checkpoints
+    pub fn checkpoint_count(&self, storage: &dyn Storage) -> StdResult<u32> {
+        let meta = self.meta(storage)?;
+        Ok(meta.end - meta.start)
+    }
+
+    /// Removes all checkpoints that are not needed for looking up values at `height`
+    /// or later and returns the number of removed checkpoints.
+    ///
+    /// Afterwards looking up values before `height` returns an error. Pruning at a
+    /// height below an earlier pruning height does nothing.
+    pub fn prune(&self, storage: &mut dyn Storage, height: u64) -> StdResult<u32> {
+        let mut meta = self.meta(storage)?;
+        if height <= meta.pruned_below {
+            return Ok(0);
+        }
+        // the checkpoint valid at `height` must be kept
+        let keep_from = match self.find(storage, meta, height)? {
+            Some(index) => index,
+            None => meta.start,
+        };
+        for index in meta.start..keep_from {
+            storage.remove(&self.key(HEIGHT_KEY, Some(index)));
+            storage.remove(&self.key(VALUE_KEY, Some(index)));
+        }
+        let removed = keep_from - meta.start;
+        meta.start = keep_from;
+        meta.pruned_below = height;
+        storage.set(&self.key(META_KEY, None), &meta.to_bytes());
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    const POWER: Snapshot<u64> = Snapshot::new(b"power");
+
+    #[test]
+    fn empty_snapshot_works() {
+        let storage = MemoryStorage::new();
+        assert_eq!(POWER.may_load(&storage).unwrap(), None);
+        assert_eq!(POWER.may_load_at_height(&storage, 0).unwrap(), None);
+        assert_eq!(POWER.may_load_at_height(&storage, u64::MAX).unwrap(), None);
+        assert_eq!(POWER.checkpoint_count(&storage).unwrap(), 0);
+        assert!(matches!(
+            POWER.load(&storage).unwrap_err(),
+            StdError::NotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn may_load_at_height_works() {
+        let mut storage = MemoryStorage::new();
+        POWER.save(&mut storage, 10, &1).unwrap();
+        POWER.save(&mut storage, 20, &2).unwrap();
+        POWER.save(&mut storage, 21, &3).unwrap();
+        POWER.save(&mut storage, 40, &4).unwrap();
+
+        let expected = [
+            (0, None),
+            (9, None),
+            (10, Some(1)),
+            (19, Some(1)),
+            (20, Some(2)),
+            (21, Some(3)),
+            (39, Some(3)),
+            (40, Some(4)),
+            (u64::MAX, Some(4)),
+        ];
+        for (height, value) in expected {
+            assert_eq!(
+                POWER.may_load_at_height(&storage, height).unwrap(),
+                value,
+                "height {}",
+                height
+            );
+        }
+        assert_eq!(POWER.load(&storage).unwrap(), 4);
+        assert_eq!(POWER.checkpoint_count(&storage).unwrap(), 4);
+    }
+
+    #[test]
+    fn may_load_at_height_matches_linear_search() {
+        let mut storage = MemoryStorage::new();
+        let mut checkpoints = vec![];
+        let mut height = 3;
+        for i in 0..100u64 {
+            height += i % 7 + 1;
+            POWER.save(&mut storage, height, &i).unwrap();
+            checkpoints.push((height, i));
+        }
+
+        for height in 0..=height + 2 {
+            let expected = checkpoints
+                .iter()
+                .rev()
+                .find(|(h, _)| *h <= height)
+                .map(|(_, value)| *value);
+            assert_eq!(
+                POWER.may_load_at_height(&storage, height).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn save_at_same_height_overwrites() {
+        let mut storage = MemoryStorage::new();
+        POWER.save(&mut storage, 10, &1).unwrap();
+        POWER.save(&mut storage, 10, &2).unwrap();
+        assert_eq!(POWER.checkpoint_count(&storage).unwrap(), 1);
+        assert_eq!(POWER.may_load_at_height(&storage, 10).unwrap(), Some(2));
+    }
+
+    #[test]
+    fn save_rejects_earlier_height() {
+        let mut storage = MemoryStorage::new();
+        POWER.save(&mut storage, 10, &1).unwrap();
+        let err = POWER.save(&mut storage, 9, &2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Snapshot height 9 is before the latest checkpoint at 10"
+        );
+        assert_eq!(POWER.load(&storage).unwrap(), 1);
+    }
+
+    #[test]
+    fn prune_works() {
+        let mut storage = MemoryStorage::new();
+        for (height, value) in [(10, 1), (20, 2), (30, 3), (40, 4)] {
+            POWER.save(&mut storage, height, &value).unwrap();
+        }
+
+        // the checkpoint at 20 is still needed for height 25
+        assert_eq!(POWER.prune(&mut storage, 25).unwrap(), 1);
+        assert_eq!(POWER.checkpoint_count(&storage).unwrap(), 3);
+        assert_eq!(POWER.may_load_at_height(&storage, 25).unwrap(), Some(2));
+        assert_eq!(POWER.may_load_at_height(&storage, 35).unwrap(), Some(3));
+        let err = POWER.may_load_at_height(&storage, 24).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Snapshot history before height 25 was pruned"
+        );
+
+        // pruning at a lower height does nothing
+        assert_eq!(POWER.prune(&mut storage, 5).unwrap(), 0);
+        assert_eq!(POWER.checkpoint_count(&storage).unwrap(), 3);
+
+        // saving continues after the pruned checkpoints
+        POWER.save(&mut storage, 50, &5).unwrap();
+        assert_eq!(POWER.prune(&mut storage, 50).unwrap(), 3);
+        assert_eq!(POWER.checkpoint_count(&storage).unwrap(), 1);
+        assert_eq!(POWER.may_load_at_height(&storage, 50).unwrap(), Some(5));
+        assert_eq!(POWER.load(&storage).unwrap(), 5);
+    }
+
+    #[test]
+    fn prune_before_first_checkpoint_keeps_everything() {
+        let mut storage = MemoryStorage::new();
+        POWER.save(&mut storage, 10, &1).unwrap();
+        assert_eq!(POWER.prune(&mut storage, 5).unwrap(), 0);
+        assert_eq!(POWER.may_load_at_height(&storage, 5).unwrap(), None);
+        assert_eq!(POWER.may_load_at_height(&storage, 10).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn namespaces_are_separated() {
+        let mut storage = MemoryStorage::new();
+        let alice: Snapshot<u64> = Snapshot::new(b"power_alice");
+        let bob: Snapshot<u64> = Snapshot::new(b"power_bob");
+        alice.save(&mut storage, 10, &1).unwrap();
+        bob.save(&mut storage, 20, &2).unwrap();
+        assert_eq!(alice.may_load_at_height(&storage, 20).unwrap(), Some(1));
+        assert_eq!(bob.may_load_at_height(&storage, 10).unwrap(), None);
+        assert_eq!(POWER.may_load(&storage).unwrap(), None);
+    }
+}
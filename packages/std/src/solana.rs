@@ -0,0 +1,249 @@
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::encoding::{base58_decode, base58_encode};
+use crate::errors::{Base58Error, VerificationError};
+use crate::{Api, Binary};
+
+/// The prefix of Solana off-chain messages, which makes them invalid transactions
+const OFFCHAIN_SIGNING_DOMAIN: &[u8] = b"\xffsolana offchain";
+/// Signing domain, version, message format and message length
+const OFFCHAIN_HEADER_LEN: usize = OFFCHAIN_SIGNING_DOMAIN.len() + 4;
+/// The maximum message length that fits into a single packet (1232 bytes) with the header
+const OFFCHAIN_MAX_LEN_LEDGER: usize = 1232 - OFFCHAIN_HEADER_LEN;
+const OFFCHAIN_MAX_LEN: usize = u16::MAX as usize - OFFCHAIN_HEADER_LEN;
+
+/// A Solana account address, i.e. a 32 byte ed25519 public key, which is displayed in base58.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::SolanaPubkey;
+/// let system_program = SolanaPubkey::from_base58("11111111111111111111111111111111").unwrap();
+/// assert_eq!(system_program.as_bytes(), &[0; 32]);
+/// assert_eq!(system_program.to_string(), "11111111111111111111111111111111");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SolanaPubkey([u8; 32]);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SolanaError {
+    #[error("Solana public key must be 32 bytes long, got {length}")]
+    InvalidLength { length: usize },
+    #[error("{0}")]
+    Base58(#[from] Base58Error),
+    #[error("Off-chain message must be valid UTF-8 of at most {max} bytes", max = OFFCHAIN_MAX_LEN)]
+    InvalidOffchainMessage,
+    #[error("{0}")]
+    Verification(#[from] VerificationError),
+}
+
+impl SolanaPubkey {
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        SolanaPubkey(bytes)
+    }
+
+    pub fn from_base58(input: &str) -> Result<Self, SolanaError> {
+        Self::try_from(base58_decode(input)?.as_slice())
+    }
+
+    pub fn to_base58(&self) -> String {
+        base58_encode(&self.0)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Verifies an ed25519 signature of the raw message by this key, as created by
+    /// `signMessage` in wallets like Phantom and Solflare.
+    pub fn verify(
+        &self,
+        api: &dyn Api,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, VerificationError> {
+        api.ed25519_verify(message, signature, &self.0)
+    }
+
+    /// Verifies an ed25519 signature by this key of the message wrapped into the Solana
+    /// off-chain message format (see [`solana_offchain_message`]), as created by
+    /// `solana sign-offchain-message`.
+    pub fn verify_offchain(
+        &self,
+        api: &dyn Api,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, SolanaError> {
+        let wrapped = solana_offchain_message(message)?;
+        Ok(api.ed25519_verify(&wrapped, signature, &self.0)?)
+    }
+}
+
+/// Wraps a message into the version 0 Solana off-chain message format, which is signed
+/// instead of the plain message to avoid signing something that is a valid transaction.
+///
+/// The format is the signing domain `"\xffsolana offchain"`, the version (0), the message format
+/// (0 for printable ASCII and 1 for UTF-8 up to 1212 bytes, 2 for longer UTF-8 messages),
+/// the message length as little endian u16 and the message.
+pub fn solana_offchain_message(message: &[u8]) -> Result<Vec<u8>, SolanaError> {
+    if std::str::from_utf8(message).is_err() || message.len() > OFFCHAIN_MAX_LEN {
+        return Err(SolanaError::InvalidOffchainMessage);
+    }
+    let format = if message.len() > OFFCHAIN_MAX_LEN_LEDGER {
+        2
+    } else if message.iter().all(|&c| (0x20..=0x7e).contains(&c)) {
+        0
+    } else {
+        1
+    };
+
+    let mut out = Vec::with_capacity(OFFCHAIN_HEADER_LEN + message.len());
+    out.extend_from_slice(OFFCHAIN_SIGNING_DOMAIN);
+    out.push(0);
+    out.push(format);
+    out.extend_from_slice(&(message.len() as u16).to_le_bytes());
+    out.extend_from_slice(message);
+    Ok(out)
+}
+
+impl fmt::Display for SolanaPubkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_base58())
+    }
+}
+
+impl From<[u8; 32]> for SolanaPubkey {
+    fn from(bytes: [u8; 32]) -> Self {
+        SolanaPubkey(bytes)
+    }
+}
+
+impl From<SolanaPubkey> for Binary {
+    fn from(pubkey: SolanaPubkey) -> Self {
+        Binary::from(pubkey.0)
+    }
+}
+
+impl TryFrom<&[u8]> for SolanaPubkey {
+    type Error = SolanaError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; 32] = value.try_into().map_err(|_| SolanaError::InvalidLength {
+            length: value.len(),
+        })?;
+        Ok(SolanaPubkey(bytes))
+    }
+}
+
+impl TryFrom<&Binary> for SolanaPubkey {
+    type Error = SolanaError;
+
+    fn try_from(value: &Binary) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockApi;
+    use hex_literal::hex;
+
+    // Created with the seed 0x0102..20 using the Python cryptography package
+    const PUBKEY: &str = "9C6hybhQ6Aycep9jaUnP6uL9ZYvDjUp1aSkFWPUFJtpj";
+    const MESSAGE: &[u8] = b"Attest: deposit 42 on chain A";
+    const SIGNATURE: [u8; 64] = hex!("8284a2a14d5e30559ef916d0f163767405390bd8339102b42fd0760d7599fd72effb9a55cfad220efcef33a78b02744789b67ce59059638adb5c7f253d2ded04");
+    const OFFCHAIN_SIGNATURE: [u8; 64] = hex!("8c75ddddfa0b4f715d8d5aeec851a291637d0e557898710cc7ee48cdbb928a3af636c85f13a4cc8ff09af5c62bb4292a3e415991bde01a0ff6881ab43650ef04");
+
+    #[test]
+    fn from_base58_works() {
+        let pubkey = SolanaPubkey::from_base58(PUBKEY).unwrap();
+        assert_eq!(
+            pubkey.as_bytes(),
+            &hex!("79b5562e8fe654f94078b112e8a98ba7901f853ae695bed7e0e3910bad049664")
+        );
+        assert_eq!(pubkey.to_string(), PUBKEY);
+
+        assert_eq!(
+            SolanaPubkey::from_base58("9C6hybhQ6Aycep9jaUnP6uL9ZYvDjUp1aSkFWPUF"),
+            Err(SolanaError::InvalidLength { length: 29 })
+        );
+        assert!(matches!(
+            SolanaPubkey::from_base58("9C6hybhQ6Aycep9jaUnP6uL9ZYvDjUp1aSkFWPUFJtp0"),
+            Err(SolanaError::Base58(_))
+        ));
+    }
+
+    #[test]
+    fn binary_conversions_work() {
+        let pubkey = SolanaPubkey::new([7; 32]);
+        let binary = Binary::from(pubkey);
+        assert_eq!(binary.as_slice(), &[7; 32]);
+        assert_eq!(SolanaPubkey::try_from(&binary).unwrap(), pubkey);
+        assert_eq!(SolanaPubkey::from([7; 32]), pubkey);
+        assert_eq!(
+            SolanaPubkey::try_from(&[7; 20][..]),
+            Err(SolanaError::InvalidLength { length: 20 })
+        );
+    }
+
+    #[test]
+    fn verify_works() {
+        let api = MockApi::default();
+        let pubkey = SolanaPubkey::from_base58(PUBKEY).unwrap();
+        assert!(pubkey.verify(&api, MESSAGE, &SIGNATURE).unwrap());
+        assert!(!pubkey
+            .verify(&api, b"Attest: deposit 43", &SIGNATURE)
+            .unwrap());
+        assert!(!pubkey.verify(&api, MESSAGE, &OFFCHAIN_SIGNATURE).unwrap());
+
+        let other = SolanaPubkey::new([1; 32]);
+        assert!(!other.verify(&api, MESSAGE, &SIGNATURE).unwrap());
+    }
+
+    #[test]
+    fn verify_offchain_works() {
+        let api = MockApi::default();
+        let pubkey = SolanaPubkey::from_base58(PUBKEY).unwrap();
+        assert!(pubkey
+            .verify_offchain(&api, MESSAGE, &OFFCHAIN_SIGNATURE)
+            .unwrap());
+        assert!(!pubkey.verify_offchain(&api, MESSAGE, &SIGNATURE).unwrap());
+        assert_eq!(
+            pubkey.verify_offchain(&api, b"\xff", &OFFCHAIN_SIGNATURE),
+            Err(SolanaError::InvalidOffchainMessage)
+        );
+    }
+
+    #[test]
+    fn solana_offchain_message_works() {
+        assert_eq!(
+            solana_offchain_message(MESSAGE).unwrap(),
+            hex!("ff736f6c616e61206f6666636861696e00001d004174746573743a206465706f736974203432206f6e20636861696e2041")
+        );
+
+        // UTF-8
+        let message = solana_offchain_message("Grüße".as_bytes()).unwrap();
+        assert_eq!(&message[16..20], &[0, 1, 7, 0]);
+
+        // long messages
+        let long = vec![b'a'; 1213];
+        let message = solana_offchain_message(&long).unwrap();
+        assert_eq!(&message[16..20], &[0, 2, 0xbd, 0x04]);
+        assert_eq!(message.len(), 20 + 1213);
+        let short = vec![b'a'; 1212];
+        assert_eq!(solana_offchain_message(&short).unwrap()[17], 0);
+
+        assert!(solana_offchain_message(&vec![b'a'; 65515]).is_ok());
+        assert_eq!(
+            solana_offchain_message(&vec![b'a'; 65516]),
+            Err(SolanaError::InvalidOffchainMessage)
+        );
+        assert_eq!(
+            solana_offchain_message(b"\xc3"),
+            Err(SolanaError::InvalidOffchainMessage)
+        );
+    }
+}
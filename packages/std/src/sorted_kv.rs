@@ -0,0 +1,720 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::addresses::Addr;
+use crate::binary::Binary;
+use crate::errors::{StdError, StdResult};
+#[cfg(feature = "iterator")]
+use crate::iterator::Order;
+use crate::math::{Decimal, Decimal256, Uint128, Uint256, Uint512, Uint64};
+use crate::serde::{from_slice, to_vec};
+use crate::timestamp::Timestamp;
+use crate::traits::Storage;
+
+/// A key with a binary encoding whose lexicographic byte order equals the natural order
+/// of the values.
+///
+/// Unsigned integers are encoded big-endian and signed integers big-endian with the sign bit
+/// flipped. The math types and [`Timestamp`] use their `to_be_key` encoding. `bool` is encoded
+/// as one byte. Strings, [`Addr`] and byte strings have a variable length, so they are
+/// terminated by `0x00 0x00` and every `0x00` byte in the content is escaped as `0x00 0xFF`.
+/// This sorts shorter strings before longer ones with the same beginning, just like `Ord`
+/// for strings does.
+///
+/// Tuples concatenate the encodings of their elements, which sorts by the first element,
+/// then by the second and so on. Since no encoding is the beginning of another encoding of
+/// the same type, the keys whose first elements are given form a contiguous range starting
+/// with the encoding of these elements. This allows secondary indexes over raw [`Storage`].
+///
+/// ## Examples
+///
+/// ```
+/// # #[cfg(feature = "iterator")] {
+/// use cosmwasm_std::{MemoryStorage, Order, SortKey, Storage};
+///
+/// // A secondary index on (owner, token id). Empty values are not allowed in storage.
+/// let mut storage = MemoryStorage::new();
+/// for (owner, token_id) in [("bob", 3u64), ("alice", 7), ("alice", 2), ("alice2", 1)] {
+///     storage.set(&(owner.to_string(), token_id).to_sort_bytes(), b"\x01");
+/// }
+///
+/// // All tokens of alice in ascending order. The encoding of a string ends with a zero
+/// // byte, so incrementing it gives the end of the range.
+/// let start = "alice".to_string().to_sort_bytes();
+/// let mut end = start.clone();
+/// *end.last_mut().unwrap() += 1;
+/// let tokens: Vec<u64> = storage
+///     .range(Some(&start), Some(&end), Order::Ascending)
+///     .map(|(key, _)| <(String, u64)>::from_sort_bytes(&key).unwrap().1)
+///     .collect();
+/// assert_eq!(tokens, [2, 7]);
+/// # }
+/// ```
+pub trait SortKey: Sized {
+    /// Appends the encoding of this key to `out`
+    fn write_sort_bytes(&self, out: &mut Vec<u8>);
+
+    /// Decodes a key from the beginning of `bytes` and advances `bytes` past it
+    fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self>;
+
+    fn to_sort_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_sort_bytes(&mut out);
+        out
+    }
+
+    /// Decodes a key from exactly the given bytes
+    fn from_sort_bytes(bytes: &[u8]) -> StdResult<Self> {
+        let mut rest = bytes;
+        let key = Self::read_sort_bytes(&mut rest)?;
+        if !rest.is_empty() {
+            return Err(StdError::parse_err(
+                "SortKey",
+                format!("got {} unexpected trailing bytes", rest.len()),
+            ));
+        }
+        Ok(key)
+    }
+}
+
+/// Takes the first `N` bytes
+fn take_bytes<const N: usize>(bytes: &mut &[u8]) -> StdResult<[u8; N]> {
+    if bytes.len() < N {
+        return Err(StdError::parse_err(
+            "SortKey",
+            format!("expected {} bytes, got {}", N, bytes.len()),
+        ));
+    }
+    let (head, rest) = bytes.split_at(N);
+    *bytes = rest;
+    Ok(head.try_into().unwrap())
+}
+
+macro_rules! impl_sort_key_for_unsigned {
+    ($($t:ty),+) => {$(
+        impl SortKey for $t {
+            fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_bytes());
+            }
+
+            fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+                Ok(<$t>::from_be_bytes(take_bytes(bytes)?))
+            }
+        }
+    )+};
+}
+
+impl_sort_key_for_unsigned!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_sort_key_for_signed {
+    ($($t:ty => $u:ty),+) => {$(
+        impl SortKey for $t {
+            fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+                // flipping the sign bit moves negative numbers below positive ones
+                let flipped = (*self as $u) ^ (1 << (<$u>::BITS - 1));
+                out.extend_from_slice(&flipped.to_be_bytes());
+            }
+
+            fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+                let flipped = <$u>::from_be_bytes(take_bytes(bytes)?);
+                Ok((flipped ^ (1 << (<$u>::BITS - 1))) as $t)
+            }
+        }
+    )+};
+}
+
+impl_sort_key_for_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+
+macro_rules! impl_sort_key_for_be_key {
+    ($($t:ty),+) => {$(
+        impl SortKey for $t {
+            fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_key());
+            }
+
+            fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+                Ok(<$t>::from_be_key(take_bytes(bytes)?))
+            }
+        }
+    )+};
+}
+
+impl_sort_key_for_be_key!(Uint64, Uint128, Uint256, Uint512, Decimal, Decimal256, Timestamp);
+
+impl SortKey for bool {
+    fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+        match take_bytes::<1>(bytes)? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [other] => Err(StdError::parse_err(
+                "SortKey",
+                format!("invalid bool byte {}", other),
+            )),
+        }
+    }
+}
+
+/// Appends `content` with escaped zero bytes and the terminator
+fn write_escaped(content: &[u8], out: &mut Vec<u8>) {
+    for &byte in content {
+        out.push(byte);
+        if byte == 0x00 {
+            out.push(0xFF);
+        }
+    }
+    out.extend_from_slice(&[0x00, 0x00]);
+}
+
+/// Reads content written by [`write_escaped`]
+fn read_escaped(bytes: &mut &[u8]) -> StdResult<Vec<u8>> {
+    let mut content = Vec::new();
+    let mut rest = *bytes;
+    loop {
+        match rest {
+            [0x00, 0x00, tail @ ..] => {
+                *bytes = tail;
+                return Ok(content);
+            }
+            [0x00, 0xFF, tail @ ..] => {
+                content.push(0x00);
+                rest = tail;
+            }
+            [0x00, ..] | [] => {
+                return Err(StdError::parse_err(
+                    "SortKey",
+                    "invalid or unterminated variable-length key",
+                ))
+            }
+            [byte, tail @ ..] => {
+                content.push(*byte);
+                rest = tail;
+            }
+        }
+    }
+}
+
+impl SortKey for Vec<u8> {
+    fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+        write_escaped(self, out);
+    }
+
+    fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+        read_escaped(bytes)
+    }
+}
+
+impl SortKey for Binary {
+    fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+        write_escaped(self.as_slice(), out);
+    }
+
+    fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+        read_escaped(bytes).map(Binary)
+    }
+}
+
+impl SortKey for String {
+    fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+        write_escaped(self.as_bytes(), out);
+    }
+
+    fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+        String::from_utf8(read_escaped(bytes)?).map_err(|_| StdError::invalid_utf8("SortKey"))
+    }
+}
+
+/// The address is encoded like a [`String`]. Decoding does not validate it.
+impl SortKey for Addr {
+    fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+        write_escaped(self.as_bytes(), out);
+    }
+
+    fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+        String::read_sort_bytes(bytes).map(Addr::unchecked)
+    }
+}
+
+macro_rules! impl_sort_key_for_tuple {
+    ($($name:ident . $index:tt),+) => {
+        impl<$($name: SortKey),+> SortKey for ($($name,)+) {
+            fn write_sort_bytes(&self, out: &mut Vec<u8>) {
+                $(self.$index.write_sort_bytes(out);)+
+            }
+
+            fn read_sort_bytes(bytes: &mut &[u8]) -> StdResult<Self> {
+                Ok(($($name::read_sort_bytes(bytes)?,)+))
+            }
+        }
+    };
+}
+
+impl_sort_key_for_tuple!(A.0, B.1);
+impl_sort_key_for_tuple!(A.0, B.1, C.2);
+impl_sort_key_for_tuple!(A.0, B.1, C.2, D.3);
+
+/// A map in storage whose entries are iterated in the order of their keys.
+///
+/// This is meant for order books, auctions and other use cases that need to find the
+/// best price quickly. Use a composite key such as `(price, sequence)` to store
+/// multiple entries with the same price in first-in-first-out order.
+///
+/// All entries are stored under a length-prefixed namespace, which must not be used
+/// by anything else.
+///
+/// ## Examples
+///
+/// ```
+/// # #[cfg(feature = "iterator")] {
+/// use cosmwasm_std::{Decimal, MemoryStorage, Order, SortedKV};
+///
+/// // (price, order id) -> owner
+/// const ASKS: SortedKV<(Decimal, u64), String> = SortedKV::new(b"asks");
+///
+/// let mut storage = MemoryStorage::new();
+/// ASKS.save(&mut storage, &(Decimal::percent(150), 1), &"alice".to_string()).unwrap();
+/// ASKS.save(&mut storage, &(Decimal::percent(120), 2), &"bob".to_string()).unwrap();
+/// ASKS.save(&mut storage, &(Decimal::percent(120), 3), &"carol".to_string()).unwrap();
+///
+/// // the cheapest and oldest ask
+/// let (key, owner) = ASKS.first(&storage).unwrap().unwrap();
+/// assert_eq!(key, (Decimal::percent(120), 2));
+/// assert_eq!(owner, "bob");
+///
+/// let owners: Vec<String> = ASKS
+///     .range(&storage, None, None, Order::Ascending)
+///     .map(|item| item.unwrap().1)
+///     .collect();
+/// assert_eq!(owners, ["bob", "carol", "alice"]);
+/// # }
+/// ```
+pub struct SortedKV<'a, K, V> {
+    namespace: &'a [u8],
+    key_type: PhantomData<K>,
+    value_type: PhantomData<V>,
+}
+
+// Trait bounds on `const fn`s require Rust 1.61, so the constructor lives in its own block
+impl<'a, K, V> SortedKV<'a, K, V> {
+    pub const fn new(namespace: &'a [u8]) -> Self {
+        SortedKV {
+            namespace,
+            key_type: PhantomData,
+            value_type: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> SortedKV<'a, K, V>
+where
+    K: SortKey,
+    V: Serialize + DeserializeOwned,
+{
+    /// The storage key of an entry, which is the length-prefixed namespace followed by the encoded key
+    fn storage_key(&self, key: &K) -> Vec<u8> {
+        let mut out = self.prefix();
+        key.write_sort_bytes(&mut out);
+        out
+    }
+
+    fn prefix(&self) -> Vec<u8> {
+        if self.namespace.len() > 0xFFFF {
+            panic!("only supports namespaces up to length 0xFFFF")
+        }
+        let mut out = Vec::with_capacity(self.namespace.len() + 2);
+        out.extend_from_slice(&(self.namespace.len() as u16).to_be_bytes());
+        out.extend_from_slice(self.namespace);
+        out
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, key: &K, value: &V) -> StdResult<()> {
+        storage.set(&self.storage_key(key), &to_vec(value)?);
+        Ok(())
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, key: &K) {
+        storage.remove(&self.storage_key(key));
+    }
+
+    /// Loads the value of the given key and returns an error if it does not exist
+    pub fn load(&self, storage: &dyn Storage, key: &K) -> StdResult<V> {
+        self.may_load(storage, key)?
+            .ok_or_else(|| StdError::not_found(std::any::type_name::<V>()))
+    }
+
+    /// Loads the value of the given key and returns `None` if it does not exist
+    pub fn may_load(&self, storage: &dyn Storage, key: &K) -> StdResult<Option<V>> {
+        storage
+            .get(&self.storage_key(key))
+            .map(|value| from_slice(&value))
+            .transpose()
+    }
+
+    pub fn has(&self, storage: &dyn Storage, key: &K) -> bool {
+        storage.get(&self.storage_key(key)).is_some()
+    }
+
+    /// Iterates over the entries with `start <= key < end` in key order.
+    /// `None` means unbounded.
+    #[cfg(feature = "iterator")]
+    pub fn range<'b>(
+        &self,
+        storage: &'b dyn Storage,
+        start: Option<&K>,
+        end: Option<&K>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<(K, V)>> + 'b>
+    where
+        K: 'b,
+        V: 'b,
+    {
+        let prefix = self.prefix();
+        let start = match start {
+            Some(start) => self.storage_key(start),
+            None => prefix.clone(),
+        };
+        let end = match end {
+            Some(end) => self.storage_key(end),
+            None => namespace_upper_bound(&prefix),
+        };
+        let prefix_len = prefix.len();
+        let iter = storage
+            .range(Some(&start), Some(&end), order)
+            .map(move |(key, value)| {
+                Ok((K::from_sort_bytes(&key[prefix_len..])?, from_slice(&value)?))
+            });
+        Box::new(iter)
+    }
+
+    /// Returns the entry with the smallest key, e.g. the best ask in an order book
+    #[cfg(feature = "iterator")]
+    pub fn first(&self, storage: &dyn Storage) -> StdResult<Option<(K, V)>> {
+        self.range(storage, None, None, Order::Ascending)
+            .next()
+            .transpose()
+    }
+
+    /// Returns the entry with the largest key, e.g. the best bid in an order book
+    #[cfg(feature = "iterator")]
+    pub fn last(&self, storage: &dyn Storage) -> StdResult<Option<(K, V)>> {
+        self.range(storage, None, None, Order::Descending)
+            .next()
+            .transpose()
+    }
+}
+
+/// The smallest key that is larger than all keys starting with `prefix`
+#[cfg(feature = "iterator")]
+fn namespace_upper_bound(prefix: &[u8]) -> Vec<u8> {
+    let mut out = prefix.to_vec();
+    // The prefix always starts with the 2 byte length, so it cannot consist of 0xFF bytes only
+    // unless the namespace is 0xFFFF bytes of 0xFF, which we don't care about.
+    while let Some(last) = out.pop() {
+        if last < 0xFF {
+            out.push(last + 1);
+            return out;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use std::str::FromStr;
+
+    fn assert_order_preserved<K: SortKey + Ord + Clone + std::fmt::Debug>(mut values: Vec<K>) {
+        values.sort();
+        for pair in values.windows(2) {
+            assert!(
+                pair[0].to_sort_bytes() <= pair[1].to_sort_bytes(),
+                "{:?} vs {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+        for value in values {
+            let bytes = value.to_sort_bytes();
+            assert_eq!(K::from_sort_bytes(&bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn sort_key_preserves_order_of_integers() {
+        assert_order_preserved(vec![0u8, 1, 127, 128, 255]);
+        assert_order_preserved(vec![0u64, 1, 255, 256, 1 << 40, u64::MAX]);
+        assert_order_preserved(vec![0u128, 9, 10, 1 << 64, u128::MAX]);
+        assert_order_preserved(vec![i8::MIN, -1, 0, 1, i8::MAX]);
+        assert_order_preserved(vec![i32::MIN, -256, -255, -1, 0, 1, 255, 256, i32::MAX]);
+        assert_order_preserved(vec![i64::MIN, i64::MIN + 1, -1, 0, i64::MAX]);
+        assert_order_preserved(vec![i128::MIN, -1, 0, 1, i128::MAX]);
+    }
+
+    #[test]
+    fn sort_key_preserves_order_of_math_types() {
+        assert_order_preserved(vec![
+            Uint64::zero(),
+            Uint64::new(255),
+            Uint64::new(256),
+            Uint64::MAX,
+        ]);
+        assert_order_preserved(vec![
+            Uint128::zero(),
+            Uint128::new(9),
+            Uint128::new(10),
+            Uint128::new(100),
+            Uint128::MAX,
+        ]);
+        assert_order_preserved(vec![
+            Uint256::zero(),
+            Uint256::from(255u32),
+            Uint256::from(u128::MAX),
+            Uint256::MAX,
+        ]);
+        assert_order_preserved(vec![
+            Decimal::zero(),
+            Decimal::from_str("0.000000000000000001").unwrap(),
+            Decimal::from_str("0.9").unwrap(),
+            Decimal::one(),
+            Decimal::from_str("10").unwrap(),
+            Decimal::from_str("123456789.5").unwrap(),
+            Decimal::MAX,
+        ]);
+        assert_order_preserved(vec![
+            Uint512::zero(),
+            Uint512::from(u128::MAX),
+            Uint512::MAX,
+        ]);
+        assert_order_preserved(vec![
+            Timestamp::from_nanos(0),
+            Timestamp::from_nanos(256),
+            Timestamp::from_seconds(1),
+        ]);
+        assert_order_preserved(vec![
+            Decimal256::zero(),
+            Decimal256::from_str("0.5").unwrap(),
+            Decimal256::from_str("2").unwrap(),
+            Decimal256::MAX,
+        ]);
+    }
+
+    #[test]
+    fn sort_key_preserves_order_of_tuples() {
+        assert_order_preserved(vec![
+            (Uint128::zero(), u64::MAX),
+            (Uint128::new(1), 0u64),
+            (Uint128::new(1), 1),
+            (Uint128::new(256), 0),
+            (Uint128::MAX, 0),
+        ]);
+        assert_order_preserved(vec![(1u8, -1i32, 5u64), (1, 0, 0), (2, i32::MIN, 0)]);
+    }
+
+    #[test]
+    fn sort_key_preserves_order_of_variable_length_types() {
+        assert_order_preserved(vec![false, true]);
+        assert_order_preserved(
+            [
+                "", "\0", "\0\0", "\0\u{1}", "a", "a\0", "a\0b", "aa", "ab", "b", "\u{ff}",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert_order_preserved(vec![
+            vec![],
+            vec![0x00],
+            vec![0x00, 0xFF],
+            vec![0x01],
+            vec![0xFF, 0x00],
+            vec![0xFF, 0xFF],
+        ]);
+        assert_order_preserved(vec![
+            Binary::from(b"".as_slice()),
+            Binary::from(b"\x00".as_slice()),
+            Binary::from(b"abc".as_slice()),
+        ]);
+        assert_order_preserved(vec![Addr::unchecked("alice"), Addr::unchecked("bob")]);
+    }
+
+    #[test]
+    fn sort_key_encodes_fixed_length_types() {
+        assert_eq!(7u8.to_sort_bytes(), [7]);
+        assert_eq!(0x0102u16.to_sort_bytes(), [1, 2]);
+        assert_eq!((-1i16).to_sort_bytes(), [0x7F, 0xFF]);
+        assert_eq!(u64::MAX.to_sort_bytes().len(), 8);
+        assert_eq!(i128::MIN.to_sort_bytes(), [0; 16]);
+        assert_eq!(Uint256::MAX.to_sort_bytes().len(), 32);
+        assert_eq!(Uint512::MAX.to_sort_bytes().len(), 64);
+        assert_eq!(Decimal::MAX.to_sort_bytes().len(), 16);
+        assert_eq!(
+            Timestamp::from_nanos(1).to_sort_bytes(),
+            [0, 0, 0, 0, 0, 0, 0, 1]
+        );
+        assert_eq!(true.to_sort_bytes(), [1]);
+    }
+
+    #[test]
+    fn sort_key_encodes_variable_length_types() {
+        assert_eq!("".to_string().to_sort_bytes(), [0, 0]);
+        assert_eq!("ab".to_string().to_sort_bytes(), b"ab\0\0");
+        assert_eq!(vec![0u8, 1].to_sort_bytes(), [0, 0xFF, 1, 0, 0]);
+        assert_eq!(Addr::unchecked("a").to_sort_bytes(), b"a\0\0");
+        assert_eq!(("a".to_string(), 1u8).to_sort_bytes(), [b'a', 0, 0, 1]);
+    }
+
+    #[test]
+    fn sort_key_preserves_order_of_tuples_with_variable_length_types() {
+        let mut values = vec![];
+        for owner in ["", "a", "a\0", "ab", "b"] {
+            for id in [0u64, 1, u64::MAX] {
+                values.push((owner.to_string(), id));
+            }
+        }
+        assert_order_preserved(values.clone());
+
+        // all keys with a given first element form a contiguous range
+        let mut encoded: Vec<Vec<u8>> = values.iter().map(SortKey::to_sort_bytes).collect();
+        encoded.sort();
+        let prefix = "a".to_string().to_sort_bytes();
+        let matching: Vec<(String, u64)> = encoded
+            .iter()
+            .filter(|key| key.starts_with(&prefix))
+            .map(|key| SortKey::from_sort_bytes(key).unwrap())
+            .collect();
+        assert_eq!(
+            matching,
+            [
+                ("a".to_string(), 0),
+                ("a".to_string(), 1),
+                ("a".to_string(), u64::MAX)
+            ]
+        );
+
+        assert_order_preserved(vec![
+            (Addr::unchecked("a"), true, "x".to_string(), -1i8),
+            (Addr::unchecked("a"), true, "x".to_string(), 0),
+            (Addr::unchecked("a"), true, "xy".to_string(), i8::MIN),
+            (Addr::unchecked("b"), false, "".to_string(), 0),
+        ]);
+    }
+
+    #[test]
+    fn read_sort_bytes_rejects_invalid_encodings() {
+        // unterminated
+        String::from_sort_bytes(b"ab").unwrap_err();
+        String::from_sort_bytes(b"ab\0").unwrap_err();
+        // invalid escape
+        Vec::<u8>::from_sort_bytes(&[0, 1, 0, 0]).unwrap_err();
+        // invalid UTF-8
+        let err = String::from_sort_bytes(&[0xC0, 0, 0]).unwrap_err();
+        assert!(matches!(err, StdError::InvalidUtf8 { .. }));
+        // invalid bool
+        bool::from_sort_bytes(&[2]).unwrap_err();
+        // trailing bytes
+        String::from_sort_bytes(b"ab\0\0c").unwrap_err();
+        <(String, u8)>::from_sort_bytes(b"ab\0\0").unwrap_err();
+    }
+
+    #[test]
+    fn from_sort_bytes_rejects_wrong_length() {
+        let err = u64::from_sort_bytes(&[0; 7]).unwrap_err();
+        assert!(matches!(err, StdError::ParseErr { .. }));
+        Uint256::from_sort_bytes(&[0; 33]).unwrap_err();
+        <(u8, u32)>::from_sort_bytes(&[0; 4]).unwrap_err();
+        <(u8, u32, u8)>::from_sort_bytes(&[0; 7]).unwrap_err();
+        assert_eq!(
+            <(u8, u32)>::from_sort_bytes(&[1, 0, 0, 0, 2]).unwrap(),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn save_load_remove_works() {
+        let kv: SortedKV<Uint128, String> = SortedKV::new(b"prices");
+        let mut storage = MemoryStorage::new();
+        let key = Uint128::new(500);
+        assert_eq!(kv.may_load(&storage, &key).unwrap(), None);
+        assert!(!kv.has(&storage, &key));
+        assert!(matches!(
+            kv.load(&storage, &key).unwrap_err(),
+            StdError::NotFound { .. }
+        ));
+
+        kv.save(&mut storage, &key, &"foo".to_string()).unwrap();
+        assert_eq!(kv.load(&storage, &key).unwrap(), "foo");
+        assert!(kv.has(&storage, &key));
+
+        kv.remove(&mut storage, &key);
+        assert_eq!(kv.may_load(&storage, &key).unwrap(), None);
+    }
+
+    #[test]
+    fn storage_keys_are_namespaced() {
+        let kv: SortedKV<u16, u8> = SortedKV::new(b"ns");
+        assert_eq!(kv.storage_key(&0x0102), b"\x00\x02ns\x01\x02");
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn range_works() {
+        let bids: SortedKV<(Decimal, u64), u32> = SortedKV::new(b"bids");
+        let other: SortedKV<(Decimal, u64), u32> = SortedKV::new(b"bidsx");
+        let mut storage = MemoryStorage::new();
+        let price = |s| Decimal::from_str(s).unwrap();
+
+        // insert out of order, including prices whose string or little endian encoding sort differently
+        let entries = [
+            ((price("10"), 1), 1),
+            ((price("9"), 2), 2),
+            ((price("100"), 3), 3),
+            ((price("9"), 4), 4),
+            ((price("0.5"), 5), 5),
+        ];
+        for (key, value) in entries {
+            bids.save(&mut storage, &key, &value).unwrap();
+        }
+        other.save(&mut storage, &(price("1"), 0), &999).unwrap();
+
+        let values = |start, end, order| -> Vec<u32> {
+            bids.range(&storage, start, end, order)
+                .map(|item| item.unwrap().1)
+                .collect()
+        };
+        assert_eq!(values(None, None, Order::Ascending), [5, 2, 4, 1, 3]);
+        assert_eq!(values(None, None, Order::Descending), [3, 1, 4, 2, 5]);
+        // start inclusive, end exclusive
+        let start = (price("9"), 0);
+        let end = (price("10"), 1);
+        assert_eq!(values(Some(&start), Some(&end), Order::Ascending), [2, 4]);
+        assert_eq!(values(Some(&start), None, Order::Descending), [3, 1, 4, 2]);
+        assert_eq!(values(None, Some(&start), Order::Ascending), [5]);
+
+        let keys: Vec<(Decimal, u64)> = bids
+            .range(&storage, None, None, Order::Ascending)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(keys[0], (price("0.5"), 5));
+
+        assert_eq!(bids.first(&storage).unwrap(), Some(((price("0.5"), 5), 5)));
+        assert_eq!(bids.last(&storage).unwrap(), Some(((price("100"), 3), 3)));
+
+        let empty: SortedKV<u64, u32> = SortedKV::new(b"empty");
+        assert_eq!(empty.first(&storage).unwrap(), None);
+        assert_eq!(empty.last(&storage).unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn namespace_upper_bound_works() {
+        assert_eq!(namespace_upper_bound(b"\x00\x02ab"), b"\x00\x02ac");
+        assert_eq!(namespace_upper_bound(b"\x00\x02a\xff"), b"\x00\x02b");
+        assert_eq!(namespace_upper_bound(b"\x00\x02\xff\xff"), b"\x00\x03");
+    }
+}
@@ -0,0 +1,392 @@
+use std::cell::RefCell;
+
+use crate::deps::{Deps, DepsMut};
+use crate::errors::{StdError, StdResult};
+#[cfg(feature = "iterator")]
+use crate::iterator::{Order, Record};
+#[cfg(feature = "iterator")]
+use crate::prefixed_storage::range_with_prefix;
+use crate::prefixed_storage::{concat, length_prefixed};
+use crate::query::CustomQuery;
+use crate::results::Empty;
+use crate::traits::{Api, Storage};
+use crate::QuerierWrapper;
+
+/// Dependencies split into views of disjoint storage namespaces, created by [`DepsMut::split`].
+///
+/// This allows passing narrower capabilities to the modules of a larger contract,
+/// e.g. read access to the price data and write access to the order book, without
+/// fighting over a single `&mut dyn Storage`.
+///
+/// Every view stores its keys under its namespace, using the same length-prefixed
+/// encoding as [`PrefixedStorage`](crate::PrefixedStorage). So a module sees its own
+/// keys only, and distinct namespaces never share any keys, even if one is a prefix of
+/// the other. Any number of read-only views can exist at the same time, but only one
+/// writable view. A writable view's namespace cannot be used by any other view at the
+/// same time. This is checked at runtime when a view is created. A namespace becomes
+/// available again when its view is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::testing::mock_dependencies;
+/// use cosmwasm_std::{DepsMut, Deps, StdResult};
+///
+/// fn update_orders(mut deps: DepsMut, price: u8) {
+///     deps.storage.set(b"last", &[price]);
+/// }
+///
+/// fn load_price(deps: Deps) -> u8 {
+///     deps.storage.get(b"atom").map_or(0, |price| price[0])
+/// }
+///
+/// # let mut owned = mock_dependencies();
+/// # let mut deps = owned.as_mut();
+/// let split = deps.split();
+/// let prices = split.read_only(b"prices")?;
+/// let mut orders = split.writable(b"orders")?;
+/// update_orders(orders.deps_mut(), load_price(prices.deps()));
+///
+/// // The namespace of the writable view is in use
+/// assert!(split.read_only(b"orders").is_err());
+/// # StdResult::Ok(())
+/// ```
+pub struct SplitDeps<'a, C: CustomQuery = Empty> {
+    storage: RefCell<&'a mut dyn Storage>,
+    pub api: &'a dyn Api,
+    pub querier: QuerierWrapper<'a, C>,
+    /// The namespaces in use. Slots of dropped views are set to `None`.
+    claims: RefCell<Vec<Option<Claim>>>,
+}
+
+struct Claim {
+    namespace: Vec<u8>,
+    writable: bool,
+}
+
+impl<'a, C: CustomQuery> DepsMut<'a, C> {
+    /// Splits the dependencies into views of disjoint storage namespaces.
+    /// See [`SplitDeps`] for details.
+    pub fn split(&mut self) -> SplitDeps<'_, C> {
+        SplitDeps {
+            storage: RefCell::new(self.storage),
+            api: self.api,
+            querier: self.querier,
+            claims: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<'a, C: CustomQuery> SplitDeps<'a, C> {
+    /// Creates a view that can read the keys in `namespace`
+    pub fn read_only(&self, namespace: &[u8]) -> StdResult<ReadonlyStorageNamespace<'_, 'a, C>> {
+        Ok(ReadonlyStorageNamespace {
+            view: self.claim(namespace, false)?,
+        })
+    }
+
+    /// Creates a view that can read and write the keys in `namespace`.
+    /// Fails if another writable view exists.
+    pub fn writable(&self, namespace: &[u8]) -> StdResult<StorageNamespace<'_, 'a, C>> {
+        Ok(StorageNamespace {
+            view: self.claim(namespace, true)?,
+        })
+    }
+
+    fn claim(&self, namespace: &[u8], writable: bool) -> StdResult<View<'_, 'a, C>> {
+        let mut claims = self.claims.borrow_mut();
+        for claim in claims.iter().flatten() {
+            if writable && claim.writable {
+                return Err(StdError::generic_err(
+                    "Only one writable storage namespace can exist at a time",
+                ));
+            }
+            if claim.namespace == namespace && (writable || claim.writable) {
+                return Err(StdError::generic_err(format!(
+                    "Storage namespace {:?} is in use by a {} view",
+                    String::from_utf8_lossy(namespace),
+                    if claim.writable {
+                        "writable"
+                    } else {
+                        "read-only"
+                    },
+                )));
+            }
+        }
+        claims.push(Some(Claim {
+            namespace: namespace.to_vec(),
+            writable,
+        }));
+        Ok(View {
+            split: self,
+            prefix: length_prefixed(namespace),
+            slot: claims.len() - 1,
+        })
+    }
+}
+
+/// A read-only view of one storage namespace, see [`SplitDeps`].
+///
+/// The storage can only be accessed through [`ReadonlyStorageNamespace::deps`].
+pub struct ReadonlyStorageNamespace<'s, 'a, C: CustomQuery = Empty> {
+    view: View<'s, 'a, C>,
+}
+
+impl<'s, 'a, C: CustomQuery> ReadonlyStorageNamespace<'s, 'a, C> {
+    /// Returns dependencies with this view as storage
+    pub fn deps(&self) -> Deps<'_, C> {
+        self.view.deps()
+    }
+}
+
+/// A writable view of one storage namespace, see [`SplitDeps`].
+///
+/// It implements [`Storage`] with the length-prefixed namespace as key prefix.
+pub struct StorageNamespace<'s, 'a, C: CustomQuery = Empty> {
+    view: View<'s, 'a, C>,
+}
+
+impl<'s, 'a, C: CustomQuery> StorageNamespace<'s, 'a, C> {
+    /// Returns dependencies with this view as storage
+    pub fn deps(&self) -> Deps<'_, C> {
+        self.view.deps()
+    }
+
+    /// Returns mutable dependencies with this view as storage
+    pub fn deps_mut(&mut self) -> DepsMut<'_, C> {
+        let api = self.view.split.api;
+        let querier = self.view.split.querier;
+        DepsMut {
+            storage: &mut self.view,
+            api,
+            querier,
+        }
+    }
+}
+
+impl<'s, 'a, C: CustomQuery> Storage for StorageNamespace<'s, 'a, C> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.view.get(key)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        self.view.range(start, end, order)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.view.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.view.remove(key);
+    }
+}
+
+/// The storage of both kinds of views. It is only handed out mutably by [`StorageNamespace`].
+struct View<'s, 'a, C: CustomQuery> {
+    split: &'s SplitDeps<'a, C>,
+    prefix: Vec<u8>,
+    slot: usize,
+}
+
+impl<'s, 'a, C: CustomQuery> View<'s, 'a, C> {
+    fn deps(&self) -> Deps<'_, C> {
+        Deps {
+            storage: self,
+            api: self.split.api,
+            querier: self.split.querier,
+        }
+    }
+}
+
+impl<'s, 'a, C: CustomQuery> Storage for View<'s, 'a, C> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.split.storage.borrow().get(&concat(&self.prefix, key))
+    }
+
+    /// Collects the records before returning them, since the underlying storage
+    /// is shared between the views.
+    #[cfg(feature = "iterator")]
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        let storage = self.split.storage.borrow();
+        let records: Vec<Record> =
+            range_with_prefix(&**storage, &self.prefix, start, end, order).collect();
+        Box::new(records.into_iter())
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.split
+            .storage
+            .borrow_mut()
+            .set(&concat(&self.prefix, key), value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.split
+            .storage
+            .borrow_mut()
+            .remove(&concat(&self.prefix, key));
+    }
+}
+
+impl<'s, 'a, C: CustomQuery> Drop for View<'s, 'a, C> {
+    fn drop(&mut self) {
+        self.split.claims.borrow_mut()[self.slot] = None;
+    }
+}
+
+/// Returns the smallest key greater than all keys starting with `namespace`,
+/// or None if there is no such key.
+#[cfg(feature = "iterator")]
+pub(crate) fn namespace_upper_bound(namespace: &[u8]) -> Option<Vec<u8>> {
+    let mut bound = namespace.to_vec();
+    while let Some(last) = bound.pop() {
+        if last < 0xff {
+            bound.push(last + 1);
+            return Some(bound);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::mock_dependencies;
+    use crate::{Namespace, ReadonlyPrefixedStorage};
+
+    #[test]
+    fn split_works() {
+        let mut owned = mock_dependencies();
+        owned.storage.set(b"\x00\x06pricesatom", b"12");
+        owned.storage.set(b"other", b"x");
+        let mut deps = owned.as_mut();
+
+        {
+            let split = deps.split();
+            let prices = split.read_only(b"prices").unwrap();
+            let prices2 = split.read_only(b"prices").unwrap();
+            let mut orders = split.writable(b"orders").unwrap();
+
+            assert_eq!(prices.deps().storage.get(b"atom"), Some(b"12".to_vec()));
+            assert_eq!(prices2.deps().storage.get(b"atom"), Some(b"12".to_vec()));
+            assert_eq!(prices.deps().storage.get(b"other"), None);
+            assert_eq!(orders.get(b"atom"), None);
+
+            orders.deps_mut().storage.set(b"1", b"buy");
+            orders.set(b"2", b"sell");
+            orders.remove(b"2");
+            assert_eq!(orders.get(b"1"), Some(b"buy".to_vec()));
+            assert_eq!(orders.deps().storage.get(b"1"), Some(b"buy".to_vec()));
+        }
+
+        assert_eq!(deps.storage.get(b"\x00\x06orders1"), Some(b"buy".to_vec()));
+        assert_eq!(deps.storage.get(b"\x00\x06orders2"), None);
+    }
+
+    struct Orders;
+
+    impl Namespace for Orders {
+        const NAMESPACE: &'static [u8] = b"orders";
+    }
+
+    #[test]
+    fn split_uses_the_key_layout_of_prefixed_storage() {
+        let mut owned = mock_dependencies();
+        let mut deps = owned.as_mut();
+        deps.split()
+            .writable(Orders::NAMESPACE)
+            .unwrap()
+            .set(b"1", b"buy");
+        let orders = ReadonlyPrefixedStorage::<Orders>::new(deps.storage);
+        assert_eq!(orders.get(b"1"), Some(b"buy".to_vec()));
+    }
+
+    #[test]
+    fn split_checks_namespaces() {
+        let mut owned = mock_dependencies();
+        let mut deps = owned.as_mut();
+        let split = deps.split();
+
+        let writer = split.writable(b"orders").unwrap();
+        let err = split.writable(b"other").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Only one writable storage namespace can exist at a time"
+        );
+        let err = split.read_only(b"orders").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Storage namespace \"orders\" is in use by a writable view"
+        );
+        // Length-prefixed namespaces are disjoint even if one is a prefix of the other
+        let _book = split.read_only(b"orders/book").unwrap();
+        let _order = split.read_only(b"order").unwrap();
+        let _prices = split.read_only(b"prices").unwrap();
+
+        // dropping a view releases its namespace
+        drop(writer);
+        split.read_only(b"orders").unwrap();
+        let err = split.writable(b"prices").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Storage namespace \"prices\" is in use by a read-only view"
+        );
+        split.writable(b"prices/eur").unwrap();
+    }
+
+    #[test]
+    fn namespaces_that_are_prefixes_do_not_collide() {
+        let mut owned = mock_dependencies();
+        let mut deps = owned.as_mut();
+        let split = deps.split();
+        let mut short = split.writable(b"a").unwrap();
+        short.set(b"bc", b"short");
+        drop(short);
+        let long = split.read_only(b"ab").unwrap();
+        assert_eq!(long.deps().storage.get(b"c"), None);
+    }
+
+    #[cfg(feature = "iterator")]
+    #[test]
+    fn range_works() {
+        let mut owned = mock_dependencies();
+        owned.storage.set(b"\x00\x01a\xff", b"before");
+        owned.storage.set(b"\x00\x01b1", b"one");
+        owned.storage.set(b"\x00\x01b2", b"two");
+        owned.storage.set(b"\x00\x01b3", b"three");
+        owned.storage.set(b"\x00\x01c", b"after");
+        let mut deps = owned.as_mut();
+        let split = deps.split();
+        let view = split.read_only(b"b").unwrap();
+        let storage = view.deps().storage;
+
+        let all: Vec<Record> = storage.range(None, None, Order::Ascending).collect();
+        assert_eq!(
+            all,
+            vec![
+                (b"1".to_vec(), b"one".to_vec()),
+                (b"2".to_vec(), b"two".to_vec()),
+                (b"3".to_vec(), b"three".to_vec()),
+            ]
+        );
+        let some: Vec<Record> = storage
+            .range(Some(b"2"), Some(b"3"), Order::Descending)
+            .collect();
+        assert_eq!(some, vec![(b"2".to_vec(), b"two".to_vec())]);
+
+        assert_eq!(namespace_upper_bound(b"b\xff"), Some(b"c".to_vec()));
+        assert_eq!(namespace_upper_bound(b"\xff\xff"), None);
+    }
+}
@@ -278,6 +278,20 @@ mod tests {
                 ]
             );
         }
+
+        // empty key as start is the same as unbounded
+        {
+            let iter = store.range(Some(b""), None, Order::Ascending);
+            let elements: Vec<Record> = iter.collect();
+            let all: Vec<Record> = store.range(None, None, Order::Ascending).collect();
+            assert_eq!(elements, all);
+        }
+
+        // empty key as end is an empty range
+        {
+            let iter = store.range(None, Some(b""), Order::Descending);
+            assert_eq!(iter.count(), 0);
+        }
     }
 
     #[test]
@@ -0,0 +1,168 @@
+use thiserror::Error;
+
+/// An error for strings whose length in bytes is outside of the allowed range
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum StringLengthError {
+    #[error("String too short: {length} bytes, min {min} bytes")]
+    TooShort { length: usize, min: usize },
+    #[error("String too long: {length} bytes, max {max} bytes")]
+    TooLong { length: usize, max: usize },
+}
+
+/// Checks that the UTF-8 encoded length of `s` is in the range `min_bytes..=max_bytes`.
+///
+/// Use this for user-supplied labels, memos or metadata that are stored on chain.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{validate_byte_length, StringLengthError};
+///
+/// assert_eq!(validate_byte_length("my label", 1, 16), Ok(()));
+/// assert_eq!(
+///     validate_byte_length("", 1, 16),
+///     Err(StringLengthError::TooShort { length: 0, min: 1 })
+/// );
+/// // "€" is 3 bytes long
+/// assert_eq!(
+///     validate_byte_length("€€€", 1, 8),
+///     Err(StringLengthError::TooLong { length: 9, max: 8 })
+/// );
+/// ```
+pub fn validate_byte_length(
+    s: &str,
+    min_bytes: usize,
+    max_bytes: usize,
+) -> Result<(), StringLengthError> {
+    let length = s.len();
+    if length < min_bytes {
+        return Err(StringLengthError::TooShort {
+            length,
+            min: min_bytes,
+        });
+    }
+    if length > max_bytes {
+        return Err(StringLengthError::TooLong {
+            length,
+            max: max_bytes,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the longest prefix of `s` that is at most `max_bytes` long
+/// and does not split a multi-byte character.
+///
+/// Unlike `&s[..max_bytes]` this never panics.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::truncate_str;
+///
+/// assert_eq!(truncate_str("hello", 3), "hel");
+/// assert_eq!(truncate_str("hello", 10), "hello");
+/// // "ü" is 2 bytes long and would be split at 2
+/// assert_eq!(truncate_str("Müller", 2), "M");
+/// ```
+pub fn truncate_str(s: &str, max_bytes: usize) -> &str {
+    &s[..floor_char_boundary(s, max_bytes)]
+}
+
+/// Shortens `s` in place to at most `max_bytes`, see [`truncate_str`].
+pub fn truncate_string(s: &mut String, max_bytes: usize) {
+    let end = floor_char_boundary(s, max_bytes);
+    s.truncate(end);
+}
+
+/// The largest char boundary in `s` that is less than or equal to `index`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    // A UTF-8 encoded char is at most 4 bytes long, so this loop runs at most 3 times
+    let mut end = index;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_byte_length_works() {
+        assert_eq!(validate_byte_length("", 0, 0), Ok(()));
+        assert_eq!(validate_byte_length("abc", 3, 3), Ok(()));
+        assert_eq!(validate_byte_length("abc", 0, 100), Ok(()));
+        assert_eq!(
+            validate_byte_length("abc", 4, 100),
+            Err(StringLengthError::TooShort { length: 3, min: 4 })
+        );
+        assert_eq!(
+            validate_byte_length("abc", 0, 2),
+            Err(StringLengthError::TooLong { length: 3, max: 2 })
+        );
+
+        // length is counted in bytes, not chars
+        assert_eq!(
+            validate_byte_length("🦀", 2, 3),
+            Err(StringLengthError::TooLong { length: 4, max: 3 })
+        );
+    }
+
+    #[test]
+    fn string_length_error_displays() {
+        assert_eq!(
+            StringLengthError::TooShort { length: 0, min: 1 }.to_string(),
+            "String too short: 0 bytes, min 1 bytes"
+        );
+        assert_eq!(
+            StringLengthError::TooLong { length: 9, max: 8 }.to_string(),
+            "String too long: 9 bytes, max 8 bytes"
+        );
+    }
+
+    #[test]
+    fn truncate_str_works() {
+        assert_eq!(truncate_str("", 0), "");
+        assert_eq!(truncate_str("", 5), "");
+        assert_eq!(truncate_str("abc", 0), "");
+        assert_eq!(truncate_str("abc", 2), "ab");
+        assert_eq!(truncate_str("abc", 3), "abc");
+        assert_eq!(truncate_str("abc", usize::MAX), "abc");
+
+        // 1, 2, 3 and 4 byte chars
+        let s = "aü€🦀";
+        assert_eq!(s.len(), 10);
+        let expected = [
+            "",
+            "a",
+            "a",
+            "aü",
+            "aü",
+            "aü",
+            "aü€",
+            "aü€",
+            "aü€",
+            "aü€",
+            "aü€🦀",
+        ];
+        for (max_bytes, expected) in expected.iter().enumerate() {
+            assert_eq!(truncate_str(s, max_bytes), *expected, "{}", max_bytes);
+        }
+    }
+
+    #[test]
+    fn truncate_string_works() {
+        let mut s = String::from("Grüße");
+        truncate_string(&mut s, 4);
+        assert_eq!(s, "Grü");
+        truncate_string(&mut s, 3);
+        assert_eq!(s, "Gr");
+        truncate_string(&mut s, 100);
+        assert_eq!(s, "Gr");
+    }
+}
@@ -0,0 +1,273 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::errors::{StdError, StdResult};
+use crate::iterator::Order;
+use crate::sorted_kv::SortedKV;
+use crate::timestamp::Timestamp;
+use crate::traits::Storage;
+
+/// A queue in storage whose entries are due at a given time, e.g. unbonding requests,
+/// expiring orders or scheduled jobs.
+///
+/// Entries are returned in the order of their times. Entries with the same time are
+/// returned in the order they were pushed. Every entry is identified by its time and a
+/// sequence number, which is unique among the queued entries with the same time.
+///
+/// All entries are stored in a [`SortedKV`] with the given namespace, which must not be used
+/// by anything else.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{MemoryStorage, TimeQueue, Timestamp};
+///
+/// const UNBONDING: TimeQueue<String> = TimeQueue::new(b"unbonding");
+///
+/// let mut storage = MemoryStorage::new();
+/// let now = Timestamp::from_seconds(1_000);
+/// UNBONDING.push(&mut storage, now.plus_seconds(20), &"bob".to_string()).unwrap();
+/// UNBONDING.push(&mut storage, now.plus_seconds(10), &"alice".to_string()).unwrap();
+/// UNBONDING.push(&mut storage, now.plus_seconds(10), &"carol".to_string()).unwrap();
+///
+/// let due = UNBONDING.pop_due(&mut storage, now.plus_seconds(15), 10).unwrap();
+/// let names: Vec<String> = due.into_iter().map(|(_, name)| name).collect();
+/// assert_eq!(names, ["alice", "carol"]);
+/// assert_eq!(UNBONDING.len(&storage), 1);
+/// ```
+pub struct TimeQueue<'a, T> {
+    entries: SortedKV<'a, TimeQueueId, T>,
+}
+
+/// Identifies an entry of a [`TimeQueue`] by its time and sequence number
+pub type TimeQueueId = (Timestamp, u64);
+
+impl<'a, T> TimeQueue<'a, T> {
+    pub const fn new(namespace: &'a [u8]) -> Self {
+        TimeQueue {
+            entries: SortedKV::new(namespace),
+        }
+    }
+}
+
+/// The first key after all keys with the given time, or `None` for the largest time
+fn end_of(time: Timestamp) -> Option<TimeQueueId> {
+    time.nanos()
+        .checked_add(1)
+        .map(|nanos| (Timestamp::from_nanos(nanos), 0))
+}
+
+impl<'a, T> TimeQueue<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Adds an entry that is due at `time` and returns its id `(time, sequence)`
+    pub fn push(
+        &self,
+        storage: &mut dyn Storage,
+        time: Timestamp,
+        value: &T,
+    ) -> StdResult<TimeQueueId> {
+        let last = self
+            .entries
+            .range(
+                storage,
+                Some(&(time, 0)),
+                end_of(time).as_ref(),
+                Order::Descending,
+            )
+            .next()
+            .transpose()?;
+        let sequence = match last {
+            Some(((_, sequence), _)) => sequence
+                .checked_add(1)
+                .ok_or_else(|| StdError::generic_err("TimeQueue sequence overflow"))?,
+            None => 0,
+        };
+        let id = (time, sequence);
+        self.entries.save(storage, &id, value)?;
+        Ok(id)
+    }
+
+    /// Removes the entry with the given id, e.g. to cancel a scheduled job
+    pub fn remove(&self, storage: &mut dyn Storage, id: &TimeQueueId) {
+        self.entries.remove(storage, id);
+    }
+
+    /// Loads the entry with the given id
+    pub fn may_load(&self, storage: &dyn Storage, id: &TimeQueueId) -> StdResult<Option<T>> {
+        self.entries.may_load(storage, id)
+    }
+
+    /// Returns the entry that is due first without removing it
+    pub fn peek(&self, storage: &dyn Storage) -> StdResult<Option<(TimeQueueId, T)>> {
+        self.entries.first(storage)
+    }
+
+    /// Returns up to `limit` entries that are due at or before `now` without removing them
+    pub fn due(
+        &self,
+        storage: &dyn Storage,
+        now: Timestamp,
+        limit: usize,
+    ) -> StdResult<Vec<(TimeQueueId, T)>> {
+        self.entries
+            .range(storage, None, end_of(now).as_ref(), Order::Ascending)
+            .take(limit)
+            .collect()
+    }
+
+    /// Removes and returns up to `limit` entries that are due at or before `now`.
+    ///
+    /// The entries are the first ones in queue order, so repeated calls eventually process
+    /// all due entries. Entries due after `now` are never returned.
+    pub fn pop_due(
+        &self,
+        storage: &mut dyn Storage,
+        now: Timestamp,
+        limit: usize,
+    ) -> StdResult<Vec<(Timestamp, T)>> {
+        let due = self.due(storage, now, limit)?;
+        Ok(due
+            .into_iter()
+            .map(|(id, value)| {
+                self.entries.remove(storage, &id);
+                (id.0, value)
+            })
+            .collect())
+    }
+
+    /// Iterates over all entries in queue order
+    pub fn iter<'b>(
+        &self,
+        storage: &'b dyn Storage,
+    ) -> Box<dyn Iterator<Item = StdResult<(TimeQueueId, T)>> + 'b>
+    where
+        T: 'b,
+    {
+        self.entries.range(storage, None, None, Order::Ascending)
+    }
+
+    /// The number of entries, which iterates over the whole queue
+    pub fn len(&self, storage: &dyn Storage) -> usize {
+        self.iter(storage).count()
+    }
+
+    pub fn is_empty(&self, storage: &dyn Storage) -> bool {
+        self.iter(storage).next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    const QUEUE: TimeQueue<u32> = TimeQueue::new(b"queue");
+
+    fn time(seconds: u64) -> Timestamp {
+        Timestamp::from_seconds(seconds)
+    }
+
+    #[test]
+    fn push_assigns_sequence_per_time() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(
+            QUEUE.push(&mut storage, time(10), &1).unwrap(),
+            (time(10), 0)
+        );
+        assert_eq!(
+            QUEUE.push(&mut storage, time(10), &2).unwrap(),
+            (time(10), 1)
+        );
+        assert_eq!(QUEUE.push(&mut storage, time(5), &3).unwrap(), (time(5), 0));
+        assert_eq!(
+            QUEUE.push(&mut storage, time(10), &4).unwrap(),
+            (time(10), 2)
+        );
+        // the next nanosecond is a different time
+        let next = time(10).plus_nanos(1);
+        assert_eq!(QUEUE.push(&mut storage, next, &5).unwrap(), (next, 0));
+
+        assert_eq!(QUEUE.len(&storage), 5);
+        assert_eq!(QUEUE.peek(&storage).unwrap(), Some(((time(5), 0), 3)));
+        assert_eq!(QUEUE.may_load(&storage, &(time(10), 1)).unwrap(), Some(2));
+    }
+
+    #[test]
+    fn pop_due_works() {
+        let mut storage = MemoryStorage::new();
+        for (seconds, value) in [(30, 1), (10, 2), (20, 3), (10, 4), (20, 5)] {
+            QUEUE.push(&mut storage, time(seconds), &value).unwrap();
+        }
+
+        assert_eq!(QUEUE.pop_due(&mut storage, time(9), 10).unwrap(), []);
+        assert_eq!(
+            QUEUE.pop_due(&mut storage, time(20), 3).unwrap(),
+            [(time(10), 2), (time(10), 4), (time(20), 3)]
+        );
+        // entries due exactly now are included
+        assert_eq!(
+            QUEUE.pop_due(&mut storage, time(20), 3).unwrap(),
+            [(time(20), 5)]
+        );
+        assert_eq!(QUEUE.pop_due(&mut storage, time(29), 3).unwrap(), []);
+        assert_eq!(QUEUE.len(&storage), 1);
+        assert_eq!(
+            QUEUE
+                .pop_due(&mut storage, Timestamp::from_nanos(u64::MAX), 3)
+                .unwrap(),
+            [(time(30), 1)]
+        );
+        assert!(QUEUE.is_empty(&storage));
+    }
+
+    #[test]
+    fn push_after_pop_keeps_order() {
+        let mut storage = MemoryStorage::new();
+        QUEUE.push(&mut storage, time(10), &1).unwrap();
+        QUEUE.push(&mut storage, time(10), &2).unwrap();
+        QUEUE.pop_due(&mut storage, time(10), 1).unwrap();
+        QUEUE.push(&mut storage, time(10), &3).unwrap();
+
+        let values: Vec<u32> = QUEUE.iter(&storage).map(|item| item.unwrap().1).collect();
+        assert_eq!(values, [2, 3]);
+    }
+
+    #[test]
+    fn due_does_not_remove() {
+        let mut storage = MemoryStorage::new();
+        QUEUE.push(&mut storage, time(10), &1).unwrap();
+        QUEUE.push(&mut storage, time(20), &2).unwrap();
+        assert_eq!(
+            QUEUE.due(&storage, time(15), 10).unwrap(),
+            [((time(10), 0), 1)]
+        );
+        assert_eq!(QUEUE.len(&storage), 2);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut storage = MemoryStorage::new();
+        let id = QUEUE.push(&mut storage, time(10), &1).unwrap();
+        QUEUE.push(&mut storage, time(10), &2).unwrap();
+        QUEUE.remove(&mut storage, &id);
+        assert_eq!(QUEUE.may_load(&storage, &id).unwrap(), None);
+        assert_eq!(
+            QUEUE.pop_due(&mut storage, time(10), 10).unwrap(),
+            [(time(10), 2)]
+        );
+    }
+
+    #[test]
+    fn max_time_works() {
+        let mut storage = MemoryStorage::new();
+        let max = Timestamp::from_nanos(u64::MAX);
+        assert_eq!(QUEUE.push(&mut storage, max, &1).unwrap(), (max, 0));
+        assert_eq!(QUEUE.push(&mut storage, max, &2).unwrap(), (max, 1));
+        assert_eq!(
+            QUEUE.pop_due(&mut storage, max, 10).unwrap(),
+            [(max, 1), (max, 2)]
+        );
+    }
+}
@@ -62,6 +62,17 @@ impl Timestamp {
         self.0.u64()
     }
 
+    /// Returns a big endian encoding of the nanoseconds whose lexicographical byte order equals
+    /// the chronological order. Use this for storage keys that should be iterated in time order.
+    pub const fn to_be_key(self) -> [u8; 8] {
+        self.0.to_be_key()
+    }
+
+    /// Creates a timestamp from an encoding created by [`Timestamp::to_be_key`].
+    pub const fn from_be_key(key: [u8; 8]) -> Self {
+        Timestamp(Uint64::from_be_key(key))
+    }
+
     /// Returns seconds since epoch (truncate nanoseconds)
     #[inline]
     pub fn seconds(&self) -> u64 {
@@ -209,4 +220,21 @@ mod tests {
         let embedded = format!("Time: {}", Timestamp::from_nanos(100000000000));
         assert_eq!(embedded, "Time: 100.000000000");
     }
+
+    #[test]
+    fn timestamp_be_key_works() {
+        let values = [
+            Timestamp::from_nanos(0),
+            Timestamp::from_nanos(255),
+            Timestamp::from_nanos(256),
+            Timestamp::from_seconds(1_000_000_000),
+            Timestamp::from_nanos(u64::MAX),
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0].to_be_key() < pair[1].to_be_key());
+        }
+        for value in values {
+            assert_eq!(Timestamp::from_be_key(value.to_be_key()), value);
+        }
+    }
 }
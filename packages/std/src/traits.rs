@@ -5,6 +5,7 @@ use std::ops::Deref;
 use crate::addresses::{Addr, CanonicalAddr};
 use crate::binary::Binary;
 use crate::coins::Coin;
+use crate::contract_version::{ContractVersion, CONTRACT_VERSION_KEY};
 use crate::errors::{RecoverPubkeyError, StdError, StdResult, VerificationError};
 #[cfg(feature = "iterator")]
 use crate::iterator::{Order, Record};
@@ -18,8 +19,10 @@ use crate::query::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, Delegation,
     DelegationResponse, FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(feature = "cosmwasm_1_2")]
+use crate::query::{HostInfoResponse, StorageUsageResponse};
 use crate::results::{ContractResult, Empty, SystemResult};
-use crate::serde::{from_binary, to_binary, to_vec};
+use crate::serde::{from_binary, from_slice, to_binary, to_vec};
 use crate::ContractInfoResponse;
 
 /// Storage provides read and write access to a persistent storage.
@@ -37,7 +40,10 @@ pub trait Storage {
     ///
     /// The bound `start` is inclusive and `end` is exclusive.
     ///
-    /// If `start` is lexicographically greater than or equal to `end`, an empty range is described, mo matter of the order.
+    /// If `start` is lexicographically greater than or equal to `end`, an empty range is described, no matter the order.
+    ///
+    /// Keys are compared bytewise. The empty key is the smallest key, so `Some(b"")` as `start`
+    /// is the same as `None`, and `Some(b"")` as `end` describes an empty range.
     fn range<'a>(
         &'a self,
         start: Option<&[u8]>,
@@ -248,6 +254,27 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         self.query(&request)
     }
 
+    /// Given a contract address, query the current size of that contract's storage.
+    /// Use `env.contract.address` to query the contract's own footprint.
+    #[cfg(feature = "cosmwasm_1_2")]
+    pub fn query_storage_usage(
+        &self,
+        contract_addr: impl Into<String>,
+    ) -> StdResult<StorageUsageResponse> {
+        let request = WasmQuery::StorageUsage {
+            contract_addr: contract_addr.into(),
+        }
+        .into();
+        self.query(&request)
+    }
+
+    /// Queries the CosmWasm version and capabilities of the host
+    #[cfg(feature = "cosmwasm_1_2")]
+    pub fn query_host_info(&self) -> StdResult<HostInfoResponse> {
+        let request = WasmQuery::HostInfo {}.into();
+        self.query(&request)
+    }
+
     // this queries the raw storage from another wasm contract.
     // you must know the exact layout and are implementation dependent
     // (not tied to an interface like query_wasm_smart)
@@ -300,6 +327,21 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         self.query(&request)
     }
 
+    /// Queries the name and version another contract stored with [`set_contract_version`].
+    ///
+    /// This is a raw query of [`CONTRACT_VERSION_KEY`], so it also works for contracts
+    /// using cw2. Returns `None` if the contract did not store its version.
+    ///
+    /// [`set_contract_version`]: crate::set_contract_version
+    pub fn query_contract_version(
+        &self,
+        contract_addr: impl Into<String>,
+    ) -> StdResult<Option<ContractVersion>> {
+        self.query_wasm_raw(contract_addr, CONTRACT_VERSION_KEY)?
+            .map(|data| from_slice(&data))
+            .transpose()
+    }
+
     #[cfg(feature = "staking")]
     pub fn query_all_validators(&self) -> StdResult<Vec<Validator>> {
         let request = StakingQuery::AllValidators {}.into();
@@ -357,7 +399,7 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
 mod tests {
     use super::*;
     use crate::mock::MockQuerier;
-    use crate::{coins, from_slice, Uint128};
+    use crate::{coins, Uint128};
 
     // this is a simple demo helper to prove we can use it
     fn demo_helper(_querier: &dyn Querier) -> u64 {
@@ -485,4 +527,85 @@ mod tests {
             } if msg == "Querier system error: No such contract: foobar"
         ));
     }
+
+    #[test]
+    fn query_contract_version_works() {
+        const ACCT: &str = "foobar";
+
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_wasm(|q| -> QuerierResult {
+            match q {
+                WasmQuery::Raw { contract_addr, key } if contract_addr == ACCT => {
+                    assert_eq!(key.as_slice(), b"contract_info");
+                    let version = ContractVersion {
+                        contract: "crates.io:foo".to_string(),
+                        version: "1.0.0".to_string(),
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&version).unwrap()))
+                }
+                // contracts that did not store a version
+                WasmQuery::Raw { .. } => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+                _ => panic!("Unexpected query"),
+            }
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let version = wrapper.query_contract_version(ACCT).unwrap().unwrap();
+        assert_eq!(version.contract, "crates.io:foo");
+        assert_eq!(version.version, "1.0.0");
+        assert_eq!(wrapper.query_contract_version("other").unwrap(), None);
+    }
+
+    #[cfg(feature = "cosmwasm_1_2")]
+    #[test]
+    fn query_storage_usage_works() {
+        const ACCT: &str = "foobar";
+
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_wasm(|q| -> QuerierResult {
+            match q {
+                WasmQuery::StorageUsage { contract_addr } if contract_addr == ACCT => {
+                    let response = StorageUsageResponse::new(3, 120);
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                }
+                _ => SystemResult::Err(crate::SystemError::NoSuchContract {
+                    addr: ACCT.to_string(),
+                }),
+            }
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let usage = wrapper.query_storage_usage(ACCT).unwrap();
+        assert_eq!(usage.key_count, 3);
+        assert_eq!(usage.total_bytes, 120);
+
+        wrapper.query_storage_usage("unknown").unwrap_err();
+    }
+
+    #[cfg(feature = "cosmwasm_1_2")]
+    #[test]
+    fn query_host_info_works() {
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let info = wrapper.query_host_info().unwrap();
+        assert_eq!(info.cosmwasm_version, env!("CARGO_PKG_VERSION"));
+        assert!(info.has_capability("cosmwasm_1_2"));
+        assert!(!info.has_capability("token_factory"));
+
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_wasm(|q| -> QuerierResult {
+            match q {
+                WasmQuery::HostInfo {} => {
+                    let response =
+                        HostInfoResponse::new("1.2.0", vec!["token_factory".to_string()]);
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                }
+                _ => panic!("Unexpected query"),
+            }
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let info = wrapper.query_host_info().unwrap();
+        assert_eq!(info.cosmwasm_version, "1.2.0");
+        assert!(info.has_capability("token_factory"));
+    }
 }
@@ -0,0 +1,295 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::errors::OverflowError;
+use crate::{Decimal, Timestamp, Uint128, Uint256};
+
+/// Keeps track of the cumulative price over time to compute time-weighted average
+/// prices (TWAP) over windows ending at the current time.
+///
+/// A recorded price is valid until the next price is recorded. The accumulator keeps the last
+/// `capacity` observations, so the longest window it can average over depends on how
+/// often prices are recorded.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{Decimal, Timestamp, TwapAccumulator};
+///
+/// let start = Timestamp::from_seconds(1_000);
+/// let mut twap = TwapAccumulator::new(10);
+/// twap.record(start, Decimal::percent(100)).unwrap();
+/// twap.record(start.plus_seconds(30), Decimal::percent(200)).unwrap();
+///
+/// let average = twap.average(start.plus_seconds(60), 60).unwrap();
+/// assert_eq!(average, Decimal::percent(150));
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TwapAccumulator {
+    capacity: u32,
+    observations: Vec<TwapObservation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TwapObservation {
+    pub time: Timestamp,
+    /// The price from `time` on
+    pub price: Decimal,
+    /// The sum of price atomics times nanoseconds from the first observation until `time`
+    pub cumulative: Uint256,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TwapError {
+    #[error("Observation time {time} is before the last observation at {last}")]
+    TimeNotIncreasing { last: Timestamp, time: Timestamp },
+    #[error("Window must not be empty")]
+    EmptyWindow,
+    #[error("No observations available for the start of the window at {start}")]
+    WindowNotCovered { start: Timestamp },
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+}
+
+impl TwapAccumulator {
+    /// Creates an empty accumulator which keeps up to `capacity` observations.
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: u32) -> Self {
+        assert!(capacity > 0, "TWAP capacity must not be 0");
+        TwapAccumulator {
+            capacity,
+            observations: vec![],
+        }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// The stored observations, oldest first
+    pub fn observations(&self) -> &[TwapObservation] {
+        &self.observations
+    }
+
+    /// The most recently recorded price
+    pub fn last_price(&self) -> Option<Decimal> {
+        self.observations
+            .last()
+            .map(|observation| observation.price)
+    }
+
+    /// Records `price` as valid from `now` on. Recording a price at the time of the last
+    /// observation replaces it, recording one before it fails.
+    pub fn record(&mut self, now: Timestamp, price: Decimal) -> Result<(), TwapError> {
+        let cumulative = match self.observations.last() {
+            Some(last) if now < last.time => {
+                return Err(TwapError::TimeNotIncreasing {
+                    last: last.time,
+                    time: now,
+                })
+            }
+            Some(last) if now == last.time => {
+                self.observations.last_mut().unwrap().price = price;
+                return Ok(());
+            }
+            Some(_) => self.cumulative_at(now)?,
+            None => Uint256::zero(),
+        };
+        if self.observations.len() >= self.capacity as usize {
+            self.observations.remove(0);
+        }
+        self.observations.push(TwapObservation {
+            time: now,
+            price,
+            cumulative,
+        });
+        Ok(())
+    }
+
+    /// Returns the cumulative price at `time`, which must not be before the oldest observation.
+    pub fn cumulative_at(&self, time: Timestamp) -> Result<Uint256, TwapError> {
+        // The index of the first observation after time
+        let next = self
+            .observations
+            .partition_point(|observation| observation.time <= time);
+        if next == 0 {
+            return Err(TwapError::WindowNotCovered { start: time });
+        }
+        let observation = &self.observations[next - 1];
+        let elapsed = time.nanos() - observation.time.nanos();
+        let cumulative = observation
+            .cumulative
+            .checked_add(observation.price.atomics().full_mul(elapsed))?;
+        Ok(cumulative)
+    }
+
+    /// Returns the time-weighted average price over the `window_seconds` ending at `now`,
+    /// rounded down.
+    pub fn average(&self, now: Timestamp, window_seconds: u64) -> Result<Decimal, TwapError> {
+        if window_seconds == 0 {
+            return Err(TwapError::EmptyWindow);
+        }
+        let window_nanos = window_seconds
+            .checked_mul(1_000_000_000)
+            .filter(|&nanos| nanos <= now.nanos())
+            .ok_or(TwapError::WindowNotCovered {
+                start: Timestamp::from_nanos(0),
+            })?;
+        let start = now.minus_nanos(window_nanos);
+        let from = self.cumulative_at(start)?;
+        let sum = self.cumulative_at(now)? - from;
+        let average = sum / Uint256::from(window_nanos);
+        // The average is never larger than the largest observed price
+        Ok(Decimal::new(Uint128::try_from(average).unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(seconds: u64) -> Timestamp {
+        Timestamp::from_seconds(1_600_000_000).plus_seconds(seconds)
+    }
+
+    #[test]
+    fn record_works() {
+        let mut twap = TwapAccumulator::new(3);
+        assert_eq!(twap.capacity(), 3);
+        assert_eq!(twap.last_price(), None);
+
+        twap.record(t(0), Decimal::percent(100)).unwrap();
+        twap.record(t(10), Decimal::percent(200)).unwrap();
+        assert_eq!(twap.last_price(), Some(Decimal::percent(200)));
+        assert_eq!(
+            twap.observations(),
+            &[
+                TwapObservation {
+                    time: t(0),
+                    price: Decimal::percent(100),
+                    cumulative: Uint256::zero(),
+                },
+                TwapObservation {
+                    time: t(10),
+                    price: Decimal::percent(200),
+                    cumulative: Uint256::from(10_000_000_000_000_000_000_000_000_000u128),
+                },
+            ]
+        );
+
+        // same time replaces the price
+        twap.record(t(10), Decimal::percent(300)).unwrap();
+        assert_eq!(twap.observations().len(), 2);
+        assert_eq!(twap.last_price(), Some(Decimal::percent(300)));
+
+        // earlier time fails
+        assert_eq!(
+            twap.record(t(9), Decimal::percent(100)),
+            Err(TwapError::TimeNotIncreasing {
+                last: t(10),
+                time: t(9)
+            })
+        );
+    }
+
+    #[test]
+    fn record_drops_oldest_observations() {
+        let mut twap = TwapAccumulator::new(2);
+        twap.record(t(0), Decimal::percent(100)).unwrap();
+        twap.record(t(10), Decimal::percent(200)).unwrap();
+        twap.record(t(20), Decimal::percent(300)).unwrap();
+        let times: Vec<_> = twap.observations().iter().map(|o| o.time).collect();
+        assert_eq!(times, [t(10), t(20)]);
+        // the cumulative value still includes the dropped observation
+        assert_eq!(
+            twap.observations()[1].cumulative,
+            Uint256::from(30_000_000_000_000_000_000_000_000_000u128)
+        );
+    }
+
+    #[test]
+    fn average_works() {
+        let mut twap = TwapAccumulator::new(10);
+        twap.record(t(0), Decimal::percent(100)).unwrap();
+        twap.record(t(30), Decimal::percent(200)).unwrap();
+        twap.record(t(40), Decimal::percent(50)).unwrap();
+
+        assert_eq!(twap.average(t(30), 30).unwrap(), Decimal::percent(100));
+        assert_eq!(twap.average(t(40), 10).unwrap(), Decimal::percent(200));
+        assert_eq!(twap.average(t(40), 40).unwrap(), Decimal::percent(125));
+        assert_eq!(twap.average(t(60), 20).unwrap(), Decimal::percent(50));
+        // windows not aligned with observations
+        assert_eq!(twap.average(t(35), 10).unwrap(), Decimal::percent(150));
+        assert_eq!(twap.average(t(50), 20).unwrap(), Decimal::percent(125));
+    }
+
+    #[test]
+    fn average_rounds_down() {
+        let mut twap = TwapAccumulator::new(10);
+        twap.record(t(0), Decimal::raw(1)).unwrap();
+        twap.record(t(1), Decimal::raw(2)).unwrap();
+        assert_eq!(twap.average(t(3), 3).unwrap(), Decimal::raw(1));
+        assert_eq!(twap.average(t(2), 2).unwrap(), Decimal::raw(1));
+    }
+
+    #[test]
+    fn average_handles_extreme_values() {
+        let mut twap = TwapAccumulator::new(10);
+        twap.record(Timestamp::from_nanos(0), Decimal::MAX).unwrap();
+        let end = Timestamp::from_nanos(u64::MAX);
+        assert_eq!(
+            twap.cumulative_at(end).unwrap(),
+            Uint128::MAX.full_mul(u64::MAX)
+        );
+        assert_eq!(
+            twap.average(end, u64::MAX / 1_000_000_000).unwrap(),
+            Decimal::MAX
+        );
+    }
+
+    #[test]
+    fn average_fails_for_uncovered_windows() {
+        let mut twap = TwapAccumulator::new(2);
+        assert_eq!(
+            twap.average(t(10), 10),
+            Err(TwapError::WindowNotCovered { start: t(0) })
+        );
+
+        twap.record(t(0), Decimal::percent(100)).unwrap();
+        twap.record(t(10), Decimal::percent(200)).unwrap();
+        twap.record(t(20), Decimal::percent(300)).unwrap();
+        twap.average(t(20), 10).unwrap();
+        assert_eq!(
+            twap.average(t(20), 11),
+            Err(TwapError::WindowNotCovered { start: t(9) })
+        );
+        assert_eq!(
+            twap.average(t(20), u64::MAX),
+            Err(TwapError::WindowNotCovered {
+                start: Timestamp::from_nanos(0)
+            })
+        );
+        assert_eq!(twap.average(t(20), 0), Err(TwapError::EmptyWindow));
+    }
+
+    #[test]
+    #[should_panic(expected = "TWAP capacity must not be 0")]
+    fn new_panics_for_zero_capacity() {
+        TwapAccumulator::new(0);
+    }
+
+    #[test]
+    fn serialization_works() {
+        let mut twap = TwapAccumulator::new(2);
+        twap.record(Timestamp::from_seconds(1), Decimal::percent(50))
+            .unwrap();
+        let json = crate::to_vec(&twap).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"capacity":2,"observations":[{"time":"1000000000","price":"0.5","cumulative":"0"}]}"#
+        );
+        assert_eq!(crate::from_slice::<TwapAccumulator>(&json).unwrap(), twap);
+    }
+}
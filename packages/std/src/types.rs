@@ -1,8 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::addresses::Addr;
 use crate::coins::Coin;
+use crate::results::BankMsg;
 use crate::timestamp::Timestamp;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -15,6 +17,61 @@ pub struct Env {
     pub contract: ContractInfo,
 }
 
+/// Separates [`Env::domain_separator`] from other uses of the same hash.
+const DOMAIN_SEPARATOR_TAG: &[u8] = b"cosmwasm_std::Env::domain_separator";
+
+impl Env {
+    /// Returns a 32 byte value identifying this deployment of the contract, i.e. the
+    /// SHA-256 hash over chain ID and contract address.
+    ///
+    /// Off-chain signed messages (permits, vouchers, meta-transactions) must commit to this
+    /// value. Otherwise a signature made for one chain or contract instance can be replayed
+    /// on every other chain or instance that accepts the same signer.
+    /// Use [`Env::signing_digest`] to bind a payload to it.
+    pub fn domain_separator(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN_SEPARATOR_TAG);
+        update_length_prefixed(&mut hasher, self.block.chain_id.as_bytes());
+        update_length_prefixed(&mut hasher, self.contract.address.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Returns the SHA-256 hash over the [domain separator](Env::domain_separator) and the
+    /// payload. This is the message hash a signer must sign for the payload to be valid
+    /// for this deployment only.
+    ///
+    /// The payload should still contain a nonce or expiry to prevent replays within
+    /// the same deployment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Deps, Env, StdResult};
+    /// fn verify_permit(
+    ///     deps: Deps,
+    ///     env: &Env,
+    ///     permit: &[u8],
+    ///     signature: &[u8],
+    ///     pubkey: &[u8],
+    /// ) -> StdResult<bool> {
+    ///     let digest = env.signing_digest(permit);
+    ///     Ok(deps.api.secp256k1_verify(&digest, signature, pubkey)?)
+    /// }
+    /// ```
+    pub fn signing_digest(&self, payload: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.domain_separator());
+        hasher.update(payload);
+        hasher.finalize().into()
+    }
+}
+
+fn update_length_prefixed(hasher: &mut Sha256, data: &[u8]) {
+    // Cast is safe because usize is 32 or 64 bit large in all environments we support
+    hasher.update((data.len() as u64).to_be_bytes());
+    hasher.update(data);
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct TransactionInfo {
     /// The position of this transaction in the block. The first
@@ -105,7 +162,108 @@ pub struct MessageInfo {
     pub funds: Vec<Coin>,
 }
 
+impl MessageInfo {
+    /// Creates a `BankMsg::Send` that forwards exactly the received funds to `to_address`.
+    ///
+    /// Returns `None` if no funds were received, since the chain rejects sends without coins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, Response};
+    /// # use cosmwasm_std::testing::mock_info;
+    /// let info = mock_info("sender", &coins(100, "ucosm"));
+    ///
+    /// let mut response: Response = Response::new();
+    /// if let Some(msg) = info.forward_funds("treasury") {
+    ///     response = response.add_message(msg);
+    /// }
+    /// assert_eq!(response.messages.len(), 1);
+    /// ```
+    pub fn forward_funds(&self, to_address: impl Into<String>) -> Option<BankMsg> {
+        if self.funds.is_empty() {
+            return None;
+        }
+        Some(BankMsg::Send {
+            to_address: to_address.into(),
+            amount: self.funds.clone(),
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct ContractInfo {
     pub address: Addr,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coin;
+    use crate::testing::mock_env;
+
+    #[test]
+    fn domain_separator_works() {
+        let env = mock_env();
+        let separator = env.domain_separator();
+        assert_eq!(separator, mock_env().domain_separator());
+
+        let mut other_chain = mock_env();
+        other_chain.block.chain_id = "other-chain".to_string();
+        assert_ne!(other_chain.domain_separator(), separator);
+
+        let mut other_contract = mock_env();
+        other_contract.contract.address = Addr::unchecked("other_contract");
+        assert_ne!(other_contract.domain_separator(), separator);
+
+        // not affected by block or transaction
+        let mut later = mock_env();
+        later.block.height += 1;
+        later.block.time = later.block.time.plus_seconds(5);
+        later.transaction = None;
+        assert_eq!(later.domain_separator(), separator);
+
+        // length prefixes prevent moving bytes from one field to the other
+        let mut shifted = mock_env();
+        shifted.block.chain_id = "ab".to_string();
+        shifted.contract.address = Addr::unchecked("c");
+        let mut shifted2 = mock_env();
+        shifted2.block.chain_id = "a".to_string();
+        shifted2.contract.address = Addr::unchecked("bc");
+        assert_ne!(shifted.domain_separator(), shifted2.domain_separator());
+    }
+
+    #[test]
+    fn signing_digest_works() {
+        let env = mock_env();
+        let digest = env.signing_digest(b"permit");
+        assert_eq!(digest, env.signing_digest(b"permit"));
+        assert_ne!(digest, env.signing_digest(b"permit2"));
+        assert_ne!(digest, env.domain_separator());
+
+        let mut other_chain = mock_env();
+        other_chain.block.chain_id = "other-chain".to_string();
+        assert_ne!(other_chain.signing_digest(b"permit"), digest);
+    }
+
+    #[test]
+    fn forward_funds_works() {
+        let info = MessageInfo {
+            sender: Addr::unchecked("sender"),
+            funds: vec![coin(5, "ucosm"), coin(7, "uatom")],
+        };
+        assert_eq!(
+            info.forward_funds("treasury"),
+            Some(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: info.funds.clone(),
+            })
+        );
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("sender"),
+            funds: vec![],
+        };
+        assert_eq!(info.forward_funds("treasury"), None);
+    }
+}
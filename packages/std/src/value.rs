@@ -0,0 +1,605 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Index;
+
+use schemars::schema::{Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::de::{self, DeserializeOwned, MapAccess, SeqAccess};
+use serde::{ser, Deserialize, Deserializer, Serialize};
+
+use crate::errors::StdResult;
+use crate::serde::{from_slice, to_vec};
+
+/// A JSON object. Keys are sorted, which makes serialization deterministic.
+type Map = BTreeMap<String, Value>;
+
+/// An arbitrary JSON value, similar to `serde_json::Value`.
+///
+/// This can be used in messages that accept loosely-structured data such as
+/// token metadata, or to forward opaque JSON to another contract.
+///
+/// Since floating point numbers are not supported in contracts,
+/// numbers are restricted to integers in the `i64` and `u64` ranges.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{from_slice, Value};
+///
+/// let value: Value = from_slice(br#"{"name":"Cool NFT","traits":[{"rarity":3}]}"#).unwrap();
+/// assert_eq!(value["name"].as_str(), Some("Cool NFT"));
+/// assert_eq!(value["traits"][0]["rarity"].as_u64(), Some(3));
+/// assert_eq!(value.pointer("/traits/0/rarity"), Some(&Value::from(3u64)));
+/// assert!(value["missing"].is_null());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Map),
+}
+
+/// An integer JSON number
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Number(N);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum N {
+    PosInt(u64),
+    /// Always less than zero
+    NegInt(i64),
+}
+
+impl Number {
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.0 {
+            N::PosInt(n) => Some(n),
+            N::NegInt(_) => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.0 {
+            N::PosInt(n) => i64::try_from(n).ok(),
+            N::NegInt(n) => Some(n),
+        }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(n: u64) -> Self {
+        Number(N::PosInt(n))
+    }
+}
+
+impl From<i64> for Number {
+    fn from(n: i64) -> Self {
+        if n < 0 {
+            Number(N::NegInt(n))
+        } else {
+            Number(N::PosInt(n as u64))
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            N::PosInt(n) => write!(f, "{}", n),
+            N::NegInt(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+static NULL: Value = Value::Null;
+
+// Like `serde_json::Value`, the default is `null`. Deriving this would need `#[default]`
+// on the variant, which is newer than the minimum Rust version of this crate.
+#[allow(clippy::derivable_impls)]
+impl Default for Value {
+    fn default() -> Self {
+        Value::Null
+    }
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) => n.as_u64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) => n.as_i64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut BTreeMap<String, Value>> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the given key if this is an object containing it
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object()?.get(key)
+    }
+
+    /// Returns the element at `index` if this is an array long enough
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.as_array()?.get(index)
+    }
+
+    /// Looks up a value by a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901),
+    /// e.g. `/traits/0/rarity`.
+    ///
+    /// The empty pointer `""` refers to the value itself. Returns `None`
+    /// if the pointer is malformed or does not resolve to a value.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        let tokens = pointer.strip_prefix('/')?;
+        tokens
+            .split('/')
+            .try_fold(self, |target, token| match target {
+                Value::Object(map) => map.get(&unescape_token(token)),
+                Value::Array(array) => parse_array_index(token).and_then(|i| array.get(i)),
+                _ => None,
+            })
+    }
+
+    /// Like [`Value::pointer`] but returns a mutable reference
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        let tokens = pointer.strip_prefix('/')?;
+        tokens
+            .split('/')
+            .try_fold(self, |target, token| match target {
+                Value::Object(map) => map.get_mut(&unescape_token(token)),
+                Value::Array(array) => parse_array_index(token).and_then(move |i| array.get_mut(i)),
+                _ => None,
+            })
+    }
+
+    /// Takes the value out, leaving `Null` in its place
+    pub fn take(&mut self) -> Value {
+        std::mem::take(self)
+    }
+}
+
+fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Array indices must be decimal numbers without leading zeros
+fn parse_array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || (token.len() > 1 && token.starts_with('0'))
+        || !token.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Converts a serializable type into a [`Value`]
+pub fn to_value<T: Serialize + ?Sized>(data: &T) -> StdResult<Value> {
+    from_slice(&to_vec(data)?)
+}
+
+/// Converts a [`Value`] into a deserializable type
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> StdResult<T> {
+    from_slice(&to_vec(value)?)
+}
+
+/// Indexing an object by key. Returns `Null` if the key does not exist
+/// or this is not an object.
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+/// Indexing an array by position. Returns `Null` if the index is out of bounds
+/// or this is not an array.
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        self.get_index(index).unwrap_or(&NULL)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Number(n.into())
+    }
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Self {
+        Value::Number(u64::from(n).into())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Number(n.into())
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Number(i64::from(n).into())
+    }
+}
+
+impl From<Number> for Value {
+    fn from(n: Number) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(array: Vec<Value>) -> Self {
+        Value::Array(array)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(map: BTreeMap<String, Value>) -> Self {
+        Value::Object(map)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(option: Option<T>) -> Self {
+        option.map(Into::into).unwrap_or_default()
+    }
+}
+
+impl JsonSchema for Value {
+    fn schema_name() -> String {
+        "Value".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    /// Any JSON value
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        Schema::Object(SchemaObject::default())
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Number(n) => n.serialize(serializer),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Array(array) => array.serialize(serializer),
+            Value::Object(map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Number {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self.0 {
+            N::PosInt(n) => serializer.serialize_u64(n),
+            N::NegInt(n) => serializer.serialize_i64(n),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> de::Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut array = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(element) = seq.next_element()? {
+            array.push(element);
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut object = Map::new();
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_slice, to_vec, Addr, Uint128};
+
+    fn nft_metadata() -> Value {
+        from_slice(
+            br#"{
+                "name": "Cool NFT",
+                "image": null,
+                "animated": false,
+                "edition": 42,
+                "offset": -7,
+                "traits": [
+                    {"trait_type": "rarity", "value": 3},
+                    {"trait_type": "color", "value": "blue"}
+                ],
+                "a/b": {"c~d": "escaped"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn value_deserialization_works() {
+        let value = nft_metadata();
+        let object = value.as_object().unwrap();
+        assert_eq!(object.len(), 7);
+        assert_eq!(object["name"], Value::String("Cool NFT".to_string()));
+        assert_eq!(object["image"], Value::Null);
+        assert_eq!(object["animated"], Value::Bool(false));
+        assert_eq!(object["edition"], Value::Number(Number::from(42u64)));
+        assert_eq!(object["offset"], Value::Number(Number::from(-7i64)));
+        assert_eq!(object["traits"].as_array().unwrap().len(), 2);
+
+        // numbers beyond i64::MAX are supported
+        let value: Value = from_slice(b"18446744073709551615").unwrap();
+        assert_eq!(value.as_u64(), Some(u64::MAX));
+        assert_eq!(value.as_i64(), None);
+
+        // floats are not
+        from_slice::<Value>(b"1.5").unwrap_err();
+        from_slice::<Value>(br#"{"a":"#).unwrap_err();
+    }
+
+    #[test]
+    fn value_serialization_works() {
+        let value = nft_metadata();
+        // keys are sorted and whitespace removed
+        assert_eq!(
+            String::from_utf8(to_vec(&value).unwrap()).unwrap(),
+            r#"{"a/b":{"c~d":"escaped"},"animated":false,"edition":42,"image":null,"name":"Cool NFT","offset":-7,"traits":[{"trait_type":"rarity","value":3},{"trait_type":"color","value":"blue"}]}"#
+        );
+
+        let roundtrip: Value = from_slice(&to_vec(&value).unwrap()).unwrap();
+        assert_eq!(roundtrip, value);
+
+        assert_eq!(to_vec(&Value::Null).unwrap(), b"null");
+        assert_eq!(to_vec(&Value::from(-1)).unwrap(), b"-1");
+        assert_eq!(to_vec(&Value::Array(vec![])).unwrap(), b"[]");
+        assert_eq!(to_vec(&Value::Object(Map::new())).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn accessors_work() {
+        let value = nft_metadata();
+        assert_eq!(value["name"].as_str(), Some("Cool NFT"));
+        assert_eq!(value["animated"].as_bool(), Some(false));
+        assert_eq!(value["edition"].as_u64(), Some(42));
+        assert_eq!(value["edition"].as_i64(), Some(42));
+        assert_eq!(value["offset"].as_u64(), None);
+        assert_eq!(value["offset"].as_i64(), Some(-7));
+        assert!(value["image"].is_null());
+        assert_eq!(value["name"].as_u64(), None);
+        assert_eq!(value["edition"].as_str(), None);
+
+        assert_eq!(value["traits"][1]["value"].as_str(), Some("blue"));
+        assert_eq!(
+            value.get("traits").unwrap().get_index(0).unwrap()["value"],
+            Value::from(3u32)
+        );
+
+        // missing entries
+        assert!(value["nope"].is_null());
+        assert!(value["traits"][2].is_null());
+        assert!(value["name"]["nested"].is_null());
+        assert!(value[0].is_null());
+        assert_eq!(value.get("nope"), None);
+        assert_eq!(value.get_index(0), None);
+    }
+
+    #[test]
+    fn pointer_works() {
+        let mut value = nft_metadata();
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/name"), Some(&Value::from("Cool NFT")));
+        assert_eq!(value.pointer("/traits/1/value"), Some(&Value::from("blue")));
+        assert_eq!(value.pointer("/a~1b/c~0d"), Some(&Value::from("escaped")));
+
+        assert_eq!(value.pointer("name"), None);
+        assert_eq!(value.pointer("/traits/2"), None);
+        assert_eq!(value.pointer("/traits/01"), None);
+        assert_eq!(value.pointer("/traits/-1"), None);
+        assert_eq!(value.pointer("/traits/"), None);
+        assert_eq!(value.pointer("/name/0"), None);
+        assert_eq!(value.pointer("/"), None);
+
+        *value.pointer_mut("/traits/0/value").unwrap() = Value::from(5);
+        assert_eq!(value["traits"][0]["value"].as_u64(), Some(5));
+        let name = value.pointer_mut("/name").unwrap().take();
+        assert_eq!(name, Value::from("Cool NFT"));
+        assert!(value["name"].is_null());
+        assert_eq!(value.pointer_mut("/nope"), None);
+    }
+
+    #[test]
+    fn conversions_work() {
+        assert_eq!(Value::from(true), Value::Bool(true));
+        assert_eq!(Value::from(Some("a")), Value::String("a".to_string()));
+        assert_eq!(Value::from(None::<u64>), Value::Null);
+        assert_eq!(Value::from(0i64).as_u64(), Some(0));
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Offer {
+            seller: Addr,
+            amount: Uint128,
+        }
+        let offer = Offer {
+            seller: Addr::unchecked("seller"),
+            amount: Uint128::new(123),
+        };
+        let value = to_value(&offer).unwrap();
+        assert_eq!(value["seller"].as_str(), Some("seller"));
+        assert_eq!(value["amount"].as_str(), Some("123"));
+        assert_eq!(from_value::<Offer>(&value).unwrap(), offer);
+        from_value::<Offer>(&Value::from(1)).unwrap_err();
+    }
+
+    #[test]
+    fn value_can_be_embedded_in_messages() {
+        #[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+        struct ForwardMsg {
+            contract: String,
+            payload: Value,
+        }
+        let msg: ForwardMsg =
+            from_slice(br#"{"contract":"other","payload":{"list":[1,-2,null,true,"x"]}}"#).unwrap();
+        assert_eq!(msg.payload["list"][1].as_i64(), Some(-2));
+        let serialized = to_vec(&msg).unwrap();
+        assert_eq!(from_slice::<ForwardMsg>(&serialized).unwrap(), msg);
+
+        let schema = schemars::schema_for!(ForwardMsg);
+        let payload = &schema.schema.object.as_ref().unwrap().properties["payload"];
+        // any value is allowed
+        assert_eq!(payload, &Schema::Object(SchemaObject::default()));
+    }
+}
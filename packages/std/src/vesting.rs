@@ -0,0 +1,430 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Timestamp, Uint128, Uint256};
+
+/// Describes how an amount of tokens becomes available over time.
+///
+/// All schedules vest nothing before they start and the full total from their last point
+/// in time on. Amounts between are rounded down, so the vested amount never exceeds what
+/// the exact calculation would give.
+///
+/// ## Examples
+///
+/// ```
+/// use cosmwasm_std::{Timestamp, Uint128, VestingSchedule};
+///
+/// let start = Timestamp::from_seconds(1_000);
+/// let schedule = VestingSchedule::Linear {
+///     start,
+///     end: start.plus_seconds(400),
+///     cliff: Some(start.plus_seconds(100)),
+///     total: Uint128::new(1000),
+/// };
+/// assert_eq!(schedule.vested(start.plus_seconds(99)).unwrap(), Uint128::zero());
+/// assert_eq!(schedule.vested(start.plus_seconds(100)).unwrap(), Uint128::new(250));
+/// assert_eq!(schedule.vested(start.plus_seconds(401)).unwrap(), Uint128::new(1000));
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VestingSchedule {
+    /// Vests linearly from `start` to `end`.
+    ///
+    /// With a cliff, nothing vests before the cliff and at the cliff the amount that would have
+    /// vested linearly until then becomes available at once.
+    Linear {
+        start: Timestamp,
+        end: Timestamp,
+        cliff: Option<Timestamp>,
+        total: Uint128,
+    },
+    /// Vests the total at once at the given point in time
+    Cliff { at: Timestamp, total: Uint128 },
+    /// Vests linearly between the given points, where each point contains the cumulative
+    /// vested amount at its time. A non-zero amount at the first point vests at once.
+    Piecewise { points: Vec<VestingPoint> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct VestingPoint {
+    pub time: Timestamp,
+    /// The total amount vested at `time`
+    pub amount: Uint128,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum VestingError {
+    #[error("Vesting end must be after the start")]
+    InvalidDuration,
+    #[error("Vesting cliff must be between start and end")]
+    InvalidCliff,
+    #[error("Vesting schedule must have at least one point")]
+    NoPoints,
+    #[error("Vesting points must be strictly ordered by time")]
+    UnorderedPoints,
+    #[error("Vested amounts must not decrease")]
+    DecreasingAmounts,
+}
+
+impl VestingSchedule {
+    /// Checks that the schedule is well-formed, i.e. that points in time are ordered
+    /// and amounts do not decrease.
+    pub fn validate(&self) -> Result<(), VestingError> {
+        match self {
+            VestingSchedule::Linear {
+                start, end, cliff, ..
+            } => {
+                if end <= start {
+                    return Err(VestingError::InvalidDuration);
+                }
+                if let Some(cliff) = cliff {
+                    if cliff < start || cliff > end {
+                        return Err(VestingError::InvalidCliff);
+                    }
+                }
+            }
+            VestingSchedule::Cliff { .. } => {}
+            VestingSchedule::Piecewise { points } => {
+                if points.is_empty() {
+                    return Err(VestingError::NoPoints);
+                }
+                for pair in points.windows(2) {
+                    if pair[1].time <= pair[0].time {
+                        return Err(VestingError::UnorderedPoints);
+                    }
+                    if pair[1].amount < pair[0].amount {
+                        return Err(VestingError::DecreasingAmounts);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The amount that is vested once the schedule is complete
+    pub fn total(&self) -> Uint128 {
+        match self {
+            VestingSchedule::Linear { total, .. } | VestingSchedule::Cliff { total, .. } => *total,
+            VestingSchedule::Piecewise { points } => {
+                points.last().map(|point| point.amount).unwrap_or_default()
+            }
+        }
+    }
+
+    /// Returns the amount vested at `now`. Fails if the schedule is invalid.
+    pub fn vested(&self, now: Timestamp) -> Result<Uint128, VestingError> {
+        self.validate()?;
+        let vested = match self {
+            VestingSchedule::Linear {
+                start,
+                end,
+                cliff,
+                total,
+            } => {
+                if matches!(cliff, Some(cliff) if now < *cliff) {
+                    Uint128::zero()
+                } else {
+                    interpolate((*start, Uint128::zero()), (*end, *total), now)
+                }
+            }
+            VestingSchedule::Cliff { at, total } => {
+                if now >= *at {
+                    *total
+                } else {
+                    Uint128::zero()
+                }
+            }
+            VestingSchedule::Piecewise { points } => {
+                // The index of the first point after now
+                let next = points.partition_point(|point| point.time <= now);
+                if next == 0 {
+                    Uint128::zero()
+                } else if next == points.len() {
+                    points[next - 1].amount
+                } else {
+                    let (from, to) = (&points[next - 1], &points[next]);
+                    interpolate((from.time, from.amount), (to.time, to.amount), now)
+                }
+            }
+        };
+        Ok(vested)
+    }
+
+    /// Returns the amount not vested at `now`. Fails if the schedule is invalid.
+    pub fn unvested(&self, now: Timestamp) -> Result<Uint128, VestingError> {
+        Ok(self.total() - self.vested(now)?)
+    }
+}
+
+/// Linear interpolation between two points, rounding down. `from.0 < to.0` and
+/// `from.1 <= to.1` must hold. Before `from` this is 0, from `to` on it is `to.1`.
+fn interpolate(from: (Timestamp, Uint128), to: (Timestamp, Uint128), now: Timestamp) -> Uint128 {
+    if now < from.0 {
+        return Uint128::zero();
+    }
+    if now >= to.0 {
+        return to.1;
+    }
+    let elapsed = now.nanos() - from.0.nanos();
+    let duration = to.0.nanos() - from.0.nanos();
+    let increase = (to.1 - from.1).full_mul(elapsed) / Uint256::from(duration);
+    // increase < to.1 - from.1 since elapsed < duration, so this cannot fail
+    from.1 + Uint128::try_from(increase).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Xoshiro256PlusPlus;
+
+    fn t(seconds: u64) -> Timestamp {
+        Timestamp::from_seconds(1_600_000_000).plus_seconds(seconds)
+    }
+
+    fn point(seconds: u64, amount: u128) -> VestingPoint {
+        VestingPoint {
+            time: t(seconds),
+            amount: Uint128::new(amount),
+        }
+    }
+
+    fn linear(start: u64, end: u64, cliff: Option<u64>, total: u128) -> VestingSchedule {
+        VestingSchedule::Linear {
+            start: t(start),
+            end: t(end),
+            cliff: cliff.map(t),
+            total: Uint128::new(total),
+        }
+    }
+
+    fn vested(schedule: &VestingSchedule, now: Timestamp) -> u128 {
+        schedule.vested(now).unwrap().u128()
+    }
+
+    #[test]
+    fn linear_works() {
+        let schedule = linear(100, 200, None, 1000);
+        assert_eq!(vested(&schedule, t(0)), 0);
+        assert_eq!(vested(&schedule, t(99)), 0);
+        assert_eq!(vested(&schedule, t(100)), 0);
+        assert_eq!(vested(&schedule, t(101)), 10);
+        assert_eq!(vested(&schedule, t(150)), 500);
+        assert_eq!(vested(&schedule, t(199)), 990);
+        assert_eq!(vested(&schedule, t(200)), 1000);
+        assert_eq!(vested(&schedule, t(500)), 1000);
+
+        // one nanosecond around the boundaries
+        assert_eq!(vested(&schedule, t(100).plus_nanos(1)), 0);
+        assert_eq!(vested(&schedule, t(200).minus_nanos(1)), 999);
+    }
+
+    #[test]
+    fn linear_rounds_down() {
+        let schedule = linear(0, 3, None, 10);
+        assert_eq!(vested(&schedule, t(1)), 3);
+        assert_eq!(vested(&schedule, t(2)), 6);
+        assert_eq!(vested(&schedule, t(3)), 10);
+
+        // less tokens than nanoseconds
+        let schedule = linear(0, 100, None, 1);
+        assert_eq!(vested(&schedule, t(99)), 0);
+        assert_eq!(vested(&schedule, t(100)), 1);
+    }
+
+    #[test]
+    fn linear_with_cliff_works() {
+        let schedule = linear(100, 200, Some(125), 1000);
+        assert_eq!(vested(&schedule, t(100)), 0);
+        assert_eq!(vested(&schedule, t(125).minus_nanos(1)), 0);
+        assert_eq!(vested(&schedule, t(125)), 250);
+        assert_eq!(vested(&schedule, t(150)), 500);
+        assert_eq!(vested(&schedule, t(200)), 1000);
+
+        // cliff at start is the same as no cliff
+        let schedule = linear(100, 200, Some(100), 1000);
+        assert_eq!(vested(&schedule, t(100)), 0);
+        assert_eq!(vested(&schedule, t(110)), 100);
+
+        // cliff at end vests everything at once
+        let schedule = linear(100, 200, Some(200), 1000);
+        assert_eq!(vested(&schedule, t(200).minus_nanos(1)), 0);
+        assert_eq!(vested(&schedule, t(200)), 1000);
+    }
+
+    #[test]
+    fn linear_handles_extreme_values() {
+        let schedule = VestingSchedule::Linear {
+            start: Timestamp::from_nanos(0),
+            end: Timestamp::from_nanos(u64::MAX),
+            cliff: None,
+            total: Uint128::MAX,
+        };
+        // u128::MAX = (2^64 - 1) * (2^64 + 1), so one step is exactly 2^64 + 1
+        assert_eq!(
+            schedule
+                .vested(Timestamp::from_nanos(u64::MAX - 1))
+                .unwrap(),
+            Uint128::MAX - Uint128::new((1 << 64) + 1)
+        );
+        assert_eq!(
+            schedule.vested(Timestamp::from_nanos(1)).unwrap(),
+            Uint128::new((1 << 64) + 1)
+        );
+        assert_eq!(
+            schedule.vested(Timestamp::from_nanos(u64::MAX)).unwrap(),
+            Uint128::MAX
+        );
+    }
+
+    #[test]
+    fn cliff_works() {
+        let schedule = VestingSchedule::Cliff {
+            at: t(100),
+            total: Uint128::new(1000),
+        };
+        assert_eq!(vested(&schedule, t(0)), 0);
+        assert_eq!(vested(&schedule, t(100).minus_nanos(1)), 0);
+        assert_eq!(vested(&schedule, t(100)), 1000);
+        assert_eq!(vested(&schedule, t(1000)), 1000);
+    }
+
+    #[test]
+    fn piecewise_works() {
+        let schedule = VestingSchedule::Piecewise {
+            points: vec![point(100, 200), point(200, 200), point(300, 1000)],
+        };
+        assert_eq!(vested(&schedule, t(0)), 0);
+        assert_eq!(vested(&schedule, t(100).minus_nanos(1)), 0);
+        // amount of the first point vests at once
+        assert_eq!(vested(&schedule, t(100)), 200);
+        // flat segment
+        assert_eq!(vested(&schedule, t(150)), 200);
+        assert_eq!(vested(&schedule, t(200)), 200);
+        assert_eq!(vested(&schedule, t(250)), 600);
+        assert_eq!(vested(&schedule, t(300).minus_nanos(1)), 999);
+        assert_eq!(vested(&schedule, t(300)), 1000);
+        assert_eq!(vested(&schedule, t(301)), 1000);
+
+        // a single point is a cliff
+        let schedule = VestingSchedule::Piecewise {
+            points: vec![point(100, 50)],
+        };
+        assert_eq!(vested(&schedule, t(100).minus_nanos(1)), 0);
+        assert_eq!(vested(&schedule, t(100)), 50);
+    }
+
+    #[test]
+    fn total_and_unvested_work() {
+        let schedule = linear(100, 200, None, 1000);
+        assert_eq!(schedule.total(), Uint128::new(1000));
+        assert_eq!(schedule.unvested(t(150)).unwrap(), Uint128::new(500));
+        assert_eq!(schedule.unvested(t(200)).unwrap(), Uint128::zero());
+
+        let schedule = VestingSchedule::Piecewise {
+            points: vec![point(100, 200), point(300, 1000)],
+        };
+        assert_eq!(schedule.total(), Uint128::new(1000));
+        assert_eq!(schedule.unvested(t(0)).unwrap(), Uint128::new(1000));
+    }
+
+    #[test]
+    fn validate_works() {
+        linear(100, 200, None, 1000).validate().unwrap();
+        linear(100, 200, Some(100), 1000).validate().unwrap();
+        linear(100, 200, Some(200), 1000).validate().unwrap();
+        assert_eq!(
+            linear(100, 100, None, 1000).validate(),
+            Err(VestingError::InvalidDuration)
+        );
+        assert_eq!(
+            linear(200, 100, None, 1000).validate(),
+            Err(VestingError::InvalidDuration)
+        );
+        assert_eq!(
+            linear(100, 200, Some(99), 1000).validate(),
+            Err(VestingError::InvalidCliff)
+        );
+        assert_eq!(
+            linear(100, 200, Some(201), 1000).validate(),
+            Err(VestingError::InvalidCliff)
+        );
+
+        assert_eq!(
+            VestingSchedule::Piecewise { points: vec![] }.validate(),
+            Err(VestingError::NoPoints)
+        );
+        assert_eq!(
+            VestingSchedule::Piecewise {
+                points: vec![point(100, 0), point(100, 10)]
+            }
+            .validate(),
+            Err(VestingError::UnorderedPoints)
+        );
+        assert_eq!(
+            VestingSchedule::Piecewise {
+                points: vec![point(100, 10), point(200, 5)]
+            }
+            .validate(),
+            Err(VestingError::DecreasingAmounts)
+        );
+
+        // invalid schedules cannot be evaluated
+        assert_eq!(
+            linear(100, 100, None, 1000).vested(t(100)),
+            Err(VestingError::InvalidDuration)
+        );
+        assert_eq!(
+            VestingSchedule::Piecewise { points: vec![] }.unvested(t(100)),
+            Err(VestingError::NoPoints)
+        );
+    }
+
+    #[test]
+    fn serialization_works() {
+        let schedule = VestingSchedule::Cliff {
+            at: Timestamp::from_seconds(1),
+            total: Uint128::new(5),
+        };
+        assert_eq!(
+            crate::to_vec(&schedule).unwrap(),
+            br#"{"cliff":{"at":"1000000000","total":"5"}}"#
+        );
+    }
+
+    #[test]
+    fn property_vested_is_monotonic_and_bounded() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(42);
+        for _ in 0..200 {
+            let mut time = rng.next_u64() % 1_000;
+            let mut amount = 0u128;
+            let mut points = vec![];
+            for _ in 0..(rng.next_u64() % 5 + 1) {
+                time += rng.next_u64() % 1_000 + 1;
+                amount += (rng.next_u64() % 1_000_000) as u128;
+                points.push(point(time, amount));
+            }
+            let start = rng.next_u64() % 1_000;
+            let end = start + rng.next_u64() % 1_000 + 1;
+            let cliff = start + rng.next_u64() % (end - start + 1);
+            let total = rng.next_u64() as u128;
+
+            for schedule in [
+                VestingSchedule::Piecewise { points },
+                linear(start, end, Some(cliff), total),
+                linear(start, end, None, total),
+            ] {
+                let mut previous = Uint128::zero();
+                let mut now = t(0);
+                while now <= t(6_000) {
+                    let vested = schedule.vested(now).unwrap();
+                    assert!(vested >= previous, "vested amount decreased");
+                    assert!(vested <= schedule.total(), "vested more than total");
+                    previous = vested;
+                    now = now.plus_nanos(rng.next_u64() % 50_000_000_000);
+                }
+                assert_eq!(previous, schedule.total());
+            }
+        }
+    }
+}
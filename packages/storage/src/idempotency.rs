@@ -0,0 +1,326 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[cfg(feature = "iterator")]
+use cosmwasm_std::Order;
+use cosmwasm_std::{Binary, StdError, StdResult, Storage, Timestamp};
+
+use crate::bucket::{Bucket, ReadonlyBucket};
+use crate::prefixed_storage::PrefixedStorage;
+#[cfg(feature = "iterator")]
+use crate::prefixed_storage::ReadonlyPrefixedStorage;
+
+const IDS_NAMESPACE: &[u8] = b"ids";
+const EXPIRY_NAMESPACE: &[u8] = b"expiry";
+
+/// Remembers the ids of processed operations (e.g. bridge transfers or relayed packets)
+/// in contract storage, such that every operation is executed at most once.
+///
+/// Without a TTL, ids are stored forever. With a TTL, an id is forgotten `ttl_seconds` after
+/// it was processed, i.e. it can be processed again afterwards. This is only safe if the
+/// operations themselves expire earlier, e.g. by a timeout that is checked by the contract.
+/// Expired ids can be removed with [`IdempotencyGuard::prune`] to free storage.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::testing::{mock_env, MockStorage};
+/// use cosmwasm_storage::{IdempotencyError, IdempotencyGuard};
+///
+/// const PROCESSED_TRANSFERS: IdempotencyGuard = IdempotencyGuard::new(b"transfers");
+///
+/// let mut storage = MockStorage::new();
+/// let now = mock_env().block.time;
+///
+/// PROCESSED_TRANSFERS.mark_processed(&mut storage, b"transfer-17", now).unwrap();
+/// let err = PROCESSED_TRANSFERS.mark_processed(&mut storage, b"transfer-17", now).unwrap_err();
+/// assert!(matches!(err, IdempotencyError::AlreadyProcessed { .. }));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdempotencyGuard<'a> {
+    namespace: &'a [u8],
+    ttl_seconds: Option<u64>,
+}
+
+/// What is stored for every processed id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct ProcessedRecord {
+    processed_at: Timestamp,
+    /// The point in time at which the id is forgotten, if a TTL is set
+    expires: Option<Timestamp>,
+}
+
+#[derive(Error, Debug)]
+pub enum IdempotencyError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("Operation {id} was already processed at {processed_at}")]
+    AlreadyProcessed { id: Binary, processed_at: Timestamp },
+}
+
+impl<'a> IdempotencyGuard<'a> {
+    /// Creates a guard storing processed ids in the given namespace forever
+    pub const fn new(namespace: &'a [u8]) -> Self {
+        IdempotencyGuard {
+            namespace,
+            ttl_seconds: None,
+        }
+    }
+
+    /// Creates a guard storing processed ids in the given namespace for `ttl_seconds`.
+    ///
+    /// Panics if `ttl_seconds` is zero.
+    pub const fn with_ttl(namespace: &'a [u8], ttl_seconds: u64) -> Self {
+        assert!(ttl_seconds > 0, "TTL must not be zero");
+        IdempotencyGuard {
+            namespace,
+            ttl_seconds: Some(ttl_seconds),
+        }
+    }
+
+    pub fn ttl_seconds(&self) -> Option<u64> {
+        self.ttl_seconds
+    }
+
+    /// Returns true if the operation with the given id was processed and not yet forgotten
+    pub fn is_processed(
+        &self,
+        storage: &dyn Storage,
+        id: &[u8],
+        now: Timestamp,
+    ) -> StdResult<bool> {
+        Ok(self.load_active(storage, id, now)?.is_some())
+    }
+
+    /// Records the operation with the given id as processed.
+    ///
+    /// Fails if it was processed before. Call this before executing the operation, such
+    /// that the transaction is reverted when the id is a duplicate.
+    pub fn mark_processed(
+        &self,
+        storage: &mut dyn Storage,
+        id: &[u8],
+        now: Timestamp,
+    ) -> Result<(), IdempotencyError> {
+        if let Some(record) = self.load_active(storage, id, now)? {
+            return Err(IdempotencyError::AlreadyProcessed {
+                id: Binary::from(id),
+                processed_at: record.processed_at,
+            });
+        }
+
+        // An expired record may still be stored. Its index entry must not remove the new record.
+        self.remove(storage, id)?;
+
+        let expires = self.ttl_seconds.map(|ttl| now.plus_seconds(ttl));
+        let record = ProcessedRecord {
+            processed_at: now,
+            expires,
+        };
+        Bucket::multilevel(storage, &[self.namespace, IDS_NAMESPACE]).save(id, &record)?;
+        if let Some(expires) = expires {
+            PrefixedStorage::multilevel(storage, &[self.namespace, EXPIRY_NAMESPACE])
+                .set(&expiry_key(expires, id), &[1]);
+        }
+        Ok(())
+    }
+
+    /// Forgets the operation with the given id, e.g. when it was rolled back by a
+    /// later message and may be retried.
+    pub fn remove(&self, storage: &mut dyn Storage, id: &[u8]) -> StdResult<()> {
+        let mut ids =
+            Bucket::<ProcessedRecord>::multilevel(storage, &[self.namespace, IDS_NAMESPACE]);
+        let record = ids.may_load(id)?;
+        if let Some(record) = record {
+            ids.remove(id);
+            if let Some(expires) = record.expires {
+                PrefixedStorage::multilevel(storage, &[self.namespace, EXPIRY_NAMESPACE])
+                    .remove(&expiry_key(expires, id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes up to `limit` ids that expired at or before `now`, oldest first,
+    /// and returns the number of removed ids.
+    ///
+    /// Use a limit to keep the gas cost bounded, e.g. by pruning a few ids in every execution.
+    #[cfg(feature = "iterator")]
+    pub fn prune(&self, storage: &mut dyn Storage, now: Timestamp, limit: u32) -> StdResult<u32> {
+        // All index keys of ids expiring at or before now are smaller than this
+        let end = now.nanos().saturating_add(1).to_be_bytes();
+        let expired: Vec<Vec<u8>> =
+            ReadonlyPrefixedStorage::multilevel(storage, &[self.namespace, EXPIRY_NAMESPACE])
+                .range(None, Some(&end), Order::Ascending)
+                .take(limit as usize)
+                .map(|(key, _)| key)
+                .collect();
+
+        for key in &expired {
+            PrefixedStorage::multilevel(storage, &[self.namespace, EXPIRY_NAMESPACE]).remove(key);
+            Bucket::<ProcessedRecord>::multilevel(storage, &[self.namespace, IDS_NAMESPACE])
+                .remove(&key[8..]);
+        }
+        // At most limit elements were taken, so this cannot truncate
+        Ok(expired.len() as u32)
+    }
+
+    fn load_active(
+        &self,
+        storage: &dyn Storage,
+        id: &[u8],
+        now: Timestamp,
+    ) -> StdResult<Option<ProcessedRecord>> {
+        let record = ReadonlyBucket::<ProcessedRecord>::multilevel(
+            storage,
+            &[self.namespace, IDS_NAMESPACE],
+        )
+        .may_load(id)?;
+        Ok(record.filter(|record| match record.expires {
+            Some(expires) => now < expires,
+            None => true,
+        }))
+    }
+}
+
+/// The key in the expiry index, which sorts by expiry first
+fn expiry_key(expires: Timestamp, id: &[u8]) -> Vec<u8> {
+    let mut key = expires.nanos().to_be_bytes().to_vec();
+    key.extend_from_slice(id);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    const GUARD: IdempotencyGuard = IdempotencyGuard::new(b"processed");
+    const TTL_GUARD: IdempotencyGuard = IdempotencyGuard::with_ttl(b"processed", 100);
+
+    fn t(seconds: u64) -> Timestamp {
+        Timestamp::from_seconds(1_600_000_000).plus_seconds(seconds)
+    }
+
+    #[test]
+    #[should_panic(expected = "TTL must not be zero")]
+    fn with_ttl_panics_for_zero_ttl() {
+        IdempotencyGuard::with_ttl(b"processed", 0);
+    }
+
+    #[test]
+    fn mark_processed_works() {
+        let mut storage = MockStorage::new();
+        assert!(!GUARD.is_processed(&storage, b"op1", t(0)).unwrap());
+
+        GUARD.mark_processed(&mut storage, b"op1", t(0)).unwrap();
+        assert!(GUARD.is_processed(&storage, b"op1", t(0)).unwrap());
+        assert!(GUARD.is_processed(&storage, b"op1", t(1_000_000)).unwrap());
+        assert!(!GUARD.is_processed(&storage, b"op2", t(0)).unwrap());
+
+        match GUARD
+            .mark_processed(&mut storage, b"op1", t(5))
+            .unwrap_err()
+        {
+            IdempotencyError::AlreadyProcessed { id, processed_at } => {
+                assert_eq!(id, Binary::from(b"op1"));
+                assert_eq!(processed_at, t(0));
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        GUARD.mark_processed(&mut storage, b"op2", t(5)).unwrap();
+    }
+
+    #[test]
+    fn namespaces_are_separate() {
+        let mut storage = MockStorage::new();
+        GUARD.mark_processed(&mut storage, b"op1", t(0)).unwrap();
+        let other = IdempotencyGuard::new(b"other");
+        assert!(!other.is_processed(&storage, b"op1", t(0)).unwrap());
+        other.mark_processed(&mut storage, b"op1", t(0)).unwrap();
+    }
+
+    #[test]
+    fn ttl_works() {
+        let mut storage = MockStorage::new();
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(0))
+            .unwrap();
+        assert!(TTL_GUARD.is_processed(&storage, b"op1", t(99)).unwrap());
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(99))
+            .unwrap_err();
+
+        // forgotten after the TTL
+        assert!(!TTL_GUARD.is_processed(&storage, b"op1", t(100)).unwrap());
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(100))
+            .unwrap();
+        assert!(TTL_GUARD.is_processed(&storage, b"op1", t(199)).unwrap());
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut storage = MockStorage::new();
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(0))
+            .unwrap();
+        TTL_GUARD.remove(&mut storage, b"op1").unwrap();
+        assert!(!TTL_GUARD.is_processed(&storage, b"op1", t(0)).unwrap());
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(0))
+            .unwrap();
+
+        // removing unknown ids is a no-op
+        GUARD.remove(&mut storage, b"unknown").unwrap();
+    }
+
+    #[cfg(feature = "iterator")]
+    #[test]
+    fn prune_works() {
+        let mut storage = MockStorage::new();
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(0))
+            .unwrap();
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op2", t(10))
+            .unwrap();
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op3", t(20))
+            .unwrap();
+
+        // nothing expired yet
+        assert_eq!(TTL_GUARD.prune(&mut storage, t(99), 10).unwrap(), 0);
+
+        // the limit is respected and the oldest ids go first
+        assert_eq!(TTL_GUARD.prune(&mut storage, t(200), 2).unwrap(), 2);
+        let remaining =
+            ReadonlyBucket::<ProcessedRecord>::multilevel(&storage, &[b"processed", IDS_NAMESPACE]);
+        assert_eq!(remaining.may_load(b"op1").unwrap(), None);
+        assert_eq!(remaining.may_load(b"op2").unwrap(), None);
+        assert!(remaining.may_load(b"op3").unwrap().is_some());
+
+        // expiry at exactly now is pruned
+        assert_eq!(TTL_GUARD.prune(&mut storage, t(120), 10).unwrap(), 1);
+        assert_eq!(TTL_GUARD.prune(&mut storage, t(200), 10).unwrap(), 0);
+        assert_eq!(storage.range(None, None, Order::Ascending).count(), 0);
+    }
+
+    #[cfg(feature = "iterator")]
+    #[test]
+    fn prune_does_not_remove_reprocessed_ids() {
+        let mut storage = MockStorage::new();
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(0))
+            .unwrap();
+        // processed again after expiry, but before pruning
+        TTL_GUARD
+            .mark_processed(&mut storage, b"op1", t(150))
+            .unwrap();
+
+        assert_eq!(TTL_GUARD.prune(&mut storage, t(200), 10).unwrap(), 0);
+        assert!(TTL_GUARD.is_processed(&storage, b"op1", t(200)).unwrap());
+        assert_eq!(TTL_GUARD.prune(&mut storage, t(250), 10).unwrap(), 1);
+        assert!(!TTL_GUARD.is_processed(&storage, b"op1", t(250)).unwrap());
+    }
+}
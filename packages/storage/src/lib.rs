@@ -1,13 +1,22 @@
 mod bucket;
+mod idempotency;
 mod length_prefixed;
 mod namespace_helpers;
+mod ownership;
 mod prefixed_storage;
+mod rate_limiter;
 mod sequence;
 mod singleton;
 mod type_helpers;
 
 pub use bucket::{bucket, bucket_read, Bucket, ReadonlyBucket};
+pub use idempotency::{IdempotencyError, IdempotencyGuard};
 pub use length_prefixed::{to_length_prefixed, to_length_prefixed_nested};
+pub use ownership::{
+    assert_owner, get_ownership, initialize_owner, update_ownership, Ownership, OwnershipAction,
+    OwnershipError, OWNERSHIP_KEY,
+};
 pub use prefixed_storage::{prefixed, prefixed_read, PrefixedStorage, ReadonlyPrefixedStorage};
+pub use rate_limiter::{RateLimitError, RateLimiter};
 pub use sequence::{currval, nextval, sequence};
 pub use singleton::{singleton, singleton_read, ReadonlySingleton, Singleton};
@@ -0,0 +1,398 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use cosmwasm_std::{
+    attr, Addr, Api, Attribute, BlockInfo, DepsMut, StdError, StdResult, Storage, Timestamp,
+};
+
+use crate::singleton::{singleton, singleton_read};
+
+/// The storage key under which the contract's [`Ownership`] is stored.
+///
+/// This is shared by all contracts using this module, so that tooling can find the owner
+/// of any contract with a single raw query.
+pub const OWNERSHIP_KEY: &[u8] = b"ownership";
+
+/// The ownership state of a contract.
+///
+/// Ownership is transferred in two steps: the current owner proposes a new owner,
+/// who then has to accept. This avoids losing control over a contract by sending it
+/// to a mistyped or otherwise unusable address.
+///
+/// This type is also the response type of an ownership query.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Ownership {
+    /// The current owner. `None` if the contract has no owner (anymore).
+    pub owner: Option<Addr>,
+    /// The account that was proposed as the new owner but did not accept yet.
+    pub pending_owner: Option<Addr>,
+    /// The point in time at which the pending ownership transfer expires.
+    /// `None` means the proposal never expires.
+    pub pending_expiry: Option<Timestamp>,
+}
+
+impl Ownership {
+    /// Returns true if the given address is the current owner
+    pub fn is_owner(&self, addr: &Addr) -> bool {
+        self.owner.as_ref() == Some(addr)
+    }
+
+    /// Ensures the given address is the current owner
+    pub fn assert_owner(&self, sender: &Addr) -> Result<(), OwnershipError> {
+        match &self.owner {
+            Some(owner) if owner == sender => Ok(()),
+            Some(_) => Err(OwnershipError::NotOwner),
+            None => Err(OwnershipError::NoOwner),
+        }
+    }
+
+    /// Creates the attributes that describe the ownership state, useful to be emitted
+    /// in the response after every ownership change.
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        fn or_none(value: Option<impl ToString>) -> String {
+            value.map_or_else(|| "none".to_string(), |v| v.to_string())
+        }
+
+        vec![
+            attr("owner", or_none(self.owner)),
+            attr("pending_owner", or_none(self.pending_owner)),
+            attr("pending_expiry", or_none(self.pending_expiry)),
+        ]
+    }
+}
+
+/// The actions that can be taken to change the ownership of a contract.
+///
+/// This is meant to be embedded into a contract's `ExecuteMsg`, e.g. as
+/// `UpdateOwnership(OwnershipAction)`, and passed to [`update_ownership`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnershipAction {
+    /// Proposes a new owner. Can only be called by the current owner.
+    /// A previous proposal is overwritten.
+    TransferOwnership {
+        new_owner: String,
+        expiry: Option<Timestamp>,
+    },
+    /// Accepts a pending ownership transfer. Can only be called by the pending owner.
+    AcceptOwnership {},
+    /// Gives up ownership forever. Can only be called by the current owner.
+    /// A pending ownership transfer is cancelled.
+    RenounceOwnership {},
+}
+
+#[derive(Error, Debug)]
+pub enum OwnershipError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("Caller is not the contract's current owner")]
+    NotOwner,
+    #[error("Contract ownership has been renounced")]
+    NoOwner,
+    #[error("There is no pending ownership transfer")]
+    TransferNotFound,
+    #[error("Caller is not the contract's pending owner")]
+    NotPendingOwner,
+    #[error("The pending ownership transfer has expired")]
+    TransferExpired,
+    #[error("The expiry of an ownership transfer must be in the future")]
+    InvalidExpiry,
+}
+
+/// Sets the initial owner of the contract. Usually called in instantiate.
+///
+/// Passing `None` creates a contract without an owner.
+pub fn initialize_owner(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    owner: Option<&str>,
+) -> StdResult<Ownership> {
+    let ownership = Ownership {
+        owner: owner.map(|o| api.addr_validate(o)).transpose()?,
+        pending_owner: None,
+        pending_expiry: None,
+    };
+    singleton(storage, OWNERSHIP_KEY).save(&ownership)?;
+    Ok(ownership)
+}
+
+/// Loads the ownership state. Returns an error if [`initialize_owner`] was never called.
+pub fn get_ownership(storage: &dyn Storage) -> StdResult<Ownership> {
+    singleton_read(storage, OWNERSHIP_KEY).load()
+}
+
+/// Ensures the given address is the current owner
+pub fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), OwnershipError> {
+    get_ownership(storage)?.assert_owner(sender)
+}
+
+/// Executes an [`OwnershipAction`] on behalf of `sender` and stores the resulting state.
+///
+/// Returns the new ownership state, which can be turned into response attributes
+/// using [`Ownership::into_attributes`].
+pub fn update_ownership(
+    deps: DepsMut,
+    block: &BlockInfo,
+    sender: &Addr,
+    action: OwnershipAction,
+) -> Result<Ownership, OwnershipError> {
+    let ownership = get_ownership(deps.storage)?;
+
+    let ownership = match action {
+        OwnershipAction::TransferOwnership { new_owner, expiry } => {
+            ownership.assert_owner(sender)?;
+            if let Some(expiry) = expiry {
+                if expiry <= block.time {
+                    return Err(OwnershipError::InvalidExpiry);
+                }
+            }
+            Ownership {
+                pending_owner: Some(deps.api.addr_validate(&new_owner)?),
+                pending_expiry: expiry,
+                ..ownership
+            }
+        }
+        OwnershipAction::AcceptOwnership {} => {
+            let pending_owner = ownership
+                .pending_owner
+                .ok_or(OwnershipError::TransferNotFound)?;
+            if &pending_owner != sender {
+                return Err(OwnershipError::NotPendingOwner);
+            }
+            if let Some(expiry) = ownership.pending_expiry {
+                if expiry <= block.time {
+                    return Err(OwnershipError::TransferExpired);
+                }
+            }
+            Ownership {
+                owner: Some(pending_owner),
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        }
+        OwnershipAction::RenounceOwnership {} => {
+            ownership.assert_owner(sender)?;
+            Ownership::default()
+        }
+    };
+
+    singleton(deps.storage, OWNERSHIP_KEY).save(&ownership)?;
+    Ok(ownership)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    fn setup(deps: DepsMut) {
+        initialize_owner(deps.storage, deps.api, Some("larry")).unwrap();
+    }
+
+    #[test]
+    fn initialize_owner_works() {
+        let mut deps = mock_dependencies();
+        let ownership = initialize_owner(&mut deps.storage, &deps.api, Some("larry")).unwrap();
+        assert_eq!(ownership.owner, Some(Addr::unchecked("larry")));
+        assert_eq!(get_ownership(&deps.storage).unwrap(), ownership);
+
+        let mut deps = mock_dependencies();
+        let ownership = initialize_owner(&mut deps.storage, &deps.api, None).unwrap();
+        assert_eq!(ownership, Ownership::default());
+    }
+
+    #[test]
+    fn initialize_owner_validates_address() {
+        let mut deps = mock_dependencies();
+        let err = initialize_owner(&mut deps.storage, &deps.api, Some("x")).unwrap_err();
+        assert!(matches!(err, StdError::GenericErr { .. }));
+    }
+
+    #[test]
+    fn assert_owner_works() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        assert_owner(&deps.storage, &Addr::unchecked("larry")).unwrap();
+        let err = assert_owner(&deps.storage, &Addr::unchecked("jake")).unwrap_err();
+        assert!(matches!(err, OwnershipError::NotOwner));
+    }
+
+    #[test]
+    fn transfer_and_accept_works() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+        let env = mock_env();
+
+        // only the owner can propose
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("jake"),
+            OwnershipAction::TransferOwnership {
+                new_owner: "jake".to_string(),
+                expiry: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::NotOwner));
+
+        let ownership = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("larry"),
+            OwnershipAction::TransferOwnership {
+                new_owner: "jake".to_string(),
+                expiry: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(ownership.owner, Some(Addr::unchecked("larry")));
+        assert_eq!(ownership.pending_owner, Some(Addr::unchecked("jake")));
+
+        // only the pending owner can accept
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("pumpkin"),
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::NotPendingOwner));
+
+        let ownership = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("jake"),
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap();
+        assert_eq!(
+            ownership,
+            Ownership {
+                owner: Some(Addr::unchecked("jake")),
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        );
+        assert_eq!(get_ownership(&deps.storage).unwrap(), ownership);
+
+        // nothing left to accept
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("jake"),
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::TransferNotFound));
+    }
+
+    #[test]
+    fn transfer_expiry_works() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+        let mut env = mock_env();
+
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("larry"),
+            OwnershipAction::TransferOwnership {
+                new_owner: "jake".to_string(),
+                expiry: Some(env.block.time),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::InvalidExpiry));
+
+        let expiry = env.block.time.plus_seconds(100);
+        update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("larry"),
+            OwnershipAction::TransferOwnership {
+                new_owner: "jake".to_string(),
+                expiry: Some(expiry),
+            },
+        )
+        .unwrap();
+
+        env.block.time = expiry;
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("jake"),
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::TransferExpired));
+    }
+
+    #[test]
+    fn renounce_works() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+        let env = mock_env();
+
+        update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("larry"),
+            OwnershipAction::TransferOwnership {
+                new_owner: "jake".to_string(),
+                expiry: None,
+            },
+        )
+        .unwrap();
+
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("jake"),
+            OwnershipAction::RenounceOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::NotOwner));
+
+        let ownership = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("larry"),
+            OwnershipAction::RenounceOwnership {},
+        )
+        .unwrap();
+        assert_eq!(ownership, Ownership::default());
+
+        // the pending transfer was cancelled
+        let err = update_ownership(
+            deps.as_mut(),
+            &env.block,
+            &Addr::unchecked("jake"),
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, OwnershipError::TransferNotFound));
+
+        let err = assert_owner(&deps.storage, &Addr::unchecked("larry")).unwrap_err();
+        assert!(matches!(err, OwnershipError::NoOwner));
+    }
+
+    #[test]
+    fn into_attributes_works() {
+        let ownership = Ownership {
+            owner: Some(Addr::unchecked("larry")),
+            pending_owner: None,
+            pending_expiry: Some(Timestamp::from_seconds(123)),
+        };
+        assert_eq!(
+            ownership.into_attributes(),
+            vec![
+                attr("owner", "larry"),
+                attr("pending_owner", "none"),
+                attr("pending_expiry", "123.000000000"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use cosmwasm_std::{StdError, StdResult, Storage, Timestamp, Uint128, Uint256};
+
+use crate::bucket::{bucket, bucket_read};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// A token bucket rate limiter storing one bucket per key (e.g. per address or per
+/// address and denom) in contract storage.
+///
+/// Every bucket holds up to `capacity` tokens and starts full. Tokens are refilled continuously
+/// such that an empty bucket is full again after `window_seconds`. In other words, no more than
+/// `capacity` tokens can be used in a burst and no more than `capacity` tokens per
+/// `window_seconds` in the long run.
+///
+/// The refill is computed with exact integer math, so frequent small updates do not lose
+/// rounding remainders.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{testing::{mock_env, MockStorage}, Uint128};
+/// use cosmwasm_storage::RateLimiter;
+///
+/// // at most 1000 tokens per day
+/// const WITHDRAWALS: RateLimiter = RateLimiter::new(b"withdrawals", Uint128::new(1000), 86_400);
+///
+/// let mut storage = MockStorage::new();
+/// let now = mock_env().block.time;
+///
+/// let remaining = WITHDRAWALS.check_and_update(&mut storage, b"alice", Uint128::new(600), now).unwrap();
+/// assert_eq!(remaining, Uint128::new(400));
+/// WITHDRAWALS.check_and_update(&mut storage, b"alice", Uint128::new(600), now).unwrap_err();
+///
+/// // half a day later, half of the capacity was refilled
+/// let later = now.plus_seconds(43_200);
+/// WITHDRAWALS.check_and_update(&mut storage, b"alice", Uint128::new(900), later).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimiter<'a> {
+    namespace: &'a [u8],
+    capacity: Uint128,
+    window_seconds: u64,
+}
+
+/// The state of a single bucket as stored in contract storage
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct BucketState {
+    /// The amount of used capacity, scaled by the window length in nanoseconds
+    level: Uint256,
+    /// The point in time at which `level` was calculated
+    last_update: Timestamp,
+}
+
+#[derive(Error, Debug)]
+pub enum RateLimitError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("Rate limit exceeded: requested {requested}, available {available}")]
+    Exceeded {
+        requested: Uint128,
+        available: Uint128,
+    },
+}
+
+impl<'a> RateLimiter<'a> {
+    /// Creates a rate limiter storing its buckets in the given namespace.
+    ///
+    /// Panics if `window_seconds` is zero.
+    pub const fn new(namespace: &'a [u8], capacity: Uint128, window_seconds: u64) -> Self {
+        assert!(window_seconds > 0, "window must not be empty");
+        RateLimiter {
+            namespace,
+            capacity,
+            window_seconds,
+        }
+    }
+
+    pub fn capacity(&self) -> Uint128 {
+        self.capacity
+    }
+
+    pub fn window_seconds(&self) -> u64 {
+        self.window_seconds
+    }
+
+    /// Returns the amount that can be used for the given key at the given point in time.
+    pub fn available(
+        &self,
+        storage: &dyn Storage,
+        key: &[u8],
+        now: Timestamp,
+    ) -> StdResult<Uint128> {
+        let state = bucket_read(storage, self.namespace).may_load(key)?;
+        let level = self.current_level(state.as_ref(), now);
+        Ok(self.available_from_level(level))
+    }
+
+    /// Checks that `amount` is available for the given key and subtracts it on success.
+    ///
+    /// Returns the amount remaining available after the update.
+    /// If the limit would be exceeded, nothing is written and an error is returned.
+    pub fn check_and_update(
+        &self,
+        storage: &mut dyn Storage,
+        key: &[u8],
+        amount: Uint128,
+        now: Timestamp,
+    ) -> Result<Uint128, RateLimitError> {
+        let mut bucket = bucket(storage, self.namespace);
+        let state = bucket.may_load(key)?;
+        let level = self.current_level(state.as_ref(), now);
+
+        let available = self.available_from_level(level);
+        if amount > available {
+            return Err(RateLimitError::Exceeded {
+                requested: amount,
+                available,
+            });
+        }
+
+        let level = level + amount.full_mul(self.window_nanos());
+        bucket.save(
+            key,
+            &BucketState {
+                level,
+                last_update: now,
+            },
+        )?;
+        Ok(self.available_from_level(level))
+    }
+
+    /// Removes the stored state for the given key, which resets the bucket to full capacity.
+    pub fn reset(&self, storage: &mut dyn Storage, key: &[u8]) {
+        bucket::<BucketState>(storage, self.namespace).remove(key)
+    }
+
+    fn window_nanos(&self) -> u64 {
+        // Windows longer than ~584 years are capped. This does not make a practical difference.
+        self.window_seconds.saturating_mul(NANOS_PER_SECOND)
+    }
+
+    /// The maximum level, i.e. an empty bucket
+    fn max_level(&self) -> Uint256 {
+        self.capacity.full_mul(self.window_nanos())
+    }
+
+    /// Calculates the used capacity at `now`, taking into account the refill since the last update.
+    /// Time running backwards is treated as no time passing.
+    fn current_level(&self, state: Option<&BucketState>, now: Timestamp) -> Uint256 {
+        match state {
+            Some(state) => {
+                let elapsed = now.nanos().saturating_sub(state.last_update.nanos());
+                let refill = self.capacity.full_mul(elapsed);
+                state.level.saturating_sub(refill).min(self.max_level())
+            }
+            None => Uint256::zero(),
+        }
+    }
+
+    fn available_from_level(&self, level: Uint256) -> Uint128 {
+        let free = self.max_level() - level;
+        // The result is <= capacity, so the conversion cannot fail
+        Uint128::try_from(free / Uint256::from(self.window_nanos())).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::Xoshiro256PlusPlus;
+
+    const LIMITER: RateLimiter = RateLimiter::new(b"limits", Uint128::new(100), 10);
+
+    fn t(seconds: u64) -> Timestamp {
+        Timestamp::from_seconds(1_600_000_000).plus_seconds(seconds)
+    }
+
+    #[test]
+    #[should_panic(expected = "window must not be empty")]
+    fn new_panics_for_empty_window() {
+        RateLimiter::new(b"limits", Uint128::new(100), 0);
+    }
+
+    #[test]
+    fn new_bucket_is_full() {
+        let storage = MockStorage::new();
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(0)).unwrap(),
+            Uint128::new(100)
+        );
+    }
+
+    #[test]
+    fn check_and_update_works() {
+        let mut storage = MockStorage::new();
+
+        let remaining = LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(30), t(0))
+            .unwrap();
+        assert_eq!(remaining, Uint128::new(70));
+
+        let remaining = LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(70), t(0))
+            .unwrap();
+        assert_eq!(remaining, Uint128::zero());
+
+        let err = LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(1), t(0))
+            .unwrap_err();
+        match err {
+            RateLimitError::Exceeded {
+                requested,
+                available,
+            } => {
+                assert_eq!(requested, Uint128::new(1));
+                assert_eq!(available, Uint128::zero());
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        // other keys are independent
+        assert_eq!(
+            LIMITER.available(&storage, b"bob", t(0)).unwrap(),
+            Uint128::new(100)
+        );
+    }
+
+    #[test]
+    fn failed_check_does_not_change_state() {
+        let mut storage = MockStorage::new();
+        LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(60), t(0))
+            .unwrap();
+        LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(60), t(0))
+            .unwrap_err();
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(0)).unwrap(),
+            Uint128::new(40)
+        );
+    }
+
+    #[test]
+    fn refill_works() {
+        let mut storage = MockStorage::new();
+        LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(100), t(0))
+            .unwrap();
+
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(1)).unwrap(),
+            Uint128::new(10)
+        );
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(5)).unwrap(),
+            Uint128::new(50)
+        );
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(10)).unwrap(),
+            Uint128::new(100)
+        );
+        // capped at capacity
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(1000)).unwrap(),
+            Uint128::new(100)
+        );
+        // time going backwards does not refill
+        assert_eq!(
+            LIMITER
+                .available(&storage, b"alice", t(0).minus_seconds(5))
+                .unwrap(),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn refill_does_not_lose_remainders() {
+        // 3 tokens per 10 seconds, i.e. one token every 3.33 seconds
+        let limiter = RateLimiter::new(b"limits", Uint128::new(3), 10);
+        let mut storage = MockStorage::new();
+        limiter
+            .check_and_update(&mut storage, b"alice", Uint128::new(3), t(0))
+            .unwrap();
+
+        // Polling every second must refill the same amount as waiting for 10 seconds
+        for second in 1..=10 {
+            limiter
+                .check_and_update(&mut storage, b"alice", Uint128::zero(), t(second))
+                .unwrap();
+        }
+        assert_eq!(
+            limiter.available(&storage, b"alice", t(10)).unwrap(),
+            Uint128::new(3)
+        );
+    }
+
+    #[test]
+    fn reset_works() {
+        let mut storage = MockStorage::new();
+        LIMITER
+            .check_and_update(&mut storage, b"alice", Uint128::new(100), t(0))
+            .unwrap();
+        LIMITER.reset(&mut storage, b"alice");
+        assert_eq!(
+            LIMITER.available(&storage, b"alice", t(0)).unwrap(),
+            Uint128::new(100)
+        );
+    }
+
+    #[test]
+    fn large_values_do_not_overflow() {
+        let limiter = RateLimiter::new(b"limits", Uint128::MAX, u64::MAX);
+        let mut storage = MockStorage::new();
+        let remaining = limiter
+            .check_and_update(&mut storage, b"alice", Uint128::MAX, t(0))
+            .unwrap();
+        assert_eq!(remaining, Uint128::zero());
+        let available = limiter
+            .available(&storage, b"alice", Timestamp::from_nanos(u64::MAX))
+            .unwrap();
+        assert!(available > Uint128::zero());
+    }
+
+    #[test]
+    fn property_usage_never_exceeds_limit() {
+        // Over a time span `T`, the total amount used can never exceed the initial capacity
+        // plus the refill for `T`, no matter how the requests are distributed.
+        for seed in 1..50u64 {
+            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+            let capacity = rng.next_u64() % 1000 + 1;
+            let window = rng.next_u64() % 100 + 1;
+            let limiter = RateLimiter::new(b"limits", Uint128::from(capacity), window);
+            let mut storage = MockStorage::new();
+
+            let start = t(0).nanos();
+            let mut now = start;
+            let mut used = 0u128;
+            for _ in 0..200 {
+                now += rng.next_u64() % (window * NANOS_PER_SECOND);
+                let amount = rng.next_u64() % (capacity + 1);
+                let time = Timestamp::from_nanos(now);
+                let before = limiter.available(&storage, b"key", time).unwrap();
+                match limiter.check_and_update(&mut storage, b"key", Uint128::from(amount), time) {
+                    Ok(remaining) => {
+                        used += amount as u128;
+                        assert_eq!(remaining + Uint128::from(amount), before);
+                    }
+                    Err(RateLimitError::Exceeded { available, .. }) => {
+                        assert_eq!(available, before);
+                        assert!(Uint128::from(amount) > available);
+                    }
+                    Err(err) => panic!("Unexpected error: {:?}", err),
+                }
+                assert!(before <= Uint128::from(capacity));
+            }
+
+            let elapsed = (now - start) as u128;
+            let max_refill =
+                capacity as u128 * elapsed / (window as u128 * NANOS_PER_SECOND as u128);
+            assert!(used <= capacity as u128 + max_refill);
+        }
+    }
+
+    #[test]
+    fn property_refill_is_monotonic() {
+        for seed in 1..50u64 {
+            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+            let capacity = rng.next_u64() % 1000 + 1;
+            let window = rng.next_u64() % 100 + 1;
+            let limiter = RateLimiter::new(b"limits", Uint128::from(capacity), window);
+            let mut storage = MockStorage::new();
+            limiter
+                .check_and_update(&mut storage, b"key", Uint128::from(capacity), t(0))
+                .unwrap();
+
+            let mut last = Uint128::zero();
+            let mut now = t(0).nanos();
+            while last < Uint128::from(capacity) {
+                now += rng.next_u64() % NANOS_PER_SECOND;
+                let available = limiter
+                    .available(&storage, b"key", Timestamp::from_nanos(now))
+                    .unwrap();
+                assert!(available >= last);
+                last = available;
+            }
+            // full again after exactly one window
+            assert_eq!(
+                limiter.available(&storage, b"key", t(window)).unwrap(),
+                Uint128::from(capacity)
+            );
+            assert!(
+                limiter
+                    .available(&storage, b"key", t(window).minus_nanos(1))
+                    .unwrap()
+                    < Uint128::from(capacity)
+            );
+        }
+    }
+}
@@ -0,0 +1,561 @@
+use crate::fixture::Fixture;
+
+const ECDSA_HASH_HEX: &str = "5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0";
+const ECDSA_SIG_HEX: &str = "207082eb2c3dfa0b454e0906051270ba4074ac93760ba9e7110cd9471475111151eb0dbbc9920e72146fb564f99d039802bf6ef2561446eb126ef364d21ee9c4";
+const ECDSA_PUBKEY_HEX: &str = "04051c1ee2190ecfb174bfe4f90763f2b4ff7517b70a2aec1876ebcfd644c4633fb03f3cfbd94b1f376e34592d9d41ccaf640bb751b00a1fadeb0c01157769eb73";
+
+const EDDSA_MSG_HEX: &str = "";
+const EDDSA_SIG_HEX: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+const EDDSA_PUBKEY_HEX: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+/// Values used to configure the cases for a backend
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// An address the backend's API considers valid
+    pub valid_address: String,
+    /// An address the backend's API considers invalid
+    pub invalid_address: String,
+    /// The gas limit of every call
+    pub gas_limit: u64,
+}
+
+/// The default works with the mocks in `cosmwasm_vm::testing`
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            valid_address: "conformance".to_string(),
+            invalid_address: "a".to_string(),
+            gas_limit: 500_000_000_000,
+        }
+    }
+}
+
+/// The entry point a case is called through
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EntryPoint {
+    Execute,
+    /// Storage is read-only in queries
+    Query,
+}
+
+/// The expected behaviour of a case
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expectation {
+    /// The call succeeds and returns these bytes
+    Returns(Vec<u8>),
+    /// The call succeeds and the storage contains `value` at `key` afterwards
+    Stores {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    /// The call returns a serialized `SystemResult` that is `Ok` if `ok` is true, `Err` otherwise
+    QueryResult { ok: bool },
+    /// The call fails with an error whose message contains this text
+    FailsWith(String),
+}
+
+/// One host import behaviour to check
+#[derive(Clone, Debug)]
+pub struct Case {
+    pub name: &'static str,
+    /// The host import under test
+    pub import: &'static str,
+    pub entry_point: EntryPoint,
+    /// The Wasm text of the fixture contract
+    pub wat: String,
+    /// Storage entries set before the call
+    pub setup: Vec<(Vec<u8>, Vec<u8>)>,
+    pub expectation: Expectation,
+    /// An upper bound of the gas used by a successful call, without gas metered by
+    /// the backend. 0 means unbounded.
+    pub max_gas: u64,
+}
+
+impl Case {
+    /// The fixture contract in binary form
+    pub fn wasm(&self) -> Vec<u8> {
+        wat::parse_str(&self.wat).unwrap()
+    }
+}
+
+struct Builder {
+    name: &'static str,
+    import: &'static str,
+    entry_point: EntryPoint,
+    fixture: Fixture,
+    setup: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Builder {
+    fn new(import: &'static str, name: &'static str) -> Self {
+        Builder {
+            name,
+            import,
+            entry_point: EntryPoint::Execute,
+            fixture: Fixture::default(),
+            setup: Vec::new(),
+        }
+    }
+
+    fn query(mut self) -> Self {
+        self.entry_point = EntryPoint::Query;
+        self
+    }
+
+    fn set(mut self, key: &[u8], value: &[u8]) -> Self {
+        self.setup.push((key.to_vec(), value.to_vec()));
+        self
+    }
+
+    fn bytes(&mut self, data: &[u8]) -> String {
+        self.fixture.bytes(data)
+    }
+
+    fn build(self, body: &str, expectation: Expectation, max_gas: u64) -> Case {
+        Case {
+            name: self.name,
+            import: self.import,
+            entry_point: self.entry_point,
+            wat: self.fixture.wat(body),
+            setup: self.setup,
+            expectation,
+            max_gas,
+        }
+    }
+}
+
+fn returns_u32(value: u32) -> Expectation {
+    Expectation::Returns(value.to_le_bytes().to_vec())
+}
+
+fn fails_with(message: &str) -> Expectation {
+    Expectation::FailsWith(message.to_string())
+}
+
+/// Encodes like the sections of `db_next` and `ed25519_batch_verify`
+fn sections(elements: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for element in elements {
+        out.extend_from_slice(element);
+        out.extend_from_slice(&(element.len() as u32).to_be_bytes());
+    }
+    out
+}
+
+/// A body that returns 1 if `expression` is non-zero and 0 otherwise
+fn is_non_zero(expression: &str) -> String {
+    format!(
+        "(if (result i32) {} (then (call $u32 (i32.const 1))) (else (call $u32 (i32.const 0))))",
+        expression
+    )
+}
+
+/// Returns all cases
+pub fn cases(options: &Options) -> Vec<Case> {
+    let mut out = Vec::new();
+    storage_cases(&mut out);
+    iterator_cases(&mut out);
+    address_cases(options, &mut out);
+    crypto_cases(&mut out);
+    other_cases(options, &mut out);
+    out
+}
+
+fn storage_cases(out: &mut Vec<Case>) {
+    let mut b = Builder::new("db_read", "db_read returns the value").set(b"key", b"value");
+    let body = format!("(call $db_read {})", b.bytes(b"key"));
+    out.push(b.build(&body, Expectation::Returns(b"value".to_vec()), 50_000_000));
+
+    let mut b = Builder::new("db_read", "db_read returns 0 for missing keys");
+    let body = format!("(call $u32 (call $db_read {}))", b.bytes(b"key"));
+    out.push(b.build(&body, returns_u32(0), 50_000_000));
+
+    let mut b = Builder::new("db_read", "db_read works in queries")
+        .query()
+        .set(b"key", b"value");
+    let body = format!("(call $db_read {})", b.bytes(b"key"));
+    out.push(b.build(&body, Expectation::Returns(b"value".to_vec()), 50_000_000));
+
+    let b = Builder::new("db_read", "db_read rejects keys above 64 KiB");
+    out.push(b.build(
+        "(call $db_read (call $filled (i32.const 65537)))",
+        fails_with("Region length too big"),
+        0,
+    ));
+
+    let mut b = Builder::new("db_write", "db_write stores the value");
+    let body = format!(
+        "(call $db_write {} {}) (call $allocate (i32.const 0))",
+        b.bytes(b"key"),
+        b.bytes(b"value")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Stores {
+            key: b"key".to_vec(),
+            value: Some(b"value".to_vec()),
+        },
+        50_000_000,
+    ));
+
+    let mut b = Builder::new("db_write", "db_write overwrites values").set(b"key", b"old");
+    let body = format!(
+        "(call $db_write {} {}) (call $allocate (i32.const 0))",
+        b.bytes(b"key"),
+        b.bytes(b"new")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Stores {
+            key: b"key".to_vec(),
+            value: Some(b"new".to_vec()),
+        },
+        50_000_000,
+    ));
+
+    let mut b = Builder::new("db_write", "db_write fails in queries").query();
+    let body = format!(
+        "(call $db_write {} {}) (call $allocate (i32.const 0))",
+        b.bytes(b"key"),
+        b.bytes(b"value")
+    );
+    out.push(b.build(
+        &body,
+        fails_with("Must not call a writing storage function in this context"),
+        0,
+    ));
+
+    let mut b = Builder::new("db_write", "db_write rejects values above 128 KiB");
+    let body = format!(
+        "(call $db_write {} (call $filled (i32.const 131073))) (call $allocate (i32.const 0))",
+        b.bytes(b"key")
+    );
+    out.push(b.build(&body, fails_with("Region length too big"), 0));
+
+    let mut b = Builder::new("db_remove", "db_remove removes the key").set(b"key", b"value");
+    let body = format!(
+        "(call $db_remove {}) (call $allocate (i32.const 0))",
+        b.bytes(b"key")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Stores {
+            key: b"key".to_vec(),
+            value: None,
+        },
+        50_000_000,
+    ));
+
+    let mut b = Builder::new("db_remove", "db_remove ignores missing keys");
+    let body = format!(
+        "(call $db_remove {}) (call $allocate (i32.const 0))",
+        b.bytes(b"key")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Stores {
+            key: b"key".to_vec(),
+            value: None,
+        },
+        50_000_000,
+    ));
+
+    let mut b = Builder::new("db_remove", "db_remove fails in queries")
+        .query()
+        .set(b"key", b"value");
+    let body = format!(
+        "(call $db_remove {}) (call $allocate (i32.const 0))",
+        b.bytes(b"key")
+    );
+    out.push(b.build(
+        &body,
+        fails_with("Must not call a writing storage function in this context"),
+        0,
+    ));
+}
+
+fn iterator_cases(out: &mut Vec<Case>) {
+    let with_data = |name| {
+        Builder::new("db_scan", name)
+            .set(b"a", b"1")
+            .set(b"b", b"2")
+            .set(b"c", b"3")
+    };
+
+    let mut b = with_data("db_scan with inclusive start ascending");
+    let body = format!(
+        "(local.set $a (call $db_scan {} (i32.const 0) (i32.const 1))) (call $db_next (local.get $a))",
+        b.bytes(b"b")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Returns(sections(&[b"b", b"2"])),
+        50_000_000,
+    ));
+
+    let mut b = with_data("db_scan with exclusive end descending");
+    let body = format!(
+        "(local.set $a (call $db_scan (i32.const 0) {} (i32.const 2))) (call $db_next (local.get $a))",
+        b.bytes(b"c")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Returns(sections(&[b"b", b"2"])),
+        50_000_000,
+    ));
+
+    let mut b = with_data("db_scan works in queries").query();
+    let body = format!(
+        "(local.set $a (call $db_scan {} (i32.const 0) (i32.const 1))) (call $db_next (local.get $a))",
+        b.bytes(b"c")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Returns(sections(&[b"c", b"3"])),
+        50_000_000,
+    ));
+
+    let mut b = with_data("db_scan iterators are independent");
+    let body = format!(
+        "(local.set $a (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (local.set $b (call $db_scan {} (i32.const 0) (i32.const 2)))
+    (drop (call $db_next (local.get $a)))
+    (drop (call $db_next (local.get $b)))
+    (call $db_next (local.get $a))",
+        b.bytes(b"a")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Returns(sections(&[b"b", b"2"])),
+        50_000_000,
+    ));
+
+    let mut b =
+        Builder::new("db_next", "db_next returns empty sections when exhausted").set(b"a", b"1");
+    let body = format!(
+        "(local.set $a (call $db_scan {} (i32.const 0) (i32.const 1)))
+    (drop (call $db_next (local.get $a)))
+    (call $db_next (local.get $a))",
+        b.bytes(b"a")
+    );
+    out.push(b.build(
+        &body,
+        Expectation::Returns(sections(&[b"", b""])),
+        50_000_000,
+    ));
+
+    let b = Builder::new("db_next", "db_next fails for unknown iterators");
+    out.push(b.build(
+        "(call $db_next (i32.const 42))",
+        fails_with("Iterator with ID 42 does not exist"),
+        0,
+    ));
+
+    let b = Builder::new("db_scan", "db_scan rejects invalid orders");
+    out.push(b.build(
+        "(call $db_scan (i32.const 0) (i32.const 0) (i32.const 3))",
+        fails_with("invalid value for iteration order"),
+        0,
+    ));
+}
+
+fn address_cases(options: &Options, out: &mut Vec<Case>) {
+    let valid = options.valid_address.as_bytes();
+    let invalid = options.invalid_address.as_bytes();
+
+    let mut b = Builder::new("addr_validate", "addr_validate accepts valid addresses");
+    let body = is_non_zero(&format!("(call $addr_validate {})", b.bytes(valid)));
+    out.push(b.build(&body, returns_u32(0), 0));
+
+    let mut b = Builder::new("addr_validate", "addr_validate rejects invalid addresses");
+    let body = is_non_zero(&format!("(call $addr_validate {})", b.bytes(invalid)));
+    out.push(b.build(&body, returns_u32(1), 0));
+
+    let mut b = Builder::new(
+        "addr_canonicalize",
+        "addr_canonicalize and addr_humanize roundtrip",
+    );
+    let body = format!(
+        "(local.set $a (call $allocate (i32.const 64)))
+    (local.set $b (call $allocate (i32.const 256)))
+    (local.set $c (i32.or
+      (call $addr_canonicalize {} (local.get $a))
+      (call $addr_humanize (local.get $a) (local.get $b))))
+    (if (result i32) (local.get $c) (then (call $allocate (i32.const 0))) (else (local.get $b)))",
+        b.bytes(valid)
+    );
+    out.push(b.build(&body, Expectation::Returns(valid.to_vec()), 0));
+
+    let mut b = Builder::new(
+        "addr_canonicalize",
+        "addr_canonicalize rejects invalid addresses",
+    );
+    let body = is_non_zero(&format!(
+        "(call $addr_canonicalize {} (call $allocate (i32.const 64)))",
+        b.bytes(invalid)
+    ));
+    out.push(b.build(&body, returns_u32(1), 0));
+}
+
+fn crypto_cases(out: &mut Vec<Case>) {
+    let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+    let signature = hex::decode(ECDSA_SIG_HEX).unwrap();
+    let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+    let mut other_hash = hash.clone();
+    other_hash[0] ^= 0x01;
+
+    let mut b = Builder::new(
+        "secp256k1_verify",
+        "secp256k1_verify accepts valid signatures",
+    );
+    let body = format!(
+        "(call $u32 (call $secp256k1_verify {} {} {}))",
+        b.bytes(&hash),
+        b.bytes(&signature),
+        b.bytes(&pubkey)
+    );
+    out.push(b.build(&body, returns_u32(0), 200_000_000_000));
+
+    let mut b = Builder::new(
+        "secp256k1_verify",
+        "secp256k1_verify rejects invalid signatures",
+    );
+    let body = format!(
+        "(call $u32 (call $secp256k1_verify {} {} {}))",
+        b.bytes(&other_hash),
+        b.bytes(&signature),
+        b.bytes(&pubkey)
+    );
+    out.push(b.build(&body, returns_u32(1), 200_000_000_000));
+
+    let mut b = Builder::new(
+        "secp256k1_verify",
+        "secp256k1_verify returns an error code for malformed pubkeys",
+    );
+    let body = format!(
+        "(call $u32 (i32.gt_u (call $secp256k1_verify {} {} {}) (i32.const 1)))",
+        b.bytes(&hash),
+        b.bytes(&signature),
+        b.bytes(&pubkey[1..])
+    );
+    out.push(b.build(&body, returns_u32(1), 200_000_000_000));
+
+    let mut b = Builder::new(
+        "secp256k1_recover_pubkey",
+        "secp256k1_recover_pubkey recovers the pubkey",
+    );
+    let body = format!(
+        "(i32.wrap_i64 (call $secp256k1_recover_pubkey {} {} (i32.const 0)))",
+        b.bytes(&hash),
+        b.bytes(&signature)
+    );
+    out.push(b.build(&body, Expectation::Returns(pubkey), 200_000_000_000));
+
+    let message = hex::decode(EDDSA_MSG_HEX).unwrap();
+    let signature = hex::decode(EDDSA_SIG_HEX).unwrap();
+    let pubkey = hex::decode(EDDSA_PUBKEY_HEX).unwrap();
+
+    let mut b = Builder::new("ed25519_verify", "ed25519_verify accepts valid signatures");
+    let body = format!(
+        "(call $u32 (call $ed25519_verify {} {} {}))",
+        b.bytes(&message),
+        b.bytes(&signature),
+        b.bytes(&pubkey)
+    );
+    out.push(b.build(&body, returns_u32(0), 80_000_000_000));
+
+    let mut b = Builder::new(
+        "ed25519_verify",
+        "ed25519_verify rejects invalid signatures",
+    );
+    let body = format!(
+        "(call $u32 (call $ed25519_verify {} {} {}))",
+        b.bytes(b"other message"),
+        b.bytes(&signature),
+        b.bytes(&pubkey)
+    );
+    out.push(b.build(&body, returns_u32(1), 80_000_000_000));
+
+    let mut b = Builder::new(
+        "ed25519_batch_verify",
+        "ed25519_batch_verify accepts valid signatures",
+    );
+    let body = format!(
+        "(call $u32 (call $ed25519_batch_verify {} {} {}))",
+        b.bytes(&sections(&[&message, &message])),
+        b.bytes(&sections(&[&signature, &signature])),
+        b.bytes(&sections(&[&pubkey, &pubkey]))
+    );
+    out.push(b.build(&body, returns_u32(0), 80_000_000_000));
+
+    let mut b = Builder::new(
+        "ed25519_batch_verify",
+        "ed25519_batch_verify rejects invalid signatures",
+    );
+    let body = format!(
+        "(call $u32 (call $ed25519_batch_verify {} {} {}))",
+        b.bytes(&sections(&[&message, b"other message"])),
+        b.bytes(&sections(&[&signature, &signature])),
+        b.bytes(&sections(&[&pubkey, &pubkey]))
+    );
+    out.push(b.build(&body, returns_u32(1), 80_000_000_000));
+}
+
+fn other_cases(options: &Options, out: &mut Vec<Case>) {
+    let mut b = Builder::new("debug", "debug succeeds");
+    let body = format!(
+        "(call $debug {}) (call $allocate (i32.const 0))",
+        b.bytes(b"conformance debug")
+    );
+    out.push(b.build(&body, Expectation::Returns(Vec::new()), 50_000_000));
+
+    let mut b = Builder::new("abort", "abort stops the execution");
+    let body = format!(
+        "(call $abort {}) (call $allocate (i32.const 0))",
+        b.bytes(b"conformance abort")
+    );
+    out.push(b.build(&body, fails_with("Aborted: conformance abort"), 0));
+
+    let mut b = Builder::new("query_chain", "query_chain returns query results");
+    let request = format!(
+        r#"{{"bank":{{"all_balances":{{"address":"{}"}}}}}}"#,
+        options.valid_address
+    );
+    let body = format!("(call $query_chain {})", b.bytes(request.as_bytes()));
+    out.push(b.build(&body, Expectation::QueryResult { ok: true }, 50_000_000));
+
+    let mut b = Builder::new(
+        "query_chain",
+        "query_chain returns system errors for invalid requests",
+    );
+    let body = format!(
+        "(call $query_chain {})",
+        b.bytes(br#"{"no_such_query":{}}"#)
+    );
+    out.push(b.build(&body, Expectation::QueryResult { ok: false }, 50_000_000));
+
+    let b = Builder::new("gas", "execution stops when the gas limit is reached");
+    out.push(b.build(
+        "(loop $forever (br $forever)) (i32.const 0)",
+        fails_with("Ran out of gas"),
+        0,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_compile() {
+        let cases = cases(&Options::default());
+        for case in &cases {
+            case.wasm();
+        }
+
+        let mut names: Vec<_> = cases.iter().map(|case| case.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), cases.len(), "Case names must be unique");
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+/// Where the constant data of a fixture starts in memory
+const DATA_OFFSET: usize = 1024;
+/// Where the heap of the bump allocator starts. Data must fit below.
+const HEAP_OFFSET: usize = 65536;
+
+/// All host imports of cosmwasm-vm with their Wasm signatures
+const IMPORTS: &[(&str, &str)] = &[
+    ("db_read", "(param i32) (result i32)"),
+    ("db_write", "(param i32 i32)"),
+    ("db_remove", "(param i32)"),
+    ("db_scan", "(param i32 i32 i32) (result i32)"),
+    ("db_next", "(param i32) (result i32)"),
+    ("addr_validate", "(param i32) (result i32)"),
+    ("addr_canonicalize", "(param i32 i32) (result i32)"),
+    ("addr_humanize", "(param i32 i32) (result i32)"),
+    ("secp256k1_verify", "(param i32 i32 i32) (result i32)"),
+    (
+        "secp256k1_recover_pubkey",
+        "(param i32 i32 i32) (result i64)",
+    ),
+    ("ed25519_verify", "(param i32 i32 i32) (result i32)"),
+    ("ed25519_batch_verify", "(param i32 i32 i32) (result i32)"),
+    ("debug", "(param i32)"),
+    ("abort", "(param i32)"),
+    ("query_chain", "(param i32) (result i32)"),
+];
+
+/// Builds the Wasm text of a minimal contract that runs a given body in `execute` and `query`.
+///
+/// The body is a sequence of instructions leaving a region pointer on the stack, whose data is
+/// returned to the host. It can use the locals `$a`, `$b` and `$c` and the helper functions
+/// `$allocate` (like the export), `$u32` (region containing a little endian u32) and
+/// `$filled` (region of the given length, filled with zeros).
+#[derive(Default)]
+pub(crate) struct Fixture {
+    data: Vec<u8>,
+}
+
+impl Fixture {
+    /// Stores `data` in the module and returns an expression creating a region pointing to it
+    pub fn bytes(&mut self, data: &[u8]) -> String {
+        let offset = DATA_OFFSET + self.data.len();
+        self.data.extend_from_slice(data);
+        assert!(
+            offset + data.len() <= HEAP_OFFSET,
+            "Fixture data does not fit into memory"
+        );
+        format!(
+            "(call $region (i32.const {}) (i32.const {}))",
+            offset,
+            data.len()
+        )
+    }
+
+    pub fn wat(&self, body: &str) -> String {
+        let mut imports = String::new();
+        for (name, signature) in IMPORTS {
+            writeln!(
+                imports,
+                r#"  (import "env" "{name}" (func ${name} {signature}))"#,
+                name = name,
+                signature = signature
+            )
+            .unwrap();
+        }
+        let mut data = String::new();
+        for byte in &self.data {
+            write!(data, "\\{:02x}", byte).unwrap();
+        }
+
+        format!(
+            r#"(module
+{imports}
+  (memory (export "memory") 4)
+  (global $heap (mut i32) (i32.const {heap}))
+  (data (i32.const {data_offset}) "{data}")
+
+  (func $allocate (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $heap))
+    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    ;; keep regions 8 byte aligned
+    (global.set $heap (i32.add (local.get $region)
+      (i32.and (i32.add (local.get $size) (i32.const 19)) (i32.const -8))))
+    (local.get $region))
+
+  (func (export "deallocate") (param i32))
+
+  (func (export "interface_version_8"))
+
+  (func $region (param $offset i32) (param $length i32) (result i32)
+    (local $region i32)
+    (local.set $region (call $allocate (i32.const 0)))
+    (i32.store (local.get $region) (local.get $offset))
+    (i32.store offset=4 (local.get $region) (local.get $length))
+    (i32.store offset=8 (local.get $region) (local.get $length))
+    (local.get $region))
+
+  (func $filled (param $length i32) (result i32)
+    (local $region i32)
+    (local.set $region (call $allocate (local.get $length)))
+    (i32.store offset=8 (local.get $region) (local.get $length))
+    (local.get $region))
+
+  (func $u32 (param $value i32) (result i32)
+    (local $region i32)
+    (local.set $region (call $filled (i32.const 4)))
+    (i32.store (i32.load (local.get $region)) (local.get $value))
+    (local.get $region))
+
+  (func $run (result i32)
+    (local $a i32)
+    (local $b i32)
+    (local $c i32)
+    {body})
+
+  (func (export "execute") (param i32 i32 i32) (result i32)
+    (call $run))
+
+  (func (export "query") (param i32 i32) (result i32)
+    (call $run))
+)
+"#,
+            imports = imports,
+            heap = HEAP_OFFSET,
+            data_offset = DATA_OFFSET,
+            data = data,
+            body = body,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_compiles() {
+        let mut fixture = Fixture::default();
+        let region = fixture.bytes(b"\x00foo\"");
+        let wat = fixture.wat(&region);
+        assert!(wat.contains(r#"(data (i32.const 1024) "\00\66\6f\6f\22")"#));
+        wat::parse_str(wat).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+//! Conformance checks for host imports, meant for chains and tools that embed
+//! cosmwasm-vm with their own [`Backend`](cosmwasm_vm::Backend).
+//!
+//! Every case is a minimal fixture contract (see [`Case::wat`]) calling one host import,
+//! together with the expected behaviour, including error cases and an upper bound of
+//! the gas used. [`run`] executes all cases against fresh backends:
+//!
+//! ```
+//! use cosmwasm_vm::testing::mock_backend;
+//! use cosmwasm_vm_conformance::{run, Options};
+//!
+//! let report = run(&Options::default(), || mock_backend(&[]));
+//! report.assert_passed();
+//! ```
+//!
+//! The cases can also be obtained via [`cases`] to run them in other runtimes.
+
+mod cases;
+mod fixture;
+mod runner;
+
+pub use crate::cases::{cases, Case, EntryPoint, Expectation, Options};
+pub use crate::runner::{run, CaseResult, Report};
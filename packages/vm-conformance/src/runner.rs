@@ -0,0 +1,203 @@
+use std::fmt;
+
+use cosmwasm_std::{Binary, ContractResult, SystemResult};
+use cosmwasm_vm::{
+    call_execute_raw, call_query_raw, from_slice, Backend, BackendApi, Instance, InstanceOptions,
+    Querier, Size, Storage, VmResult,
+};
+
+use crate::cases::{cases, Case, EntryPoint, Expectation, Options};
+
+const MEMORY_LIMIT: Option<Size> = Some(Size::mebi(16));
+
+/// The outcome of one case
+#[derive(Clone, Debug)]
+pub struct CaseResult {
+    pub name: &'static str,
+    pub import: &'static str,
+    /// The gas used by the call, without gas metered by the backend
+    pub gas_used: u64,
+    /// Describes the deviation from the expected behaviour, if any
+    pub failure: Option<String>,
+}
+
+/// The outcome of all cases, created by [`run`]
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub results: Vec<CaseResult>,
+}
+
+impl Report {
+    pub fn failures(&self) -> impl Iterator<Item = &CaseResult> {
+        self.results
+            .iter()
+            .filter(|result| result.failure.is_some())
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Panics with a list of all failures, if any
+    pub fn assert_passed(&self) {
+        if !self.passed() {
+            panic!("Conformance check failed:\n{}", self);
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            match &result.failure {
+                None => writeln!(f, "ok   [{}] {}", result.import, result.name)?,
+                Some(failure) => {
+                    writeln!(f, "FAIL [{}] {}: {}", result.import, result.name, failure)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs all cases, each with a fresh backend returned by `make_backend`.
+///
+/// The storage of the backend is populated through its [`Storage`] implementation
+/// before each call.
+pub fn run<A, S, Q>(options: &Options, mut make_backend: impl FnMut() -> Backend<A, S, Q>) -> Report
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    let results = cases(options)
+        .iter()
+        .map(|case| {
+            let (gas_used, failure) = match run_case(options, case, make_backend()) {
+                Ok(outcome) => outcome,
+                Err(err) => (0, Some(format!("Could not run case: {}", err))),
+            };
+            CaseResult {
+                name: case.name,
+                import: case.import,
+                gas_used,
+                failure,
+            }
+        })
+        .collect();
+    Report { results }
+}
+
+fn run_case<A, S, Q>(
+    options: &Options,
+    case: &Case,
+    mut backend: Backend<A, S, Q>,
+) -> VmResult<(u64, Option<String>)>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    for (key, value) in &case.setup {
+        backend.storage.set(key, value).0?;
+    }
+    let instance_options = InstanceOptions {
+        gas_limit: options.gas_limit,
+        print_debug: false,
+    };
+    let mut instance = Instance::from_code(&case.wasm(), backend, instance_options, MEMORY_LIMIT)?;
+
+    let result = match case.entry_point {
+        EntryPoint::Execute => call_execute_raw(&mut instance, b"{}", b"{}", b"{}"),
+        EntryPoint::Query => call_query_raw(&mut instance, b"{}", b"{}"),
+    };
+    let gas_used = instance.create_gas_report().used_internally;
+
+    let failure = match (&case.expectation, result) {
+        (Expectation::FailsWith(expected), Ok(data)) => Some(format!(
+            "Expected an error containing {:?}, got {:?}",
+            expected, data
+        )),
+        (Expectation::FailsWith(expected), Err(err)) => {
+            let err = err.to_string();
+            if err.contains(expected.as_str()) {
+                None
+            } else {
+                Some(format!(
+                    "Expected an error containing {:?}, got {:?}",
+                    expected, err
+                ))
+            }
+        }
+        (_, Err(err)) => Some(format!("Unexpected error: {}", err)),
+        (Expectation::Returns(expected), Ok(data)) => {
+            if &data == expected {
+                None
+            } else {
+                Some(format!("Expected {:?}, got {:?}", expected, data))
+            }
+        }
+        (Expectation::Stores { key, value }, Ok(_)) => {
+            let stored = instance.with_storage(|storage| Ok(storage.get(key).0?))?;
+            if &stored == value {
+                None
+            } else {
+                Some(format!(
+                    "Expected {:?} at key {:?}, got {:?}",
+                    value, key, stored
+                ))
+            }
+        }
+        (Expectation::QueryResult { ok }, Ok(data)) => {
+            match from_slice::<SystemResult<ContractResult<Binary>>>(&data, data.len()) {
+                Ok(result) if matches!(result, SystemResult::Ok(_)) == *ok => None,
+                Ok(result) => Some(format!("Unexpected query result: {:?}", result)),
+                Err(err) => Some(format!("Invalid query result: {}", err)),
+            }
+        }
+    };
+
+    let failure = match failure {
+        None if case.max_gas != 0 && gas_used > case.max_gas => Some(format!(
+            "Used {} gas, which exceeds the bound of {}",
+            gas_used, case.max_gas
+        )),
+        other => other,
+    };
+    Ok((gas_used, failure))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_vm::testing::mock_backend;
+
+    #[test]
+    fn run_works_with_mock_backend() {
+        let report = run(&Options::default(), || mock_backend(&[]));
+        report.assert_passed();
+        assert!(report.results.len() > 30);
+    }
+
+    #[test]
+    fn run_reports_failures() {
+        // a valid address the mock API rejects
+        let options = Options {
+            valid_address: "NOT VALID".to_string(),
+            ..Options::default()
+        };
+        let report = run(&options, || mock_backend(&[]));
+        assert!(!report.passed());
+        let failed: Vec<_> = report.failures().map(|result| result.name).collect();
+        assert_eq!(
+            failed,
+            [
+                "addr_validate accepts valid addresses",
+                "addr_canonicalize and addr_humanize roundtrip"
+            ]
+        );
+        assert!(report.to_string().contains(
+            "FAIL [addr_validate] addr_validate accepts valid addresses: Expected [0, 0, 0, 0], got [1, 0, 0, 0]"
+        ));
+    }
+}
@@ -11,8 +11,8 @@ use cosmwasm_vm::testing::{
     mock_backend, mock_env, mock_info, mock_instance_options, MockApi, MockQuerier, MockStorage,
 };
 use cosmwasm_vm::{
-    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, Checksum, Instance,
-    InstanceOptions, Size,
+    call_execute, call_instantiate, call_query, capabilities_from_csv, Cache, CacheOptions,
+    Checksum, Instance, InstanceOptions, Size, WasmProposals,
 };
 
 // Instance
@@ -126,6 +126,7 @@ fn bench_cache(c: &mut Criterion) {
         available_capabilities: capabilities_from_csv("iterator,staking"),
         memory_cache_size: MEMORY_CACHE_SIZE,
         instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+        wasm_proposals: WasmProposals::default(),
     };
 
     group.bench_function("save wasm", |b| {
@@ -166,6 +167,7 @@ fn bench_cache(c: &mut Criterion) {
             available_capabilities: capabilities_from_csv("iterator,staking"),
             memory_cache_size: Size(0),
             instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+            wasm_proposals: WasmProposals::default(),
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(non_memcache).unwrap() };
@@ -222,6 +224,60 @@ fn bench_cache(c: &mut Criterion) {
         });
     });
 
+    // A tiny query in a fresh instance, which is what every query call costs. Compare to
+    // "reset instance and query" to see how much of the latency is spent on instance setup.
+    group.bench_function("instantiate from pinned memory and query", |b| {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        cache.pin(&checksum).unwrap();
+
+        b.iter(|| {
+            let backend = mock_backend(&[]);
+            let mut instance = cache
+                .get_instance(&checksum, backend, DEFAULT_INSTANCE_OPTIONS)
+                .unwrap();
+            let result = call_query(&mut instance, &mock_env(), br#"{"get_int":{}}"#).unwrap();
+            assert!(result.into_result().is_ok());
+        });
+    });
+
+    // The same query in one instance that gets a fresh memory before every call
+    group.bench_function("reset instance and query", |b| {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let mut instance = cache
+            .get_instance(&checksum, mock_backend(&[]), DEFAULT_INSTANCE_OPTIONS)
+            .unwrap();
+
+        b.iter(|| {
+            instance.reset(DEFAULT_GAS_LIMIT).unwrap();
+            let result = call_query(&mut instance, &mock_env(), br#"{"get_int":{}}"#).unwrap();
+            assert!(result.into_result().is_ok());
+        });
+    });
+
+    // The same query without any instance setup. Memory and globals are left over from
+    // the previous call, so this is a lower bound only.
+    group.bench_function("query on reused instance", |b| {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let much_gas: InstanceOptions = InstanceOptions {
+            gas_limit: HIGH_GAS_LIMIT,
+            ..DEFAULT_INSTANCE_OPTIONS
+        };
+        let mut instance = cache
+            .get_instance(&checksum, mock_backend(&[]), much_gas)
+            .unwrap();
+
+        b.iter(|| {
+            let result = call_query(&mut instance, &mock_env(), br#"{"get_int":{}}"#).unwrap();
+            assert!(result.into_result().is_ok());
+        });
+    });
+
     group.finish();
 }
 
@@ -232,6 +288,7 @@ pub fn bench_instance_threads(c: &mut Criterion) {
             available_capabilities: capabilities_from_csv("iterator,staking"),
             memory_cache_size: MEMORY_CACHE_SIZE,
             instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+            wasm_proposals: WasmProposals::default(),
         };
 
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
@@ -0,0 +1,258 @@
+//! Measures the cost of Wasm operations and of the host functions priced by `GasConfig`
+//! on the current machine and prints suggested gas costs for it.
+//!
+//! The suggestions are based on the target of 1 Teragas per millisecond (see GAS.md).
+//! Host functions priced by the backend (storage, address and querier imports) are not
+//! covered here as their cost depends on the chain's implementation.
+//!
+//! Run with `cargo run --release --example gas_calibration [ITERATIONS]`.
+
+use std::time::Instant;
+
+use clap::{App, Arg};
+use ed25519_zebra::{SigningKey, VerificationKey};
+use rand_core::OsRng;
+
+use cosmwasm_crypto::{
+    ed25519_batch_verify, ed25519_verify, secp256k1_recover_pubkey, secp256k1_verify,
+};
+use cosmwasm_vm::testing::{mock_backend, MockApi, MockQuerier, MockStorage};
+use cosmwasm_vm::{call_query_raw, Instance, InstanceOptions, Size};
+
+/// Target is 10^12 per millisecond (see GAS.md), i.e. 10^6 gas per nanosecond.
+const GAS_PER_NS: f64 = 1_000_000.0;
+/// The flat cost per Wasm operation charged by the metering middleware.
+/// Used to derive the number of executed operations from the gas report.
+const CURRENT_COST_PER_OPERATION: u64 = 150_000;
+/// Every measurement is repeated this many times and the fastest run is used
+const RUNS: usize = 5;
+/// Number of signatures used for the batch verification measurements
+const BATCH_SIZE: usize = 16;
+
+const ECDSA_HASH_HEX: &str = "5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0";
+const ECDSA_SIG_HEX: &str = "207082eb2c3dfa0b454e0906051270ba4074ac93760ba9e7110cd9471475111151eb0dbbc9920e72146fb564f99d039802bf6ef2561446eb126ef364d21ee9c4";
+const ECDSA_PUBKEY_HEX: &str = "04051c1ee2190ecfb174bfe4f90763f2b4ff7517b70a2aec1876ebcfd644c4633fb03f3cfbd94b1f376e34592d9d41ccaf640bb751b00a1fadeb0c01157769eb73";
+
+/// Wasm operation classes as (name, loop body) pairs.
+/// The body can use the i64 local `$x` and must leave the stack empty.
+const OPERATION_CLASSES: &[(&str, &str)] = &[
+    ("control flow (empty loop)", ""),
+    (
+        "integer arithmetic",
+        "(local.set $x (i64.add (i64.mul (local.get $x) (i64.const 3)) (i64.const 1)))",
+    ),
+    (
+        "integer division",
+        "(local.set $x (i64.div_u (i64.rem_u (local.get $x) (i64.const 7919)) (i64.const 3)))",
+    ),
+    (
+        "memory access",
+        "(i64.store (i32.const 4096) (i64.add (i64.load (i32.const 4096)) (local.get $x)))",
+    ),
+    (
+        "globals",
+        "(global.set $g (i64.add (global.get $g) (i64.const 1)))",
+    ),
+    (
+        "function calls",
+        "(local.set $x (call $identity (local.get $x)))",
+    ),
+    (
+        "indirect function calls",
+        "(local.set $x (call_indirect (param i64) (result i64) (local.get $x) (i32.const 0)))",
+    ),
+];
+
+pub fn main() {
+    let matches = App::new("Gas calibration")
+        .about("Measures Wasm operations and host functions and suggests gas costs")
+        .arg(
+            Arg::with_name("ITERATIONS")
+                .help("Number of iterations per measurement (default: 1000000)")
+                .index(1),
+        )
+        .get_matches();
+    let iterations: u32 = matches
+        .value_of("ITERATIONS")
+        .map(|value| value.parse().expect("Error parsing iterations"))
+        .unwrap_or(1_000_000);
+
+    println!("Wasm operations ({} loop iterations each):", iterations);
+    println!(
+        "  {:<28} {:>10} {:>18}",
+        "class", "ns/op", "suggested gas/op"
+    );
+    let mut max_cost = 0;
+    for (name, body) in OPERATION_CLASSES {
+        let per_operation = measure_operations(body, iterations);
+        let cost = to_gas(per_operation);
+        max_cost = max_cost.max(cost);
+        println!(
+            "  {:<28} {:>10.3} {:>18}",
+            name,
+            per_operation,
+            underscored(cost)
+        );
+    }
+    println!(
+        "Suggested flat cost per operation (slowest class): {} (currently {})",
+        underscored(max_cost),
+        underscored(CURRENT_COST_PER_OPERATION)
+    );
+    println!();
+
+    // Crypto iterations are much slower than Wasm operations
+    let crypto_iterations = (iterations / 1000).max(1);
+    let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+    let signature = hex::decode(ECDSA_SIG_HEX).unwrap();
+    let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+    let secp256k1_verify_time = measure(crypto_iterations, || {
+        assert!(secp256k1_verify(&hash, &signature, &pubkey).unwrap());
+    });
+    let secp256k1_recover_pubkey_time = measure(crypto_iterations, || {
+        secp256k1_recover_pubkey(&hash, &signature, 0).unwrap();
+    });
+
+    let keys: Vec<SigningKey> = (0..BATCH_SIZE).map(|_| SigningKey::new(OsRng)).collect();
+    let messages: Vec<Vec<u8>> = (0..BATCH_SIZE)
+        .map(|i| format!("gas calibration message {}", i).into_bytes())
+        .collect();
+    let public_keys: Vec<[u8; 32]> = keys
+        .iter()
+        .map(|key| VerificationKey::from(key).into())
+        .collect();
+    let signatures: Vec<[u8; 64]> = keys
+        .iter()
+        .zip(&messages)
+        .map(|(key, message)| key.sign(message).into())
+        .collect();
+    let one_key_signatures: Vec<[u8; 64]> = messages
+        .iter()
+        .map(|message| keys[0].sign(message).into())
+        .collect();
+    let messages: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+    let public_keys: Vec<&[u8]> = public_keys.iter().map(|k| k.as_slice()).collect();
+    let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+    let one_key_signatures: Vec<&[u8]> = one_key_signatures.iter().map(|s| s.as_slice()).collect();
+
+    let ed25519_verify_time = measure(crypto_iterations, || {
+        assert!(ed25519_verify(messages[0], signatures[0], public_keys[0]).unwrap());
+    });
+    let batch_iterations = (crypto_iterations / BATCH_SIZE as u32).max(1);
+    let ed25519_batch_verify_time = measure(batch_iterations, || {
+        assert!(ed25519_batch_verify(&messages, &signatures, &public_keys).unwrap());
+    }) / BATCH_SIZE as f64;
+    let ed25519_batch_verify_one_pubkey_time = measure(batch_iterations, || {
+        assert!(ed25519_batch_verify(&messages, &one_key_signatures, &public_keys[..1]).unwrap());
+    }) / BATCH_SIZE as f64;
+
+    println!(
+        "Suggested GasConfig ({} iterations per function, batches of {} signatures):",
+        crypto_iterations, BATCH_SIZE
+    );
+    let entries = [
+        ("secp256k1_verify_cost", secp256k1_verify_time),
+        (
+            "secp256k1_recover_pubkey_cost",
+            secp256k1_recover_pubkey_time,
+        ),
+        ("ed25519_verify_cost", ed25519_verify_time),
+        ("ed25519_batch_verify_cost", ed25519_batch_verify_time),
+        (
+            "ed25519_batch_verify_one_pubkey_cost",
+            ed25519_batch_verify_one_pubkey_time,
+        ),
+    ];
+    println!("GasConfig {{");
+    for (field, time) in entries.iter() {
+        println!("    // ~{:.1} us on this machine", time / 1000.0);
+        println!("    {}: {},", field, underscored(to_gas(*time)));
+    }
+    println!("}}");
+}
+
+/// Runs `f` the given number of times and returns the average duration of a call
+/// in nanoseconds in the fastest of [`RUNS`] runs
+fn measure(iterations: u32, mut f: impl FnMut()) -> f64 {
+    fastest((0..RUNS).map(|_| {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            f();
+        }
+        start.elapsed().as_secs_f64() * 1e9 / iterations as f64
+    }))
+}
+
+/// Executes the loop body the given number of times in a contract and returns the
+/// average duration of a Wasm operation in nanoseconds in the fastest of [`RUNS`] runs
+fn measure_operations(body: &str, iterations: u32) -> f64 {
+    let wasm = wat::parse_str(operations_wat(body, iterations)).unwrap();
+    let options = InstanceOptions {
+        gas_limit: 1_000_000_000_000_000_000,
+        print_debug: false,
+    };
+    fastest((0..RUNS).map(|_| {
+        let mut instance: Instance<MockApi, MockStorage, MockQuerier> =
+            Instance::from_code(&wasm, mock_backend(&[]), options, Some(Size::mebi(16))).unwrap();
+        let start = Instant::now();
+        call_query_raw(&mut instance, b"{}", b"{}").unwrap();
+        let elapsed = start.elapsed();
+        let operations = instance.create_gas_report().used_internally / CURRENT_COST_PER_OPERATION;
+        elapsed.as_secs_f64() * 1e9 / operations as f64
+    }))
+}
+
+fn fastest(durations: impl Iterator<Item = f64>) -> f64 {
+    durations.fold(f64::INFINITY, f64::min)
+}
+
+/// A minimal contract whose `query` runs the loop body the given number of times
+fn operations_wat(body: &str, iterations: u32) -> String {
+    format!(
+        r#"(module
+  (memory (export "memory") 1)
+  (global $g (mut i64) (i64.const 0))
+  ;; a region at 16 pointing to 1024 with a capacity of 32 KiB, used for all allocations
+  (data (i32.const 16) "\00\04\00\00\00\80\00\00\00\00\00\00")
+  (table 1 funcref)
+  (elem (i32.const 0) $identity)
+
+  (func (export "interface_version_8"))
+  (func (export "allocate") (param i32) (result i32) (i32.const 16))
+  (func (export "deallocate") (param i32))
+  (func $identity (param i64) (result i64) (local.get 0))
+
+  (func (export "query") (param i32 i32) (result i32)
+    (local $i i32)
+    (local $x i64)
+    (local.set $x (i64.const 1))
+    (loop $loop
+      {body}
+      (br_if $loop (i32.ne
+        (local.tee $i (i32.add (local.get $i) (i32.const 1)))
+        (i32.const {iterations}))))
+    ;; keep the result observable
+    (i64.store (i32.const 2048) (local.get $x))
+    ;; an empty region as the result
+    (i32.store offset=8 (i32.const 16) (i32.const 0))
+    (i32.const 16))
+)"#,
+        body = body,
+        iterations = iterations,
+    )
+}
+
+fn to_gas(nanos: f64) -> u64 {
+    (nanos * GAS_PER_NS).round() as u64
+}
+
+/// Formats a number with digit group separators like in the `GasConfig` source
+fn underscored(value: u64) -> String {
+    let digits = value.to_string().into_bytes();
+    let mut groups: Vec<&str> = digits
+        .rchunks(3)
+        .map(|group| std::str::from_utf8(group).unwrap())
+        .collect();
+    groups.reverse();
+    groups.join("_")
+}
@@ -1,16 +1,17 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::ops::AddAssign;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
-use cosmwasm_std::{Binary, ContractResult, SystemResult};
+use cosmwasm_std::{Addr, BankMsg, Binary, ContractResult, SystemResult, WasmMsg};
 #[cfg(feature = "iterator")]
 use cosmwasm_std::{Order, Record};
 
 /// A structure that represents gas cost to be deducted from the remaining gas.
 /// This is always needed when computations are performed outside of
 /// Wasm execution, such as calling crypto APIs or calls into the blockchain.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct GasInfo {
     /// The gas cost of a computation that was executed already but not yet charged.
     ///
@@ -89,7 +90,12 @@ pub trait Storage {
     ///
     /// The bound `start` is inclusive and `end` is exclusive.
     ///
-    /// If `start` is lexicographically greater than or equal to `end`, an empty range is described, mo matter of the order.
+    /// If `start` is lexicographically greater than or equal to `end`, an empty range is described, no matter the order.
+    ///
+    /// Keys are compared bytewise. The empty key is the smallest key, so `Some(b"")` as `start`
+    /// is the same as `None`, and `Some(b"")` as `end` describes an empty range.
+    ///
+    /// `testing::check_storage_conformance` checks that an implementation follows these rules.
     ///
     /// This call must not change data in the storage, but creating and storing a new iterator can be a mutating operation on
     /// the Storage implementation.
@@ -152,12 +158,36 @@ pub trait Querier {
     ) -> BackendResult<SystemResult<ContractResult<Binary>>>;
 }
 
+/// A message sent by a contract that is checked by a [`MessagePolicy`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OutgoingMsg<'a> {
+    Bank(&'a BankMsg),
+    Wasm(&'a WasmMsg),
+}
+
+/// Decides which messages a contract may send, e.g. to restrict the contracts that can be
+/// called in a permissioned chain. See [`Instance::set_message_policy`].
+///
+/// [`Instance::set_message_policy`]: crate::Instance::set_message_policy
+pub trait MessagePolicy: Send {
+    /// Checks a message in the response of the contract at `contract`, before the response
+    /// is returned to the embedder for dispatching.
+    ///
+    /// Returning [`BackendError::UserErr`] rejects the message and makes the call fail with
+    /// [`VmError::MessageRejected`]. Other errors are treated like errors of any other call
+    /// into the backend.
+    ///
+    /// [`VmError::MessageRejected`]: crate::VmError::MessageRejected
+    fn check_message(&self, contract: &Addr, msg: OutgoingMsg) -> BackendResult<()>;
+}
+
 /// A result type for calling into the backend. Such a call can cause
 /// non-negligible computational cost in both success and faiure case and must always have gas information
 /// attached.
 pub type BackendResult<T> = (core::result::Result<T, BackendError>, GasInfo);
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum BackendError {
     #[error("Panic in FFI call")]
@@ -1,20 +1,25 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{Read, Write};
 use std::marker::PhantomData;
-use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use crate::backend::{Backend, BackendApi, Querier, Storage};
 use crate::capabilities::required_capabilities_from_module;
 use crate::checksum::Checksum;
-use crate::compatibility::check_wasm;
+use crate::compatibility::check_wasm_with_proposals;
 use crate::errors::{VmError, VmResult};
 use crate::instance::{Instance, InstanceOptions};
-use crate::modules::{FileSystemCache, InMemoryCache, PinnedMemoryCache};
+use crate::modules::{
+    module_serialization_version, FileSystemCache, InMemoryCache, PinnedMemoryCache,
+};
+use crate::proposals::WasmProposals;
 use crate::size::Size;
 use crate::static_analysis::{deserialize_wasm, has_ibc_entry_points};
-use crate::wasm_backend::{compile, make_runtime_store};
+use crate::telemetry::{self, CacheSource};
+use crate::wasm_backend::{compile_with_proposals, make_runtime_store};
 
 const STATE_DIR: &str = "state";
 // Things related to the state of the blockchain.
@@ -49,30 +54,157 @@ pub struct CacheOptions {
     /// Memory limit for instances, in bytes. Use a value that is divisible by the Wasm page size 65536,
     /// e.g. full MiBs.
     pub instance_memory_limit: Size,
+    /// The Wasm proposals contracts may use
+    pub wasm_proposals: WasmProposals,
+}
+
+/// A memory cache of compiled modules that can be shared by multiple caches in one process,
+/// e.g. caches of different chains in a simulation service.
+///
+/// Modules are shared between caches where checksums match. Everything else, i.e. the stored
+/// Wasm, the file system cache, pinned modules, available capabilities and stats, stays
+/// separate. A cache only uses modules from the shared memory cache whose Wasm was stored
+/// in that cache.
+///
+/// Cloning creates another handle to the same memory cache.
+#[derive(Clone)]
+pub struct SharedMemoryCache {
+    memory_cache: Arc<Mutex<InMemoryCache>>,
+    /// Compiled modules contain the memory limit, so all caches sharing them need to use the
+    /// same limit.
+    instance_memory_limit: Size,
+    /// To prevent concurrent access to `WasmerInstance::new`
+    instantiation_lock: Arc<Mutex<()>>,
+}
+
+impl SharedMemoryCache {
+    /// Creates a memory cache of the given size in bytes for caches whose instance memory limit
+    /// is `instance_memory_limit`.
+    pub fn new(memory_cache_size: Size, instance_memory_limit: Size) -> Self {
+        SharedMemoryCache {
+            memory_cache: Arc::new(Mutex::new(InMemoryCache::new(memory_cache_size))),
+            instance_memory_limit,
+            instantiation_lock: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+/// The place where the original Wasm blobs are kept
+enum WasmStore {
+    /// One file per Wasm blob in the given directory
+    Disk(PathBuf),
+    /// For caches without file system state, see [`Cache::new_in_memory`]
+    Memory(HashMap<Checksum, Vec<u8>>),
+}
+
+impl WasmStore {
+    fn save(&mut self, wasm: &[u8]) -> VmResult<Checksum> {
+        match self {
+            WasmStore::Disk(wasm_path) => save_wasm_to_disk(wasm_path.as_path(), wasm),
+            WasmStore::Memory(blobs) => {
+                let checksum = Checksum::generate(wasm);
+                blobs.insert(checksum, wasm.to_vec());
+                Ok(checksum)
+            }
+        }
+    }
+
+    /// Loads a Wasm blob and verifies that it matches the checksum
+    fn load(&self, checksum: &Checksum) -> VmResult<Vec<u8>> {
+        let code = match self {
+            WasmStore::Disk(wasm_path) => load_wasm_from_disk(wasm_path.as_path(), checksum)?,
+            WasmStore::Memory(blobs) => blobs.get(checksum).cloned().ok_or_else(|| {
+                VmError::cache_err(format!("Wasm {} not found", checksum.to_hex()))
+            })?,
+        };
+        // verify hash matches (integrity check)
+        if Checksum::generate(&code) != *checksum {
+            Err(VmError::integrity_err())
+        } else {
+            Ok(code)
+        }
+    }
+
+    fn contains(&self, checksum: &Checksum) -> bool {
+        match self {
+            WasmStore::Disk(wasm_path) => wasm_path.join(checksum.to_hex()).is_file(),
+            WasmStore::Memory(blobs) => blobs.contains_key(checksum),
+        }
+    }
 }
 
 pub struct CacheInner {
-    wasm_path: PathBuf,
+    wasm_store: WasmStore,
     /// Instances memory limit in bytes. Use a value that is divisible by the Wasm page size 65536,
     /// e.g. full MiBs.
     instance_memory_limit: Size,
     pinned_memory_cache: PinnedMemoryCache,
-    memory_cache: InMemoryCache,
-    fs_cache: FileSystemCache,
+    /// Possibly shared with other caches. Always lock after the `CacheInner`.
+    memory_cache: Arc<Mutex<InMemoryCache>>,
+    /// `None` for caches without file system state
+    fs_cache: Option<FileSystemCache>,
     stats: Stats,
+    /// Checksums of Wasm known to be stored in `wasm_store`
+    known_checksums: HashSet<Checksum>,
+}
+
+impl CacheInner {
+    /// Loads a module from the memory cache. Modules of Wasm that was not stored in this cache
+    /// are not returned, such that a shared memory cache does not leak code between caches.
+    fn load_from_memory_cache(
+        &mut self,
+        checksum: &Checksum,
+    ) -> VmResult<Option<(wasmer::Module, usize)>> {
+        let loaded = self.memory_cache.lock().unwrap().load(checksum)?;
+        match loaded {
+            Some(module) if self.is_known(checksum) => Ok(Some((module.module, module.size))),
+            _ => Ok(None),
+        }
+    }
+
+    fn is_known(&mut self, checksum: &Checksum) -> bool {
+        if self.known_checksums.contains(checksum) {
+            return true;
+        }
+        let stored = self.wasm_store.contains(checksum);
+        if stored {
+            self.known_checksums.insert(*checksum);
+        }
+        stored
+    }
+
+    fn load_from_fs_cache(
+        &self,
+        checksum: &Checksum,
+        store: &wasmer::Store,
+    ) -> VmResult<Option<wasmer::Module>> {
+        match &self.fs_cache {
+            Some(fs_cache) => fs_cache.load(checksum, store),
+            None => Ok(None),
+        }
+    }
+
+    fn store_to_fs_cache(&mut self, checksum: &Checksum, module: &wasmer::Module) -> VmResult<()> {
+        match &mut self.fs_cache {
+            Some(fs_cache) => fs_cache.store(checksum, module),
+            None => Ok(()),
+        }
+    }
 }
 
 pub struct Cache<A: BackendApi, S: Storage, Q: Querier> {
     /// Available capabilities are immutable for the lifetime of the cache,
     /// i.e. any number of read-only references is allowed to access it concurrently.
     available_capabilities: HashSet<String>,
+    /// Immutable like `available_capabilities`
+    wasm_proposals: WasmProposals,
     inner: Mutex<CacheInner>,
     // Those two don't store data but only fix type information
     type_api: PhantomData<A>,
     type_storage: PhantomData<S>,
     type_querier: PhantomData<Q>,
     /// To prevent concurrent access to `WasmerInstance::new`
-    instantiation_lock: Mutex<()>,
+    instantiation_lock: Arc<Mutex<()>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -95,13 +227,39 @@ where
     /// assumes the disk contents are correct, and there's no way to ensure the artifacts
     /// stored in the cache haven't been corrupted or tampered with.
     pub unsafe fn new(options: CacheOptions) -> VmResult<Self> {
+        let shared =
+            SharedMemoryCache::new(options.memory_cache_size, options.instance_memory_limit);
+        Self::new_with_shared_memory_cache(options, &shared)
+    }
+
+    /// Creates a new cache that stores data in `base_dir` and keeps compiled modules in the
+    /// given memory cache, which can be shared with other caches in the same process.
+    ///
+    /// `memory_cache_size` of the options is ignored since the size is set on the shared
+    /// memory cache. `instance_memory_limit` must match the limit of the shared memory cache.
+    ///
+    /// # Safety
+    ///
+    /// This function is marked unsafe for the same reason as [`Cache::new`].
+    pub unsafe fn new_with_shared_memory_cache(
+        options: CacheOptions,
+        shared: &SharedMemoryCache,
+    ) -> VmResult<Self> {
         let CacheOptions {
             base_dir,
             available_capabilities,
-            memory_cache_size,
+            memory_cache_size: _,
             instance_memory_limit,
+            wasm_proposals,
         } = options;
 
+        if instance_memory_limit.0 != shared.instance_memory_limit.0 {
+            return Err(VmError::cache_err(format!(
+                "Instance memory limit {} does not match the limit {} of the shared memory cache",
+                instance_memory_limit.0, shared.instance_memory_limit.0
+            )));
+        }
+
         let state_path = base_dir.join(STATE_DIR);
         let cache_path = base_dir.join(CACHE_DIR);
 
@@ -122,63 +280,158 @@ where
             .map_err(|e| VmError::cache_err(format!("Error file system cache: {}", e)))?;
         Ok(Cache {
             available_capabilities,
+            wasm_proposals,
             inner: Mutex::new(CacheInner {
-                wasm_path,
+                wasm_store: WasmStore::Disk(wasm_path),
                 instance_memory_limit,
                 pinned_memory_cache: PinnedMemoryCache::new(),
-                memory_cache: InMemoryCache::new(memory_cache_size),
-                fs_cache,
+                memory_cache: Arc::clone(&shared.memory_cache),
+                fs_cache: Some(fs_cache),
                 stats: Stats::default(),
+                known_checksums: HashSet::new(),
             }),
             type_storage: PhantomData::<S>,
             type_api: PhantomData::<A>,
             type_querier: PhantomData::<Q>,
-            instantiation_lock: Mutex::new(()),
+            instantiation_lock: Arc::clone(&shared.instantiation_lock),
         })
     }
 
+    /// Creates a new cache that keeps all data in memory and never touches the file system,
+    /// e.g. for simulations or serverless environments with a read-only file system.
+    ///
+    /// Nothing is persisted, i.e. Wasm needs to be stored again after a restart. To avoid
+    /// recompiling it, modules can be exported with [`Cache::serialize_module`] and restored
+    /// with [`Cache::save_wasm_with_module`].
+    pub fn new_in_memory(
+        available_capabilities: HashSet<String>,
+        wasm_proposals: WasmProposals,
+        memory_cache_size: Size,
+        instance_memory_limit: Size,
+    ) -> Self {
+        let shared = SharedMemoryCache::new(memory_cache_size, instance_memory_limit);
+        Self::new_in_memory_with_shared_memory_cache(
+            available_capabilities,
+            wasm_proposals,
+            &shared,
+        )
+    }
+
+    /// Creates a new cache like [`Cache::new_in_memory`] that keeps compiled modules in the
+    /// given memory cache, which can be shared with other caches in the same process.
+    pub fn new_in_memory_with_shared_memory_cache(
+        available_capabilities: HashSet<String>,
+        wasm_proposals: WasmProposals,
+        shared: &SharedMemoryCache,
+    ) -> Self {
+        Cache {
+            available_capabilities,
+            wasm_proposals,
+            inner: Mutex::new(CacheInner {
+                wasm_store: WasmStore::Memory(HashMap::new()),
+                instance_memory_limit: shared.instance_memory_limit,
+                pinned_memory_cache: PinnedMemoryCache::new(),
+                memory_cache: Arc::clone(&shared.memory_cache),
+                fs_cache: None,
+                stats: Stats::default(),
+                known_checksums: HashSet::new(),
+            }),
+            type_storage: PhantomData::<S>,
+            type_api: PhantomData::<A>,
+            type_querier: PhantomData::<Q>,
+            instantiation_lock: Arc::clone(&shared.instantiation_lock),
+        }
+    }
+
     pub fn stats(&self) -> Stats {
         self.inner.lock().unwrap().stats
     }
 
+    /// Returns the metrics of this cache. If the memory cache is shared with other caches,
+    /// its number of elements and size are the ones of the shared memory cache.
     pub fn metrics(&self) -> Metrics {
         let cache = self.inner.lock().unwrap();
+        let memory_cache = cache.memory_cache.lock().unwrap();
         Metrics {
             stats: cache.stats,
             elements_pinned_memory_cache: cache.pinned_memory_cache.len(),
-            elements_memory_cache: cache.memory_cache.len(),
+            elements_memory_cache: memory_cache.len(),
             size_pinned_memory_cache: cache.pinned_memory_cache.size(),
-            size_memory_cache: cache.memory_cache.size(),
+            size_memory_cache: memory_cache.size(),
         }
     }
 
     pub fn save_wasm(&self, wasm: &[u8]) -> VmResult<Checksum> {
-        check_wasm(wasm, &self.available_capabilities)?;
-        let module = compile(wasm, None, &[])?;
+        check_wasm_with_proposals(wasm, &self.available_capabilities, &self.wasm_proposals)?;
+        let module = compile_with_proposals(wasm, None, &[], &self.wasm_proposals)?;
 
         let mut cache = self.inner.lock().unwrap();
-        let checksum = save_wasm_to_disk(&cache.wasm_path, wasm)?;
-        cache.fs_cache.store(&checksum, &module)?;
+        let checksum = cache.wasm_store.save(wasm)?;
+        cache.store_to_fs_cache(&checksum, &module)?;
+        cache.known_checksums.insert(checksum);
         Ok(checksum)
     }
 
+    /// Stores Wasm together with its compiled module, which was created by
+    /// [`Cache::serialize_module`]. This avoids compiling the Wasm, e.g. when warm-starting
+    /// an in-memory cache from a snapshot.
+    ///
+    /// The module is stored in the memory cache and, if available, in the file system cache.
+    ///
+    /// # Safety
+    ///
+    /// The serialized module is not validated and must be the module of the given Wasm.
+    /// Only use serialized modules from trusted sources, e.g. snapshots created by the same node.
+    pub unsafe fn save_wasm_with_module(
+        &self,
+        wasm: &[u8],
+        serialized_module: &[u8],
+    ) -> VmResult<Checksum> {
+        check_wasm_with_proposals(wasm, &self.available_capabilities, &self.wasm_proposals)?;
+        let serialized_module = strip_module_serialization_version(serialized_module)?;
+
+        let mut cache = self.inner.lock().unwrap();
+        let store = make_runtime_store(Some(cache.instance_memory_limit));
+        // Wasmer expects the module to start at an aligned address like a fresh allocation
+        let serialized_module = serialized_module.to_vec();
+        let module = wasmer::Module::deserialize(&store, &serialized_module)
+            .map_err(|e| VmError::cache_err(format!("Error deserializing module: {}", e)))?;
+        let checksum = cache.wasm_store.save(wasm)?;
+        cache.store_to_fs_cache(&checksum, &module)?;
+        cache.known_checksums.insert(checksum);
+        let module_size = loupe::size_of_val(&module);
+        cache
+            .memory_cache
+            .lock()
+            .unwrap()
+            .store(&checksum, module, module_size)?;
+        Ok(checksum)
+    }
+
+    /// Serializes the compiled module of a previously saved Wasm, such that it can be restored
+    /// with [`Cache::save_wasm_with_module`] by caches of the same VM version.
+    ///
+    /// The module is loaded like for [`Cache::get_instance`], i.e. it is compiled if needed.
+    pub fn serialize_module(&self, checksum: &Checksum) -> VmResult<Vec<u8>> {
+        let module = self.get_module(checksum)?;
+        let serialized = module
+            .serialize()
+            .map_err(|e| VmError::cache_err(format!("Error serializing module: {}", e)))?;
+        let version = module_serialization_version();
+        let mut out = Vec::with_capacity(1 + version.len() + serialized.len());
+        out.push(version.len() as u8);
+        out.extend_from_slice(version.as_bytes());
+        out.extend_from_slice(&serialized);
+        Ok(out)
+    }
+
     /// Retrieves a Wasm blob that was previously stored via save_wasm.
     /// When the cache is instantiated with the same base dir, this finds Wasm files on disc across multiple cache instances (i.e. node restarts).
     /// This function is public to allow a checksum to Wasm lookup in the blockchain.
     ///
     /// If the given ID is not found or the content does not match the hash (=ID), an error is returned.
     pub fn load_wasm(&self, checksum: &Checksum) -> VmResult<Vec<u8>> {
-        self.load_wasm_with_path(&self.inner.lock().unwrap().wasm_path, checksum)
-    }
-
-    fn load_wasm_with_path(&self, wasm_path: &Path, checksum: &Checksum) -> VmResult<Vec<u8>> {
-        let code = load_wasm_from_disk(&wasm_path, checksum)?;
-        // verify hash matches (integrity check)
-        if Checksum::generate(&code) != *checksum {
-            Err(VmError::integrity_err())
-        } else {
-            Ok(code)
-        }
+        self.inner.lock().unwrap().wasm_store.load(checksum)
     }
 
     /// Performs static anlyzation on this Wasm without compiling or instantiating it.
@@ -208,16 +461,16 @@ where
         }
 
         // Try to get module from the memory cache
-        if let Some(module) = cache.memory_cache.load(checksum)? {
+        if let Some((module, module_size)) = cache.load_from_memory_cache(checksum)? {
             cache.stats.hits_memory_cache += 1;
             return cache
                 .pinned_memory_cache
-                .store(checksum, module.module, module.size);
+                .store(checksum, module, module_size);
         }
 
         // Try to get module from file system cache
         let store = make_runtime_store(Some(cache.instance_memory_limit));
-        if let Some(module) = cache.fs_cache.load(checksum, &store)? {
+        if let Some(module) = cache.load_from_fs_cache(checksum, &store)? {
             cache.stats.hits_fs_cache += 1;
             let module_size = loupe::size_of_val(&module);
             return cache
@@ -226,10 +479,15 @@ where
         }
 
         // Re-compile from original Wasm bytecode
-        let code = self.load_wasm_with_path(&cache.wasm_path, checksum)?;
-        let module = compile(&code, Some(cache.instance_memory_limit), &[])?;
+        let code = cache.wasm_store.load(checksum)?;
+        let module = compile_with_proposals(
+            &code,
+            Some(cache.instance_memory_limit),
+            &[],
+            &self.wasm_proposals,
+        )?;
         // Store into the fs cache too
-        cache.fs_cache.store(checksum, &module)?;
+        cache.store_to_fs_cache(checksum, &module)?;
         let module_size = loupe::size_of_val(&module);
         cache
             .pinned_memory_cache
@@ -258,14 +516,17 @@ where
         options: InstanceOptions,
     ) -> VmResult<Instance<A, S, Q>> {
         let module = self.get_module(checksum)?;
-        let instance = Instance::from_module(
+        let start = Instant::now();
+        let mut instance = Instance::from_module(
             &module,
             backend,
             options.gas_limit,
             options.print_debug,
             None,
-            Some(&self.instantiation_lock),
+            Some(self.instantiation_lock.clone()),
         )?;
+        instance.set_checksum(*checksum);
+        telemetry::record_instantiation_time(start.elapsed());
         Ok(instance)
     }
 
@@ -277,22 +538,27 @@ where
         // Try to get module from the pinned memory cache
         if let Some(module) = cache.pinned_memory_cache.load(checksum)? {
             cache.stats.hits_pinned_memory_cache += 1;
+            telemetry::record_cache_hit(CacheSource::PinnedMemory);
             return Ok(module);
         }
 
         // Get module from memory cache
-        if let Some(module) = cache.memory_cache.load(checksum)? {
+        if let Some((module, _)) = cache.load_from_memory_cache(checksum)? {
             cache.stats.hits_memory_cache += 1;
-            return Ok(module.module);
+            telemetry::record_cache_hit(CacheSource::Memory);
+            return Ok(module);
         }
 
         // Get module from file system cache
         let store = make_runtime_store(Some(cache.instance_memory_limit));
-        if let Some(module) = cache.fs_cache.load(checksum, &store)? {
+        if let Some(module) = cache.load_from_fs_cache(checksum, &store)? {
             cache.stats.hits_fs_cache += 1;
+            telemetry::record_cache_hit(CacheSource::FileSystem);
             let module_size = loupe::size_of_val(&module);
             cache
                 .memory_cache
+                .lock()
+                .unwrap()
                 .store(checksum, module.clone(), module_size)?;
             return Ok(module);
         }
@@ -302,13 +568,21 @@ where
         // This is needed for chains that upgrade their node software in a way that changes the module
         // serialization format. If you do not replay all transactions, previous calls of `save_wasm`
         // stored the old module format.
-        let wasm = self.load_wasm_with_path(&cache.wasm_path, checksum)?;
+        let wasm = cache.wasm_store.load(checksum)?;
         cache.stats.misses += 1;
-        let module = compile(&wasm, Some(cache.instance_memory_limit), &[])?;
-        cache.fs_cache.store(checksum, &module)?;
+        telemetry::record_cache_miss();
+        let module = compile_with_proposals(
+            &wasm,
+            Some(cache.instance_memory_limit),
+            &[],
+            &self.wasm_proposals,
+        )?;
+        cache.store_to_fs_cache(checksum, &module)?;
         let module_size = loupe::size_of_val(&module);
         cache
             .memory_cache
+            .lock()
+            .unwrap()
             .store(checksum, module.clone(), module_size)?;
         Ok(module)
     }
@@ -330,6 +604,23 @@ where
 {
 }
 
+/// Removes the version prefix written by [`Cache::serialize_module`] and returns the module
+fn strip_module_serialization_version(serialized_module: &[u8]) -> VmResult<&[u8]> {
+    let (version, module) = match serialized_module.split_first() {
+        Some((len, rest)) if rest.len() >= *len as usize => rest.split_at(*len as usize),
+        _ => return Err(VmError::cache_err("Serialized module is too short")),
+    };
+    let expected = module_serialization_version();
+    if version != expected.as_bytes() {
+        return Err(VmError::cache_err(format!(
+            "Serialized module has version {} but this VM uses {}",
+            String::from_utf8_lossy(version),
+            expected
+        )));
+    }
+    Ok(module)
+}
+
 /// save stores the wasm code in the given directory and returns an ID for lookup.
 /// It will create the directory if it doesn't exist.
 /// Saving the same byte code multiple times is allowed.
@@ -398,6 +689,7 @@ mod tests {
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            wasm_proposals: WasmProposals::default(),
         }
     }
 
@@ -409,6 +701,7 @@ mod tests {
             available_capabilities: capabilities,
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            wasm_proposals: WasmProposals::default(),
         }
     }
 
@@ -492,6 +785,7 @@ mod tests {
                 available_capabilities: default_capabilities(),
                 memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
                 instance_memory_limit: TESTING_MEMORY_LIMIT,
+                wasm_proposals: WasmProposals::default(),
             };
             let cache1: Cache<MockApi, MockStorage, MockQuerier> =
                 unsafe { Cache::new(options1).unwrap() };
@@ -504,6 +798,7 @@ mod tests {
                 available_capabilities: default_capabilities(),
                 memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
                 instance_memory_limit: TESTING_MEMORY_LIMIT,
+                wasm_proposals: WasmProposals::default(),
             };
             let cache2: Cache<MockApi, MockStorage, MockQuerier> =
                 unsafe { Cache::new(options2).unwrap() };
@@ -538,6 +833,7 @@ mod tests {
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            wasm_proposals: WasmProposals::default(),
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(options).unwrap() };
@@ -565,9 +861,10 @@ mod tests {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
         let checksum = cache.save_wasm(CONTRACT).unwrap();
         let backend = mock_backend(&[]);
-        let _instance = cache
+        let instance = cache
             .get_instance(&checksum, backend, TESTING_OPTIONS)
             .unwrap();
+        assert_eq!(instance.checksum(), Some(checksum));
         assert_eq!(cache.stats().hits_pinned_memory_cache, 0);
         assert_eq!(cache.stats().hits_memory_cache, 0);
         assert_eq!(cache.stats().hits_fs_cache, 1);
@@ -1064,4 +1361,208 @@ mod tests {
         let non_id = Checksum::generate(b"non_existent");
         cache.unpin(&non_id).unwrap();
     }
+
+    #[test]
+    fn shared_memory_cache_shares_modules() {
+        let shared = SharedMemoryCache::new(TESTING_MEMORY_CACHE_SIZE, TESTING_MEMORY_LIMIT);
+        let cache1: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new_with_shared_memory_cache(make_testing_options(), &shared).unwrap()
+        };
+        let cache2: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new_with_shared_memory_cache(make_testing_options(), &shared).unwrap()
+        };
+        let checksum = cache1.save_wasm(CONTRACT).unwrap();
+        assert_eq!(cache2.save_wasm(CONTRACT).unwrap(), checksum);
+
+        // from file system, which stores the module into the shared memory cache
+        cache1
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache1.stats().hits_fs_cache, 1);
+
+        // the other cache finds the module in memory
+        cache2
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache2.stats().hits_memory_cache, 1);
+        assert_eq!(cache2.stats().hits_fs_cache, 0);
+        assert_eq!(cache2.stats().misses, 0);
+        assert_eq!(cache1.stats().hits_memory_cache, 0);
+
+        assert_eq!(cache1.metrics().elements_memory_cache, 1);
+        assert_eq!(cache2.metrics().elements_memory_cache, 1);
+    }
+
+    #[test]
+    fn shared_memory_cache_does_not_leak_code() {
+        let shared = SharedMemoryCache::new(TESTING_MEMORY_CACHE_SIZE, TESTING_MEMORY_LIMIT);
+        let cache1: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new_with_shared_memory_cache(make_testing_options(), &shared).unwrap()
+        };
+        let cache2: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new_with_shared_memory_cache(make_testing_options(), &shared).unwrap()
+        };
+        let checksum = cache1.save_wasm(CONTRACT).unwrap();
+        cache1
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+
+        // the Wasm was never stored in the other cache
+        let res = cache2.get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS);
+        match res {
+            Err(VmError::CacheErr { msg, .. }) => {
+                assert!(msg.starts_with("Error opening Wasm file for reading"))
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+            Ok(_) => panic!("Must not find code of another cache"),
+        }
+        assert_eq!(cache2.stats().hits_memory_cache, 0);
+        cache2.pin(&checksum).unwrap_err();
+    }
+
+    #[test]
+    fn shared_memory_cache_keeps_pinning_separate() {
+        let shared = SharedMemoryCache::new(TESTING_MEMORY_CACHE_SIZE, TESTING_MEMORY_LIMIT);
+        let cache1: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new_with_shared_memory_cache(make_testing_options(), &shared).unwrap()
+        };
+        let cache2: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new_with_shared_memory_cache(make_testing_options(), &shared).unwrap()
+        };
+        let checksum = cache1.save_wasm(CONTRACT).unwrap();
+        cache2.save_wasm(CONTRACT).unwrap();
+
+        cache1.pin(&checksum).unwrap();
+        assert_eq!(cache1.metrics().elements_pinned_memory_cache, 1);
+        assert_eq!(cache2.metrics().elements_pinned_memory_cache, 0);
+
+        cache2
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache2.stats().hits_pinned_memory_cache, 0);
+    }
+
+    #[test]
+    fn new_with_shared_memory_cache_fails_for_different_memory_limit() {
+        let shared = SharedMemoryCache::new(TESTING_MEMORY_CACHE_SIZE, Size::mebi(32));
+        let res: VmResult<Cache<MockApi, MockStorage, MockQuerier>> =
+            unsafe { Cache::new_with_shared_memory_cache(make_testing_options(), &shared) };
+        match res {
+            Err(VmError::CacheErr { msg, .. }) => assert_eq!(
+                msg,
+                "Instance memory limit 16777216 does not match the limit 33554432 of the shared memory cache"
+            ),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+            Ok(_) => panic!("Must not succeed"),
+        }
+    }
+
+    fn make_in_memory_cache() -> Cache<MockApi, MockStorage, MockQuerier> {
+        Cache::new_in_memory(
+            default_capabilities(),
+            WasmProposals::default(),
+            TESTING_MEMORY_CACHE_SIZE,
+            TESTING_MEMORY_LIMIT,
+        )
+    }
+
+    #[test]
+    fn in_memory_cache_works() {
+        let cache = make_in_memory_cache();
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        assert_eq!(cache.load_wasm(&checksum).unwrap(), CONTRACT);
+        assert!(!cache.analyze(&checksum).unwrap().has_ibc_entry_points);
+
+        // compiled on first use, then taken from memory
+        let mut instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().misses, 1);
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        let res =
+            call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap();
+        assert_eq!(res.unwrap().messages.len(), 0);
+
+        cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_memory_cache, 1);
+        assert_eq!(cache.stats().hits_fs_cache, 0);
+        assert_eq!(cache.stats().misses, 1);
+
+        cache.pin(&checksum).unwrap();
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+    }
+
+    #[test]
+    fn in_memory_cache_errors_for_unknown_wasm() {
+        let cache = make_in_memory_cache();
+        let checksum = Checksum::generate(CONTRACT);
+        match cache.load_wasm(&checksum).unwrap_err() {
+            VmError::CacheErr { msg, .. } => {
+                assert_eq!(msg, format!("Wasm {} not found", checksum.to_hex()))
+            }
+            e => panic!("Unexpected error {:?}", e),
+        }
+        // caches are independent
+        make_in_memory_cache().save_wasm(CONTRACT).unwrap();
+        let res = cache.get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn save_wasm_with_module_works() {
+        let cache1 = make_in_memory_cache();
+        let checksum = cache1.save_wasm(CONTRACT).unwrap();
+        let serialized = cache1.serialize_module(&checksum).unwrap();
+
+        // a warm start does not compile
+        let cache2 = make_in_memory_cache();
+        let restored = unsafe { cache2.save_wasm_with_module(CONTRACT, &serialized).unwrap() };
+        assert_eq!(restored, checksum);
+        let mut instance = cache2
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache2.stats().hits_memory_cache, 1);
+        assert_eq!(cache2.stats().misses, 0);
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // modules can be restored into a cache with file system state too
+        let cache3: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        unsafe { cache3.save_wasm_with_module(CONTRACT, &serialized).unwrap() };
+        cache3
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache3.stats().hits_memory_cache, 1);
+        assert_eq!(cache3.load_wasm(&checksum).unwrap(), CONTRACT);
+    }
+
+    #[test]
+    fn save_wasm_with_module_checks_version() {
+        let cache = make_in_memory_cache();
+        let res = unsafe { cache.save_wasm_with_module(CONTRACT, b"\x02v0module") };
+        match res.unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(
+                msg,
+                format!(
+                    "Serialized module has version v0 but this VM uses {}",
+                    module_serialization_version()
+                )
+            ),
+            e => panic!("Unexpected error {:?}", e),
+        }
+
+        let res = unsafe { cache.save_wasm_with_module(CONTRACT, b"\x20v0") };
+        match res.unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Serialized module is too short"),
+            e => panic!("Unexpected error {:?}", e),
+        }
+        cache.load_wasm(&Checksum::generate(CONTRACT)).unwrap_err();
+    }
 }
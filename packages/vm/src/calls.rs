@@ -1,7 +1,11 @@
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use wasmer::Val;
 
-use cosmwasm_std::{ContractResult, CustomMsg, Env, MessageInfo, QueryResponse, Reply, Response};
+use cosmwasm_std::{
+    Attribute, BankMsg, Binary, ContractResult, CustomMsg, Env, Event, MessageInfo, QueryResponse,
+    Reply, Response, WasmMsg,
+};
 #[cfg(feature = "stargate")]
 use cosmwasm_std::{
     Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
@@ -9,11 +13,12 @@ use cosmwasm_std::{
     IbcReceiveResponse,
 };
 
-use crate::backend::{BackendApi, Querier, Storage};
+use crate::backend::{BackendApi, OutgoingMsg, Querier, Storage};
 use crate::conversion::ref_to_u32;
 use crate::errors::{VmError, VmResult};
 use crate::instance::Instance;
 use crate::serde::{from_slice, to_vec};
+use crate::telemetry;
 
 /// The limits in here protect the host from allocating an unreasonable amount of memory
 /// and copying an unreasonable amount of data.
@@ -341,13 +346,21 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, Some(info))?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "instantiate",
         &[env, info, msg],
         read_limits::RESULT_INSTANTIATE,
-    )
+    )?;
+    check_response(
+        instance,
+        env,
+        &data,
+        deserialization_limits::RESULT_INSTANTIATE,
+    )?;
+    Ok(data)
 }
 
 /// Calls Wasm export "execute" and returns raw data from the contract.
@@ -363,13 +376,16 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, Some(info))?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "execute",
         &[env, info, msg],
         read_limits::RESULT_EXECUTE,
-    )
+    )?;
+    check_response(instance, env, &data, deserialization_limits::RESULT_EXECUTE)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "migrate" and returns raw data from the contract.
@@ -384,13 +400,16 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "migrate",
         &[env, msg],
         read_limits::RESULT_MIGRATE,
-    )
+    )?;
+    check_response(instance, env, &data, deserialization_limits::RESULT_MIGRATE)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "sudo" and returns raw data from the contract.
@@ -405,8 +424,11 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(instance, "sudo", &[env, msg], read_limits::RESULT_SUDO)
+    let data = call_raw(instance, "sudo", &[env, msg], read_limits::RESULT_SUDO)?;
+    check_response(instance, env, &data, deserialization_limits::RESULT_SUDO)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "reply" and returns raw data from the contract.
@@ -421,8 +443,11 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(instance, "reply", &[env, msg], read_limits::RESULT_REPLY)
+    let data = call_raw(instance, "reply", &[env, msg], read_limits::RESULT_REPLY)?;
+    check_response(instance, env, &data, deserialization_limits::RESULT_REPLY)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "query" and returns raw data from the contract.
@@ -437,6 +462,7 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(true);
     call_raw(instance, "query", &[env, msg], read_limits::RESULT_QUERY)
 }
@@ -452,6 +478,7 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
     call_raw(
         instance,
@@ -472,13 +499,21 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_channel_connect",
         &[env, msg],
         read_limits::RESULT_IBC_CHANNEL_CONNECT,
-    )
+    )?;
+    check_response(
+        instance,
+        env,
+        &data,
+        deserialization_limits::RESULT_IBC_CHANNEL_CONNECT,
+    )?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -492,13 +527,21 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_channel_close",
         &[env, msg],
         read_limits::RESULT_IBC_CHANNEL_CLOSE,
-    )
+    )?;
+    check_response(
+        instance,
+        env,
+        &data,
+        deserialization_limits::RESULT_IBC_CHANNEL_CLOSE,
+    )?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -512,13 +555,21 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_packet_receive",
         &[env, msg],
         read_limits::RESULT_IBC_PACKET_RECEIVE,
-    )
+    )?;
+    check_response(
+        instance,
+        env,
+        &data,
+        deserialization_limits::RESULT_IBC_PACKET_RECEIVE,
+    )?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -532,13 +583,21 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_packet_ack",
         &[env, msg],
         read_limits::RESULT_IBC_PACKET_ACK,
-    )
+    )?;
+    check_response(
+        instance,
+        env,
+        &data,
+        deserialization_limits::RESULT_IBC_PACKET_ACK,
+    )?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -552,13 +611,121 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    check_environment(instance, env, None)?;
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_packet_timeout",
         &[env, msg],
         read_limits::RESULT_IBC_PACKET_TIMEOUT,
-    )
+    )?;
+    check_response(
+        instance,
+        env,
+        &data,
+        deserialization_limits::RESULT_IBC_PACKET_TIMEOUT,
+    )?;
+    Ok(data)
+}
+
+/// Checks the environment provided by the host if enabled via [`Instance::set_env_checks`]
+fn check_environment<A, S, Q>(
+    instance: &Instance<A, S, Q>,
+    env: &[u8],
+    info: Option<&[u8]>,
+) -> VmResult<()>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    if let Some(checks) = instance.env_checks() {
+        checks.check_raw_env(env)?;
+        if let Some(info) = info {
+            checks.check_raw_info(info)?;
+        }
+    }
+    Ok(())
+}
+
+/// Max length (in bytes) of a serialized `Env` that is deserialized for the message policy
+const ENV_DESERIALIZATION_LIMIT: usize = 256 * 1024;
+
+/// The parts of a response, which all response types have in the same format
+#[derive(Deserialize)]
+struct ResponseParts {
+    #[serde(default)]
+    messages: Vec<ResponseMessage>,
+    #[serde(default)]
+    attributes: Vec<Attribute>,
+    #[serde(default)]
+    events: Vec<Event>,
+    data: Option<Binary>,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    /// Kept generic, since the custom and feature gated messages are not known here
+    msg: serde_json::Value,
+}
+
+/// Charges the response gas and checks a successful response against the response limits
+/// and the message policy of the instance, if any
+fn check_response<A, S, Q>(
+    instance: &Instance<A, S, Q>,
+    env: &[u8],
+    data: &[u8],
+    deserialization_limit: usize,
+) -> VmResult<()>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    if instance.response_limits().is_none()
+        && !instance.has_message_policy()
+        && instance.response_gas_costs().is_none()
+    {
+        return Ok(());
+    }
+    let size = data.len();
+    let response = match from_slice(data, deserialization_limit)? {
+        ContractResult::Ok(response) => response,
+        ContractResult::Err(_) => {
+            if let Some(costs) = instance.response_gas_costs() {
+                instance.charge_response_gas(costs.cost(size, &[], &[]))?;
+            }
+            return Ok(());
+        }
+    };
+    let ResponseParts {
+        messages,
+        attributes,
+        events,
+        data,
+    } = response;
+    if let Some(costs) = instance.response_gas_costs() {
+        instance.charge_response_gas(costs.cost(size, &attributes, &events))?;
+    }
+    if let Some(limits) = instance.response_limits() {
+        limits.check(messages.len(), &attributes, &events, data.as_ref())?;
+    }
+    if messages.is_empty() || !instance.has_message_policy() {
+        return Ok(());
+    }
+    let contract = from_slice::<Env>(env, ENV_DESERIALIZATION_LIMIT)?
+        .contract
+        .address;
+    for ResponseMessage { msg } in &messages {
+        if let Some(bank) = msg.get("bank") {
+            let bank = BankMsg::deserialize(bank).map_err(|e| VmError::parse_err("BankMsg", e))?;
+            instance.check_message(&contract, OutgoingMsg::Bank(&bank))?;
+        } else if let Some(wasm) = msg.get("wasm") {
+            let wasm = WasmMsg::deserialize(wasm).map_err(|e| VmError::parse_err("WasmMsg", e))?;
+            instance.check_message(&contract, OutgoingMsg::Wasm(&wasm))?;
+        }
+    }
+    Ok(())
 }
 
 /// Calls a function with the given arguments.
@@ -580,7 +747,26 @@ where
         instance.write_memory(region_ptr, arg)?;
         arg_region_ptrs.push(region_ptr.into());
     }
-    let result = instance.call_function1(name, &arg_region_ptrs)?;
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "call",
+        entry_point = name,
+        checksum = tracing::field::Empty,
+        gas_used = tracing::field::Empty
+    )
+    .entered();
+    #[cfg(feature = "tracing")]
+    if let Some(checksum) = instance.checksum() {
+        span.record("checksum", &tracing::field::display(checksum.to_hex()));
+    }
+
+    let gas_before = instance.get_gas_left();
+    let result = instance.call_function1(name, &arg_region_ptrs);
+    let gas_used = gas_before.saturating_sub(instance.get_gas_left());
+    telemetry::record_gas_used(name, gas_used);
+    #[cfg(feature = "tracing")]
+    span.record("gas_used", &gas_used);
+    let result = result?;
     let res_region_ptr = ref_to_u32(&result)?;
     let data = instance.read_memory(res_region_ptr, result_max_length)?;
     // free return value in wasm (arguments were freed in wasm code)
@@ -591,8 +777,10 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::{BackendError, BackendResult, GasInfo, MessagePolicy};
     use crate::testing::{mock_env, mock_info, mock_instance};
-    use cosmwasm_std::{coins, Empty};
+    use crate::{EnvChecks, ResponseGasCosts};
+    use cosmwasm_std::{coins, Addr, Empty, ResponseLimitError, ResponseLimits};
 
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
 
@@ -608,6 +796,39 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn env_checks_are_applied() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        instance.set_env_checks(Some(EnvChecks::default()));
+
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        let info = mock_info("creator", &coins(1000, "a"));
+        match call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap_err()
+        {
+            VmError::InvalidEnvironment { msg, .. } => {
+                assert_eq!(msg, "Invalid denom in funds: \"a\"")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        let mut env = mock_env();
+        env.block.chain_id = String::new();
+        let err = call_query(&mut instance, &env, br#"{"verifier":{}}"#).unwrap_err();
+        assert!(matches!(err, VmError::InvalidEnvironment { .. }));
+
+        // disabled again
+        instance.set_env_checks(None);
+        call_query(&mut instance, &env, br#"{"verifier":{}}"#)
+            .unwrap()
+            .unwrap();
+    }
+
     #[test]
     fn call_execute_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -627,6 +848,153 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn response_limits_are_applied() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        instance.set_response_limits(Some(ResponseLimits {
+            max_messages: 0,
+            ..ResponseLimits::default()
+        }));
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // release sends the balance to the beneficiary
+        let info = mock_info("verifies", &[]);
+        let msg = br#"{"release":{}}"#;
+        match call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap_err() {
+            VmError::ResponseLimitExceeded { source, .. } => assert_eq!(
+                source,
+                ResponseLimitError::TooManyMessages { count: 1, max: 0 }
+            ),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        // errors of the contract are passed on
+        let info = mock_info("someone else", &[]);
+        let result =
+            call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap();
+        assert!(result.is_err());
+
+        // disabled again
+        instance.set_response_limits(None);
+        let info = mock_info("verifies", &[]);
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn response_gas_is_charged() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let env = to_vec(&mock_env()).unwrap();
+        let info = to_vec(&mock_info("creator", &[])).unwrap();
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+
+        // not charged by default
+        call_instantiate_raw(&mut instance, &env, &info, msg).unwrap();
+        assert_eq!(instance.create_gas_report().used_for_responses, 0);
+
+        let costs = ResponseGasCosts {
+            per_byte: 1,
+            per_event: 1000,
+            per_attribute: 100,
+            per_attribute_byte: 10,
+        };
+        instance.set_response_gas_costs(Some(costs));
+        let response = call_instantiate_raw(&mut instance, &env, &info, msg).unwrap();
+        // one attribute "Let the": "hacking begin" and no events
+        let expected = response.len() as u64 + 100 + 20 * 10;
+        assert_eq!(instance.create_gas_report().used_for_responses, expected);
+
+        // release has 3 attributes with 40 bytes and a "hackatom" event
+        let info = to_vec(&mock_info("verifies", &[])).unwrap();
+        let response = call_execute_raw(&mut instance, &env, &info, br#"{"release":{}}"#).unwrap();
+        let expected = expected + response.len() as u64 + 1000 + 3 * 100 + 53 * 10;
+        assert_eq!(instance.create_gas_report().used_for_responses, expected);
+
+        // errors are charged for their size
+        let info = to_vec(&mock_info("someone else", &[])).unwrap();
+        let response = call_execute_raw(&mut instance, &env, &info, br#"{"release":{}}"#).unwrap();
+        assert!(from_slice::<ContractResult<Empty>>(&response, usize::MAX)
+            .unwrap()
+            .is_err());
+        let expected = expected + response.len() as u64;
+        let report = instance.create_gas_report();
+        assert_eq!(report.used_for_responses, expected);
+        assert!(report.used_internally > report.used_for_responses);
+    }
+
+    #[test]
+    fn response_gas_can_deplete_gas() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        instance.set_response_gas_costs(Some(ResponseGasCosts {
+            per_event: u64::MAX,
+            ..ResponseGasCosts::default()
+        }));
+        let info = mock_info("creator", &[]);
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        let info = mock_info("verifies", &[]);
+        let msg = br#"{"release":{}}"#;
+        match call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        assert_eq!(instance.get_gas_left(), 0);
+    }
+
+    struct DenyBankSends;
+
+    impl MessagePolicy for DenyBankSends {
+        fn check_message(&self, contract: &Addr, msg: OutgoingMsg) -> BackendResult<()> {
+            let result = match msg {
+                OutgoingMsg::Bank(BankMsg::Send { to_address, .. }) => Err(BackendError::user_err(
+                    format!("{} may not send to {}", contract, to_address),
+                )),
+                _ => Ok(()),
+            };
+            (result, GasInfo::with_cost(1000))
+        }
+    }
+
+    #[test]
+    fn message_policy_is_applied() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        instance.set_message_policy(Some(Box::new(DenyBankSends)));
+
+        // responses without messages are not affected
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // release sends the balance to the beneficiary
+        let gas_before = instance.get_gas_left();
+        let info = mock_info("verifies", &[]);
+        let msg = br#"{"release":{}}"#;
+        match call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap_err() {
+            VmError::MessageRejected { msg, .. } => {
+                assert_eq!(msg, "cosmos2contract may not send to benefits")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        assert!(gas_before - instance.get_gas_left() > 1000);
+
+        // disabled again
+        instance.set_message_policy(None);
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+    }
+
     #[test]
     fn call_migrate_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
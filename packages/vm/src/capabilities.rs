@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
-use crate::static_analysis::ExportInfo;
+use crate::errors::VmResult;
+use crate::static_analysis::{deserialize_wasm, ExportInfo};
 
 const REQUIRES_PREFIX: &str = "requires_";
 
@@ -30,10 +31,15 @@ pub fn required_capabilities_from_module(module: &impl ExportInfo) -> HashSet<St
         .collect()
 }
 
+/// Returns the capabilities a contract requires, given by its `requires_*` exports
+pub fn required_capabilities(wasm_code: &[u8]) -> VmResult<HashSet<String>> {
+    let module = deserialize_wasm(wasm_code)?;
+    Ok(required_capabilities_from_module(&module))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::static_analysis::deserialize_wasm;
 
     #[test]
     fn capabilities_from_csv_works() {
@@ -89,4 +95,24 @@ mod tests {
         let required_capabilities = required_capabilities_from_module(&module);
         assert_eq!(required_capabilities.len(), 0);
     }
+
+    #[test]
+    fn required_capabilities_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+            (type (func))
+            (func (type 0) nop)
+            (export "requires_iterator" (func 0))
+            (export "requires_stargate" (func 0))
+            )"#,
+        )
+        .unwrap();
+        let capabilities = required_capabilities(&wasm).unwrap();
+        assert_eq!(
+            capabilities,
+            HashSet::from(["iterator".to_string(), "stargate".to_string()])
+        );
+
+        assert!(required_capabilities(b"not wasm").is_err());
+    }
 }
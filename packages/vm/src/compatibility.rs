@@ -1,10 +1,14 @@
 use parity_wasm::elements::{External, ImportEntry, Module};
 use std::collections::BTreeSet;
 use std::collections::HashSet;
+use wasmer::wasmparser::{
+    ExternalKind, ImportSectionEntryType, Parser, Payload, Validator, WasmFeatures,
+};
 
 use crate::capabilities::required_capabilities_from_module;
 use crate::errors::{VmError, VmResult};
 use crate::limited::LimitedDisplay;
+use crate::proposals::WasmProposals;
 use crate::static_analysis::{deserialize_wasm, ExportInfo};
 
 /// Lists all imports we provide upon instantiating the instance in Instance::from_module()
@@ -50,17 +54,139 @@ const SUPPORTED_INTERFACE_VERSIONS: &[&str] = &[
 
 const MEMORY_LIMIT: u32 = 512; // in pages
 
+/// The capability a chain needs to accept contracts with a 64-bit memory (memory64 proposal)
+const MEMORY64_CAPABILITY: &str = "memory64";
+
 /// Checks if the data is valid wasm and compatibility with the CosmWasm API (imports and exports)
 pub fn check_wasm(wasm_code: &[u8], available_capabilities: &HashSet<String>) -> VmResult<()> {
+    check_wasm_with_proposals(wasm_code, available_capabilities, &WasmProposals::default())
+}
+
+/// Checks the Wasm like [`check_wasm`] and allows it to use the given proposals
+pub fn check_wasm_with_proposals(
+    wasm_code: &[u8],
+    available_capabilities: &HashSet<String>,
+    proposals: &WasmProposals,
+) -> VmResult<()> {
+    if uses_memory64(wasm_code) {
+        return check_wasm64(wasm_code, available_capabilities, proposals);
+    }
     let module = deserialize_wasm(wasm_code)?;
     check_wasm_memories(&module)?;
     check_interface_version(&module)?;
     check_wasm_exports(&module)?;
     check_wasm_imports(&module, SUPPORTED_IMPORTS)?;
     check_wasm_capabilities(&module, available_capabilities)?;
+    check_wasm_proposals(wasm_code, proposals, false)?;
     Ok(())
 }
 
+/// Checks a contract with a 64-bit memory (memory64 proposal) like [`check_wasm_with_proposals`].
+///
+/// Those are only accepted when the memory64 capability is available. parity-wasm cannot
+/// deserialize them, so the checks run on the sections read by wasmparser.
+fn check_wasm64(
+    wasm_code: &[u8],
+    available_capabilities: &HashSet<String>,
+    proposals: &WasmProposals,
+) -> VmResult<()> {
+    if !available_capabilities.contains(MEMORY64_CAPABILITY) {
+        return Err(VmError::static_validation_err(format!(
+            "Wasm contract uses a 64-bit memory (memory64 proposal), which requires the {} capability. Compile the contract for wasm32.",
+            MEMORY64_CAPABILITY
+        )));
+    }
+    // Checked first because the sections below are only read from valid Wasm
+    check_wasm_proposals(wasm_code, proposals, true)?;
+    let module = Wasm64Module::parse(wasm_code)?;
+    check_memory_count(module.memories.len())?;
+    let (initial, has_maximum) = module.memories[0];
+    check_memory_limits(initial, has_maximum)?;
+    check_interface_version(&module)?;
+    check_wasm_exports(&module)?;
+    check_imports(&module.imports, SUPPORTED_IMPORTS)?;
+    check_wasm_capabilities(&module, available_capabilities)?;
+    Ok(())
+}
+
+/// The sections of a module with a 64-bit memory that static validation looks at
+struct Wasm64Module {
+    /// The initial number of pages and whether a maximum is set, for each memory
+    memories: Vec<(u64, bool)>,
+    /// The full name of each import and whether it is a function
+    imports: Vec<(String, bool)>,
+    function_exports: Vec<String>,
+}
+
+impl Wasm64Module {
+    fn parse(wasm_code: &[u8]) -> VmResult<Self> {
+        let mut module = Wasm64Module {
+            memories: vec![],
+            imports: vec![],
+            function_exports: vec![],
+        };
+        for payload in Parser::new(0).parse_all(wasm_code) {
+            let payload = payload.map_err(|err| {
+                VmError::static_validation_err(format!(
+                    "Wasm bytecode could not be parsed: {}",
+                    err.message()
+                ))
+            })?;
+            match payload {
+                Payload::MemorySection(reader) => {
+                    for memory in reader {
+                        let memory = memory.map_err(|err| {
+                            VmError::static_validation_err(err.message().to_string())
+                        })?;
+                        module
+                            .memories
+                            .push((memory.initial, memory.maximum.is_some()));
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(|err| {
+                            VmError::static_validation_err(err.message().to_string())
+                        })?;
+                        let full_name =
+                            format!("{}.{}", import.module, import.field.unwrap_or_default());
+                        let is_function = matches!(import.ty, ImportSectionEntryType::Function(_));
+                        module.imports.push((full_name, is_function));
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.map_err(|err| {
+                            VmError::static_validation_err(err.message().to_string())
+                        })?;
+                        if matches!(export.kind, ExternalKind::Function) {
+                            module.function_exports.push(export.field.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(module)
+    }
+}
+
+impl ExportInfo for Wasm64Module {
+    fn exported_function_names(&self, prefix: Option<&str>) -> HashSet<String> {
+        self.function_exports
+            .iter()
+            .filter(|name| {
+                if let Some(required_prefix) = prefix {
+                    name.starts_with(required_prefix)
+                } else {
+                    true
+                }
+            })
+            .cloned()
+            .collect()
+    }
+}
+
 fn check_wasm_memories(module: &Module) -> VmResult<()> {
     let section = match module.memory_section() {
         Some(section) => section,
@@ -72,24 +198,33 @@ fn check_wasm_memories(module: &Module) -> VmResult<()> {
     };
 
     let memories = section.entries();
-    if memories.len() != 1 {
-        return Err(VmError::static_validation_err(
-            "Wasm contract must contain exactly one memory",
-        ));
-    }
+    check_memory_count(memories.len())?;
 
     let memory = memories[0];
     // println!("Memory: {:?}", memory);
     let limits = memory.limits();
+    check_memory_limits(limits.initial().into(), limits.maximum().is_some())
+}
 
-    if limits.initial() > MEMORY_LIMIT {
+fn check_memory_count(count: usize) -> VmResult<()> {
+    if count != 1 {
+        return Err(VmError::static_validation_err(
+            "Wasm contract must contain exactly one memory",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the limits of the contract memory, given in pages
+fn check_memory_limits(initial: u64, has_maximum: bool) -> VmResult<()> {
+    if initial > MEMORY_LIMIT.into() {
         return Err(VmError::static_validation_err(format!(
             "Wasm contract memory's minimum must not exceed {} pages.",
             MEMORY_LIMIT
         )));
     }
 
-    if limits.maximum().is_some() {
+    if has_maximum {
         return Err(VmError::static_validation_err(
             "Wasm contract memory's maximum must be unset. The host will set it for you.",
         ));
@@ -97,7 +232,44 @@ fn check_wasm_memories(module: &Module) -> VmResult<()> {
     Ok(())
 }
 
-fn check_interface_version(module: &Module) -> VmResult<()> {
+/// Returns true if the Wasm has a 64-bit memory of the memory64 proposal. Those are checked
+/// separately since the deserialization would fail with a generic error for them.
+fn uses_memory64(wasm_code: &[u8]) -> bool {
+    for payload in Parser::new(0).parse_all(wasm_code) {
+        match payload {
+            Ok(Payload::MemorySection(reader)) => {
+                if reader.into_iter().flatten().any(|memory| memory.memory64) {
+                    return true;
+                }
+            }
+            // Invalid Wasm is reported by the deserialization
+            Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    false
+}
+
+/// Validates the Wasm with only the given proposals enabled, plus memory64 if requested
+fn check_wasm_proposals(
+    wasm_code: &[u8],
+    proposals: &WasmProposals,
+    memory64: bool,
+) -> VmResult<()> {
+    let mut validator = Validator::new();
+    validator.wasm_features(WasmFeatures {
+        memory64,
+        ..proposals.wasm_features()
+    });
+    validator.validate_all(wasm_code).map_err(|err| {
+        VmError::static_validation_err(format!(
+            "Wasm contract is invalid or uses a Wasm proposal that is not enabled: {}",
+            err.message()
+        ))
+    })
+}
+
+fn check_interface_version(module: &impl ExportInfo) -> VmResult<()> {
     let mut interface_version_exports = module
         .exported_function_names(Some(INTERFACE_VERSION_PREFIX))
         .into_iter();
@@ -127,7 +299,7 @@ fn check_interface_version(module: &Module) -> VmResult<()> {
     }
 }
 
-fn check_wasm_exports(module: &Module) -> VmResult<()> {
+fn check_wasm_exports(module: &impl ExportInfo) -> VmResult<()> {
     let available_exports: HashSet<String> = module.exported_function_names(None);
     for required_export in REQUIRED_EXPORTS {
         if !available_exports.contains(*required_export) {
@@ -144,14 +316,28 @@ fn check_wasm_exports(module: &Module) -> VmResult<()> {
 /// When this is not the case, we either have an incompatibility between contract and VM
 /// or a error in the contract.
 fn check_wasm_imports(module: &Module, supported_imports: &[&str]) -> VmResult<()> {
-    let required_imports: Vec<ImportEntry> = module
-        .import_section()
-        .map_or(vec![], |import_section| import_section.entries().to_vec());
-    let required_import_names: BTreeSet<_> =
-        required_imports.iter().map(full_import_name).collect();
-
-    for required_import in required_imports {
-        let full_name = full_import_name(&required_import);
+    let required_imports: Vec<(String, bool)> =
+        module.import_section().map_or(vec![], |import_section| {
+            import_section
+                .entries()
+                .iter()
+                .map(|entry| {
+                    let is_function = matches!(entry.external(), External::Function(_));
+                    (full_import_name(entry), is_function)
+                })
+                .collect()
+        });
+    check_imports(&required_imports, supported_imports)
+}
+
+/// Checks the imports given by their full name and whether they are a function
+fn check_imports(required_imports: &[(String, bool)], supported_imports: &[&str]) -> VmResult<()> {
+    let required_import_names: BTreeSet<_> = required_imports
+        .iter()
+        .map(|(full_name, _)| full_name)
+        .collect();
+
+    for (full_name, is_function) in required_imports {
         if !supported_imports.contains(&full_name.as_str()) {
             return Err(VmError::static_validation_err(format!(
                 "Wasm contract requires unsupported import: \"{}\". Required imports: {}. Available imports: {:?}.",
@@ -159,13 +345,12 @@ fn check_wasm_imports(module: &Module, supported_imports: &[&str]) -> VmResult<(
             )));
         }
 
-        match required_import.external() {
-            External::Function(_) => {}, // ok
-            _ => return Err(VmError::static_validation_err(format!(
+        if !is_function {
+            return Err(VmError::static_validation_err(format!(
                 "Wasm contract requires non-function import: \"{}\". Right now, all supported imports are functions.",
                 full_name
-            ))),
-        };
+            )));
+        }
     }
     Ok(())
 }
@@ -175,7 +360,7 @@ fn full_import_name(ie: &ImportEntry) -> String {
 }
 
 fn check_wasm_capabilities(
-    module: &Module,
+    module: &impl ExportInfo,
     available_capabilities: &HashSet<String>,
 ) -> VmResult<()> {
     let required_capabilities = required_capabilities_from_module(module);
@@ -195,6 +380,7 @@ fn check_wasm_capabilities(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::capabilities::capabilities_from_csv;
     use crate::errors::VmError;
 
     static CONTRACT_0_7: &[u8] = include_bytes!("../testdata/hackatom_0.7.wasm");
@@ -330,6 +516,132 @@ mod tests {
         }
     }
 
+    #[test]
+    fn uses_memory64_works() {
+        let wasm = wat::parse_str("(module (memory 1))").unwrap();
+        assert!(!uses_memory64(&wasm));
+        let wasm64 = wat::parse_str("(module (memory i64 1))").unwrap();
+        assert!(uses_memory64(&wasm64));
+
+        // invalid Wasm is left to the deserialization
+        assert!(!uses_memory64(b"not wasm"));
+    }
+
+    #[test]
+    fn check_wasm_memory64_requires_capability() {
+        let wasm64 = wat::parse_str(
+            r#"(module
+                (import "env" "db_read" (func (param i64) (result i64)))
+                (memory i64 17)
+                (func (export "interface_version_8"))
+                (func (export "allocate") (param i64) (result i64) i64.const 0)
+                (func (export "deallocate") (param i64))
+                (func (export "instantiate") (param i64 i64 i64) (result i64) i64.const 0)
+            )"#,
+        )
+        .unwrap();
+        match check_wasm(&wasm64, &default_capabilities()) {
+            Err(VmError::StaticValidationErr { msg, .. }) => {
+                assert!(msg.starts_with("Wasm contract uses a 64-bit memory (memory64 proposal), which requires the memory64 capability."));
+            }
+            Err(e) => panic!("Unexpected error {:?}", e),
+            Ok(_) => panic!("Didn't reject wasm with 64-bit memory"),
+        }
+
+        let with_memory64 = capabilities_from_csv("staking,memory64");
+        check_wasm(&wasm64, &with_memory64).unwrap();
+    }
+
+    #[test]
+    fn check_wasm_memory64_runs_all_checks() {
+        let capabilities = capabilities_from_csv("memory64");
+        let check = |wat: &str| {
+            let wasm = wat::parse_str(wat).unwrap();
+            match check_wasm(&wasm, &capabilities) {
+                Err(VmError::StaticValidationErr { msg, .. }) => msg,
+                Err(e) => panic!("Unexpected error {:?}", e),
+                Ok(_) => panic!("Didn't reject invalid wasm"),
+            }
+        };
+        let exports = r#"
+            (func (export "interface_version_8"))
+            (func (export "allocate") (param i64) (result i64) i64.const 0)
+            (func (export "deallocate") (param i64))
+            (func (export "instantiate") (param i64 i64 i64) (result i64) i64.const 0)
+        "#;
+
+        let msg = check(&format!("(module (memory i64 513) {})", exports));
+        assert!(msg.starts_with("Wasm contract memory's minimum must not exceed 512 pages"));
+        let msg = check(&format!("(module (memory i64 1 2) {})", exports));
+        assert!(msg.starts_with("Wasm contract memory's maximum must be unset"));
+        let msg = check("(module (memory i64 1) (func (export \"interface_version_8\")))");
+        assert!(msg.starts_with("Wasm contract doesn't have required export: \"allocate\""));
+        let msg = check(&format!(
+            r#"(module (import "env" "foo" (func)) (memory i64 1) {})"#,
+            exports
+        ));
+        assert!(msg.starts_with("Wasm contract requires unsupported import: \"env.foo\""));
+        let msg = check(&format!(
+            r#"(module (memory i64 1) (func (export "requires_iterator")) {})"#,
+            exports
+        ));
+        assert_eq!(
+            msg,
+            "Wasm contract requires unavailable capabilities: {\"iterator\"}"
+        );
+        let msg = check(&format!(
+            "(module (memory i64 1) (func (drop (i32.extend8_s (i32.const 0)))) {})",
+            exports
+        ));
+        assert!(msg
+            .starts_with("Wasm contract is invalid or uses a Wasm proposal that is not enabled:"));
+    }
+
+    #[test]
+    fn check_wasm_proposals_works() {
+        let sign_ext = wat::parse_str(
+            "(module (func (param i32) (result i32) (i32.extend8_s (local.get 0))))",
+        )
+        .unwrap();
+        let multi_value =
+            wat::parse_str("(module (func (result i32 i32) (i32.const 1) (i32.const 2)))").unwrap();
+        let bulk_memory = wat::parse_str(
+            "(module (memory 1) (func (memory.copy (i32.const 0) (i32.const 1) (i32.const 1))))",
+        )
+        .unwrap();
+        let none = WasmProposals::default();
+        let all = WasmProposals {
+            sign_extension: true,
+            multi_value: true,
+            bulk_memory: true,
+        };
+
+        for wasm in [&sign_ext, &multi_value, &bulk_memory] {
+            // all can be deserialized
+            deserialize_wasm(wasm).unwrap();
+            match check_wasm_proposals(wasm, &none, false) {
+                Err(VmError::StaticValidationErr { msg, .. }) => {
+                    assert!(msg.starts_with(
+                        "Wasm contract is invalid or uses a Wasm proposal that is not enabled:"
+                    ));
+                }
+                Err(e) => panic!("Unexpected error {:?}", e),
+                Ok(_) => panic!("Didn't reject a disabled proposal"),
+            }
+            check_wasm_proposals(wasm, &all, false).unwrap();
+        }
+
+        // proposals are enabled individually
+        let sign_ext_only = WasmProposals {
+            sign_extension: true,
+            ..WasmProposals::default()
+        };
+        check_wasm_proposals(&sign_ext, &sign_ext_only, false).unwrap();
+        check_wasm_proposals(&bulk_memory, &sign_ext_only, false).unwrap_err();
+
+        check_wasm_with_proposals(CONTRACT, &default_capabilities(), &none).unwrap();
+    }
+
     #[test]
     fn check_wasm_memories_maximum_size() {
         let wasm_max = wat::parse_str("(module (memory 1 5))").unwrap();
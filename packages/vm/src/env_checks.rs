@@ -0,0 +1,171 @@
+//! Validation of the `Env` and `MessageInfo` provided by the host
+
+use cosmwasm_std::{Env, MessageInfo, Timestamp};
+
+use crate::errors::{VmError, VmResult};
+use crate::serde::from_slice;
+
+/// Max length (in bytes) of a serialized `Env` or `MessageInfo` that is checked.
+/// Both are created by the host, so this is only hit by broken embedders.
+const DESERIALIZATION_LIMIT: usize = 256 * 1024;
+
+/// Invariants of the `Env` and `MessageInfo` provided by the host, which are checked before
+/// they are passed to the contract. See [`Instance::set_env_checks`].
+///
+/// The following is checked:
+///
+/// - the chain ID is not empty
+/// - the block time is not before `min_block_time`
+/// - all denoms in the funds are valid Cosmos SDK denoms
+///
+/// [`Instance::set_env_checks`]: crate::Instance::set_env_checks
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnvChecks {
+    /// The earliest block time accepted, e.g. the genesis time of the chain
+    pub min_block_time: Timestamp,
+}
+
+impl EnvChecks {
+    pub(crate) fn check_env(&self, env: &Env) -> VmResult<()> {
+        if env.block.chain_id.is_empty() {
+            return Err(VmError::invalid_environment("Chain ID must not be empty"));
+        }
+        if env.block.time < self.min_block_time {
+            return Err(VmError::invalid_environment(format!(
+                "Block time {} is before the minimum block time {}",
+                env.block.time, self.min_block_time
+            )));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_info(&self, info: &MessageInfo) -> VmResult<()> {
+        for coin in &info.funds {
+            if !is_valid_denom(&coin.denom) {
+                return Err(VmError::invalid_environment(format!(
+                    "Invalid denom in funds: {:?}",
+                    coin.denom
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_raw_env(&self, env: &[u8]) -> VmResult<()> {
+        self.check_env(&from_slice(env, DESERIALIZATION_LIMIT)?)
+    }
+
+    pub(crate) fn check_raw_info(&self, info: &[u8]) -> VmResult<()> {
+        self.check_info(&from_slice(info, DESERIALIZATION_LIMIT)?)
+    }
+}
+
+/// Checks a denom against the Cosmos SDK rules, i.e. the regular expression
+/// `[a-zA-Z][a-zA-Z0-9/:._-]{2,127}`
+fn is_valid_denom(denom: &str) -> bool {
+    let bytes = denom.as_bytes();
+    (3..=128).contains(&bytes.len())
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1..]
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || b"/:._-".contains(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_env, mock_info};
+    use crate::to_vec;
+    use cosmwasm_std::{coin, coins};
+
+    #[test]
+    fn is_valid_denom_works() {
+        assert!(is_valid_denom("uatom"));
+        assert!(is_valid_denom(
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        ));
+        assert!(is_valid_denom("factory/osmo1abc/my.token-1_a:b"));
+        assert!(is_valid_denom(&"a".repeat(128)));
+
+        assert!(!is_valid_denom(""));
+        assert!(!is_valid_denom("ab"));
+        assert!(!is_valid_denom(&"a".repeat(129)));
+        assert!(!is_valid_denom("1atom"));
+        assert!(!is_valid_denom("/atom"));
+        assert!(!is_valid_denom("u atom"));
+        assert!(!is_valid_denom("uätom"));
+    }
+
+    #[test]
+    fn check_env_works() {
+        let checks = EnvChecks::default();
+        checks.check_env(&mock_env()).unwrap();
+
+        let mut env = mock_env();
+        env.block.chain_id = String::new();
+        match checks.check_env(&env).unwrap_err() {
+            VmError::InvalidEnvironment { msg, .. } => {
+                assert_eq!(msg, "Chain ID must not be empty")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn check_env_enforces_min_block_time() {
+        let env = mock_env();
+        let checks = EnvChecks {
+            min_block_time: env.block.time,
+        };
+        checks.check_env(&env).unwrap();
+
+        let checks = EnvChecks {
+            min_block_time: env.block.time.plus_nanos(1),
+        };
+        match checks.check_env(&env).unwrap_err() {
+            VmError::InvalidEnvironment { msg, .. } => assert_eq!(
+                msg,
+                "Block time 1571797419.879305533 is before the minimum block time 1571797419.879305534"
+            ),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn check_info_works() {
+        let checks = EnvChecks::default();
+        checks
+            .check_info(&mock_info(
+                "creator",
+                &[coin(1, "earth"), coin(2, "ibc/ABC")],
+            ))
+            .unwrap();
+        checks.check_info(&mock_info("creator", &[])).unwrap();
+
+        match checks
+            .check_info(&mock_info("creator", &coins(1, "x")))
+            .unwrap_err()
+        {
+            VmError::InvalidEnvironment { msg, .. } => {
+                assert_eq!(msg, "Invalid denom in funds: \"x\"")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn check_raw_env_works() {
+        let checks = EnvChecks::default();
+        checks.check_raw_env(&to_vec(&mock_env()).unwrap()).unwrap();
+        checks
+            .check_raw_info(&to_vec(&mock_info("creator", &[])).unwrap())
+            .unwrap();
+
+        match checks.check_raw_env(b"{}").unwrap_err() {
+            VmError::ParseErr { target_type, .. } => {
+                assert_eq!(target_type, "cosmwasm_std::types::Env")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+}
@@ -3,12 +3,18 @@ use std::borrow::{Borrow, BorrowMut};
 use std::ptr::NonNull;
 use std::sync::{Arc, RwLock};
 
-use wasmer::{HostEnvInitError, Instance as WasmerInstance, Memory, Val, WasmerEnv};
+use wasmer::{HostEnvInitError, Instance as WasmerInstance, Memory, RuntimeError, Val, WasmerEnv};
 use wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints};
 
+use cosmwasm_std::{Attribute, Event};
+
 use crate::backend::{BackendApi, GasInfo, Querier, Storage};
 use crate::errors::{VmError, VmResult};
 
+/// The default maximum depth of nested queries, i.e. contracts querying contracts
+/// that query contracts. A top level call has depth 0.
+pub const DEFAULT_MAX_QUERY_DEPTH: u32 = 10;
+
 /// Never can never be instantiated.
 /// Replace this with the [never primitive type](https://doc.rust-lang.org/std/primitive.never.html) when stable.
 #[derive(Debug)]
@@ -50,15 +56,80 @@ impl Default for GasConfig {
     }
 }
 
+/// Gas costs for the size of the responses of `instantiate`, `execute`, `migrate`, `sudo`,
+/// `reply` and the IBC entry points, charged to the call that returned the response.
+///
+/// Without them, a contract can return large responses almost for free, since the Wasm
+/// execution that produces them is cheap compared to processing the response in the VM
+/// and the chain.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResponseGasCosts {
+    /// Cost per byte of the serialized response for deserializing it
+    pub per_byte: u64,
+    /// Cost per event
+    pub per_event: u64,
+    /// Cost per attribute, including the attributes of the events
+    pub per_attribute: u64,
+    /// Cost per byte of the event types and the keys and values of the attributes
+    pub per_attribute_byte: u64,
+}
+
+impl Default for ResponseGasCosts {
+    fn default() -> Self {
+        // Target is 10^12 per millisecond (see GAS.md), i.e. 10^6 gas per nanosecond.
+        const GAS_PER_NS: u64 = 1_000_000;
+        // Cosmos SDK gas is smaller by a factor of 150_000 (see GAS.md)
+        const SDK_GAS: u64 = 150_000;
+        Self {
+            // Deserializing JSON takes a few nanoseconds per byte
+            per_byte: 3 * GAS_PER_NS,
+            // The event costs of wasmd
+            per_event: 20 * SDK_GAS,
+            per_attribute: 10 * SDK_GAS,
+            per_attribute_byte: SDK_GAS,
+        }
+    }
+}
+
+impl ResponseGasCosts {
+    /// The cost of a response of the given size with the given attributes and events
+    pub(crate) fn cost(&self, size: usize, attributes: &[Attribute], events: &[Event]) -> u64 {
+        let attribute_count = attributes.len()
+            + events
+                .iter()
+                .map(|event| event.attributes.len())
+                .sum::<usize>();
+        let attribute_bytes = attributes
+            .iter()
+            .chain(events.iter().flat_map(|event| &event.attributes))
+            .map(|attribute| attribute.key.len() + attribute.value.len())
+            .sum::<usize>()
+            + events.iter().map(|event| event.ty.len()).sum::<usize>();
+        [
+            (size, self.per_byte),
+            (events.len(), self.per_event),
+            (attribute_count, self.per_attribute),
+            (attribute_bytes, self.per_attribute_byte),
+        ]
+        .iter()
+        .fold(0u64, |total, (count, cost)| {
+            total.saturating_add((*count as u64).saturating_mul(*cost))
+        })
+    }
+}
+
 /** context data **/
 
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct GasState {
     /// Gas limit for the computation, including internally and externally used gas.
-    /// This is set when the Environment is created and never mutated.
+    /// This is set when the Environment is created or the instance is reset.
     pub gas_limit: u64,
     /// Tracking the gas used in the Cosmos SDK, in CosmWasm gas units.
     pub externally_used_gas: u64,
+    /// Tracking the gas charged for the size of responses, see [`ResponseGasCosts`].
+    /// This is part of the internally used gas.
+    pub response_gas: u64,
 }
 
 impl GasState {
@@ -66,6 +137,7 @@ impl GasState {
         Self {
             gas_limit,
             externally_used_gas: 0,
+            response_gas: 0,
         }
     }
 }
@@ -168,7 +240,12 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
         func.call(args).map_err(|runtime_err| -> VmError {
             self.with_wasmer_instance::<_, Never>(|instance| {
                 let err: VmError = match get_remaining_points(instance) {
-                    MeteringPoints::Remaining(_) => VmError::from(runtime_err),
+                    MeteringPoints::Remaining(_) => match runtime_err.downcast::<VmError>() {
+                        // Passed through such that the caller can handle it
+                        Ok(err @ VmError::QueryDepthExceeded { .. }) => err,
+                        Ok(err) => VmError::from(RuntimeError::user(Box::new(err))),
+                        Err(runtime_err) => VmError::from(runtime_err),
+                    },
                     MeteringPoints::Exhausted => VmError::gas_depletion(),
                 };
                 Err(err)
@@ -217,6 +294,13 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
         })
     }
 
+    /// Starts gas accounting anew with the given limit, e.g. when the instance is reset
+    pub fn reset_gas_state(&self, gas_limit: u64) {
+        self.with_context_data_mut(|context_data| {
+            context_data.gas_state = GasState::with_limit(gas_limit);
+        });
+    }
+
     /// Creates a back reference from a contact to its partent instance
     pub fn set_wasmer_instance(&self, wasmer_instance: Option<NonNull<WasmerInstance>>) {
         self.with_context_data_mut(|context_data| {
@@ -235,6 +319,30 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
         })
     }
 
+    /// Returns the depth of nested queries the instance is executed in
+    pub fn query_depth(&self) -> u32 {
+        self.with_context_data(|context_data| context_data.query_depth)
+    }
+
+    pub fn set_query_depth(&self, depth: u32, max_depth: u32) {
+        self.with_context_data_mut(|context_data| {
+            context_data.query_depth = depth;
+            context_data.max_query_depth = max_depth;
+        })
+    }
+
+    /// Fails with [`VmError::QueryDepthExceeded`] if a query sent from this instance
+    /// would exceed the maximum depth of nested queries
+    pub fn check_query_depth(&self) -> VmResult<()> {
+        self.with_context_data(|context_data| {
+            if context_data.query_depth >= context_data.max_query_depth {
+                Err(VmError::query_depth_exceeded(context_data.max_query_depth))
+            } else {
+                Ok(())
+            }
+        })
+    }
+
     pub fn get_gas_left(&self) -> u64 {
         self.with_wasmer_instance(|instance| {
             Ok(match get_remaining_points(instance) {
@@ -315,6 +423,8 @@ pub struct ContextData<S: Storage, Q: Querier> {
     storage: Option<S>,
     storage_readonly: bool,
     querier: Option<Q>,
+    query_depth: u32,
+    max_query_depth: u32,
     /// A non-owning link to the wasmer instance
     wasmer_instance: Option<NonNull<WasmerInstance>>,
 }
@@ -326,6 +436,8 @@ impl<S: Storage, Q: Querier> ContextData<S, Q> {
             storage: None,
             storage_readonly: true,
             querier: None,
+            query_depth: 0,
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
             wasmer_instance: None,
         }
     }
@@ -459,6 +571,36 @@ mod tests {
         assert!(endq.is_none());
     }
 
+    #[test]
+    fn response_gas_costs_cost_works() {
+        let costs = ResponseGasCosts {
+            per_byte: 1,
+            per_event: 1000,
+            per_attribute: 100,
+            per_attribute_byte: 10,
+        };
+        assert_eq!(costs.cost(0, &[], &[]), 0);
+        assert_eq!(costs.cost(42, &[], &[]), 42);
+
+        let attributes = [Attribute::new("action", "swap")];
+        let events = [
+            Event::new("swap").add_attribute("pool", "1"),
+            Event::new("empty"),
+        ];
+        // 3 bytes, 2 events, 2 attributes and 10 + 4 + 5 + 5 bytes in events and attributes
+        assert_eq!(
+            costs.cost(3, &attributes, &events),
+            3 + 2 * 1000 + 2 * 100 + 24 * 10
+        );
+
+        // saturates instead of overflowing
+        let costs = ResponseGasCosts {
+            per_byte: u64::MAX,
+            ..ResponseGasCosts::default()
+        };
+        assert_eq!(costs.cost(2, &attributes, &[]), u64::MAX);
+    }
+
     #[test]
     fn process_gas_info_works_for_cost() {
         let (env, _instance) = make_instance(100);
@@ -634,6 +776,35 @@ mod tests {
         assert!(env.is_storage_readonly());
     }
 
+    #[test]
+    fn query_depth_defaults_to_zero() {
+        let (env, _instance) = make_instance(TESTING_GAS_LIMIT);
+        leave_default_data(&env);
+
+        assert_eq!(env.query_depth(), 0);
+        env.check_query_depth().unwrap();
+    }
+
+    #[test]
+    fn check_query_depth_works() {
+        let (env, _instance) = make_instance(TESTING_GAS_LIMIT);
+        leave_default_data(&env);
+
+        env.set_query_depth(2, 3);
+        assert_eq!(env.query_depth(), 2);
+        env.check_query_depth().unwrap();
+
+        env.set_query_depth(3, 3);
+        match env.check_query_depth().unwrap_err() {
+            VmError::QueryDepthExceeded { max_depth, .. } => assert_eq!(max_depth, 3),
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        // no queries at all
+        env.set_query_depth(0, 0);
+        env.check_query_depth().unwrap_err();
+    }
+
     #[test]
     fn call_function_works() {
         let (env, _instance) = make_instance(TESTING_GAS_LIMIT);
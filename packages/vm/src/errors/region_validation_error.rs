@@ -10,23 +10,23 @@ pub enum RegionValidationError {
         length,
         capacity
     )]
-    LengthExceedsCapacity { length: u32, capacity: u32 },
+    LengthExceedsCapacity { length: u64, capacity: u64 },
     #[error(
         "Region exceeds address space. Offset {}, capacity {}",
         offset,
         capacity
     )]
-    OutOfRange { offset: u32, capacity: u32 },
+    OutOfRange { offset: u64, capacity: u64 },
     #[error("Got a zero Wasm address in the offset")]
     ZeroOffset {},
 }
 
 impl RegionValidationError {
-    pub(crate) fn length_exceeds_capacity(length: u32, capacity: u32) -> Self {
+    pub(crate) fn length_exceeds_capacity(length: u64, capacity: u64) -> Self {
         RegionValidationError::LengthExceedsCapacity { length, capacity }
     }
 
-    pub(crate) fn out_of_range(offset: u32, capacity: u32) -> Self {
+    pub(crate) fn out_of_range(offset: u64, capacity: u64) -> Self {
         RegionValidationError::OutOfRange { offset, capacity }
     }
 
@@ -57,12 +57,12 @@ mod tests {
 
     #[test]
     fn out_of_range_works() {
-        let error = RegionValidationError::out_of_range(u32::MAX, 1);
+        let error = RegionValidationError::out_of_range(u64::MAX, 1);
         match error {
             RegionValidationError::OutOfRange {
                 offset, capacity, ..
             } => {
-                assert_eq!(offset, u32::MAX);
+                assert_eq!(offset, u64::MAX);
                 assert_eq!(capacity, 1);
             }
             e => panic!("Unexpected error: {:?}", e),
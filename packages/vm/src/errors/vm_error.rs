@@ -4,6 +4,7 @@ use std::fmt::{Debug, Display};
 use thiserror::Error;
 
 use cosmwasm_crypto::CryptoError;
+use cosmwasm_std::ResponseLimitError;
 
 use super::communication_error::CommunicationError;
 use crate::backend::BackendError;
@@ -79,6 +80,18 @@ pub enum VmError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Invalid environment: {msg}")]
+    InvalidEnvironment {
+        msg: String,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Message rejected by the message policy: {msg}")]
+    MessageRejected {
+        msg: String,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
     #[error("Error parsing into type {target_type}: {msg}")]
     ParseErr {
         /// the target type that was attempted
@@ -103,12 +116,24 @@ pub enum VmError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Maximum depth of nested queries exceeded. Limit: {max_depth}")]
+    QueryDepthExceeded {
+        max_depth: u32,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
     #[error("Error resolving Wasm function: {}", msg)]
     ResolveErr {
         msg: String,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Response limit exceeded: {source}")]
+    ResponseLimitExceeded {
+        source: ResponseLimitError,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
     #[error(
         "Unexpected number of result values when calling '{}'. Expected: {}, actual: {}.",
         function_name,
@@ -232,6 +257,22 @@ impl VmError {
         }
     }
 
+    pub(crate) fn invalid_environment(msg: impl Into<String>) -> Self {
+        VmError::InvalidEnvironment {
+            msg: msg.into(),
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub(crate) fn message_rejected(msg: impl Into<String>) -> Self {
+        VmError::MessageRejected {
+            msg: msg.into(),
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     pub(crate) fn parse_err(target: impl Into<String>, msg: impl Display) -> Self {
         VmError::ParseErr {
             target_type: target.into(),
@@ -259,6 +300,14 @@ impl VmError {
         }
     }
 
+    pub(crate) fn query_depth_exceeded(max_depth: u32) -> Self {
+        VmError::QueryDepthExceeded {
+            max_depth,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     pub(crate) fn resolve_err(msg: impl Into<String>) -> Self {
         VmError::ResolveErr {
             msg: msg.into(),
@@ -267,6 +316,14 @@ impl VmError {
         }
     }
 
+    pub(crate) fn response_limit_exceeded(source: ResponseLimitError) -> Self {
+        VmError::ResponseLimitExceeded {
+            source,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     pub(crate) fn result_mismatch(
         function_name: impl Into<String>,
         expected: usize,
@@ -325,6 +382,12 @@ impl From<BackendError> for VmError {
     }
 }
 
+impl From<ResponseLimitError> for VmError {
+    fn from(original: ResponseLimitError) -> Self {
+        VmError::response_limit_exceeded(original)
+    }
+}
+
 impl From<CryptoError> for VmError {
     fn from(original: CryptoError) -> Self {
         VmError::crypto_err(original)
@@ -491,6 +554,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_environment_works() {
+        let error = VmError::invalid_environment("Chain ID must not be empty");
+        match error {
+            VmError::InvalidEnvironment { msg, .. } => {
+                assert_eq!(msg, "Chain ID must not be empty")
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn message_rejected_works() {
+        let error = VmError::message_rejected("Contract may not send funds");
+        match error {
+            VmError::MessageRejected { msg, .. } => {
+                assert_eq!(msg, "Contract may not send funds")
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn parse_err_works() {
         let error = VmError::parse_err("Book", "Missing field: title");
@@ -519,6 +604,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn query_depth_exceeded_works() {
+        let error = VmError::query_depth_exceeded(10);
+        match error {
+            VmError::QueryDepthExceeded { max_depth, .. } => assert_eq!(max_depth, 10),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn resolve_err_works() {
         let error = VmError::resolve_err("function has different signature");
@@ -528,6 +622,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn response_limit_exceeded_works() {
+        let error = VmError::response_limit_exceeded(ResponseLimitError::TooManyMessages {
+            count: 3,
+            max: 2,
+        });
+        match error {
+            VmError::ResponseLimitExceeded { source, .. } => {
+                assert_eq!(
+                    source,
+                    ResponseLimitError::TooManyMessages { count: 3, max: 2 }
+                )
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn result_mismatch_works() {
         let error = VmError::result_mismatch("action", 0, 1);
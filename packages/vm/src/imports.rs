@@ -391,6 +391,7 @@ pub fn do_query_chain<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     request_ptr: u32,
 ) -> VmResult<u32> {
+    env.check_query_depth()?;
     let request = read_region(&env.memory(), request_ptr, MAX_LENGTH_QUERY_CHAIN_REQUEST)?;
 
     let gas_remaining = env.get_gas_left();
@@ -1746,6 +1747,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn do_query_chain_fails_when_query_depth_exceeded() {
+        let api = MockApi::default();
+        let (env, _instance) = make_instance(api);
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let request_data = cosmwasm_std::to_vec(&request).unwrap();
+        let request_ptr = write_data(&env, &request_data);
+
+        leave_default_data(&env);
+
+        // one level below the limit works
+        env.set_query_depth(4, 5);
+        do_query_chain(&env, request_ptr).unwrap();
+
+        env.set_query_depth(5, 5);
+        let result = do_query_chain(&env, request_ptr);
+        match result.unwrap_err() {
+            VmError::QueryDepthExceeded { max_depth, .. } => assert_eq!(max_depth, 5),
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
     #[test]
     #[cfg(feature = "iterator")]
     fn do_db_scan_unbound_works() {
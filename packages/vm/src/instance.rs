@@ -1,13 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use std::ptr::NonNull;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use wasmer::{Exports, Function, ImportObject, Instance as WasmerInstance, Module, Val};
 
-use crate::backend::{Backend, BackendApi, Querier, Storage};
+use cosmwasm_std::{Addr, ResponseLimits};
+
+use crate::backend::{
+    Backend, BackendApi, BackendError, GasInfo, MessagePolicy, OutgoingMsg, Querier, Storage,
+};
 use crate::capabilities::required_capabilities_from_module;
+use crate::checksum::Checksum;
 use crate::conversion::{ref_to_u32, to_u32};
-use crate::environment::Environment;
+use crate::env_checks::EnvChecks;
+use crate::environment::{process_gas_info, Environment, ResponseGasCosts};
 use crate::errors::{CommunicationError, VmError, VmResult};
 use crate::imports::{
     do_abort, do_addr_canonicalize, do_addr_humanize, do_addr_validate, do_db_read, do_db_remove,
@@ -31,6 +37,9 @@ pub struct GasReport {
     /// The amount of gas that was spend and metered internally (i.e. by executing Wasm and calling
     /// API methods which are not metered externally)
     pub used_internally: u64,
+    /// The amount of gas that was charged for the size of the responses, which is included
+    /// in `used_internally`. See [`Instance::set_response_gas_costs`].
+    pub used_for_responses: u64,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -47,6 +56,16 @@ pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
     /// This instance should only be accessed via the Environment, which provides safe access.
     _inner: Box<WasmerInstance>,
     env: Environment<A, S, Q>,
+    /// The host functions, which are linked to every Wasmer instance created in [`Instance::reset`]
+    imports: ImportObject,
+    instantiation_lock: Option<Arc<Mutex<()>>>,
+    /// The checksum of the Wasm code if the instance was created from the cache.
+    /// Used for diagnostics only.
+    checksum: Option<Checksum>,
+    env_checks: Option<EnvChecks>,
+    message_policy: Option<Box<dyn MessagePolicy>>,
+    response_limits: Option<ResponseLimits>,
+    response_gas_costs: Option<ResponseGasCosts>,
 }
 
 impl<A, S, Q> Instance<A, S, Q>
@@ -80,7 +99,7 @@ where
         gas_limit: u64,
         print_debug: bool,
         extra_imports: Option<HashMap<&str, Exports>>,
-        instantiation_lock: Option<&Mutex<()>>,
+        instantiation_lock: Option<Arc<Mutex<()>>>,
     ) -> VmResult<Self> {
         let store = module.store();
 
@@ -224,15 +243,7 @@ where
             }
         }
 
-        let wasmer_instance = Box::from(
-            {
-                let _lock = instantiation_lock.map(|l| l.lock().unwrap());
-                WasmerInstance::new(module, &import_obj)
-            }
-            .map_err(|original| {
-                VmError::instantiation_err(format!("Error instantiating module: {:?}", original))
-            })?,
-        );
+        let wasmer_instance = instantiate(module, &import_obj, instantiation_lock.as_deref())?;
 
         let instance_ptr = NonNull::from(wasmer_instance.as_ref());
         env.set_wasmer_instance(Some(instance_ptr));
@@ -241,14 +252,53 @@ where
         let instance = Instance {
             _inner: wasmer_instance,
             env,
+            imports: import_obj,
+            instantiation_lock,
+            checksum: None,
+            env_checks: None,
+            message_policy: None,
+            response_limits: None,
+            response_gas_costs: None,
         };
         Ok(instance)
     }
 
+    pub(crate) fn set_checksum(&mut self, checksum: Checksum) {
+        self.checksum = Some(checksum);
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) fn checksum(&self) -> Option<Checksum> {
+        self.checksum
+    }
+
     pub fn api(&self) -> &A {
         &self.env.api
     }
 
+    /// Prepares the instance for another call, with the contract in the same state as in a new
+    /// instance and the given gas limit.
+    ///
+    /// This instantiates the compiled module again, so the contract gets a new memory and
+    /// globals initialized from the module. Only the host functions and the backend are kept,
+    /// which saves setting them up and is cheaper than getting a new instance from the cache.
+    /// Settings like the message policy or the query depth are kept as well. The gas used so far
+    /// is reset.
+    pub fn reset(&mut self, gas_limit: u64) -> VmResult<()> {
+        let wasmer_instance = instantiate(
+            self._inner.module(),
+            &self.imports,
+            self.instantiation_lock.as_deref(),
+        )?;
+        self.env
+            .set_wasmer_instance(Some(NonNull::from(wasmer_instance.as_ref())));
+        // The previous Wasmer instance is dropped only after the environment stopped using it
+        self._inner = wasmer_instance;
+        self.env.reset_gas_state(gas_limit);
+        self.env.set_gas_left(gas_limit);
+        Ok(())
+    }
+
     /// Decomposes this instance into its components.
     /// External dependencies are returned for reuse, the rest is dropped.
     pub fn recycle(self) -> Option<Backend<A, S, Q>> {
@@ -303,6 +353,7 @@ where
                 .gas_limit
                 .saturating_sub(state.externally_used_gas)
                 .saturating_sub(gas_left),
+            used_for_responses: state.response_gas,
         }
     }
 
@@ -313,6 +364,98 @@ where
         self.env.set_storage_readonly(new_value);
     }
 
+    /// Enables or disables checking the `Env` and `MessageInfo` passed to the contract in
+    /// every call. Calls with data violating the checks fail with
+    /// [`VmError::InvalidEnvironment`]. Disabled by default.
+    ///
+    /// This is meant for embedders and test harnesses that create the environment themselves.
+    /// Enabling it costs an additional deserialization of the data in raw calls.
+    pub fn set_env_checks(&mut self, checks: Option<EnvChecks>) {
+        self.env_checks = checks;
+    }
+
+    pub(crate) fn env_checks(&self) -> Option<&EnvChecks> {
+        self.env_checks.as_ref()
+    }
+
+    /// Sets the limits for the responses of the contract. Successful responses exceeding
+    /// them fail with [`VmError::ResponseLimitExceeded`] before they are returned.
+    /// Disabled by default.
+    ///
+    /// Enabling it costs an additional deserialization of the response in raw calls.
+    pub fn set_response_limits(&mut self, limits: Option<ResponseLimits>) {
+        self.response_limits = limits;
+    }
+
+    pub(crate) fn response_limits(&self) -> Option<&ResponseLimits> {
+        self.response_limits.as_ref()
+    }
+
+    /// Sets the gas costs for the size of the responses of the contract. The gas is charged
+    /// before the response is returned and reported in
+    /// [`GasReport::used_for_responses`](GasReport). Disabled by default.
+    ///
+    /// Enabling it costs an additional deserialization of the response in raw calls.
+    pub fn set_response_gas_costs(&mut self, costs: Option<ResponseGasCosts>) {
+        self.response_gas_costs = costs;
+    }
+
+    pub(crate) fn response_gas_costs(&self) -> Option<&ResponseGasCosts> {
+        self.response_gas_costs.as_ref()
+    }
+
+    pub(crate) fn charge_response_gas(&self, cost: u64) -> VmResult<()> {
+        process_gas_info(&self.env, GasInfo::with_cost(cost))?;
+        self.env
+            .with_gas_state_mut(|gas_state| gas_state.response_gas += cost);
+        Ok(())
+    }
+
+    /// Sets the policy deciding which bank and wasm messages the contract may send.
+    /// The messages of successful responses are checked before the response is returned,
+    /// such that calls sending a rejected message fail with [`VmError::MessageRejected`]
+    /// and nothing reaches the dispatcher. Disabled by default.
+    ///
+    /// Enabling it costs an additional deserialization of the environment and the response
+    /// in raw calls. The gas reported by the policy is charged to the call.
+    pub fn set_message_policy(&mut self, policy: Option<Box<dyn MessagePolicy>>) {
+        self.message_policy = policy;
+    }
+
+    pub(crate) fn has_message_policy(&self) -> bool {
+        self.message_policy.is_some()
+    }
+
+    pub(crate) fn check_message(&self, contract: &Addr, msg: OutgoingMsg) -> VmResult<()> {
+        let policy = match &self.message_policy {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+        let (result, gas_info) = policy.check_message(contract, msg);
+        process_gas_info(&self.env, gas_info)?;
+        result.map_err(|err| match err {
+            BackendError::UserErr { msg } => VmError::message_rejected(msg),
+            err => err.into(),
+        })
+    }
+
+    /// Sets the depth of nested queries this instance is executed in and the maximum depth.
+    ///
+    /// Top level calls have depth 0. A backend that executes a query sent by a contract at
+    /// depth `n` sets the depth of the queried contract's instance to `n + 1`. Once the
+    /// maximum depth is reached, queries sent by the contract fail with
+    /// [`VmError::QueryDepthExceeded`]. Defaults to depth 0 and [`DEFAULT_MAX_QUERY_DEPTH`].
+    ///
+    /// [`DEFAULT_MAX_QUERY_DEPTH`]: crate::DEFAULT_MAX_QUERY_DEPTH
+    pub fn set_query_depth(&mut self, depth: u32, max_depth: u32) {
+        self.env.set_query_depth(depth, max_depth);
+    }
+
+    /// Returns the depth of nested queries this instance is executed in
+    pub fn query_depth(&self) -> u32 {
+        self.env.query_depth()
+    }
+
     pub fn with_storage<F: FnOnce(&mut S) -> VmResult<T>, T>(&mut self, func: F) -> VmResult<T> {
         self.env.with_storage_from_context::<F, T>(func)
     }
@@ -364,6 +507,18 @@ where
     }
 }
 
+fn instantiate(
+    module: &Module,
+    imports: &ImportObject,
+    instantiation_lock: Option<&Mutex<()>>,
+) -> VmResult<Box<WasmerInstance>> {
+    let _lock = instantiation_lock.map(|l| l.lock().unwrap());
+    let instance = WasmerInstance::new(module, imports).map_err(|original| {
+        VmError::instantiation_err(format!("Error instantiating module: {:?}", original))
+    })?;
+    Ok(Box::from(instance))
+}
+
 /// This exists only to be exported through `internals` for use by crates that are
 /// part of Cosmwasm.
 pub fn instance_from_module<A, S, Q>(
@@ -715,6 +870,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reset_works() {
+        const LIMIT: u64 = 700_000_000_000;
+        let mut instance = mock_instance_with_gas_limit(CONTRACT, LIMIT);
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        instance.allocate(100 * 1024).expect("error allocating");
+        assert!(instance.memory_pages() > 17);
+        assert!(instance.create_gas_report().used_externally > 0);
+
+        instance.reset(LIMIT).unwrap();
+
+        // The contract starts with a new memory and no gas used
+        assert_eq!(instance.memory_pages(), 17);
+        let report = instance.create_gas_report();
+        assert_eq!(report.used_externally, 0);
+        assert_eq!(report.used_internally, 0);
+        assert_eq!(report.remaining, LIMIT);
+
+        // The storage is kept
+        let res = call_query(&mut instance, &mock_env(), br#"{"verifier":{}}"#).unwrap();
+        assert_eq!(res.unwrap().as_slice(), b"{\"verifier\":\"verifies\"}");
+
+        // A different gas limit can be used for every call
+        instance.reset(123321).unwrap();
+        assert_eq!(instance.get_gas_left(), 123321);
+        assert_eq!(instance.create_gas_report().limit, 123321);
+    }
+
     #[test]
     fn set_storage_readonly_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -770,6 +958,26 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn set_query_depth_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        assert_eq!(instance.query_depth(), 0);
+
+        // other_balance sends a bank query to the chain
+        let msg = br#"{"other_balance":{"address":"foobar"}}"#;
+        instance.set_query_depth(2, 3);
+        assert_eq!(instance.query_depth(), 2);
+        call_query(&mut instance, &mock_env(), msg)
+            .unwrap()
+            .unwrap();
+
+        instance.set_query_depth(3, 3);
+        match call_query(&mut instance, &mock_env(), msg).unwrap_err() {
+            VmError::QueryDepthExceeded { max_depth, .. } => assert_eq!(max_depth, 3),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn with_querier_works_readonly() {
         let rich_addr = String::from("foobar");
@@ -7,6 +7,7 @@ mod capabilities;
 mod checksum;
 mod compatibility;
 mod conversion;
+mod env_checks;
 mod environment;
 mod errors;
 mod imports;
@@ -14,17 +15,23 @@ mod instance;
 mod limited;
 mod memory;
 mod modules;
+mod producers;
+mod proposals;
+mod reachability;
 mod sections;
 mod serde;
 mod size;
 mod static_analysis;
+mod telemetry;
 pub mod testing;
+mod trace;
 mod wasm_backend;
 
 pub use crate::backend::{
-    Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage,
+    Backend, BackendApi, BackendError, BackendResult, GasInfo, MessagePolicy, OutgoingMsg, Querier,
+    Storage,
 };
-pub use crate::cache::{AnalysisReport, Cache, CacheOptions, Metrics, Stats};
+pub use crate::cache::{AnalysisReport, Cache, CacheOptions, Metrics, SharedMemoryCache, Stats};
 pub use crate::calls::{
     call_execute, call_execute_raw, call_instantiate, call_instantiate_raw, call_migrate,
     call_migrate_raw, call_query, call_query_raw, call_reply, call_reply_raw, call_sudo,
@@ -37,15 +44,24 @@ pub use crate::calls::{
     call_ibc_packet_ack, call_ibc_packet_ack_raw, call_ibc_packet_receive,
     call_ibc_packet_receive_raw, call_ibc_packet_timeout, call_ibc_packet_timeout_raw,
 };
-pub use crate::capabilities::capabilities_from_csv;
+pub use crate::capabilities::{capabilities_from_csv, required_capabilities};
 pub use crate::checksum::Checksum;
+pub use crate::env_checks::EnvChecks;
+pub use crate::environment::{ResponseGasCosts, DEFAULT_MAX_QUERY_DEPTH};
 pub use crate::errors::{
     CommunicationError, CommunicationResult, RegionValidationError, RegionValidationResult,
     VmError, VmResult,
 };
 pub use crate::instance::{GasReport, Instance, InstanceOptions};
+pub use crate::producers::{check_optimizer, processed_by, Producer};
+pub use crate::proposals::{wasm_proposals_from_csv, WasmProposals};
+pub use crate::reachability::{entry_point_sizes, import_usage, EntryPointSize, ImportUsage};
 pub use crate::serde::{from_slice, to_vec};
 pub use crate::size::Size;
+pub use crate::trace::{
+    call_trace_backend, record_call, replay_trace, CallTrace, CallTraceQuerier, CallTraceStorage,
+    HostCall, HostCallLog,
+};
 
 #[doc(hidden)]
 pub mod internals {
@@ -54,7 +70,7 @@ pub mod internals {
     //! Please don't use any of these types directly, as
     //! they might change frequently or be removed in the future.
 
-    pub use crate::compatibility::check_wasm;
+    pub use crate::compatibility::{check_wasm, check_wasm_with_proposals};
     pub use crate::instance::instance_from_module;
-    pub use crate::wasm_backend::{compile, make_runtime_store};
+    pub use crate::wasm_backend::{compile, compile_with_proposals, make_runtime_store};
 }
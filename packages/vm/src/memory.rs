@@ -26,6 +26,25 @@ struct Region {
 
 unsafe impl ValueType for Region {}
 
+/// The [`Region`] of contracts with a 64-bit memory (memory64 proposal), which can address
+/// more than 4 GiB. It has the same fields, but offsets and lengths are 64 bits wide.
+///
+/// Those contracts pass validation with the memory64 capability, but the engine cannot run
+/// them and the imports take 32-bit pointers. So this is only validated for now.
+#[repr(C)]
+#[derive(Default, Clone, Copy, Debug)]
+#[allow(dead_code)]
+struct Region64 {
+    /// The beginning of the region expressed as bytes from the beginning of the linear memory
+    pub offset: u64,
+    /// The number of bytes available in this region
+    pub capacity: u64,
+    /// The number of bytes used in this region
+    pub length: u64,
+}
+
+unsafe impl ValueType for Region64 {}
+
 /// Expects a (fixed size) Region struct at ptr, which is read. This links to the
 /// memory region, which is copied in the second step.
 /// Errors if the length of the region exceeds `max_length`.
@@ -120,20 +139,37 @@ fn get_region(memory: &wasmer::Memory, ptr: u32) -> CommunicationResult<Region>
 /// Performs plausibility checks in the given Region. Regions are always created by the
 /// contract and this can be used to detect problems in the standard library of the contract.
 fn validate_region(region: &Region) -> RegionValidationResult<()> {
-    if region.offset == 0 {
+    validate_region_bounds(
+        region.offset.into(),
+        region.capacity.into(),
+        region.length.into(),
+        u32::MAX.into(),
+    )
+}
+
+/// Performs the checks of [`validate_region`] for a 64-bit Region
+#[allow(dead_code)]
+fn validate_region64(region: &Region64) -> RegionValidationResult<()> {
+    validate_region_bounds(region.offset, region.capacity, region.length, u64::MAX)
+}
+
+/// Checks a Region given by its fields in a memory whose addresses go up to `max_address`
+fn validate_region_bounds(
+    offset: u64,
+    capacity: u64,
+    length: u64,
+    max_address: u64,
+) -> RegionValidationResult<()> {
+    if offset == 0 {
         return Err(RegionValidationError::zero_offset());
     }
-    if region.length > region.capacity {
+    if length > capacity {
         return Err(RegionValidationError::length_exceeds_capacity(
-            region.length,
-            region.capacity,
+            length, capacity,
         ));
     }
-    if region.capacity > (u32::MAX - region.offset) {
-        return Err(RegionValidationError::out_of_range(
-            region.offset,
-            region.capacity,
-        ));
+    if capacity > (max_address - offset) {
+        return Err(RegionValidationError::out_of_range(offset, capacity));
     }
     Ok(())
 }
@@ -247,7 +283,7 @@ mod tests {
                 offset, capacity, ..
             } => {
                 assert_eq!(offset, 23);
-                assert_eq!(capacity, u32::MAX);
+                assert_eq!(capacity, u64::from(u32::MAX));
             }
             e => panic!("Got unexpected error: {:?}", e),
         }
@@ -262,7 +298,65 @@ mod tests {
             RegionValidationError::OutOfRange {
                 offset, capacity, ..
             } => {
-                assert_eq!(offset, u32::MAX);
+                assert_eq!(offset, u64::from(u32::MAX));
+                assert_eq!(capacity, 1);
+            }
+            e => panic!("Got unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn validate_region64_works() {
+        let region = Region64 {
+            offset: 23,
+            capacity: 500,
+            length: 250,
+        };
+        validate_region64(&region).unwrap();
+
+        // beyond the 32-bit address space
+        let region = Region64 {
+            offset: u64::from(u32::MAX) + 1,
+            capacity: 1 << 33,
+            length: 1 << 32,
+        };
+        validate_region64(&region).unwrap();
+
+        let region = Region64 {
+            offset: 0,
+            capacity: 500,
+            length: 250,
+        };
+        match validate_region64(&region).unwrap_err() {
+            RegionValidationError::ZeroOffset { .. } => {}
+            e => panic!("Got unexpected error: {:?}", e),
+        }
+
+        let region = Region64 {
+            offset: 23,
+            capacity: 500,
+            length: 501,
+        };
+        match validate_region64(&region).unwrap_err() {
+            RegionValidationError::LengthExceedsCapacity {
+                length, capacity, ..
+            } => {
+                assert_eq!(length, 501);
+                assert_eq!(capacity, 500);
+            }
+            e => panic!("Got unexpected error: {:?}", e),
+        }
+
+        let region = Region64 {
+            offset: u64::MAX,
+            capacity: 1,
+            length: 0,
+        };
+        match validate_region64(&region).unwrap_err() {
+            RegionValidationError::OutOfRange {
+                offset, capacity, ..
+            } => {
+                assert_eq!(offset, u64::MAX);
                 assert_eq!(capacity, 1);
             }
             e => panic!("Got unexpected error: {:?}", e),
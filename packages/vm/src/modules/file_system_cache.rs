@@ -34,6 +34,16 @@ use crate::modules::current_wasmer_module_version;
 ///   Version for Wasmer 2.2.0 which contains a [module breaking change to 2.1.x](https://github.com/wasmerio/wasmer/pull/2747).
 const MODULE_SERIALIZATION_VERSION: &str = "v3";
 
+/// The version of the module serialization format of this VM, including the Wasmer module
+/// version. Modules serialized with a different version cannot be loaded.
+pub fn module_serialization_version() -> String {
+    format!(
+        "{}-wasmer{}",
+        MODULE_SERIALIZATION_VERSION,
+        current_wasmer_module_version()
+    )
+}
+
 /// Representation of a directory that contains compiled Wasm artifacts.
 pub struct FileSystemCache {
     /// The base path this cache operates in. Within this path, versioned directories are created.
@@ -30,6 +30,11 @@ pub struct InMemoryCache {
     modules: Option<CLruCache<Checksum, SizedModule, RandomState, SizeScale>>,
 }
 
+// `CLruCache` is not `Send` because it uses raw pointers internally, which only point into
+// data it owns. Moving the whole cache to another thread is fine. This allows sharing the
+// cache between caches behind a mutex.
+unsafe impl Send for InMemoryCache {}
+
 impl InMemoryCache {
     /// Creates a new cache with the given size (in bytes)
     /// and pre-allocated entries.
@@ -4,7 +4,7 @@ mod pinned_memory_cache;
 mod sized_module;
 mod versioning;
 
-pub use file_system_cache::FileSystemCache;
+pub use file_system_cache::{module_serialization_version, FileSystemCache};
 pub use in_memory_cache::InMemoryCache;
 pub use pinned_memory_cache::PinnedMemoryCache;
 pub use versioning::current_wasmer_module_version;
@@ -0,0 +1,270 @@
+//! Reading of the `producers` custom section, which records the tools used to build a Wasm file.
+//!
+//! See https://github.com/WebAssembly/tool-conventions/blob/main/ProducersSection.md
+//! for the format.
+
+use semver::{Version, VersionReq};
+
+use crate::errors::{VmError, VmResult};
+use crate::static_analysis::deserialize_wasm;
+
+const PRODUCERS_SECTION: &str = "producers";
+const PROCESSED_BY_FIELD: &str = "processed-by";
+
+/// A tool listed in the `producers` section of a Wasm file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Producer {
+    pub name: String,
+    pub version: String,
+}
+
+/// Returns the tools listed in the `processed-by` field of the `producers` section.
+///
+/// Returns an empty list if the Wasm file has no `producers` section.
+pub fn processed_by(wasm_code: &[u8]) -> VmResult<Vec<Producer>> {
+    let module = deserialize_wasm(wasm_code)?;
+    let mut out = Vec::new();
+    for section in module
+        .custom_sections()
+        .filter(|section| section.name() == PRODUCERS_SECTION)
+    {
+        for (field, producers) in parse_producers_section(section.payload())? {
+            if field == PROCESSED_BY_FIELD {
+                out.extend(producers);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Checks that the Wasm file was processed by one of the given optimizers
+/// (e.g. `rust-optimizer` or `workspace-optimizer`) in a version matching `version_req`.
+///
+/// The version requirement uses Cargo's syntax, e.g. `">=0.12.6, <0.13"`.
+///
+/// This relies on the optimizer adding itself to the `processed-by` field of the
+/// `producers` section, so it can only be used to enforce reproducible-build policies,
+/// not to prove where a Wasm file comes from.
+pub fn check_optimizer(wasm_code: &[u8], optimizers: &[&str], version_req: &str) -> VmResult<()> {
+    let req = VersionReq::parse(version_req).map_err(|err| {
+        VmError::static_validation_err(format!(
+            "Invalid optimizer version requirement \"{}\": {}",
+            version_req, err
+        ))
+    })?;
+
+    let producers = processed_by(wasm_code)?;
+    let matching = producers
+        .iter()
+        .filter(|producer| optimizers.contains(&producer.name.as_str()))
+        .collect::<Vec<_>>();
+    if matching.is_empty() {
+        return Err(VmError::static_validation_err(format!(
+            "Wasm file was not processed by any of the optimizers {:?}. Processed by: {:?}",
+            optimizers,
+            producers
+                .iter()
+                .map(|producer| format!("{} {}", producer.name, producer.version))
+                .collect::<Vec<_>>()
+        )));
+    }
+
+    for producer in &matching {
+        if let Ok(version) = Version::parse(&producer.version) {
+            if req.matches(&version) {
+                return Ok(());
+            }
+        }
+    }
+    Err(VmError::static_validation_err(format!(
+        "Optimizer version does not match requirement \"{}\". Found: {:?}",
+        version_req,
+        matching
+            .iter()
+            .map(|producer| format!("{} {}", producer.name, producer.version))
+            .collect::<Vec<_>>()
+    )))
+}
+
+fn parse_producers_section(payload: &[u8]) -> VmResult<Vec<(String, Vec<Producer>)>> {
+    let mut reader = Reader { data: payload };
+    let mut fields = Vec::new();
+    for _ in 0..reader.read_u32()? {
+        let field = reader.read_string()?;
+        let mut producers = Vec::new();
+        for _ in 0..reader.read_u32()? {
+            producers.push(Producer {
+                name: reader.read_string()?,
+                version: reader.read_string()?,
+            });
+        }
+        fields.push((field, producers));
+    }
+    Ok(fields)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Reads an unsigned LEB128 encoded u32
+    fn read_u32(&mut self) -> VmResult<u32> {
+        let mut result = 0u32;
+        for i in 0..5 {
+            let (&byte, rest) = self.data.split_first().ok_or_else(truncated)?;
+            self.data = rest;
+            result |= u32::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(VmError::static_validation_err(
+            "Invalid integer in producers section",
+        ))
+    }
+
+    fn read_string(&mut self) -> VmResult<String> {
+        let len = self.read_u32()? as usize;
+        if self.data.len() < len {
+            return Err(truncated());
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        String::from_utf8(bytes.to_vec()).map_err(|_| {
+            VmError::static_validation_err("Invalid UTF-8 string in producers section")
+        })
+    }
+}
+
+fn truncated() -> VmError {
+    VmError::static_validation_err("Producers section is truncated")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_string(out: &mut Vec<u8>, s: &str) {
+        leb128::write::unsigned(out, s.len() as u64).unwrap();
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    /// Returns a minimal Wasm file with a producers section containing the given fields
+    fn wasm_with_producers(fields: &[(&str, &[(&str, &str)])]) -> Vec<u8> {
+        let mut payload = Vec::new();
+        leb128::write::unsigned(&mut payload, fields.len() as u64).unwrap();
+        for (field, producers) in fields {
+            encode_string(&mut payload, field);
+            leb128::write::unsigned(&mut payload, producers.len() as u64).unwrap();
+            for (name, version) in producers.iter() {
+                encode_string(&mut payload, name);
+                encode_string(&mut payload, version);
+            }
+        }
+
+        let mut section = Vec::new();
+        encode_string(&mut section, PRODUCERS_SECTION);
+        section.extend(payload);
+
+        let mut wasm = wat::parse_str("(module)").unwrap();
+        wasm.push(0); // custom section id
+        leb128::write::unsigned(&mut wasm, section.len() as u64).unwrap();
+        wasm.extend(section);
+        wasm
+    }
+
+    #[test]
+    fn processed_by_works() {
+        let wasm = wasm_with_producers(&[
+            ("language", &[("Rust", "")]),
+            (
+                "processed-by",
+                &[("rustc", "1.59.0"), ("rust-optimizer", "0.12.8")],
+            ),
+        ]);
+        assert_eq!(
+            processed_by(&wasm).unwrap(),
+            vec![
+                Producer {
+                    name: "rustc".to_string(),
+                    version: "1.59.0".to_string()
+                },
+                Producer {
+                    name: "rust-optimizer".to_string(),
+                    version: "0.12.8".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn processed_by_returns_empty_list_without_producers_section() {
+        let wasm = wat::parse_str("(module)").unwrap();
+        assert_eq!(processed_by(&wasm).unwrap(), vec![]);
+        let wasm = include_bytes!("../testdata/hackatom.wasm");
+        assert_eq!(processed_by(wasm).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn processed_by_fails_for_truncated_section() {
+        let mut wasm = wat::parse_str("(module)").unwrap();
+        // custom section "producers" with 1 field, but no data
+        wasm.extend([0, 11, 9]);
+        wasm.extend(b"producers");
+        wasm.push(1);
+        match processed_by(&wasm).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert_eq!(msg, "Producers section is truncated")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn check_optimizer_works() {
+        let optimizers = ["rust-optimizer", "workspace-optimizer"];
+        let wasm = wasm_with_producers(&[(
+            "processed-by",
+            &[("rustc", "1.59.0"), ("workspace-optimizer", "0.12.8")],
+        )]);
+        check_optimizer(&wasm, &optimizers, ">=0.12.6, <0.13").unwrap();
+        check_optimizer(&wasm, &optimizers, "0.12").unwrap();
+        check_optimizer(&wasm, &optimizers, "*").unwrap();
+
+        match check_optimizer(&wasm, &optimizers, ">=0.13").unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => assert_eq!(
+                msg,
+                "Optimizer version does not match requirement \">=0.13\". Found: [\"workspace-optimizer 0.12.8\"]"
+            ),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        match check_optimizer(&wasm, &["rust-optimizer"], "*").unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => assert_eq!(
+                msg,
+                "Wasm file was not processed by any of the optimizers [\"rust-optimizer\"]. Processed by: [\"rustc 1.59.0\", \"workspace-optimizer 0.12.8\"]"
+            ),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        match check_optimizer(&wasm, &optimizers, "not a version").unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert!(msg.starts_with("Invalid optimizer version requirement \"not a version\""))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn check_optimizer_ignores_invalid_versions() {
+        let wasm = wasm_with_producers(&[(
+            "processed-by",
+            &[("rust-optimizer", "latest"), ("rust-optimizer", "0.12.8")],
+        )]);
+        check_optimizer(&wasm, &["rust-optimizer"], "0.12").unwrap();
+
+        let wasm = wasm_with_producers(&[("processed-by", &[("rust-optimizer", "latest")])]);
+        check_optimizer(&wasm, &["rust-optimizer"], "*").unwrap_err();
+    }
+}
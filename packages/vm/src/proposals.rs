@@ -0,0 +1,101 @@
+use wasmer::wasmparser::WasmFeatures;
+
+use crate::errors::{VmError, VmResult};
+
+/// The Wasm proposals beyond the MVP that contracts are allowed to use.
+///
+/// All nodes of a chain must accept the same contracts, so chains enable proposals in a
+/// coordinated upgrade. By default, none are enabled. Proposals that are not deterministic
+/// (SIMD with its float operations, threads) or that the VM cannot deserialize
+/// (reference types) are not available.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WasmProposals {
+    /// Sign-extension operators, e.g. `i32.extend8_s`
+    pub sign_extension: bool,
+    /// Functions with multiple results. Blocks with parameters or multiple results
+    /// are not supported.
+    pub multi_value: bool,
+    /// Bulk memory operations, e.g. `memory.copy`, and passive data segments
+    pub bulk_memory: bool,
+}
+
+impl WasmProposals {
+    /// The features for validating contracts with wasmparser
+    pub(crate) fn wasm_features(&self) -> WasmFeatures {
+        WasmFeatures {
+            sign_extension: self.sign_extension,
+            multi_value: self.multi_value,
+            bulk_memory: self.bulk_memory,
+            reference_types: false,
+            simd: false,
+            relaxed_simd: false,
+            threads: false,
+            memory64: false,
+            ..WasmFeatures::default()
+        }
+    }
+}
+
+/// Takes a comma-separated list of proposal names like "sign_extension,bulk_memory" and returns
+/// the proposals with those enabled. This can be used e.g. to configure the cache.
+pub fn wasm_proposals_from_csv(csv: &str) -> VmResult<WasmProposals> {
+    let mut proposals = WasmProposals::default();
+    for name in csv
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match name {
+            "sign_extension" => proposals.sign_extension = true,
+            "multi_value" => proposals.multi_value = true,
+            "bulk_memory" => proposals.bulk_memory = true,
+            unknown => {
+                return Err(VmError::generic_err(format!(
+                    "Unknown Wasm proposal: {}. Available proposals are sign_extension, multi_value and bulk_memory.",
+                    unknown
+                )))
+            }
+        }
+    }
+    Ok(proposals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wasm_proposals_from_csv_works() {
+        assert_eq!(
+            wasm_proposals_from_csv("").unwrap(),
+            WasmProposals::default()
+        );
+        assert_eq!(
+            wasm_proposals_from_csv(" bulk_memory,sign_extension, ").unwrap(),
+            WasmProposals {
+                sign_extension: true,
+                multi_value: false,
+                bulk_memory: true,
+            }
+        );
+
+        let err = wasm_proposals_from_csv("multi_value,simd").unwrap_err();
+        assert!(err.to_string().contains("Unknown Wasm proposal: simd."));
+    }
+
+    #[test]
+    fn wasm_features_works() {
+        let features = WasmProposals::default().wasm_features();
+        assert!(!features.sign_extension);
+        assert!(!features.simd);
+        assert!(!features.reference_types);
+
+        let features = WasmProposals {
+            multi_value: true,
+            ..WasmProposals::default()
+        }
+        .wasm_features();
+        assert!(features.multi_value);
+        assert!(!features.bulk_memory);
+    }
+}
@@ -0,0 +1,426 @@
+//! Call graph based analysis of the code size of a contract's exported entry points
+//! and of the host functions it calls
+
+use std::collections::{BTreeSet, HashMap};
+
+use parity_wasm::elements::{External, Instruction, Internal, Module};
+
+use crate::errors::{VmError, VmResult};
+use crate::static_analysis::deserialize_wasm;
+
+/// The code reachable from an exported function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPointSize {
+    /// The name of the export, e.g. "execute"
+    pub name: String,
+    /// The number of functions defined in the module that can be called from this entry point,
+    /// including the entry point itself
+    pub functions: usize,
+    /// The size in bytes of the code of all reachable functions
+    pub reachable_size: usize,
+    /// The size in bytes of the code reachable from this entry point only.
+    /// This is the code that is not needed anymore if the entry point is removed.
+    pub exclusive_size: usize,
+}
+
+/// A function imported by a contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportUsage {
+    /// The module the function is imported from, e.g. "env"
+    pub module: String,
+    /// The name of the imported function, e.g. "db_read"
+    pub name: String,
+    /// Whether the import can be called from an exported function or the start function.
+    /// Imports that are exported themselves count as used.
+    pub used: bool,
+}
+
+/// Computes the code size reachable from each exported function of the given Wasm.
+///
+/// Reachability is determined from direct calls. Since the targets of indirect calls
+/// are not known statically, a function containing a `call_indirect` is assumed to be
+/// able to call every function in the table. The results are sorted by name.
+pub fn entry_point_sizes(wasm_code: &[u8]) -> VmResult<Vec<EntryPointSize>> {
+    let module = deserialize_wasm(wasm_code)?;
+    let graph = CallGraph::new(&module)?;
+    let exports = exported_functions(&module);
+
+    let reachable: Vec<BTreeSet<u32>> = exports
+        .iter()
+        .map(|(_, index)| graph.reachable_from(*index))
+        .collect();
+
+    // For each function, the number of entry points it is reachable from
+    let mut usage = HashMap::<u32, usize>::new();
+    for functions in &reachable {
+        for function in functions {
+            *usage.entry(*function).or_default() += 1;
+        }
+    }
+
+    let mut out: Vec<EntryPointSize> = exports
+        .into_iter()
+        .zip(reachable)
+        .map(|((name, _), functions)| EntryPointSize {
+            name,
+            functions: functions.len(),
+            reachable_size: functions.iter().map(|f| graph.size(*f)).sum(),
+            exclusive_size: functions
+                .iter()
+                .filter(|f| usage[*f] == 1)
+                .map(|f| graph.size(*f))
+                .sum(),
+        })
+        .collect();
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(out)
+}
+
+/// Lists the functions imported by the given Wasm in declaration order and whether the
+/// contract can call them.
+///
+/// An import is used if it can be called from an exported function or the start function,
+/// following the same rules for indirect calls as [`entry_point_sizes`]. Imports that are
+/// declared but never used widen the interface to the host without need.
+pub fn import_usage(wasm_code: &[u8]) -> VmResult<Vec<ImportUsage>> {
+    let module = deserialize_wasm(wasm_code)?;
+    let graph = CallGraph::new(&module)?;
+
+    let mut called = BTreeSet::new();
+    let roots = exported_functions(&module)
+        .into_iter()
+        .map(|(_, index)| index)
+        .chain(module.start_section());
+    for root in roots {
+        called.extend(graph.imports_called_from(root));
+    }
+
+    let imports = module
+        .import_section()
+        .map(|section| section.entries())
+        .unwrap_or_default()
+        .iter()
+        .filter(|entry| matches!(entry.external(), External::Function(_)));
+    Ok(imports
+        .zip(0u32..)
+        .map(|(entry, index)| ImportUsage {
+            module: entry.module().to_string(),
+            name: entry.field().to_string(),
+            used: called.contains(&index),
+        })
+        .collect())
+}
+
+/// The exported functions as (name, function index) pairs
+fn exported_functions(module: &Module) -> Vec<(String, u32)> {
+    module
+        .export_section()
+        .map(|section| {
+            section
+                .entries()
+                .iter()
+                .filter_map(|entry| match entry.internal() {
+                    Internal::Function(index) => Some((entry.field().to_string(), *index)),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+struct CallGraph {
+    /// Number of imported functions. Those come first in the function index space.
+    imported: u32,
+    /// For each defined function the body size and the indices of the functions it calls
+    functions: Vec<(usize, BTreeSet<u32>)>,
+}
+
+impl CallGraph {
+    fn new(module: &Module) -> VmResult<Self> {
+        let imported = module
+            .import_section()
+            .map(|section| {
+                section
+                    .entries()
+                    .iter()
+                    .filter(|entry| matches!(entry.external(), External::Function(_)))
+                    .count() as u32
+            })
+            .unwrap_or_default();
+
+        let table_functions: BTreeSet<u32> = module
+            .elements_section()
+            .map(|section| {
+                section
+                    .entries()
+                    .iter()
+                    .flat_map(|segment| segment.members().iter().copied())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let bodies = module
+            .code_section()
+            .map(|section| section.bodies())
+            .unwrap_or_default();
+        let mut functions = Vec::with_capacity(bodies.len());
+        for body in bodies {
+            let size = parity_wasm::serialize(body.clone())
+                .map_err(|err| {
+                    VmError::static_validation_err(format!(
+                        "Function body could not be serialized: {}",
+                        err
+                    ))
+                })?
+                .len();
+            let mut callees = BTreeSet::new();
+            for instruction in body.code().elements() {
+                match instruction {
+                    Instruction::Call(index) => {
+                        callees.insert(*index);
+                    }
+                    Instruction::CallIndirect(..) => {
+                        callees.extend(&table_functions);
+                    }
+                    _ => {}
+                }
+            }
+            functions.push((size, callees));
+        }
+
+        Ok(CallGraph {
+            imported,
+            functions,
+        })
+    }
+
+    /// The defined function with the given index in the function index space.
+    /// Returns None for imports and invalid indices.
+    fn function(&self, index: u32) -> Option<&(usize, BTreeSet<u32>)> {
+        let defined = index.checked_sub(self.imported)?;
+        self.functions.get(defined as usize)
+    }
+
+    fn size(&self, index: u32) -> usize {
+        self.function(index).map_or(0, |(size, _)| *size)
+    }
+
+    /// All defined functions reachable from the given function, including itself
+    fn reachable_from(&self, root: u32) -> BTreeSet<u32> {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![root];
+        while let Some(index) = stack.pop() {
+            if let Some((_, callees)) = self.function(index) {
+                if visited.insert(index) {
+                    stack.extend(callees.iter().filter(|callee| !visited.contains(*callee)));
+                }
+            }
+        }
+        visited
+    }
+
+    /// The indices of all imported functions that can be called from the given function,
+    /// including the function itself if it is an import
+    fn imports_called_from(&self, root: u32) -> BTreeSet<u32> {
+        let mut out: BTreeSet<u32> = self
+            .reachable_from(root)
+            .iter()
+            .filter_map(|index| self.function(*index))
+            .flat_map(|(_, callees)| callees.iter().copied())
+            .filter(|callee| *callee < self.imported)
+            .collect();
+        if root < self.imported {
+            out.insert(root);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    #[test]
+    fn entry_point_sizes_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+            (import "env" "abort" (func $abort (param i32)))
+            (func $shared (result i32) i32.const 1)
+            (func $heavy (result i32)
+                call $shared
+                i32.const 2
+                i32.add
+                i32.const 3
+                i32.add)
+            (func $query (result i32) call $shared)
+            (func $migrate (result i32) call $heavy)
+            (func $execute (result i32)
+                i32.const 0
+                call $abort
+                call $heavy)
+            (export "query" (func $query))
+            (export "migrate" (func $migrate))
+            (export "execute" (func $execute))
+            (export "abort" (func $abort))
+        )"#,
+        )
+        .unwrap();
+
+        let sizes = entry_point_sizes(&wasm).unwrap();
+        let names: Vec<&str> = sizes.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["abort", "execute", "migrate", "query"]);
+        let [abort, execute, migrate, query] = [&sizes[0], &sizes[1], &sizes[2], &sizes[3]];
+
+        // imports have no code
+        assert_eq!(abort.functions, 0);
+        assert_eq!(abort.reachable_size, 0);
+
+        assert_eq!(query.functions, 2);
+        assert_eq!(migrate.functions, 3);
+        assert_eq!(execute.functions, 3);
+
+        // Only the entry point functions themselves are exclusive. $shared is
+        // reachable from all entry points and $heavy from migrate and execute.
+        let shared_size = query.reachable_size - query.exclusive_size;
+        let heavy_and_shared_size = migrate.reachable_size - migrate.exclusive_size;
+        assert!(shared_size > 0);
+        assert!(heavy_and_shared_size > shared_size);
+        assert_eq!(
+            execute.reachable_size - execute.exclusive_size,
+            heavy_and_shared_size
+        );
+        assert!(query.reachable_size < migrate.reachable_size);
+    }
+
+    #[test]
+    fn entry_point_sizes_handles_indirect_calls() {
+        let wasm = wat::parse_str(
+            r#"(module
+            (type $t (func (result i32)))
+            (table 2 funcref)
+            (elem (i32.const 0) $a $b)
+            (func $a (result i32) i32.const 1)
+            (func $b (result i32) i32.const 2)
+            (func $unused (result i32) i32.const 3)
+            (func $dispatch (param i32) (result i32)
+                local.get 0
+                call_indirect (type $t))
+            (export "dispatch" (func $dispatch))
+        )"#,
+        )
+        .unwrap();
+
+        let sizes = entry_point_sizes(&wasm).unwrap();
+        assert_eq!(sizes.len(), 1);
+        assert_eq!(sizes[0].name, "dispatch");
+        // $dispatch, $a and $b
+        assert_eq!(sizes[0].functions, 3);
+        assert_eq!(sizes[0].exclusive_size, sizes[0].reachable_size);
+    }
+
+    #[test]
+    fn import_usage_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+            (import "env" "db_read" (func $db_read (param i32) (result i32)))
+            (import "env" "db_scan" (func $db_scan (param i32 i32 i32) (result i32)))
+            (import "env" "debug" (func $debug (param i32)))
+            (import "env" "abort" (func $abort (param i32)))
+            (import "env" "addr_validate" (func $addr_validate (param i32) (result i32)))
+            (type $t (func (param i32)))
+            (table 1 funcref)
+            (elem (i32.const 0) $debug)
+            (func $helper (param i32) (result i32)
+                local.get 0
+                call $db_read)
+            (func $unused (param i32) (result i32)
+                (call $db_scan (i32.const 0) (i32.const 0) (local.get 0)))
+            (func $dispatch (param i32)
+                local.get 0
+                i32.const 0
+                call_indirect (type $t))
+            (func $query (param i32) (result i32)
+                local.get 0
+                call $dispatch
+                local.get 0
+                call $helper)
+            (export "query" (func $query))
+            (export "abort" (func $abort))
+        )"#,
+        )
+        .unwrap();
+
+        let usage = import_usage(&wasm).unwrap();
+        let summary: Vec<(&str, &str, bool)> = usage
+            .iter()
+            .map(|u| (u.module.as_str(), u.name.as_str(), u.used))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("env", "db_read", true),
+                // only called from a function that cannot be reached
+                ("env", "db_scan", false),
+                // called indirectly
+                ("env", "debug", true),
+                // exported
+                ("env", "abort", true),
+                ("env", "addr_validate", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_usage_follows_start_function() {
+        let wasm = wat::parse_str(
+            r#"(module
+            (import "env" "debug" (func $debug (param i32)))
+            (func $init
+                i32.const 0
+                call $debug)
+            (start $init)
+        )"#,
+        )
+        .unwrap();
+
+        let usage = import_usage(&wasm).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert!(usage[0].used);
+    }
+
+    #[test]
+    fn import_usage_works_for_contract() {
+        let usage = import_usage(CONTRACT).unwrap();
+        assert!(!usage.is_empty());
+        for import in &usage {
+            assert_eq!(import.module, "env");
+        }
+        let db_read = usage.iter().find(|u| u.name == "db_read").unwrap();
+        assert!(db_read.used);
+    }
+
+    #[test]
+    fn entry_point_sizes_works_for_contract() {
+        let sizes = entry_point_sizes(CONTRACT).unwrap();
+        let names: Vec<&str> = sizes.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "allocate",
+                "deallocate",
+                "execute",
+                "instantiate",
+                "interface_version_8",
+                "migrate",
+                "query",
+                "sudo"
+            ]
+        );
+        for size in sizes {
+            assert!(size.functions > 0);
+            assert!(size.exclusive_size <= size.reachable_size);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Reporting of cache and execution metrics through the [metrics](https://docs.rs/metrics) facade.
+//!
+//! This is only active when the `metrics` feature is enabled. Otherwise all functions are no-ops.
+//! The node is responsible for installing a recorder, e.g. a Prometheus exporter.
+//! Without a recorder, metrics are discarded.
+
+use std::time::Duration;
+
+/// Where a module was found when getting an instance from the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CacheSource {
+    PinnedMemory,
+    Memory,
+    FileSystem,
+}
+
+impl CacheSource {
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    fn as_str(self) -> &'static str {
+        match self {
+            CacheSource::PinnedMemory => "pinned_memory",
+            CacheSource::Memory => "memory",
+            CacheSource::FileSystem => "file_system",
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_cache_hit(source: CacheSource) {
+    #[cfg(feature = "metrics")]
+    metrics::increment_counter!("cosmwasm_vm_cache_hits_total", "cache" => source.as_str());
+}
+
+pub(crate) fn record_cache_miss() {
+    #[cfg(feature = "metrics")]
+    metrics::increment_counter!("cosmwasm_vm_cache_misses_total");
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_compile_time(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("cosmwasm_vm_compile_duration_seconds", duration);
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_instantiation_time(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("cosmwasm_vm_instantiation_duration_seconds", duration);
+}
+
+/// Records the gas used by a single call of an export (e.g. "instantiate" or "execute")
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_gas_used(entry_point: &str, gas_used: u64) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(
+        "cosmwasm_vm_gas_used",
+        gas_used as f64,
+        "entry_point" => entry_point.to_string()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_source_as_str_works() {
+        assert_eq!(CacheSource::PinnedMemory.as_str(), "pinned_memory");
+        assert_eq!(CacheSource::Memory.as_str(), "memory");
+        assert_eq!(CacheSource::FileSystem.as_str(), "file_system");
+    }
+
+    #[test]
+    fn recording_without_recorder_works() {
+        record_cache_hit(CacheSource::Memory);
+        record_cache_miss();
+        record_compile_time(Duration::from_millis(12));
+        record_instantiation_time(Duration::from_micros(340));
+        record_gas_used("execute", 123456);
+    }
+}
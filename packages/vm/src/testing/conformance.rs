@@ -0,0 +1,211 @@
+use cosmwasm_std::{MemoryStorage, Order, Record, Storage as StdStorage};
+
+use crate::{BackendError, Storage};
+
+/// The data every check starts with. Keys are chosen such that bytewise and
+/// other orderings (e.g. case insensitive or by length) differ.
+const DATA: &[(&[u8], &[u8])] = &[
+    (b"\x00", b"zero"),
+    (b"B", b"capital b"),
+    (b"a", b"a"),
+    (b"a\x00", b"a zero"),
+    (b"ab", b"ab"),
+    (b"b", b"b"),
+    (b"\xff", b"ff"),
+    (b"\xff\xff", b"ff ff"),
+];
+
+/// The (start, end) bounds of a range
+type Bounds = (Option<&'static [u8]>, Option<&'static [u8]>);
+
+/// The bounds checked in both orders
+const BOUNDS: &[Bounds] = &[
+    (None, None),
+    // the empty key is the smallest key
+    (Some(b""), None),
+    (None, Some(b"")),
+    (Some(b"a"), None),
+    (None, Some(b"b")),
+    (Some(b"a"), Some(b"b")),
+    (Some(b"a\x00"), Some(b"ab")),
+    (Some(b"A"), Some(b"a")),
+    (Some(b"\xff"), None),
+    (Some(b"\xff\xff\xff"), None),
+    // no keys in range
+    (Some(b"c"), Some(b"d")),
+    // start == end
+    (Some(b"a"), Some(b"a")),
+    // start > end
+    (Some(b"b"), Some(b"a")),
+];
+
+/// Checks that the iterator implementation of a [`Storage`] behaves like the
+/// `MemoryStorage` of cosmwasm-std, which contracts are unit tested with.
+///
+/// This is meant for implementers of backends, who can call it in their tests
+/// with a constructor of an empty storage. It panics with a description of the
+/// first mismatch found. The following behaviour is checked:
+///
+/// - keys are ordered bytewise
+/// - `start` is inclusive and `end` is exclusive
+/// - a range with `start` greater than or equal to `end` is empty, no matter the order
+/// - the empty key as `start` is the same as no `start`
+/// - removed keys are not returned
+/// - iterators are independent of each other and return `None` once exhausted
+/// - iterator IDs are deterministic
+/// - using an unknown iterator ID fails with [`BackendError::IteratorDoesNotExist`]
+///
+/// # Examples
+///
+/// ```
+/// use cosmwasm_vm::testing::{check_storage_conformance, MockStorage};
+///
+/// check_storage_conformance(MockStorage::new);
+/// ```
+pub fn check_storage_conformance<S: Storage>(mut new_storage: impl FnMut() -> S) {
+    let mut reference = MemoryStorage::new();
+    let mut storage = new_storage();
+    for (key, value) in DATA {
+        reference.set(key, value);
+        storage.set(key, value).0.unwrap();
+    }
+    // removed keys must not show up
+    reference.set(b"removed", b"removed");
+    reference.remove(b"removed");
+    storage.set(b"removed", b"removed").0.unwrap();
+    storage.remove(b"removed").0.unwrap();
+
+    for &(start, end) in BOUNDS {
+        for order in [Order::Ascending, Order::Descending] {
+            let expected: Vec<Record> = reference.range(start, end, order).collect();
+            let actual = collect(&mut storage, start, end, order);
+            assert_eq!(
+                actual, expected,
+                "Range mismatch for start {:?}, end {:?}, order {:?}",
+                start, end, order
+            );
+        }
+    }
+
+    // interleaved iterators
+    let first = storage.scan(None, None, Order::Ascending).0.unwrap();
+    let second = storage.scan(None, None, Order::Descending).0.unwrap();
+    assert_ne!(first, second, "Iterator IDs must be unique");
+    let (first_key, _) = storage.next(first).0.unwrap().unwrap();
+    let (second_key, _) = storage.next(second).0.unwrap().unwrap();
+    let (first_key2, _) = storage.next(first).0.unwrap().unwrap();
+    assert_eq!(
+        first_key, DATA[0].0,
+        "Interleaved iterators must be independent"
+    );
+    assert_eq!(
+        first_key2, DATA[1].0,
+        "Interleaved iterators must be independent"
+    );
+    assert_eq!(
+        second_key,
+        DATA[DATA.len() - 1].0,
+        "Interleaved iterators must be independent"
+    );
+
+    // exhausted iterators
+    let empty = storage
+        .scan(Some(b"c"), Some(b"d"), Order::Ascending)
+        .0
+        .unwrap();
+    for _ in 0..2 {
+        assert_eq!(
+            storage.next(empty).0.unwrap(),
+            None,
+            "Exhausted iterators must keep returning None"
+        );
+    }
+
+    // unknown iterator ID
+    let unknown = first.max(second).max(empty) + 1;
+    match storage.next(unknown).0.unwrap_err() {
+        BackendError::IteratorDoesNotExist { id } => assert_eq!(id, unknown),
+        err => panic!("Unexpected error for unknown iterator ID: {:?}", err),
+    }
+
+    // deterministic iterator IDs
+    let ids = |storage: &mut S| -> Vec<u32> {
+        (0..3)
+            .map(|_| storage.scan(None, None, Order::Ascending).0.unwrap())
+            .collect()
+    };
+    assert_eq!(
+        ids(&mut new_storage()),
+        ids(&mut new_storage()),
+        "Iterator IDs must be deterministic"
+    );
+}
+
+fn collect<S: Storage>(
+    storage: &mut S,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Vec<Record> {
+    let id = storage.scan(start, end, order).0.unwrap();
+    let mut out = Vec::new();
+    while let Some(record) = storage.next(id).0.unwrap() {
+        out.push(record);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockStorage, RecordingStorage};
+    use crate::BackendResult;
+
+    #[test]
+    fn mock_storage_conforms() {
+        check_storage_conformance(MockStorage::new);
+        check_storage_conformance(|| RecordingStorage::new(MockStorage::new()));
+    }
+
+    /// Treats the end bound as inclusive
+    #[derive(Default)]
+    struct InclusiveEnd(MockStorage);
+
+    impl Storage for InclusiveEnd {
+        fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+            self.0.get(key)
+        }
+
+        fn scan(
+            &mut self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> BackendResult<u32> {
+            let end = end.map(|end| {
+                let mut end = end.to_vec();
+                end.push(0);
+                end
+            });
+            self.0.scan(start, end.as_deref(), order)
+        }
+
+        fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+            self.0.next(iterator_id)
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+            self.0.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+            self.0.remove(key)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Range mismatch for start None, end Some([98]), order Ascending")]
+    fn check_storage_conformance_detects_mismatch() {
+        check_storage_conformance(InclusiveEnd::default);
+    }
+}
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 #[cfg(feature = "iterator")]
 use std::ops::{Bound, RangeBounds};
 
+use cosmwasm_std::testing::StorageMutation;
 #[cfg(feature = "iterator")]
 use cosmwasm_std::{Order, Record};
 
@@ -137,6 +138,84 @@ impl Storage for MockStorage {
     }
 }
 
+/// Records the writes a compiled contract makes through the VM's [`Storage`] trait.
+///
+/// This is the integration test counterpart of `cosmwasm_std::testing::RecordingStorage`.
+/// The log uses the same [`StorageMutation`] type, re-exported from cosmwasm-std, so logs
+/// from unit tests and from the running Wasm can be compared directly.
+///
+/// Every call is forwarded to the inner storage, and its [`BackendResult`] is returned
+/// unchanged, including the [`GasInfo`]. Wrapping a storage therefore does not change the
+/// gas a contract is charged. A mutation is logged before it is forwarded, so writes the
+/// inner storage fails are in the log as well. Reads, scans and iterator steps are not.
+///
+/// Use it as the storage of a [`Backend`](crate::Backend) and get the log after a call via
+/// `instance.with_storage(|storage| ...)`.
+#[derive(Default, Debug)]
+pub struct RecordingStorage<S: Storage = MockStorage> {
+    inner: S,
+    mutations: Vec<StorageMutation>,
+}
+
+impl<S: Storage> RecordingStorage<S> {
+    pub fn new(inner: S) -> Self {
+        RecordingStorage {
+            inner,
+            mutations: Vec::new(),
+        }
+    }
+
+    /// Returns all mutations recorded so far, oldest first
+    pub fn mutations(&self) -> &[StorageMutation] {
+        &self.mutations
+    }
+
+    /// Returns all mutations recorded so far and clears the log.
+    /// Calling this after each call to the instance splits the log by call.
+    pub fn take_mutations(&mut self) -> Vec<StorageMutation> {
+        std::mem::take(&mut self.mutations)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Storage> Storage for RecordingStorage<S> {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        self.inner.get(key)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        self.inner.scan(start, end, order)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        self.inner.next(iterator_id)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        self.mutations.push(StorageMutation::Set {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+        self.inner.set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        self.mutations
+            .push(StorageMutation::Remove { key: key.to_vec() });
+        self.inner.remove(key)
+    }
+}
+
 #[cfg(feature = "iterator")]
 fn range_bounds(start: Option<&[u8]>, end: Option<&[u8]>) -> impl RangeBounds<Vec<u8>> {
     (
@@ -180,6 +259,27 @@ mod tests {
         assert_eq!(Some(b"bank".to_vec()), store.get(b"food").0.unwrap());
     }
 
+    #[test]
+    fn recording_storage_works() {
+        let mut store = RecordingStorage::new(MockStorage::new());
+        store.set(b"foo", b"bar").0.unwrap();
+        store.remove(b"foo").0.unwrap();
+        assert_eq!(None, store.get(b"foo").0.unwrap());
+        assert_eq!(
+            store.take_mutations(),
+            vec![
+                StorageMutation::Set {
+                    key: b"foo".to_vec(),
+                    value: b"bar".to_vec()
+                },
+                StorageMutation::Remove {
+                    key: b"foo".to_vec()
+                },
+            ]
+        );
+        assert_eq!(store.mutations(), []);
+    }
+
     #[test]
     #[cfg(feature = "iterator")]
     fn iterator() {
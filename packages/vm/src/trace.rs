@@ -0,0 +1,676 @@
+//! Recording of all host calls of a contract call and replaying them off-chain.
+//!
+//! A node wraps the storage and querier of a call with [`CallTraceStorage`] and
+//! [`CallTraceQuerier`] (see [`call_trace_backend`]) and runs the call through [`record_call`].
+//! The resulting [`CallTrace`] is serializable and contains the call's arguments, the gas limit,
+//! every storage access and query with its response, and the result. [`replay_trace`] runs the
+//! call again against the recorded responses, e.g. with a debug build of the contract, without
+//! access to the chain state.
+//!
+//! Address conversions are pure functions of the chain's address format and are not recorded.
+//! `replay_trace` uses the given [`BackendApi`] for them instead.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, ContractResult, SystemResult};
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Record};
+
+use crate::backend::{Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage};
+use crate::calls::{
+    call_execute_raw, call_instantiate_raw, call_migrate_raw, call_query_raw, call_reply_raw,
+    call_sudo_raw,
+};
+#[cfg(feature = "stargate")]
+use crate::calls::{
+    call_ibc_channel_close_raw, call_ibc_channel_connect_raw, call_ibc_channel_open_raw,
+    call_ibc_packet_ack_raw, call_ibc_packet_receive_raw, call_ibc_packet_timeout_raw,
+};
+use crate::errors::{VmError, VmResult};
+use crate::instance::{Instance, InstanceOptions};
+
+/// A single call from the contract into the host together with the host's response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HostCall {
+    StorageGet {
+        key: Binary,
+        result: Result<Option<Binary>, BackendError>,
+        gas: GasInfo,
+    },
+    StorageSet {
+        key: Binary,
+        value: Binary,
+        result: Result<(), BackendError>,
+        gas: GasInfo,
+    },
+    StorageRemove {
+        key: Binary,
+        result: Result<(), BackendError>,
+        gas: GasInfo,
+    },
+    #[cfg(feature = "iterator")]
+    StorageScan {
+        start: Option<Binary>,
+        end: Option<Binary>,
+        /// The [`Order`] as integer
+        order: i32,
+        result: Result<u32, BackendError>,
+        gas: GasInfo,
+    },
+    #[cfg(feature = "iterator")]
+    StorageNext {
+        iterator_id: u32,
+        result: Result<Option<(Binary, Binary)>, BackendError>,
+        gas: GasInfo,
+    },
+    Query {
+        request: Binary,
+        gas_limit: u64,
+        result: Result<SystemResult<ContractResult<Binary>>, BackendError>,
+        gas: GasInfo,
+    },
+}
+
+impl HostCall {
+    /// The call without the host's response, used to check that a replay makes the same calls.
+    /// The gas limit of queries depends on the gas used before and is ignored, such that
+    /// a different build of the contract can be replayed.
+    fn request(&self) -> HostCall {
+        let mut request = self.clone();
+        match &mut request {
+            HostCall::StorageGet { result, gas, .. } => {
+                *result = Ok(None);
+                *gas = GasInfo::free();
+            }
+            HostCall::StorageSet { result, gas, .. }
+            | HostCall::StorageRemove { result, gas, .. } => {
+                *result = Ok(());
+                *gas = GasInfo::free();
+            }
+            #[cfg(feature = "iterator")]
+            HostCall::StorageScan { result, gas, .. } => {
+                *result = Ok(0);
+                *gas = GasInfo::free();
+            }
+            #[cfg(feature = "iterator")]
+            HostCall::StorageNext { result, gas, .. } => {
+                *result = Ok(None);
+                *gas = GasInfo::free();
+            }
+            HostCall::Query {
+                gas_limit,
+                result,
+                gas,
+                ..
+            } => {
+                *gas_limit = 0;
+                *result = Err(BackendError::unknown("not executed"));
+                *gas = GasInfo::free();
+            }
+        }
+        request
+    }
+}
+
+/// Everything needed to replay a single call into a contract export
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CallTrace {
+    /// The name of the export, e.g. "execute"
+    pub entry_point: String,
+    /// The raw arguments of the export, e.g. env, info and msg for "execute"
+    pub args: Vec<Binary>,
+    /// The gas left when the call started
+    pub gas_limit: u64,
+    /// The host calls in the order they were made
+    pub host_calls: Vec<HostCall>,
+    /// The raw result of the export or the error message of the VM
+    pub result: Result<Binary, String>,
+}
+
+/// The host calls recorded by a [`CallTraceStorage`] and [`CallTraceQuerier`] pair,
+/// which share one log to keep the calls in order
+#[derive(Clone, Debug, Default)]
+pub struct HostCallLog(Rc<RefCell<Vec<HostCall>>>);
+
+impl HostCallLog {
+    pub fn new() -> Self {
+        HostCallLog::default()
+    }
+
+    /// Returns all host calls recorded so far and clears the log
+    pub fn take(&self) -> Vec<HostCall> {
+        std::mem::take(&mut self.0.borrow_mut())
+    }
+
+    fn push(&self, call: HostCall) {
+        self.0.borrow_mut().push(call);
+    }
+}
+
+/// A storage that records all calls into a [`HostCallLog`]
+///
+/// In contrast to `cosmwasm_std::testing::RecordingStorage`, which wraps a contract's storage
+/// in unit tests and records writes only, this wraps the storage of the VM's backend. It
+/// records reads and iterations as well, together with the results and gas used, so that
+/// [`replay_trace`] can answer every host call of the recorded call.
+pub struct CallTraceStorage<S: Storage> {
+    inner: S,
+    log: HostCallLog,
+}
+
+impl<S: Storage> CallTraceStorage<S> {
+    pub fn new(inner: S, log: HostCallLog) -> Self {
+        CallTraceStorage { inner, log }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Storage> Storage for CallTraceStorage<S> {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        let (result, gas) = self.inner.get(key);
+        self.log.push(HostCall::StorageGet {
+            key: key.into(),
+            result: result.clone().map(|value| value.map(Binary)),
+            gas,
+        });
+        (result, gas)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        let (result, gas) = self.inner.scan(start, end, order);
+        self.log.push(HostCall::StorageScan {
+            start: start.map(Binary::from),
+            end: end.map(Binary::from),
+            order: order.into(),
+            result: result.clone(),
+            gas,
+        });
+        (result, gas)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let (result, gas) = self.inner.next(iterator_id);
+        self.log.push(HostCall::StorageNext {
+            iterator_id,
+            result: result
+                .clone()
+                .map(|record| record.map(|(key, value)| (Binary(key), Binary(value)))),
+            gas,
+        });
+        (result, gas)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        let (result, gas) = self.inner.set(key, value);
+        self.log.push(HostCall::StorageSet {
+            key: key.into(),
+            value: value.into(),
+            result: result.clone(),
+            gas,
+        });
+        (result, gas)
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        let (result, gas) = self.inner.remove(key);
+        self.log.push(HostCall::StorageRemove {
+            key: key.into(),
+            result: result.clone(),
+            gas,
+        });
+        (result, gas)
+    }
+}
+
+/// A querier that records all queries into a [`HostCallLog`]
+pub struct CallTraceQuerier<Q: Querier> {
+    inner: Q,
+    log: HostCallLog,
+}
+
+impl<Q: Querier> CallTraceQuerier<Q> {
+    pub fn new(inner: Q, log: HostCallLog) -> Self {
+        CallTraceQuerier { inner, log }
+    }
+
+    pub fn into_inner(self) -> Q {
+        self.inner
+    }
+}
+
+impl<Q: Querier> Querier for CallTraceQuerier<Q> {
+    fn query_raw(
+        &self,
+        request: &[u8],
+        gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+        let (result, gas) = self.inner.query_raw(request, gas_limit);
+        self.log.push(HostCall::Query {
+            request: request.into(),
+            gas_limit,
+            result: result.clone(),
+            gas,
+        });
+        (result, gas)
+    }
+}
+
+/// Wraps the storage and querier of a backend such that all their calls are recorded
+/// into the returned log
+pub fn call_trace_backend<A: BackendApi, S: Storage, Q: Querier>(
+    backend: Backend<A, S, Q>,
+) -> (
+    Backend<A, CallTraceStorage<S>, CallTraceQuerier<Q>>,
+    HostCallLog,
+) {
+    let log = HostCallLog::new();
+    let backend = Backend {
+        api: backend.api,
+        storage: CallTraceStorage::new(backend.storage, log.clone()),
+        querier: CallTraceQuerier::new(backend.querier, log.clone()),
+    };
+    (backend, log)
+}
+
+/// Calls the given export with the raw arguments like the `call_*_raw` functions do and
+/// returns the trace of the call.
+///
+/// `log` must be the log of the instance's [`CallTraceStorage`] and [`CallTraceQuerier`].
+/// Calls recorded before are discarded. Errors of the call end up in [`CallTrace::result`].
+pub fn record_call<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    log: &HostCallLog,
+    entry_point: &str,
+    args: &[&[u8]],
+) -> CallTrace
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    log.take();
+    let gas_limit = instance.get_gas_left();
+    let result = call_entry_point_raw(instance, entry_point, args);
+    CallTrace {
+        entry_point: entry_point.to_string(),
+        args: args.iter().map(|arg| Binary::from(*arg)).collect(),
+        gas_limit,
+        host_calls: log.take(),
+        result: result.map(Binary).map_err(|err| err.to_string()),
+    }
+}
+
+/// Runs the traced call again in a new instance of `code`, answering all storage accesses
+/// and queries with the recorded responses and gas costs.
+///
+/// Returns the raw result of the export, which can be compared with [`CallTrace::result`].
+/// If the contract makes a host call that differs from the recorded one, e.g. because `code`
+/// is not the traced contract, an error describing the first difference is returned.
+pub fn replay_trace<A>(
+    code: &[u8],
+    api: A,
+    trace: &CallTrace,
+    print_debug: bool,
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+{
+    let replay = Rc::new(RefCell::new(Replay {
+        host_calls: trace.host_calls.clone(),
+        position: 0,
+        mismatch: None,
+    }));
+    let backend = Backend {
+        api,
+        storage: ReplayStorage(replay.clone()),
+        querier: ReplayQuerier(replay.clone()),
+    };
+    let options = InstanceOptions {
+        gas_limit: trace.gas_limit,
+        print_debug,
+    };
+    let mut instance = Instance::from_code(code, backend, options, None)?;
+    let args: Vec<&[u8]> = trace.args.iter().map(|arg| arg.as_slice()).collect();
+    let result = call_entry_point_raw(&mut instance, &trace.entry_point, &args);
+
+    let replay = replay.borrow();
+    if let Some(mismatch) = &replay.mismatch {
+        return Err(VmError::generic_err(mismatch.clone()));
+    }
+    let result = result?;
+    if replay.position != replay.host_calls.len() {
+        return Err(VmError::generic_err(format!(
+            "Trace mismatch: the replay made {} of {} recorded host calls",
+            replay.position,
+            replay.host_calls.len()
+        )));
+    }
+    Ok(result)
+}
+
+fn call_entry_point_raw<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    entry_point: &str,
+    args: &[&[u8]],
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    match (entry_point, args) {
+        ("instantiate", [env, info, msg]) => call_instantiate_raw(instance, env, info, msg),
+        ("execute", [env, info, msg]) => call_execute_raw(instance, env, info, msg),
+        ("migrate", [env, msg]) => call_migrate_raw(instance, env, msg),
+        ("sudo", [env, msg]) => call_sudo_raw(instance, env, msg),
+        ("reply", [env, msg]) => call_reply_raw(instance, env, msg),
+        ("query", [env, msg]) => call_query_raw(instance, env, msg),
+        #[cfg(feature = "stargate")]
+        ("ibc_channel_open", [env, msg]) => call_ibc_channel_open_raw(instance, env, msg),
+        #[cfg(feature = "stargate")]
+        ("ibc_channel_connect", [env, msg]) => call_ibc_channel_connect_raw(instance, env, msg),
+        #[cfg(feature = "stargate")]
+        ("ibc_channel_close", [env, msg]) => call_ibc_channel_close_raw(instance, env, msg),
+        #[cfg(feature = "stargate")]
+        ("ibc_packet_receive", [env, msg]) => call_ibc_packet_receive_raw(instance, env, msg),
+        #[cfg(feature = "stargate")]
+        ("ibc_packet_ack", [env, msg]) => call_ibc_packet_ack_raw(instance, env, msg),
+        #[cfg(feature = "stargate")]
+        ("ibc_packet_timeout", [env, msg]) => call_ibc_packet_timeout_raw(instance, env, msg),
+        _ => Err(VmError::generic_err(format!(
+            "Cannot call entry point {} with {} arguments",
+            entry_point,
+            args.len()
+        ))),
+    }
+}
+
+struct Replay {
+    host_calls: Vec<HostCall>,
+    position: usize,
+    /// The first difference between the replay and the trace
+    mismatch: Option<String>,
+}
+
+impl Replay {
+    /// Returns the recorded call if it matches the given request
+    fn next(&mut self, request: HostCall) -> Result<HostCall, BackendError> {
+        if let Some(mismatch) = &self.mismatch {
+            return Err(BackendError::unknown(mismatch.clone()));
+        }
+        let recorded = match self.host_calls.get(self.position) {
+            Some(recorded) if recorded.request() == request => recorded.clone(),
+            recorded => {
+                let mismatch = format!(
+                    "Trace mismatch at host call {}: recorded {:?}, replayed {:?}",
+                    self.position, recorded, request
+                );
+                self.mismatch = Some(mismatch.clone());
+                return Err(BackendError::unknown(mismatch));
+            }
+        };
+        self.position += 1;
+        Ok(recorded)
+    }
+}
+
+struct ReplayStorage(Rc<RefCell<Replay>>);
+
+impl ReplayStorage {
+    fn next(&self, request: HostCall) -> Result<HostCall, BackendError> {
+        self.0.borrow_mut().next(request.request())
+    }
+}
+
+impl Storage for ReplayStorage {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        let request = HostCall::StorageGet {
+            key: key.into(),
+            result: Ok(None),
+            gas: GasInfo::free(),
+        };
+        match self.next(request) {
+            Ok(HostCall::StorageGet { result, gas, .. }) => {
+                (result.map(|value| value.map(|value| value.0)), gas)
+            }
+            Ok(_) => unreachable!("request was matched"),
+            Err(err) => (Err(err), GasInfo::free()),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        let request = HostCall::StorageScan {
+            start: start.map(Binary::from),
+            end: end.map(Binary::from),
+            order: order.into(),
+            result: Ok(0),
+            gas: GasInfo::free(),
+        };
+        match ReplayStorage::next(self, request) {
+            Ok(HostCall::StorageScan { result, gas, .. }) => (result, gas),
+            Ok(_) => unreachable!("request was matched"),
+            Err(err) => (Err(err), GasInfo::free()),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let request = HostCall::StorageNext {
+            iterator_id,
+            result: Ok(None),
+            gas: GasInfo::free(),
+        };
+        match ReplayStorage::next(self, request) {
+            Ok(HostCall::StorageNext { result, gas, .. }) => (
+                result.map(|record| record.map(|(key, value)| (key.0, value.0))),
+                gas,
+            ),
+            Ok(_) => unreachable!("request was matched"),
+            Err(err) => (Err(err), GasInfo::free()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        let request = HostCall::StorageSet {
+            key: key.into(),
+            value: value.into(),
+            result: Ok(()),
+            gas: GasInfo::free(),
+        };
+        match ReplayStorage::next(self, request) {
+            Ok(HostCall::StorageSet { result, gas, .. }) => (result, gas),
+            Ok(_) => unreachable!("request was matched"),
+            Err(err) => (Err(err), GasInfo::free()),
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        let request = HostCall::StorageRemove {
+            key: key.into(),
+            result: Ok(()),
+            gas: GasInfo::free(),
+        };
+        match ReplayStorage::next(self, request) {
+            Ok(HostCall::StorageRemove { result, gas, .. }) => (result, gas),
+            Ok(_) => unreachable!("request was matched"),
+            Err(err) => (Err(err), GasInfo::free()),
+        }
+    }
+}
+
+struct ReplayQuerier(Rc<RefCell<Replay>>);
+
+impl Querier for ReplayQuerier {
+    fn query_raw(
+        &self,
+        request: &[u8],
+        gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+        let request = HostCall::Query {
+            request: request.into(),
+            gas_limit,
+            result: Err(BackendError::unknown("not executed")),
+            gas: GasInfo::free(),
+        };
+        let next = self.0.borrow_mut().next(request.request());
+        match next {
+            Ok(HostCall::Query { result, gas, .. }) => (result, gas),
+            Ok(_) => unreachable!("request was matched"),
+            Err(err) => (Err(err), GasInfo::free()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        mock_backend, mock_env, mock_info, mock_instance_options, MockApi, MockStorage,
+    };
+    use crate::to_vec;
+    use cosmwasm_std::coins;
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    /// Instantiates hackatom and returns the storage and the trace of a release, which
+    /// reads the config, queries the balance and stores nothing
+    fn traced_release() -> (MockStorage, CallTrace) {
+        let (options, memory_limit) = mock_instance_options();
+        let (backend, log) = call_trace_backend(mock_backend(&coins(500, "earth")));
+        let mut instance = Instance::from_code(CONTRACT, backend, options, memory_limit).unwrap();
+
+        let env = to_vec(&mock_env()).unwrap();
+        let info = to_vec(&mock_info("creator", &[])).unwrap();
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        let instantiate = record_call(&mut instance, &log, "instantiate", &[&env, &info, msg]);
+        assert!(matches!(
+            instantiate.host_calls.as_slice(),
+            [HostCall::StorageSet { .. }]
+        ));
+
+        let info = to_vec(&mock_info("verifies", &[])).unwrap();
+        let trace = record_call(
+            &mut instance,
+            &log,
+            "execute",
+            &[&env, &info, br#"{"release":{}}"#],
+        );
+        let storage = instance.recycle().unwrap().storage.into_inner();
+        (storage, trace)
+    }
+
+    #[test]
+    fn record_call_works() {
+        let (_, trace) = traced_release();
+        assert_eq!(trace.entry_point, "execute");
+        assert_eq!(trace.args.len(), 3);
+        assert!(matches!(
+            trace.host_calls.as_slice(),
+            [
+                HostCall::StorageGet {
+                    result: Ok(Some(_)),
+                    ..
+                },
+                HostCall::Query { .. }
+            ]
+        ));
+        let result = String::from_utf8(trace.result.clone().unwrap().0).unwrap();
+        assert!(result.contains("benefits"), "{}", result);
+    }
+
+    #[test]
+    fn record_call_records_errors() {
+        let (options, memory_limit) = mock_instance_options();
+        let (backend, log) = call_trace_backend(mock_backend(&[]));
+        let mut instance = Instance::from_code(CONTRACT, backend, options, memory_limit).unwrap();
+
+        let trace = record_call(&mut instance, &log, "execute", &[b"{}"]);
+        assert_eq!(
+            trace.result,
+            Err("Generic error: Cannot call entry point execute with 1 arguments".to_string())
+        );
+        assert_eq!(trace.host_calls, []);
+    }
+
+    #[test]
+    fn trace_is_serializable() {
+        let (_, trace) = traced_release();
+        let json = serde_json::to_vec(&trace).unwrap();
+        let restored: CallTrace = serde_json::from_slice(&json).unwrap();
+        assert_eq!(restored, trace);
+    }
+
+    #[test]
+    fn replay_trace_works() {
+        let (_, trace) = traced_release();
+        let result = replay_trace(CONTRACT, MockApi::default(), &trace, false).unwrap();
+        assert_eq!(Ok(Binary(result)), trace.result);
+    }
+
+    #[test]
+    fn replay_trace_uses_recorded_responses() {
+        let (_, mut trace) = traced_release();
+        // a different balance on chain changes the result of the replay
+        match &mut trace.host_calls[1] {
+            HostCall::Query { result, .. } => {
+                let response = br#"{"amount":[{"denom":"earth","amount":"7"}]}"#;
+                *result = Ok(SystemResult::Ok(ContractResult::Ok(Binary::from(
+                    &response[..],
+                ))));
+            }
+            other => panic!("unexpected host call {:?}", other),
+        }
+        let result = replay_trace(CONTRACT, MockApi::default(), &trace, false).unwrap();
+        let result = String::from_utf8(result).unwrap();
+        assert!(result.contains(r#""amount":"7""#), "{}", result);
+    }
+
+    #[test]
+    fn replay_trace_detects_mismatches() {
+        let (_, mut trace) = traced_release();
+        match &mut trace.host_calls[0] {
+            HostCall::StorageGet { key, .. } => *key = Binary::from(b"other key"),
+            other => panic!("unexpected host call {:?}", other),
+        }
+        let err = replay_trace(CONTRACT, MockApi::default(), &trace, false).unwrap_err();
+        assert!(
+            err.to_string().contains("Trace mismatch at host call 0"),
+            "{}",
+            err
+        );
+
+        let (_, mut trace) = traced_release();
+        trace.host_calls.push(HostCall::StorageRemove {
+            key: Binary::from(b"foo"),
+            result: Ok(()),
+            gas: GasInfo::free(),
+        });
+        let err = replay_trace(CONTRACT, MockApi::default(), &trace, false).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("the replay made 2 of 3 recorded host calls"),
+            "{}",
+            err
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use wasmer::{Module, ModuleMiddleware};
 
 use crate::errors::VmResult;
+use crate::proposals::WasmProposals;
 use crate::size::Size;
+use crate::telemetry;
 
 use super::store::make_compile_time_store;
 
@@ -16,8 +19,21 @@ pub fn compile(
     memory_limit: Option<Size>,
     middlewares: &[Arc<dyn ModuleMiddleware>],
 ) -> VmResult<Module> {
-    let store = make_compile_time_store(memory_limit, middlewares);
+    compile_with_proposals(code, memory_limit, middlewares, &WasmProposals::default())
+}
+
+/// Compiles a given Wasm bytecode like [`compile`], allowing the operators of the
+/// given proposals.
+pub fn compile_with_proposals(
+    code: &[u8],
+    memory_limit: Option<Size>,
+    middlewares: &[Arc<dyn ModuleMiddleware>],
+    proposals: &WasmProposals,
+) -> VmResult<Module> {
+    let start = Instant::now();
+    let store = make_compile_time_store(memory_limit, middlewares, proposals);
     let module = Module::new(&store, code)?;
+    telemetry::record_compile_time(start.elapsed());
     Ok(module)
 }
 
@@ -32,4 +48,30 @@ mod tests {
         let err = compile(CONTRACT, None, &[]).unwrap_err();
         assert!(err.to_string().contains("Float operator detected:"));
     }
+
+    #[test]
+    fn compile_with_proposals_allows_bulk_memory() {
+        let wasm = wat::parse_str(
+            "(module (memory 1) (func (memory.fill (i32.const 0) (i32.const 0) (i32.const 0))))",
+        )
+        .unwrap();
+        let err = compile(&wasm, None, &[]).unwrap_err();
+        assert!(err.to_string().contains("Bulk memory operation detected:"));
+
+        let proposals = WasmProposals {
+            bulk_memory: true,
+            ..WasmProposals::default()
+        };
+        compile_with_proposals(&wasm, None, &[], &proposals).unwrap();
+    }
+
+    #[test]
+    fn compile_rejects_memory64() {
+        let wasm = wat::parse_str("(module (memory i64 1))").unwrap();
+        let err = compile(&wasm, None, &[]).unwrap_err();
+        // check_wasm accepts them with the memory64 capability, but the engine cannot compile them yet
+        assert!(err
+            .to_string()
+            .contains("memory64 must be enabled for 64-bit memories"));
+    }
 }
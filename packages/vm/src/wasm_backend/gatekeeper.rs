@@ -5,6 +5,8 @@ use wasmer::{
     ModuleMiddleware,
 };
 
+use crate::proposals::WasmProposals;
+
 #[derive(Debug, MemoryUsage, Clone, Copy)]
 struct GatekeeperConfig {
     /// True iff float operations are allowed.
@@ -53,6 +55,14 @@ impl Gatekeeper {
     fn new(config: GatekeeperConfig) -> Self {
         Self { config }
     }
+
+    /// Creates a Gatekeeper that also allows the operators of the given proposals.
+    /// Sign-extension operators are always allowed here, since `check_wasm` checks for them.
+    pub fn with_proposals(proposals: &WasmProposals) -> Self {
+        let mut gatekeeper = Self::default();
+        gatekeeper.config.allow_feature_bulk_memory_operations = proposals.bulk_memory;
+        gatekeeper
+    }
 }
 
 impl Default for Gatekeeper {
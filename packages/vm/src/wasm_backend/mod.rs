@@ -3,6 +3,6 @@ mod gatekeeper;
 mod limiting_tunables;
 mod store;
 
-pub use compile::compile;
+pub use compile::{compile, compile_with_proposals};
 pub use limiting_tunables::LimitingTunables;
 pub use store::make_runtime_store;
@@ -9,6 +9,7 @@ use wasmer::{
 };
 use wasmer_middlewares::Metering;
 
+use crate::proposals::WasmProposals;
 use crate::size::Size;
 
 use super::gatekeeper::Gatekeeper;
@@ -31,13 +32,14 @@ fn cost(_operator: &Operator) -> u64 {
 }
 
 /// Created a store with the default compiler and the given memory limit (in bytes).
-/// If memory_limit is None, no limit is applied.
+/// If memory_limit is None, no limit is applied. Operators of the given proposals are allowed.
 pub fn make_compile_time_store(
     memory_limit: Option<Size>,
     middlewares: &[Arc<dyn ModuleMiddleware>],
+    proposals: &WasmProposals,
 ) -> Store {
     let gas_limit = 0;
-    let deterministic = Arc::new(Gatekeeper::default());
+    let deterministic = Arc::new(Gatekeeper::with_proposals(proposals));
     let metering = Arc::new(Metering::new(gas_limit, cost));
 
     #[cfg(feature = "cranelift")]
@@ -131,7 +133,7 @@ mod tests {
         let wasm = wat::parse_str(EXPORTED_MEMORY_WAT).unwrap();
 
         // No limit
-        let store = make_compile_time_store(None, &[]);
+        let store = make_compile_time_store(None, &[], &WasmProposals::default());
         let module = Module::new(&store, &wasm).unwrap();
         let module_memory = module.info().memories.last().unwrap();
         assert_eq!(module_memory.minimum, Pages(4));
@@ -148,7 +150,8 @@ mod tests {
         assert_eq!(instance_memory.ty().maximum, None);
 
         // Set limit
-        let store = make_compile_time_store(Some(Size::kibi(23 * 64)), &[]);
+        let store =
+            make_compile_time_store(Some(Size::kibi(23 * 64)), &[], &WasmProposals::default());
         let module = Module::new(&store, &wasm).unwrap();
         let module_memory = module.info().memories.last().unwrap();
         assert_eq!(module_memory.minimum, Pages(4));
@@ -170,7 +173,7 @@ mod tests {
         // Compile
         let serialized = {
             let wasm = wat::parse_str(EXPORTED_MEMORY_WAT).unwrap();
-            let store = make_compile_time_store(None, &[]);
+            let store = make_compile_time_store(None, &[], &WasmProposals::default());
             let module = Module::new(&store, &wasm).unwrap();
             module.serialize().unwrap()
         };